@@ -0,0 +1,702 @@
+use crate::engine::batch::ResultBatch;
+use crate::engine::burst::BurstTracker;
+use crate::engine::chaos::ChaosCounters;
+use crate::engine::connection_cap::ConnectionCap;
+use crate::engine::duplicates::Duplicates;
+use crate::engine::extractions::ExtractionCounts;
+use crate::engine::failover;
+use crate::engine::health::WorkerHealth;
+use crate::engine::outage::Outages;
+use crate::engine::prometheus::{PrometheusExporter, push_to_gateway, serve_metrics_endpoint};
+use crate::engine::revalidation::Revalidation;
+use crate::engine::scenario_mix::ScenarioMix;
+use crate::engine::scheduler::StageInfo;
+use crate::engine::shards::StatsShards;
+use crate::engine::throttle::Throttle;
+use crate::engine::vu_pool::VuScaling;
+use crate::engine::warmup::WarmupDetector;
+use crate::engine::{Stats, create_snapshot, create_snapshot_with_arrival_rate};
+use crate::history::{self, HistoryRun};
+#[cfg(feature = "plugins")]
+use crate::plugin::PluginMetrics;
+use crate::types::{AutoWarmup, PrometheusConfig, PrometheusExport, RunPhase, StatsSnapshot};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
+
+/// (passed, total) per check name
+type CheckCounts = Mutex<HashMap<String, (u64, u64)>>;
+
+pub struct Aggregator {
+    stats: Stats,
+    result_rx: mpsc::Receiver<ResultBatch>,
+    snapshot_tx: watch::Sender<StatsSnapshot>,
+    warmup_duration: Duration,
+    phase_tx: watch::Sender<RunPhase>,
+    start_time: Instant,
+    warmup_complete: bool,
+    max_requests: u64,
+    cancel_token: CancellationToken,
+    // Arrival rate metrics (optional)
+    dropped_iterations: Option<Arc<AtomicU64>>,
+    vus_active: Option<Arc<AtomicU32>>,
+    vus_max: u32,
+    target_rate: u32,
+    // SQLite logging (optional)
+    sqlite_conn: Option<Connection>,
+    history_run_id: Option<i64>,
+    // Prometheus metrics export (optional)
+    prometheus_exporter: Option<Arc<PrometheusExporter>>,
+    prometheus_config: Option<PrometheusConfig>,
+    // WebSocket snapshots merged in for mixed HTTP + WS runs (optional)
+    ws_snapshot_rx: Option<watch::Receiver<StatsSnapshot>>,
+    // Per-core shards workers record into, folded in every tick (optional)
+    shards: Option<Arc<StatsShards>>,
+    // Results workers discarded under the drop backpressure policy (optional)
+    results_dropped: Option<Arc<AtomicU64>>,
+    // Connections opened by the HTTP client, and how many were already counted (optional)
+    connections_opened: Option<Arc<AtomicU64>>,
+    connections_seen: u64,
+    // (passed, total) per check, and how much of it was already counted (optional)
+    checks: Option<Arc<CheckCounts>>,
+    checks_seen: HashMap<String, (u64, u64)>,
+    // Ends warmup early once the numbers settle (optional), and the outcome
+    warmup_detector: Option<WarmupDetector>,
+    auto_warmup: Option<AutoWarmup>,
+    // Custom metrics WASM plugins report (optional)
+    #[cfg(feature = "plugins")]
+    plugin_metrics: Option<Arc<PluginMetrics>>,
+    // Faults `[chaos]` injected (optional)
+    chaos: Option<Arc<ChaosCounters>>,
+    // 429s and 503s the workers backed off after (optional)
+    throttle: Option<Arc<Throttle>>,
+    // Bursts sent in burst mode (optional)
+    bursts: Option<Arc<BurstTracker>>,
+    vu_scaling: Option<Arc<VuScaling>>,
+    health: Option<Arc<WorkerHealth>>,
+    duplicates: Option<Arc<Duplicates>>,
+    revalidation: Option<Arc<Revalidation>>,
+    outages: Option<Arc<Outages>>,
+    connection_cap: Option<Arc<ConnectionCap>>,
+    // Work out downtime and recovery from the timeline (--failover)
+    failover: bool,
+    extractions: Option<Arc<ExtractionCounts>>,
+    scenario_mix: Option<Arc<ScenarioMix>>,
+    // Stage the scheduler is in, to tag results with (optional)
+    stage_info: Option<watch::Receiver<StageInfo>>,
+}
+
+impl Aggregator {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        duration: Duration,
+        result_rx: mpsc::Receiver<ResultBatch>,
+        snapshot_tx: watch::Sender<StatsSnapshot>,
+        warmup_duration: Duration,
+        phase_tx: watch::Sender<RunPhase>,
+        max_requests: u64,
+        cancel_token: CancellationToken,
+        history: Option<HistoryRun>,
+        prometheus: Option<PrometheusConfig>,
+        target_url: &str,
+    ) -> Self {
+        Self::with_arrival_rate_metrics(
+            duration,
+            result_rx,
+            snapshot_tx,
+            warmup_duration,
+            phase_tx,
+            max_requests,
+            cancel_token,
+            None,
+            None,
+            0,
+            0,
+            history,
+            prometheus,
+            target_url,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_arrival_rate_metrics(
+        duration: Duration,
+        result_rx: mpsc::Receiver<ResultBatch>,
+        snapshot_tx: watch::Sender<StatsSnapshot>,
+        warmup_duration: Duration,
+        phase_tx: watch::Sender<RunPhase>,
+        max_requests: u64,
+        cancel_token: CancellationToken,
+        dropped_iterations: Option<Arc<AtomicU64>>,
+        vus_active: Option<Arc<AtomicU32>>,
+        vus_max: u32,
+        target_rate: u32,
+        history: Option<HistoryRun>,
+        prometheus: Option<PrometheusConfig>,
+        target_url: &str,
+    ) -> Self {
+        let in_warmup = !warmup_duration.is_zero();
+        if !in_warmup {
+            let _ = phase_tx.send(RunPhase::Running);
+        }
+
+        // Initialize SQLite connection if a history run was started
+        let history_run_id = history.as_ref().map(|run| run.id);
+        let sqlite_conn = history.and_then(|run| match history::open_db(&run.path) {
+            Ok(conn) => Some(conn),
+            Err(e) => {
+                tracing::warn!("Failed to initialize SQLite database: {}", e);
+                None
+            }
+        });
+
+        // Initialize Prometheus exporter if configured
+        let prometheus_exporter = prometheus.as_ref().map(|config| {
+            Arc::new(
+                PrometheusExporter::with_labels(
+                    config.instance.as_deref().unwrap_or(target_url),
+                    &config.labels,
+                )
+                .with_buckets(&config.buckets),
+            )
+        });
+        let mut stats = Stats::new(duration);
+        if let Some(ref config) = prometheus {
+            stats = stats.with_latency_buckets(&config.buckets);
+        }
+
+        // Spawn metrics endpoint server if configured
+        if let Some(PrometheusExport::Endpoint { port }) = prometheus.as_ref().map(|c| &c.export)
+            && let Some(ref exporter) = prometheus_exporter
+        {
+            let exporter_clone = exporter.clone();
+            let cancel_clone = cancel_token.clone();
+            let port = *port;
+            tokio::spawn(async move {
+                serve_metrics_endpoint(port, exporter_clone, cancel_clone).await;
+            });
+        }
+
+        Self {
+            stats,
+            result_rx,
+            snapshot_tx,
+            warmup_duration,
+            phase_tx,
+            start_time: Instant::now(),
+            warmup_complete: !in_warmup,
+            max_requests,
+            cancel_token,
+            dropped_iterations,
+            vus_active,
+            vus_max,
+            target_rate,
+            sqlite_conn,
+            history_run_id,
+            prometheus_exporter,
+            prometheus_config: prometheus,
+            ws_snapshot_rx: None,
+            shards: None,
+            results_dropped: None,
+            connections_opened: None,
+            connections_seen: 0,
+            checks: None,
+            checks_seen: HashMap::new(),
+            warmup_detector: None,
+            auto_warmup: None,
+            #[cfg(feature = "plugins")]
+            plugin_metrics: None,
+            chaos: None,
+            throttle: None,
+            bursts: None,
+            vu_scaling: None,
+            health: None,
+            duplicates: None,
+            revalidation: None,
+            outages: None,
+            connection_cap: None,
+            failover: false,
+            extractions: None,
+            scenario_mix: None,
+            stage_info: None,
+        }
+    }
+
+    /// Take results from sharded stats instead of the channel
+    pub fn with_shards(mut self, shards: Arc<StatsShards>) -> Self {
+        if self.warmup_complete {
+            shards.start_measuring();
+        }
+        self.shards = Some(shards);
+        self
+    }
+
+    /// Bucket width of the results timeline
+    pub fn with_timeline_interval(mut self, interval: Duration) -> Self {
+        self.stats = self.stats.with_timeline_interval(interval);
+        self
+    }
+
+    /// Example failures kept per error kind or status
+    pub fn with_failure_samples(mut self, limit: usize) -> Self {
+        self.stats = self.stats.with_failure_samples(limit);
+        self
+    }
+
+    /// Traced requests whose IDs are kept, slowest first
+    pub fn with_slowest_traces(mut self, limit: usize) -> Self {
+        self.stats = self.stats.with_slowest_traces(limit);
+        self
+    }
+
+    /// Apdex target time (None = no Apdex)
+    pub fn with_apdex(mut self, t: Option<Duration>) -> Self {
+        self.stats = self.stats.with_apdex(t);
+        self
+    }
+
+    /// Content-Types a successful response may have (empty = any)
+    pub fn with_expected_content_types(mut self, expected: Vec<String>) -> Self {
+        self.stats = self.stats.with_expected_content_types(expected);
+        self
+    }
+
+    /// Latency percentiles reported in snapshots
+    pub fn with_percentiles(mut self, percentiles: Vec<f64>) -> Self {
+        self.stats = self.stats.with_percentiles(percentiles);
+        self
+    }
+
+    /// Report the full latency histogram in snapshots (`--runner-stream`)
+    pub fn with_latency_histogram(mut self, enabled: bool) -> Self {
+        if enabled {
+            self.stats = self.stats.with_latency_histogram();
+        }
+        self
+    }
+
+    /// End warmup as soon as throughput and latency settle, rather than
+    /// always waiting out the warmup duration
+    pub fn with_auto_warmup(mut self, enabled: bool) -> Self {
+        if enabled && !self.warmup_complete {
+            self.warmup_detector = Some(WarmupDetector::new(self.start_time));
+        }
+        self
+    }
+
+    /// Report results workers had to drop because the channel was full
+    pub fn with_results_dropped(mut self, results_dropped: Arc<AtomicU64>) -> Self {
+        self.results_dropped = Some(results_dropped);
+        self
+    }
+
+    /// Report connection reuse from the HTTP client's new-connection counter
+    pub fn with_connection_counter(mut self, connections_opened: Arc<AtomicU64>) -> Self {
+        self.connections_seen = connections_opened.load(Ordering::Relaxed);
+        self.connections_opened = Some(connections_opened);
+        self.stats = self.stats.with_connection_tracking();
+        self
+    }
+
+    /// Copy the metrics WASM plugins report into every snapshot
+    #[cfg(feature = "plugins")]
+    pub fn with_plugin_metrics(mut self, metrics: Option<Arc<PluginMetrics>>) -> Self {
+        self.plugin_metrics = metrics;
+        self
+    }
+
+    /// Report the faults `[chaos]` injected in every snapshot
+    pub fn with_chaos(mut self, counters: Option<Arc<ChaosCounters>>) -> Self {
+        self.chaos = counters;
+        self
+    }
+
+    /// Report the throttling workers honored in every snapshot
+    pub fn with_throttle(mut self, throttle: Option<Arc<Throttle>>) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Report how the responses to duplicated requests compared
+    pub fn with_duplicates(mut self, duplicates: Option<Arc<Duplicates>>) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Break check results down per timeline bucket
+    pub fn with_checks(mut self, checks: Arc<CheckCounts>) -> Self {
+        self.checks = Some(checks);
+        self
+    }
+
+    /// Report how often scenario extractions found their value
+    pub fn with_extractions(mut self, extractions: Option<Arc<ExtractionCounts>>) -> Self {
+        self.extractions = extractions;
+        self
+    }
+
+    /// Report the requests each weighted scenario got
+    pub fn with_scenario_mix(mut self, scenario_mix: Option<Arc<ScenarioMix>>) -> Self {
+        self.scenario_mix = scenario_mix;
+        self
+    }
+
+    /// Report how conditional requests were answered
+    pub fn with_revalidation(mut self, revalidation: Option<Arc<Revalidation>>) -> Self {
+        self.revalidation = revalidation;
+        self
+    }
+
+    /// Report the outages the run paused through
+    pub fn with_outages(mut self, outages: Option<Arc<Outages>>) -> Self {
+        self.outages = outages;
+        self
+    }
+
+    /// Report how long requests waited for a connection
+    pub fn with_connection_cap(mut self, connection_cap: Option<Arc<ConnectionCap>>) -> Self {
+        self.connection_cap = connection_cap;
+        self
+    }
+
+    /// Report availability, downtime and recovery per timeline bucket
+    pub fn with_failover(mut self, failover: bool) -> Self {
+        self.failover = failover;
+        self
+    }
+
+    /// Report per-worker request counts and failure streaks
+    pub fn with_worker_health(mut self, health: Arc<WorkerHealth>) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Report how `--vu-autoscale` grew and shed the arrival-rate VUs
+    pub fn with_vu_scaling(mut self, scaling: Option<Arc<VuScaling>>) -> Self {
+        self.vu_scaling = scaling;
+        self
+    }
+
+    /// Report burst sizes and timings
+    pub fn with_bursts(mut self, bursts: Arc<BurstTracker>) -> Self {
+        self.bursts = Some(bursts);
+        self
+    }
+
+    /// Break results down by the stage the scheduler reports
+    pub fn with_stages(mut self, stage_info: Option<watch::Receiver<StageInfo>>) -> Self {
+        self.stage_info = stage_info;
+        self
+    }
+
+    /// Merge WebSocket stats from a WsAggregator into every snapshot
+    pub fn with_ws_snapshots(mut self, ws_snapshot_rx: watch::Receiver<StatsSnapshot>) -> Self {
+        self.ws_snapshot_rx = Some(ws_snapshot_rx);
+        self
+    }
+
+    pub async fn run(mut self) -> Stats {
+        let mut snapshot_interval = tokio::time::interval(Duration::from_millis(100));
+        snapshot_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                biased;
+
+                result = self.result_rx.recv() => {
+                    match result {
+                        Some(batch) => {
+                            self.check_warmup_complete();
+                            self.track_stage();
+                            if self.warmup_complete {
+                                self.record_batch(batch);
+                            }
+                        }
+                        None => {
+                            self.drain_shards();
+                            self.drain_connections();
+                            self.drain_checks();
+                            self.send_snapshot();
+                            break;
+                        }
+                    }
+                }
+
+                // Shards tag results with the stage as soon as it changes
+                _ = stage_changed(&mut self.stage_info) => self.track_stage(),
+
+                _ = snapshot_interval.tick() => {
+                    self.check_warmup_complete();
+                    self.track_stage();
+                    self.drain_shards();
+                    self.drain_connections();
+                    self.drain_checks();
+                    self.send_snapshot();
+                }
+            }
+        }
+
+        self.stats
+    }
+
+    fn record_batch(&mut self, batch: ResultBatch) {
+        for req_result in batch {
+            // A batch can overshoot max_requests; results past the limit are dropped
+            if self.max_requests > 0 && self.stats.total_requests() >= self.max_requests {
+                break;
+            }
+            self.stats.record(&req_result);
+
+            if self.max_requests > 0 && self.stats.total_requests() >= self.max_requests {
+                tracing::info!("Max requests ({}) reached, stopping", self.max_requests);
+                self.cancel_token.cancel();
+            }
+        }
+    }
+
+    fn check_warmup_complete(&mut self) {
+        if self.warmup_complete {
+            return;
+        }
+        let elapsed = self.start_time.elapsed();
+        let settled = self.warmup_detector.as_mut().is_some_and(|detector| {
+            let requests = self.stats.total_requests();
+            detector.observe(
+                Instant::now(),
+                requests,
+                self.stats.latency_mean() * requests as f64,
+            )
+        });
+        if settled || elapsed >= self.warmup_duration {
+            if self.warmup_detector.take().is_some() {
+                if settled {
+                    tracing::info!("Warmup settled after {:.1}s", elapsed.as_secs_f64());
+                } else {
+                    tracing::warn!(
+                        "Throughput and latency didn't settle within {:?}; measuring anyway",
+                        self.warmup_duration
+                    );
+                }
+                self.auto_warmup = Some(AutoWarmup {
+                    length: elapsed,
+                    settled,
+                });
+            }
+            self.warmup_complete = true;
+            self.stats.reset();
+            #[cfg(feature = "plugins")]
+            if let Some(ref metrics) = self.plugin_metrics {
+                metrics.reset();
+            }
+            if let Some(ref counters) = self.chaos {
+                counters.reset();
+            }
+            if let Some(ref throttle) = self.throttle {
+                throttle.reset();
+            }
+            if let Some(ref health) = self.health {
+                health.reset();
+            }
+            if let Some(ref duplicates) = self.duplicates {
+                duplicates.reset();
+            }
+            if let Some(ref revalidation) = self.revalidation {
+                revalidation.reset();
+            }
+            if let Some(ref connection_cap) = self.connection_cap {
+                connection_cap.reset();
+            }
+            if let Some(ref extractions) = self.extractions {
+                extractions.reset();
+            }
+            if let Some(ref scenario_mix) = self.scenario_mix {
+                scenario_mix.reset();
+            }
+            if let Some(ref scaling) = self.vu_scaling {
+                scaling.reset();
+            }
+            if let Some(ref bursts) = self.bursts {
+                bursts.reset();
+            }
+            if let Some(ref shards) = self.shards {
+                shards.discard();
+                shards.start_measuring();
+            }
+            let _ = self.phase_tx.send(RunPhase::Running);
+            tracing::info!("Warmup complete, starting measurement");
+        }
+    }
+
+    /// Tag results from here on with the stage the run is in
+    fn track_stage(&mut self) {
+        if let Some(ref rx) = self.stage_info {
+            let info = rx.borrow();
+            self.stats.enter_stage(info.stage_index, info.target);
+            if let Some(ref shards) = self.shards {
+                shards.enter_stage(info.stage_index);
+            }
+        }
+    }
+
+    /// Fold sharded results in; warmup results are dropped, unless auto
+    /// warmup needs them to tell when the numbers settle
+    fn drain_shards(&mut self) {
+        if let Some(ref shards) = self.shards {
+            if self.warmup_complete || self.warmup_detector.is_some() {
+                shards.drain_into(&mut self.stats);
+            } else {
+                shards.discard();
+            }
+        }
+    }
+
+    /// Count connections opened since the last tick; warmup ones are dropped
+    fn drain_connections(&mut self) {
+        if let Some(ref counter) = self.connections_opened {
+            let total = counter.load(Ordering::Relaxed);
+            let opened = total - self.connections_seen;
+            self.connections_seen = total;
+            if self.warmup_complete && opened > 0 {
+                self.stats.record_connections(opened);
+            }
+        }
+    }
+
+    /// Count check results since the last tick; warmup ones are dropped
+    fn drain_checks(&mut self) {
+        let Some(ref checks) = self.checks else {
+            return;
+        };
+        let checks = checks.lock().unwrap_or_else(|e| e.into_inner());
+        for (name, &(passed, total)) in checks.iter() {
+            let seen = self.checks_seen.entry(name.clone()).or_default();
+            let (new_passed, new_total) = (passed - seen.0, total - seen.1);
+            *seen = (passed, total);
+            if self.warmup_complete && new_total > 0 {
+                self.stats.record_checks(name, new_passed, new_total);
+            }
+        }
+    }
+
+    fn send_snapshot(&self) {
+        let mut snapshot = if self.dropped_iterations.is_some() || self.vus_active.is_some() {
+            let dropped = self
+                .dropped_iterations
+                .as_ref()
+                .map(|d| d.load(Ordering::Relaxed))
+                .unwrap_or(0);
+            let active = self
+                .vus_active
+                .as_ref()
+                .map(|v| v.load(Ordering::Relaxed))
+                .unwrap_or(0);
+            create_snapshot_with_arrival_rate(
+                &self.stats,
+                dropped,
+                active,
+                self.vus_max,
+                self.target_rate,
+            )
+        } else {
+            create_snapshot(&self.stats)
+        };
+
+        if let Some(ref dropped) = self.results_dropped {
+            snapshot.results_dropped = dropped.load(Ordering::Relaxed);
+        }
+        snapshot.auto_warmup = self.auto_warmup;
+        #[cfg(feature = "plugins")]
+        if let Some(ref metrics) = self.plugin_metrics {
+            snapshot.plugin_metrics = metrics.summary();
+        }
+        snapshot.chaos = self.chaos.as_ref().map(|counters| counters.stats());
+        snapshot.throttle = self.throttle.as_ref().map(|throttle| throttle.stats());
+        snapshot.bursts = self.bursts.as_ref().map(|bursts| bursts.stats());
+        snapshot.workers = self.health.as_ref().map(|health| health.stats());
+        snapshot.duplicates = self
+            .duplicates
+            .as_ref()
+            .map(|duplicates| duplicates.stats());
+        snapshot.revalidation = self
+            .revalidation
+            .as_ref()
+            .map(|revalidation| revalidation.stats());
+        if let Some(ref outages) = self.outages {
+            snapshot.outages = outages.windows();
+        }
+        snapshot.connection_cap = self.connection_cap.as_ref().map(|cap| cap.stats());
+        if self.failover {
+            snapshot.failover = Some(failover::analyze(
+                &snapshot.timeline,
+                self.stats.timeline_interval(),
+            ));
+        }
+        if let Some(ref extractions) = self.extractions {
+            snapshot.extractions = extractions.stats();
+        }
+        if let Some(ref scenario_mix) = self.scenario_mix {
+            snapshot.scenario_mix = scenario_mix.stats();
+        }
+        if let Some(ref scaling) = self.vu_scaling {
+            let scaling = scaling.stats(self.stats.elapsed());
+            snapshot.vus_max = scaling.allocated;
+            snapshot.vu_scaling = Some(scaling);
+        }
+
+        if let Some(ref ws_rx) = self.ws_snapshot_rx {
+            snapshot.merge_websocket(&ws_rx.borrow());
+        }
+
+        // Log snapshot to SQLite if configured
+        if let Some(ref conn) = self.sqlite_conn
+            && let Err(e) = history::log_snapshot(conn, self.history_run_id, &snapshot)
+        {
+            tracing::warn!("Failed to log snapshot to SQLite: {}", e);
+        }
+
+        // Update and export Prometheus metrics if configured
+        if let Some(ref exporter) = self.prometheus_exporter {
+            // Update metrics synchronously (uses internal locks)
+            let exporter_clone = exporter.clone();
+            let snapshot_clone = snapshot.clone();
+            tokio::spawn(async move {
+                exporter_clone.update(&snapshot_clone).await;
+            });
+
+            // Push to Pushgateway if in push mode (fire-and-forget)
+            if let Some(PrometheusConfig {
+                export: PrometheusExport::Pushgateway { ref url },
+                ref instance,
+                ..
+            }) = self.prometheus_config
+            {
+                let metrics = exporter.encode();
+                let url = url.clone();
+                let job = exporter.job().to_string();
+                let instance = instance.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = push_to_gateway(&url, &job, instance.as_deref(), &metrics).await
+                    {
+                        tracing::warn!("Failed to push to Prometheus Pushgateway: {}", e);
+                    }
+                });
+            }
+        }
+
+        let _ = self.snapshot_tx.send(snapshot);
+    }
+}
+
+/// Resolves whenever the stage changes; never without stages
+async fn stage_changed(stage_info: &mut Option<watch::Receiver<StageInfo>>) {
+    if let Some(rx) = stage_info
+        && rx.changed().await.is_ok()
+    {
+        return;
+    }
+    std::future::pending().await
+}
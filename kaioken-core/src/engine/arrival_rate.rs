@@ -0,0 +1,1171 @@
+#[cfg(feature = "plugins")]
+use super::worker::BuiltRequest;
+use crate::http::{PreparedRequest, execute_prepared, execute_request, now_us};
+#[cfg(feature = "http3")]
+use crate::http3::{Http3Client, execute_http3_request};
+#[cfg(feature = "plugins")]
+use crate::plugin::{PluginHost, PluginRequest};
+use crate::probe::Prober;
+#[cfg(feature = "plugins")]
+use crate::types::ErrorKind;
+use crate::types::{
+    BodyCompression, Check, CheckCondition, FormField, NetworkProfile, RequestResult, Scenario,
+    Stage, TracePropagation,
+};
+use rand::Rng;
+use rand_regex::Regex as RandRegex;
+use reqwest::Client;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
+
+use super::connection_cap::ConnectionCap;
+use super::control::{Plan, RunControl};
+use super::endpoints::EndpointNamer;
+use super::hosts::HostPicker;
+use super::proxies::ProxyPool;
+use super::scenario_mix::ScenarioMix;
+use super::scheduler::{ArrivalSchedule, StageInfo, next_stages};
+use super::seed;
+use super::shards::ResultSink;
+use super::vu_pool::{VuPool, VuScaling};
+use super::worker::CheckResult;
+
+/// Client the arrival-rate executors send iterations through
+#[derive(Clone)]
+pub enum Transport {
+    Http {
+        client: Client,
+        trace: Option<TracePropagation>,
+        id_header: Option<Arc<str>>,
+    },
+    #[cfg(feature = "http3")]
+    Http3 {
+        client: Arc<Http3Client>,
+        timeout: Duration,
+    },
+    /// No requests, only the DNS, connect or TLS layer under them (`--probe`)
+    Probe(Arc<Prober>),
+}
+
+impl Transport {
+    #[allow(clippy::too_many_arguments)]
+    async fn execute(
+        &self,
+        url: &str,
+        method: &reqwest::Method,
+        headers: &[(String, String)],
+        body: Option<&str>,
+        compression: Option<BodyCompression>,
+        form_data: Option<&[FormField]>,
+        basic_auth: Option<(&str, Option<&str>)>,
+        capture_body: bool,
+        scheduled_at_us: Option<u64>,
+    ) -> RequestResult {
+        match self {
+            Transport::Http {
+                client,
+                trace,
+                id_header,
+            } => {
+                execute_request(
+                    client,
+                    url,
+                    method,
+                    headers,
+                    body,
+                    compression,
+                    form_data,
+                    basic_auth,
+                    capture_body,
+                    *trace,
+                    id_header.as_deref(),
+                    scheduled_at_us,
+                )
+                .await
+            }
+            #[cfg(feature = "http3")]
+            // Form fields and basic auth are rejected with --http3 up front
+            Transport::Http3 { client, timeout } => {
+                let started_at_us = now_us();
+                let result =
+                    execute_http3_request(client, url, method.as_str(), headers, body, *timeout)
+                        .await;
+                match scheduled_at_us {
+                    Some(scheduled_at_us) => result.with_timing(scheduled_at_us, started_at_us),
+                    None => result,
+                }
+            }
+            Transport::Probe(prober) => {
+                let started_at_us = now_us();
+                let result = prober.probe(url).await;
+                match scheduled_at_us {
+                    Some(scheduled_at_us) => result.with_timing(scheduled_at_us, started_at_us),
+                    None => result,
+                }
+            }
+        }
+    }
+}
+
+/// The next plan from `kaioken ctl`; never resolves without a control
+async fn next_plan(plan: &mut Option<watch::Receiver<Plan>>) -> Plan {
+    if let Some(rx) = plan
+        && rx.changed().await.is_ok()
+    {
+        return *rx.borrow_and_update();
+    }
+    std::future::pending().await
+}
+
+/// Default target and scenarios shared by every iteration, plus prebuilt
+/// requests for those that need no interpolation
+struct Targets {
+    url: String,
+    method: reqwest::Method,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    scenarios: Arc<Vec<Scenario>>,
+    prepared: Option<PreparedRequest>,
+    prepared_scenarios: Vec<Option<PreparedRequest>>,
+    form_fields: Vec<FormField>,
+    basic_auth: Option<(String, Option<String>)>,
+    compression: Option<BodyCompression>,
+    // The default target's URL and body, rotated per iteration
+    url_list: Option<Arc<Vec<String>>>,
+    body_lines: Option<Arc<Vec<String>>>,
+    rand_regex: Option<RandRegex>,
+    hosts: Option<Arc<HostPicker>>,
+    network: Option<NetworkProfile>,
+    proxies: Option<Arc<ProxyPool>>,
+    connection_cap: Option<Arc<ConnectionCap>>,
+    scenario_mix: Option<Arc<ScenarioMix>>,
+    seed: Option<u64>,
+    #[cfg(feature = "plugins")]
+    plugins: Option<Arc<PluginHost>>,
+}
+
+impl Targets {
+    fn new(
+        url: String,
+        method: reqwest::Method,
+        headers: Vec<(String, String)>,
+        body: Option<String>,
+        scenarios: Arc<Vec<Scenario>>,
+    ) -> Self {
+        let prepared = PreparedRequest::new(&method, &url, &headers, body.as_deref());
+        let prepared_scenarios = scenarios
+            .iter()
+            .map(PreparedRequest::for_scenario)
+            .collect();
+        Self {
+            url,
+            method,
+            headers,
+            body,
+            scenarios,
+            prepared,
+            prepared_scenarios,
+            form_fields: Vec::new(),
+            basic_auth: None,
+            compression: None,
+            url_list: None,
+            body_lines: None,
+            rand_regex: None,
+            hosts: None,
+            network: None,
+            proxies: None,
+            connection_cap: None,
+            scenario_mix: None,
+            seed: None,
+            #[cfg(feature = "plugins")]
+            plugins: None,
+        }
+    }
+
+    /// The default target rotates through URL/body lists per iteration, so it
+    /// can only be prebuilt when it is a single fixed request
+    fn set_rotation(
+        &mut self,
+        url_list: Option<Arc<Vec<String>>>,
+        body_lines: Option<Arc<Vec<String>>>,
+        rand_regex_pattern: Option<&str>,
+    ) {
+        self.rand_regex = rand_regex_pattern.map(|pattern| {
+            RandRegex::compile(pattern, 100).expect("Invalid rand-regex-url pattern")
+        });
+        if url_list.is_some() || body_lines.is_some() || self.rand_regex.is_some() {
+            self.prepared = None;
+        }
+        self.url_list = url_list;
+        self.body_lines = body_lines;
+    }
+
+    /// URL and body of the default target for iteration `iteration_id`, before
+    /// interpolation: rand_regex > url_list > url, and body_lines > body
+    fn default_target(&self, iteration_id: u64, rng: &mut impl Rng) -> (String, Option<&str>) {
+        let url = if let Some(ref generator) = self.rand_regex {
+            rng.sample::<String, _>(generator)
+        } else if let Some(ref urls) = self.url_list {
+            urls[(iteration_id % urls.len() as u64) as usize].clone()
+        } else {
+            self.url.clone()
+        };
+        let body = match self.body_lines {
+            Some(ref lines) => Some(lines[(iteration_id % lines.len() as u64) as usize].as_str()),
+            None => self.body.as_deref(),
+        };
+        (url, body)
+    }
+
+    /// Compress request bodies; prebuilt ones are compressed here, once
+    fn set_compression(&mut self, compression: Option<BodyCompression>) {
+        self.prepared = self.prepared.take().map(|p| p.compressed(compression));
+        for prepared in self.prepared_scenarios.iter_mut() {
+            *prepared = prepared.take().map(|p| p.compressed(compression));
+        }
+        self.compression = compression;
+    }
+
+    /// Routed requests are built per iteration, since each may go to a different host
+    fn set_hosts(&mut self, hosts: Option<Arc<HostPicker>>) {
+        if hosts.is_some() {
+            self.prepared = None;
+            self.prepared_scenarios.iter_mut().for_each(|p| *p = None);
+        }
+        self.hosts = hosts;
+    }
+
+    /// Hooked requests are built per iteration, since a plugin can change any of them
+    #[cfg(feature = "plugins")]
+    fn set_plugins(&mut self, plugins: Option<Arc<PluginHost>>) {
+        if plugins.is_some() {
+            self.prepared = None;
+            self.prepared_scenarios.iter_mut().for_each(|p| *p = None);
+        }
+        self.plugins = plugins;
+    }
+
+    /// Pass a built request through the plugins' `on_request` hooks
+    #[cfg(feature = "plugins")]
+    fn hook_request(&self, request: BuiltRequest) -> Result<BuiltRequest, String> {
+        let Some(ref plugins) = self.plugins else {
+            return Ok(request);
+        };
+        if !plugins.has_request_hooks() {
+            return Ok(request);
+        }
+        let (url, method, headers, body) = request;
+        let hooked = plugins.on_request(PluginRequest {
+            method: method.to_string(),
+            url,
+            headers,
+            body,
+        })?;
+        let method =
+            reqwest::Method::from_bytes(hooked.method.as_bytes()).map_err(|e| e.to_string())?;
+        Ok((hooked.url, method, hooked.headers, hooked.body))
+    }
+
+    /// Send the plugins' `on_response` verdicts on as checks
+    #[cfg(feature = "plugins")]
+    async fn hook_response(
+        &self,
+        method: &reqwest::Method,
+        url: &str,
+        result: &RequestResult,
+        check_tx: &Option<mpsc::Sender<CheckResult>>,
+    ) {
+        if let Some(ref plugins) = self.plugins
+            && plugins.has_response_hooks()
+            && let Some(tx) = check_tx
+        {
+            for (name, passed) in plugins.on_response(method.as_str(), url, result) {
+                let _ = tx.send(CheckResult { name, passed }).await;
+            }
+        }
+    }
+}
+
+/// Executes load test at a constant arrival rate (fixed RPS).
+/// Unlike constant VUs, this spawns iterations at a fixed rate regardless of response time.
+pub struct ArrivalRateExecutor {
+    rate: u32,
+    duration: Duration,
+    latency_correction: bool,
+
+    // Request configuration
+    transport: Transport,
+    targets: Targets,
+    checks: Arc<Vec<Check>>,
+    jsonrpc: bool,
+
+    // Runtime state
+    vus: VuPool,
+    vus_active: Arc<AtomicU32>,
+    dropped_iterations: Arc<AtomicU64>,
+    iteration_counter: Arc<AtomicU64>,
+
+    // Channels
+    results: ResultSink,
+    check_tx: Option<mpsc::Sender<CheckResult>>,
+    cancel_token: CancellationToken,
+    control: Option<Arc<RunControl>>,
+    endpoints: Option<Arc<EndpointNamer>>,
+}
+
+impl ArrivalRateExecutor {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        rate: u32,
+        duration: Duration,
+        max_vus: u32,
+        pre_allocated_vus: u32,
+        latency_correction: bool,
+        transport: Transport,
+        url: String,
+        method: reqwest::Method,
+        headers: Vec<(String, String)>,
+        body: Option<String>,
+        scenarios: Arc<Vec<Scenario>>,
+        checks: Arc<Vec<Check>>,
+        jsonrpc: bool,
+        results: ResultSink,
+        check_tx: Option<mpsc::Sender<CheckResult>>,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            rate,
+            duration,
+            latency_correction,
+            transport,
+            targets: Targets::new(url, method, headers, body, scenarios),
+            checks,
+            jsonrpc,
+            vus: VuPool::new(pre_allocated_vus, max_vus),
+            vus_active: Arc::new(AtomicU32::new(0)),
+            dropped_iterations: Arc::new(AtomicU64::new(0)),
+            iteration_counter: Arc::new(AtomicU64::new(0)),
+            results,
+            check_tx,
+            cancel_token,
+            control: None,
+            endpoints: None,
+        }
+    }
+
+    /// Follow `extend` and `set-rate` from `kaioken ctl`
+    pub fn with_control(mut self, control: Arc<RunControl>) -> Self {
+        self.control = Some(control);
+        self
+    }
+
+    pub fn with_endpoints(mut self, endpoints: Option<Arc<EndpointNamer>>) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Split iterations across weighted hosts
+    pub fn with_hosts(mut self, hosts: Option<Arc<HostPicker>>) -> Self {
+        self.targets.set_hosts(hosts);
+        self
+    }
+
+    /// Send `--form` fields and `--basic-auth` with every iteration
+    pub fn with_form(
+        mut self,
+        form_fields: Vec<FormField>,
+        basic_auth: Option<(String, Option<String>)>,
+    ) -> Self {
+        self.targets.form_fields = form_fields;
+        self.targets.basic_auth = basic_auth;
+        self
+    }
+
+    /// Rotate the default target over `--urls-from-file`, `--body-lines` and
+    /// `--rand-regex-url` per iteration
+    pub fn with_rotation(
+        mut self,
+        url_list: Option<Arc<Vec<String>>>,
+        body_lines: Option<Arc<Vec<String>>>,
+        rand_regex_pattern: Option<&str>,
+    ) -> Self {
+        self.targets
+            .set_rotation(url_list, body_lines, rand_regex_pattern);
+        self
+    }
+
+    /// Seed each iteration's random choices (`--seed`)
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.targets.seed = seed;
+        self
+    }
+
+    /// Compress request bodies (`--compress-body`)
+    pub fn with_compression(mut self, compression: Option<BodyCompression>) -> Self {
+        self.targets.set_compression(compression);
+        self
+    }
+
+    /// Slow every response down to a client network (`--network-profile`)
+    pub fn with_network(mut self, network: Option<NetworkProfile>) -> Self {
+        self.targets.network = network;
+        self
+    }
+
+    /// Rotate iterations over `--proxy-list` and per-scenario proxies
+    pub fn with_proxies(mut self, proxies: Option<Arc<ProxyPool>>) -> Self {
+        self.targets.proxies = proxies;
+        self
+    }
+
+    /// Send iterations over at most `--max-connections` connections
+    pub fn with_connection_cap(mut self, connection_cap: Option<Arc<ConnectionCap>>) -> Self {
+        self.targets.connection_cap = connection_cap;
+        self
+    }
+
+    /// Count the iterations sent for each weighted scenario
+    pub fn with_scenario_mix(mut self, scenario_mix: Option<Arc<ScenarioMix>>) -> Self {
+        self.targets.scenario_mix = scenario_mix;
+        self
+    }
+
+    /// Run iterations through WASM plugin hooks
+    #[cfg(feature = "plugins")]
+    pub fn with_plugins(mut self, plugins: Option<Arc<PluginHost>>) -> Self {
+        self.targets.set_plugins(plugins);
+        self
+    }
+
+    /// Grow and shed VUs by `scaling`'s policy instead of up to `max_vus`
+    pub fn with_autoscale(mut self, scaling: Option<Arc<VuScaling>>) -> Self {
+        self.vus = self.vus.autoscale(scaling);
+        self
+    }
+
+    pub fn dropped_iterations(&self) -> Arc<AtomicU64> {
+        self.dropped_iterations.clone()
+    }
+
+    pub fn vus_active(&self) -> Arc<AtomicU32> {
+        self.vus_active.clone()
+    }
+
+    pub async fn run(mut self) {
+        if self.rate == 0 {
+            tracing::warn!("Arrival rate is 0, no iterations will be spawned");
+            return;
+        }
+
+        let start = Instant::now();
+        let start_us = now_us();
+        let mut schedule = ArrivalSchedule::new(start, self.rate, self.duration);
+
+        tracing::info!(
+            "Starting arrival rate executor: {} req/s, max {} VUs, duration {:?}",
+            self.rate,
+            self.vus.cap(),
+            self.duration
+        );
+
+        let iterations = Iterations {
+            transport: self.transport,
+            targets: Arc::new(self.targets),
+            checks: self.checks,
+            jsonrpc: self.jsonrpc,
+            endpoints: self.endpoints,
+            results: self.results,
+            check_tx: self.check_tx,
+            cancel_token: self.cancel_token.clone(),
+            vus_active: self.vus_active,
+            counter: self.iteration_counter,
+        };
+        let mut plan = self.control.as_ref().map(|c| c.subscribe());
+        let mut rate = self.rate;
+        let mut extended = Duration::ZERO;
+
+        while let Some(deadline) = schedule.next_deadline() {
+            // Sleep until the next iteration is due
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline.into()) => {}
+                _ = self.cancel_token.cancelled() => break,
+                adjusted = next_plan(&mut plan) => {
+                    if let Some(total) = adjusted.extended
+                        && total > extended
+                    {
+                        schedule.extend(total - extended);
+                        extended = total;
+                    }
+                    if let Some(new_rate) = adjusted.rate
+                        && new_rate != rate
+                    {
+                        tracing::info!("Arrival rate changed: {} -> {} req/s", rate, new_rate);
+                        schedule.set_rate(Instant::now(), new_rate);
+                        rate = new_rate;
+                    }
+                    continue;
+                }
+            }
+
+            // Spawn every iteration that came due while we slept, each stamped
+            // with its own scheduled time
+            for n in schedule.release(Instant::now()) {
+                let scheduled_at_us = self
+                    .latency_correction
+                    .then(|| start_us + schedule.offset(n).as_micros() as u64);
+
+                // Take a free VU, growing the pool if allowed; otherwise
+                // the iteration is dropped
+                match self.vus.acquire() {
+                    Some(permit) => iterations.spawn(permit, scheduled_at_us),
+                    None => {
+                        self.dropped_iterations.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+            self.vus.shed_idle();
+        }
+
+        tracing::info!(
+            "Arrival rate executor finished. Dropped iterations: {}",
+            self.dropped_iterations.load(Ordering::Relaxed)
+        );
+    }
+}
+
+/// What every iteration an executor spawns shares; the targets are only
+/// shared once the executor runs, so its builders can still change them
+struct Iterations {
+    transport: Transport,
+    targets: Arc<Targets>,
+    checks: Arc<Vec<Check>>,
+    jsonrpc: bool,
+    endpoints: Option<Arc<EndpointNamer>>,
+    results: ResultSink,
+    check_tx: Option<mpsc::Sender<CheckResult>>,
+    cancel_token: CancellationToken,
+    vus_active: Arc<AtomicU32>,
+    counter: Arc<AtomicU64>,
+}
+
+impl Iterations {
+    fn spawn(&self, permit: tokio::sync::OwnedSemaphorePermit, scheduled_at_us: Option<u64>) {
+        let iteration_id = self.counter.fetch_add(1, Ordering::Relaxed);
+        let vus_active = self.vus_active.clone();
+        let results = self.results.clone();
+        let check_tx = self.check_tx.clone();
+        let cancel_token = self.cancel_token.clone();
+
+        let transport = self.transport.clone();
+        let targets = self.targets.clone();
+        let checks = self.checks.clone();
+        let jsonrpc = self.jsonrpc;
+        let endpoints = self.endpoints.clone();
+
+        tokio::spawn(async move {
+            vus_active.fetch_add(1, Ordering::Relaxed);
+
+            // Execute single iteration
+            let result = execute_iteration(
+                iteration_id,
+                &transport,
+                &targets,
+                &checks,
+                jsonrpc,
+                endpoints.as_deref(),
+                &results,
+                &check_tx,
+                &cancel_token,
+                scheduled_at_us,
+            )
+            .await;
+
+            if let Some(result) = result {
+                results.send(&result);
+            }
+
+            vus_active.fetch_sub(1, Ordering::Relaxed);
+            drop(permit); // Release VU back to pool
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn execute_iteration(
+    iteration_id: u64,
+    transport: &Transport,
+    targets: &Targets,
+    checks: &[Check],
+    jsonrpc: bool,
+    endpoints: Option<&EndpointNamer>,
+    results: &ResultSink,
+    check_tx: &Option<mpsc::Sender<CheckResult>>,
+    cancel_token: &CancellationToken,
+    scheduled_at_us: Option<u64>,
+) -> Option<RequestResult> {
+    if cancel_token.is_cancelled() {
+        return None;
+    }
+
+    let timestamp_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+
+    // Determine if we need to capture body
+    let has_body_checks = checks.iter().any(|c| {
+        matches!(
+            c.condition,
+            CheckCondition::BodyContains(_)
+                | CheckCondition::BodyNotContains(_)
+                | CheckCondition::BodyMatches(_)
+        )
+    });
+    let scenarios = targets.scenarios.as_slice();
+    let has_extractions = scenarios.iter().any(|s| !s.extractions.is_empty());
+    #[cfg(feature = "plugins")]
+    let has_body_checks = has_body_checks
+        || targets
+            .plugins
+            .as_ref()
+            .is_some_and(|plugins| plugins.has_response_hooks());
+    let capture_body = has_body_checks || has_extractions || jsonrpc;
+
+    // Select scenario or use default target
+    let scenario = (!scenarios.is_empty()).then(|| select_scenario(scenarios, iteration_id));
+    let scenario_name = scenario.map(|idx| scenarios[idx].name.as_str());
+    let prepared = match scenario {
+        Some(idx) => targets.prepared_scenarios[idx].as_ref(),
+        None => targets.prepared.as_ref(),
+    };
+
+    // Iterations have no VU of their own, so proxies rotate per request
+    let proxied;
+    let transport = match (transport, targets.proxies.as_deref()) {
+        (
+            Transport::Http {
+                client,
+                trace,
+                id_header,
+            },
+            Some(proxies),
+        ) => {
+            proxied = Transport::Http {
+                client: proxies.client(None, scenario, client).clone(),
+                trace: *trace,
+                id_header: id_header.clone(),
+            };
+            &proxied
+        }
+        _ => transport,
+    };
+
+    let form_data = (!targets.form_fields.is_empty()).then_some(targets.form_fields.as_slice());
+    let basic_auth = targets
+        .basic_auth
+        .as_ref()
+        .map(|(u, p)| (u.as_str(), p.as_deref()));
+    let mut rng = seed::rng(targets.seed, iteration_id);
+
+    // With a connection cap, the iteration waits for one to be free
+    let connection = match targets.connection_cap {
+        Some(ref cap) => Some(cap.acquire(cancel_token).await?),
+        None => None,
+    };
+    let mut result = match (transport, prepared) {
+        // HTTP/3 builds its own requests, so prebuilt ones only apply to reqwest
+        (
+            Transport::Http {
+                client,
+                trace,
+                id_header,
+            },
+            Some(prepared),
+        ) => {
+            let mut result = execute_prepared(
+                client,
+                prepared,
+                form_data,
+                basic_auth,
+                capture_body,
+                *trace,
+                id_header.as_deref(),
+                scheduled_at_us,
+            )
+            .await;
+            // Prebuilt requests have no placeholders, so the template is the URL
+            let (method, url) = match scenario {
+                Some(idx) => (&scenarios[idx].method, &scenarios[idx].url),
+                None => (&targets.method, &targets.url),
+            };
+            result.endpoint = endpoints.map(|namer| namer.name(method, url));
+            results.log_slow(&result, method, url, scenario_name);
+            result
+        }
+        _ => {
+            let (url, method, headers, body) = match scenario {
+                Some(idx) => {
+                    let scenario = &scenarios[idx];
+                    let url = interpolate_vars(&scenario.url, iteration_id, timestamp_ms);
+                    let headers: Vec<(String, String)> = scenario
+                        .headers
+                        .iter()
+                        .map(|(k, v)| (k.clone(), interpolate_vars(v, iteration_id, timestamp_ms)))
+                        .collect();
+                    let body = scenario
+                        .body
+                        .as_ref()
+                        .map(|b| interpolate_vars(b, iteration_id, timestamp_ms));
+                    (url, scenario.method.clone(), headers, body)
+                }
+                None => {
+                    let (url, body) = targets.default_target(iteration_id, &mut rng);
+                    let url = interpolate_vars(&url, iteration_id, timestamp_ms);
+                    let headers: Vec<(String, String)> = targets
+                        .headers
+                        .iter()
+                        .map(|(k, v)| (k.clone(), interpolate_vars(v, iteration_id, timestamp_ms)))
+                        .collect();
+                    let body = body.map(|b| interpolate_vars(b, iteration_id, timestamp_ms));
+                    (url, targets.method.clone(), headers, body)
+                }
+            };
+            #[cfg(feature = "plugins")]
+            let (url, method, headers, body) =
+                match targets.hook_request((url, method, headers, body)) {
+                    Ok(request) => request,
+                    // The plugin has logged why; the request isn't sent
+                    Err(_) => return Some(RequestResult::error(0, ErrorKind::Plugin)),
+                };
+            let mut headers = headers;
+            let (url, host) = match targets.hosts {
+                Some(ref hosts) => {
+                    let (url, host) = hosts.route(&url, &mut headers, &mut rng);
+                    (url, Some(host.to_string()))
+                }
+                None => (url, None),
+            };
+
+            let mut result = transport
+                .execute(
+                    &url,
+                    &method,
+                    &headers,
+                    body.as_deref(),
+                    targets.compression,
+                    form_data,
+                    basic_auth,
+                    capture_body,
+                    scheduled_at_us,
+                )
+                .await;
+            result.host = host;
+            result.endpoint = endpoints.map(|namer| namer.name(&method, &url));
+            results.log_slow(&result, &method, &url, scenario_name);
+            #[cfg(feature = "plugins")]
+            targets
+                .hook_response(&method, &url, &result, check_tx)
+                .await;
+            result
+        }
+    };
+    drop(connection);
+    // Hold the iteration as long as the network profile would have taken longer
+    if let Some(ref network) = targets.network
+        && result.status.is_some()
+    {
+        let delay = network.delay(result.bytes_sent, result.bytes_received);
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = cancel_token.cancelled() => return None,
+        }
+        result.latency_us += delay.as_micros() as u64;
+    }
+    if jsonrpc {
+        crate::http::jsonrpc::classify(&mut result);
+    }
+    if let (Some(mix), Some(idx)) = (&targets.scenario_mix, scenario) {
+        mix.record(idx);
+    }
+
+    // Evaluate checks
+    if !checks.is_empty()
+        && let Some(tx) = &check_tx
+    {
+        let body_str = result.body.as_deref().unwrap_or("");
+        for check in checks.iter() {
+            let passed = check.condition.evaluate(result.status, body_str);
+            let _ = tx
+                .send(CheckResult {
+                    name: check.name.clone(),
+                    passed,
+                })
+                .await;
+        }
+    }
+
+    Some(result)
+}
+
+fn select_scenario(scenarios: &[Scenario], iteration_id: u64) -> usize {
+    if scenarios.len() == 1 {
+        return 0;
+    }
+
+    let total_weight: u32 = scenarios.iter().map(|s| s.weight).sum();
+    if total_weight == 0 {
+        return 0;
+    }
+
+    let roll = (iteration_id % total_weight as u64) as u32;
+    let mut cumulative = 0u32;
+
+    for (idx, scenario) in scenarios.iter().enumerate() {
+        cumulative += scenario.weight;
+        if roll < cumulative {
+            return idx;
+        }
+    }
+
+    0
+}
+
+fn interpolate_vars(s: &str, request_id: u64, timestamp_ms: u128) -> String {
+    s.replace("${REQUEST_ID}", &request_id.to_string())
+        .replace("${TIMESTAMP_MS}", &timestamp_ms.to_string())
+}
+
+/// Stage definition for ramping arrival rate
+#[derive(Debug, Clone)]
+pub struct RateStage {
+    pub duration: Duration,
+    pub target_rate: u32,
+}
+
+impl RateStage {
+    /// The `target_rate` stages among `stages`
+    pub fn from_stages(stages: &[Stage]) -> Vec<RateStage> {
+        stages
+            .iter()
+            .filter_map(|s| {
+                s.target_rate.map(|rate| RateStage {
+                    duration: s.duration,
+                    target_rate: rate,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The stage `elapsed` falls in and the rate there, ramping linearly from
+/// the previous stage's target (0 before the first), or `None` once all of
+/// them have run
+fn rate_at(stages: &[RateStage], elapsed: Duration) -> Option<(usize, u32)> {
+    let mut start = Duration::ZERO;
+    let mut prev_rate: u32 = 0;
+    for (index, stage) in stages.iter().enumerate() {
+        let end = start + stage.duration;
+        if elapsed < end {
+            let progress = (elapsed - start).as_secs_f64() / stage.duration.as_secs_f64();
+            let rate = prev_rate as f64 + (stage.target_rate as f64 - prev_rate as f64) * progress;
+            return Some((index, rate.round() as u32));
+        }
+        start = end;
+        prev_rate = stage.target_rate;
+    }
+    None
+}
+
+/// Executes load test with ramping arrival rate across stages.
+/// Gradually transitions RPS between stages.
+pub struct RampingArrivalRateExecutor {
+    stages: Vec<RateStage>,
+    latency_correction: bool,
+
+    // Request configuration
+    transport: Transport,
+    targets: Targets,
+    checks: Arc<Vec<Check>>,
+    jsonrpc: bool,
+
+    // Runtime state
+    vus: VuPool,
+    vus_active: Arc<AtomicU32>,
+    dropped_iterations: Arc<AtomicU64>,
+    iteration_counter: Arc<AtomicU64>,
+    current_rate: Arc<AtomicU32>,
+
+    // Channels
+    results: ResultSink,
+    check_tx: Option<mpsc::Sender<CheckResult>>,
+    cancel_token: CancellationToken,
+    endpoints: Option<Arc<EndpointNamer>>,
+    reloads: Option<watch::Receiver<Vec<Stage>>>,
+    stage_info_tx: Option<watch::Sender<StageInfo>>,
+}
+
+impl RampingArrivalRateExecutor {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        stages: Vec<RateStage>,
+        max_vus: u32,
+        pre_allocated_vus: u32,
+        latency_correction: bool,
+        transport: Transport,
+        url: String,
+        method: reqwest::Method,
+        headers: Vec<(String, String)>,
+        body: Option<String>,
+        scenarios: Arc<Vec<Scenario>>,
+        checks: Arc<Vec<Check>>,
+        jsonrpc: bool,
+        results: ResultSink,
+        check_tx: Option<mpsc::Sender<CheckResult>>,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        let initial_rate = stages.first().map(|s| s.target_rate).unwrap_or(10);
+
+        Self {
+            stages,
+            latency_correction,
+            transport,
+            targets: Targets::new(url, method, headers, body, scenarios),
+            checks,
+            jsonrpc,
+            vus: VuPool::new(pre_allocated_vus, max_vus),
+            vus_active: Arc::new(AtomicU32::new(0)),
+            dropped_iterations: Arc::new(AtomicU64::new(0)),
+            iteration_counter: Arc::new(AtomicU64::new(0)),
+            current_rate: Arc::new(AtomicU32::new(initial_rate)),
+            results,
+            check_tx,
+            cancel_token,
+            endpoints: None,
+            reloads: None,
+            stage_info_tx: None,
+        }
+    }
+
+    /// Follow stages reloaded through `control`
+    pub fn with_control(mut self, control: &RunControl) -> Self {
+        self.reloads = Some(control.subscribe_stages());
+        self
+    }
+
+    /// Report the stage the executor is in and the rate it is sending at
+    pub fn with_stage_info(mut self, stage_info_tx: watch::Sender<StageInfo>) -> Self {
+        self.stage_info_tx = Some(stage_info_tx);
+        self
+    }
+
+    pub fn with_endpoints(mut self, endpoints: Option<Arc<EndpointNamer>>) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Split iterations across weighted hosts
+    pub fn with_hosts(mut self, hosts: Option<Arc<HostPicker>>) -> Self {
+        self.targets.set_hosts(hosts);
+        self
+    }
+
+    /// Send `--form` fields and `--basic-auth` with every iteration
+    pub fn with_form(
+        mut self,
+        form_fields: Vec<FormField>,
+        basic_auth: Option<(String, Option<String>)>,
+    ) -> Self {
+        self.targets.form_fields = form_fields;
+        self.targets.basic_auth = basic_auth;
+        self
+    }
+
+    /// Rotate the default target over `--urls-from-file`, `--body-lines` and
+    /// `--rand-regex-url` per iteration
+    pub fn with_rotation(
+        mut self,
+        url_list: Option<Arc<Vec<String>>>,
+        body_lines: Option<Arc<Vec<String>>>,
+        rand_regex_pattern: Option<&str>,
+    ) -> Self {
+        self.targets
+            .set_rotation(url_list, body_lines, rand_regex_pattern);
+        self
+    }
+
+    /// Seed each iteration's random choices (`--seed`)
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.targets.seed = seed;
+        self
+    }
+
+    /// Compress request bodies (`--compress-body`)
+    pub fn with_compression(mut self, compression: Option<BodyCompression>) -> Self {
+        self.targets.set_compression(compression);
+        self
+    }
+
+    /// Slow every response down to a client network (`--network-profile`)
+    pub fn with_network(mut self, network: Option<NetworkProfile>) -> Self {
+        self.targets.network = network;
+        self
+    }
+
+    /// Rotate iterations over `--proxy-list` and per-scenario proxies
+    pub fn with_proxies(mut self, proxies: Option<Arc<ProxyPool>>) -> Self {
+        self.targets.proxies = proxies;
+        self
+    }
+
+    /// Send iterations over at most `--max-connections` connections
+    pub fn with_connection_cap(mut self, connection_cap: Option<Arc<ConnectionCap>>) -> Self {
+        self.targets.connection_cap = connection_cap;
+        self
+    }
+
+    /// Count the iterations sent for each weighted scenario
+    pub fn with_scenario_mix(mut self, scenario_mix: Option<Arc<ScenarioMix>>) -> Self {
+        self.targets.scenario_mix = scenario_mix;
+        self
+    }
+
+    /// Run iterations through WASM plugin hooks
+    #[cfg(feature = "plugins")]
+    pub fn with_plugins(mut self, plugins: Option<Arc<PluginHost>>) -> Self {
+        self.targets.set_plugins(plugins);
+        self
+    }
+
+    /// Grow and shed VUs by `scaling`'s policy instead of up to `max_vus`
+    pub fn with_autoscale(mut self, scaling: Option<Arc<VuScaling>>) -> Self {
+        self.vus = self.vus.autoscale(scaling);
+        self
+    }
+
+    pub fn dropped_iterations(&self) -> Arc<AtomicU64> {
+        self.dropped_iterations.clone()
+    }
+
+    pub fn vus_active(&self) -> Arc<AtomicU32> {
+        self.vus_active.clone()
+    }
+
+    #[allow(dead_code)]
+    pub fn current_rate(&self) -> Arc<AtomicU32> {
+        self.current_rate.clone()
+    }
+
+    pub async fn run(mut self) {
+        if self.stages.is_empty() {
+            tracing::warn!("No stages defined, nothing to run");
+            return;
+        }
+
+        let total_duration: Duration = self.stages.iter().map(|s| s.duration).sum();
+        tracing::info!(
+            "Starting ramping arrival rate: {} stages, total {:?}, max {} VUs",
+            self.stages.len(),
+            total_duration,
+            self.vus.cap()
+        );
+
+        let iterations = Iterations {
+            transport: self.transport,
+            targets: Arc::new(self.targets),
+            checks: self.checks,
+            jsonrpc: self.jsonrpc,
+            endpoints: self.endpoints,
+            results: self.results,
+            check_tx: self.check_tx,
+            cancel_token: self.cancel_token.clone(),
+            vus_active: self.vus_active,
+            counter: self.iteration_counter,
+        };
+        let start = Instant::now();
+        let start_us = now_us();
+        let mut schedule = ArrivalSchedule::new(start, 0, total_duration);
+        let mut stage_idx = None;
+
+        // The rate is sampled every tick and held until the next, while the
+        // schedule releases iterations against their own deadlines
+        let tick_interval = Duration::from_millis(10);
+        let mut now = start;
+        let mut sample_at = start;
+
+        loop {
+            if now >= sample_at {
+                // Stages are placed by time since the start, so reloaded ones
+                // pick up where the run is
+                let Some((index, current_rate)) = rate_at(&self.stages, now - start) else {
+                    break;
+                };
+                if stage_idx != Some(index) {
+                    let stage = &self.stages[index];
+                    tracing::info!(
+                        "Stage {}: ramping to {} RPS over {:?}",
+                        index + 1,
+                        stage.target_rate,
+                        stage.duration
+                    );
+                    stage_idx = Some(index);
+                }
+                self.current_rate.store(current_rate, Ordering::Relaxed);
+                if let Some(ref tx) = self.stage_info_tx {
+                    let stage_start: Duration =
+                        self.stages[..index].iter().map(|s| s.duration).sum();
+                    let _ = tx.send(StageInfo {
+                        stage_index: index,
+                        stage_count: self.stages.len(),
+                        target: self.stages[index].target_rate,
+                        current: current_rate,
+                        stage_elapsed: (now - start).saturating_sub(stage_start),
+                        stage_duration: self.stages[index].duration,
+                    });
+                }
+                schedule.set_rate(now, current_rate);
+                sample_at = now + tick_interval;
+            }
+
+            // Sleep until the next iteration is due or the rate is sampled again
+            let wake = schedule
+                .next_deadline()
+                .map_or(sample_at, |deadline| deadline.min(sample_at));
+            tokio::select! {
+                _ = tokio::time::sleep_until(wake.into()) => {}
+                _ = self.cancel_token.cancelled() => return,
+                Some(stages) = next_stages(&mut self.reloads) => {
+                    self.stages = RateStage::from_stages(&stages);
+                    schedule.set_end(start + self.stages.iter().map(|s| s.duration).sum::<Duration>());
+                    sample_at = Instant::now();
+                    tracing::info!("Stages reloaded: {} stages", self.stages.len());
+                }
+            }
+
+            // Spawn every iteration that came due, each stamped with its own
+            // scheduled time
+            now = Instant::now();
+            for n in schedule.release(now) {
+                let scheduled_at_us = self
+                    .latency_correction
+                    .then(|| start_us + schedule.offset(n).as_micros() as u64);
+
+                match self.vus.acquire() {
+                    Some(permit) => iterations.spawn(permit, scheduled_at_us),
+                    None => {
+                        self.dropped_iterations.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+            self.vus.shed_idle();
+        }
+
+        tracing::info!(
+            "Ramping executor finished in {:?}. Dropped: {}",
+            start.elapsed(),
+            self.dropped_iterations.load(Ordering::Relaxed)
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use crate::types::{BackpressurePolicy, RequestResult};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::time::Instant;
+
+/// Results travel to the aggregator in batches to cut channel contention
+pub type ResultBatch = Vec<RequestResult>;
+
+/// Flush once this many results are buffered
+const BATCH_SIZE: usize = 64;
+/// ...or once this long has passed since the last flush; no result waits
+/// longer than this while the worker awaits its next one (`wait_for`)
+const FLUSH_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Per-worker buffer in front of the result channel. Slow workers (one result
+/// per flush interval or less) still send every result straight away.
+pub struct ResultBatcher {
+    tx: mpsc::Sender<ResultBatch>,
+    buf: ResultBatch,
+    last_flush: Instant,
+    /// When the oldest buffered result is due to be sent
+    deadline: Option<Instant>,
+    policy: BackpressurePolicy,
+    /// Batches waiting for room in the channel (`grow` policy)
+    backlog: VecDeque<ResultBatch>,
+    /// Results discarded because the channel was full (`drop` policy)
+    dropped: Arc<AtomicU64>,
+}
+
+impl ResultBatcher {
+    pub fn new(tx: mpsc::Sender<ResultBatch>) -> Self {
+        Self {
+            tx,
+            buf: Vec::with_capacity(BATCH_SIZE),
+            last_flush: Instant::now(),
+            deadline: None,
+            policy: BackpressurePolicy::Block,
+            backlog: VecDeque::new(),
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// What to do when the channel is full; `dropped` counts discarded results
+    pub fn with_policy(mut self, policy: BackpressurePolicy, dropped: Arc<AtomicU64>) -> Self {
+        self.policy = policy;
+        self.dropped = dropped;
+        self
+    }
+
+    /// Buffer a result, sending the batch if it is full or due; false once
+    /// the aggregator has gone away
+    pub async fn push(&mut self, result: RequestResult) -> bool {
+        self.buf.push(result);
+        self.deadline
+            .get_or_insert_with(|| Instant::now() + FLUSH_INTERVAL);
+        if self.buf.len() >= BATCH_SIZE || self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            return self.send_buffered().await;
+        }
+        true
+    }
+
+    /// Await `work` (the next request), sending the buffered results if
+    /// they fall due in the meantime rather than holding them until the
+    /// next push
+    pub async fn wait_for<F: Future>(&mut self, work: F) -> F::Output {
+        tokio::pin!(work);
+        loop {
+            let Some(deadline) = self.deadline else {
+                return work.await;
+            };
+            tokio::select! {
+                output = &mut work => return output,
+                _ = tokio::time::sleep_until(deadline) => {
+                    // A closed channel shows up at the next push or flush
+                    self.send_buffered().await;
+                }
+            }
+        }
+    }
+
+    /// Send the buffered results as one batch, as the policy says
+    async fn send_buffered(&mut self) -> bool {
+        self.last_flush = Instant::now();
+        let batch = self.take();
+        match self.policy {
+            BackpressurePolicy::Block => self.tx.send(batch).await.is_ok(),
+            BackpressurePolicy::Drop => match self.tx.try_send(batch) {
+                Ok(()) => true,
+                Err(TrySendError::Full(batch)) => {
+                    self.dropped
+                        .fetch_add(batch.len() as u64, Ordering::Relaxed);
+                    true
+                }
+                Err(TrySendError::Closed(_)) => false,
+            },
+            BackpressurePolicy::Grow => {
+                self.backlog.push_back(batch);
+                self.drain_backlog()
+            }
+        }
+    }
+
+    /// Send whatever is buffered, waiting for room whatever the policy;
+    /// workers call this once they are done
+    pub async fn flush(&mut self) -> bool {
+        self.last_flush = Instant::now();
+        let batch = self.take();
+        if !batch.is_empty() {
+            self.backlog.push_back(batch);
+        }
+        while let Some(batch) = self.backlog.pop_front() {
+            if self.tx.send(batch).await.is_err() {
+                return false;
+            }
+        }
+        !self.tx.is_closed()
+    }
+
+    fn take(&mut self) -> ResultBatch {
+        self.deadline = None;
+        std::mem::replace(&mut self.buf, Vec::with_capacity(BATCH_SIZE))
+    }
+
+    /// Send queued batches until the channel is full
+    fn drain_backlog(&mut self) -> bool {
+        while let Some(batch) = self.backlog.pop_front() {
+            match self.tx.try_send(batch) {
+                Ok(()) => {}
+                Err(TrySendError::Full(batch)) => {
+                    self.backlog.push_front(batch);
+                    break;
+                }
+                Err(TrySendError::Closed(_)) => return false,
+            }
+        }
+        true
+    }
+}
+
+impl Drop for ResultBatcher {
+    // Workers flush on exit; this only catches tasks that end early
+    fn drop(&mut self) {
+        let leftovers = std::mem::take(&mut self.buf);
+        for batch in self.backlog.drain(..).chain(Some(leftovers)) {
+            if !batch.is_empty() && self.tx.try_send(batch).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok() -> RequestResult {
+        RequestResult::success(100, 200, 0, None)
+    }
+
+    #[tokio::test]
+    async fn flushes_full_batches_and_leftovers() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let mut batcher = ResultBatcher::new(tx);
+
+        for _ in 0..BATCH_SIZE + 3 {
+            assert!(batcher.push(ok()).await);
+        }
+        assert_eq!(rx.try_recv().unwrap().len(), BATCH_SIZE);
+        assert!(rx.try_recv().is_err());
+
+        assert!(batcher.flush().await);
+        assert_eq!(rx.try_recv().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn slow_workers_send_immediately() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let mut batcher = ResultBatcher::new(tx);
+
+        tokio::time::sleep(FLUSH_INTERVAL).await;
+        assert!(batcher.push(ok()).await);
+        assert_eq!(rx.try_recv().unwrap().len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sends_due_results_while_the_next_request_hangs() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let mut batcher = ResultBatcher::new(tx);
+        let start = Instant::now();
+
+        // Buffered, as the last flush was just now
+        assert!(batcher.push(ok()).await);
+        assert!(rx.try_recv().is_err());
+
+        let hung = tokio::time::sleep(Duration::from_secs(30));
+        tokio::select! {
+            batch = rx.recv() => assert_eq!(batch.unwrap().len(), 1),
+            _ = batcher.wait_for(hung) => panic!("result held until the request ended"),
+        }
+        assert!(start.elapsed() <= FLUSH_INTERVAL);
+    }
+
+    #[tokio::test]
+    async fn drop_sends_leftovers() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let mut batcher = ResultBatcher::new(tx);
+        assert!(batcher.push(ok()).await);
+        drop(batcher);
+        assert_eq!(rx.recv().await.unwrap().len(), 1);
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn drop_policy_counts_discarded_results() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let mut batcher =
+            ResultBatcher::new(tx).with_policy(BackpressurePolicy::Drop, dropped.clone());
+
+        // The first batch fills the channel; the next two are discarded
+        for _ in 0..BATCH_SIZE * 3 {
+            assert!(batcher.push(ok()).await);
+        }
+        assert_eq!(dropped.load(Ordering::Relaxed), 2 * BATCH_SIZE as u64);
+        assert_eq!(rx.try_recv().unwrap().len(), BATCH_SIZE);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn grow_policy_queues_without_blocking() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let mut batcher =
+            ResultBatcher::new(tx).with_policy(BackpressurePolicy::Grow, dropped.clone());
+
+        for _ in 0..BATCH_SIZE * 3 {
+            assert!(batcher.push(ok()).await);
+        }
+        assert_eq!(batcher.backlog.len(), 2);
+
+        // Flushing delivers the backlog in full
+        let (flushed, received) = tokio::join!(batcher.flush(), async {
+            let mut received = 0;
+            for _ in 0..3 {
+                received += rx.recv().await.unwrap().len();
+            }
+            received
+        });
+        assert!(flushed);
+        assert_eq!(received, BATCH_SIZE * 3);
+        assert_eq!(dropped.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn reports_closed_channel() {
+        let (tx, rx) = mpsc::channel(16);
+        let mut batcher = ResultBatcher::new(tx);
+        drop(rx);
+        assert!(!batcher.flush().await);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::types::{BurstConfig, BurstStats};
+use rand::Rng;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// Requests in the burst starting `elapsed` into a run of `total`, ramping
+/// linearly to `ramp_to` when set
+pub fn burst_size(config: &BurstConfig, elapsed: Duration, total: Duration) -> u32 {
+    let Some(to) = config.ramp_to else {
+        return config.requests_per_burst;
+    };
+    let progress = (elapsed.as_secs_f64() / total.as_secs_f64().max(f64::EPSILON)).min(1.0);
+    let from = config.requests_per_burst as f64;
+    (from + (to as f64 - from) * progress).round() as u32
+}
+
+/// The wait before the next burst: the configured delay ± jitter
+pub fn next_delay(config: &BurstConfig, rng: &mut impl Rng) -> Duration {
+    if config.jitter.is_zero() {
+        return config.delay_between_bursts;
+    }
+    let jitter_us = config.jitter.as_micros() as u64;
+    let offset = rng.random_range(0..=2 * jitter_us);
+    (config.delay_between_bursts + Duration::from_micros(offset)).saturating_sub(config.jitter)
+}
+
+/// Sizes, completion times and peak concurrency of the bursts sent; the
+/// aggregator reads them into each snapshot
+#[derive(Debug, Default)]
+pub struct BurstTracker {
+    in_flight: AtomicU32,
+    max_concurrent: AtomicU32,
+    bursts: Mutex<BurstTotals>,
+}
+
+#[derive(Debug, Default)]
+struct BurstTotals {
+    count: u64,
+    min_size: u32,
+    max_size: u32,
+    completion_total: Duration,
+    completion_max: Duration,
+}
+
+impl BurstTracker {
+    /// A request of the current burst was sent
+    pub fn started(&self) {
+        let in_flight = self.in_flight.fetch_add(1, Ordering::Relaxed) + 1;
+        self.max_concurrent.fetch_max(in_flight, Ordering::Relaxed);
+    }
+
+    /// A request of the current burst completed
+    pub fn finished(&self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// A burst of `size` requests completed `completion` after it started
+    pub fn record(&self, size: u32, completion: Duration) {
+        let mut totals = self.bursts.lock().unwrap();
+        totals.min_size = if totals.count == 0 {
+            size
+        } else {
+            totals.min_size.min(size)
+        };
+        totals.max_size = totals.max_size.max(size);
+        totals.count += 1;
+        totals.completion_total += completion;
+        totals.completion_max = totals.completion_max.max(completion);
+    }
+
+    pub fn stats(&self) -> BurstStats {
+        let totals = self.bursts.lock().unwrap();
+        BurstStats {
+            bursts: totals.count,
+            min_size: totals.min_size,
+            max_size: totals.max_size,
+            completion_mean: totals
+                .completion_total
+                .checked_div(totals.count as u32)
+                .unwrap_or_default(),
+            completion_max: totals.completion_max,
+            max_concurrent: self.max_concurrent.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Forget warmup bursts
+    pub fn reset(&self) {
+        *self.bursts.lock().unwrap() = BurstTotals::default();
+        self.max_concurrent
+            .store(self.in_flight.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn config(ramp_to: Option<u32>, jitter: Duration) -> BurstConfig {
+        BurstConfig {
+            requests_per_burst: 10,
+            delay_between_bursts: Duration::from_secs(1),
+            ramp_to,
+            jitter,
+        }
+    }
+
+    #[test]
+    fn sizes_ramp_across_the_run() {
+        let total = Duration::from_secs(100);
+        let flat = config(None, Duration::ZERO);
+        assert_eq!(burst_size(&flat, Duration::from_secs(50), total), 10);
+
+        let ramp = config(Some(110), Duration::ZERO);
+        assert_eq!(burst_size(&ramp, Duration::ZERO, total), 10);
+        assert_eq!(burst_size(&ramp, Duration::from_secs(50), total), 60);
+        assert_eq!(burst_size(&ramp, Duration::from_secs(150), total), 110);
+
+        let down = BurstConfig {
+            requests_per_burst: 100,
+            ramp_to: Some(0),
+            ..ramp
+        };
+        assert_eq!(burst_size(&down, Duration::from_secs(25), total), 75);
+    }
+
+    #[test]
+    fn delays_stay_within_the_jitter() {
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(
+            next_delay(&config(None, Duration::ZERO), &mut rng),
+            Duration::from_secs(1)
+        );
+
+        let jittered = config(None, Duration::from_millis(200));
+        let delays: Vec<Duration> = (0..200).map(|_| next_delay(&jittered, &mut rng)).collect();
+        assert!(delays.iter().all(|d| *d >= Duration::from_millis(800)));
+        assert!(delays.iter().all(|d| *d <= Duration::from_millis(1200)));
+        assert!(delays.iter().any(|d| *d < Duration::from_millis(950)));
+        assert!(delays.iter().any(|d| *d > Duration::from_millis(1050)));
+    }
+
+    #[test]
+    fn tracks_sizes_completion_and_concurrency() {
+        let tracker = BurstTracker::default();
+        for _ in 0..3 {
+            tracker.started();
+        }
+        tracker.finished();
+        tracker.started();
+        (0..3).for_each(|_| tracker.finished());
+        tracker.record(4, Duration::from_millis(30));
+        tracker.record(2, Duration::from_millis(10));
+
+        let stats = tracker.stats();
+        assert_eq!(stats.bursts, 2);
+        assert_eq!((stats.min_size, stats.max_size), (2, 4));
+        assert_eq!(stats.completion_mean, Duration::from_millis(20));
+        assert_eq!(stats.completion_max, Duration::from_millis(30));
+        assert_eq!(stats.max_concurrent, 3);
+
+        tracker.reset();
+        assert_eq!(tracker.stats().bursts, 0);
+        assert_eq!(tracker.stats().max_concurrent, 0);
+    }
+}
@@ -0,0 +1,76 @@
+//! Client certificate pool (`--client-cert-pool`)
+//!
+//! Services behind mTLS often rate-limit or bind sessions per client
+//! identity, so one certificate shared by every VU measures a single very
+//! busy client. A reqwest client has one identity, so each certificate gets
+//! its own client, and VUs take them in turn.
+
+use crate::types::{ClientIdentity, LoadConfig};
+use reqwest::Client;
+
+pub struct CertPool {
+    /// One client per certificate, in pool order
+    clients: Vec<Client>,
+}
+
+impl CertPool {
+    /// `None` without a pool. `client` builds a client presenting an identity.
+    pub fn for_config(
+        config: &LoadConfig,
+        client: impl Fn(&ClientIdentity) -> Result<Client, String>,
+    ) -> Result<Option<Self>, String> {
+        if config.client_cert_pool.is_empty() {
+            return Ok(None);
+        }
+        let clients = config
+            .client_cert_pool
+            .iter()
+            .map(client)
+            .collect::<Result<_, _>>()?;
+        Ok(Some(Self { clients }))
+    }
+
+    /// Client for VU `vu`. Pools at least as large as the VU count (checked
+    /// for `unique` assignment) give every VU its own.
+    pub fn client(&self, vu: u32) -> &Client {
+        &self.clients[vu as usize % self.clients.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::ptr;
+
+    #[test]
+    fn deals_certificates_to_vus_in_turn() {
+        let identity = |name: &str| ClientIdentity {
+            cert: PathBuf::from(format!("{}.crt", name)),
+            key: PathBuf::from(format!("{}.key", name)),
+        };
+        let config = LoadConfig {
+            client_cert_pool: vec![identity("a"), identity("b"), identity("c")],
+            ..LoadConfig::default()
+        };
+        let pool = CertPool::for_config(&config, |_| Ok(Client::new()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(pool.clients.len(), 3);
+
+        let picks: Vec<usize> = (0..5)
+            .map(|vu| {
+                (0..pool.clients.len())
+                    .find(|&i| ptr::eq(pool.client(vu), &pool.clients[i]))
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(picks, [0, 1, 2, 0, 1]);
+
+        assert!(
+            CertPool::for_config(&LoadConfig::default(), |_| Ok(Client::new()))
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use crate::net::SocketOptions;
+use crate::types::{ChaosConfig, ChaosStats, RequestResult};
+use rand::Rng;
+use reqwest::{Method, Url};
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// Faults injected so far; the aggregator reads them into each snapshot
+#[derive(Debug, Default)]
+pub struct ChaosCounters {
+    delayed: AtomicU64,
+    aborted: AtomicU64,
+    reset: AtomicU64,
+}
+
+impl ChaosCounters {
+    pub fn stats(&self) -> ChaosStats {
+        ChaosStats {
+            delayed: self.delayed.load(Ordering::Relaxed),
+            aborted: self.aborted.load(Ordering::Relaxed),
+            reset: self.reset.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Forget warmup faults
+    pub fn reset(&self) {
+        self.delayed.store(0, Ordering::Relaxed);
+        self.aborted.store(0, Ordering::Relaxed);
+        self.reset.store(0, Ordering::Relaxed);
+    }
+}
+
+/// What to do to one request
+#[derive(Debug, Default, PartialEq)]
+pub struct Fault {
+    pub delay: Option<Duration>,
+    pub abort_after: Option<Duration>,
+    pub reset: bool,
+}
+
+/// `[chaos]`: client-side misbehavior the VU workers inject, so servers and
+/// the clients in front of them can be tested against it
+pub struct Chaos {
+    config: ChaosConfig,
+    socket: SocketOptions,
+    connect_timeout: Duration,
+    connect_to: Option<(String, SocketAddr)>,
+    counters: Arc<ChaosCounters>,
+}
+
+impl Chaos {
+    pub fn new(
+        config: ChaosConfig,
+        socket: SocketOptions,
+        connect_timeout: Duration,
+        connect_to: Option<(String, SocketAddr)>,
+    ) -> Self {
+        Self {
+            config,
+            socket,
+            connect_timeout,
+            connect_to,
+            counters: Arc::new(ChaosCounters::default()),
+        }
+    }
+
+    pub fn counters(&self) -> Arc<ChaosCounters> {
+        self.counters.clone()
+    }
+
+    /// Pick the faults for the next request. A request is reset or aborted,
+    /// not both; the extra delay applies on top of either.
+    pub fn roll(&self, rng: &mut impl Rng) -> Fault {
+        let delay = self.config.latency.and_then(|(min, max)| {
+            (rng.random::<f64>() < self.config.latency_rate).then(|| pick(rng, min, max))
+        });
+        if delay.is_some() {
+            self.counters.delayed.fetch_add(1, Ordering::Relaxed);
+        }
+        let roll = rng.random::<f64>();
+        let reset = roll < self.config.reset_rate;
+        let abort_after = (!reset && roll < self.config.reset_rate + self.config.abort_rate)
+            .then(|| pick(rng, self.config.abort_after.0, self.config.abort_after.1));
+        Fault {
+            delay,
+            abort_after,
+            reset,
+        }
+    }
+
+    /// Run `request`, giving up on it after `abort_after` if that comes
+    /// first. Abandoned requests return None and aren't measured.
+    pub async fn run(
+        &self,
+        abort_after: Option<Duration>,
+        request: impl Future<Output = RequestResult>,
+    ) -> Option<RequestResult> {
+        let Some(abort_after) = abort_after else {
+            return Some(request.await);
+        };
+        tokio::select! {
+            result = request => Some(result),
+            _ = tokio::time::sleep(abort_after) => {
+                self.counters.aborted.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Stand in for a request: connect to its target, send the start of the
+    /// request and reset the connection
+    pub async fn reset(&self, method: &Method, url: &str) {
+        match tokio::time::timeout(self.connect_timeout, self.send_reset(method, url)).await {
+            Ok(Ok(())) => {
+                self.counters.reset.fetch_add(1, Ordering::Relaxed);
+            }
+            Ok(Err(e)) => tracing::debug!("Chaos reset of {} failed: {}", url, e),
+            Err(_) => tracing::debug!("Chaos reset of {} timed out connecting", url),
+        }
+    }
+
+    async fn send_reset(&self, method: &Method, url: &str) -> io::Result<()> {
+        let url = Url::parse(url).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "URL has no host"))?;
+        let addr = match self.connect_to {
+            Some((ref name, addr)) if name == host => addr.to_string(),
+            _ => format!("{}:{}", host, url.port_or_known_default().unwrap_or(80)),
+        };
+        let mut stream = self.socket.connect(&addr).await?;
+        // Over TLS the reset comes before the handshake instead
+        if url.scheme() == "http" {
+            let target = match url.query() {
+                Some(query) => format!("{}?{}", url.path(), query),
+                None => url.path().to_string(),
+            };
+            let head = format!("{} {} HTTP/1.1\r\nHost: {}\r\n", method, target, host);
+            stream.write_all(head.as_bytes()).await?;
+        }
+        // Closing with a zero linger sends RST instead of FIN
+        stream.set_zero_linger()?;
+        Ok(())
+    }
+}
+
+/// A uniformly random duration in `[min, max]`
+fn pick(rng: &mut impl Rng, min: Duration, max: Duration) -> Duration {
+    if max <= min {
+        return min;
+    }
+    rng.random_range(min..=max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    fn chaos(config: ChaosConfig) -> Chaos {
+        Chaos::new(
+            config,
+            SocketOptions::default(),
+            Duration::from_secs(2),
+            None,
+        )
+    }
+
+    #[test]
+    fn rolls_follow_the_rates() {
+        let always = chaos(ChaosConfig {
+            latency: Some((Duration::from_millis(10), Duration::from_millis(20))),
+            latency_rate: 1.0,
+            reset_rate: 1.0,
+            ..Default::default()
+        });
+        for _ in 0..100 {
+            let fault = always.roll(&mut rand::rng());
+            let delay = fault.delay.unwrap();
+            assert!(delay >= Duration::from_millis(10) && delay <= Duration::from_millis(20));
+            assert!(fault.reset && fault.abort_after.is_none());
+        }
+        assert_eq!(always.counters().stats().delayed, 100);
+
+        let aborts = chaos(ChaosConfig {
+            abort_rate: 1.0,
+            abort_after: (Duration::from_millis(5), Duration::from_millis(5)),
+            ..Default::default()
+        });
+        assert_eq!(
+            aborts.roll(&mut rand::rng()),
+            Fault {
+                delay: None,
+                abort_after: Some(Duration::from_millis(5)),
+                reset: false,
+            }
+        );
+
+        assert_eq!(
+            chaos(ChaosConfig::default()).roll(&mut rand::rng()),
+            Fault::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn abandons_requests_that_outlast_the_abort() {
+        let chaos = chaos(ChaosConfig::default());
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            RequestResult::success(5_000_000, 200, 0, None)
+        };
+        assert!(
+            chaos
+                .run(Some(Duration::from_millis(10)), slow)
+                .await
+                .is_none()
+        );
+
+        let fast = async { RequestResult::success(1_000, 200, 0, None) };
+        assert!(
+            chaos
+                .run(Some(Duration::from_secs(5)), fast)
+                .await
+                .is_some()
+        );
+        assert_eq!(chaos.counters().stats().aborted, 1);
+    }
+
+    #[tokio::test]
+    async fn reset_sends_rst_after_the_request_line() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/orders?id=7", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut received = Vec::new();
+            let mut buf = [0u8; 256];
+            loop {
+                match stream.read(&mut buf).await {
+                    Ok(0) => return (received, None),
+                    Ok(n) => received.extend_from_slice(&buf[..n]),
+                    Err(e) => return (received, Some(e.kind())),
+                }
+            }
+        });
+
+        let chaos = chaos(ChaosConfig::default());
+        chaos.reset(&Method::POST, &url).await;
+        let (received, error) = server.await.unwrap();
+
+        // The RST can overtake the request line, which is then discarded
+        let received = String::from_utf8(received).unwrap();
+        assert!(
+            "POST /orders?id=7 HTTP/1.1\r\n".starts_with(&received)
+                || received.starts_with("POST /orders?id=7 HTTP/1.1\r\n")
+        );
+        assert_eq!(error, Some(io::ErrorKind::ConnectionReset));
+        assert_eq!(chaos.counters().stats().reset, 1);
+    }
+}
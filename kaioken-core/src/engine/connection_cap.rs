@@ -0,0 +1,115 @@
+//! Connection cap (`--max-connections`)
+//!
+//! The client opens a connection for every request that finds none idle, so
+//! the pool grows to as many connections as there are requests in flight:
+//! one per VU. Letting only N requests be in flight at once caps the
+//! HTTP/1.1 connections the pool needs at N however many VUs share them,
+//! and the time VUs spend waiting for one is what "many users, few
+//! connections" costs them.
+
+use crate::types::ConnectionCapStats;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug)]
+pub struct ConnectionCap {
+    limit: u32,
+    permits: Semaphore,
+    /// Requests that found every connection busy
+    waited: AtomicU64,
+    wait_us: AtomicU64,
+    max_wait_us: AtomicU64,
+}
+
+impl ConnectionCap {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            permits: Semaphore::new(limit as usize),
+            waited: AtomicU64::new(0),
+            wait_us: AtomicU64::new(0),
+            max_wait_us: AtomicU64::new(0),
+        }
+    }
+
+    /// A connection to send one request on, held until it's dropped. None
+    /// once cancelled.
+    pub async fn acquire(&self, cancel: &CancellationToken) -> Option<SemaphorePermit<'_>> {
+        if let Ok(permit) = self.permits.try_acquire() {
+            return Some(permit);
+        }
+        let start = Instant::now();
+        let permit = tokio::select! {
+            permit = self.permits.acquire() => permit.ok()?,
+            _ = cancel.cancelled() => return None,
+        };
+        let wait_us = start.elapsed().as_micros() as u64;
+        self.waited.fetch_add(1, Ordering::Relaxed);
+        self.wait_us.fetch_add(wait_us, Ordering::Relaxed);
+        self.max_wait_us.fetch_max(wait_us, Ordering::Relaxed);
+        Some(permit)
+    }
+
+    pub fn stats(&self) -> ConnectionCapStats {
+        let waited = self.waited.load(Ordering::Relaxed);
+        ConnectionCapStats {
+            limit: self.limit,
+            waited,
+            wait_mean_us: self
+                .wait_us
+                .load(Ordering::Relaxed)
+                .checked_div(waited)
+                .unwrap_or(0),
+            wait_max_us: self.max_wait_us.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Forget warmup waits
+    pub fn reset(&self) {
+        self.waited.store(0, Ordering::Relaxed);
+        self.wait_us.store(0, Ordering::Relaxed);
+        self.max_wait_us.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn holds_requests_beyond_the_limit_until_one_finishes() {
+        let cap = ConnectionCap::new(2);
+        let cancel = CancellationToken::new();
+
+        let first = cap.acquire(&cancel).await.unwrap();
+        let _second = cap.acquire(&cancel).await.unwrap();
+        assert_eq!(cap.stats().waited, 0);
+
+        // A third request waits for one of the two to finish
+        let third = cap.acquire(&cancel);
+        tokio::pin!(third);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), &mut third)
+                .await
+                .is_err()
+        );
+        drop(first);
+        let _third = third.await.unwrap();
+
+        let stats = cap.stats();
+        assert_eq!(stats.limit, 2);
+        assert_eq!(stats.waited, 1);
+        assert!(stats.wait_max_us >= 20_000);
+        assert_eq!(stats.wait_mean_us, stats.wait_max_us);
+
+        // Cancelling gives up on a wait
+        cancel.cancel();
+        assert!(cap.acquire(&cancel).await.is_none());
+
+        cap.reset();
+        assert_eq!(cap.stats().waited, 0);
+    }
+}
@@ -0,0 +1,175 @@
+//! Live adjustments to a running test, driven by `kaioken ctl`
+//!
+//! Run modes opt in to what they can honor: constant-VU HTTP runs can be
+//! extended (and re-rated when started with `--rate`), constant arrival rate
+//! runs can be extended and re-rated. Everything else can only be stopped.
+//! VU and arrival rate stages can be replaced mid-run by a config reload.
+
+use crate::types::Stage;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::{Instant, sleep_until};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Plan {
+    /// Planned run time including warmup, before any extension
+    pub duration: Duration,
+    /// Time added with `extend`, or `None` when this mode can't be extended
+    pub extended: Option<Duration>,
+    /// Current target rate, or `None` when this mode can't change it
+    pub rate: Option<u32>,
+}
+
+pub struct RunControl {
+    plan: watch::Sender<Plan>,
+    // Empty unless the run mode follows reloaded stages
+    stages: watch::Sender<Vec<Stage>>,
+    // Highest VU target a reload may ask for (0 = no limit)
+    max_target: AtomicU32,
+}
+
+impl Default for RunControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunControl {
+    pub fn new() -> Self {
+        Self {
+            plan: watch::Sender::new(Plan::default()),
+            stages: watch::Sender::new(Vec::new()),
+            max_target: AtomicU32::new(0),
+        }
+    }
+
+    pub fn plan(&self) -> Plan {
+        *self.plan.borrow()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<Plan> {
+        self.plan.subscribe()
+    }
+
+    /// Called by a run mode that supports `extend` and/or `set-rate`
+    pub fn allow(&self, extend: bool, rate: Option<u32>) {
+        self.plan.send_modify(|plan| {
+            plan.extended = extend.then_some(Duration::ZERO);
+            plan.rate = rate;
+        });
+    }
+
+    /// Called by a run mode that re-plans when its stages are reloaded.
+    /// `max_target` caps VU targets at the workers the run has spawned.
+    pub fn allow_stages(&self, stages: Vec<Stage>, max_target: Option<u32>) {
+        self.max_target
+            .store(max_target.unwrap_or(0), Ordering::Relaxed);
+        self.stages.send_replace(stages);
+    }
+
+    /// The run's current stages, empty if they can't be reloaded
+    pub fn stages(&self) -> Vec<Stage> {
+        self.stages.borrow().clone()
+    }
+
+    pub fn subscribe_stages(&self) -> watch::Receiver<Vec<Stage>> {
+        self.stages.subscribe()
+    }
+
+    /// Replace the run's stages. Time already run is kept: the run carries
+    /// on from wherever its elapsed time falls in the new stages, and ends
+    /// when they do.
+    pub fn reload_stages(&self, stages: Vec<Stage>) -> Result<(), String> {
+        let current = self.stages();
+        if current.is_empty() {
+            return Err("This run's stages can't be reloaded".to_string());
+        }
+        if stages.is_empty() {
+            return Err("The reloaded config has no stages".to_string());
+        }
+        let by_rate = current.iter().any(|s| s.target_rate.is_some());
+        let matching = stages.iter().all(|s| {
+            if by_rate {
+                s.target_rate.is_some()
+            } else {
+                s.target.is_some()
+            }
+        });
+        if !matching {
+            let field = if by_rate { "target_rate" } else { "target" };
+            return Err(format!("Every reloaded stage needs a {}", field));
+        }
+        let max_target = self.max_target.load(Ordering::Relaxed);
+        if let Some(target) = stages.iter().filter_map(|s| s.target).max()
+            && max_target > 0
+            && target > max_target
+        {
+            return Err(format!(
+                "Stage target {} is above {}, the most VUs this run started with",
+                target, max_target
+            ));
+        }
+
+        let old: Duration = current.iter().map(|s| s.duration).sum();
+        let new: Duration = stages.iter().map(|s| s.duration).sum();
+        self.plan
+            .send_modify(|plan| plan.duration = plan.duration.saturating_sub(old) + new);
+        self.stages.send_replace(stages);
+        Ok(())
+    }
+
+    /// Add `by` to the run; returns the total extension so far
+    pub fn extend(&self, by: Duration) -> Result<Duration, String> {
+        let mut total = None;
+        self.plan.send_if_modified(|plan| match plan.extended {
+            Some(ref mut extended) => {
+                *extended += by;
+                total = Some(*extended);
+                true
+            }
+            None => false,
+        });
+        total.ok_or_else(|| "This run can't be extended (stages define its length)".to_string())
+    }
+
+    pub fn set_rate(&self, rate: u32) -> Result<(), String> {
+        if rate == 0 {
+            return Err("Rate must be at least 1".to_string());
+        }
+        let mut supported = false;
+        self.plan.send_if_modified(|plan| match plan.rate {
+            Some(ref mut current) => {
+                supported = true;
+                *current = rate;
+                true
+            }
+            None => false,
+        });
+        if supported {
+            Ok(())
+        } else {
+            Err(
+                "This run's rate can't be changed (start it with --rate or --arrival-rate)"
+                    .to_string(),
+            )
+        }
+    }
+
+    /// Sleep for `duration` plus whatever `extend` adds while waiting, or
+    /// as long as reloaded stages make it
+    pub async fn wait(&self, duration: Duration) {
+        let start = Instant::now();
+        self.plan.send_modify(|plan| plan.duration = duration);
+        let mut plan = self.plan.subscribe();
+        loop {
+            let current = *plan.borrow_and_update();
+            let end = start + current.duration + current.extended.unwrap_or_default();
+            tokio::select! {
+                _ = sleep_until(end) => return,
+                // The sender lives in `self`, so this only fails if we're gone
+                _ = plan.changed() => {}
+            }
+        }
+    }
+}
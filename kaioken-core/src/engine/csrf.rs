@@ -0,0 +1,132 @@
+//! Automatic CSRF tokens (`[csrf]`)
+//!
+//! Each VU keeps the token its last safe request (GET, HEAD, OPTIONS) came
+//! back with and sends it in a header on its mutating requests, the way a
+//! browser form or single-page app does, without an extraction chain in
+//! every scenario.
+
+use super::worker::extract_value;
+use crate::types::{CsrfConfig, CsrfSource, RequestResult};
+use reqwest::Method;
+use reqwest::header::SET_COOKIE;
+
+/// A VU's CSRF token
+pub struct CsrfState {
+    config: CsrfConfig,
+    token: Option<String>,
+}
+
+impl CsrfState {
+    pub fn new(config: CsrfConfig) -> Self {
+        Self {
+            config,
+            token: None,
+        }
+    }
+
+    /// Add the token to a mutating request's `headers`, once there is one
+    pub fn inject(&self, method: &Method, headers: &mut Vec<(String, String)>) {
+        if let Some(ref token) = self.token
+            && !method.is_safe()
+        {
+            headers.retain(|(name, _)| !name.eq_ignore_ascii_case(&self.config.header));
+            headers.push((self.config.header.clone(), token.clone()));
+        }
+    }
+
+    /// Keep the token from a safe request's response, if it has one
+    pub fn observe(&mut self, method: &Method, result: &RequestResult) {
+        if method.is_safe()
+            && let Some(token) = find_token(&self.config.source, result)
+        {
+            self.token = Some(token);
+        }
+    }
+}
+
+fn find_token(source: &CsrfSource, result: &RequestResult) -> Option<String> {
+    let headers = result.headers.as_deref();
+    let token = match source {
+        CsrfSource::Cookie(name) => headers?
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|cookie| cookie.split(';').next()?.split_once('='))
+            .find(|(cookie, _)| cookie.trim() == name)
+            .map(|(_, value)| value.trim().to_string()),
+        CsrfSource::Header(name) => headers?
+            .get(name.as_str())
+            .and_then(|value| value.to_str().ok())
+            .map(String::from),
+        CsrfSource::Body(source) => extract_value(source, result.body.as_deref()?, &[]),
+    };
+    token.filter(|token| !token.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    fn response(headers: &[(&'static str, &'static str)], body: &str) -> RequestResult {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.append(*name, HeaderValue::from_static(value));
+        }
+        let mut result = RequestResult::success(1_000, 200, 0, Some(body.to_string()));
+        result.headers = Some(Box::new(map));
+        result
+    }
+
+    fn state(source: &str) -> CsrfState {
+        CsrfState::new(CsrfConfig {
+            source: CsrfSource::parse(source).unwrap(),
+            header: "X-CSRF-Token".to_string(),
+        })
+    }
+
+    #[test]
+    fn finds_tokens_in_cookies_headers_and_bodies() {
+        let result = response(
+            &[
+                ("set-cookie", "session=s1; Path=/; HttpOnly"),
+                ("set-cookie", "XSRF-TOKEN=c2; Path=/"),
+                ("x-csrf-token", "h3"),
+            ],
+            r#"{"csrf":"j4"} <meta name="csrf-token" content="r5">"#,
+        );
+        let token = |source: &str| find_token(&CsrfSource::parse(source).unwrap(), &result);
+
+        assert_eq!(token("cookie:XSRF-TOKEN").as_deref(), Some("c2"));
+        assert_eq!(token("header:X-CSRF-Token").as_deref(), Some("h3"));
+        assert_eq!(token("regex:content=\"(\\w+)\":1").as_deref(), Some("r5"));
+        assert_eq!(token("cookie:missing"), None);
+        assert!(CsrfSource::parse("body").is_err());
+    }
+
+    #[test]
+    fn carries_tokens_from_safe_to_mutating_requests() {
+        let mut csrf = state("header:X-CSRF-Token");
+        let mut headers = vec![("Accept".to_string(), "*/*".to_string())];
+
+        // No token yet
+        csrf.inject(&Method::POST, &mut headers);
+        assert_eq!(headers.len(), 1);
+
+        // Mutating requests' responses don't replace it
+        csrf.observe(&Method::GET, &response(&[("x-csrf-token", "t1")], ""));
+        csrf.observe(&Method::POST, &response(&[("x-csrf-token", "t2")], ""));
+
+        csrf.inject(&Method::GET, &mut headers);
+        assert_eq!(headers.len(), 1);
+        csrf.inject(&Method::DELETE, &mut headers);
+        csrf.inject(&Method::DELETE, &mut headers);
+        assert_eq!(
+            headers,
+            [
+                ("Accept".to_string(), "*/*".to_string()),
+                ("X-CSRF-Token".to_string(), "t1".to_string())
+            ]
+        );
+    }
+}
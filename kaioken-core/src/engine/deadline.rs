@@ -0,0 +1,176 @@
+//! Retries under a total deadline (`--retries`, `--deadline`)
+//!
+//! `--timeout` bounds a single attempt; the deadline bounds the whole request
+//! the way a client SLA does, across redirects, retries and the waits between
+//! them. Running out of budget is its own error kind, `deadline`.
+
+use crate::types::{ErrorKind, LoadConfig, RequestResult};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::time::{sleep, timeout};
+use tokio_util::sync::CancellationToken;
+
+/// Wait before a retry the server didn't time with Retry-After, doubling with
+/// each retry up to `MAX_BACKOFF`
+const FIRST_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_BACKOFF: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    retries: u32,
+    deadline: Option<Duration>,
+}
+
+impl Budget {
+    /// `None` unless the run retries or has a deadline
+    pub fn for_config(config: &LoadConfig) -> Option<Self> {
+        (config.retries > 0 || config.deadline.is_some()).then_some(Self {
+            retries: config.retries,
+            deadline: config.deadline,
+        })
+    }
+
+    /// Send attempts until one isn't worth retrying, the retries run out or
+    /// the deadline passes. A retried request's latency covers every attempt.
+    /// `None` once an attempt is abandoned or the run is cancelled.
+    pub async fn run<Fut>(
+        &self,
+        cancel: &CancellationToken,
+        mut attempt: impl FnMut() -> Fut,
+    ) -> Option<RequestResult>
+    where
+        Fut: Future<Output = Option<RequestResult>>,
+    {
+        let started = Instant::now();
+        let mut retries = 0;
+        loop {
+            let mut result = match self.remaining(started) {
+                Some(Duration::ZERO) => return Some(exceeded(started)),
+                Some(remaining) => match timeout(remaining, attempt()).await {
+                    Ok(result) => result?,
+                    Err(_) => return Some(exceeded(started)),
+                },
+                None => attempt().await?,
+            };
+            if retries > 0 {
+                result.latency_us = started.elapsed().as_micros() as u64;
+            }
+            if retries >= self.retries || !retryable(&result) {
+                return Some(result);
+            }
+            let mut wait = result
+                .retry_after
+                .unwrap_or_else(|| (FIRST_BACKOFF * 2u32.saturating_pow(retries)).min(MAX_BACKOFF));
+            if let Some(remaining) = self.remaining(started) {
+                wait = wait.min(remaining);
+            }
+            retries += 1;
+            tokio::select! {
+                _ = sleep(wait) => {}
+                _ = cancel.cancelled() => return None,
+            }
+        }
+    }
+
+    fn remaining(&self, started: Instant) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_sub(started.elapsed()))
+    }
+}
+
+/// Transport failures, 5xx and 429 are worth another attempt
+fn retryable(result: &RequestResult) -> bool {
+    match result.error {
+        Some(kind) => !matches!(kind, ErrorKind::JsonRpc | ErrorKind::Plugin),
+        None => matches!(result.status, Some(429 | 500..)),
+    }
+}
+
+fn exceeded(started: Instant) -> RequestResult {
+    RequestResult::error(started.elapsed().as_micros() as u64, ErrorKind::Deadline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn budget(retries: u32, deadline: Option<u64>) -> Budget {
+        Budget {
+            retries,
+            deadline: deadline.map(Duration::from_millis),
+        }
+    }
+
+    fn response(status: u16) -> Option<RequestResult> {
+        Some(RequestResult::success(1_000, status, 0, None))
+    }
+
+    #[test]
+    fn retries_failures_only() {
+        assert!(retryable(&response(503).unwrap()));
+        assert!(retryable(&response(429).unwrap()));
+        assert!(retryable(&RequestResult::error(0, ErrorKind::Timeout)));
+        assert!(!retryable(&response(200).unwrap()));
+        assert!(!retryable(&response(404).unwrap()));
+        assert!(!retryable(&RequestResult::error(0, ErrorKind::JsonRpc)));
+    }
+
+    #[tokio::test]
+    async fn retries_until_an_attempt_succeeds() {
+        let attempts = Cell::new(0);
+        let result = budget(3, None)
+            .run(&CancellationToken::new(), || {
+                attempts.set(attempts.get() + 1);
+                let status = if attempts.get() < 3 { 503 } else { 200 };
+                async move { response(status) }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(attempts.get(), 3);
+        assert_eq!(result.status, Some(200));
+        // Covers the 100ms and 200ms backoffs
+        assert!(result.latency_us >= 300_000);
+    }
+
+    #[tokio::test]
+    async fn gives_up_when_retries_run_out() {
+        let attempts = Cell::new(0);
+        let result = budget(1, None)
+            .run(&CancellationToken::new(), || {
+                attempts.set(attempts.get() + 1);
+                async { response(500) }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(attempts.get(), 2);
+        assert_eq!(result.status, Some(500));
+    }
+
+    #[tokio::test]
+    async fn deadline_cuts_off_slow_attempts_and_backoffs() {
+        let slow = budget(0, Some(50))
+            .run(&CancellationToken::new(), || async {
+                sleep(Duration::from_secs(5)).await;
+                response(200)
+            })
+            .await
+            .unwrap();
+        assert_eq!(slow.error, Some(ErrorKind::Deadline));
+        assert!(slow.latency_us < 1_000_000);
+
+        // The first backoff alone outlasts the budget
+        let attempts = Cell::new(0);
+        let retried = budget(5, Some(50))
+            .run(&CancellationToken::new(), || {
+                attempts.set(attempts.get() + 1);
+                async { response(503) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(attempts.get(), 1);
+        assert_eq!(retried.error, Some(ErrorKind::Deadline));
+    }
+}
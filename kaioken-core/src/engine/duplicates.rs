@@ -0,0 +1,128 @@
+use crate::types::{DuplicateConfig, DuplicateStats, RequestResult};
+use rand::Rng;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// `--duplicate-rate`: VU workers send a share of their requests twice at
+/// once under one idempotency key, the way a client retrying on a timeout
+/// would, and compare what came back. An idempotent endpoint answers both
+/// copies the same; a race in its key handling shows up as two different
+/// responses. The aggregator reads the counts into each snapshot.
+#[derive(Debug)]
+pub struct Duplicates {
+    config: DuplicateConfig,
+    pairs: AtomicU64,
+    consistent: AtomicU64,
+    status_mismatch: AtomicU64,
+    body_mismatch: AtomicU64,
+    incomplete: AtomicU64,
+}
+
+impl Duplicates {
+    pub fn new(config: DuplicateConfig) -> Self {
+        Self {
+            config,
+            pairs: AtomicU64::new(0),
+            consistent: AtomicU64::new(0),
+            status_mismatch: AtomicU64::new(0),
+            body_mismatch: AtomicU64::new(0),
+            incomplete: AtomicU64::new(0),
+        }
+    }
+
+    /// Header the idempotency key goes in
+    pub fn header(&self) -> &str {
+        &self.config.header
+    }
+
+    /// An idempotency key if the next request is to be sent twice. Keys
+    /// stay random under `--seed`, so a rerun can't hit the previous run's.
+    pub fn roll(&self, rng: &mut impl Rng) -> Option<String> {
+        (rng.random::<f64>() < self.config.rate).then(|| format!("{:032x}", rand::random::<u128>()))
+    }
+
+    /// Compare the responses to both copies of a request
+    pub fn compare(&self, first: &RequestResult, second: &RequestResult) {
+        self.pairs.fetch_add(1, Ordering::Relaxed);
+        let counter = if first.error.is_some() || second.error.is_some() {
+            &self.incomplete
+        } else if first.status != second.status {
+            &self.status_mismatch
+        } else if first.body != second.body {
+            &self.body_mismatch
+        } else {
+            &self.consistent
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> DuplicateStats {
+        DuplicateStats {
+            pairs: self.pairs.load(Ordering::Relaxed),
+            consistent: self.consistent.load(Ordering::Relaxed),
+            status_mismatch: self.status_mismatch.load(Ordering::Relaxed),
+            body_mismatch: self.body_mismatch.load(Ordering::Relaxed),
+            incomplete: self.incomplete.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Forget warmup pairs
+    pub fn reset(&self) {
+        self.pairs.store(0, Ordering::Relaxed);
+        self.consistent.store(0, Ordering::Relaxed);
+        self.status_mismatch.store(0, Ordering::Relaxed);
+        self.body_mismatch.store(0, Ordering::Relaxed);
+        self.incomplete.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ErrorKind;
+
+    fn duplicates(rate: f64) -> Duplicates {
+        Duplicates::new(DuplicateConfig {
+            rate,
+            header: "Idempotency-Key".to_string(),
+        })
+    }
+
+    fn response(status: u16, body: &str) -> RequestResult {
+        RequestResult::success(1_000, status, 0, Some(body.to_string()))
+    }
+
+    #[test]
+    fn rolls_fresh_keys_at_the_rate() {
+        let mut rng = rand::rng();
+        assert!(duplicates(0.0).roll(&mut rng).is_none());
+
+        let always = duplicates(1.0);
+        let first = always.roll(&mut rng).unwrap();
+        let second = always.roll(&mut rng).unwrap();
+        assert_eq!(first.len(), 32);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn counts_how_the_copies_compare() {
+        let duplicates = duplicates(0.5);
+        duplicates.compare(&response(201, "{\"id\":1}"), &response(201, "{\"id\":1}"));
+        duplicates.compare(&response(201, "{\"id\":1}"), &response(409, "conflict"));
+        duplicates.compare(&response(201, "{\"id\":1}"), &response(201, "{\"id\":2}"));
+        duplicates.compare(
+            &response(201, "{\"id\":1}"),
+            &RequestResult::error(1_000, ErrorKind::Timeout),
+        );
+
+        let stats = duplicates.stats();
+        assert_eq!(stats.pairs, 4);
+        assert_eq!(stats.consistent, 1);
+        assert_eq!(stats.status_mismatch, 1);
+        assert_eq!(stats.body_mismatch, 1);
+        assert_eq!(stats.incomplete, 1);
+        assert_eq!(stats.inconsistent(), 2);
+
+        duplicates.reset();
+        assert_eq!(duplicates.stats().pairs, 0);
+    }
+}
@@ -0,0 +1,141 @@
+//! Endpoint names for the per-endpoint breakdown
+//!
+//! URLs from `--urls-from-file`, `--rand-regex-url` or interpolated scenarios
+//! carry IDs, so bucketing by raw path would give every request its own row.
+//! Paths go through the configured `endpoint_groups` rewrites first, then
+//! numeric, UUID and hash-like segments are folded into placeholders.
+
+use crate::types::{EndpointGroup, LoadConfig, StdinInput};
+
+/// Distinct endpoints tracked before the rest are counted as `OTHER_ENDPOINT`
+pub const MAX_ENDPOINTS: usize = 100;
+pub const OTHER_ENDPOINT: &str = "(other)";
+
+/// Hex segments at least this long are treated as hashes or object IDs
+const MIN_HASH_LEN: usize = 16;
+
+pub struct EndpointNamer {
+    groups: Vec<EndpointGroup>,
+}
+
+impl EndpointNamer {
+    /// `None` when the run only ever hits one endpoint
+    pub fn for_config(config: &LoadConfig) -> Option<Self> {
+        let http_scenarios = config
+            .scenarios
+            .iter()
+            .filter(|s| !s.is_websocket())
+            .count();
+        let varied = config.url_list.is_some()
+            || config
+                .stdin
+                .as_ref()
+                .is_some_and(|s| s.input == StdinInput::Urls)
+            || config.rand_regex_url.is_some()
+            || http_scenarios > 1
+            || config.scenarios.iter().any(|s| s.url.contains("${"))
+            || config.url.contains("${");
+        (varied || !config.endpoint_groups.is_empty()).then(|| Self {
+            groups: config.endpoint_groups.clone(),
+        })
+    }
+
+    /// `GET /users/{id}` for `GET https://api.example.com/users/42?x=1`
+    pub fn name(&self, method: &reqwest::Method, url: &str) -> String {
+        let mut path = url_path(url).to_string();
+        for group in &self.groups {
+            if group.pattern.is_match(&path) {
+                path = group
+                    .pattern
+                    .replace_all(&path, group.name.as_str())
+                    .into_owned();
+            }
+        }
+        format!("{} {}", method, normalize_path(&path))
+    }
+}
+
+/// Path of an absolute URL, without query or fragment
+fn url_path(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = rest.find('/').map_or("/", |i| &rest[i..]);
+    path.split(['?', '#']).next().unwrap_or("/")
+}
+
+fn normalize_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| placeholder(segment).unwrap_or(segment))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn placeholder(segment: &str) -> Option<&'static str> {
+    if segment.is_empty() {
+        None
+    } else if segment.bytes().all(|b| b.is_ascii_digit()) {
+        Some("{id}")
+    } else if is_uuid(segment) {
+        Some("{uuid}")
+    } else if segment.len() >= MIN_HASH_LEN && segment.bytes().all(|b| b.is_ascii_hexdigit()) {
+        Some("{hash}")
+    } else {
+        None
+    }
+}
+
+fn is_uuid(segment: &str) -> bool {
+    segment.len() == 36
+        && segment.bytes().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::Method;
+
+    fn namer(groups: &[(&str, &str)]) -> EndpointNamer {
+        EndpointNamer {
+            groups: groups
+                .iter()
+                .map(|(pattern, name)| EndpointGroup {
+                    pattern: regex_lite::Regex::new(pattern).unwrap(),
+                    name: name.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn folds_ids_into_placeholders() {
+        let namer = namer(&[]);
+        assert_eq!(
+            namer.name(&Method::GET, "https://api.example.com/users/42?page=2"),
+            "GET /users/{id}"
+        );
+        assert_eq!(
+            namer.name(
+                &Method::DELETE,
+                "http://localhost:8080/orders/3f2504e0-4f89-11d3-9a0c-0305e82c3301/items/7"
+            ),
+            "DELETE /orders/{uuid}/items/{id}"
+        );
+        assert_eq!(
+            namer.name(&Method::GET, "http://h/blobs/0123456789abcdef0123"),
+            "GET /blobs/{hash}"
+        );
+        assert_eq!(namer.name(&Method::GET, "http://h"), "GET /");
+        assert_eq!(namer.name(&Method::GET, "http://h/v2/cafe"), "GET /v2/cafe");
+    }
+
+    #[test]
+    fn groups_rewrite_before_normalizing() {
+        let namer = namer(&[(r"/products/[a-z0-9-]+", "/products/{slug}")]);
+        assert_eq!(
+            namer.name(&Method::GET, "http://h/products/red-shoes-42/reviews/9"),
+            "GET /products/{slug}/reviews/{id}"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use crate::types::{ExtractionStats, Scenario};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Attempts and failures of every `[scenarios.extract]` entry. A failed
+/// extraction leaves the variable unset or stale, so the requests that use
+/// it go out with garbage; counting them makes that visible. VU workers
+/// record each attempt; the aggregator reads them into each snapshot.
+#[derive(Debug)]
+pub struct ExtractionCounts {
+    // Per scenario, one slot per extraction, in config order
+    scenarios: Vec<Vec<Slot>>,
+}
+
+#[derive(Debug)]
+struct Slot {
+    scenario: String,
+    name: String,
+    attempts: AtomicU64,
+    failed: AtomicU64,
+    warned: AtomicBool,
+}
+
+impl ExtractionCounts {
+    /// None when no scenario extracts anything
+    pub fn for_scenarios(scenarios: &[Scenario]) -> Option<Self> {
+        if scenarios.iter().all(|s| s.extractions.is_empty()) {
+            return None;
+        }
+        let scenarios = scenarios
+            .iter()
+            .map(|scenario| {
+                scenario
+                    .extractions
+                    .iter()
+                    .map(|extraction| Slot {
+                        scenario: scenario.name.clone(),
+                        name: extraction.name.clone(),
+                        attempts: AtomicU64::new(0),
+                        failed: AtomicU64::new(0),
+                        warned: AtomicBool::new(false),
+                    })
+                    .collect()
+            })
+            .collect();
+        Some(Self { scenarios })
+    }
+
+    /// Count an attempt at extraction `index` of `scenario`; the first
+    /// failure of each is logged
+    pub fn record(&self, scenario: usize, index: usize, found: bool) {
+        let Some(slot) = self.scenarios.get(scenario).and_then(|s| s.get(index)) else {
+            return;
+        };
+        slot.attempts.fetch_add(1, Ordering::Relaxed);
+        if found {
+            return;
+        }
+        slot.failed.fetch_add(1, Ordering::Relaxed);
+        if !slot.warned.swap(true, Ordering::Relaxed) {
+            tracing::warn!(
+                "Extraction '{}' in scenario '{}' found nothing; requests using it are sent without the value",
+                slot.name,
+                slot.scenario
+            );
+        }
+    }
+
+    pub fn stats(&self) -> Vec<ExtractionStats> {
+        self.scenarios
+            .iter()
+            .flatten()
+            .map(|slot| ExtractionStats {
+                scenario: slot.scenario.clone(),
+                name: slot.name.clone(),
+                attempts: slot.attempts.load(Ordering::Relaxed),
+                failed: slot.failed.load(Ordering::Relaxed),
+            })
+            .filter(|stats| stats.attempts > 0)
+            .collect()
+    }
+
+    /// Forget warmup attempts
+    pub fn reset(&self) {
+        for slot in self.scenarios.iter().flatten() {
+            slot.attempts.store(0, Ordering::Relaxed);
+            slot.failed.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Extraction, ExtractionSource};
+    use std::collections::HashMap;
+
+    fn scenario(name: &str, extract: &[&str]) -> Scenario {
+        Scenario {
+            name: name.to_string(),
+            url: "http://localhost/".to_string(),
+            method: reqwest::Method::GET,
+            headers: Vec::new(),
+            body: None,
+            weight: 1,
+            extractions: extract
+                .iter()
+                .map(|name| Extraction {
+                    name: name.to_string(),
+                    source: ExtractionSource::Body,
+                })
+                .collect(),
+            depends_on: None,
+            tags: HashMap::new(),
+            proxy: None,
+            think_time: None,
+        }
+    }
+
+    #[test]
+    fn counts_attempts_and_failures_per_extraction() {
+        assert!(ExtractionCounts::for_scenarios(&[scenario("home", &[])]).is_none());
+
+        let counts = ExtractionCounts::for_scenarios(&[
+            scenario("home", &[]),
+            scenario("login", &["token", "session"]),
+        ])
+        .unwrap();
+        counts.record(1, 0, true);
+        counts.record(1, 0, false);
+        counts.record(1, 0, false);
+
+        let stats = counts.stats();
+        // Extractions that were never tried aren't reported
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].scenario, "login");
+        assert_eq!(stats[0].name, "token");
+        assert_eq!(stats[0].attempts, 3);
+        assert_eq!(stats[0].failed, 2);
+
+        counts.reset();
+        assert!(counts.stats().is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Failover drill results (`--failover`)
+//!
+//! Restarting a database primary or pulling a node out from behind a load
+//! balancer mid-run is judged on how long it hurt, not on the run's overall
+//! error rate. The timeline already counts requests and errors per bucket,
+//! so each bucket gets an availability, and every run of buckets with
+//! failures in it becomes an incident: how long errors lasted, how much of
+//! that the target was down, and how long it took to recover.
+
+use crate::types::{FailoverIncident, FailoverStats, TimelineBucket};
+use std::time::Duration;
+
+/// Availability under which a bucket counts as down. Results reach the
+/// timeline as they're collected, so a few of them land in the bucket after
+/// the one they completed in, and a target that was down for a whole bucket
+/// rarely shows exactly none.
+const DOWN_BELOW: f64 = 0.5;
+
+/// Incidents in `timeline`, whose buckets are `interval` wide. A bucket no
+/// request completed in (a target hanging on to its connections) counts as
+/// down.
+pub fn analyze(timeline: &[TimelineBucket], interval: Duration) -> FailoverStats {
+    let interval_ms = interval.as_millis().max(1) as u64;
+    let mut availability = Vec::with_capacity(timeline.len());
+    let mut expected = timeline.first().map(|b| b.elapsed_ms).unwrap_or(0);
+    for bucket in timeline {
+        while expected < bucket.elapsed_ms {
+            availability.push(0.0);
+            expected += interval_ms;
+        }
+        availability.push(if bucket.requests == 0 {
+            0.0
+        } else {
+            bucket.requests.saturating_sub(bucket.errors) as f64 / bucket.requests as f64
+        });
+        expected = bucket.elapsed_ms + interval_ms;
+    }
+
+    let first_ms = timeline.first().map(|b| b.elapsed_ms).unwrap_or(0);
+    let at = |i: usize| Duration::from_millis(first_ms + i as u64 * interval_ms);
+    let mut incidents = Vec::new();
+    let mut i = 0;
+    while i < availability.len() {
+        if availability[i] >= 1.0 {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut first_down = None;
+        let mut down = 0u32;
+        while i < availability.len() && availability[i] < 1.0 {
+            if availability[i] < DOWN_BELOW {
+                first_down.get_or_insert(i);
+                down += 1;
+            }
+            i += 1;
+        }
+        let recovered = i < availability.len();
+        incidents.push(FailoverIncident {
+            start: at(start),
+            error_burst: at(i) - at(start),
+            downtime: interval * down,
+            time_to_recovery: recovered.then(|| at(i) - at(first_down.unwrap_or(start))),
+        });
+    }
+
+    FailoverStats {
+        interval,
+        availability,
+        incidents,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket(secs: u64, requests: u64, errors: u64) -> TimelineBucket {
+        TimelineBucket {
+            elapsed_secs: secs as u32,
+            elapsed_ms: secs * 1000,
+            requests,
+            errors,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn measures_bursts_downtime_and_recovery() {
+        let secs = Duration::from_secs;
+        // Degraded at 2s, down at 3s and through the empty 4s, back at 6s;
+        // then failing again when the run ends
+        let timeline = [
+            bucket(0, 100, 0),
+            bucket(1, 100, 0),
+            bucket(2, 100, 40),
+            bucket(3, 50, 45),
+            bucket(5, 100, 10),
+            bucket(6, 100, 0),
+            bucket(7, 100, 5),
+        ];
+        let stats = analyze(&timeline, secs(1));
+
+        assert_eq!(
+            stats.availability,
+            [1.0, 1.0, 0.6, 0.1, 0.0, 0.9, 1.0, 0.95]
+        );
+        assert_eq!(
+            stats.incidents,
+            [
+                FailoverIncident {
+                    start: secs(2),
+                    error_burst: secs(4),
+                    downtime: secs(2),
+                    time_to_recovery: Some(secs(3)),
+                },
+                FailoverIncident {
+                    start: secs(7),
+                    error_burst: secs(1),
+                    downtime: Duration::ZERO,
+                    time_to_recovery: None,
+                },
+            ]
+        );
+        assert_eq!(stats.downtime(), secs(2));
+        assert_eq!(stats.longest_burst(), secs(4));
+        assert_eq!(stats.uptime_ratio(), 3.0 / 8.0);
+
+        assert!(analyze(&[], secs(1)).incidents.is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+use crate::types::{
+    ErrorKind, RequestResult, UnhealthyWorker, WORKER_FAILURE_STREAK, WorkerHealthStats,
+};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// Per-worker request counts and failure streaks, so a single broken VU
+/// (a bad extracted token, a stuck session) is reported instead of just
+/// diluting the aggregate error rate. Workers record every result; the
+/// aggregator reads them into each snapshot.
+#[derive(Debug)]
+pub struct WorkerHealth {
+    slots: Vec<Slot>,
+}
+
+#[derive(Debug, Default)]
+struct Slot {
+    requests: AtomicU64,
+    failed: AtomicU64,
+    streak: AtomicU32,
+    longest: AtomicU32,
+    last_error: Mutex<Option<(Option<ErrorKind>, Option<u16>)>>,
+}
+
+impl WorkerHealth {
+    pub fn new(workers: u32) -> Self {
+        Self {
+            slots: (0..workers).map(|_| Slot::default()).collect(),
+        }
+    }
+
+    pub fn record(&self, id: u32, result: &RequestResult) {
+        let Some(slot) = self.slots.get(id as usize) else {
+            return;
+        };
+        slot.requests.fetch_add(1, Ordering::Relaxed);
+        if result.is_success() {
+            slot.streak.store(0, Ordering::Relaxed);
+            return;
+        }
+
+        slot.failed.fetch_add(1, Ordering::Relaxed);
+        let streak = slot.streak.fetch_add(1, Ordering::Relaxed) + 1;
+        slot.longest.fetch_max(streak, Ordering::Relaxed);
+        *slot.last_error.lock().unwrap() = Some((result.error, result.status));
+        if streak == WORKER_FAILURE_STREAK {
+            tracing::warn!(
+                "Worker {} has failed its last {} requests ({})",
+                id,
+                streak,
+                describe(result.error, result.status)
+            );
+        }
+    }
+
+    pub fn stats(&self) -> WorkerHealthStats {
+        let mut stats = WorkerHealthStats {
+            workers: self.slots.len() as u32,
+            requests_min: u64::MAX,
+            ..Default::default()
+        };
+        for (id, slot) in self.slots.iter().enumerate() {
+            let requests = slot.requests.load(Ordering::Relaxed);
+            stats.requests_min = stats.requests_min.min(requests);
+            stats.requests_max = stats.requests_max.max(requests);
+
+            let longest_streak = slot.longest.load(Ordering::Relaxed);
+            if longest_streak < WORKER_FAILURE_STREAK {
+                continue;
+            }
+            let last_error = slot
+                .last_error
+                .lock()
+                .unwrap()
+                .map(|(error, status)| describe(error, status))
+                .unwrap_or_default();
+            stats.unhealthy.push(UnhealthyWorker {
+                id: id as u32,
+                requests,
+                failed: slot.failed.load(Ordering::Relaxed),
+                streak: slot.streak.load(Ordering::Relaxed),
+                longest_streak,
+                last_error,
+            });
+        }
+        if stats.requests_min == u64::MAX {
+            stats.requests_min = 0;
+        }
+        stats
+    }
+
+    /// Forget warmup requests
+    pub fn reset(&self) {
+        for slot in &self.slots {
+            slot.requests.store(0, Ordering::Relaxed);
+            slot.failed.store(0, Ordering::Relaxed);
+            slot.streak.store(0, Ordering::Relaxed);
+            slot.longest.store(0, Ordering::Relaxed);
+            *slot.last_error.lock().unwrap() = None;
+        }
+    }
+}
+
+fn describe(error: Option<ErrorKind>, status: Option<u16>) -> String {
+    match (error, status) {
+        (Some(kind), _) => kind.as_str().to_string(),
+        (None, Some(status)) => format!("status {}", status),
+        (None, None) => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16) -> RequestResult {
+        RequestResult::success(1_000, status, 0, None)
+    }
+
+    #[test]
+    fn reports_workers_that_keep_failing() {
+        let health = WorkerHealth::new(3);
+        for _ in 0..WORKER_FAILURE_STREAK {
+            health.record(0, &response(200));
+            health.record(1, &response(401));
+        }
+        health.record(2, &response(500));
+
+        let stats = health.stats();
+        assert_eq!(stats.workers, 3);
+        assert_eq!(stats.requests_min, 1);
+        assert_eq!(stats.requests_max, WORKER_FAILURE_STREAK as u64);
+        assert_eq!(stats.unhealthy.len(), 1);
+        let worker = &stats.unhealthy[0];
+        assert_eq!(worker.id, 1);
+        assert_eq!(worker.streak, WORKER_FAILURE_STREAK);
+        assert_eq!(worker.last_error, "status 401");
+        assert_eq!(stats.failing().count(), 1);
+    }
+
+    #[test]
+    fn recovered_workers_keep_their_longest_streak() {
+        let health = WorkerHealth::new(1);
+        for _ in 0..WORKER_FAILURE_STREAK {
+            health.record(0, &RequestResult::error(1_000, ErrorKind::Timeout));
+        }
+        health.record(0, &response(200));
+
+        let stats = health.stats();
+        assert_eq!(stats.unhealthy[0].streak, 0);
+        assert_eq!(stats.unhealthy[0].longest_streak, WORKER_FAILURE_STREAK);
+        assert_eq!(stats.unhealthy[0].last_error, "timeout");
+        assert_eq!(stats.failing().count(), 0);
+
+        health.reset();
+        assert!(health.stats().unhealthy.is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+//! Weighted hosts: one run split across several base URLs
+//!
+//! With `[[target.hosts]]`, each HTTP request keeps its path and query but
+//! goes to a host picked by weight, e.g. two regions or the blue and green
+//! halves of a deployment. Virtual hosts (`--host-header-list`) keep the URL
+//! and swap the `Host` header instead, fanning one address out across the
+//! tenants of a gateway. Results are tagged with the host's name for the
+//! per-host breakdown.
+
+use crate::types::{LoadConfig, TargetHost};
+use rand::Rng;
+
+pub struct HostPicker {
+    hosts: Vec<TargetHost>,
+    total_weight: u32,
+}
+
+impl HostPicker {
+    /// `None` unless the run has hosts configured
+    pub fn for_config(config: &LoadConfig) -> Option<Self> {
+        (!config.hosts.is_empty()).then(|| Self {
+            hosts: config.hosts.clone(),
+            total_weight: config.hosts.iter().map(|h| h.weight).sum(),
+        })
+    }
+
+    /// `url` sent to a host picked by weight, and the host's name. A virtual
+    /// host replaces any `Host` header in `headers`.
+    pub fn route(
+        &self,
+        url: &str,
+        headers: &mut Vec<(String, String)>,
+        rng: &mut impl Rng,
+    ) -> (String, &str) {
+        let host = self.pick(rng.random_range(0..self.total_weight.max(1)));
+        if let Some(ref host_header) = host.host_header {
+            headers.retain(|(name, _)| !name.eq_ignore_ascii_case("host"));
+            headers.push(("Host".to_string(), host_header.clone()));
+        }
+        let url = match host.url {
+            Some(ref base) => rebase(url, base),
+            None => url.to_string(),
+        };
+        (url, &host.name)
+    }
+
+    fn pick(&self, roll: u32) -> &TargetHost {
+        let mut cumulative = 0;
+        for host in &self.hosts {
+            cumulative += host.weight;
+            if roll < cumulative {
+                return host;
+            }
+        }
+        &self.hosts[0]
+    }
+}
+
+/// `url` with its scheme and authority replaced by `base`'s
+fn rebase(url: &str, base: &str) -> String {
+    let Some(scheme_end) = url.find("://") else {
+        return url.to_string();
+    };
+    let rest = &url[scheme_end + 3..];
+    let path = rest.find(['/', '?', '#']).map_or("", |i| &rest[i..]);
+    format!("{}{}", base.trim_end_matches('/'), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(name: &str, url: &str, weight: u32) -> TargetHost {
+        TargetHost {
+            name: name.to_string(),
+            url: Some(url.to_string()),
+            host_header: None,
+            weight,
+        }
+    }
+
+    #[test]
+    fn rebase_keeps_path_and_query() {
+        assert_eq!(
+            rebase(
+                "https://api.example.com/users/1?x=1",
+                "https://eu.example.com"
+            ),
+            "https://eu.example.com/users/1?x=1"
+        );
+        assert_eq!(
+            rebase("http://localhost:8080", "https://blue.example.com:8443/"),
+            "https://blue.example.com:8443"
+        );
+        assert_eq!(
+            rebase("http://localhost:8080?q", "http://green:9000"),
+            "http://green:9000?q"
+        );
+    }
+
+    #[test]
+    fn picks_hosts_by_weight() {
+        let picker = HostPicker {
+            hosts: vec![
+                host("us", "https://us.example.com", 3),
+                host("eu", "https://eu.example.com", 1),
+            ],
+            total_weight: 4,
+        };
+        let names: Vec<&str> = (0..4).map(|roll| picker.pick(roll).name.as_str()).collect();
+        assert_eq!(names, ["us", "us", "us", "eu"]);
+
+        let mut headers = Vec::new();
+        let (url, name) = picker.route(
+            "https://api.example.com/health",
+            &mut headers,
+            &mut rand::rng(),
+        );
+        assert!(url.ends_with(".example.com/health"));
+        assert!(url.contains(name));
+        assert!(headers.is_empty());
+    }
+
+    #[test]
+    fn virtual_hosts_keep_the_url_and_swap_the_host_header() {
+        let picker = HostPicker {
+            hosts: vec![TargetHost {
+                name: "tenant-a.example.com".to_string(),
+                url: None,
+                host_header: Some("tenant-a.example.com".to_string()),
+                weight: 1,
+            }],
+            total_weight: 1,
+        };
+        let mut headers = vec![
+            ("host".to_string(), "gateway".to_string()),
+            ("Accept".to_string(), "*/*".to_string()),
+        ];
+        let (url, name) = picker.route("http://10.0.0.7/api", &mut headers, &mut rand::rng());
+        assert_eq!(url, "http://10.0.0.7/api");
+        assert_eq!(name, "tenant-a.example.com");
+        assert_eq!(
+            headers,
+            vec![
+                ("Accept".to_string(), "*/*".to_string()),
+                ("Host".to_string(), "tenant-a.example.com".to_string()),
+            ]
+        );
+    }
+}
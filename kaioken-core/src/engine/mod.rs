@@ -0,0 +1,53 @@
+mod aggregator;
+mod arrival_rate;
+mod batch;
+mod burst;
+mod certs;
+mod chaos;
+mod connection_cap;
+mod control;
+mod csrf;
+mod deadline;
+mod duplicates;
+pub mod endpoints;
+mod extractions;
+mod failover;
+mod health;
+mod hosts;
+#[cfg(feature = "mqtt")]
+mod mqtt_worker;
+mod outage;
+pub mod prometheus;
+mod proxies;
+mod raw_worker;
+mod revalidation;
+mod runner;
+mod runtimes;
+mod scenario_mix;
+mod scheduler;
+mod seed;
+mod shards;
+mod slow_log;
+mod snapshot;
+#[cfg(feature = "socketio")]
+mod socketio_worker;
+mod stats;
+mod stdin;
+mod think;
+mod thresholds;
+mod throttle;
+mod vu_pool;
+mod warmup;
+mod worker;
+mod ws_aggregator;
+mod ws_broadcast;
+mod ws_stats;
+mod ws_worker;
+
+pub use control::RunControl;
+pub use runner::Engine;
+
+pub use snapshot::{apply_check_stats, create_snapshot, create_snapshot_with_arrival_rate};
+pub use stats::Stats;
+pub use thresholds::{evaluate_thresholds, metric_value, print_threshold_results};
+pub use ws_stats::WsStats;
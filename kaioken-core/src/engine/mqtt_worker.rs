@@ -0,0 +1,283 @@
+use crate::engine::ws_broadcast::{BROADCAST_TAG, broadcast_tag, now_unix_us, parse_broadcast_tag};
+use crate::mqtt::{Broker, MqttConnection, connect, publish_topic, subscription_filter};
+use crate::net::SocketOptions;
+use crate::types::{WsErrorKind, WsMessageResult};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+/// Broker and topic settings shared by publishers and subscribers
+#[derive(Clone)]
+pub struct MqttTarget {
+    pub broker: Broker,
+    pub topic: String,
+    pub qos: u8,
+    pub timeout: Duration,
+    pub socket: SocketOptions,
+}
+
+/// Publishes tagged messages to its own topic and reports publish latency
+pub struct MqttPublisher {
+    id: u32,
+    target: MqttTarget,
+    message: String,
+    message_interval: Duration,
+    subscribers_connected: Arc<AtomicU32>,
+    result_tx: mpsc::Sender<WsMessageResult>,
+    cancel_token: CancellationToken,
+}
+
+/// Subscribes to every publisher's topic and reports delivery latency
+pub struct MqttSubscriber {
+    id: u32,
+    target: MqttTarget,
+    subscribers_connected: Arc<AtomicU32>,
+    result_tx: mpsc::Sender<WsMessageResult>,
+    cancel_token: CancellationToken,
+}
+
+impl MqttPublisher {
+    pub fn new(
+        id: u32,
+        target: MqttTarget,
+        message: String,
+        message_interval: Duration,
+        subscribers_connected: Arc<AtomicU32>,
+        result_tx: mpsc::Sender<WsMessageResult>,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            id,
+            target,
+            message,
+            message_interval,
+            subscribers_connected,
+            result_tx,
+            cancel_token,
+        }
+    }
+
+    pub async fn run(self) {
+        tracing::debug!("MqttPublisher {} starting", self.id);
+
+        let client_id = format!("kaioken-{}-pub-{}", std::process::id(), self.id);
+        let topic = publish_topic(&self.target.topic, self.id);
+        let mut connection: Option<MqttConnection> = None;
+        let mut pending_connect_time: Option<u64> = None;
+        let mut seq: u64 = 0;
+
+        loop {
+            if self.cancel_token.is_cancelled() {
+                break;
+            }
+
+            if connection.is_none() {
+                match connect(
+                    &self.target.broker,
+                    &client_id,
+                    &self.target.socket,
+                    self.target.timeout,
+                )
+                .await
+                {
+                    Ok(conn) => {
+                        pending_connect_time = Some(conn.connect_time_us);
+                        connection = Some(conn);
+                    }
+                    Err(e) => {
+                        if self
+                            .result_tx
+                            .send(WsMessageResult::error(e))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                        tokio::select! {
+                            _ = sleep(Duration::from_secs(1)) => {}
+                            _ = self.cancel_token.cancelled() => break,
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            let conn = connection.as_mut().unwrap();
+
+            seq += 1;
+            let message = self
+                .message
+                .replace(BROADCAST_TAG, &broadcast_tag(self.id, seq))
+                .replace("${MESSAGE_ID}", &seq.to_string());
+            // Every subscriber connected right now should receive this message
+            let fanout = self.subscribers_connected.load(Ordering::Relaxed);
+
+            let start = Instant::now();
+            let mut result = match conn
+                .publish(
+                    &topic,
+                    message.as_bytes(),
+                    self.target.qos,
+                    self.target.timeout,
+                )
+                .await
+            {
+                Ok(()) => WsMessageResult::success(
+                    start.elapsed().as_micros() as u64,
+                    message.len() as u64,
+                    0,
+                )
+                .with_fanout(fanout),
+                Err(e) => WsMessageResult::error(e),
+            };
+            if let Some(connect_time) = pending_connect_time.take() {
+                result = result.with_connect_time(connect_time);
+            }
+
+            let connection_lost = matches!(
+                result.error,
+                Some(WsErrorKind::ConnectionClosed)
+                    | Some(WsErrorKind::SendFailed)
+                    | Some(WsErrorKind::ReceiveFailed)
+                    | Some(WsErrorKind::ProtocolError)
+            );
+
+            if self.result_tx.send(result).await.is_err() {
+                break;
+            }
+
+            if connection_lost {
+                connection = None;
+                continue;
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed < self.message_interval {
+                tokio::select! {
+                    _ = sleep(self.message_interval - elapsed) => {}
+                    _ = self.cancel_token.cancelled() => break,
+                }
+            }
+        }
+
+        if let Some(conn) = connection {
+            let _ = conn.close().await;
+        }
+
+        tracing::debug!("MqttPublisher {} stopped", self.id);
+    }
+}
+
+impl MqttSubscriber {
+    pub fn new(
+        id: u32,
+        target: MqttTarget,
+        subscribers_connected: Arc<AtomicU32>,
+        result_tx: mpsc::Sender<WsMessageResult>,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            id,
+            target,
+            subscribers_connected,
+            result_tx,
+            cancel_token,
+        }
+    }
+
+    pub async fn run(self) {
+        tracing::debug!("MqttSubscriber {} starting", self.id);
+
+        let client_id = format!("kaioken-{}-sub-{}", std::process::id(), self.id);
+        // Validated during config merge
+        let filter = subscription_filter(&self.target.topic).unwrap_or_default();
+
+        'outer: loop {
+            if self.cancel_token.is_cancelled() {
+                break;
+            }
+
+            let connected = async {
+                let mut conn = connect(
+                    &self.target.broker,
+                    &client_id,
+                    &self.target.socket,
+                    self.target.timeout,
+                )
+                .await?;
+                conn.subscribe(&filter, self.target.qos, self.target.timeout)
+                    .await?;
+                Ok::<_, WsErrorKind>(conn)
+            };
+            let mut conn = match connected.await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    if self
+                        .result_tx
+                        .send(WsMessageResult::error(e))
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                    tokio::select! {
+                        _ = sleep(Duration::from_secs(1)) => {}
+                        _ = self.cancel_token.cancelled() => break,
+                    }
+                    continue;
+                }
+            };
+
+            let subscribed = WsMessageResult::subscribed(conn.connect_time_us);
+            if self.result_tx.send(subscribed).await.is_err() {
+                break;
+            }
+            self.subscribers_connected.fetch_add(1, Ordering::Relaxed);
+
+            loop {
+                let received = tokio::select! {
+                    r = conn.receive(Duration::from_secs(1)) => r,
+                    _ = self.cancel_token.cancelled() => {
+                        self.subscribers_connected.fetch_sub(1, Ordering::Relaxed);
+                        let _ = conn.close().await;
+                        break 'outer;
+                    }
+                };
+
+                match received {
+                    Ok(payload) => {
+                        let text = String::from_utf8_lossy(&payload);
+                        let Some(sent_us) = parse_broadcast_tag(&text) else {
+                            continue;
+                        };
+                        let latency_us = now_unix_us().saturating_sub(sent_us);
+                        let result = WsMessageResult::delivery(latency_us, payload.len() as u64);
+                        if self.result_tx.send(result).await.is_err() {
+                            self.subscribers_connected.fetch_sub(1, Ordering::Relaxed);
+                            break 'outer;
+                        }
+                    }
+                    // Idle subscribers are normal; keep waiting
+                    Err(WsErrorKind::Timeout) => continue,
+                    Err(e) => {
+                        self.subscribers_connected.fetch_sub(1, Ordering::Relaxed);
+                        if self
+                            .result_tx
+                            .send(WsMessageResult::error(e))
+                            .await
+                            .is_err()
+                        {
+                            break 'outer;
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+
+        tracing::debug!("MqttSubscriber {} stopped", self.id);
+    }
+}
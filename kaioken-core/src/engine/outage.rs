@@ -0,0 +1,184 @@
+//! Pausing through target outages (`--pause-on-errors`)
+//!
+//! A target down for maintenance or mid-failover fails every request, and
+//! keeping the load on only buries the run in errors and hits the target
+//! again the moment it comes back. Once every request has failed for the
+//! configured window, the VUs hold off while a single health check polls the
+//! target once a second. The first check the target answers without a 5xx
+//! lets them go again, and the outage is kept as a window in the results.
+
+use crate::types::{OutageWindow, RequestResult};
+use reqwest::Client;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+/// How often a paused run checks whether the target is back
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct Outages {
+    window: Duration,
+    start: Instant,
+    /// Microseconds since `start` of the first failure in the current run
+    /// of failures, plus one (0 = the last result succeeded)
+    failing_since: AtomicU64,
+    paused: watch::Sender<bool>,
+    windows: Mutex<Vec<OutageWindow>>,
+}
+
+impl Outages {
+    /// Pause once every result for `window` has failed
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            start: Instant::now(),
+            failing_since: AtomicU64::new(0),
+            paused: watch::Sender::new(false),
+            windows: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn record(&self, result: &RequestResult) {
+        if result.is_success() {
+            self.failing_since.store(0, Ordering::Relaxed);
+            return;
+        }
+        let now = self.start.elapsed();
+        let first = now.as_micros() as u64 + 1;
+        let since = match self.failing_since.compare_exchange(
+            0,
+            first,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => first,
+            Err(since) => since,
+        };
+        let since = Duration::from_micros(since - 1);
+        if now.saturating_sub(since) < self.window {
+            return;
+        }
+        let paused = self
+            .paused
+            .send_if_modified(|paused| !std::mem::replace(paused, true));
+        if paused {
+            tracing::warn!(
+                "Every request has failed for {:?}; pausing until the target recovers",
+                now - since
+            );
+            self.windows.lock().unwrap().push(OutageWindow {
+                start: since,
+                duration: now - since,
+                health_checks: 0,
+                recovered: false,
+            });
+        }
+    }
+
+    /// Wait out a pause. False once cancelled.
+    pub async fn wait(&self, cancel: &CancellationToken) -> bool {
+        let mut paused = self.paused.subscribe();
+        tokio::select! {
+            _ = paused.wait_for(|paused| !paused) => true,
+            _ = cancel.cancelled() => false,
+        }
+    }
+
+    /// GET `url` once a second while paused, resuming the run on the first
+    /// response that isn't a 5xx
+    pub async fn monitor(&self, client: Client, url: String, cancel: CancellationToken) {
+        let mut paused = self.paused.subscribe();
+        loop {
+            tokio::select! {
+                changed = paused.wait_for(|paused| *paused) => if changed.is_err() { return },
+                _ = cancel.cancelled() => return,
+            }
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(HEALTH_CHECK_INTERVAL) => {}
+                    _ = cancel.cancelled() => return,
+                }
+                // Any answer but a 5xx means the target is serving again
+                let healthy = client
+                    .get(&url)
+                    .send()
+                    .await
+                    .is_ok_and(|response| !response.status().is_server_error());
+                if self.checked(healthy) {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Count a health check, resuming the run if it passed. True if it did.
+    fn checked(&self, healthy: bool) -> bool {
+        let mut windows = self.windows.lock().unwrap();
+        let Some(outage) = windows.last_mut() else {
+            return false;
+        };
+        outage.health_checks += 1;
+        if !healthy {
+            return false;
+        }
+        outage.duration = self.start.elapsed() - outage.start;
+        outage.recovered = true;
+        drop(windows);
+        tracing::info!("Target is back; resuming load");
+        self.failing_since.store(0, Ordering::Relaxed);
+        self.paused.send_replace(false);
+        true
+    }
+
+    /// Outages so far, the one still going on (if any) up to now
+    pub fn windows(&self) -> Vec<OutageWindow> {
+        let mut windows = self.windows.lock().unwrap().clone();
+        if let Some(outage) = windows.last_mut()
+            && !outage.recovered
+        {
+            outage.duration = self.start.elapsed() - outage.start;
+        }
+        windows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ErrorKind;
+
+    #[tokio::test]
+    async fn pauses_after_a_window_of_failures_until_a_check_passes() {
+        let outages = Outages::new(Duration::from_millis(20));
+        let cancel = CancellationToken::new();
+        let refused = RequestResult::error(100, ErrorKind::Refused);
+
+        // Failures broken up by a success don't add up
+        outages.record(&refused);
+        std::thread::sleep(Duration::from_millis(25));
+        outages.record(&RequestResult::success(100, 200, 0, None));
+        outages.record(&refused);
+        assert!(!*outages.paused.borrow());
+
+        std::thread::sleep(Duration::from_millis(25));
+        outages.record(&refused);
+        assert!(*outages.paused.borrow());
+        let windows = outages.windows();
+        assert_eq!(windows.len(), 1);
+        assert!(!windows[0].recovered);
+        assert!(windows[0].duration >= Duration::from_millis(20));
+
+        // VUs wait until a health check passes
+        let waiting = tokio::time::timeout(Duration::from_millis(20), outages.wait(&cancel));
+        assert!(waiting.await.is_err());
+        assert!(!outages.checked(false));
+        assert!(outages.checked(true));
+        assert!(outages.wait(&cancel).await);
+
+        let windows = outages.windows();
+        assert_eq!(windows[0].health_checks, 2);
+        assert!(windows[0].recovered);
+    }
+}
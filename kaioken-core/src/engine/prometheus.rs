@@ -0,0 +1,839 @@
+//! Prometheus metrics export for kaioken
+//!
+//! Supports two modes:
+//! - Push to Pushgateway: POST metrics to a Prometheus Pushgateway
+//! - Serve endpoint: Expose /metrics HTTP endpoint for scraping
+
+use crate::types::{DEFAULT_PROMETHEUS_BUCKETS, StatsSnapshot};
+use prometheus::core::{Collector, Desc};
+use prometheus::{Counter, Encoder, Gauge, Opts, Registry, TextEncoder, proto};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+
+/// Prometheus metrics exporter
+pub struct PrometheusExporter {
+    registry: Registry,
+    job: String,
+    instance: String,
+
+    // Counters (monotonically increasing)
+    requests_total: Counter,
+    requests_success: Counter,
+    requests_failed: Counter,
+    bytes_received: Counter,
+    bytes_sent: Counter,
+    connections_opened: Counter,
+    dropped_iterations: Counter,
+    results_dropped: Counter,
+
+    // Gauges (point-in-time values)
+    rps: Gauge,
+    error_rate: Gauge,
+    rx_mbps: Gauge,
+    tx_mbps: Gauge,
+    latency_p50: Gauge,
+    latency_p95: Gauge,
+    latency_p99: Gauge,
+    latency_p999: Gauge,
+    vus_active: Gauge,
+    vus_max: Gauge,
+
+    // Histogram (bucketed latency, mergeable across scrapes and generators)
+    latency: LatencyHistogram,
+
+    // Track previous values for counter deltas
+    prev_total: RwLock<u64>,
+    prev_success: RwLock<u64>,
+    prev_failed: RwLock<u64>,
+    prev_bytes: RwLock<u64>,
+    prev_bytes_sent: RwLock<u64>,
+    prev_connections: RwLock<u64>,
+    prev_dropped: RwLock<u64>,
+    prev_results_dropped: RwLock<u64>,
+}
+
+impl PrometheusExporter {
+    /// Create a new PrometheusExporter labelled with `job="kaioken"` and the target URL as `instance`
+    pub fn new(target_url: &str) -> Self {
+        Self::with_labels(target_url, &[])
+    }
+
+    /// Create a new PrometheusExporter with an explicit `instance` and extra const labels
+    /// on every metric. A `job` label replaces the default `kaioken`.
+    pub fn with_labels(instance: &str, extra_labels: &[(String, String)]) -> Self {
+        let registry = Registry::new();
+
+        let mut labels = HashMap::from([
+            ("job".to_string(), "kaioken".to_string()),
+            ("instance".to_string(), instance.to_string()),
+        ]);
+        labels.extend(extra_labels.iter().cloned());
+        let job = labels["job"].clone();
+
+        // Create counters
+        let requests_total = Counter::with_opts(
+            Opts::new("kaioken_requests_total", "Total HTTP requests made")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let requests_success = Counter::with_opts(
+            Opts::new("kaioken_requests_success_total", "Successful HTTP requests")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let requests_failed = Counter::with_opts(
+            Opts::new("kaioken_requests_failed_total", "Failed HTTP requests")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let bytes_received = Counter::with_opts(
+            Opts::new("kaioken_bytes_received_total", "Total bytes received")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let bytes_sent = Counter::with_opts(
+            Opts::new("kaioken_bytes_sent_total", "Total request body bytes sent")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let connections_opened = Counter::with_opts(
+            Opts::new(
+                "kaioken_connections_opened_total",
+                "New connections opened by the HTTP client",
+            )
+            .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let dropped_iterations = Counter::with_opts(
+            Opts::new(
+                "kaioken_dropped_iterations_total",
+                "Dropped iterations (arrival rate mode)",
+            )
+            .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let results_dropped = Counter::with_opts(
+            Opts::new(
+                "kaioken_results_dropped_total",
+                "Results discarded by the drop backpressure policy",
+            )
+            .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        // Create gauges
+        let rps = Gauge::with_opts(
+            Opts::new("kaioken_rps", "Current requests per second").const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let error_rate = Gauge::with_opts(
+            Opts::new("kaioken_error_rate", "Current error rate (0.0-1.0)")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let rx_mbps = Gauge::with_opts(
+            Opts::new("kaioken_rx_mbps", "Ingress throughput over the last second")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let tx_mbps = Gauge::with_opts(
+            Opts::new("kaioken_tx_mbps", "Egress throughput over the last second")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let latency_p50 = Gauge::with_opts(
+            Opts::new("kaioken_latency_p50_ms", "50th percentile latency in ms")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let latency_p95 = Gauge::with_opts(
+            Opts::new("kaioken_latency_p95_ms", "95th percentile latency in ms")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let latency_p99 = Gauge::with_opts(
+            Opts::new("kaioken_latency_p99_ms", "99th percentile latency in ms")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let latency_p999 = Gauge::with_opts(
+            Opts::new("kaioken_latency_p999_ms", "99.9th percentile latency in ms")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let vus_active = Gauge::with_opts(
+            Opts::new("kaioken_vus_active", "Currently active virtual users")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let vus_max = Gauge::with_opts(
+            Opts::new("kaioken_vus_max", "Maximum virtual users configured")
+                .const_labels(labels.clone()),
+        )
+        .unwrap();
+
+        let latency = LatencyHistogram::new(
+            Desc::new(
+                "kaioken_latency_ms".to_string(),
+                "Request latency in ms".to_string(),
+                Vec::new(),
+                labels.clone(),
+            )
+            .unwrap(),
+            &DEFAULT_PROMETHEUS_BUCKETS,
+        );
+
+        // Register all metrics
+        registry.register(Box::new(requests_total.clone())).unwrap();
+        registry
+            .register(Box::new(requests_success.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(requests_failed.clone()))
+            .unwrap();
+        registry.register(Box::new(bytes_received.clone())).unwrap();
+        registry.register(Box::new(bytes_sent.clone())).unwrap();
+        registry
+            .register(Box::new(connections_opened.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(dropped_iterations.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(results_dropped.clone()))
+            .unwrap();
+        registry.register(Box::new(rps.clone())).unwrap();
+        registry.register(Box::new(error_rate.clone())).unwrap();
+        registry.register(Box::new(rx_mbps.clone())).unwrap();
+        registry.register(Box::new(tx_mbps.clone())).unwrap();
+        registry.register(Box::new(latency_p50.clone())).unwrap();
+        registry.register(Box::new(latency_p95.clone())).unwrap();
+        registry.register(Box::new(latency_p99.clone())).unwrap();
+        registry.register(Box::new(latency_p999.clone())).unwrap();
+        registry.register(Box::new(vus_active.clone())).unwrap();
+        registry.register(Box::new(vus_max.clone())).unwrap();
+        registry.register(Box::new(latency.clone())).unwrap();
+
+        Self {
+            registry,
+            job,
+            instance: instance.to_string(),
+            requests_total,
+            requests_success,
+            requests_failed,
+            bytes_received,
+            bytes_sent,
+            connections_opened,
+            dropped_iterations,
+            results_dropped,
+            rps,
+            error_rate,
+            rx_mbps,
+            tx_mbps,
+            latency_p50,
+            latency_p95,
+            latency_p99,
+            latency_p999,
+            vus_active,
+            vus_max,
+            latency,
+            prev_total: RwLock::new(0),
+            prev_success: RwLock::new(0),
+            prev_failed: RwLock::new(0),
+            prev_bytes: RwLock::new(0),
+            prev_bytes_sent: RwLock::new(0),
+            prev_connections: RwLock::new(0),
+            prev_dropped: RwLock::new(0),
+            prev_results_dropped: RwLock::new(0),
+        }
+    }
+
+    /// Update all metrics from a snapshot
+    pub async fn update(&self, snapshot: &StatsSnapshot) {
+        // Update counters with deltas (counters can only increase)
+        let mut prev_total = self.prev_total.write().await;
+        if snapshot.total_requests > *prev_total {
+            self.requests_total
+                .inc_by((snapshot.total_requests - *prev_total) as f64);
+            *prev_total = snapshot.total_requests;
+        }
+
+        let mut prev_success = self.prev_success.write().await;
+        if snapshot.successful > *prev_success {
+            self.requests_success
+                .inc_by((snapshot.successful - *prev_success) as f64);
+            *prev_success = snapshot.successful;
+        }
+
+        let mut prev_failed = self.prev_failed.write().await;
+        if snapshot.failed > *prev_failed {
+            self.requests_failed
+                .inc_by((snapshot.failed - *prev_failed) as f64);
+            *prev_failed = snapshot.failed;
+        }
+
+        let mut prev_bytes = self.prev_bytes.write().await;
+        if snapshot.bytes_received > *prev_bytes {
+            self.bytes_received
+                .inc_by((snapshot.bytes_received - *prev_bytes) as f64);
+            *prev_bytes = snapshot.bytes_received;
+        }
+
+        let mut prev_bytes_sent = self.prev_bytes_sent.write().await;
+        if snapshot.bytes_sent > *prev_bytes_sent {
+            self.bytes_sent
+                .inc_by((snapshot.bytes_sent - *prev_bytes_sent) as f64);
+            *prev_bytes_sent = snapshot.bytes_sent;
+        }
+
+        let opened = snapshot.connections.map_or(0, |c| c.opened);
+        let mut prev_connections = self.prev_connections.write().await;
+        if opened > *prev_connections {
+            self.connections_opened
+                .inc_by((opened - *prev_connections) as f64);
+            *prev_connections = opened;
+        }
+
+        let mut prev_dropped = self.prev_dropped.write().await;
+        if snapshot.dropped_iterations > *prev_dropped {
+            self.dropped_iterations
+                .inc_by((snapshot.dropped_iterations - *prev_dropped) as f64);
+            *prev_dropped = snapshot.dropped_iterations;
+        }
+
+        let mut prev_results_dropped = self.prev_results_dropped.write().await;
+        if snapshot.results_dropped > *prev_results_dropped {
+            self.results_dropped
+                .inc_by((snapshot.results_dropped - *prev_results_dropped) as f64);
+            *prev_results_dropped = snapshot.results_dropped;
+        }
+
+        // Update gauges (point-in-time values)
+        self.rps.set(snapshot.requests_per_sec);
+        self.error_rate.set(snapshot.error_rate);
+        self.rx_mbps.set(snapshot.rolling_rx_bps / 1_000_000.0);
+        self.tx_mbps.set(snapshot.rolling_tx_bps / 1_000_000.0);
+        self.latency_p50
+            .set(snapshot.latency_p50_us as f64 / 1000.0);
+        self.latency_p95
+            .set(snapshot.latency_p95_us as f64 / 1000.0);
+        self.latency_p99
+            .set(snapshot.latency_p99_us as f64 / 1000.0);
+        self.latency_p999
+            .set(snapshot.latency_p999_us as f64 / 1000.0);
+        self.vus_active.set(snapshot.vus_active as f64);
+        self.vus_max.set(snapshot.vus_max as f64);
+
+        self.latency.update(snapshot);
+    }
+
+    /// Use these `kaioken_latency_ms` bucket bounds (ms, ascending) instead of
+    /// `DEFAULT_PROMETHEUS_BUCKETS`; snapshots must carry the same bounds
+    pub fn with_buckets(self, bounds: &[f64]) -> Self {
+        *self.latency.state.lock().unwrap() = HistogramState::new(bounds);
+        self
+    }
+
+    /// Encode all metrics in Prometheus text format
+    pub fn encode(&self) -> String {
+        let encoder = TextEncoder::new();
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        encoder.encode(&metric_families, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    /// The `job` label on every metric
+    pub fn job(&self) -> &str {
+        &self.job
+    }
+
+    /// The `instance` label on every metric
+    pub fn instance(&self) -> &str {
+        &self.instance
+    }
+}
+
+/// Push metrics to a Prometheus Pushgateway
+pub async fn push_to_gateway(
+    url: &str,
+    job: &str,
+    instance: Option<&str>,
+    metrics: &str,
+) -> Result<(), String> {
+    let client = reqwest::Client::new();
+    let push_url = grouping_url(url, job, instance);
+
+    let response = client
+        .post(&push_url)
+        .body(metrics.to_string())
+        .header("Content-Type", "text/plain; charset=utf-8")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to push to Pushgateway: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Pushgateway returned error status: {}",
+            response.status()
+        ));
+    }
+
+    Ok(())
+}
+
+/// `kaioken_latency_ms` as a native histogram. Snapshots already carry
+/// cumulative bucket counts, so this is a collector over those rather than a
+/// `prometheus::Histogram` fed one observation at a time.
+#[derive(Clone)]
+struct LatencyHistogram {
+    desc: Desc,
+    state: Arc<Mutex<HistogramState>>,
+}
+
+struct HistogramState {
+    /// Totals from before the stats were last reset (at the end of warmup),
+    /// so the exported histogram only ever grows
+    base: HistogramCounts,
+    current: HistogramCounts,
+}
+
+#[derive(Clone)]
+struct HistogramCounts {
+    /// (upper bound in ms, cumulative count)
+    buckets: Vec<(f64, u64)>,
+    count: u64,
+    sum_ms: f64,
+}
+
+impl HistogramCounts {
+    fn zero(bounds: &[f64]) -> Self {
+        Self {
+            buckets: bounds.iter().map(|&bound| (bound, 0)).collect(),
+            count: 0,
+            sum_ms: 0.0,
+        }
+    }
+
+    fn plus(&self, other: &HistogramCounts) -> Self {
+        Self {
+            buckets: self
+                .buckets
+                .iter()
+                .zip(&other.buckets)
+                .map(|(&(bound, a), &(_, b))| (bound, a + b))
+                .collect(),
+            count: self.count + other.count,
+            sum_ms: self.sum_ms + other.sum_ms,
+        }
+    }
+}
+
+impl HistogramState {
+    fn new(bounds: &[f64]) -> Self {
+        Self {
+            base: HistogramCounts::zero(bounds),
+            current: HistogramCounts::zero(bounds),
+        }
+    }
+}
+
+impl LatencyHistogram {
+    fn new(desc: Desc, bounds: &[f64]) -> Self {
+        Self {
+            desc,
+            state: Arc::new(Mutex::new(HistogramState::new(bounds))),
+        }
+    }
+
+    fn update(&self, snapshot: &StatsSnapshot) {
+        let mut state = self.state.lock().unwrap();
+        if snapshot.latency_buckets.len() != state.current.buckets.len() {
+            return;
+        }
+        let counts = HistogramCounts {
+            buckets: snapshot.latency_buckets.clone(),
+            count: snapshot.total_requests,
+            sum_ms: snapshot.latency_mean_us * snapshot.total_requests as f64 / 1000.0,
+        };
+        if counts.count < state.current.count {
+            state.base = state.base.plus(&state.current);
+        }
+        state.current = counts;
+    }
+}
+
+impl Collector for LatencyHistogram {
+    fn desc(&self) -> Vec<&Desc> {
+        vec![&self.desc]
+    }
+
+    fn collect(&self) -> Vec<proto::MetricFamily> {
+        let total = {
+            let state = self.state.lock().unwrap();
+            state.base.plus(&state.current)
+        };
+
+        let mut histogram = proto::Histogram::default();
+        histogram.set_sample_count(total.count);
+        histogram.set_sample_sum(total.sum_ms);
+        let buckets: Vec<proto::Bucket> = total
+            .buckets
+            .iter()
+            .map(|&(bound, count)| {
+                let mut bucket = proto::Bucket::default();
+                bucket.set_upper_bound(bound);
+                bucket.set_cumulative_count(count);
+                bucket
+            })
+            .collect();
+        histogram.set_bucket(buckets.into());
+
+        let mut metric = proto::Metric::default();
+        metric.set_label(self.desc.const_label_pairs.clone().into());
+        metric.set_histogram(histogram);
+
+        let mut family = proto::MetricFamily::default();
+        family.set_name(self.desc.fq_name.clone());
+        family.set_help(self.desc.help.clone());
+        family.set_field_type(proto::MetricType::HISTOGRAM);
+        family.set_metric(vec![metric].into());
+        vec![family]
+    }
+}
+
+/// Pushgateway URL for a grouping key. Named instances get their own group so
+/// several generators pushing to one gateway don't overwrite each other.
+fn grouping_url(url: &str, job: &str, instance: Option<&str>) -> String {
+    let mut push_url = format!("{}/metrics/job/{}", url.trim_end_matches('/'), job);
+    if let Some(instance) = instance {
+        push_url.push_str("/instance/");
+        push_url.push_str(instance);
+    }
+    push_url
+}
+
+/// Serve a /metrics endpoint for Prometheus scraping
+pub async fn serve_metrics_endpoint(
+    port: u16,
+    exporter: Arc<PrometheusExporter>,
+    cancel_token: tokio_util::sync::CancellationToken,
+) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    let addr = format!("0.0.0.0:{}", port);
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!(
+                "Failed to bind Prometheus metrics endpoint on {}: {}",
+                addr,
+                e
+            );
+            return;
+        }
+    };
+
+    tracing::info!(
+        "Prometheus metrics endpoint listening on http://{}/metrics",
+        addr
+    );
+
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => {
+                tracing::debug!("Prometheus metrics endpoint shutting down");
+                break;
+            }
+            accept_result = listener.accept() => {
+                match accept_result {
+                    Ok((mut socket, _)) => {
+                        let exporter = exporter.clone();
+                        tokio::spawn(async move {
+                            let mut buf = [0u8; 1024];
+                            if socket.read(&mut buf).await.is_ok() {
+                                let request = String::from_utf8_lossy(&buf);
+
+                                // Simple HTTP parsing - check if it's a GET /metrics request
+                                if request.starts_with("GET /metrics") || request.starts_with("GET / ") {
+                                    let body = exporter.encode();
+                                    let response = format!(
+                                        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                                        body.len(),
+                                        body
+                                    );
+                                    let _ = socket.write_all(response.as_bytes()).await;
+                                } else if request.starts_with("GET /health") {
+                                    let response = "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 2\r\nConnection: close\r\n\r\nOK";
+                                    let _ = socket.write_all(response.as_bytes()).await;
+                                } else {
+                                    let response = "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nContent-Length: 9\r\nConnection: close\r\n\r\nNot Found";
+                                    let _ = socket.write_all(response.as_bytes()).await;
+                                }
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to accept connection: {}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exporter_creation() {
+        let exporter = PrometheusExporter::new("https://example.com/api");
+        assert_eq!(exporter.job(), "kaioken");
+        assert_eq!(exporter.instance(), "https://example.com/api");
+    }
+
+    #[test]
+    fn test_custom_labels_and_instance() {
+        let labels = vec![
+            ("env".to_string(), "staging".to_string()),
+            ("job".to_string(), "checkout-load".to_string()),
+        ];
+        let exporter = PrometheusExporter::with_labels("gen-1", &labels);
+        assert_eq!(exporter.job(), "checkout-load");
+        assert_eq!(exporter.instance(), "gen-1");
+
+        let encoded = exporter.encode();
+        assert!(
+            encoded.contains(r#"kaioken_rps{env="staging",instance="gen-1",job="checkout-load"}"#)
+        );
+    }
+
+    #[test]
+    fn test_latency_histogram_from_stats() {
+        use crate::engine::{Stats, create_snapshot};
+        use crate::types::RequestResult;
+        use std::time::Duration;
+
+        let mut stats = Stats::new(Duration::from_secs(1)).with_latency_buckets(&[5.0, 50.0]);
+        for latency_us in [1_000, 4_000, 20_000, 80_000] {
+            stats.record(&RequestResult::success(latency_us, 200, 10, None));
+        }
+        let snapshot = create_snapshot(&stats);
+        assert_eq!(snapshot.latency_buckets, vec![(5.0, 2), (50.0, 3)]);
+
+        let exporter = PrometheusExporter::new("https://example.com").with_buckets(&[5.0, 50.0]);
+        exporter.latency.update(&snapshot);
+        let encoded = exporter.encode();
+        assert!(encoded.contains("# TYPE kaioken_latency_ms histogram"));
+        assert!(encoded.contains(
+            r#"kaioken_latency_ms_bucket{instance="https://example.com",job="kaioken",le="5"} 2"#
+        ));
+        assert!(encoded.contains(
+            r#"kaioken_latency_ms_bucket{instance="https://example.com",job="kaioken",le="50"} 3"#
+        ));
+        assert!(encoded.contains(
+            r#"kaioken_latency_ms_bucket{instance="https://example.com",job="kaioken",le="+Inf"} 4"#
+        ));
+        assert!(encoded.contains(
+            r#"kaioken_latency_ms_count{instance="https://example.com",job="kaioken"} 4"#
+        ));
+    }
+
+    #[test]
+    fn test_latency_histogram_survives_stats_reset() {
+        let exporter = PrometheusExporter::new("https://example.com").with_buckets(&[10.0]);
+        let snapshot = |buckets: u64, total: u64| StatsSnapshot {
+            total_requests: total,
+            latency_mean_us: 2000.0,
+            latency_buckets: vec![(10.0, buckets)],
+            ..Default::default()
+        };
+        exporter.latency.update(&snapshot(8, 10));
+        // Stats reset at the end of warmup; the exported counts keep growing
+        exporter.latency.update(&snapshot(3, 4));
+
+        let encoded = exporter.encode();
+        assert!(encoded.contains(r#"le="10"} 11"#));
+        assert!(encoded.contains(
+            r#"kaioken_latency_ms_count{instance="https://example.com",job="kaioken"} 14"#
+        ));
+        assert!(encoded.contains(
+            r#"kaioken_latency_ms_sum{instance="https://example.com",job="kaioken"} 28"#
+        ));
+    }
+
+    #[test]
+    fn test_grouping_url() {
+        assert_eq!(
+            grouping_url("http://gw:9091/", "kaioken", None),
+            "http://gw:9091/metrics/job/kaioken"
+        );
+        assert_eq!(
+            grouping_url("http://gw:9091", "kaioken", Some("gen-1")),
+            "http://gw:9091/metrics/job/kaioken/instance/gen-1"
+        );
+    }
+
+    #[test]
+    fn test_metrics_encoding() {
+        let exporter = PrometheusExporter::new("https://example.com");
+        let encoded = exporter.encode();
+
+        // Should contain all our metric names
+        assert!(encoded.contains("kaioken_requests_total"));
+        assert!(encoded.contains("kaioken_rps"));
+        assert!(encoded.contains("kaioken_latency_p99_ms"));
+        assert!(encoded.contains("kaioken_vus_active"));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_update() {
+        use std::collections::{BTreeMap, HashMap};
+        use std::time::Duration;
+
+        let exporter = PrometheusExporter::new("https://example.com");
+
+        let snapshot = StatsSnapshot {
+            elapsed: Duration::from_secs(10),
+            started_at_ms: 0,
+            total_requests: 1000,
+            successful: 990,
+            failed: 10,
+            bytes_received: 500000,
+            bytes_sent: 0,
+            rolling_rps: 100.0,
+            rolling_rx_bps: 8_000_000.0,
+            rolling_tx_bps: 0.0,
+            requests_per_sec: 100.0,
+            error_rate: 0.01,
+            latency_min_us: 1000,
+            latency_max_us: 100000,
+            latency_mean_us: 5000.0,
+            latency_stddev_us: 2000.0,
+            latency_p50_us: 4000,
+            latency_p75_us: 6000,
+            latency_p90_us: 8000,
+            latency_p95_us: 10000,
+            latency_p99_us: 20000,
+            latency_p999_us: 50000,
+            latency_percentiles: Vec::new(),
+            latency_buckets: Vec::new(),
+            latency_histogram: None,
+            status_codes: HashMap::new(),
+            errors: HashMap::new(),
+            jsonrpc_errors: HashMap::new(),
+            timeline: vec![],
+            vus_active: 50,
+            vus_max: 100,
+            target_rate: 0,
+            dropped_iterations: 5,
+            results_dropped: 0,
+            latency_correction_enabled: false,
+            corrected_latency_min_us: None,
+            corrected_latency_max_us: None,
+            corrected_latency_mean_us: None,
+            corrected_latency_p50_us: None,
+            corrected_latency_p75_us: None,
+            corrected_latency_p90_us: None,
+            corrected_latency_p95_us: None,
+            corrected_latency_p99_us: None,
+            corrected_latency_p999_us: None,
+            queue_time_mean_us: None,
+            queue_time_p99_us: None,
+            total_queue_time_us: 0,
+            is_websocket: false,
+            ws_messages_sent: 0,
+            ws_messages_received: 0,
+            ws_bytes_sent: 0,
+            ws_bytes_received: 0,
+            ws_connections_active: 0,
+            ws_connections_established: 0,
+            ws_connection_errors: 0,
+            ws_disconnects: 0,
+            ws_messages_per_sec: 0.0,
+            ws_rolling_mps: 0.0,
+            ws_error_rate: 0.0,
+            ws_errors: HashMap::new(),
+            ws_latency_min_us: 0,
+            ws_latency_max_us: 0,
+            ws_latency_mean_us: 0.0,
+            ws_latency_stddev_us: 0.0,
+            ws_latency_p50_us: 0,
+            ws_latency_p95_us: 0,
+            ws_latency_p99_us: 0,
+            ws_connect_time_mean_us: 0.0,
+            ws_connect_time_p99_us: 0,
+            ws_reconnects: 0,
+            ws_reconnect_failures: 0,
+            ws_reconnect_time_mean_us: 0.0,
+            ws_reconnect_time_p99_us: 0,
+            ws_broadcast_expected: 0,
+            ws_broadcast_delivered: 0,
+            ws_delivery_latency_p50_us: 0,
+            ws_delivery_latency_p95_us: 0,
+            ws_delivery_latency_p99_us: 0,
+            ws_delivery_latency_max_us: 0,
+            ws_compression: None,
+            ws_close_codes: HashMap::new(),
+            quic: None,
+            endpoints: Vec::new(),
+            hosts: Vec::new(),
+            stages: Vec::new(),
+            upload: None,
+            request_ids: None,
+            failure_samples: Vec::new(),
+            slowest_traces: Vec::new(),
+            apdex: None,
+            connections: None,
+            connection_cap: None,
+            content_types: None,
+            chaos: None,
+            throttle: None,
+            bursts: None,
+            workers: None,
+            duplicates: None,
+            revalidation: None,
+            outages: Vec::new(),
+            failover: None,
+            extractions: Vec::new(),
+            scenario_mix: Vec::new(),
+            vu_scaling: None,
+            auto_warmup: None,
+            recent: None,
+            plugin_metrics: BTreeMap::new(),
+            check_stats: HashMap::new(),
+            overall_check_pass_rate: None,
+        };
+
+        exporter.update(&snapshot).await;
+
+        let encoded = exporter.encode();
+        // After update, metrics should have values
+        // Prometheus format: metric{labels} value
+        assert!(encoded.contains("kaioken_rps{"));
+        assert!(encoded.contains("} 100")); // rps should be 100
+        assert!(encoded.contains("kaioken_latency_p99_ms{"));
+        assert!(encoded.contains("} 20")); // 20000us = 20ms
+        assert!(encoded.contains("kaioken_rx_mbps{"));
+        assert!(encoded.contains("} 8")); // 8M bits/sec
+    }
+}
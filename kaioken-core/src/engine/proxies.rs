@@ -0,0 +1,124 @@
+//! Proxy rotation (`--proxy-list`) and per-scenario proxies
+//!
+//! A reqwest client has one proxy, so each proxy gets its own client. Load
+//! then leaves from several egress IPs, one per proxy, and a scenario with
+//! its own `proxy` (or `proxy = ""` to go direct) uses its own client.
+
+use crate::types::{LoadConfig, ProxyRotation, Scenario};
+use reqwest::Client;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub struct ProxyPool {
+    /// One client per `--proxy-list` entry (empty = the worker's own client)
+    clients: Vec<Client>,
+    rotation: ProxyRotation,
+    next: AtomicUsize,
+    /// Clients for scenarios with their own proxy, by scenario index
+    scenarios: Vec<Option<Client>>,
+}
+
+impl ProxyPool {
+    /// `None` unless the run has a proxy list or one of the workers'
+    /// `scenarios` has its own proxy. `client` builds a client going through
+    /// a proxy.
+    pub fn for_config(
+        config: &LoadConfig,
+        scenarios: &[Scenario],
+        client: impl Fn(&str) -> Result<Client, String>,
+    ) -> Result<Option<Self>, String> {
+        if config.proxy_list.is_empty() && scenarios.iter().all(|s| s.proxy.is_none()) {
+            return Ok(None);
+        }
+        let clients = config
+            .proxy_list
+            .iter()
+            .map(|proxy| client(proxy))
+            .collect::<Result<_, _>>()?;
+        let scenarios = scenarios
+            .iter()
+            .map(|s| s.proxy.as_deref().map(&client).transpose())
+            .collect::<Result<_, _>>()?;
+        Ok(Some(Self {
+            clients,
+            rotation: config.proxy_rotation,
+            next: AtomicUsize::new(0),
+            scenarios,
+        }))
+    }
+
+    /// Client for a request from VU `vu` (`None` = rotate per request) for
+    /// `scenario`, falling back to `own`
+    pub fn client<'a>(
+        &'a self,
+        vu: Option<u32>,
+        scenario: Option<usize>,
+        own: &'a Client,
+    ) -> &'a Client {
+        if let Some(client) = scenario.and_then(|idx| self.scenarios.get(idx)?.as_ref()) {
+            return client;
+        }
+        if self.clients.is_empty() {
+            return own;
+        }
+        let index = match (self.rotation, vu) {
+            (ProxyRotation::Vu, Some(vu)) => vu as usize,
+            _ => self.next.fetch_add(1, Ordering::Relaxed),
+        };
+        &self.clients[index % self.clients.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    fn pool(rotation: ProxyRotation) -> ProxyPool {
+        ProxyPool {
+            clients: vec![Client::new(), Client::new()],
+            rotation,
+            next: AtomicUsize::new(0),
+            scenarios: vec![None, Some(Client::new())],
+        }
+    }
+
+    #[test]
+    fn rotates_per_vu_or_per_request() {
+        let own = Client::new();
+        let sticky = pool(ProxyRotation::Vu);
+        for _ in 0..3 {
+            assert!(ptr::eq(
+                sticky.client(Some(3), None, &own),
+                &sticky.clients[1]
+            ));
+        }
+        // Without a VU, requests rotate whatever the setting
+        assert!(ptr::eq(sticky.client(None, None, &own), &sticky.clients[0]));
+        assert!(ptr::eq(sticky.client(None, None, &own), &sticky.clients[1]));
+
+        let rotating = pool(ProxyRotation::Request);
+        let picks: Vec<bool> = (0..4)
+            .map(|_| {
+                ptr::eq(
+                    rotating.client(Some(0), Some(0), &own),
+                    &rotating.clients[0],
+                )
+            })
+            .collect();
+        assert_eq!(picks, [true, false, true, false]);
+    }
+
+    #[test]
+    fn scenario_proxies_come_first() {
+        let own = Client::new();
+        let pool = pool(ProxyRotation::Vu);
+        let scenario = pool.scenarios[1].as_ref().unwrap();
+        assert!(ptr::eq(pool.client(Some(0), Some(1), &own), scenario));
+
+        let only_scenarios = ProxyPool {
+            clients: Vec::new(),
+            ..pool
+        };
+        assert!(ptr::eq(only_scenarios.client(Some(0), Some(0), &own), &own));
+    }
+}
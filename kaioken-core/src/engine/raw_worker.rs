@@ -0,0 +1,150 @@
+use crate::net::SocketOptions;
+use crate::raw::{RawConnection, RawTarget, connect};
+use crate::types::{RawExpect, WsErrorKind, WsMessageResult};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+/// Sends payloads over raw TCP/UDP and reports round-trip time
+pub struct RawWorker {
+    id: u32,
+    target: RawTarget,
+    payload: String,
+    expect: Option<RawExpect>,
+    message_interval: Duration,
+    timeout: Duration,
+    socket: SocketOptions,
+    reconnect_per_message: bool,
+    result_tx: mpsc::Sender<WsMessageResult>,
+    cancel_token: CancellationToken,
+}
+
+impl RawWorker {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u32,
+        target: RawTarget,
+        payload: String,
+        expect: Option<RawExpect>,
+        message_interval: Duration,
+        timeout: Duration,
+        socket: SocketOptions,
+        reconnect_per_message: bool,
+        result_tx: mpsc::Sender<WsMessageResult>,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            id,
+            target,
+            payload,
+            expect,
+            message_interval,
+            timeout,
+            socket,
+            reconnect_per_message,
+            result_tx,
+            cancel_token,
+        }
+    }
+
+    pub async fn run(self) {
+        tracing::debug!("RawWorker {} starting", self.id);
+
+        let mut connection: Option<RawConnection> = None;
+        let mut pending_connect_time: Option<u64> = None;
+        let mut message_counter: u64 = 0;
+
+        loop {
+            if self.cancel_token.is_cancelled() {
+                break;
+            }
+
+            if connection.is_none() {
+                match connect(&self.target, &self.socket, self.timeout).await {
+                    Ok(conn) => {
+                        pending_connect_time = Some(conn.connect_time_us());
+                        connection = Some(conn);
+                    }
+                    Err(e) => {
+                        if self
+                            .result_tx
+                            .send(WsMessageResult::error(e))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                        tokio::select! {
+                            _ = sleep(Duration::from_secs(1)) => {}
+                            _ = self.cancel_token.cancelled() => break,
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            let conn = connection.as_mut().unwrap();
+
+            message_counter += 1;
+            let timestamp_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+            let payload = self
+                .payload
+                .replace("${MESSAGE_ID}", &message_counter.to_string())
+                .replace("${TIMESTAMP_MS}", &timestamp_ms.to_string());
+
+            let start = Instant::now();
+            let mut result = match conn
+                .exchange(payload.as_bytes(), self.expect.as_ref(), self.timeout)
+                .await
+            {
+                Ok(bytes_received) => WsMessageResult::success(
+                    start.elapsed().as_micros() as u64,
+                    payload.len() as u64,
+                    bytes_received,
+                ),
+                Err(e) => WsMessageResult::error(e),
+            };
+            if let Some(connect_time) = pending_connect_time.take() {
+                result = result.with_connect_time(connect_time);
+            }
+
+            // A timed-out TCP exchange may deliver its response late and
+            // confuse the next match, so start over on a fresh connection
+            let connection_lost = self.reconnect_per_message
+                || matches!(
+                    result.error,
+                    Some(WsErrorKind::ConnectionClosed)
+                        | Some(WsErrorKind::SendFailed)
+                        | Some(WsErrorKind::ReceiveFailed)
+                        | Some(WsErrorKind::ConnectFailed)
+                        | Some(WsErrorKind::Timeout)
+                );
+
+            if self.result_tx.send(result).await.is_err() {
+                break;
+            }
+
+            if connection_lost && let Some(conn) = connection.take() {
+                conn.close().await;
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed < self.message_interval {
+                tokio::select! {
+                    _ = sleep(self.message_interval - elapsed) => {}
+                    _ = self.cancel_token.cancelled() => break,
+                }
+            }
+        }
+
+        if let Some(conn) = connection {
+            conn.close().await;
+        }
+
+        tracing::debug!("RawWorker {} stopped", self.id);
+    }
+}
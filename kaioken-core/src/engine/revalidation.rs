@@ -0,0 +1,248 @@
+//! Conditional requests (`--conditional-requests`)
+//!
+//! Each VU remembers the `ETag` and `Last-Modified` a URL last came back
+//! with and revalidates it on the next GET or HEAD with `If-None-Match` and
+//! `If-Modified-Since`, the way a browser cache does. How often the answer
+//! is a 304, and how much faster a 304 is than a full fetch, shows whether
+//! the cache layer in front of the target earns its keep.
+
+use crate::types::{RequestResult, RevalidationStats};
+use reqwest::Method;
+use reqwest::header::{ETAG, HeaderMap, LAST_MODIFIED};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// What a response said to revalidate it with
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from)
+        };
+        let validators = Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+        (validators != Self::default()).then_some(validators)
+    }
+}
+
+/// A VU's validators, by URL
+#[derive(Debug, Default)]
+pub struct ValidatorCache {
+    urls: HashMap<String, Validators>,
+}
+
+impl ValidatorCache {
+    /// Make a GET or HEAD to a URL seen before conditional. True if it is.
+    /// Conditional headers the request already has are left alone.
+    pub fn inject(&self, method: &Method, url: &str, headers: &mut Vec<(String, String)>) -> bool {
+        if !cacheable(method) {
+            return false;
+        }
+        let Some(validators) = self.urls.get(url) else {
+            return false;
+        };
+        let has = |name: &str| headers.iter().any(|(h, _)| h.eq_ignore_ascii_case(name));
+        let mut added = Vec::new();
+        if let Some(ref etag) = validators.etag
+            && !has("If-None-Match")
+        {
+            added.push(("If-None-Match".to_string(), etag.clone()));
+        }
+        if let Some(ref last_modified) = validators.last_modified
+            && !has("If-Modified-Since")
+        {
+            added.push(("If-Modified-Since".to_string(), last_modified.clone()));
+        }
+        let conditional = !added.is_empty();
+        headers.extend(added);
+        conditional
+    }
+
+    /// Keep the validators a full response came with. A 304 keeps the ones
+    /// it revalidated unless it sends new ones.
+    pub fn observe(&mut self, method: &Method, url: &str, result: &RequestResult) {
+        if !cacheable(method) || result.error.is_some() {
+            return;
+        }
+        let validators = result.headers.as_deref().and_then(Validators::from_headers);
+        match (result.status, validators) {
+            (Some(200..=299 | 304), Some(validators)) => {
+                self.urls.insert(url.to_string(), validators);
+            }
+            (Some(200..=299), None) => {
+                self.urls.remove(url);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn cacheable(method: &Method) -> bool {
+    method == Method::GET || method == Method::HEAD
+}
+
+/// Conditional requests and how they were answered, shared by the VUs and
+/// read by the aggregator into each snapshot
+#[derive(Debug, Default)]
+pub struct Revalidation {
+    conditional: AtomicU64,
+    not_modified: AtomicU64,
+    not_modified_us: AtomicU64,
+    full_fetches: AtomicU64,
+    full_fetch_us: AtomicU64,
+}
+
+impl Revalidation {
+    /// Count a response. `conditional` is whether it was revalidating.
+    pub fn record(&self, conditional: bool, result: &RequestResult) {
+        if conditional {
+            self.conditional.fetch_add(1, Ordering::Relaxed);
+        }
+        if result.error.is_some() {
+            return;
+        }
+        let (count, latency) = match result.status {
+            Some(304) => (&self.not_modified, &self.not_modified_us),
+            Some(200..=299) => (&self.full_fetches, &self.full_fetch_us),
+            _ => return,
+        };
+        count.fetch_add(1, Ordering::Relaxed);
+        latency.fetch_add(result.latency_us, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> RevalidationStats {
+        let mean = |total: &AtomicU64, count: u64| {
+            total
+                .load(Ordering::Relaxed)
+                .checked_div(count)
+                .unwrap_or(0)
+        };
+        let not_modified = self.not_modified.load(Ordering::Relaxed);
+        let full_fetches = self.full_fetches.load(Ordering::Relaxed);
+        RevalidationStats {
+            conditional: self.conditional.load(Ordering::Relaxed),
+            not_modified,
+            full_fetches,
+            not_modified_mean_us: mean(&self.not_modified_us, not_modified),
+            full_fetch_mean_us: mean(&self.full_fetch_us, full_fetches),
+        }
+    }
+
+    /// Forget warmup responses
+    pub fn reset(&self) {
+        self.conditional.store(0, Ordering::Relaxed);
+        self.not_modified.store(0, Ordering::Relaxed);
+        self.not_modified_us.store(0, Ordering::Relaxed);
+        self.full_fetches.store(0, Ordering::Relaxed);
+        self.full_fetch_us.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn response(
+        status: u16,
+        latency_us: u64,
+        headers: &[(&'static str, &'static str)],
+    ) -> RequestResult {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.append(*name, HeaderValue::from_static(value));
+        }
+        let mut result = RequestResult::success(latency_us, status, 0, None);
+        result.headers = Some(Box::new(map));
+        result
+    }
+
+    #[test]
+    fn revalidates_urls_with_the_validators_they_came_with() {
+        let mut cache = ValidatorCache::default();
+        let url = "http://example.com/a";
+        let mut headers = Vec::new();
+
+        // Nothing to revalidate yet
+        assert!(!cache.inject(&Method::GET, url, &mut headers));
+
+        cache.observe(
+            &Method::GET,
+            url,
+            &response(
+                200,
+                1_000,
+                &[
+                    ("etag", "\"v1\""),
+                    ("last-modified", "Sun, 06 Nov 1994 08:49:37 GMT"),
+                ],
+            ),
+        );
+        assert!(cache.inject(&Method::GET, url, &mut headers));
+        assert_eq!(
+            headers,
+            vec![
+                ("If-None-Match".to_string(), "\"v1\"".to_string()),
+                (
+                    "If-Modified-Since".to_string(),
+                    "Sun, 06 Nov 1994 08:49:37 GMT".to_string()
+                ),
+            ]
+        );
+
+        // Only GET and HEAD, and only the URL it was for
+        let mut other = Vec::new();
+        assert!(!cache.inject(&Method::POST, url, &mut other));
+        assert!(!cache.inject(&Method::GET, "http://example.com/b", &mut other));
+        assert!(other.is_empty());
+
+        // A 304 without validators keeps the old ones; a new version replaces them
+        cache.observe(&Method::GET, url, &response(304, 100, &[]));
+        cache.observe(
+            &Method::GET,
+            url,
+            &response(200, 1_000, &[("etag", "\"v2\"")]),
+        );
+        let mut headers = Vec::new();
+        cache.inject(&Method::GET, url, &mut headers);
+        assert_eq!(
+            headers,
+            vec![("If-None-Match".to_string(), "\"v2\"".to_string())]
+        );
+
+        // And one without validators can't be revalidated
+        cache.observe(&Method::GET, url, &response(200, 1_000, &[]));
+        assert!(!cache.inject(&Method::GET, url, &mut Vec::new()));
+    }
+
+    #[test]
+    fn compares_not_modified_with_full_fetches() {
+        let revalidation = Revalidation::default();
+        revalidation.record(false, &response(200, 9_000, &[]));
+        revalidation.record(true, &response(304, 1_000, &[]));
+        revalidation.record(true, &response(304, 3_000, &[]));
+        revalidation.record(true, &response(200, 11_000, &[]));
+        revalidation.record(true, &response(500, 50_000, &[]));
+
+        let stats = revalidation.stats();
+        assert_eq!(stats.conditional, 4);
+        assert_eq!(stats.not_modified, 2);
+        assert_eq!(stats.full_fetches, 2);
+        assert_eq!(stats.not_modified_mean_us, 2_000);
+        assert_eq!(stats.full_fetch_mean_us, 10_000);
+        assert_eq!(stats.not_modified_ratio(), 0.5);
+
+        revalidation.reset();
+        assert_eq!(revalidation.stats().conditional, 0);
+    }
+}
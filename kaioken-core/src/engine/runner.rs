@@ -0,0 +1,2523 @@
+use crate::engine::Stats;
+use crate::engine::aggregator::Aggregator;
+use crate::engine::arrival_rate::{
+    ArrivalRateExecutor, RampingArrivalRateExecutor, RateStage, Transport,
+};
+use crate::engine::batch::{ResultBatch, ResultBatcher};
+use crate::engine::burst::{self, BurstTracker};
+use crate::engine::certs::CertPool;
+use crate::engine::chaos::Chaos;
+use crate::engine::connection_cap::ConnectionCap;
+use crate::engine::control::RunControl;
+use crate::engine::deadline::Budget;
+use crate::engine::duplicates::Duplicates;
+use crate::engine::endpoints::EndpointNamer;
+use crate::engine::extractions::ExtractionCounts;
+use crate::engine::health::WorkerHealth;
+use crate::engine::hosts::HostPicker;
+#[cfg(feature = "mqtt")]
+use crate::engine::mqtt_worker::{MqttPublisher, MqttSubscriber, MqttTarget};
+use crate::engine::outage::Outages;
+use crate::engine::proxies::ProxyPool;
+use crate::engine::raw_worker::RawWorker;
+use crate::engine::revalidation::Revalidation;
+use crate::engine::runtimes::WorkerRuntimes;
+use crate::engine::scenario_mix::ScenarioMix;
+use crate::engine::scheduler::{
+    MessageRateScheduler, RampUpScheduler, RateLimiter, StageInfo, StagesScheduler,
+};
+use crate::engine::seed;
+use crate::engine::shards::{ResultSink, StatsShards};
+use crate::engine::slow_log::SlowLog;
+#[cfg(feature = "socketio")]
+use crate::engine::socketio_worker::SocketIoWorker;
+use crate::engine::stdin::LineStream;
+use crate::engine::thresholds::evaluate_thresholds;
+use crate::engine::throttle::Throttle;
+use crate::engine::vu_pool::VuScaling;
+use crate::engine::worker::{CheckResult, Worker};
+use crate::engine::ws_aggregator::WsAggregator;
+use crate::engine::ws_broadcast::{
+    BROADCAST_TAG, BroadcastPublisher, BroadcastSubscriber, BroadcastTarget,
+};
+use crate::engine::ws_worker::WsWorker;
+#[cfg(feature = "grpc")]
+use crate::grpc::{GrpcConfig, GrpcError, execute_grpc_request};
+use crate::history::{self, HistoryRun};
+use crate::http::{PreparedRequest, create_client, execute_prepared, route_sni};
+#[cfg(feature = "http3")]
+use crate::http3::{Http3Client, execute_http3_request};
+#[cfg(feature = "plugins")]
+use crate::plugin::PluginHost;
+use crate::probe::Prober;
+use crate::types::{
+    ClientIdentity, LoadConfig, RunPhase, RunState, Scenario, StatsSnapshot, Threshold,
+    WsMessageResult, WsMode,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Semaphore, mpsc, watch};
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+const RESULT_CHANNEL_SIZE: usize = 10_000;
+
+/// One load test run, configured by a `LoadConfig`. Grab the receivers you
+/// need, then consume it with `run`.
+pub struct Engine {
+    config: LoadConfig,
+    cancel_token: CancellationToken,
+    state_tx: watch::Sender<RunState>,
+    phase_tx: watch::Sender<RunPhase>,
+    snapshot_rx: watch::Receiver<StatsSnapshot>,
+    snapshot_tx: watch::Sender<StatsSnapshot>,
+    stage_info_rx: Option<watch::Receiver<StageInfo>>,
+    threshold_failed: Arc<AtomicBool>,
+    check_stats: Arc<std::sync::Mutex<HashMap<String, (u64, u64)>>>, // (passed, total)
+    // Arrival rate metrics
+    dropped_iterations: Arc<AtomicU64>,
+    vus_active: Arc<AtomicU32>,
+    vus_max: Arc<AtomicU32>,
+    // Results discarded under the drop backpressure policy
+    results_dropped: Arc<AtomicU64>,
+    // Connections opened by the HTTP client pools
+    connections_opened: Arc<AtomicU64>,
+    // Requests written to the --slow-log file
+    slow_logged: Arc<AtomicU64>,
+    // Row in the --db-url database this run's snapshots are logged against
+    history: Option<HistoryRun>,
+    // Extensions and rate changes from `kaioken ctl`
+    control: Arc<RunControl>,
+}
+
+impl Engine {
+    /// Set up a run; with `db_url` set this also creates its history row
+    pub fn new(config: LoadConfig) -> Self {
+        let cancel_token = CancellationToken::new();
+        let (state_tx, _) = watch::channel(RunState::Initializing);
+        let (phase_tx, _) = watch::channel(RunPhase::Warmup);
+        let (snapshot_tx, snapshot_rx) = watch::channel(StatsSnapshot::default());
+
+        let history =
+            config
+                .db_url
+                .as_ref()
+                .and_then(|path| match history::begin_run(path, &config) {
+                    Ok(run) => Some(run),
+                    Err(e) => {
+                        tracing::warn!("Failed to initialize SQLite database: {}", e);
+                        None
+                    }
+                });
+
+        Self {
+            config,
+            cancel_token,
+            state_tx,
+            phase_tx,
+            snapshot_rx,
+            snapshot_tx,
+            stage_info_rx: None,
+            threshold_failed: Arc::new(AtomicBool::new(false)),
+            check_stats: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            dropped_iterations: Arc::new(AtomicU64::new(0)),
+            vus_active: Arc::new(AtomicU32::new(0)),
+            vus_max: Arc::new(AtomicU32::new(0)),
+            results_dropped: Arc::new(AtomicU64::new(0)),
+            connections_opened: Arc::new(AtomicU64::new(0)),
+            slow_logged: Arc::new(AtomicU64::new(0)),
+            history,
+            control: Arc::new(RunControl::new()),
+        }
+    }
+
+    /// Check if this is a WebSocket URL
+    fn is_websocket(&self) -> bool {
+        self.config.url.starts_with("ws://") || self.config.url.starts_with("wss://")
+    }
+
+    /// Check if Socket.IO mode is enabled
+    #[cfg(feature = "socketio")]
+    fn is_socketio(&self) -> bool {
+        self.config.socketio_event.is_some()
+    }
+
+    /// Check if this is a raw tcp:// or udp:// target
+    fn is_raw(&self) -> bool {
+        self.config.url.starts_with("tcp://") || self.config.url.starts_with("udp://")
+    }
+
+    /// Check if this is a Redis server URL
+    #[cfg(feature = "redis")]
+    fn is_redis(&self) -> bool {
+        self.config.url.starts_with("redis://")
+    }
+
+    /// Check if this is an MQTT broker URL
+    #[cfg(feature = "mqtt")]
+    fn is_mqtt(&self) -> bool {
+        self.config.url.starts_with("mqtt://")
+    }
+
+    /// Check if arrival rate mode is enabled
+    fn is_arrival_rate_mode(&self) -> bool {
+        self.config.arrival_rate.is_some()
+            || self.config.stages.iter().any(|s| s.target_rate.is_some())
+    }
+
+    /// Check if burst mode is enabled
+    fn is_burst_mode(&self) -> bool {
+        self.config.burst_config.is_some()
+    }
+
+    /// Check if HTTP/3 mode is enabled
+    #[cfg(feature = "http3")]
+    fn is_http3(&self) -> bool {
+        self.config.http3
+    }
+
+    /// Check if gRPC mode is enabled
+    #[cfg(feature = "grpc")]
+    fn is_grpc(&self) -> bool {
+        self.config.grpc_service.is_some() && self.config.grpc_method.is_some()
+    }
+
+    /// Arrival-rate iterations skipped because no VU was free
+    pub fn dropped_iterations(&self) -> u64 {
+        self.dropped_iterations.load(Ordering::Relaxed)
+    }
+
+    /// VUs currently busy (arrival-rate mode)
+    pub fn vus_active(&self) -> u32 {
+        self.vus_active.load(Ordering::Relaxed)
+    }
+
+    /// VUs allocated so far (arrival-rate mode)
+    pub fn vus_max(&self) -> u32 {
+        self.vus_max.load(Ordering::Relaxed)
+    }
+
+    /// Whether a threshold failed during the run (`fail_fast`)
+    pub fn threshold_failed(&self) -> bool {
+        self.threshold_failed.load(Ordering::Relaxed)
+    }
+
+    pub fn threshold_failed_flag(&self) -> Arc<AtomicBool> {
+        self.threshold_failed.clone()
+    }
+
+    /// (passed, total) per check name
+    pub fn check_stats(&self) -> HashMap<String, (u64, u64)> {
+        self.check_stats.lock().unwrap().clone()
+    }
+
+    pub fn check_stats_ref(&self) -> Arc<std::sync::Mutex<HashMap<String, (u64, u64)>>> {
+        self.check_stats.clone()
+    }
+
+    /// Requests written to the slow log so far
+    pub fn slow_logged(&self) -> Arc<AtomicU64> {
+        self.slow_logged.clone()
+    }
+
+    /// Run row in the --db-url database, for storing the final result
+    pub fn history_run(&self) -> Option<HistoryRun> {
+        self.history.clone()
+    }
+
+    /// Extend the run or change the rate while it's going
+    pub fn control(&self) -> Arc<RunControl> {
+        self.control.clone()
+    }
+
+    /// Cancel to stop the run early; results so far are kept
+    pub fn cancel_token(&self) -> CancellationToken {
+        self.cancel_token.clone()
+    }
+
+    /// Latest stats, published about every 100ms; the last one is final
+    pub fn snapshot_rx(&self) -> watch::Receiver<StatsSnapshot> {
+        self.snapshot_rx.clone()
+    }
+
+    pub fn state_rx(&self) -> watch::Receiver<RunState> {
+        self.state_tx.subscribe()
+    }
+
+    /// Warmup or measuring
+    pub fn phase_rx(&self) -> watch::Receiver<RunPhase> {
+        self.phase_tx.subscribe()
+    }
+
+    /// Current stage, when `stages` are configured
+    pub fn stage_info_rx(&self) -> Option<watch::Receiver<StageInfo>> {
+        self.stage_info_rx.clone()
+    }
+
+    /// Run the load test to completion (or cancellation) and return the
+    /// merged stats of the measured phase
+    pub async fn run(mut self) -> Result<Stats, String> {
+        // --sni moves the target's URLs onto the server name before any client is built
+        route_sni(&mut self.config).await?;
+
+        // Check if this is a Socket.IO test
+        #[cfg(feature = "socketio")]
+        if self.is_socketio() {
+            return self.run_socketio_mode().await;
+        }
+
+        // Check if this is a raw TCP/UDP test
+        if self.is_raw() {
+            return self.run_raw_mode().await;
+        }
+
+        // Check if this is a Redis test
+        #[cfg(feature = "redis")]
+        if self.is_redis() {
+            return self.run_redis_mode().await;
+        }
+
+        // Check if this is an MQTT test
+        #[cfg(feature = "mqtt")]
+        if self.is_mqtt() {
+            return self.run_mqtt_mode().await;
+        }
+
+        // Check if this is a WebSocket test
+        if self.is_websocket() {
+            return self.run_websocket_mode().await;
+        }
+
+        // Check if this is a gRPC test
+        #[cfg(feature = "grpc")]
+        if self.is_grpc() {
+            return self.run_grpc_mode().await;
+        }
+
+        // Check if this is a closed-model HTTP/3 test (open-model HTTP/3
+        // runs go through the arrival rate executors below)
+        #[cfg(feature = "http3")]
+        if self.is_http3() && !self.is_arrival_rate_mode() {
+            return self.run_http3_mode().await;
+        }
+
+        // Check if we should use arrival rate mode
+        if self.is_arrival_rate_mode() {
+            return self.run_arrival_rate_mode().await;
+        }
+
+        // Check if we should use burst mode
+        if self.is_burst_mode() {
+            return self.run_burst_mode().await;
+        }
+
+        // Otherwise, use constant VUs mode
+        self.run_constant_vus_mode().await
+    }
+
+    /// HTTP/3 or reqwest client for the arrival rate executors, or the
+    /// prober that stands in for one
+    fn arrival_rate_transport(&self, max_vus: u32) -> Result<Transport, String> {
+        if let Some(kind) = self.config.probe {
+            return Ok(Transport::Probe(Arc::new(Prober::new(kind, &self.config)?)));
+        }
+
+        #[cfg(feature = "http3")]
+        if self.config.http3 {
+            let client = Http3Client::new(&self.config)
+                .map_err(|e| format!("Failed to create HTTP/3 client: {}", e))?;
+            return Ok(Transport::Http3 {
+                client: Arc::new(client),
+                timeout: self.config.timeout,
+            });
+        }
+
+        let client = self.proxied_client(max_vus, self.config.proxy.as_deref())?;
+        Ok(Transport::Http {
+            client,
+            trace: self.config.trace_propagation,
+            id_header: self.config.request_id_header.as_deref().map(Arc::from),
+        })
+    }
+
+    async fn run_arrival_rate_mode(self) -> Result<Stats, String> {
+        // An autoscaling policy's cap stands in for the fixed ceiling
+        let max_vus = match self.config.vu_autoscale {
+            Some(policy) => policy.cap,
+            None => self.config.max_vus.unwrap_or(100),
+        };
+        let vu_scaling = self
+            .config
+            .vu_autoscale
+            .map(|policy| Arc::new(VuScaling::new(policy)));
+
+        let transport = self.arrival_rate_transport(max_vus)?;
+
+        self.vus_max.store(max_vus, Ordering::Relaxed);
+
+        // Iterations record into per-core shards; the channel only tells the
+        // aggregator when the last iteration has finished
+        let (result_tx, result_rx) = mpsc::channel::<ResultBatch>(1);
+        let slow_log = self.slow_log()?;
+        #[cfg(feature = "plugins")]
+        let plugins = self.plugins()?;
+        let shards = Arc::new(
+            StatsShards::new(self.config.max_requests, self.cancel_token.clone())
+                .with_failure_samples(self.config.error_samples)
+                .with_slowest_traces(self.config.trace_slowest)
+                .with_apdex(self.config.apdex_t)
+                .with_slow_log(slow_log.clone()),
+        );
+        let results = ResultSink::sharded(result_tx, shards.clone());
+
+        let _ = self.state_tx.send(RunState::Running);
+        if !self.config.auto_warmup {
+            let _ = self.phase_tx.send(RunPhase::Running);
+        }
+
+        // Check if we have rate-based stages
+        let has_rate_stages = self.config.stages.iter().any(|s| s.target_rate.is_some());
+
+        // Calculate total duration
+        let total_duration = if has_rate_stages {
+            self.config.warmup
+                + self
+                    .config
+                    .stages
+                    .iter()
+                    .map(|s| s.duration)
+                    .sum::<Duration>()
+        } else {
+            self.config.warmup + self.config.duration
+        };
+
+        let scenarios = Arc::new(self.config.scenarios.clone());
+        let proxies = self.proxy_pool(max_vus, &scenarios)?;
+        let scenario_mix = ScenarioMix::for_scenarios(&scenarios).map(Arc::new);
+        let connection_cap = self
+            .config
+            .max_connections
+            .map(|limit| Arc::new(ConnectionCap::new(limit)));
+        let checks = Arc::new(self.config.checks.clone());
+        let url_list = self.config.url_list.as_ref().map(|v| Arc::new(v.clone()));
+        let body_lines = self.config.body_lines.as_ref().map(|v| Arc::new(v.clone()));
+
+        // Create check results channel if checks are configured
+        let (check_tx, check_rx) = if !self.config.checks.is_empty() || self.has_plugins() {
+            let (tx, rx) = mpsc::channel::<CheckResult>(RESULT_CHANNEL_SIZE);
+            (Some(tx), Some(rx))
+        } else {
+            (None, None)
+        };
+
+        // Spawn check stats aggregator
+        let check_stats_clone = self.check_stats.clone();
+        let check_agg_handle = check_rx.map(|mut rx| {
+            tokio::spawn(async move {
+                while let Some(check_result) = rx.recv().await {
+                    let mut stats = check_stats_clone.lock().unwrap();
+                    let entry = stats.entry(check_result.name).or_insert((0, 0));
+                    if check_result.passed {
+                        entry.0 += 1;
+                    }
+                    entry.1 += 1;
+                }
+            })
+        });
+
+        // Create shared metric references for arrival rate tracking
+        let dropped_ref = Arc::new(AtomicU64::new(0));
+        let vus_active_ref = Arc::new(AtomicU32::new(0));
+        let target_rate_ref = Arc::new(AtomicU32::new(0));
+
+        // Determine initial target rate
+        let initial_target_rate = if has_rate_stages {
+            self.config
+                .stages
+                .first()
+                .and_then(|s| s.target_rate)
+                .unwrap_or(0)
+        } else {
+            self.config.arrival_rate.unwrap_or(0)
+        };
+        target_rate_ref.store(initial_target_rate, Ordering::Relaxed);
+
+        // The ramping executor reports its stage for the per-stage breakdown.
+        // The run starts in the first stage that takes time; a rate
+        // profile's zero-length first stage only sets the starting rate.
+        let first_stage = self
+            .config
+            .stages
+            .iter()
+            .position(|s| !s.duration.is_zero())
+            .unwrap_or(0);
+        let (stage_info_tx, stage_info_rx) = watch::channel(StageInfo {
+            stage_index: first_stage,
+            stage_count: self.config.stages.len(),
+            target: self
+                .config
+                .stages
+                .get(first_stage)
+                .and_then(|s| s.target_rate)
+                .unwrap_or(0),
+            ..StageInfo::default()
+        });
+
+        // Create aggregator with arrival rate metrics
+        let mut aggregator = Aggregator::with_arrival_rate_metrics(
+            total_duration,
+            result_rx,
+            self.snapshot_tx.clone(),
+            self.config.warmup,
+            self.phase_tx.clone(),
+            self.config.max_requests,
+            self.cancel_token.clone(),
+            Some(dropped_ref.clone()),
+            Some(vus_active_ref.clone()),
+            max_vus,
+            initial_target_rate,
+            self.history.clone(),
+            self.config.prometheus.clone(),
+            &self.config.url,
+        )
+        .with_timeline_interval(self.config.timeline_interval)
+        .with_failure_samples(self.config.error_samples)
+        .with_slowest_traces(self.config.trace_slowest)
+        .with_apdex(self.config.apdex_t)
+        .with_expected_content_types(self.config.expect_content_types.clone())
+        .with_percentiles(self.config.percentiles.clone())
+        .with_latency_histogram(self.config.latency_histogram)
+        .with_auto_warmup(self.config.auto_warmup)
+        .with_shards(shards)
+        .with_vu_scaling(vu_scaling.clone())
+        .with_checks(self.check_stats.clone())
+        .with_stages(has_rate_stages.then_some(stage_info_rx))
+        .with_connection_cap(connection_cap.clone())
+        .with_scenario_mix(scenario_mix.clone())
+        .with_failover(self.config.failover);
+        #[cfg(feature = "plugins")]
+        {
+            aggregator = aggregator.with_plugin_metrics(plugins.as_ref().map(|p| p.metrics()));
+        }
+        if matches!(transport, Transport::Http { .. }) {
+            aggregator = aggregator.with_connection_counter(self.connections_opened.clone());
+        }
+        let aggregator_handle = tokio::spawn(aggregator.run());
+
+        // Create and spawn appropriate executor based on configuration
+        let executor_handle = if has_rate_stages {
+            // Use ramping arrival rate executor with stages
+            let rate_stages = RateStage::from_stages(&self.config.stages);
+            self.control.allow_stages(self.config.stages.clone(), None);
+
+            let max_rate = rate_stages
+                .iter()
+                .map(|s| s.target_rate)
+                .max()
+                .unwrap_or(10);
+            let pre_allocated_vus = (max_rate / 10).max(1).min(max_vus);
+
+            let executor = RampingArrivalRateExecutor::new(
+                rate_stages,
+                max_vus,
+                pre_allocated_vus,
+                self.config.latency_correction,
+                transport,
+                self.config.url.clone(),
+                self.config.method.clone(),
+                self.config.headers.clone(),
+                self.config.body.clone(),
+                scenarios,
+                checks,
+                self.config.jsonrpc_method.is_some(),
+                results,
+                check_tx,
+                self.cancel_token.clone(),
+            )
+            .with_control(&self.control)
+            .with_stage_info(stage_info_tx)
+            .with_endpoints(EndpointNamer::for_config(&self.config).map(Arc::new))
+            .with_hosts(HostPicker::for_config(&self.config).map(Arc::new))
+            .with_seed(self.config.seed)
+            .with_form(
+                self.config.form_fields.clone(),
+                self.config.basic_auth.clone(),
+            )
+            .with_rotation(
+                url_list.clone(),
+                body_lines.clone(),
+                self.config.rand_regex_url.as_deref(),
+            )
+            .with_network(self.config.network.clone())
+            .with_compression(self.config.compress_body)
+            .with_proxies(proxies.clone())
+            .with_connection_cap(connection_cap.clone())
+            .with_scenario_mix(scenario_mix.clone())
+            .with_autoscale(vu_scaling.clone());
+            #[cfg(feature = "plugins")]
+            let executor = executor.with_plugins(plugins.clone());
+
+            // Link our shared metrics to executor's metrics
+            let exec_dropped = executor.dropped_iterations();
+            let exec_active = executor.vus_active();
+            let dropped_clone = dropped_ref.clone();
+            let active_clone = vus_active_ref.clone();
+
+            tokio::spawn(async move {
+                // Spawn a task to sync metrics periodically
+                let sync_dropped = dropped_clone;
+                let sync_active = active_clone;
+                let sync_exec_dropped = exec_dropped.clone();
+                let sync_exec_active = exec_active.clone();
+
+                tokio::spawn(async move {
+                    loop {
+                        sync_dropped
+                            .store(sync_exec_dropped.load(Ordering::Relaxed), Ordering::Relaxed);
+                        sync_active
+                            .store(sync_exec_active.load(Ordering::Relaxed), Ordering::Relaxed);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                    }
+                });
+
+                executor.run().await;
+            })
+        } else {
+            // Use constant arrival rate executor
+            let arrival_rate = self.config.arrival_rate.unwrap_or(10);
+            let pre_allocated_vus = (arrival_rate / 10).max(1).min(max_vus);
+            self.control.allow(true, Some(arrival_rate));
+
+            // Warmup iterations come out of the same schedule, ahead of the
+            // measured duration
+            let executor = ArrivalRateExecutor::new(
+                arrival_rate,
+                total_duration,
+                max_vus,
+                pre_allocated_vus,
+                self.config.latency_correction,
+                transport,
+                self.config.url.clone(),
+                self.config.method.clone(),
+                self.config.headers.clone(),
+                self.config.body.clone(),
+                scenarios,
+                checks,
+                self.config.jsonrpc_method.is_some(),
+                results,
+                check_tx,
+                self.cancel_token.clone(),
+            )
+            .with_control(self.control.clone())
+            .with_endpoints(EndpointNamer::for_config(&self.config).map(Arc::new))
+            .with_hosts(HostPicker::for_config(&self.config).map(Arc::new))
+            .with_seed(self.config.seed)
+            .with_form(
+                self.config.form_fields.clone(),
+                self.config.basic_auth.clone(),
+            )
+            .with_rotation(
+                url_list.clone(),
+                body_lines.clone(),
+                self.config.rand_regex_url.as_deref(),
+            )
+            .with_network(self.config.network.clone())
+            .with_compression(self.config.compress_body)
+            .with_proxies(proxies.clone())
+            .with_connection_cap(connection_cap.clone())
+            .with_scenario_mix(scenario_mix.clone())
+            .with_autoscale(vu_scaling.clone());
+            #[cfg(feature = "plugins")]
+            let executor = executor.with_plugins(plugins.clone());
+
+            // Link our shared metrics to executor's metrics
+            let exec_dropped = executor.dropped_iterations();
+            let exec_active = executor.vus_active();
+            let dropped_clone = dropped_ref.clone();
+            let active_clone = vus_active_ref.clone();
+
+            tokio::spawn(async move {
+                // Spawn a task to sync metrics periodically
+                let sync_dropped = dropped_clone;
+                let sync_active = active_clone;
+                let sync_exec_dropped = exec_dropped.clone();
+                let sync_exec_active = exec_active.clone();
+
+                tokio::spawn(async move {
+                    loop {
+                        sync_dropped
+                            .store(sync_exec_dropped.load(Ordering::Relaxed), Ordering::Relaxed);
+                        sync_active
+                            .store(sync_exec_active.load(Ordering::Relaxed), Ordering::Relaxed);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                    }
+                });
+
+                executor.run().await;
+            })
+        };
+
+        // Spawn fail-fast threshold checker if enabled
+        let fail_fast_handle = if self.config.fail_fast && !self.config.thresholds.is_empty() {
+            let thresholds = self.config.thresholds.clone();
+            let snapshot_rx = self.snapshot_rx.clone();
+            let cancel = self.cancel_token.clone();
+            let threshold_failed = self.threshold_failed.clone();
+            Some(tokio::spawn(async move {
+                run_fail_fast_checker(thresholds, snapshot_rx, cancel, threshold_failed).await
+            }))
+        } else {
+            None
+        };
+
+        // Wait for duration or cancellation
+        let cancel_token = self.cancel_token.clone();
+        tokio::select! {
+            _ = self.wait_for_end(total_duration) => {
+                tracing::info!("Duration elapsed, stopping");
+                cancel_token.cancel();
+            }
+            _ = cancel_token.cancelled() => {
+                tracing::info!("Cancellation requested");
+            }
+            _ = executor_handle => {
+                tracing::info!("Executor finished");
+            }
+        }
+
+        // Store final metrics
+        self.dropped_iterations
+            .store(dropped_ref.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.vus_active
+            .store(vus_active_ref.load(Ordering::Relaxed), Ordering::Relaxed);
+
+        if let Some(handle) = fail_fast_handle {
+            handle.abort();
+        }
+
+        if let Some(handle) = check_agg_handle {
+            let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+        }
+
+        let stats = aggregator_handle
+            .await
+            .map_err(|e| format!("Aggregator task failed: {}", e))?;
+        flush_slow_log(slow_log.as_deref());
+
+        let final_state = if self.cancel_token.is_cancelled() {
+            RunState::Cancelled
+        } else {
+            RunState::Completed
+        };
+        let _ = self.state_tx.send(final_state);
+
+        Ok(stats)
+    }
+
+    /// Sleep for `total` (warmup plus duration and any `extend`), or with
+    /// auto warmup, until `duration` after warmup actually ended
+    async fn wait_for_end(&self, total: Duration) {
+        if self.config.auto_warmup {
+            let mut phase = self.phase_tx.subscribe();
+            let _ = phase.wait_for(|p| *p == RunPhase::Running).await;
+            self.control.wait(self.config.duration).await
+        } else {
+            self.control.wait(total).await
+        }
+    }
+
+    /// Open the --slow-log file, if one was asked for
+    fn slow_log(&self) -> Result<Option<Arc<SlowLog>>, String> {
+        self.config
+            .slow_log
+            .as_ref()
+            .map(|config| SlowLog::create(config, self.slow_logged.clone()).map(Arc::new))
+            .transpose()
+    }
+
+    /// Load the --plugin modules, if any were given
+    #[cfg(feature = "plugins")]
+    fn plugins(&self) -> Result<Option<Arc<PluginHost>>, String> {
+        if self.config.plugins.is_empty() {
+            return Ok(None);
+        }
+        PluginHost::load(&self.config.plugins).map(|host| Some(Arc::new(host)))
+    }
+
+    /// Plugin response hooks report through the check channel too
+    fn has_plugins(&self) -> bool {
+        #[cfg(feature = "plugins")]
+        {
+            !self.config.plugins.is_empty()
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            false
+        }
+    }
+
+    /// Client for constant-VU HTTP workers
+    fn http_client(&self) -> Result<reqwest::Client, String> {
+        self.proxied_client(self.config.concurrency, self.config.proxy.as_deref())
+    }
+
+    /// Client for `concurrency` HTTP workers going through `proxy`
+    fn proxied_client(
+        &self,
+        concurrency: u32,
+        proxy: Option<&str>,
+    ) -> Result<reqwest::Client, String> {
+        let identity = self
+            .config
+            .client_cert
+            .as_deref()
+            .zip(self.config.client_key.as_deref());
+        self.build_client(concurrency, proxy, identity)
+    }
+
+    /// Client for `concurrency` HTTP workers presenting one of the
+    /// `--client-cert-pool` certificates
+    fn identity_client(
+        &self,
+        concurrency: u32,
+        identity: &ClientIdentity,
+    ) -> Result<reqwest::Client, String> {
+        self.build_client(
+            concurrency,
+            self.config.proxy.as_deref(),
+            Some((&identity.cert, &identity.key)),
+        )
+    }
+
+    fn build_client(
+        &self,
+        concurrency: u32,
+        proxy: Option<&str>,
+        identity: Option<(&Path, &Path)>,
+    ) -> Result<reqwest::Client, String> {
+        create_client(
+            concurrency,
+            self.config.timeout,
+            self.config.connect_timeout,
+            self.config.insecure,
+            self.config.tls_backend,
+            self.config.http2,
+            self.config.overrides_host(),
+            self.config.cookie_jar,
+            &self.config.cookies,
+            self.config.follow_redirects,
+            self.config.disable_keepalive,
+            proxy,
+            identity.map(|(cert, _)| cert),
+            identity.map(|(_, key)| key),
+            self.config.ca_cert.as_deref(),
+            self.config
+                .connect_to
+                .as_ref()
+                .map(|(h, a)| (h.as_str(), *a)),
+            &self.config.socket,
+            Some(self.connections_opened.clone()),
+        )
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+    }
+
+    /// Clients for `--proxy-list` and the `scenarios` with their own proxy
+    fn proxy_pool(
+        &self,
+        concurrency: u32,
+        scenarios: &[Scenario],
+    ) -> Result<Option<Arc<ProxyPool>>, String> {
+        ProxyPool::for_config(&self.config, scenarios, |proxy| {
+            self.proxied_client(concurrency, Some(proxy))
+        })
+        .map(|pool| pool.map(Arc::new))
+    }
+
+    /// Batcher for a worker feeding the result channel, under the configured
+    /// backpressure policy
+    fn result_batcher(&self, tx: mpsc::Sender<ResultBatch>) -> ResultBatcher {
+        ResultBatcher::new(tx).with_policy(self.config.backpressure, self.results_dropped.clone())
+    }
+
+    async fn run_constant_vus_mode(mut self) -> Result<Stats, String> {
+        let client = self.http_client()?;
+
+        // Set up rate limiter if configured
+        let rate_limiter = if self.config.rate > 0 {
+            let limiter = RateLimiter::new(self.config.rate, self.config.rate_burst);
+            let refiller = limiter.clone();
+            tokio::spawn(async move { refiller.run_refiller().await });
+            Some(limiter)
+        } else {
+            None
+        };
+
+        // Determine if using stages or simple concurrency
+        let use_stages =
+            !self.config.stages.is_empty() && self.config.stages.iter().any(|s| s.target.is_some());
+        let (worker_permits, total_duration, max_workers): (Arc<Semaphore>, Duration, u32) =
+            if use_stages {
+                // Stages mode: use StagesScheduler
+                let max_target = self
+                    .config
+                    .stages
+                    .iter()
+                    .filter_map(|s| s.target)
+                    .max()
+                    .unwrap_or(1);
+                let (stages_scheduler, stage_info_rx) =
+                    StagesScheduler::new(self.config.stages.clone(), max_target);
+                self.control
+                    .allow_stages(self.config.stages.clone(), Some(max_target));
+                let stages_scheduler = stages_scheduler.with_control(&self.control);
+                let permits = stages_scheduler.permits();
+                let duration = stages_scheduler.total_duration();
+                self.stage_info_rx = Some(stage_info_rx);
+                tokio::spawn(stages_scheduler.run());
+                (permits, self.config.warmup + duration, max_target)
+            } else {
+                // Simple mode: use RampUpScheduler
+                let ramp_scheduler =
+                    RampUpScheduler::new(self.config.concurrency, self.config.ramp_up);
+                let permits = ramp_scheduler.permits();
+                tokio::spawn(ramp_scheduler.run());
+                (
+                    permits,
+                    self.config.warmup + self.config.duration,
+                    self.config.concurrency,
+                )
+            };
+
+        // Workers record into per-core shards; the channel only tells the
+        // aggregator when the last worker has finished
+        let (result_tx, result_rx) = mpsc::channel::<ResultBatch>(1);
+        let slow_log = self.slow_log()?;
+        #[cfg(feature = "plugins")]
+        let plugins = self.plugins()?;
+        let chaos = self.config.chaos.clone().map(|config| {
+            Arc::new(Chaos::new(
+                config,
+                self.config.socket.clone(),
+                self.config.connect_timeout,
+                self.config.connect_to.clone(),
+            ))
+        });
+        let throttle = self
+            .config
+            .honor_retry_after
+            .then(|| Arc::new(Throttle::default()));
+        let health = Arc::new(WorkerHealth::new(max_workers));
+        let duplicates = self
+            .config
+            .duplicates
+            .clone()
+            .map(|config| Arc::new(Duplicates::new(config)));
+        let revalidation = self
+            .config
+            .conditional_requests
+            .then(|| Arc::new(Revalidation::default()));
+        let outages = self
+            .config
+            .pause_on_errors
+            .map(|window| Arc::new(Outages::new(window)));
+        let connection_cap = self
+            .config
+            .max_connections
+            .map(|limit| Arc::new(ConnectionCap::new(limit)));
+        let budget = Budget::for_config(&self.config);
+        let shards = Arc::new(
+            StatsShards::new(self.config.max_requests, self.cancel_token.clone())
+                .with_failure_samples(self.config.error_samples)
+                .with_slowest_traces(self.config.trace_slowest)
+                .with_apdex(self.config.apdex_t)
+                .with_slow_log(slow_log.clone()),
+        );
+        let results = ResultSink::sharded(result_tx, shards.clone());
+
+        let _ = self.state_tx.send(RunState::Running);
+
+        // Mixed HTTP + WebSocket: WS scenarios get their own VUs, split by weight
+        let (ws_scenarios, http_scenarios): (Vec<Scenario>, Vec<Scenario>) = self
+            .config
+            .scenarios
+            .iter()
+            .cloned()
+            .partition(|s| s.is_websocket());
+        let ws_vus = mixed_ws_vus(&http_scenarios, &ws_scenarios, max_workers);
+        let extractions = ExtractionCounts::for_scenarios(&http_scenarios).map(Arc::new);
+        let scenario_mix = ScenarioMix::for_scenarios(&http_scenarios).map(Arc::new);
+        let mut ws_handles = Vec::with_capacity(ws_vus as usize);
+        let mut ws_aggregator_handle = None;
+        let (ws_snapshot_tx, ws_snapshot_rx) = watch::channel(StatsSnapshot::default());
+
+        if ws_vus > 0 {
+            let (ws_result_tx, ws_result_rx) =
+                mpsc::channel::<WsMessageResult>(RESULT_CHANNEL_SIZE);
+            let ws_aggregator = WsAggregator::new(
+                total_duration,
+                ws_result_rx,
+                ws_snapshot_tx,
+                self.config.warmup,
+                self.phase_tx.clone(),
+                self.cancel_token.clone(),
+                ws_vus,
+            );
+            ws_aggregator_handle = Some(tokio::spawn(ws_aggregator.run()));
+
+            // Each WS VU holds one connection to one scenario for the run
+            let assigned = split_by_weight(&ws_scenarios, ws_vus)
+                .into_iter()
+                .enumerate()
+                .flat_map(|(idx, vus)| std::iter::repeat_n(idx, vus as usize));
+            for (id, idx) in (0..ws_vus).zip(assigned) {
+                let scenario = &ws_scenarios[idx];
+                let worker = WsWorker::new(
+                    id,
+                    scenario.url.clone(),
+                    scenario.headers.clone(),
+                    self.config.ws_subprotocols.clone(),
+                    self.config.ws_compression,
+                    scenario.body.clone().unwrap_or_else(|| "ping".to_string()),
+                    self.config.ws_mode,
+                    self.config.ws_message_interval,
+                    None,
+                    self.config.timeout,
+                    self.config.ws_reconnect_every,
+                    ws_result_tx.clone(),
+                    self.cancel_token.clone(),
+                );
+                ws_handles.push(tokio::spawn(worker.run()));
+            }
+        }
+
+        // Create aggregator
+        let mut aggregator = Aggregator::new(
+            total_duration,
+            result_rx,
+            self.snapshot_tx.clone(),
+            self.config.warmup,
+            self.phase_tx.clone(),
+            self.config.max_requests,
+            self.cancel_token.clone(),
+            self.history.clone(),
+            self.config.prometheus.clone(),
+            &self.config.url,
+        )
+        .with_timeline_interval(self.config.timeline_interval)
+        .with_failure_samples(self.config.error_samples)
+        .with_slowest_traces(self.config.trace_slowest)
+        .with_apdex(self.config.apdex_t)
+        .with_expected_content_types(self.config.expect_content_types.clone())
+        .with_percentiles(self.config.percentiles.clone())
+        .with_latency_histogram(self.config.latency_histogram)
+        .with_auto_warmup(self.config.auto_warmup)
+        .with_shards(shards)
+        .with_connection_counter(self.connections_opened.clone())
+        .with_chaos(chaos.as_ref().map(|c| c.counters()))
+        .with_throttle(throttle.clone())
+        .with_worker_health(health.clone())
+        .with_duplicates(duplicates.clone())
+        .with_revalidation(revalidation.clone())
+        .with_outages(outages.clone())
+        .with_connection_cap(connection_cap.clone())
+        .with_failover(self.config.failover)
+        .with_extractions(extractions.clone())
+        .with_scenario_mix(scenario_mix.clone())
+        .with_checks(self.check_stats.clone())
+        .with_stages(self.stage_info_rx.clone());
+        #[cfg(feature = "plugins")]
+        {
+            aggregator = aggregator.with_plugin_metrics(plugins.as_ref().map(|p| p.metrics()));
+        }
+        if ws_vus > 0 {
+            aggregator = aggregator.with_ws_snapshots(ws_snapshot_rx.clone());
+        }
+        let aggregator_handle = tokio::spawn(aggregator.run());
+
+        // Spawn workers (up to max needed)
+        let http_workers = max_workers - ws_vus;
+        let mut worker_handles = Vec::with_capacity(http_workers as usize);
+        let scenarios = Arc::new(http_scenarios);
+        let checks = Arc::new(self.config.checks.clone());
+
+        // Create check results channel if checks are configured
+        let (check_tx, check_rx) = if !self.config.checks.is_empty() || self.has_plugins() {
+            let (tx, rx) = mpsc::channel::<CheckResult>(RESULT_CHANNEL_SIZE);
+            (Some(tx), Some(rx))
+        } else {
+            (None, None)
+        };
+
+        // Spawn check stats aggregator - drains channel completely
+        let check_stats_clone = self.check_stats.clone();
+        let check_agg_handle = check_rx.map(|mut rx| {
+            tokio::spawn(async move {
+                while let Some(check_result) = rx.recv().await {
+                    let mut stats = check_stats_clone.lock().unwrap();
+                    let entry = stats.entry(check_result.name).or_insert((0, 0));
+                    if check_result.passed {
+                        entry.0 += 1;
+                    }
+                    entry.1 += 1;
+                }
+            })
+        });
+
+        let form_fields = Arc::new(self.config.form_fields.clone());
+
+        // v1.3.0 features
+        let url_list = self.config.url_list.as_ref().map(|v| Arc::new(v.clone()));
+        let body_lines = self.config.body_lines.as_ref().map(|v| Arc::new(v.clone()));
+        let stdin = self.config.stdin.as_ref().map(|stream| {
+            Arc::new(LineStream::spawn(
+                stream,
+                http_workers as usize,
+                self.cancel_token.clone(),
+            ))
+        });
+
+        // With --threads, workers are spread over independent runtimes, each
+        // with its own client so connections stay on that runtime's core
+        let runtimes = self
+            .config
+            .threads
+            .map(|threads| WorkerRuntimes::new(threads, http_workers))
+            .transpose()?;
+        let clients = match runtimes {
+            Some(ref runtimes) => {
+                let mut clients = vec![client];
+                for _ in 1..runtimes.count() {
+                    clients.push(self.http_client()?);
+                }
+                clients
+            }
+            None => vec![client],
+        };
+        let proxies = clients
+            .iter()
+            .map(|_| self.proxy_pool(self.config.concurrency, &scenarios))
+            .collect::<Result<Vec<_>, _>>()?;
+        // VUs share each certificate's client the way they'd share the one
+        let certs = CertPool::for_config(&self.config, |identity| {
+            let vus = http_workers.div_ceil(self.config.client_cert_pool.len() as u32);
+            self.identity_client(vus, identity)
+        })?;
+
+        let endpoints = EndpointNamer::for_config(&self.config).map(Arc::new);
+        let hosts = HostPicker::for_config(&self.config).map(Arc::new);
+        let id_header: Option<Arc<str>> = self.config.request_id_header.as_deref().map(Arc::from);
+        for id in 0..http_workers {
+            let slot = id as usize % clients.len();
+            let client = match certs {
+                Some(ref certs) => certs.client(id),
+                None => &clients[slot],
+            };
+            let worker = Worker::new(
+                id,
+                client.clone(),
+                self.config.url.clone(),
+                self.config.method.clone(),
+                self.config.headers.clone(),
+                self.config.body.clone(),
+                scenarios.clone(),
+                results.clone(),
+                self.cancel_token.clone(),
+                rate_limiter.clone(),
+                worker_permits.clone(),
+                self.config.think_time,
+                checks.clone(),
+                check_tx.clone(),
+                form_fields.clone(),
+                self.config.basic_auth.clone(),
+                url_list.clone(),
+                body_lines.clone(),
+                self.config.rand_regex_url.as_deref(),
+                self.config.jsonrpc_method.is_some(),
+            )
+            .with_endpoints(endpoints.clone())
+            .with_hosts(hosts.clone())
+            .with_seed(self.config.seed)
+            .with_network(self.config.network.clone())
+            .with_budget(budget)
+            .with_proxies(proxies[slot].clone())
+            .with_csrf(self.config.csrf.clone())
+            .with_stdin(stdin.clone())
+            .with_trace(self.config.trace_propagation)
+            .with_compression(self.config.compress_body)
+            .with_request_id(id_header.clone())
+            .with_chaos(chaos.clone())
+            .with_throttle(throttle.clone())
+            .with_duplicates(duplicates.clone())
+            .with_revalidation(revalidation.clone())
+            .with_outages(outages.clone())
+            .with_connection_cap(connection_cap.clone())
+            .with_extractions(extractions.clone())
+            .with_scenario_mix(scenario_mix.clone())
+            .with_health(Some(health.clone()));
+            #[cfg(feature = "plugins")]
+            let worker = worker.with_plugins(plugins.clone());
+            worker_handles.push(match runtimes {
+                Some(ref runtimes) => runtimes.spawn(slot, worker.run()),
+                None => tokio::spawn(worker.run()),
+            });
+        }
+
+        drop(results);
+        drop(check_tx);
+
+        let cancel_token = self.cancel_token.clone();
+
+        // One health check stands in for the VUs while the target is down
+        if let Some(ref outages) = outages {
+            let outages = outages.clone();
+            let client = match certs {
+                Some(ref certs) => certs.client(0),
+                None => &clients[0],
+            }
+            .clone();
+            let url = self.config.url.clone();
+            let cancel = cancel_token.clone();
+            tokio::spawn(async move { outages.monitor(client, url, cancel).await });
+        }
+
+        // Spawn fail-fast threshold checker if enabled
+        let fail_fast_handle = if self.config.fail_fast && !self.config.thresholds.is_empty() {
+            let thresholds = self.config.thresholds.clone();
+            let snapshot_rx = self.snapshot_rx.clone();
+            let cancel = cancel_token.clone();
+            let threshold_failed = self.threshold_failed.clone();
+            Some(tokio::spawn(async move {
+                run_fail_fast_checker(thresholds, snapshot_rx, cancel, threshold_failed).await
+            }))
+        } else {
+            None
+        };
+
+        // Stages define the run's length; a rate limiter can be re-rated
+        self.control
+            .allow(!use_stages, rate_limiter.as_ref().map(|_| self.config.rate));
+        if let Some(ref limiter) = rate_limiter {
+            let limiter = limiter.clone();
+            let mut plan = self.control.subscribe();
+            tokio::spawn(async move {
+                while plan.changed().await.is_ok() {
+                    if let Some(rate) = plan.borrow_and_update().rate {
+                        limiter.set_rate(rate);
+                    }
+                }
+            });
+        }
+
+        // Wait for total duration
+        tokio::select! {
+            _ = self.wait_for_end(total_duration) => {
+                tracing::info!("Duration elapsed, stopping workers");
+                cancel_token.cancel();
+            }
+            _ = cancel_token.cancelled() => {
+                tracing::info!("Cancellation requested");
+            }
+        }
+
+        // Cancel fail-fast checker if running
+        if let Some(handle) = fail_fast_handle {
+            handle.abort();
+        }
+
+        // Wait for workers to finish (with timeout)
+        for handle in worker_handles.into_iter().chain(ws_handles) {
+            let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+        }
+        if let Some(runtimes) = runtimes {
+            runtimes.shutdown();
+        }
+
+        // Wait for check aggregator to drain all results
+        if let Some(handle) = check_agg_handle {
+            let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+        }
+
+        let stats = aggregator_handle
+            .await
+            .map_err(|e| format!("Aggregator task failed: {}", e))?;
+        flush_slow_log(slow_log.as_deref());
+
+        // Fold the final WebSocket stats into the last snapshot
+        if let Some(handle) = ws_aggregator_handle {
+            handle
+                .await
+                .map_err(|e| format!("WebSocket aggregator task failed: {}", e))?;
+            self.snapshot_tx
+                .send_modify(|s| s.merge_websocket(&ws_snapshot_rx.borrow()));
+        }
+
+        let final_state = if self.cancel_token.is_cancelled() {
+            RunState::Cancelled
+        } else {
+            RunState::Completed
+        };
+        let _ = self.state_tx.send(final_state);
+
+        Ok(stats)
+    }
+
+    /// Run burst mode - send N requests, wait, repeat
+    async fn run_burst_mode(self) -> Result<Stats, String> {
+        let burst_config = self
+            .config
+            .burst_config
+            .as_ref()
+            .ok_or("Burst config not set")?
+            .clone();
+
+        let client = create_client(
+            burst_config.requests_per_burst,
+            self.config.timeout,
+            self.config.connect_timeout,
+            self.config.insecure,
+            self.config.tls_backend,
+            self.config.http2,
+            self.config.overrides_host(),
+            self.config.cookie_jar,
+            &self.config.cookies,
+            self.config.follow_redirects,
+            self.config.disable_keepalive,
+            self.config.proxy.as_deref(),
+            self.config.client_cert.as_deref(),
+            self.config.client_key.as_deref(),
+            self.config.ca_cert.as_deref(),
+            self.config
+                .connect_to
+                .as_ref()
+                .map(|(h, a)| (h.as_str(), *a)),
+            &self.config.socket,
+            Some(self.connections_opened.clone()),
+        )
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        let total_duration = self.config.warmup + self.config.duration;
+
+        let (result_tx, result_rx) = mpsc::channel::<ResultBatch>(RESULT_CHANNEL_SIZE);
+
+        let _ = self.state_tx.send(RunState::Running);
+
+        // Create aggregator
+        let aggregator = Aggregator::new(
+            total_duration,
+            result_rx,
+            self.snapshot_tx.clone(),
+            self.config.warmup,
+            self.phase_tx.clone(),
+            self.config.max_requests,
+            self.cancel_token.clone(),
+            self.history.clone(),
+            self.config.prometheus.clone(),
+            &self.config.url,
+        )
+        .with_timeline_interval(self.config.timeline_interval)
+        .with_failure_samples(self.config.error_samples)
+        .with_slowest_traces(self.config.trace_slowest)
+        .with_apdex(self.config.apdex_t)
+        .with_percentiles(self.config.percentiles.clone())
+        .with_latency_histogram(self.config.latency_histogram)
+        .with_results_dropped(self.results_dropped.clone())
+        .with_connection_counter(self.connections_opened.clone());
+        let tracker = Arc::new(BurstTracker::default());
+        let aggregator = aggregator.with_bursts(tracker.clone());
+        let aggregator_handle = tokio::spawn(aggregator.run());
+
+        // Spawn burst executor
+        let url = self.config.url.clone();
+        let method = self.config.method.clone();
+        let headers = self.config.headers.clone();
+        let body = self.config.body.clone();
+        let cancel_token = self.cancel_token.clone();
+        let form_fields = Arc::new(self.config.form_fields.clone());
+        let basic_auth = self.config.basic_auth.clone();
+        let trace = self.config.trace_propagation;
+        let id_header: Option<Arc<str>> = self.config.request_id_header.as_deref().map(Arc::from);
+        let compression = self.config.compress_body;
+        let prepared = PreparedRequest::new(&method, &url, &headers, body.as_deref())
+            .map(|prepared| Arc::new(prepared.compressed(compression)));
+        let mut results = self.result_batcher(result_tx);
+        let mut rng = seed::rng(self.config.seed, 0);
+
+        let burst_handle = tokio::spawn(async move {
+            let start = Instant::now();
+            let mut burst_count = 0u64;
+
+            while start.elapsed() < total_duration && !cancel_token.is_cancelled() {
+                burst_count += 1;
+                let size = burst::burst_size(&burst_config, start.elapsed(), total_duration);
+                tracing::debug!("Starting burst {} of {} requests", burst_count, size);
+                let burst_start = Instant::now();
+
+                // Send burst of requests concurrently
+                let mut handles = Vec::with_capacity(size as usize);
+
+                for _ in 0..size {
+                    if cancel_token.is_cancelled() {
+                        break;
+                    }
+
+                    let client = client.clone();
+                    let url = url.clone();
+                    let method = method.clone();
+                    let headers = headers.clone();
+                    let body = body.clone();
+                    let form_fields = form_fields.clone();
+                    let basic_auth = basic_auth.clone();
+                    let prepared = prepared.clone();
+                    let tracker = tracker.clone();
+                    let id_header = id_header.clone();
+
+                    tracker.started();
+                    let handle = tokio::spawn(async move {
+                        let form_data = if !form_fields.is_empty() {
+                            Some(form_fields.as_slice())
+                        } else {
+                            None
+                        };
+                        let basic_auth_ref =
+                            basic_auth.as_ref().map(|(u, p)| (u.as_str(), p.as_deref()));
+
+                        let result = match prepared {
+                            Some(prepared) => {
+                                execute_prepared(
+                                    &client,
+                                    &prepared,
+                                    form_data,
+                                    basic_auth_ref,
+                                    false, // capture_body
+                                    trace,
+                                    id_header.as_deref(),
+                                    None, // scheduled_at
+                                )
+                                .await
+                            }
+                            None => {
+                                crate::http::execute_request(
+                                    &client,
+                                    &url,
+                                    &method,
+                                    &headers,
+                                    body.as_deref(),
+                                    compression,
+                                    form_data,
+                                    basic_auth_ref,
+                                    false, // capture_body
+                                    trace,
+                                    id_header.as_deref(),
+                                    None, // scheduled_at
+                                )
+                                .await
+                            }
+                        };
+                        tracker.finished();
+                        result
+                    });
+                    handles.push(handle);
+                }
+
+                // Wait for all requests in this burst to complete
+                let sent = handles.len() as u32;
+                for handle in handles {
+                    if let Ok(result) = results.wait_for(handle).await {
+                        results.push(result).await;
+                    }
+                }
+                if sent > 0 {
+                    tracker.record(sent, burst_start.elapsed());
+                }
+                if !results.flush().await {
+                    break;
+                }
+
+                // Check if we should continue
+                if start.elapsed() >= total_duration || cancel_token.is_cancelled() {
+                    break;
+                }
+
+                // Wait before next burst
+                tokio::select! {
+                    _ = sleep(burst::next_delay(&burst_config, &mut rng)) => {}
+                    _ = cancel_token.cancelled() => break,
+                }
+            }
+
+            tracing::info!("Burst mode completed: {} bursts sent", burst_count);
+        });
+
+        // Wait for duration or cancellation
+        let cancel_token = self.cancel_token.clone();
+        tokio::select! {
+            _ = self.control.wait(total_duration) => {
+                tracing::info!("Duration elapsed, stopping burst mode");
+                cancel_token.cancel();
+            }
+            _ = cancel_token.cancelled() => {
+                tracing::info!("Cancellation requested");
+            }
+            _ = burst_handle => {
+                tracing::info!("Burst executor finished");
+            }
+        }
+
+        let stats = aggregator_handle
+            .await
+            .map_err(|e| format!("Aggregator task failed: {}", e))?;
+
+        let final_state = if self.cancel_token.is_cancelled() {
+            RunState::Cancelled
+        } else {
+            RunState::Completed
+        };
+        let _ = self.state_tx.send(final_state);
+
+        Ok(stats)
+    }
+
+    /// Run HTTP/3 load test mode
+    #[cfg(feature = "http3")]
+    async fn run_http3_mode(self) -> Result<Stats, String> {
+        let total_duration = self.config.warmup + self.config.duration;
+        let concurrency = self.config.concurrency;
+
+        // Create HTTP/3 client
+        let client = Http3Client::new(&self.config)
+            .map_err(|e| format!("Failed to create HTTP/3 client: {}", e))?;
+        let client = Arc::new(client);
+
+        let (result_tx, result_rx) = mpsc::channel::<ResultBatch>(RESULT_CHANNEL_SIZE);
+
+        let _ = self.state_tx.send(RunState::Running);
+
+        // Create aggregator
+        let aggregator = Aggregator::new(
+            total_duration,
+            result_rx,
+            self.snapshot_tx.clone(),
+            self.config.warmup,
+            self.phase_tx.clone(),
+            self.config.max_requests,
+            self.cancel_token.clone(),
+            self.history.clone(),
+            self.config.prometheus.clone(),
+            &self.config.url,
+        )
+        .with_timeline_interval(self.config.timeline_interval)
+        .with_failure_samples(self.config.error_samples)
+        .with_slowest_traces(self.config.trace_slowest)
+        .with_apdex(self.config.apdex_t)
+        .with_percentiles(self.config.percentiles.clone())
+        .with_latency_histogram(self.config.latency_histogram)
+        .with_results_dropped(self.results_dropped.clone())
+        .with_failover(self.config.failover);
+        let aggregator_handle = tokio::spawn(aggregator.run());
+
+        // Each request targets either the main URL or a weighted scenario
+        let targets: Arc<Vec<Scenario>> = Arc::new(if self.config.scenarios.is_empty() {
+            vec![Scenario {
+                name: "default".to_string(),
+                url: self.config.url.clone(),
+                method: self.config.method.clone(),
+                headers: self.config.headers.clone(),
+                body: self.config.body.clone(),
+                weight: 1,
+                extractions: Vec::new(),
+                depends_on: None,
+                tags: HashMap::new(),
+                proxy: None,
+                think_time: None,
+            }]
+        } else {
+            self.config.scenarios.clone()
+        });
+        let total_weight: u32 = targets.iter().map(|s| s.weight).sum();
+
+        // Spawn workers
+        let mut worker_handles = Vec::with_capacity(concurrency as usize);
+        let timeout = self.config.timeout;
+
+        for id in 0..concurrency {
+            let client = client.clone();
+            let mut results = self.result_batcher(result_tx.clone());
+            let cancel_token = self.cancel_token.clone();
+            let targets = targets.clone();
+
+            let handle = tokio::spawn(async move {
+                let mut counter = id as u64;
+                loop {
+                    if cancel_token.is_cancelled() {
+                        break;
+                    }
+
+                    let target = select_weighted(&targets, total_weight, counter);
+                    counter = counter.wrapping_add(1);
+
+                    let result = results
+                        .wait_for(execute_http3_request(
+                            &client,
+                            &target.url,
+                            target.method.as_str(),
+                            &target.headers,
+                            target.body.as_deref(),
+                            timeout,
+                        ))
+                        .await;
+
+                    if !results.push(result).await {
+                        break;
+                    }
+                }
+                results.flush().await;
+            });
+            worker_handles.push(handle);
+        }
+
+        drop(result_tx);
+
+        let cancel_token = self.cancel_token.clone();
+
+        // Wait for duration or cancellation
+        tokio::select! {
+            _ = self.control.wait(total_duration) => {
+                tracing::info!("Duration elapsed, stopping HTTP/3 workers");
+                cancel_token.cancel();
+            }
+            _ = cancel_token.cancelled() => {
+                tracing::info!("Cancellation requested");
+            }
+        }
+
+        // Wait for workers to finish
+        for handle in worker_handles {
+            let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+        }
+
+        let stats = aggregator_handle
+            .await
+            .map_err(|e| format!("Aggregator task failed: {}", e))?;
+
+        let final_state = if self.cancel_token.is_cancelled() {
+            RunState::Cancelled
+        } else {
+            RunState::Completed
+        };
+        let _ = self.state_tx.send(final_state);
+
+        Ok(stats)
+    }
+
+    /// Run gRPC load test mode
+    #[cfg(feature = "grpc")]
+    async fn run_grpc_mode(self) -> Result<Stats, String> {
+        use crate::types::{ErrorKind, RequestResult};
+        use reqwest::Url;
+
+        let total_duration = self.config.warmup + self.config.duration;
+        let concurrency = self.config.concurrency;
+
+        // Parse URL for address
+        let url = Url::parse(&self.config.url).map_err(|e| format!("Invalid URL: {}", e))?;
+        let host = url.host_str().ok_or("Missing host in URL")?;
+        let port = url
+            .port()
+            .unwrap_or(if url.scheme() == "https" { 443 } else { 80 });
+        let address = format!("{}:{}", host, port);
+        let tls = url.scheme() == "https";
+
+        let service = self
+            .config
+            .grpc_service
+            .clone()
+            .ok_or("gRPC service not specified")?;
+        let method = self
+            .config
+            .grpc_method
+            .clone()
+            .ok_or("gRPC method not specified")?;
+
+        // Use binary body_bytes if available, otherwise fall back to string body as bytes
+        let request_bytes = self.config.body_bytes.clone().unwrap_or_else(|| {
+            self.config
+                .body
+                .as_ref()
+                .map(|s| s.as_bytes().to_vec())
+                .unwrap_or_default()
+        });
+
+        let grpc_config = GrpcConfig {
+            address,
+            service,
+            method,
+            request: request_bytes,
+            timeout: self.config.timeout,
+            tls,
+            insecure: self.config.insecure,
+            metadata: self.config.headers.clone(),
+            ..Default::default()
+        };
+        let grpc_config = Arc::new(grpc_config);
+
+        let (result_tx, result_rx) = mpsc::channel::<ResultBatch>(RESULT_CHANNEL_SIZE);
+
+        let _ = self.state_tx.send(RunState::Running);
+
+        // Create aggregator
+        let aggregator = Aggregator::new(
+            total_duration,
+            result_rx,
+            self.snapshot_tx.clone(),
+            self.config.warmup,
+            self.phase_tx.clone(),
+            self.config.max_requests,
+            self.cancel_token.clone(),
+            self.history.clone(),
+            self.config.prometheus.clone(),
+            &self.config.url,
+        )
+        .with_timeline_interval(self.config.timeline_interval)
+        .with_failure_samples(self.config.error_samples)
+        .with_slowest_traces(self.config.trace_slowest)
+        .with_apdex(self.config.apdex_t)
+        .with_percentiles(self.config.percentiles.clone())
+        .with_latency_histogram(self.config.latency_histogram)
+        .with_results_dropped(self.results_dropped.clone())
+        .with_failover(self.config.failover);
+        let aggregator_handle = tokio::spawn(aggregator.run());
+
+        // Spawn workers
+        let mut worker_handles = Vec::with_capacity(concurrency as usize);
+
+        for _id in 0..concurrency {
+            let grpc_config = grpc_config.clone();
+            let mut results = self.result_batcher(result_tx.clone());
+            let cancel_token = self.cancel_token.clone();
+
+            let handle = tokio::spawn(async move {
+                loop {
+                    if cancel_token.is_cancelled() {
+                        break;
+                    }
+
+                    let grpc_result = results.wait_for(execute_grpc_request(&grpc_config)).await;
+
+                    // Convert gRPC result to HTTP-like RequestResult for aggregation
+                    let result = RequestResult {
+                        status: if grpc_result.error.is_some() {
+                            None // Error case - no valid HTTP status
+                        } else if grpc_result.status_code == 0 {
+                            Some(200) // gRPC OK -> HTTP 200
+                        } else {
+                            // gRPC status codes are 0-16, clamp and map to 5xx range
+                            Some(500 + grpc_result.status_code.clamp(0, 16) as u16)
+                        },
+                        latency_us: grpc_result.latency_us,
+                        bytes_received: grpc_result.bytes_received,
+                        error: grpc_result.error.map(|e| match e {
+                            GrpcError::Connect(_) => ErrorKind::Connect,
+                            GrpcError::Timeout => ErrorKind::Timeout,
+                            _ => ErrorKind::Other,
+                        }),
+                        body: grpc_result.responses.first().cloned(),
+                        scheduled_at_us: None,
+                        started_at_us: None,
+                        queue_time_us: None,
+                        quic: None,
+                        jsonrpc_error: None,
+                        endpoint: None,
+                        host: None,
+                        bytes_sent: 0,
+                        upload_us: None,
+                        failure: None,
+                        trace_id: None,
+                        id_echo: None,
+                        retry_after: None,
+                        content_type: None,
+                        headers: None,
+                    };
+
+                    if !results.push(result).await {
+                        break;
+                    }
+                }
+                results.flush().await;
+            });
+            worker_handles.push(handle);
+        }
+
+        drop(result_tx);
+
+        let cancel_token = self.cancel_token.clone();
+
+        // Wait for duration or cancellation
+        tokio::select! {
+            _ = self.control.wait(total_duration) => {
+                tracing::info!("Duration elapsed, stopping gRPC workers");
+                cancel_token.cancel();
+            }
+            _ = cancel_token.cancelled() => {
+                tracing::info!("Cancellation requested");
+            }
+        }
+
+        // Wait for workers to finish
+        for handle in worker_handles {
+            let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+        }
+
+        let stats = aggregator_handle
+            .await
+            .map_err(|e| format!("Aggregator task failed: {}", e))?;
+
+        let final_state = if self.cancel_token.is_cancelled() {
+            RunState::Cancelled
+        } else {
+            RunState::Completed
+        };
+        let _ = self.state_tx.send(final_state);
+
+        Ok(stats)
+    }
+
+    #[cfg(feature = "redis")]
+    async fn run_redis_mode(self) -> Result<Stats, String> {
+        use crate::redis::{RedisConnection, parse_redis_url};
+        use crate::types::RequestResult;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let total_duration = self.config.warmup + self.config.duration;
+        let concurrency = self.config.concurrency;
+        let target = Arc::new(parse_redis_url(&self.config.url)?);
+        let commands = Arc::new(self.config.redis_commands.clone());
+        let keyspace = self.config.redis_keyspace;
+        let timeout = self.config.timeout;
+        let socket = Arc::new(self.config.socket.clone());
+
+        let (result_tx, result_rx) = mpsc::channel::<ResultBatch>(RESULT_CHANNEL_SIZE);
+
+        let _ = self.state_tx.send(RunState::Running);
+
+        let aggregator = Aggregator::new(
+            total_duration,
+            result_rx,
+            self.snapshot_tx.clone(),
+            self.config.warmup,
+            self.phase_tx.clone(),
+            self.config.max_requests,
+            self.cancel_token.clone(),
+            self.history.clone(),
+            self.config.prometheus.clone(),
+            &self.config.url,
+        )
+        .with_timeline_interval(self.config.timeline_interval)
+        .with_failure_samples(self.config.error_samples)
+        .with_slowest_traces(self.config.trace_slowest)
+        .with_apdex(self.config.apdex_t)
+        .with_percentiles(self.config.percentiles.clone())
+        .with_latency_histogram(self.config.latency_histogram)
+        .with_results_dropped(self.results_dropped.clone())
+        .with_failover(self.config.failover);
+        let aggregator_handle = tokio::spawn(aggregator.run());
+
+        let mut worker_handles = Vec::with_capacity(concurrency as usize);
+
+        for id in 0..concurrency {
+            let target = target.clone();
+            let commands = commands.clone();
+            let socket = socket.clone();
+            let mut results = self.result_batcher(result_tx.clone());
+            let cancel_token = self.cancel_token.clone();
+
+            let seed = self.config.seed;
+
+            let handle = tokio::spawn(async move {
+                let mut connection: Option<RedisConnection> = None;
+                let mut request_id: u64 = 0;
+                let mut rng = crate::engine::seed::rng(seed, id as u64);
+                // Offset each VU so a GET/SET mix interleaves across VUs
+                let mut next_command = id as usize;
+
+                loop {
+                    if cancel_token.is_cancelled() {
+                        break;
+                    }
+
+                    let start = Instant::now();
+                    if connection.is_none() {
+                        let connecting = RedisConnection::connect(&target, &socket, timeout);
+                        match results.wait_for(connecting).await {
+                            Ok(conn) => connection = Some(conn),
+                            Err(kind) => {
+                                let result =
+                                    RequestResult::error(start.elapsed().as_micros() as u64, kind);
+                                if !results.push(result).await {
+                                    break;
+                                }
+                                let backoff = sleep(Duration::from_secs(1));
+                                tokio::select! {
+                                    _ = results.wait_for(backoff) => {}
+                                    _ = cancel_token.cancelled() => break,
+                                }
+                                continue;
+                            }
+                        }
+                    }
+                    let conn = connection.as_mut().unwrap();
+
+                    request_id += 1;
+                    let timestamp_ms = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|d| d.as_millis())
+                        .unwrap_or(0);
+                    let template = &commands[next_command % commands.len()];
+                    next_command += 1;
+                    let args: Vec<String> = template
+                        .iter()
+                        .map(|arg| {
+                            let arg = arg
+                                .replace("${VU}", &id.to_string())
+                                .replace("${REQUEST_ID}", &request_id.to_string())
+                                .replace("${TIMESTAMP_MS}", &timestamp_ms.to_string());
+                            if arg.contains("${RANDOM_KEY}") {
+                                arg.replace(
+                                    "${RANDOM_KEY}",
+                                    &rand::Rng::random_range(&mut rng, 0..keyspace).to_string(),
+                                )
+                            } else {
+                                arg
+                            }
+                        })
+                        .collect();
+
+                    let start = Instant::now();
+                    // Redis replies map onto HTTP-like results: error replies
+                    // count as 500 so error_rate thresholds apply
+                    let result = match results.wait_for(conn.command(&args, timeout)).await {
+                        Ok((reply, bytes)) => {
+                            let status = if reply.is_error() { 500 } else { 200 };
+                            RequestResult::success(
+                                start.elapsed().as_micros() as u64,
+                                status,
+                                bytes,
+                                Some(reply.render()),
+                            )
+                        }
+                        Err(kind) => {
+                            // A late reply would be read as the answer to the
+                            // next command, so start over on a fresh connection
+                            connection = None;
+                            RequestResult::error(start.elapsed().as_micros() as u64, kind)
+                        }
+                    };
+
+                    if !results.push(result).await {
+                        break;
+                    }
+                }
+                results.flush().await;
+            });
+            worker_handles.push(handle);
+        }
+
+        drop(result_tx);
+
+        let cancel_token = self.cancel_token.clone();
+
+        tokio::select! {
+            _ = self.control.wait(total_duration) => {
+                tracing::info!("Duration elapsed, stopping Redis workers");
+                cancel_token.cancel();
+            }
+            _ = cancel_token.cancelled() => {
+                tracing::info!("Cancellation requested");
+            }
+        }
+
+        for handle in worker_handles {
+            let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+        }
+
+        let stats = aggregator_handle
+            .await
+            .map_err(|e| format!("Aggregator task failed: {}", e))?;
+
+        let final_state = if self.cancel_token.is_cancelled() {
+            RunState::Cancelled
+        } else {
+            RunState::Completed
+        };
+        let _ = self.state_tx.send(final_state);
+
+        Ok(stats)
+    }
+
+    async fn run_websocket_mode(mut self) -> Result<Stats, String> {
+        // Message-rate stages ramp each connection's send rate instead of a fixed interval
+        let use_message_rate_stages = self
+            .config
+            .stages
+            .iter()
+            .any(|s| s.target_message_rate.is_some());
+        let (message_rate, total_duration) = if use_message_rate_stages {
+            let (rate_scheduler, stage_info_rx) =
+                MessageRateScheduler::new(self.config.stages.clone());
+            let rate = rate_scheduler.current_rate();
+            let duration = rate_scheduler.total_duration();
+            self.stage_info_rx = Some(stage_info_rx);
+            tokio::spawn(rate_scheduler.run());
+            (Some(rate), self.config.warmup + duration)
+        } else {
+            (None, self.config.warmup + self.config.duration)
+        };
+        let connection_count = self.config.concurrency;
+        let message = self
+            .config
+            .body
+            .clone()
+            .unwrap_or_else(|| "ping".to_string());
+
+        let (result_tx, result_rx) = mpsc::channel::<WsMessageResult>(RESULT_CHANNEL_SIZE);
+
+        let _ = self.state_tx.send(RunState::Running);
+
+        // Create WebSocket aggregator
+        let aggregator = WsAggregator::new(
+            total_duration,
+            result_rx,
+            self.snapshot_tx.clone(),
+            self.config.warmup,
+            self.phase_tx.clone(),
+            self.cancel_token.clone(),
+            connection_count,
+        );
+        let aggregator_handle = tokio::spawn(aggregator.run());
+
+        // Spawn WebSocket workers
+        let mut worker_handles = Vec::with_capacity(connection_count as usize);
+        if self.config.ws_mode == WsMode::Broadcast {
+            let target = BroadcastTarget {
+                url: self.config.url.clone(),
+                headers: self.config.headers.clone(),
+                subprotocols: self.config.ws_subprotocols.clone(),
+                compression: self.config.ws_compression,
+                timeout: self.config.timeout,
+            };
+            let message = self
+                .config
+                .body
+                .clone()
+                .unwrap_or_else(|| BROADCAST_TAG.to_string());
+            let subscribers_connected = Arc::new(AtomicU32::new(0));
+            let publishers = self.config.ws_publishers.min(connection_count);
+
+            for id in publishers..connection_count {
+                let subscriber = BroadcastSubscriber::new(
+                    id,
+                    target.clone(),
+                    subscribers_connected.clone(),
+                    result_tx.clone(),
+                    self.cancel_token.clone(),
+                );
+                worker_handles.push(tokio::spawn(subscriber.run()));
+            }
+            for id in 0..publishers {
+                let publisher = BroadcastPublisher::new(
+                    id,
+                    target.clone(),
+                    message.clone(),
+                    self.config.ws_message_interval,
+                    message_rate.clone(),
+                    subscribers_connected.clone(),
+                    result_tx.clone(),
+                    self.cancel_token.clone(),
+                );
+                worker_handles.push(tokio::spawn(publisher.run()));
+            }
+        } else {
+            for id in 0..connection_count {
+                let worker = WsWorker::new(
+                    id,
+                    self.config.url.clone(),
+                    self.config.headers.clone(),
+                    self.config.ws_subprotocols.clone(),
+                    self.config.ws_compression,
+                    message.clone(),
+                    self.config.ws_mode,
+                    self.config.ws_message_interval,
+                    message_rate.clone(),
+                    self.config.timeout,
+                    self.config.ws_reconnect_every,
+                    result_tx.clone(),
+                    self.cancel_token.clone(),
+                );
+                worker_handles.push(tokio::spawn(worker.run()));
+            }
+        }
+
+        drop(result_tx);
+
+        let cancel_token = self.cancel_token.clone();
+
+        // Wait for total duration
+        tokio::select! {
+            _ = self.control.wait(total_duration) => {
+                tracing::info!("Duration elapsed, stopping WebSocket workers");
+                cancel_token.cancel();
+            }
+            _ = cancel_token.cancelled() => {
+                tracing::info!("Cancellation requested");
+            }
+        }
+
+        // Wait for workers to finish (with timeout)
+        for handle in worker_handles {
+            let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+        }
+
+        // Wait for aggregator to finish
+        let _ws_stats = aggregator_handle
+            .await
+            .map_err(|e| format!("Aggregator task failed: {}", e))?;
+
+        let final_state = if self.cancel_token.is_cancelled() {
+            RunState::Cancelled
+        } else {
+            RunState::Completed
+        };
+        let _ = self.state_tx.send(final_state);
+
+        // Return empty HTTP Stats (WS stats are in snapshot)
+        Ok(Stats::new(total_duration))
+    }
+
+    #[cfg(feature = "socketio")]
+    async fn run_socketio_mode(self) -> Result<Stats, String> {
+        let total_duration = self.config.warmup + self.config.duration;
+        let connection_count = self.config.concurrency;
+        let url = crate::socketio::socketio_url(&self.config.url)?;
+        let event = self
+            .config
+            .socketio_event
+            .clone()
+            .ok_or("Socket.IO event not specified")?;
+        let message = self
+            .config
+            .body
+            .clone()
+            .unwrap_or_else(|| "ping".to_string());
+
+        let (result_tx, result_rx) = mpsc::channel::<WsMessageResult>(RESULT_CHANNEL_SIZE);
+
+        let _ = self.state_tx.send(RunState::Running);
+
+        // Socket.IO results share the WebSocket stat family
+        let aggregator = WsAggregator::new(
+            total_duration,
+            result_rx,
+            self.snapshot_tx.clone(),
+            self.config.warmup,
+            self.phase_tx.clone(),
+            self.cancel_token.clone(),
+            connection_count,
+        );
+        let aggregator_handle = tokio::spawn(aggregator.run());
+
+        let mut worker_handles = Vec::with_capacity(connection_count as usize);
+        for id in 0..connection_count {
+            let worker = SocketIoWorker::new(
+                id,
+                url.clone(),
+                self.config.socketio_namespace.clone(),
+                event.clone(),
+                self.config.headers.clone(),
+                message.clone(),
+                self.config.ws_mode,
+                self.config.ws_message_interval,
+                self.config.timeout,
+                result_tx.clone(),
+                self.cancel_token.clone(),
+            );
+            worker_handles.push(tokio::spawn(worker.run()));
+        }
+
+        drop(result_tx);
+
+        let cancel_token = self.cancel_token.clone();
+
+        tokio::select! {
+            _ = self.control.wait(total_duration) => {
+                tracing::info!("Duration elapsed, stopping Socket.IO workers");
+                cancel_token.cancel();
+            }
+            _ = cancel_token.cancelled() => {
+                tracing::info!("Cancellation requested");
+            }
+        }
+
+        for handle in worker_handles {
+            let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+        }
+
+        let _ws_stats = aggregator_handle
+            .await
+            .map_err(|e| format!("Aggregator task failed: {}", e))?;
+
+        let final_state = if self.cancel_token.is_cancelled() {
+            RunState::Cancelled
+        } else {
+            RunState::Completed
+        };
+        let _ = self.state_tx.send(final_state);
+
+        Ok(Stats::new(total_duration))
+    }
+
+    async fn run_raw_mode(self) -> Result<Stats, String> {
+        let total_duration = self.config.warmup + self.config.duration;
+        let connection_count = self.config.concurrency;
+        let target = crate::raw::parse_raw_url(&self.config.url)?;
+        let payload = self
+            .config
+            .body
+            .clone()
+            .unwrap_or_else(|| "ping\n".to_string());
+
+        let (result_tx, result_rx) = mpsc::channel::<WsMessageResult>(RESULT_CHANNEL_SIZE);
+
+        let _ = self.state_tx.send(RunState::Running);
+
+        // Raw results share the WebSocket stat family: connect time and
+        // send-to-response round trips
+        let aggregator = WsAggregator::new(
+            total_duration,
+            result_rx,
+            self.snapshot_tx.clone(),
+            self.config.warmup,
+            self.phase_tx.clone(),
+            self.cancel_token.clone(),
+            connection_count,
+        );
+        let aggregator_handle = tokio::spawn(aggregator.run());
+
+        let mut worker_handles = Vec::with_capacity(connection_count as usize);
+        for id in 0..connection_count {
+            let worker = RawWorker::new(
+                id,
+                target.clone(),
+                payload.clone(),
+                self.config.raw_expect.clone(),
+                self.config.ws_message_interval,
+                self.config.timeout,
+                self.config.socket.clone(),
+                self.config.disable_keepalive,
+                result_tx.clone(),
+                self.cancel_token.clone(),
+            );
+            worker_handles.push(tokio::spawn(worker.run()));
+        }
+
+        drop(result_tx);
+
+        let cancel_token = self.cancel_token.clone();
+
+        tokio::select! {
+            _ = self.control.wait(total_duration) => {
+                tracing::info!("Duration elapsed, stopping raw workers");
+                cancel_token.cancel();
+            }
+            _ = cancel_token.cancelled() => {
+                tracing::info!("Cancellation requested");
+            }
+        }
+
+        for handle in worker_handles {
+            let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+        }
+
+        let _ws_stats = aggregator_handle
+            .await
+            .map_err(|e| format!("Aggregator task failed: {}", e))?;
+
+        let final_state = if self.cancel_token.is_cancelled() {
+            RunState::Cancelled
+        } else {
+            RunState::Completed
+        };
+        let _ = self.state_tx.send(final_state);
+
+        Ok(Stats::new(total_duration))
+    }
+
+    #[cfg(feature = "mqtt")]
+    async fn run_mqtt_mode(self) -> Result<Stats, String> {
+        let total_duration = self.config.warmup + self.config.duration;
+        let connection_count = self.config.concurrency;
+        let subscribers = self.config.mqtt_subscribers.min(connection_count);
+        let publishers = connection_count - subscribers;
+        let target = MqttTarget {
+            broker: crate::mqtt::parse_broker_url(&self.config.url)?,
+            topic: self.config.mqtt_topic.clone(),
+            qos: self.config.mqtt_qos,
+            timeout: self.config.timeout,
+            socket: self.config.socket.clone(),
+        };
+        let message = self
+            .config
+            .body
+            .clone()
+            .unwrap_or_else(|| BROADCAST_TAG.to_string());
+        let message_interval = self
+            .config
+            .mqtt_publish_rate
+            .and_then(crate::engine::scheduler::message_rate_interval)
+            .unwrap_or(self.config.ws_message_interval);
+
+        let (result_tx, result_rx) = mpsc::channel::<WsMessageResult>(RESULT_CHANNEL_SIZE);
+
+        let _ = self.state_tx.send(RunState::Running);
+
+        // MQTT results share the WebSocket stat family: publish latency as
+        // message latency, subscriber deliveries as broadcast fan-out
+        let aggregator = WsAggregator::new(
+            total_duration,
+            result_rx,
+            self.snapshot_tx.clone(),
+            self.config.warmup,
+            self.phase_tx.clone(),
+            self.cancel_token.clone(),
+            connection_count,
+        );
+        let aggregator_handle = tokio::spawn(aggregator.run());
+
+        let subscribers_connected = Arc::new(AtomicU32::new(0));
+        let mut worker_handles = Vec::with_capacity(connection_count as usize);
+        for id in publishers..connection_count {
+            let subscriber = MqttSubscriber::new(
+                id,
+                target.clone(),
+                subscribers_connected.clone(),
+                result_tx.clone(),
+                self.cancel_token.clone(),
+            );
+            worker_handles.push(tokio::spawn(subscriber.run()));
+        }
+        for id in 0..publishers {
+            let publisher = MqttPublisher::new(
+                id,
+                target.clone(),
+                message.clone(),
+                message_interval,
+                subscribers_connected.clone(),
+                result_tx.clone(),
+                self.cancel_token.clone(),
+            );
+            worker_handles.push(tokio::spawn(publisher.run()));
+        }
+
+        drop(result_tx);
+
+        let cancel_token = self.cancel_token.clone();
+
+        tokio::select! {
+            _ = self.control.wait(total_duration) => {
+                tracing::info!("Duration elapsed, stopping MQTT workers");
+                cancel_token.cancel();
+            }
+            _ = cancel_token.cancelled() => {
+                tracing::info!("Cancellation requested");
+            }
+        }
+
+        for handle in worker_handles {
+            let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+        }
+
+        let _ws_stats = aggregator_handle
+            .await
+            .map_err(|e| format!("Aggregator task failed: {}", e))?;
+
+        let final_state = if self.cancel_token.is_cancelled() {
+            RunState::Cancelled
+        } else {
+            RunState::Completed
+        };
+        let _ = self.state_tx.send(final_state);
+
+        Ok(Stats::new(total_duration))
+    }
+}
+
+/// Number of VUs to dedicate to WebSocket scenarios in a mixed HTTP + WS run
+fn mixed_ws_vus(http_scenarios: &[Scenario], ws_scenarios: &[Scenario], max_workers: u32) -> u32 {
+    if ws_scenarios.is_empty() {
+        return 0;
+    }
+    if http_scenarios.is_empty() {
+        return max_workers;
+    }
+
+    let ws_weight: u64 = ws_scenarios.iter().map(|s| s.weight as u64).sum();
+    let total_weight: u64 = ws_weight + http_scenarios.iter().map(|s| s.weight as u64).sum::<u64>();
+    let share = (max_workers as u64 * ws_weight / total_weight.max(1)) as u32;
+
+    // Keep at least one VU on each side
+    share.clamp(1, max_workers.saturating_sub(1).max(1))
+}
+
+/// VUs for each scenario in proportion to its weight, by largest
+/// remainder, so a scenario only goes without when there are fewer VUs
+/// than scenarios. Without any weights, all go to the first.
+fn split_by_weight(scenarios: &[Scenario], vus: u32) -> Vec<u32> {
+    let total_weight: u64 = scenarios.iter().map(|s| s.weight as u64).sum();
+    let mut split = vec![0; scenarios.len()];
+    if total_weight == 0 {
+        if let Some(first) = split.first_mut() {
+            *first = vus;
+        }
+        return split;
+    }
+
+    let mut remainders = Vec::with_capacity(scenarios.len());
+    for (idx, scenario) in scenarios.iter().enumerate() {
+        let exact = vus as u64 * scenario.weight as u64;
+        split[idx] = (exact / total_weight) as u32;
+        remainders.push((exact % total_weight, idx));
+    }
+    // Hand the VUs rounding left over to the largest remainders, earlier
+    // scenarios first on ties
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    let left = vus - split.iter().sum::<u32>();
+    for &(_, idx) in remainders.iter().take(left as usize) {
+        split[idx] += 1;
+    }
+    split
+}
+
+/// Deterministic weighted pick (same scheme as Worker::select_scenario)
+#[cfg(feature = "http3")]
+fn select_weighted(scenarios: &[Scenario], total_weight: u32, counter: u64) -> &Scenario {
+    let roll = (counter % total_weight.max(1) as u64) as u32;
+    let mut cumulative = 0u32;
+
+    for scenario in scenarios {
+        cumulative += scenario.weight;
+        if roll < cumulative {
+            return scenario;
+        }
+    }
+
+    &scenarios[0]
+}
+
+/// Write out buffered slow log entries once the run is over
+fn flush_slow_log(slow_log: Option<&SlowLog>) {
+    if let Some(slow_log) = slow_log
+        && let Err(e) = slow_log.flush()
+    {
+        tracing::warn!("Failed to write slow log: {}", e);
+    }
+}
+
+async fn run_fail_fast_checker(
+    thresholds: Vec<Threshold>,
+    snapshot_rx: watch::Receiver<StatsSnapshot>,
+    cancel_token: CancellationToken,
+    threshold_failed: Arc<AtomicBool>,
+) {
+    // Wait a bit before starting checks (need some data first)
+    sleep(Duration::from_secs(2)).await;
+
+    loop {
+        tokio::select! {
+            _ = sleep(Duration::from_secs(1)) => {
+                let snapshot = snapshot_rx.borrow().clone();
+
+                // Only check if we have some requests
+                if snapshot.total_requests == 0 {
+                    continue;
+                }
+
+                let results = evaluate_thresholds(&thresholds, &snapshot);
+                let any_failed = results.iter().any(|r| !r.passed);
+
+                if any_failed {
+                    eprintln!("\n\x1b[31m⚠ FAIL-FAST: Threshold breached, aborting test\x1b[0m");
+                    for result in &results {
+                        if !result.passed {
+                            eprintln!("  \x1b[31m✗ {} (actual: {:.2})\x1b[0m", result.condition, result.actual);
+                        }
+                    }
+                    threshold_failed.store(true, Ordering::Relaxed);
+                    cancel_token.cancel();
+                    break;
+                }
+            }
+            _ = cancel_token.cancelled() => {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario(name: &str, url: &str, weight: u32) -> Scenario {
+        Scenario {
+            name: name.to_string(),
+            url: url.to_string(),
+            method: reqwest::Method::GET,
+            headers: Vec::new(),
+            body: None,
+            weight,
+            extractions: Vec::new(),
+            depends_on: None,
+            tags: HashMap::new(),
+            proxy: None,
+            think_time: None,
+        }
+    }
+
+    #[test]
+    fn splits_websocket_vus_by_weight() {
+        let ws = [
+            scenario("chat", "ws://localhost/chat", 70),
+            scenario("feed", "ws://localhost/feed", 30),
+        ];
+        // Fewer VUs than the weights add up to still run both scenarios
+        assert_eq!(split_by_weight(&ws, 10), vec![7, 3]);
+        assert_eq!(split_by_weight(&ws, 3), vec![2, 1]);
+        assert_eq!(split_by_weight(&ws, 2), vec![1, 1]);
+        assert_eq!(split_by_weight(&ws, 1), vec![1, 0]);
+
+        let even = [
+            scenario("a", "ws://localhost/a", 1),
+            scenario("b", "ws://localhost/b", 1),
+            scenario("c", "ws://localhost/c", 1),
+        ];
+        assert_eq!(split_by_weight(&even, 4), vec![2, 1, 1]);
+        assert_eq!(split_by_weight(&even, 5).iter().sum::<u32>(), 5);
+
+        let unweighted = [
+            scenario("a", "ws://localhost/a", 0),
+            scenario("b", "ws://localhost/b", 0),
+        ];
+        assert_eq!(split_by_weight(&unweighted, 4), vec![4, 0]);
+    }
+
+    #[test]
+    fn keeps_a_vu_on_each_side_of_a_mixed_run() {
+        let http = [scenario("api", "http://localhost/", 90)];
+        let ws = [scenario("chat", "ws://localhost/chat", 10)];
+        assert_eq!(mixed_ws_vus(&http, &ws, 2), 1);
+        assert_eq!(mixed_ws_vus(&http, &ws, 100), 10);
+        assert_eq!(mixed_ws_vus(&[], &ws, 5), 5);
+        assert_eq!(mixed_ws_vus(&http, &[], 5), 0);
+    }
+}
@@ -0,0 +1,68 @@
+use std::future::Future;
+use tokio::runtime::{Builder, Runtime};
+use tokio::task::JoinHandle;
+
+/// Independent tokio runtimes HTTP workers are spread across (`--threads`).
+///
+/// A single work-stealing runtime tops out well before the NIC on big
+/// machines; each runtime here has one worker thread pinned to its own core,
+/// so workers and their connection pool stay on that core.
+pub struct WorkerRuntimes {
+    runtimes: Vec<Runtime>,
+}
+
+impl WorkerRuntimes {
+    /// `threads` runtimes (0 = one per core), never more than `max_workers`
+    pub fn new(threads: u32, max_workers: u32) -> Result<Self, String> {
+        let core_ids = core_affinity::get_core_ids().unwrap_or_default();
+        let threads = match threads {
+            0 => std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            n => n as usize,
+        };
+        let count = threads.min(max_workers as usize).max(1);
+
+        let runtimes = (0..count)
+            .map(|idx| {
+                let core = (!core_ids.is_empty()).then(|| core_ids[idx % core_ids.len()]);
+                Builder::new_multi_thread()
+                    .worker_threads(1)
+                    .thread_name(format!("kaioken-rt-{}", idx))
+                    .on_thread_start(move || {
+                        if let Some(core) = core
+                            && !core_affinity::set_for_current(core)
+                        {
+                            tracing::debug!("Failed to pin runtime {} to core {}", idx, core.id);
+                        }
+                    })
+                    .enable_all()
+                    .build()
+                    .map_err(|e| format!("Failed to start worker runtime: {}", e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        tracing::info!("Running HTTP workers on {} runtimes", runtimes.len());
+        Ok(Self { runtimes })
+    }
+
+    pub fn count(&self) -> usize {
+        self.runtimes.len()
+    }
+
+    /// Spawn a task on runtime `idx` (wrapping around)
+    pub fn spawn<F>(&self, idx: usize, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.runtimes[idx % self.runtimes.len()].spawn(future)
+    }
+
+    /// Stop the runtimes without blocking; any task still running is dropped
+    pub fn shutdown(self) {
+        for runtime in self.runtimes {
+            runtime.shutdown_background();
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::types::{Scenario, ScenarioShare};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Requests each weighted scenario got. Scenarios are picked by rotating
+/// through the weights per VU (per iteration in arrival-rate modes), so a
+/// long run lands on the configured mix but a short one, or one where a
+/// scenario's requests fail fast, may not; reporting the mix it achieved
+/// shows whether the results describe the traffic that was intended.
+#[derive(Debug)]
+pub struct ScenarioMix {
+    slots: Vec<Slot>,
+    total_weight: u32,
+}
+
+#[derive(Debug)]
+struct Slot {
+    scenario: String,
+    weight: u32,
+    requests: AtomicU64,
+}
+
+impl ScenarioMix {
+    /// None unless requests are split across two or more scenarios
+    pub fn for_scenarios(scenarios: &[Scenario]) -> Option<Self> {
+        if scenarios.iter().filter(|s| s.weight > 0).count() < 2 {
+            return None;
+        }
+        let total_weight: u32 = scenarios.iter().map(|s| s.weight).sum();
+        let slots = scenarios
+            .iter()
+            .map(|scenario| Slot {
+                scenario: scenario.name.clone(),
+                weight: scenario.weight,
+                requests: AtomicU64::new(0),
+            })
+            .collect();
+        Some(Self {
+            slots,
+            total_weight,
+        })
+    }
+
+    /// Count a request sent for scenario `index`
+    pub fn record(&self, index: usize) {
+        if let Some(slot) = self.slots.get(index) {
+            slot.requests.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Every weighted scenario's share, empty until a request was counted;
+    /// `weight = 0` scenarios only run as dependencies
+    pub fn stats(&self) -> Vec<ScenarioShare> {
+        let weighted = || self.slots.iter().filter(|slot| slot.weight > 0);
+        let total: u64 = weighted()
+            .map(|slot| slot.requests.load(Ordering::Relaxed))
+            .sum();
+        if total == 0 {
+            return Vec::new();
+        }
+        weighted()
+            .map(|slot| {
+                let requests = slot.requests.load(Ordering::Relaxed);
+                ScenarioShare {
+                    scenario: slot.scenario.clone(),
+                    weight: slot.weight,
+                    requests,
+                    expected: slot.weight as f64 / self.total_weight as f64,
+                    actual: requests as f64 / total as f64,
+                }
+            })
+            .collect()
+    }
+
+    /// Forget warmup requests
+    pub fn reset(&self) {
+        for slot in &self.slots {
+            slot.requests.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn scenario(name: &str, weight: u32) -> Scenario {
+        Scenario {
+            name: name.to_string(),
+            url: "http://localhost/".to_string(),
+            method: reqwest::Method::GET,
+            headers: Vec::new(),
+            body: None,
+            weight,
+            extractions: Vec::new(),
+            depends_on: None,
+            tags: HashMap::new(),
+            proxy: None,
+            think_time: None,
+        }
+    }
+
+    #[test]
+    fn compares_requests_per_scenario_with_the_weights() {
+        assert!(ScenarioMix::for_scenarios(&[scenario("home", 1)]).is_none());
+        assert!(ScenarioMix::for_scenarios(&[scenario("a", 1), scenario("b", 0)]).is_none());
+
+        let mix = ScenarioMix::for_scenarios(&[
+            scenario("login", 0),
+            scenario("browse", 3),
+            scenario("buy", 1),
+        ])
+        .unwrap();
+        assert!(mix.stats().is_empty());
+        for _ in 0..60 {
+            mix.record(1);
+        }
+        for _ in 0..40 {
+            mix.record(2);
+        }
+        // Dependency requests don't count against the weighted shares
+        for _ in 0..25 {
+            mix.record(0);
+        }
+
+        // The dependency-only scenario has no share to keep
+        let stats = mix.stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].scenario, "browse");
+        assert_eq!(stats[0].requests, 60);
+        assert_eq!(stats[0].expected, 0.75);
+        assert_eq!(stats[0].actual, 0.6);
+        assert!((stats[0].drift_pct() + 20.0).abs() < 1e-9);
+        assert_eq!(stats[1].weight, 1);
+        assert!((stats[1].drift_pct() - 60.0).abs() < 1e-9);
+        assert_eq!(stats.iter().map(|s| s.actual).sum::<f64>(), 1.0);
+
+        mix.reset();
+        assert!(mix.stats().is_empty());
+    }
+}
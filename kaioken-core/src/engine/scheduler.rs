@@ -0,0 +1,683 @@
+use crate::engine::control::RunControl;
+use crate::types::Stage;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, Semaphore, watch};
+use tokio::time::sleep;
+
+/// Token bucket behind `--rate`: refills at `rate` tokens per second and holds
+/// up to `burst` of them, so idle VUs can catch up in a bounded burst
+pub struct RateLimiter {
+    // Adjustable mid-run with `kaioken ctl set-rate`
+    rate: AtomicU32,
+    // None = one second of tokens at the current rate
+    burst: Option<u32>,
+    tokens: AtomicU64,
+    refill_notify: Notify,
+}
+
+impl RateLimiter {
+    pub fn new(rate: u32, burst: Option<u32>) -> Arc<Self> {
+        Arc::new(Self {
+            rate: AtomicU32::new(rate),
+            burst,
+            // Start full, like a client that has been idle
+            tokens: AtomicU64::new(burst.unwrap_or(rate) as u64),
+            refill_notify: Notify::new(),
+        })
+    }
+
+    fn capacity(&self, rate: u32) -> u64 {
+        self.burst.unwrap_or(rate) as u64
+    }
+
+    pub fn set_rate(&self, rate: u32) {
+        self.rate.store(rate.max(1), Ordering::Relaxed);
+    }
+
+    pub async fn acquire(&self) {
+        loop {
+            let current = self.tokens.load(Ordering::Relaxed);
+            if current > 0 {
+                if self
+                    .tokens
+                    .compare_exchange(current, current - 1, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    // Pass the wakeup on while tokens are left for other waiters
+                    if current > 1 {
+                        self.refill_notify.notify_one();
+                    }
+                    return;
+                }
+            } else {
+                self.refill_notify.notified().await;
+            }
+        }
+    }
+
+    pub async fn run_refiller(self: Arc<Self>) {
+        // Measured on tokio's clock, the one `sleep` runs on
+        let mut last = tokio::time::Instant::now();
+        let mut earned = 0.0;
+        loop {
+            let rate = self.rate.load(Ordering::Relaxed);
+            // Timers don't fire faster than 1ms, so high rates add several tokens a tick
+            let interval =
+                Duration::from_micros(1_000_000 / rate as u64).max(Duration::from_millis(1));
+            sleep(interval).await;
+
+            let now = tokio::time::Instant::now();
+            earned += now.duration_since(last).as_secs_f64() * rate as f64;
+            last = now;
+            let whole = earned.floor();
+            earned -= whole;
+
+            let capacity = self.capacity(rate);
+            let refilled =
+                self.tokens
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                        (current < capacity).then(|| (current + whole as u64).min(capacity))
+                    });
+            if refilled.is_ok() && whole > 0.0 {
+                self.refill_notify.notify_one();
+            }
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub struct RampUpScheduler {
+    concurrency: u32,
+    ramp_duration: Duration,
+    active_permits: Arc<Semaphore>,
+    start_time: Instant,
+}
+
+impl RampUpScheduler {
+    pub fn new(concurrency: u32, ramp_duration: Duration) -> Self {
+        let initial = if ramp_duration.is_zero() {
+            concurrency as usize
+        } else {
+            1
+        };
+
+        Self {
+            concurrency,
+            ramp_duration,
+            active_permits: Arc::new(Semaphore::new(initial)),
+            start_time: Instant::now(),
+        }
+    }
+
+    pub fn permits(&self) -> Arc<Semaphore> {
+        self.active_permits.clone()
+    }
+
+    pub async fn run(self) {
+        if self.ramp_duration.is_zero() {
+            return;
+        }
+
+        let interval = self.ramp_duration.as_micros() as u64 / self.concurrency as u64;
+        let interval = Duration::from_micros(interval.max(1));
+
+        let mut activated = 1u32;
+
+        while activated < self.concurrency {
+            sleep(interval).await;
+            activated += 1;
+            self.active_permits.add_permits(1);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn current_active(&self) -> u32 {
+        if self.ramp_duration.is_zero() {
+            return self.concurrency;
+        }
+
+        let elapsed = self.start_time.elapsed();
+        if elapsed >= self.ramp_duration {
+            self.concurrency
+        } else {
+            let progress = elapsed.as_secs_f64() / self.ramp_duration.as_secs_f64();
+            ((self.concurrency as f64 * progress) as u32).max(1)
+        }
+    }
+}
+
+/// The stage a scheduler is in, for tagging results and display
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct StageInfo {
+    pub stage_index: usize,
+    pub stage_count: usize,
+    pub target: u32,
+    pub current: u32,
+    pub stage_elapsed: Duration,
+    pub stage_duration: Duration,
+}
+
+#[allow(dead_code)]
+pub struct StagesScheduler {
+    stages: Vec<Stage>,
+    active_permits: Arc<Semaphore>,
+    current_target: Arc<AtomicU32>,
+    stage_info_tx: watch::Sender<StageInfo>,
+    start_time: Instant,
+    reloads: Option<watch::Receiver<Vec<Stage>>>,
+}
+
+impl StagesScheduler {
+    pub fn new(stages: Vec<Stage>, max_concurrency: u32) -> (Self, watch::Receiver<StageInfo>) {
+        let initial_target = stages
+            .first()
+            .and_then(|s| s.target)
+            .unwrap_or(max_concurrency);
+        let initial_permits = initial_target.min(1) as usize; // Start with at least 1
+
+        let (stage_info_tx, stage_info_rx) = watch::channel(StageInfo {
+            stage_index: 0,
+            stage_count: stages.len(),
+            target: initial_target,
+            current: initial_permits as u32,
+            stage_elapsed: Duration::ZERO,
+            stage_duration: stages.first().map(|s| s.duration).unwrap_or(Duration::ZERO),
+        });
+
+        (
+            Self {
+                stages,
+                active_permits: Arc::new(Semaphore::new(initial_permits)),
+                current_target: Arc::new(AtomicU32::new(initial_target)),
+                stage_info_tx,
+                start_time: Instant::now(),
+                reloads: None,
+            },
+            stage_info_rx,
+        )
+    }
+
+    /// Follow stages reloaded through `control`
+    pub fn with_control(mut self, control: &RunControl) -> Self {
+        self.reloads = Some(control.subscribe_stages());
+        self
+    }
+
+    pub fn permits(&self) -> Arc<Semaphore> {
+        self.active_permits.clone()
+    }
+
+    #[allow(dead_code)]
+    pub fn current_target(&self) -> u32 {
+        self.current_target.load(Ordering::Relaxed)
+    }
+
+    /// Calculate total duration of all stages
+    pub fn total_duration(&self) -> Duration {
+        self.stages.iter().map(|s| s.duration).sum()
+    }
+
+    pub async fn run(mut self) {
+        if self.stages.is_empty() {
+            return;
+        }
+
+        let mut current_workers: u32 = self
+            .stages
+            .first()
+            .and_then(|s| s.target)
+            .map(|t| t.min(1))
+            .unwrap_or(1);
+
+        // Stages are placed by time since the start, so reloaded ones pick
+        // up where the run is
+        'stages: loop {
+            let Some((stage_idx, into)) = stage_at(&self.stages, self.start_time.elapsed()) else {
+                // Stages added after the last one ended still run
+                match next_stages(&mut self.reloads).await {
+                    Some(stages) => {
+                        self.stages = stages;
+                        continue;
+                    }
+                    None => break,
+                }
+            };
+            let stage = self.stages[stage_idx].clone();
+            let stage_start = Instant::now() - into;
+            let stage_end = stage_start + stage.duration;
+
+            // Stages without a VU target hold the current VUs
+            let target = stage.target.unwrap_or(current_workers);
+            self.current_target.store(target, Ordering::Relaxed);
+
+            // Calculate ramp rate: how often to add/remove a worker
+            let workers_diff = (target as i64 - current_workers as i64).unsigned_abs() as u32;
+            let remaining = stage.duration - into;
+            let ramp_interval = if workers_diff > 0 && !remaining.is_zero() {
+                remaining / workers_diff
+            } else {
+                Duration::from_millis(100) // Default tick for status updates
+            };
+
+            while Instant::now() < stage_end {
+                // Update stage info
+                let _ = self.stage_info_tx.send(StageInfo {
+                    stage_index: stage_idx,
+                    stage_count: self.stages.len(),
+                    target,
+                    current: current_workers,
+                    stage_elapsed: stage_start.elapsed(),
+                    stage_duration: stage.duration,
+                });
+
+                // Adjust workers toward target
+                if current_workers < target {
+                    self.active_permits.add_permits(1);
+                    current_workers += 1;
+                } else if current_workers > target && current_workers > 0 {
+                    // To reduce workers, we'd need to signal workers to stop
+                    // For simplicity, we just track the target - workers will naturally
+                    // complete and not be replaced
+                    current_workers = target;
+                }
+
+                let sleep_time =
+                    ramp_interval.min(stage_end.saturating_duration_since(Instant::now()));
+                if sleep_time.is_zero() {
+                    break;
+                }
+                tokio::select! {
+                    _ = sleep(sleep_time) => {}
+                    Some(stages) = next_stages(&mut self.reloads) => {
+                        tracing::info!("Stages reloaded: {} stages", stages.len());
+                        self.stages = stages;
+                        continue 'stages;
+                    }
+                }
+            }
+
+            // The last stage has reached its target
+            if stage_idx == self.stages.len() - 1 {
+                let _ = self.stage_info_tx.send(StageInfo {
+                    stage_index: stage_idx,
+                    stage_count: self.stages.len(),
+                    target,
+                    current: target,
+                    stage_elapsed: stage.duration,
+                    stage_duration: stage.duration,
+                });
+            }
+        }
+    }
+}
+
+/// The stage `elapsed` falls in and how far into it, or `None` once all
+/// of them have run
+pub fn stage_at(stages: &[Stage], elapsed: Duration) -> Option<(usize, Duration)> {
+    let mut start = Duration::ZERO;
+    for (index, stage) in stages.iter().enumerate() {
+        if elapsed < start + stage.duration {
+            return Some((index, elapsed - start));
+        }
+        start += stage.duration;
+    }
+    None
+}
+
+/// The next stages from a config reload; `None` when there are no reloads
+/// to wait for
+pub(crate) async fn next_stages(
+    reloads: &mut Option<watch::Receiver<Vec<Stage>>>,
+) -> Option<Vec<Stage>> {
+    let rx = reloads.as_mut()?;
+    rx.changed().await.ok()?;
+    Some(rx.borrow_and_update().clone())
+}
+
+/// Ramps the per-connection WebSocket message rate across `target_message_rate`
+/// stages. Workers read the current rate before every send.
+pub struct MessageRateScheduler {
+    stages: Vec<Stage>,
+    current_rate: Arc<AtomicU32>,
+    stage_info_tx: watch::Sender<StageInfo>,
+}
+
+impl MessageRateScheduler {
+    pub fn new(stages: Vec<Stage>) -> (Self, watch::Receiver<StageInfo>) {
+        let (stage_info_tx, stage_info_rx) = watch::channel(StageInfo {
+            stage_index: 0,
+            stage_count: stages.len(),
+            target: stages
+                .first()
+                .and_then(|s| s.target_message_rate)
+                .unwrap_or(0),
+            current: 0,
+            stage_elapsed: Duration::ZERO,
+            stage_duration: stages.first().map(|s| s.duration).unwrap_or(Duration::ZERO),
+        });
+
+        (
+            Self {
+                stages,
+                current_rate: Arc::new(AtomicU32::new(0)),
+                stage_info_tx,
+            },
+            stage_info_rx,
+        )
+    }
+
+    pub fn current_rate(&self) -> Arc<AtomicU32> {
+        self.current_rate.clone()
+    }
+
+    /// Calculate total duration of all stages
+    pub fn total_duration(&self) -> Duration {
+        self.stages.iter().map(|s| s.duration).sum()
+    }
+
+    pub async fn run(self) {
+        let tick_interval = Duration::from_millis(100);
+        let mut prev_rate: u32 = 0;
+
+        for (stage_idx, stage) in self.stages.iter().enumerate() {
+            let target = match stage.target_message_rate {
+                Some(t) => t,
+                None => continue,
+            };
+            let stage_start = Instant::now();
+
+            tracing::info!(
+                "Stage {}: ramping {} -> {} msg/s per connection over {:?}",
+                stage_idx + 1,
+                prev_rate,
+                target,
+                stage.duration
+            );
+
+            loop {
+                let elapsed = stage_start.elapsed();
+                let progress = if stage.duration.is_zero() {
+                    1.0
+                } else {
+                    (elapsed.as_secs_f64() / stage.duration.as_secs_f64()).clamp(0.0, 1.0)
+                };
+                let rate = prev_rate as f64 + (target as f64 - prev_rate as f64) * progress;
+                let rate = rate.round() as u32;
+                self.current_rate.store(rate, Ordering::Relaxed);
+
+                let _ = self.stage_info_tx.send(StageInfo {
+                    stage_index: stage_idx,
+                    stage_count: self.stages.len(),
+                    target,
+                    current: rate,
+                    stage_elapsed: elapsed,
+                    stage_duration: stage.duration,
+                });
+
+                if elapsed >= stage.duration {
+                    break;
+                }
+                sleep(tick_interval.min(stage.duration - elapsed)).await;
+            }
+
+            prev_rate = target;
+        }
+    }
+}
+
+/// Interval between messages at `rate` messages/sec, or `None` when paused at zero.
+pub fn message_rate_interval(rate: u32) -> Option<Duration> {
+    if rate == 0 {
+        None
+    } else {
+        Some(Duration::from_secs(1) / rate)
+    }
+}
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Absolute-deadline pacing for an arrival rate.
+///
+/// Iteration `n` (counting from 1) is due at `start + n / rate`. Each wakeup
+/// releases every iteration that has come due since the last one, so coarse
+/// timer granularity delays a batch by at most one tick instead of letting
+/// the achieved rate drift below the target. A rate change starts a new
+/// segment at the time of the change and keeps the progress already made
+/// toward the next iteration, so a ramp changing the rate every few
+/// milliseconds paces as exactly as a constant rate. Iterations already
+/// released keep their deadlines.
+pub struct ArrivalSchedule {
+    origin: Instant,
+    start: Instant,
+    end: Instant,
+    rate: u128,
+    // Iterations due when the current segment started, in billionths
+    base: u128,
+    total: u64,
+    released: u64,
+}
+
+impl ArrivalSchedule {
+    pub fn new(start: Instant, rate: u32, duration: Duration) -> Self {
+        let mut schedule = Self {
+            origin: start,
+            start,
+            end: start + duration,
+            rate: rate as u128,
+            base: 0,
+            total: 0,
+            released: 0,
+        };
+        schedule.total = schedule.segment_total();
+        schedule
+    }
+
+    /// Iterations due by `at`, in billionths
+    fn progress(&self, at: Instant) -> u128 {
+        let elapsed = at.min(self.end).saturating_duration_since(self.start);
+        self.base + elapsed.as_nanos() * self.rate
+    }
+
+    fn segment_total(&self) -> u64 {
+        (self.progress(self.end) / NANOS_PER_SEC) as u64
+    }
+
+    fn segment_offset(&self, n: u64) -> Duration {
+        let remaining = (n as u128 * NANOS_PER_SEC).saturating_sub(self.base);
+        Duration::from_nanos(remaining.checked_div(self.rate).unwrap_or(0) as u64)
+    }
+
+    /// Offset of iteration `n` from the start of the schedule
+    pub fn offset(&self, n: u64) -> Duration {
+        (self.start - self.origin) + self.segment_offset(n)
+    }
+
+    /// When the next iteration is due, or `None` once all have been released
+    /// or while the rate is 0
+    pub fn next_deadline(&self) -> Option<Instant> {
+        (self.released < self.total && self.rate > 0)
+            .then(|| self.start + self.segment_offset(self.released + 1))
+    }
+
+    /// Iterations due by `now` that haven't been released yet
+    pub fn release(&mut self, now: Instant) -> std::ops::Range<u64> {
+        let due = ((self.progress(now) / NANOS_PER_SEC) as u64).min(self.total);
+        let first = self.released + 1;
+        self.released = self.released.max(due);
+        first..self.released + 1
+    }
+
+    /// Continue at `rate` from `now` until the end of the schedule
+    pub fn set_rate(&mut self, now: Instant, rate: u32) {
+        let now = now.max(self.start);
+        self.base = self.progress(now);
+        self.start = now;
+        self.rate = rate as u128;
+        self.total = self.segment_total();
+    }
+
+    /// Push the end of the schedule back by `by`
+    pub fn extend(&mut self, by: Duration) {
+        self.set_end(self.end + by);
+    }
+
+    /// End the schedule at `end` instead
+    pub fn set_end(&mut self, end: Instant) {
+        self.end = end;
+        self.total = self.segment_total();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limiter_allows_burst_then_refills_at_rate() {
+        let limiter = RateLimiter::new(20, Some(5));
+        tokio::spawn(limiter.clone().run_refiller());
+
+        // A full bucket lets the burst through at once
+        let start = tokio::time::Instant::now();
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // Then requests wait for tokens at 20/s, one every 50ms
+        for _ in 0..4 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::from_millis(200));
+    }
+
+    fn stage(millis: u64, target: u32) -> Stage {
+        Stage {
+            duration: Duration::from_millis(millis),
+            target: Some(target),
+            target_rate: None,
+            target_message_rate: None,
+        }
+    }
+
+    #[test]
+    fn stage_at_places_elapsed_time() {
+        let stages = [stage(100, 1), stage(200, 2)];
+        assert_eq!(stage_at(&stages, Duration::ZERO), Some((0, Duration::ZERO)));
+        assert_eq!(
+            stage_at(&stages, Duration::from_millis(150)),
+            Some((1, Duration::from_millis(50)))
+        );
+        assert_eq!(stage_at(&stages, Duration::from_millis(300)), None);
+    }
+
+    #[tokio::test]
+    async fn stages_scheduler_follows_reloads() {
+        let control = RunControl::new();
+        control.allow_stages(vec![stage(200, 2)], Some(5));
+        let (scheduler, _info) = StagesScheduler::new(vec![stage(200, 2)], 5);
+        let scheduler = scheduler.with_control(&control);
+        let permits = scheduler.permits();
+        tokio::spawn(scheduler.run());
+
+        sleep(Duration::from_millis(300)).await;
+        assert_eq!(permits.available_permits(), 2);
+
+        // More VUs than the run started with can't be added
+        assert!(control.reload_stages(vec![stage(200, 6)]).is_err());
+        control
+            .reload_stages(vec![stage(200, 2), stage(300, 5)])
+            .unwrap();
+        assert_eq!(control.plan().duration, Duration::from_millis(500));
+
+        // The added stage runs, though the first had already ended
+        sleep(Duration::from_millis(400)).await;
+        assert_eq!(permits.available_permits(), 5);
+    }
+
+    #[test]
+    fn arrival_schedule_releases_due_batches() {
+        let start = Instant::now();
+        let mut schedule = ArrivalSchedule::new(start, 30_000, Duration::from_secs(1));
+
+        assert_eq!(
+            schedule.next_deadline(),
+            Some(start + Duration::from_nanos(33_333))
+        );
+        assert!(schedule.release(start).is_empty());
+
+        // A 1ms timer tick releases the whole batch that came due
+        assert_eq!(schedule.release(start + Duration::from_millis(1)), 1..31);
+        // Oversleeping catches up without drifting
+        assert_eq!(schedule.release(start + Duration::from_millis(10)), 31..301);
+        assert!(
+            schedule
+                .release(start + Duration::from_millis(10))
+                .is_empty()
+        );
+
+        // Never releases past the end of the run
+        assert_eq!(
+            schedule.release(start + Duration::from_secs(5)),
+            301..30_001
+        );
+        assert_eq!(schedule.next_deadline(), None);
+        assert_eq!(schedule.offset(30_000), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn arrival_schedule_follows_rate_changes_and_extensions() {
+        let start = Instant::now();
+        let mut schedule = ArrivalSchedule::new(start, 100, Duration::from_secs(2));
+        assert_eq!(schedule.release(start + Duration::from_secs(1)), 1..101);
+
+        // Doubling the rate halfway through: 200 more over the last second
+        schedule.set_rate(start + Duration::from_secs(1), 200);
+        assert_eq!(
+            schedule.next_deadline(),
+            Some(start + Duration::from_secs(1) + Duration::from_millis(5))
+        );
+        assert_eq!(schedule.offset(101), Duration::from_millis(1005));
+        assert_eq!(
+            schedule.release(start + Duration::from_millis(1500)),
+            101..201
+        );
+
+        // Another second at 200/s
+        schedule.extend(Duration::from_secs(1));
+        assert_eq!(schedule.release(start + Duration::from_secs(5)), 201..501);
+        assert_eq!(schedule.next_deadline(), None);
+    }
+
+    #[test]
+    fn arrival_schedule_keeps_pace_through_frequent_rate_changes() {
+        // 50/s re-set every 10ms, as a ramp does: a new segment mustn't
+        // restart the wait for the next iteration
+        let start = Instant::now();
+        let mut schedule = ArrivalSchedule::new(start, 0, Duration::from_secs(2));
+        assert_eq!(schedule.next_deadline(), None);
+        let mut released = 0;
+        for tick in 0..100 {
+            let now = start + Duration::from_millis(10 * tick);
+            released += schedule.release(now).count();
+            schedule.set_rate(now, 50);
+        }
+        released += schedule.release(start + Duration::from_secs(1)).count();
+        assert_eq!(released, 50);
+
+        // Paused at 0, then ended early
+        schedule.set_rate(start + Duration::from_secs(1), 0);
+        assert!(
+            schedule
+                .release(start + Duration::from_millis(1500))
+                .is_empty()
+        );
+        schedule.set_rate(start + Duration::from_millis(1500), 100);
+        schedule.set_end(start + Duration::from_millis(1600));
+        assert_eq!(schedule.release(start + Duration::from_secs(2)), 51..61);
+        assert_eq!(schedule.next_deadline(), None);
+    }
+}
@@ -0,0 +1,317 @@
+use crate::engine::Stats;
+use crate::engine::batch::ResultBatch;
+use crate::engine::slow_log::SlowLog;
+use crate::types::RequestResult;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// Per-core stats shards. Workers record finished requests into the shard for
+/// the thread they run on; the aggregator folds every shard into its `Stats`
+/// on each tick. Each shard lock is only ever shared with that tick, so the
+/// hot path is an uncontended lock instead of a channel send per request.
+pub struct StatsShards {
+    shards: Box<[Mutex<Stats>]>,
+    max_requests: u64,
+    measuring: AtomicBool,
+    recorded: AtomicU64,
+    // Stage results are tagged with as they are recorded (NO_STAGE = none)
+    stage: AtomicUsize,
+    cancel_token: CancellationToken,
+    slow_log: Option<Arc<SlowLog>>,
+}
+
+const NO_STAGE: usize = usize::MAX;
+
+static NEXT_THREAD_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_SHARD: Cell<usize> = Cell::new(NEXT_THREAD_SHARD.fetch_add(1, Ordering::Relaxed));
+}
+
+impl StatsShards {
+    /// One shard per available core. `max_requests` (0 = unlimited) is
+    /// enforced here so the run stops on the exact request, not the next tick.
+    pub fn new(max_requests: u64, cancel_token: CancellationToken) -> Self {
+        let count = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        Self {
+            shards: (0..count).map(|_| Mutex::new(Stats::shard())).collect(),
+            max_requests,
+            measuring: AtomicBool::new(false),
+            recorded: AtomicU64::new(0),
+            stage: AtomicUsize::new(NO_STAGE),
+            cancel_token,
+            slow_log: None,
+        }
+    }
+
+    /// Keep up to `limit` example failures per kind in each shard
+    pub fn with_failure_samples(mut self, limit: usize) -> Self {
+        for shard in self.shards.iter_mut() {
+            shard
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner())
+                .set_failure_samples(limit);
+        }
+        self
+    }
+
+    /// Keep the `limit` slowest traced requests in each shard
+    pub fn with_slowest_traces(mut self, limit: usize) -> Self {
+        for shard in self.shards.iter_mut() {
+            shard
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner())
+                .set_slowest_traces(limit);
+        }
+        self
+    }
+
+    /// Count Apdex against target time `t` in each shard
+    pub fn with_apdex(mut self, t: Option<Duration>) -> Self {
+        for shard in self.shards.iter_mut() {
+            shard
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner())
+                .set_apdex(t);
+        }
+        self
+    }
+
+    /// Log measured requests over the slow log's threshold
+    pub fn with_slow_log(mut self, slow_log: Option<Arc<SlowLog>>) -> Self {
+        self.slow_log = slow_log;
+        self
+    }
+
+    pub fn record(&self, result: &RequestResult) {
+        let idx = THREAD_SHARD.with(Cell::get) % self.shards.len();
+        let stage = self.stage.load(Ordering::Relaxed);
+        let mut shard = self.shards[idx].lock().unwrap_or_else(|e| e.into_inner());
+        if stage != NO_STAGE {
+            // Shards only tag; the aggregator keeps the stage's target
+            shard.enter_stage(stage, 0);
+        }
+        shard.record_sample(result);
+        drop(shard);
+
+        if self.max_requests > 0
+            && self.measuring.load(Ordering::Relaxed)
+            && self.recorded.fetch_add(1, Ordering::Relaxed) + 1 == self.max_requests
+        {
+            tracing::info!("Max requests ({}) reached, stopping", self.max_requests);
+            self.cancel_token.cancel();
+        }
+    }
+
+    /// Tag results recorded from now on with stage `index`
+    pub fn enter_stage(&self, index: usize) {
+        self.stage.store(index, Ordering::Relaxed);
+    }
+
+    /// Called once warmup is over; max_requests counts from here
+    pub fn start_measuring(&self) {
+        self.recorded.store(0, Ordering::Relaxed);
+        self.measuring.store(true, Ordering::Relaxed);
+    }
+
+    /// Warmup requests are left out, like they are from the stats
+    fn log_slow(
+        &self,
+        result: &RequestResult,
+        method: &reqwest::Method,
+        url: &str,
+        scenario: Option<&str>,
+    ) {
+        if let Some(ref slow_log) = self.slow_log
+            && slow_log.is_slow(result)
+            && self.measuring.load(Ordering::Relaxed)
+        {
+            slow_log.write(result, method, url, scenario);
+        }
+    }
+
+    /// Fold every shard into `stats`
+    pub fn drain_into(&self, stats: &mut Stats) {
+        for shard in self.shards.iter() {
+            stats.absorb(&mut shard.lock().unwrap_or_else(|e| e.into_inner()));
+        }
+    }
+
+    /// Drop everything recorded so far (warmup results)
+    pub fn discard(&self) {
+        for shard in self.shards.iter() {
+            shard.lock().unwrap_or_else(|e| e.into_inner()).reset();
+        }
+    }
+}
+
+/// Where workers deliver finished requests. Results go straight into the
+/// sharded stats; the channel only signals completion - the aggregator stops
+/// once every sink has been dropped.
+#[derive(Clone)]
+pub struct ResultSink {
+    tx: mpsc::Sender<ResultBatch>,
+    shards: Arc<StatsShards>,
+}
+
+impl ResultSink {
+    pub fn sharded(tx: mpsc::Sender<ResultBatch>, shards: Arc<StatsShards>) -> Self {
+        Self { tx, shards }
+    }
+
+    /// Deliver a result; false once the aggregator has gone away
+    pub fn send(&self, result: &RequestResult) -> bool {
+        if self.tx.is_closed() {
+            return false;
+        }
+        self.shards.record(result);
+        true
+    }
+
+    /// Add `result`, sent as `METHOD url` for `scenario`, to the slow log if
+    /// one is configured and the request was slow enough
+    pub fn log_slow(
+        &self,
+        result: &RequestResult,
+        method: &reqwest::Method,
+        url: &str,
+        scenario: Option<&str>,
+    ) {
+        self.shards.log_slow(result, method, url, scenario);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ErrorKind;
+
+    #[test]
+    fn drained_shards_match_direct_recording() {
+        let results: Vec<RequestResult> = (1..=200u64)
+            .map(|i| match i % 10 {
+                0 => RequestResult::error(i * 100, ErrorKind::Timeout),
+                5 => RequestResult::success(i * 100, 503, 10, None),
+                _ => RequestResult::success(i * 100, 200, 10, None),
+            })
+            .collect();
+
+        let mut direct = Stats::new(Duration::from_secs(1));
+        for result in &results {
+            direct.record(result);
+        }
+
+        let shards = StatsShards::new(0, CancellationToken::new());
+        std::thread::scope(|s| {
+            for chunk in results.chunks(50) {
+                let shards = &shards;
+                s.spawn(move || chunk.iter().for_each(|r| shards.record(r)));
+            }
+        });
+        let mut merged = Stats::new(Duration::from_secs(1));
+        shards.drain_into(&mut merged);
+
+        assert_eq!(merged.total_requests, direct.total_requests);
+        assert_eq!(merged.successful, direct.successful);
+        assert_eq!(merged.failed, direct.failed);
+        assert_eq!(merged.bytes_received, direct.bytes_received);
+        assert_eq!(merged.status_codes, direct.status_codes);
+        assert_eq!(merged.errors, direct.errors);
+        assert_eq!(merged.latency_max(), direct.latency_max());
+        assert_eq!(
+            merged.latency_percentile(99.0),
+            direct.latency_percentile(99.0)
+        );
+        assert_eq!(merged.rolling_rps(), 200.0);
+        assert_eq!(merged.rolling_rx_bps(), direct.rolling_rx_bps());
+
+        // Draining empties the shards
+        let mut again = Stats::new(Duration::from_secs(1));
+        shards.drain_into(&mut again);
+        assert_eq!(again.total_requests, 0);
+    }
+
+    #[test]
+    fn results_keep_the_stage_they_were_recorded_in() {
+        let shards = StatsShards::new(0, CancellationToken::new());
+        let mut stats = Stats::new(Duration::from_secs(1));
+        stats.enter_stage(0, 10);
+        shards.enter_stage(0);
+        for _ in 0..3 {
+            shards.record(&RequestResult::success(1_000, 200, 0, None));
+        }
+
+        // The stage changes before the aggregator's next tick drains them
+        shards.enter_stage(1);
+        for _ in 0..2 {
+            shards.record(&RequestResult::success(50_000, 200, 0, None));
+        }
+        shards.drain_into(&mut stats);
+        stats.enter_stage(1, 20);
+
+        let stages = stats.stage_stats();
+        assert_eq!(stages.len(), 2);
+        assert_eq!((stages[0].index, stages[0].requests), (0, 3));
+        assert!(stages[0].latency_max_us < 2_000);
+        assert_eq!((stages[1].index, stages[1].target), (1, 20));
+        assert_eq!(stages[1].requests, 2);
+        assert!(stages[1].latency_p50_us >= 49_000);
+
+        // Drained shards stay in the stage the run is in
+        shards.record(&RequestResult::success(50_000, 200, 0, None));
+        shards.drain_into(&mut stats);
+        assert_eq!(stats.stage_stats()[1].requests, 3);
+    }
+
+    #[test]
+    fn max_requests_counts_after_warmup() {
+        let cancel = CancellationToken::new();
+        let shards = StatsShards::new(3, cancel.clone());
+        let ok = RequestResult::success(100, 200, 0, None);
+
+        // Warmup results don't count toward the limit
+        for _ in 0..5 {
+            shards.record(&ok);
+        }
+        assert!(!cancel.is_cancelled());
+
+        shards.start_measuring();
+        shards.record(&ok);
+        shards.record(&ok);
+        assert!(!cancel.is_cancelled());
+        shards.record(&ok);
+        assert!(cancel.is_cancelled());
+    }
+
+    #[test]
+    fn failure_samples_capped_per_kind() {
+        let shards = StatsShards::new(0, CancellationToken::new()).with_failure_samples(2);
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                let shards = &shards;
+                s.spawn(move || {
+                    for _ in 0..5 {
+                        shards.record(&RequestResult::success(100, 503, 0, None));
+                        shards.record(&RequestResult::error(100, ErrorKind::Timeout));
+                        shards.record(&RequestResult::success(100, 200, 0, None));
+                    }
+                });
+            }
+        });
+        let mut merged = Stats::new(Duration::from_secs(1)).with_failure_samples(2);
+        shards.drain_into(&mut merged);
+
+        let kinds: Vec<String> = merged
+            .failure_samples()
+            .into_iter()
+            .map(|sample| sample.kind)
+            .collect();
+        assert_eq!(kinds, ["status 503", "status 503", "timeout", "timeout"]);
+    }
+}
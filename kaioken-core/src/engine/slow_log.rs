@@ -0,0 +1,97 @@
+use crate::types::{RequestResult, SlowLogConfig};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// `--slow-log`: every measured request that took at least the threshold,
+/// one JSON object per line, to line tail latency up with endpoints and time
+pub struct SlowLog {
+    threshold_us: u64,
+    writer: Mutex<BufWriter<File>>,
+    written: Arc<AtomicU64>,
+}
+
+#[derive(Serialize)]
+struct SlowRequest<'a> {
+    /// When the request started
+    timestamp: DateTime<Utc>,
+    /// `METHOD URL`
+    request: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scenario: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'static str>,
+    latency_us: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    queue_time_us: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upload_us: Option<u64>,
+    bytes_sent: u64,
+    bytes_received: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trace_id: Option<String>,
+}
+
+impl SlowLog {
+    /// Create (or truncate) the log file; `written` counts the lines logged
+    pub fn create(config: &SlowLogConfig, written: Arc<AtomicU64>) -> Result<Self, String> {
+        let file = File::create(&config.path)
+            .map_err(|e| format!("Failed to create slow log {}: {}", config.path, e))?;
+        Ok(Self {
+            threshold_us: config.threshold.as_micros() as u64,
+            writer: Mutex::new(BufWriter::new(file)),
+            written,
+        })
+    }
+
+    pub fn is_slow(&self, result: &RequestResult) -> bool {
+        result.latency_us >= self.threshold_us
+    }
+
+    /// Append `result`, sent as `METHOD url` for `scenario`
+    pub fn write(
+        &self,
+        result: &RequestResult,
+        method: &reqwest::Method,
+        url: &str,
+        scenario: Option<&str>,
+    ) {
+        let latency = chrono::Duration::microseconds(result.latency_us as i64);
+        let entry = SlowRequest {
+            timestamp: Utc::now() - latency,
+            request: format!("{} {}", method, url),
+            scenario,
+            status: result.status,
+            error: result.error.map(|e| e.as_str()),
+            latency_us: result.latency_us,
+            queue_time_us: result.queue_time_us,
+            upload_us: result.upload_us,
+            bytes_sent: result.bytes_sent,
+            bytes_received: result.bytes_received,
+            trace_id: result.trace_id.map(|id| format!("{:032x}", id)),
+        };
+
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let written = serde_json::to_writer(&mut *writer, &entry)
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(writer));
+        match written {
+            Ok(()) => {
+                self.written.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => tracing::warn!("Failed to write slow log entry: {}", e),
+        }
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        self.writer
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .flush()
+    }
+}
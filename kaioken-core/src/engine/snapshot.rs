@@ -1,6 +1,7 @@
 use crate::engine::Stats;
-use crate::types::StatsSnapshot;
-use std::collections::HashMap;
+use crate::types::{StatsSnapshot, unix_ms_ago};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 
 pub fn create_snapshot(stats: &Stats) -> StatsSnapshot {
     create_snapshot_with_arrival_rate(stats, 0, 0, 0, 0)
@@ -47,14 +48,19 @@ pub fn create_snapshot_with_arrival_rate(
         (None, None)
     };
 
+    let elapsed = stats.elapsed();
     StatsSnapshot {
-        elapsed: stats.elapsed(),
+        elapsed,
+        started_at_ms: unix_ms_ago(elapsed),
         total_requests: stats.total_requests,
         successful: stats.successful,
         failed: stats.failed,
         bytes_received: stats.bytes_received,
+        bytes_sent: stats.bytes_sent,
 
         rolling_rps: stats.rolling_rps(),
+        rolling_rx_bps: stats.rolling_rx_bps(),
+        rolling_tx_bps: stats.rolling_tx_bps(),
         requests_per_sec: stats.requests_per_sec(),
         error_rate: stats.error_rate(),
 
@@ -68,16 +74,21 @@ pub fn create_snapshot_with_arrival_rate(
         latency_p95_us: stats.latency_percentile(95.0),
         latency_p99_us: stats.latency_percentile(99.0),
         latency_p999_us: stats.latency_percentile(99.9),
+        latency_percentiles: stats.latency_percentiles(),
+        latency_buckets: stats.latency_buckets(),
+        latency_histogram: stats.latency_histogram().map(Arc::new),
 
         status_codes: stats.status_codes.clone(),
         errors: stats.errors.clone(),
-        timeline: stats.timeline.clone(),
+        jsonrpc_errors: stats.jsonrpc_errors.clone(),
+        timeline: stats.timeline(),
         check_stats: HashMap::new(),
         overall_check_pass_rate: None,
         dropped_iterations,
         vus_active,
         vus_max,
         target_rate,
+        results_dropped: 0,
 
         // Latency correction metrics
         latency_correction_enabled,
@@ -117,5 +128,56 @@ pub fn create_snapshot_with_arrival_rate(
         ws_latency_p99_us: 0,
         ws_connect_time_mean_us: 0.0,
         ws_connect_time_p99_us: 0,
+        ws_reconnects: 0,
+        ws_reconnect_failures: 0,
+        ws_reconnect_time_mean_us: 0.0,
+        ws_reconnect_time_p99_us: 0,
+        ws_broadcast_expected: 0,
+        ws_broadcast_delivered: 0,
+        ws_delivery_latency_p50_us: 0,
+        ws_delivery_latency_p95_us: 0,
+        ws_delivery_latency_p99_us: 0,
+        ws_delivery_latency_max_us: 0,
+        ws_compression: None,
+        ws_close_codes: HashMap::new(),
+
+        quic: stats.quic_stats(),
+
+        endpoints: stats.endpoint_stats(),
+        hosts: stats.host_stats(),
+        stages: stats.stage_stats(),
+
+        upload: stats.upload_stats(),
+        request_ids: stats.request_id_stats(),
+        failure_samples: stats.failure_samples(),
+        slowest_traces: stats.slowest_traces(),
+        apdex: stats.apdex_stats(),
+        connections: stats.connection_stats(),
+        connection_cap: None,
+        content_types: stats.content_type_stats(),
+        chaos: None,
+        throttle: None,
+        bursts: None,
+        workers: None,
+        duplicates: None,
+        revalidation: None,
+        outages: Vec::new(),
+        failover: None,
+        extractions: Vec::new(),
+        scenario_mix: Vec::new(),
+        vu_scaling: None,
+        auto_warmup: None,
+        recent: stats.recent_stats(),
+        plugin_metrics: BTreeMap::new(),
+    }
+}
+
+/// Fill in the overall check pass rate from per-check (passed, total) counts
+pub fn apply_check_stats(snapshot: &mut StatsSnapshot, check_stats: &HashMap<String, (u64, u64)>) {
+    let (total_passed, total_checks): (u64, u64) = check_stats
+        .values()
+        .fold((0, 0), |(p, t), (passed, total)| (p + passed, t + total));
+    if total_checks > 0 {
+        snapshot.overall_check_pass_rate = Some(total_passed as f64 / total_checks as f64);
     }
 }
@@ -1,13 +1,16 @@
+use crate::socketio::{SocketIoConnection, connect};
 use crate::types::{WsErrorKind, WsMessageResult, WsMode};
-use crate::ws::{WsConnection, connect, execute_ws_message};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
 
-pub struct WsWorker {
+pub struct SocketIoWorker {
     id: u32,
     url: String,
+    namespace: String,
+    event: String,
+    headers: Vec<(String, String)>,
     message: String,
     mode: WsMode,
     message_interval: Duration,
@@ -16,11 +19,14 @@ pub struct WsWorker {
     cancel_token: CancellationToken,
 }
 
-impl WsWorker {
+impl SocketIoWorker {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: u32,
         url: String,
+        namespace: String,
+        event: String,
+        headers: Vec<(String, String)>,
         message: String,
         mode: WsMode,
         message_interval: Duration,
@@ -31,6 +37,9 @@ impl WsWorker {
         Self {
             id,
             url,
+            namespace,
+            event,
+            headers,
             message,
             mode,
             message_interval,
@@ -41,32 +50,35 @@ impl WsWorker {
     }
 
     pub async fn run(self) {
-        tracing::debug!("WsWorker {} starting", self.id);
+        tracing::debug!("SocketIoWorker {} starting", self.id);
 
-        let mut connection: Option<WsConnection> = None;
+        let mut connection: Option<SocketIoConnection> = None;
+        let mut pending_connect_time: Option<u64> = None;
         let mut message_counter: u64 = 0;
-        let base_message_id = (self.id as u64) * 1_000_000_000;
+        // Echo mode waits for the server ack; fire-and-forget emits without an ack id
+        let ack = self.mode == WsMode::Echo;
 
         loop {
             if self.cancel_token.is_cancelled() {
                 break;
             }
 
-            // Ensure we have a connection
             if connection.is_none() {
-                match connect(&self.url, self.timeout).await {
+                match connect(&self.url, &self.namespace, &self.headers, self.timeout).await {
                     Ok(conn) => {
-                        tracing::debug!("WsWorker {} connected", self.id);
-                        // Record the connection time with the first message
+                        tracing::debug!("SocketIoWorker {} connected", self.id);
+                        pending_connect_time = Some(conn.connect_time_us);
                         connection = Some(conn);
                     }
                     Err(e) => {
-                        // Send connection error result
-                        let result = WsMessageResult::error(e);
-                        if self.result_tx.send(result).await.is_err() {
+                        if self
+                            .result_tx
+                            .send(WsMessageResult::error(e))
+                            .await
+                            .is_err()
+                        {
                             break;
                         }
-                        // Wait before retry
                         tokio::select! {
                             _ = sleep(Duration::from_secs(1)) => {}
                             _ = self.cancel_token.cancelled() => break,
@@ -77,30 +89,24 @@ impl WsWorker {
             }
 
             let conn = connection.as_mut().unwrap();
-            let is_first_message = message_counter == 0;
 
             message_counter += 1;
-            let _message_id = base_message_id + message_counter;
             let timestamp_ms = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .map(|d| d.as_millis())
                 .unwrap_or(0);
-
-            // Interpolate message
             let message = self
                 .message
                 .replace("${MESSAGE_ID}", &message_counter.to_string())
                 .replace("${TIMESTAMP_MS}", &timestamp_ms.to_string());
 
             let start = Instant::now();
-            let mut result = execute_ws_message(conn, &message, self.mode, self.timeout).await;
+            let mut result = conn.emit(&self.event, &message, ack, self.timeout).await;
 
-            // Include connect time on first message
-            if is_first_message {
-                result = result.with_connect_time(conn.connect_time_us);
+            if let Some(connect_time) = pending_connect_time.take() {
+                result = result.with_connect_time(connect_time);
             }
 
-            // Check for connection loss
             let connection_lost = matches!(
                 result.error,
                 Some(WsErrorKind::ConnectionClosed) | Some(WsErrorKind::SendFailed)
@@ -111,27 +117,24 @@ impl WsWorker {
             }
 
             if connection_lost {
-                tracing::debug!("WsWorker {} connection lost, will reconnect", self.id);
+                tracing::debug!("SocketIoWorker {} connection lost, will reconnect", self.id);
                 connection = None;
                 continue;
             }
 
-            // Wait for next message interval
             let elapsed = start.elapsed();
             if elapsed < self.message_interval {
-                let remaining = self.message_interval - elapsed;
                 tokio::select! {
-                    _ = sleep(remaining) => {}
+                    _ = sleep(self.message_interval - elapsed) => {}
                     _ = self.cancel_token.cancelled() => break,
                 }
             }
         }
 
-        // Close connection gracefully
         if let Some(conn) = connection {
             let _ = conn.close().await;
         }
 
-        tracing::debug!("WsWorker {} stopped", self.id);
+        tracing::debug!("SocketIoWorker {} stopped", self.id);
     }
 }
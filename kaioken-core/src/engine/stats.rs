@@ -0,0 +1,1191 @@
+use crate::engine::endpoints::{MAX_ENDPOINTS, OTHER_ENDPOINT};
+use crate::types::{
+    ApdexStats, ConnectionStats, ContentTypeCount, ContentTypeStats, DEFAULT_PERCENTILES,
+    EndpointStats, ErrorKind, FailureDetail, FailureSample, QuicHandshake, QuicStats,
+    RECENT_WINDOW, RecentStats, RequestIdStats, RequestResult, StageStats, TimelineBucket,
+    TraceSample, UploadStats, ZeroRttStats,
+};
+use hdrhistogram::Histogram;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Distinct Content-Types counted before the rest are lumped together
+const MAX_CONTENT_TYPES: usize = 20;
+const OTHER_CONTENT_TYPE: &str = "other";
+
+pub struct Stats {
+    histogram: Histogram<u64>,
+    pub total_requests: u64,
+    pub successful: u64,
+    pub failed: u64,
+    pub bytes_received: u64,
+    pub status_codes: HashMap<u16, u64>,
+    pub errors: HashMap<ErrorKind, u64>,
+    pub jsonrpc_errors: HashMap<i64, u64>,
+    // Responses and successful responses per Content-Type, and the types a
+    // successful response may have (empty = any)
+    content_types: HashMap<String, (u64, u64)>,
+    expected_content_types: Vec<String>,
+    timeline: Vec<TimelineBucket>,
+    timeline_interval: Duration,
+    // Samples in the newest timeline bucket
+    bucket: BucketSamples,
+    start_time: Instant,
+    // Requests, bytes received and bytes sent completed in the last second
+    rolling_window: Vec<(Instant, u64, u64, u64)>,
+    // Latency correction (v1.1)
+    corrected_histogram: Histogram<u64>,
+    queue_time_histogram: Histogram<u64>,
+    pub total_queue_time_us: u64,
+    corrected_samples: u64,
+    // QUIC path stats (HTTP/3)
+    quic_rtt_histogram: Histogram<u64>,
+    quic_connections: u64,
+    quic_cwnd_total: u64,
+    quic_sent_packets: u64,
+    quic_lost_packets: u64,
+    quic_congestion_events: u64,
+    // 0-RTT vs 1-RTT latency (--http3-0rtt)
+    zero_rtt_histogram: Histogram<u64>,
+    one_rtt_histogram: Histogram<u64>,
+    zero_rtt_rejected: u64,
+    zero_rtt_mode: bool,
+    // Per-endpoint breakdown, keyed by grouped `METHOD /path`
+    endpoints: HashMap<String, EndpointSamples>,
+    // Per-host breakdown, keyed by `[[target.hosts]]` name
+    hosts: HashMap<String, EndpointSamples>,
+    // Per-stage breakdown in the order stages ran, and the one results go to
+    // with when it was entered
+    stages: Vec<StageSamples>,
+    current_stage: Option<(usize, Instant)>,
+    // Request bodies: total bytes, plus write time for large ones
+    pub bytes_sent: u64,
+    upload_histogram: Histogram<u64>,
+    upload_timed_bytes: u64,
+    upload_time_total_us: u64,
+    // Responses checked for the request ID they were sent
+    request_ids: RequestIdStats,
+    // Example failures, up to `failure_sample_limit` per kind (0 = none)
+    failure_samples: HashMap<FailureKind, Vec<FailureSample>>,
+    failure_sample_limit: usize,
+    // Slowest traced requests, fastest on top so it's the one replaced
+    slowest_traces: BinaryHeap<Reverse<TraceSample>>,
+    trace_limit: usize,
+    // Apdex counts against T (0 = off); failures are never satisfied
+    apdex_t_us: u64,
+    apdex_satisfied: u64,
+    apdex_tolerating: u64,
+    // New connections reported by the HTTP client, when it reports them
+    connections_tracked: bool,
+    connections_opened: u64,
+    connection_window: Vec<(Instant, u64)>,
+    // Latency percentiles reported in snapshots
+    percentiles: Vec<f64>,
+    // Upper bounds (µs) of the latency histogram reported in snapshots
+    latency_buckets: Vec<u64>,
+    // Whether snapshots carry a copy of the full latency histogram
+    latency_histogram: bool,
+    // Requests and latencies per second of the run, over the last RECENT_WINDOW
+    recent: VecDeque<RecentSecond>,
+}
+
+/// Whether `content_type` is one of `expected`, where `type/*` matches a
+/// whole type and an empty list matches anything
+fn expects(expected: &[String], content_type: &str) -> bool {
+    expected.is_empty()
+        || expected.iter().any(|e| match e.strip_suffix('*') {
+            Some(prefix) if prefix.ends_with('/') => e == "*/*" || content_type.starts_with(prefix),
+            _ => e == content_type,
+        })
+}
+
+struct RecentSecond {
+    second: u64,
+    requests: u64,
+    histogram: Histogram<u64>,
+}
+
+/// What failure samples are grouped by
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum FailureKind {
+    Error(ErrorKind),
+    Status(u16),
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailureKind::Error(kind) => f.write_str(kind.as_str()),
+            FailureKind::Status(status) => write!(f, "status {}", status),
+        }
+    }
+}
+
+struct BucketSamples {
+    histogram: Histogram<u64>,
+    requests: u64,
+    errors: u64,
+    bytes_received: u64,
+    bytes_sent: u64,
+}
+
+impl BucketSamples {
+    fn reset(&mut self) {
+        self.histogram.reset();
+        self.requests = 0;
+        self.errors = 0;
+        self.bytes_received = 0;
+        self.bytes_sent = 0;
+    }
+
+    /// Copy the percentiles into `bucket`; done when the bucket closes and
+    /// for snapshots, rather than on every update
+    fn fill_latency(&self, bucket: &mut TimelineBucket) {
+        bucket.latency_p50_us = self.histogram.value_at_percentile(50.0);
+        bucket.latency_p95_us = self.histogram.value_at_percentile(95.0);
+        bucket.latency_p99_us = self.histogram.value_at_percentile(99.0);
+    }
+}
+
+struct EndpointSamples {
+    requests: u64,
+    failed: u64,
+    histogram: Histogram<u64>,
+}
+
+impl EndpointSamples {
+    fn new() -> Self {
+        Self {
+            requests: 0,
+            failed: 0,
+            histogram: Histogram::<u64>::new(3).expect("Failed to create histogram"),
+        }
+    }
+
+    fn record(&mut self, result: &RequestResult, latency: u64) {
+        self.requests += 1;
+        if !result.is_success() {
+            self.failed += 1;
+        }
+        let _ = self.histogram.record(latency);
+    }
+
+    fn add(&mut self, other: &EndpointSamples) {
+        self.requests += other.requests;
+        self.failed += other.failed;
+        let _ = self.histogram.add(&other.histogram);
+    }
+}
+
+/// Results tagged with one stage, and the time measured in it
+struct StageSamples {
+    index: usize,
+    target: u32,
+    elapsed: Duration,
+    samples: EndpointSamples,
+}
+
+impl StageSamples {
+    fn new(index: usize, target: u32) -> Self {
+        Self {
+            index,
+            target,
+            elapsed: Duration::ZERO,
+            samples: EndpointSamples::new(),
+        }
+    }
+}
+
+/// Breakdown rows, busiest first
+fn breakdown(samples: &HashMap<String, EndpointSamples>) -> Vec<EndpointStats> {
+    let mut rows: Vec<EndpointStats> = samples
+        .iter()
+        .map(|(name, samples)| EndpointStats {
+            name: name.clone(),
+            requests: samples.requests,
+            failed: samples.failed,
+            latency_p50_us: samples.histogram.value_at_percentile(50.0),
+            latency_p95_us: samples.histogram.value_at_percentile(95.0),
+            latency_p99_us: samples.histogram.value_at_percentile(99.0),
+            latency_max_us: samples.histogram.max(),
+        })
+        .collect();
+    rows.sort_by(|a, b| b.requests.cmp(&a.requests).then(a.name.cmp(&b.name)));
+    rows
+}
+
+impl Stats {
+    pub fn new(duration: Duration) -> Self {
+        Self::with_histograms(duration, || {
+            Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).expect("Failed to create histogram")
+        })
+    }
+
+    /// Recording shard for `StatsShards`: histograms start small and grow
+    /// with the recorded range, since there is one shard per core
+    pub fn shard() -> Self {
+        Self::with_histograms(Duration::ZERO, || {
+            Histogram::<u64>::new(3).expect("Failed to create histogram")
+        })
+    }
+
+    fn with_histograms(duration: Duration, histogram: impl Fn() -> Histogram<u64>) -> Self {
+        let timeline_capacity = duration.as_secs() as usize + 60;
+
+        Self {
+            histogram: histogram(),
+            total_requests: 0,
+            successful: 0,
+            failed: 0,
+            bytes_received: 0,
+            status_codes: HashMap::new(),
+            errors: HashMap::new(),
+            jsonrpc_errors: HashMap::new(),
+            content_types: HashMap::new(),
+            expected_content_types: Vec::new(),
+            timeline: Vec::with_capacity(timeline_capacity),
+            timeline_interval: Duration::from_secs(1),
+            bucket: BucketSamples {
+                histogram: Histogram::<u64>::new(3).expect("Failed to create histogram"),
+                requests: 0,
+                errors: 0,
+                bytes_received: 0,
+                bytes_sent: 0,
+            },
+            start_time: Instant::now(),
+            rolling_window: Vec::with_capacity(100),
+            corrected_histogram: histogram(),
+            queue_time_histogram: histogram(),
+            total_queue_time_us: 0,
+            corrected_samples: 0,
+            quic_rtt_histogram: histogram(),
+            quic_connections: 0,
+            quic_cwnd_total: 0,
+            quic_sent_packets: 0,
+            quic_lost_packets: 0,
+            quic_congestion_events: 0,
+            zero_rtt_histogram: histogram(),
+            one_rtt_histogram: histogram(),
+            zero_rtt_rejected: 0,
+            zero_rtt_mode: false,
+            endpoints: HashMap::new(),
+            hosts: HashMap::new(),
+            stages: Vec::new(),
+            current_stage: None,
+            bytes_sent: 0,
+            upload_histogram: histogram(),
+            upload_timed_bytes: 0,
+            upload_time_total_us: 0,
+            request_ids: RequestIdStats::default(),
+            failure_samples: HashMap::new(),
+            failure_sample_limit: 0,
+            slowest_traces: BinaryHeap::new(),
+            trace_limit: 0,
+            apdex_t_us: 0,
+            apdex_satisfied: 0,
+            apdex_tolerating: 0,
+            connections_tracked: false,
+            connections_opened: 0,
+            connection_window: Vec::new(),
+            percentiles: DEFAULT_PERCENTILES.to_vec(),
+            latency_buckets: Vec::new(),
+            latency_histogram: false,
+            recent: VecDeque::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.histogram.reset();
+        self.total_requests = 0;
+        self.successful = 0;
+        self.failed = 0;
+        self.bytes_received = 0;
+        self.status_codes.clear();
+        self.errors.clear();
+        self.jsonrpc_errors.clear();
+        self.content_types.clear();
+        self.timeline.clear();
+        self.bucket.reset();
+        self.start_time = Instant::now();
+        self.rolling_window.clear();
+        self.corrected_histogram.reset();
+        self.queue_time_histogram.reset();
+        self.total_queue_time_us = 0;
+        self.corrected_samples = 0;
+        self.quic_rtt_histogram.reset();
+        self.quic_connections = 0;
+        self.quic_cwnd_total = 0;
+        self.quic_sent_packets = 0;
+        self.quic_lost_packets = 0;
+        self.quic_congestion_events = 0;
+        self.zero_rtt_histogram.reset();
+        self.one_rtt_histogram.reset();
+        self.zero_rtt_rejected = 0;
+        self.endpoints.clear();
+        self.hosts.clear();
+        // Measuring restarts in the stage the run is in
+        if let Some((position, _)) = self.current_stage {
+            let stage = &self.stages[position];
+            self.stages = vec![StageSamples::new(stage.index, stage.target)];
+            self.current_stage = Some((0, Instant::now()));
+        }
+        self.bytes_sent = 0;
+        self.upload_histogram.reset();
+        self.upload_timed_bytes = 0;
+        self.upload_time_total_us = 0;
+        self.request_ids = RequestIdStats::default();
+        self.failure_samples.clear();
+        self.slowest_traces.clear();
+        self.apdex_satisfied = 0;
+        self.apdex_tolerating = 0;
+        self.connections_opened = 0;
+        self.connection_window.clear();
+        self.recent.clear();
+    }
+
+    /// Bucket width of the timeline (1s unless changed)
+    pub fn with_timeline_interval(mut self, interval: Duration) -> Self {
+        self.timeline_interval = interval;
+        self
+    }
+
+    /// Keep up to `limit` example failures per error kind or status
+    pub fn with_failure_samples(mut self, limit: usize) -> Self {
+        self.set_failure_samples(limit);
+        self
+    }
+
+    pub fn set_failure_samples(&mut self, limit: usize) {
+        self.failure_sample_limit = limit;
+    }
+
+    /// Keep the trace IDs of the `limit` slowest traced requests
+    pub fn with_slowest_traces(mut self, limit: usize) -> Self {
+        self.set_slowest_traces(limit);
+        self
+    }
+
+    pub fn set_slowest_traces(&mut self, limit: usize) {
+        self.trace_limit = limit;
+    }
+
+    /// Count requests toward Apdex with target time `t`
+    pub fn with_apdex(mut self, t: Option<Duration>) -> Self {
+        self.set_apdex(t);
+        self
+    }
+
+    pub fn set_apdex(&mut self, t: Option<Duration>) {
+        self.apdex_t_us = t.map_or(0, |t| t.as_micros() as u64);
+    }
+
+    /// Latency percentiles to report instead of `DEFAULT_PERCENTILES`
+    pub fn with_percentiles(mut self, percentiles: Vec<f64>) -> Self {
+        self.percentiles = percentiles;
+        self
+    }
+
+    /// Latency histogram bucket bounds in ms, reported in snapshots
+    pub fn with_latency_buckets(mut self, bounds_ms: &[f64]) -> Self {
+        self.latency_buckets = bounds_ms
+            .iter()
+            .map(|ms| (ms * 1000.0).round() as u64)
+            .collect();
+        self
+    }
+
+    /// Report the full latency histogram in snapshots, for merging runs
+    pub fn with_latency_histogram(mut self) -> Self {
+        self.latency_histogram = true;
+        self
+    }
+
+    /// Full latency histogram (µs), when snapshots report it
+    pub fn latency_histogram(&self) -> Option<Histogram<u64>> {
+        self.latency_histogram.then(|| self.histogram.clone())
+    }
+
+    /// Requests at or under each latency bucket bound (ms), cumulative
+    pub fn latency_buckets(&self) -> Vec<(f64, u64)> {
+        let mut counts = vec![0u64; self.latency_buckets.len()];
+        for v in self.histogram.iter_recorded() {
+            let first = self
+                .latency_buckets
+                .partition_point(|&bound| bound < v.value_iterated_to());
+            if let Some(count) = counts.get_mut(first) {
+                *count += v.count_at_value();
+            }
+        }
+        let mut cumulative = 0;
+        self.latency_buckets
+            .iter()
+            .zip(counts)
+            .map(|(&bound, count)| {
+                cumulative += count;
+                (bound as f64 / 1000.0, cumulative)
+            })
+            .collect()
+    }
+
+    /// Each configured percentile with its latency
+    pub fn latency_percentiles(&self) -> Vec<(f64, u64)> {
+        self.percentiles
+            .iter()
+            .map(|&p| (p, self.latency_percentile(p)))
+            .collect()
+    }
+
+    /// Count successful responses of any other Content-Type as unexpected
+    pub fn with_expected_content_types(mut self, expected: Vec<String>) -> Self {
+        self.expected_content_types = expected;
+        self
+    }
+
+    /// Report connection reuse from connections counted via `record_connections`
+    pub fn with_connection_tracking(mut self) -> Self {
+        self.connections_tracked = true;
+        self
+    }
+
+    /// Count connections the client opened since the last call
+    pub fn record_connections(&mut self, opened: u64) {
+        let now = Instant::now();
+        self.connections_opened += opened;
+        self.connection_window.push((now, opened));
+        self.connection_window
+            .retain(|(t, _)| now.duration_since(*t) < Duration::from_secs(1));
+    }
+
+    /// Count `total` evaluations of check `name`, `passed` of which passed,
+    /// into the current timeline bucket
+    pub fn record_checks(&mut self, name: &str, passed: u64, total: u64) {
+        self.roll_timeline();
+        if let Some(last) = self.timeline.last_mut() {
+            let counts = last.checks.entry(name.to_string()).or_default();
+            counts.0 += passed;
+            counts.1 += total;
+        }
+    }
+
+    pub fn record(&mut self, result: &RequestResult) {
+        self.record_sample(result);
+
+        self.roll_timeline();
+        let _ = self
+            .bucket
+            .histogram
+            .record(result.latency_us.min(60_000_000));
+        let _ = self
+            .recent_second()
+            .histogram
+            .record(result.latency_us.min(60_000_000));
+        if !result.is_success() {
+            self.bucket.errors += 1;
+        }
+        self.bucket.bytes_received += result.bytes_received;
+        self.bucket.bytes_sent += result.bytes_sent;
+        self.count_completed(1, result.bytes_received, result.bytes_sent);
+    }
+
+    /// Accumulate a result without touching the time-based rolling window
+    /// and timeline (shards leave those to the stats they are merged into)
+    pub fn record_sample(&mut self, result: &RequestResult) {
+        self.total_requests += 1;
+        self.bytes_received += result.bytes_received;
+        self.bytes_sent += result.bytes_sent;
+        if let Some(upload_us) = result.upload_us {
+            let _ = self.upload_histogram.record(upload_us.clamp(1, 60_000_000));
+            self.upload_timed_bytes += result.bytes_sent;
+            self.upload_time_total_us += upload_us;
+        }
+        if let Some(echo) = result.id_echo {
+            self.request_ids.record(echo);
+        }
+
+        let latency = result.latency_us.min(60_000_000);
+        let _ = self.histogram.record(latency);
+
+        // Record corrected latency and queue time if available
+        if let Some(queue_time) = result.queue_time_us {
+            let queue_time_clamped = queue_time.min(60_000_000);
+            let _ = self.queue_time_histogram.record(queue_time_clamped);
+            self.total_queue_time_us += queue_time;
+
+            // Corrected latency = total latency - queue wait time
+            if let Some(corrected) = result.corrected_latency_us() {
+                let corrected_clamped = corrected.min(60_000_000);
+                let _ = self.corrected_histogram.record(corrected_clamped);
+                self.corrected_samples += 1;
+            }
+        }
+
+        if let Some(quic) = result.quic {
+            let _ = self
+                .quic_rtt_histogram
+                .record(quic.rtt_us.clamp(1, 60_000_000));
+            self.quic_connections += 1;
+            self.quic_cwnd_total += quic.cwnd;
+            self.quic_sent_packets += quic.sent_packets;
+            self.quic_lost_packets += quic.lost_packets;
+            self.quic_congestion_events += quic.congestion_events;
+
+            match quic.handshake {
+                Some(QuicHandshake::ZeroRtt) => {
+                    let _ = self.zero_rtt_histogram.record(latency.max(1));
+                }
+                Some(QuicHandshake::OneRtt) => {
+                    let _ = self.one_rtt_histogram.record(latency.max(1));
+                }
+                Some(QuicHandshake::ZeroRttRejected) => self.zero_rtt_rejected += 1,
+                None => {}
+            }
+            if quic.handshake.is_some() {
+                self.zero_rtt_mode = true;
+            }
+        }
+
+        if result.is_success() {
+            self.successful += 1;
+            if self.apdex_t_us > 0 {
+                if result.latency_us <= self.apdex_t_us {
+                    self.apdex_satisfied += 1;
+                } else if result.latency_us <= self.apdex_t_us * 4 {
+                    self.apdex_tolerating += 1;
+                }
+            }
+        } else {
+            self.failed += 1;
+        }
+
+        if let Some(status) = result.status {
+            *self.status_codes.entry(status).or_insert(0) += 1;
+        }
+
+        if let Some(kind) = result.error {
+            *self.errors.entry(kind).or_insert(0) += 1;
+        }
+
+        if let Some(code) = result.jsonrpc_error {
+            *self.jsonrpc_errors.entry(code).or_insert(0) += 1;
+        }
+
+        if let Some(ref content_type) = result.content_type {
+            let counts = self.content_type_counts(content_type);
+            counts.0 += 1;
+            counts.1 += result.is_success() as u64;
+        }
+
+        if let Some(ref endpoint) = result.endpoint {
+            self.endpoint_samples(endpoint).record(result, latency);
+        }
+        if let Some(ref host) = result.host {
+            self.hosts
+                .entry(host.clone())
+                .or_insert_with(EndpointSamples::new)
+                .record(result, latency);
+        }
+        if let Some((position, _)) = self.current_stage {
+            self.stages[position].samples.record(result, latency);
+        }
+
+        if !result.is_success() && self.failure_sample_limit > 0 {
+            self.sample_failure(result);
+        }
+
+        if let Some(trace_id) = result.trace_id
+            && self.is_slow_trace(result.latency_us)
+        {
+            let now_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            self.keep_trace(TraceSample {
+                latency_us: result.latency_us,
+                trace_id,
+                status: result.status,
+                timestamp_ms: now_ms.saturating_sub(result.latency_us / 1000),
+            });
+        }
+    }
+
+    /// Whether a traced request this slow makes the slowest list
+    fn is_slow_trace(&self, latency_us: u64) -> bool {
+        if self.slowest_traces.len() < self.trace_limit {
+            return true;
+        }
+        self.trace_limit > 0
+            && self
+                .slowest_traces
+                .peek()
+                .is_some_and(|Reverse(fastest)| fastest.latency_us < latency_us)
+    }
+
+    fn keep_trace(&mut self, sample: TraceSample) {
+        if !self.is_slow_trace(sample.latency_us) {
+            return;
+        }
+        if self.slowest_traces.len() >= self.trace_limit {
+            self.slowest_traces.pop();
+        }
+        self.slowest_traces.push(Reverse(sample));
+    }
+
+    /// Apdex so far (None unless apdex_t is set or before any requests)
+    pub fn apdex_stats(&self) -> Option<ApdexStats> {
+        if self.apdex_t_us == 0 || self.total_requests == 0 {
+            return None;
+        }
+        Some(ApdexStats {
+            t_us: self.apdex_t_us,
+            satisfied: self.apdex_satisfied,
+            tolerating: self.apdex_tolerating,
+            frustrated: self.total_requests - self.apdex_satisfied - self.apdex_tolerating,
+        })
+    }
+
+    /// Connection reuse so far (None unless tracked)
+    pub fn connection_stats(&self) -> Option<ConnectionStats> {
+        if !self.connections_tracked {
+            return None;
+        }
+        let elapsed = self.elapsed().as_secs_f64();
+        Some(ConnectionStats {
+            opened: self.connections_opened,
+            reused: self.total_requests.saturating_sub(self.connections_opened),
+            opened_per_sec: if elapsed > 0.0 {
+                self.connections_opened as f64 / elapsed
+            } else {
+                0.0
+            },
+            rolling_opened_per_sec: self.connection_window.iter().map(|(_, c)| *c as f64).sum(),
+        })
+    }
+
+    /// Slowest traced requests, slowest first
+    pub fn slowest_traces(&self) -> Vec<TraceSample> {
+        let mut traces: Vec<TraceSample> = self
+            .slowest_traces
+            .iter()
+            .map(|Reverse(sample)| sample.clone())
+            .collect();
+        traces.sort_by(|a, b| b.cmp(a));
+        traces
+    }
+
+    fn sample_failure(&mut self, result: &RequestResult) {
+        let kind = match (result.error, result.status) {
+            (Some(error), _) => FailureKind::Error(error),
+            (None, Some(status)) => FailureKind::Status(status),
+            (None, None) => return,
+        };
+        let samples = self.failure_samples.entry(kind).or_default();
+        if samples.len() >= self.failure_sample_limit {
+            return;
+        }
+
+        let detail = result.failure.as_deref();
+        let body = detail.and_then(|d| d.body.clone()).or_else(|| {
+            let body = result.body.as_deref()?;
+            Some(FailureDetail::excerpt(body.as_bytes()))
+        });
+        samples.push(FailureSample {
+            kind: kind.to_string(),
+            request: detail.map(|d| d.request.clone()),
+            status: result.status,
+            message: detail.and_then(|d| d.message.clone()),
+            body,
+            latency_us: result.latency_us,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        });
+    }
+
+    /// Example failures, grouped by kind in the order they were seen
+    pub fn failure_samples(&self) -> Vec<FailureSample> {
+        let mut samples: Vec<FailureSample> =
+            self.failure_samples.values().flatten().cloned().collect();
+        samples.sort_by(|a, b| a.kind.cmp(&b.kind));
+        samples
+    }
+
+    /// Samples for `name`, or for `OTHER_ENDPOINT` once `MAX_ENDPOINTS` are tracked
+    fn endpoint_samples(&mut self, name: &str) -> &mut EndpointSamples {
+        let name = if self.endpoints.contains_key(name) || self.endpoints.len() < MAX_ENDPOINTS {
+            name
+        } else {
+            OTHER_ENDPOINT
+        };
+        if !self.endpoints.contains_key(name) {
+            self.endpoints
+                .insert(name.to_string(), EndpointSamples::new());
+        }
+        self.endpoints
+            .get_mut(name)
+            .expect("endpoint was just inserted")
+    }
+
+    fn content_type_counts(&mut self, content_type: &str) -> &mut (u64, u64) {
+        let content_type = if self.content_types.contains_key(content_type)
+            || self.content_types.len() < MAX_CONTENT_TYPES
+        {
+            content_type
+        } else {
+            OTHER_CONTENT_TYPE
+        };
+        if !self.content_types.contains_key(content_type) {
+            self.content_types.insert(content_type.to_string(), (0, 0));
+        }
+        self.content_types
+            .get_mut(content_type)
+            .expect("content type was just inserted")
+    }
+
+    /// Responses per Content-Type, busiest first (None if no response had one)
+    pub fn content_type_stats(&self) -> Option<ContentTypeStats> {
+        if self.content_types.is_empty() {
+            return None;
+        }
+        let expected = &self.expected_content_types;
+        let mut types: Vec<ContentTypeCount> = self
+            .content_types
+            .iter()
+            .map(
+                |(content_type, &(responses, successful))| ContentTypeCount {
+                    content_type: content_type.clone(),
+                    responses,
+                    unexpected: if expects(expected, content_type) {
+                        0
+                    } else {
+                        successful
+                    },
+                },
+            )
+            .collect();
+        types.sort_by(|a, b| {
+            b.responses
+                .cmp(&a.responses)
+                .then_with(|| a.content_type.cmp(&b.content_type))
+        });
+        Some(ContentTypeStats {
+            types,
+            expected: expected.clone(),
+        })
+    }
+
+    /// Per-endpoint breakdown, busiest first
+    pub fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        breakdown(&self.endpoints)
+    }
+
+    /// Per-host breakdown, busiest first
+    pub fn host_stats(&self) -> Vec<EndpointStats> {
+        breakdown(&self.hosts)
+    }
+
+    /// Tag results recorded from now on with stage `index`, ramping to `target`
+    pub fn enter_stage(&mut self, index: usize, target: u32) {
+        let now = Instant::now();
+        if let Some((position, since)) = self.current_stage {
+            let current = &mut self.stages[position];
+            if current.index == index {
+                current.target = target;
+                return;
+            }
+            current.elapsed += now - since;
+        }
+        let position = self.stage_position(index);
+        self.stages[position].target = target;
+        self.current_stage = Some((position, now));
+    }
+
+    /// Where stage `index` is kept, added at the end if it hasn't run yet; a
+    /// reload can move the run back into a stage it has been in
+    fn stage_position(&mut self, index: usize) -> usize {
+        match self.stages.iter().position(|s| s.index == index) {
+            Some(position) => position,
+            None => {
+                self.stages.push(StageSamples::new(index, 0));
+                self.stages.len() - 1
+            }
+        }
+    }
+
+    /// Per-stage breakdown, in the order the stages ran
+    pub fn stage_stats(&self) -> Vec<StageStats> {
+        self.stages
+            .iter()
+            .enumerate()
+            .map(|(position, stage)| {
+                let mut elapsed = stage.elapsed;
+                if let Some((current, since)) = self.current_stage
+                    && current == position
+                {
+                    elapsed += since.elapsed();
+                }
+                let histogram = &stage.samples.histogram;
+                StageStats {
+                    index: stage.index,
+                    target: stage.target,
+                    elapsed,
+                    requests: stage.samples.requests,
+                    failed: stage.samples.failed,
+                    latency_p50_us: histogram.value_at_percentile(50.0),
+                    latency_p95_us: histogram.value_at_percentile(95.0),
+                    latency_p99_us: histogram.value_at_percentile(99.0),
+                    latency_max_us: histogram.max(),
+                }
+            })
+            .collect()
+    }
+
+    /// Fold a shard's samples into these stats and clear the shard for reuse
+    pub fn absorb(&mut self, shard: &mut Stats) {
+        if shard.total_requests == 0 {
+            return;
+        }
+
+        let _ = self.histogram.add(&shard.histogram);
+        let _ = self.corrected_histogram.add(&shard.corrected_histogram);
+        let _ = self.queue_time_histogram.add(&shard.queue_time_histogram);
+        let _ = self.quic_rtt_histogram.add(&shard.quic_rtt_histogram);
+        let _ = self.zero_rtt_histogram.add(&shard.zero_rtt_histogram);
+        let _ = self.one_rtt_histogram.add(&shard.one_rtt_histogram);
+
+        self.total_requests += shard.total_requests;
+        self.successful += shard.successful;
+        self.apdex_satisfied += shard.apdex_satisfied;
+        self.apdex_tolerating += shard.apdex_tolerating;
+        self.failed += shard.failed;
+        self.bytes_received += shard.bytes_received;
+        self.bytes_sent += shard.bytes_sent;
+        let _ = self.upload_histogram.add(&shard.upload_histogram);
+        self.upload_timed_bytes += shard.upload_timed_bytes;
+        self.upload_time_total_us += shard.upload_time_total_us;
+        self.request_ids.add(&shard.request_ids);
+        self.total_queue_time_us += shard.total_queue_time_us;
+        self.corrected_samples += shard.corrected_samples;
+        self.quic_connections += shard.quic_connections;
+        self.quic_cwnd_total += shard.quic_cwnd_total;
+        self.quic_sent_packets += shard.quic_sent_packets;
+        self.quic_lost_packets += shard.quic_lost_packets;
+        self.quic_congestion_events += shard.quic_congestion_events;
+        self.zero_rtt_rejected += shard.zero_rtt_rejected;
+        self.zero_rtt_mode |= shard.zero_rtt_mode;
+
+        for (status, count) in shard.status_codes.drain() {
+            *self.status_codes.entry(status).or_insert(0) += count;
+        }
+        for (kind, count) in shard.errors.drain() {
+            *self.errors.entry(kind).or_insert(0) += count;
+        }
+        for (code, count) in shard.jsonrpc_errors.drain() {
+            *self.jsonrpc_errors.entry(code).or_insert(0) += count;
+        }
+        for (content_type, (responses, successful)) in shard.content_types.drain() {
+            let counts = self.content_type_counts(&content_type);
+            counts.0 += responses;
+            counts.1 += successful;
+        }
+        for Reverse(sample) in shard.slowest_traces.drain() {
+            self.keep_trace(sample);
+        }
+        for (kind, samples) in shard.failure_samples.drain() {
+            let kept = self.failure_samples.entry(kind).or_default();
+            let room = self.failure_sample_limit.saturating_sub(kept.len());
+            kept.extend(samples.into_iter().take(room));
+        }
+        for (name, shard_samples) in shard.endpoints.drain() {
+            self.endpoint_samples(&name).add(&shard_samples);
+        }
+        for (name, shard_samples) in shard.hosts.drain() {
+            self.hosts
+                .entry(name)
+                .or_insert_with(EndpointSamples::new)
+                .add(&shard_samples);
+        }
+        // Shards tag samples with the stage the run was in when recorded
+        for stage in &shard.stages {
+            if stage.samples.requests > 0 {
+                let position = self.stage_position(stage.index);
+                self.stages[position].samples.add(&stage.samples);
+            }
+        }
+
+        self.roll_timeline();
+        let _ = self.bucket.histogram.add(&shard.histogram);
+        let _ = self.recent_second().histogram.add(&shard.histogram);
+        self.bucket.errors += shard.failed;
+        self.bucket.bytes_received += shard.bytes_received;
+        self.bucket.bytes_sent += shard.bytes_sent;
+
+        self.count_completed(shard.total_requests, shard.bytes_received, shard.bytes_sent);
+        shard.reset();
+    }
+
+    fn count_completed(&mut self, requests: u64, bytes_received: u64, bytes_sent: u64) {
+        let now = Instant::now();
+        self.rolling_window
+            .push((now, requests, bytes_received, bytes_sent));
+        self.rolling_window
+            .retain(|(t, ..)| now.duration_since(*t) < Duration::from_secs(1));
+        self.recent_second().requests += requests;
+
+        self.update_timeline(requests);
+    }
+
+    /// First whole second of the run covering the last RECENT_WINDOW
+    fn recent_start(&self) -> u64 {
+        self.elapsed().saturating_sub(RECENT_WINDOW).as_secs()
+    }
+
+    /// Samples for the current second, dropping seconds that left the window
+    fn recent_second(&mut self) -> &mut RecentSecond {
+        let second = self.elapsed().as_secs();
+        if self.recent.back().is_none_or(|s| s.second < second) {
+            let start = self.recent_start();
+            let mut expired = None;
+            while self.recent.front().is_some_and(|s| s.second < start) {
+                expired = self.recent.pop_front();
+            }
+            // Reuse an expired histogram rather than allocating one every second
+            let histogram = match expired {
+                Some(mut expired) => {
+                    expired.histogram.reset();
+                    expired.histogram
+                }
+                None => Histogram::new_from(&self.histogram),
+            };
+            self.recent.push_back(RecentSecond {
+                second,
+                requests: 0,
+                histogram,
+            });
+        }
+        self.recent.back_mut().expect("just pushed")
+    }
+
+    /// Throughput and latency over the last RECENT_WINDOW, if anything
+    /// completed in it
+    pub fn recent_stats(&self) -> Option<RecentStats> {
+        let start = self.recent_start();
+        let seconds = self.recent.iter().filter(|s| s.second >= start);
+        let requests: u64 = seconds.clone().map(|s| s.requests).sum();
+        if requests == 0 {
+            return None;
+        }
+
+        let mut histogram = Histogram::new_from(&self.histogram);
+        for s in seconds {
+            let _ = histogram.add(&s.histogram);
+        }
+        let window = self.elapsed().saturating_sub(Duration::from_secs(start));
+        Some(RecentStats {
+            window,
+            requests,
+            requests_per_sec: requests as f64 / window.as_secs_f64().max(0.001),
+            latency_percentiles: self
+                .percentiles
+                .iter()
+                .map(|&p| (p, histogram.value_at_percentile(p)))
+                .collect(),
+        })
+    }
+
+    /// Close the newest timeline bucket once its interval has passed
+    fn roll_timeline(&mut self) {
+        let interval_ms = self.timeline_interval.as_millis().max(1) as u64;
+        let elapsed_ms = self.start_time.elapsed().as_millis() as u64 / interval_ms * interval_ms;
+
+        if self
+            .timeline
+            .last()
+            .is_none_or(|bucket| bucket.elapsed_ms < elapsed_ms)
+        {
+            if let Some(last) = self.timeline.last_mut() {
+                self.bucket.fill_latency(last);
+            }
+            self.timeline.push(TimelineBucket {
+                elapsed_secs: (elapsed_ms / 1000) as u32,
+                elapsed_ms,
+                ..Default::default()
+            });
+            self.bucket.reset();
+        }
+    }
+
+    fn update_timeline(&mut self, requests: u64) {
+        self.roll_timeline();
+        self.bucket.requests += requests;
+
+        if let Some(last) = self.timeline.last_mut() {
+            last.requests = self.bucket.requests;
+            last.errors = self.bucket.errors;
+            last.bytes_received = self.bucket.bytes_received;
+            last.bytes_sent = self.bucket.bytes_sent;
+        }
+    }
+
+    pub fn timeline_interval(&self) -> Duration {
+        self.timeline_interval
+    }
+
+    /// Timeline so far, including percentiles of the still-open bucket
+    pub fn timeline(&self) -> Vec<TimelineBucket> {
+        let mut timeline = self.timeline.clone();
+        if let Some(last) = timeline.last_mut() {
+            self.bucket.fill_latency(last);
+        }
+        timeline
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start_time.elapsed()
+    }
+
+    pub fn total_requests(&self) -> u64 {
+        self.total_requests
+    }
+
+    pub fn rolling_rps(&self) -> f64 {
+        self.rolling_window.iter().map(|(_, c, ..)| *c as f64).sum()
+    }
+
+    /// Bits per second received over the last second
+    pub fn rolling_rx_bps(&self) -> f64 {
+        self.rolling_window
+            .iter()
+            .map(|(_, _, rx, _)| *rx as f64 * 8.0)
+            .sum()
+    }
+
+    /// Bits per second sent over the last second
+    pub fn rolling_tx_bps(&self) -> f64 {
+        self.rolling_window
+            .iter()
+            .map(|(_, _, _, tx)| *tx as f64 * 8.0)
+            .sum()
+    }
+
+    pub fn requests_per_sec(&self) -> f64 {
+        let elapsed = self.elapsed().as_secs_f64();
+        if elapsed > 0.0 {
+            self.total_requests as f64 / elapsed
+        } else {
+            0.0
+        }
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        if self.total_requests > 0 {
+            self.failed as f64 / self.total_requests as f64
+        } else {
+            0.0
+        }
+    }
+
+    pub fn latency_min(&self) -> u64 {
+        self.histogram.min()
+    }
+
+    pub fn latency_max(&self) -> u64 {
+        self.histogram.max()
+    }
+
+    pub fn latency_mean(&self) -> f64 {
+        self.histogram.mean()
+    }
+
+    pub fn latency_stddev(&self) -> f64 {
+        self.histogram.stdev()
+    }
+
+    pub fn latency_percentile(&self, p: f64) -> u64 {
+        self.histogram.value_at_percentile(p)
+    }
+
+    // Latency correction methods
+
+    pub fn has_corrected_latency(&self) -> bool {
+        self.corrected_samples > 0
+    }
+
+    pub fn corrected_latency_min(&self) -> u64 {
+        self.corrected_histogram.min()
+    }
+
+    pub fn corrected_latency_max(&self) -> u64 {
+        self.corrected_histogram.max()
+    }
+
+    pub fn corrected_latency_mean(&self) -> f64 {
+        self.corrected_histogram.mean()
+    }
+
+    pub fn corrected_latency_percentile(&self, p: f64) -> u64 {
+        self.corrected_histogram.value_at_percentile(p)
+    }
+
+    pub fn queue_time_mean(&self) -> f64 {
+        self.queue_time_histogram.mean()
+    }
+
+    pub fn queue_time_percentile(&self, p: f64) -> u64 {
+        self.queue_time_histogram.value_at_percentile(p)
+    }
+
+    /// Upload stats, or None when no request sent a body
+    pub fn upload_stats(&self) -> Option<UploadStats> {
+        if self.bytes_sent == 0 {
+            return None;
+        }
+        Some(UploadStats {
+            bytes_sent: self.bytes_sent,
+            timed_requests: self.upload_histogram.len(),
+            time_mean_us: self.upload_histogram.mean(),
+            time_p50_us: self.upload_histogram.value_at_percentile(50.0),
+            time_p95_us: self.upload_histogram.value_at_percentile(95.0),
+            time_p99_us: self.upload_histogram.value_at_percentile(99.0),
+            time_max_us: self.upload_histogram.max(),
+            throughput_bytes_per_sec: if self.upload_time_total_us > 0 {
+                self.upload_timed_bytes as f64 * 1_000_000.0 / self.upload_time_total_us as f64
+            } else {
+                0.0
+            },
+        })
+    }
+
+    /// Echoed request IDs, or None when no response was checked
+    pub fn request_id_stats(&self) -> Option<RequestIdStats> {
+        (self.request_ids.responses > 0).then_some(self.request_ids)
+    }
+
+    /// Aggregated QUIC stats, or None when no HTTP/3 connection reported any
+    pub fn quic_stats(&self) -> Option<QuicStats> {
+        if self.quic_connections == 0 {
+            return None;
+        }
+        Some(QuicStats {
+            connections: self.quic_connections,
+            rtt_mean_us: self.quic_rtt_histogram.mean(),
+            rtt_p50_us: self.quic_rtt_histogram.value_at_percentile(50.0),
+            rtt_p99_us: self.quic_rtt_histogram.value_at_percentile(99.0),
+            cwnd_mean_bytes: self.quic_cwnd_total as f64 / self.quic_connections as f64,
+            sent_packets: self.quic_sent_packets,
+            lost_packets: self.quic_lost_packets,
+            congestion_events: self.quic_congestion_events,
+            zero_rtt: self.zero_rtt_mode.then(|| ZeroRttStats {
+                zero_rtt_requests: self.zero_rtt_histogram.len(),
+                one_rtt_requests: self.one_rtt_histogram.len(),
+                rejected: self.zero_rtt_rejected,
+                zero_rtt_p50_us: self.zero_rtt_histogram.value_at_percentile(50.0),
+                zero_rtt_p99_us: self.zero_rtt_histogram.value_at_percentile(99.0),
+                one_rtt_p50_us: self.one_rtt_histogram.value_at_percentile(50.0),
+                one_rtt_p99_us: self.one_rtt_histogram.value_at_percentile(99.0),
+            }),
+        })
+    }
+}
@@ -0,0 +1,117 @@
+//! URLs and body lines streamed from stdin (`--urls-from-file -`, `--body-lines -`)
+//!
+//! Lines are read on their own thread as the workers use them, so the input
+//! can be an endless generator. Each line is sent once; when stdin closes,
+//! the run ends the way it does at `--max-requests`.
+
+use crate::types::{StdinInput, StdinStream};
+use std::io::BufRead;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::{Mutex, mpsc};
+use tokio_util::sync::CancellationToken;
+
+/// Lines read ahead of the workers
+const READ_AHEAD: usize = 1024;
+
+pub struct LineStream {
+    input: StdinInput,
+    lines: Mutex<mpsc::Receiver<String>>,
+    /// Workers still sending; the last to run out of lines ends the run
+    workers: AtomicUsize,
+    cancel_token: CancellationToken,
+}
+
+impl LineStream {
+    /// Start reading stdin for `workers` workers
+    pub fn spawn(stream: &StdinStream, workers: usize, cancel_token: CancellationToken) -> Self {
+        let (tx, rx) = mpsc::channel(READ_AHEAD);
+        let input = stream.input;
+        let first = stream.first.clone();
+        std::thread::spawn(move || {
+            if tx.blocking_send(first).is_err() {
+                return;
+            }
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                if let Some(line) = accept(input, line)
+                    && tx.blocking_send(line).is_err()
+                {
+                    break;
+                }
+            }
+        });
+        Self {
+            input,
+            lines: Mutex::new(rx),
+            workers: AtomicUsize::new(workers),
+            cancel_token,
+        }
+    }
+
+    pub fn input(&self) -> StdinInput {
+        self.input
+    }
+
+    /// The next line, or `None` once stdin is drained or the run is cancelled.
+    /// A worker must stop at `None`.
+    pub async fn next(&self) -> Option<String> {
+        let line = tokio::select! {
+            line = async { self.lines.lock().await.recv().await } => line,
+            _ = self.cancel_token.cancelled() => return None,
+        };
+        if line.is_none() && self.workers.fetch_sub(1, Ordering::AcqRel) == 1 {
+            tracing::info!("stdin closed, stopping");
+            self.cancel_token.cancel();
+        }
+        line
+    }
+}
+
+/// URL lists skip blank lines and comments, as in a URL file; body lines are
+/// sent as they are
+fn accept(input: StdinInput, line: String) -> Option<String> {
+    match input {
+        StdinInput::Urls => {
+            let url = line.trim();
+            (!url.is_empty() && !url.starts_with('#')).then(|| url.to_string())
+        }
+        StdinInput::BodyLines => Some(line),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_streams_skip_blank_lines_and_comments() {
+        let urls = |line: &str| accept(StdinInput::Urls, line.to_string());
+        assert_eq!(urls("  http://a/1 ").as_deref(), Some("http://a/1"));
+        assert_eq!(urls(""), None);
+        assert_eq!(urls("# replayed from prod"), None);
+        assert_eq!(
+            accept(StdinInput::BodyLines, String::new()).as_deref(),
+            Some("")
+        );
+    }
+
+    #[tokio::test]
+    async fn last_worker_to_drain_the_stream_ends_the_run() {
+        let (tx, rx) = mpsc::channel(4);
+        let cancel_token = CancellationToken::new();
+        let stream = LineStream {
+            input: StdinInput::BodyLines,
+            lines: Mutex::new(rx),
+            workers: AtomicUsize::new(2),
+            cancel_token: cancel_token.clone(),
+        };
+        tx.send("a".to_string()).await.unwrap();
+        drop(tx);
+
+        assert_eq!(stream.next().await.as_deref(), Some("a"));
+        assert_eq!(stream.next().await, None);
+        assert!(!cancel_token.is_cancelled());
+        assert_eq!(stream.next().await, None);
+        assert!(cancel_token.is_cancelled());
+    }
+}
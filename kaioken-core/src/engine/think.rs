@@ -0,0 +1,75 @@
+//! Think time (`--think-time`, a scenario's `think_time`)
+//!
+//! Real users don't pause the same length of time after every page, and a
+//! fixed pause lines the VUs up into waves. Think times can be drawn from a
+//! range or a distribution instead, from the VU's own RNG so `--seed`
+//! replays them.
+
+use crate::types::ThinkTime;
+use rand::Rng;
+use std::f64::consts::TAU;
+use std::time::Duration;
+
+/// The next pause
+pub fn pause(think_time: &ThinkTime, rng: &mut impl Rng) -> Duration {
+    match *think_time {
+        ThinkTime::Fixed(pause) => pause,
+        ThinkTime::Uniform(min, max) => rng.random_range(min..=max),
+        ThinkTime::Normal(mean, std_dev) => {
+            // Box-Muller, with u1 kept off zero for the log
+            let u1 = 1.0 - rng.random::<f64>();
+            let u2 = rng.random::<f64>();
+            let z = (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos();
+            Duration::from_secs_f64((mean.as_secs_f64() + z * std_dev.as_secs_f64()).max(0.0))
+        }
+        ThinkTime::Exponential(mean) => {
+            let u = 1.0 - rng.random::<f64>();
+            Duration::from_secs_f64(-mean.as_secs_f64() * u.ln())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn mean_of(think_time: ThinkTime) -> (Duration, Vec<Duration>) {
+        let mut rng = StdRng::seed_from_u64(7);
+        let pauses: Vec<Duration> = (0..10_000).map(|_| pause(&think_time, &mut rng)).collect();
+        (
+            pauses.iter().sum::<Duration>() / pauses.len() as u32,
+            pauses,
+        )
+    }
+
+    #[test]
+    fn draws_pauses_from_the_distribution() {
+        let ms = Duration::from_millis;
+        let near = |mean: Duration, expected: Duration| mean.abs_diff(expected) < expected / 20;
+
+        let (mean, pauses) = mean_of(ThinkTime::Fixed(ms(500)));
+        assert!(pauses.iter().all(|&p| p == ms(500)));
+        assert_eq!(mean, ms(500));
+
+        let (mean, pauses) = mean_of(ThinkTime::Uniform(ms(1000), ms(3000)));
+        assert!(pauses.iter().all(|p| (ms(1000)..=ms(3000)).contains(p)));
+        assert!(near(mean, ms(2000)), "{:?}", mean);
+
+        let (mean, pauses) = mean_of(ThinkTime::Normal(ms(2000), ms(500)));
+        assert!(near(mean, ms(2000)), "{:?}", mean);
+        // About 95% within two standard deviations
+        let within = pauses
+            .iter()
+            .filter(|&&p| (ms(1000)..=ms(3000)).contains(&p))
+            .count();
+        assert!((9_300..=9_700).contains(&within), "{}", within);
+
+        let (mean, pauses) = mean_of(ThinkTime::Exponential(ms(2000)));
+        assert!(near(mean, ms(2000)), "{:?}", mean);
+        // Most pauses are shorter than the mean, a few much longer
+        assert!(pauses.iter().filter(|&&p| p < ms(2000)).count() > 6_000);
+        assert!(pauses.iter().any(|&p| p > ms(8000)));
+    }
+}
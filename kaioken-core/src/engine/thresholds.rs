@@ -11,14 +11,14 @@ pub fn evaluate_thresholds(
 }
 
 fn evaluate_threshold(threshold: &Threshold, snapshot: &StatsSnapshot) -> ThresholdResult {
-    let actual = get_metric_value(&threshold.metric, snapshot);
+    let actual = metric_value(&threshold.metric, snapshot);
     let passed = threshold.operator.evaluate(actual, threshold.value);
 
     ThresholdResult {
-        metric: threshold.metric.as_str().to_string(),
+        metric: threshold.metric.to_string(),
         condition: format!(
             "{} {} {}",
-            threshold.metric.as_str(),
+            threshold.metric,
             threshold.operator.as_str(),
             threshold.value
         ),
@@ -27,19 +27,24 @@ fn evaluate_threshold(threshold: &Threshold, snapshot: &StatsSnapshot) -> Thresh
     }
 }
 
-fn get_metric_value(metric: &ThresholdMetric, snapshot: &StatsSnapshot) -> f64 {
+/// The value a threshold on `metric` is checked against
+pub fn metric_value(metric: &ThresholdMetric, snapshot: &StatsSnapshot) -> f64 {
     match metric {
-        ThresholdMetric::P50LatencyMs => snapshot.latency_p50_us as f64 / 1000.0,
-        ThresholdMetric::P75LatencyMs => snapshot.latency_p75_us as f64 / 1000.0,
-        ThresholdMetric::P90LatencyMs => snapshot.latency_p90_us as f64 / 1000.0,
-        ThresholdMetric::P95LatencyMs => snapshot.latency_p95_us as f64 / 1000.0,
-        ThresholdMetric::P99LatencyMs => snapshot.latency_p99_us as f64 / 1000.0,
-        ThresholdMetric::P999LatencyMs => snapshot.latency_p999_us as f64 / 1000.0,
+        ThresholdMetric::LatencyPercentileMs(p) => {
+            let latency_us = snapshot.latency_percentile_us(*p as f64 / 1000.0);
+            latency_us.unwrap_or(0) as f64 / 1000.0
+        }
         ThresholdMetric::MeanLatencyMs => snapshot.latency_mean_us / 1000.0,
         ThresholdMetric::MaxLatencyMs => snapshot.latency_max_us as f64 / 1000.0,
         ThresholdMetric::ErrorRate => snapshot.error_rate,
         ThresholdMetric::Rps => snapshot.requests_per_sec,
         ThresholdMetric::CheckPassRate => snapshot.overall_check_pass_rate.unwrap_or(1.0),
+        ThresholdMetric::Apdex => snapshot.apdex.map_or(1.0, |apdex| apdex.score()),
+        ThresholdMetric::ExtractionFailureRate => snapshot.extraction_failure_rate(),
+        ThresholdMetric::UnexpectedContentType => snapshot
+            .content_types
+            .as_ref()
+            .map_or(0.0, |types| types.unexpected() as f64),
     }
 }
 
@@ -74,10 +79,14 @@ pub fn print_threshold_results(results: &[ThresholdResult]) {
 }
 
 fn format_metric_value(metric: &str, value: f64) -> String {
-    if metric.contains("latency") {
+    if metric.ends_with("_trend") {
+        format!("{:+.1}%", value)
+    } else if metric.contains("latency") {
         format!("{:.2}ms", value)
-    } else if metric == "error_rate" || metric == "check_pass_rate" {
+    } else if metric.ends_with("_rate") || metric == "apdex" {
         format!("{:.4}", value)
+    } else if metric == "unexpected_content_type" {
+        format!("{}", value)
     } else {
         format!("{:.2}", value)
     }
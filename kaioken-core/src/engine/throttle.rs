@@ -0,0 +1,112 @@
+use crate::types::{RequestResult, ThrottleStats};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Backoff after a 429 or 503 without a usable Retry-After, doubling with
+/// each one in a row up to `MAX_BACKOFF`
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(32);
+
+/// `honor_retry_after`: VU workers wait out 429 and 503 responses the way a
+/// well-behaved client would, so a rate limiter can be tested for what it
+/// tells clients instead of simply being hammered. Counts what the server
+/// asked for; the aggregator reads them into each snapshot.
+#[derive(Debug, Default)]
+pub struct Throttle {
+    throttled: AtomicU64,
+    retry_after: AtomicU64,
+    waited_us: AtomicU64,
+}
+
+impl Throttle {
+    pub fn stats(&self) -> ThrottleStats {
+        ThrottleStats {
+            throttled: self.throttled.load(Ordering::Relaxed),
+            retry_after: self.retry_after.load(Ordering::Relaxed),
+            waited: Duration::from_micros(self.waited_us.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Forget warmup throttling
+    pub fn reset(&self) {
+        self.throttled.store(0, Ordering::Relaxed);
+        self.retry_after.store(0, Ordering::Relaxed);
+        self.waited_us.store(0, Ordering::Relaxed);
+    }
+
+    /// How long to wait after `result`, if the server throttled it. `streak`
+    /// counts throttled responses in a row, for the backoff when the server
+    /// doesn't say.
+    pub fn backoff(&self, result: &RequestResult, streak: &mut u32) -> Option<Duration> {
+        if !matches!(result.status, Some(429 | 503)) {
+            *streak = 0;
+            return None;
+        }
+        self.throttled.fetch_add(1, Ordering::Relaxed);
+        let wait = match result.retry_after {
+            Some(wait) => {
+                self.retry_after.fetch_add(1, Ordering::Relaxed);
+                wait
+            }
+            None => (FIRST_BACKOFF * 2u32.saturating_pow(*streak)).min(MAX_BACKOFF),
+        };
+        *streak = streak.saturating_add(1);
+        Some(wait)
+    }
+
+    pub fn waited(&self, wait: Duration) {
+        self.waited_us
+            .fetch_add(wait.as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16, retry_after: Option<u64>) -> RequestResult {
+        let mut result = RequestResult::success(1_000, status, 0, None);
+        result.retry_after = retry_after.map(Duration::from_secs);
+        result
+    }
+
+    #[test]
+    fn follows_retry_after_and_backs_off_without_it() {
+        let throttle = Throttle::default();
+        let mut streak = 0;
+
+        assert_eq!(
+            throttle.backoff(&response(429, Some(7)), &mut streak),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            throttle.backoff(&response(503, None), &mut streak),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            throttle.backoff(&response(429, None), &mut streak),
+            Some(Duration::from_secs(4))
+        );
+        for _ in 0..10 {
+            throttle.backoff(&response(429, None), &mut streak);
+        }
+        assert_eq!(
+            throttle.backoff(&response(429, None), &mut streak),
+            Some(MAX_BACKOFF)
+        );
+
+        // Any other response ends the streak
+        assert_eq!(throttle.backoff(&response(200, None), &mut streak), None);
+        assert_eq!(throttle.backoff(&response(500, Some(5)), &mut streak), None);
+        assert_eq!(
+            throttle.backoff(&response(429, None), &mut streak),
+            Some(FIRST_BACKOFF)
+        );
+
+        throttle.waited(Duration::from_millis(1500));
+        let stats = throttle.stats();
+        assert_eq!(stats.throttled, 15);
+        assert_eq!(stats.retry_after, 1);
+        assert_eq!(stats.waited, Duration::from_millis(1500));
+    }
+}
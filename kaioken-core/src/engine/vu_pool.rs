@@ -0,0 +1,258 @@
+use crate::types::{VuAutoscale, VuScaleEvent, VuScalingStats};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// VUs added at once without `--vu-autoscale`
+const FIXED_STEP: u32 = 10;
+
+/// The VUs an arrival-rate executor hands iterations to. Without a policy
+/// it grows in steps of 10 up to `max_vus` and never shrinks; with
+/// `--vu-autoscale` it grows by the policy's step no faster than its
+/// cooldown, up to its cap, and sheds VUs that sat unused through the idle
+/// window, down to the VUs it started with.
+pub(super) struct VuPool {
+    semaphore: Arc<Semaphore>,
+    allocated: u32,
+    floor: u32,
+    cap: u32,
+    step: u32,
+    scaling: Option<Arc<VuScaling>>,
+    last_grown: Option<Instant>,
+    // Most VUs busy at once since `window` started
+    busiest: u32,
+    window: Instant,
+}
+
+impl VuPool {
+    pub(super) fn new(pre_allocated: u32, max_vus: u32) -> Self {
+        let allocated = pre_allocated.min(max_vus).max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(allocated as usize)),
+            allocated,
+            floor: allocated,
+            cap: max_vus.max(allocated),
+            step: FIXED_STEP,
+            scaling: None,
+            last_grown: None,
+            busiest: 0,
+            window: Instant::now(),
+        }
+    }
+
+    /// Follow `scaling`'s policy instead of the fixed ceiling
+    pub(super) fn autoscale(self, scaling: Option<Arc<VuScaling>>) -> Self {
+        let Some(scaling) = scaling else {
+            return self;
+        };
+        let mut pool = Self::new(self.floor, scaling.policy.cap);
+        pool.step = scaling.policy.step.max(1);
+        scaling.start(pool.allocated);
+        pool.scaling = Some(scaling);
+        pool
+    }
+
+    /// Most VUs the pool can grow to
+    pub(super) fn cap(&self) -> u32 {
+        self.cap
+    }
+
+    /// A free VU for the next iteration, growing the pool if it's allowed
+    /// to; None means the iteration is dropped
+    pub(super) fn acquire(&mut self) -> Option<OwnedSemaphorePermit> {
+        let permit = match self.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                if !self.grow() {
+                    return None;
+                }
+                self.semaphore.clone().try_acquire_owned().ok()?
+            }
+        };
+        self.busiest = self.busiest.max(self.busy());
+        Some(permit)
+    }
+
+    /// Shed VUs that went unused through the last idle window
+    pub(super) fn shed_idle(&mut self) {
+        let Some(idle) = self.scaling.as_ref().map(|s| s.policy.idle) else {
+            return;
+        };
+        if self.window.elapsed() < idle {
+            return;
+        }
+        let spare = (self.allocated - self.busiest)
+            .min(self.allocated - self.floor)
+            .min(self.step);
+        if spare > 0
+            && let Ok(permits) = self.semaphore.clone().try_acquire_many_owned(spare)
+        {
+            permits.forget();
+            self.resize(self.allocated - spare);
+        }
+        self.window = Instant::now();
+        self.busiest = self.busy();
+    }
+
+    fn grow(&mut self) -> bool {
+        if self.allocated >= self.cap {
+            return false;
+        }
+        if let (Some(scaling), Some(last)) = (&self.scaling, self.last_grown)
+            && last.elapsed() < scaling.policy.cooldown
+        {
+            return false;
+        }
+        let to_add = (self.cap - self.allocated).min(self.step);
+        self.semaphore.add_permits(to_add as usize);
+        self.resize(self.allocated + to_add);
+        self.last_grown = Some(Instant::now());
+        true
+    }
+
+    fn busy(&self) -> u32 {
+        self.allocated
+            .saturating_sub(self.semaphore.available_permits() as u32)
+    }
+
+    fn resize(&mut self, to: u32) {
+        tracing::debug!("Scaled VUs to {}", to);
+        if let Some(ref scaling) = self.scaling {
+            scaling.record(self.allocated, to);
+        }
+        self.allocated = to;
+    }
+}
+
+/// Pool size changes under `--vu-autoscale`, shared between the executor
+/// growing the pool and the aggregator reporting it
+#[derive(Debug)]
+pub struct VuScaling {
+    policy: VuAutoscale,
+    state: Mutex<ScalingState>,
+}
+
+#[derive(Debug, Default)]
+struct ScalingState {
+    allocated: u32,
+    peak: u32,
+    events: Vec<(Instant, u32, u32)>,
+}
+
+impl VuScaling {
+    pub fn new(policy: VuAutoscale) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(ScalingState::default()),
+        }
+    }
+
+    fn start(&self, allocated: u32) {
+        let mut state = self.state.lock().unwrap();
+        state.allocated = allocated;
+        state.peak = allocated;
+    }
+
+    fn record(&self, from: u32, to: u32) {
+        let mut state = self.state.lock().unwrap();
+        state.allocated = to;
+        state.peak = state.peak.max(to);
+        state.events.push((Instant::now(), from, to));
+    }
+
+    /// Events are placed by time since the measured run started, `elapsed` ago
+    pub fn stats(&self, elapsed: Duration) -> VuScalingStats {
+        let state = self.state.lock().unwrap();
+        VuScalingStats {
+            allocated: state.allocated,
+            peak: state.peak,
+            cap: self.policy.cap,
+            events: state
+                .events
+                .iter()
+                .map(|&(at, from, to)| VuScaleEvent {
+                    elapsed_ms: elapsed.saturating_sub(at.elapsed()).as_millis() as u64,
+                    from,
+                    to,
+                })
+                .collect(),
+        }
+    }
+
+    /// Forget warmup scaling; the pool keeps its size
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.peak = state.allocated;
+        state.events.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scaling(step: u32, cooldown: Duration, cap: u32, idle: Duration) -> Arc<VuScaling> {
+        Arc::new(VuScaling::new(VuAutoscale {
+            step,
+            cooldown,
+            cap,
+            idle,
+        }))
+    }
+
+    #[test]
+    fn fixed_pool_grows_to_max_vus() {
+        let mut pool = VuPool::new(2, 15);
+        let permits: Vec<_> = (0..15).map_while(|_| pool.acquire()).collect();
+        assert_eq!(permits.len(), 15);
+        assert!(pool.acquire().is_none());
+        assert_eq!(pool.cap(), 15);
+    }
+
+    #[test]
+    fn autoscaled_pool_grows_by_step_after_cooldown() {
+        let scaling = scaling(3, Duration::from_secs(60), 100, Duration::from_secs(60));
+        let mut pool = VuPool::new(1, 100).autoscale(Some(scaling.clone()));
+
+        let _first = pool.acquire().unwrap();
+        // Grows by one step, then waits out the cooldown
+        let grown: Vec<_> = (0..5).map_while(|_| pool.acquire()).collect();
+        assert_eq!(grown.len(), 3);
+
+        let stats = scaling.stats(Duration::from_secs(1));
+        assert_eq!(stats.allocated, 4);
+        assert_eq!(stats.peak, 4);
+        assert_eq!(stats.scale_ups(), 1);
+        assert_eq!(
+            (stats.events[0].from, stats.events[0].to),
+            (1, 4),
+            "{:?}",
+            stats.events
+        );
+    }
+
+    #[test]
+    fn idle_vus_are_shed_down_to_the_start() {
+        let scaling = scaling(2, Duration::ZERO, 10, Duration::ZERO);
+        let mut pool = VuPool::new(1, 10).autoscale(Some(scaling.clone()));
+        let permits: Vec<_> = (0..5).map_while(|_| pool.acquire()).collect();
+        assert_eq!(permits.len(), 5);
+        drop(permits);
+
+        // The busiest window still needed all 5
+        pool.shed_idle();
+        assert_eq!(scaling.stats(Duration::ZERO).allocated, 5);
+        for _ in 0..5 {
+            pool.shed_idle();
+        }
+        let stats = scaling.stats(Duration::ZERO);
+        assert_eq!(stats.allocated, 1);
+        assert_eq!(stats.peak, 5);
+        assert_eq!(stats.scale_downs(), 2);
+
+        scaling.reset();
+        let stats = scaling.stats(Duration::ZERO);
+        assert!(stats.events.is_empty());
+        assert_eq!(stats.peak, 1);
+    }
+}
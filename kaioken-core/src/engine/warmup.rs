@@ -0,0 +1,129 @@
+//! Detect the end of warmup from the results themselves (--auto-warmup)
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Length of each sample window
+const WINDOW: Duration = Duration::from_secs(1);
+/// Consecutive windows that have to agree
+const WINDOWS: usize = 5;
+/// Largest coefficient of variation (stddev / mean) still counted as stable
+const MAX_CV: f64 = 0.1;
+
+/// Splits the run into one-second windows and reports stability once
+/// throughput and mean latency have barely moved over the last few of them
+pub struct WarmupDetector {
+    window_start: Instant,
+    requests_at_start: u64,
+    latency_sum_at_start: f64,
+    // (requests/sec, mean latency in us) per finished window
+    windows: VecDeque<(f64, f64)>,
+}
+
+impl WarmupDetector {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            requests_at_start: 0,
+            latency_sum_at_start: 0.0,
+            windows: VecDeque::with_capacity(WINDOWS + 1),
+        }
+    }
+
+    /// Feed running totals; true once the last windows are stable
+    pub fn observe(&mut self, now: Instant, requests: u64, latency_sum_us: f64) -> bool {
+        let elapsed = now.duration_since(self.window_start);
+        if elapsed < WINDOW {
+            return false;
+        }
+
+        let count = requests.saturating_sub(self.requests_at_start);
+        let mean_latency = if count > 0 {
+            (latency_sum_us - self.latency_sum_at_start) / count as f64
+        } else {
+            0.0
+        };
+        self.windows
+            .push_back((count as f64 / elapsed.as_secs_f64(), mean_latency));
+        if self.windows.len() > WINDOWS {
+            self.windows.pop_front();
+        }
+        self.window_start = now;
+        self.requests_at_start = requests;
+        self.latency_sum_at_start = latency_sum_us;
+
+        self.windows.len() == WINDOWS
+            && is_stable(self.windows.iter().map(|w| w.0))
+            && is_stable(self.windows.iter().map(|w| w.1))
+    }
+}
+
+/// Low relative spread; never true for all-zero samples (no traffic yet)
+fn is_stable(values: impl Iterator<Item = f64> + Clone) -> bool {
+    let n = values.clone().count() as f64;
+    let mean = values.clone().sum::<f64>() / n;
+    if mean <= 0.0 {
+        return false;
+    }
+    let variance = values.map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    variance.sqrt() / mean <= MAX_CV
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed one window per (requests, mean latency) pair; returns the result
+    /// of each observation
+    fn feed(windows: &[(u64, f64)]) -> Vec<bool> {
+        let start = Instant::now();
+        let mut detector = WarmupDetector::new(start);
+        let (mut requests, mut latency_sum) = (0, 0.0);
+        windows
+            .iter()
+            .enumerate()
+            .map(|(i, &(count, latency))| {
+                requests += count;
+                latency_sum += count as f64 * latency;
+                detector.observe(start + WINDOW * (i as u32 + 1), requests, latency_sum)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn settles_after_steady_windows() {
+        let results = feed(&[
+            (100, 900.0),
+            (400, 600.0),
+            (1000, 300.0),
+            (1010, 310.0),
+            (990, 295.0),
+            (1005, 305.0),
+            (1000, 300.0),
+        ]);
+        // The ramp-up windows keep it unstable until five steady ones are in
+        assert_eq!(results, [false, false, false, false, false, false, true]);
+    }
+
+    #[test]
+    fn noisy_latency_never_settles() {
+        let results = feed(&[(1000, 300.0), (1000, 900.0)].repeat(5));
+        assert!(results.iter().all(|stable| !stable));
+    }
+
+    #[test]
+    fn partial_windows_are_ignored() {
+        let start = Instant::now();
+        let mut detector = WarmupDetector::new(start);
+        for tick in 1..10 {
+            let now = start + Duration::from_millis(tick * 100);
+            assert!(!detector.observe(now, tick * 100, tick as f64 * 100.0 * 300.0));
+        }
+        assert!(detector.windows.is_empty());
+    }
+
+    #[test]
+    fn no_traffic_is_not_stable() {
+        assert!(!is_stable([0.0; WINDOWS].into_iter()));
+    }
+}
@@ -0,0 +1,984 @@
+use crate::engine::chaos::{Chaos, Fault};
+use crate::engine::connection_cap::ConnectionCap;
+use crate::engine::csrf::CsrfState;
+use crate::engine::deadline::Budget;
+use crate::engine::duplicates::Duplicates;
+use crate::engine::endpoints::EndpointNamer;
+use crate::engine::extractions::ExtractionCounts;
+use crate::engine::health::WorkerHealth;
+use crate::engine::hosts::HostPicker;
+use crate::engine::outage::Outages;
+use crate::engine::proxies::ProxyPool;
+use crate::engine::revalidation::{Revalidation, ValidatorCache};
+use crate::engine::scenario_mix::ScenarioMix;
+use crate::engine::scheduler::RateLimiter;
+use crate::engine::seed;
+use crate::engine::shards::ResultSink;
+use crate::engine::stdin::LineStream;
+use crate::engine::think;
+use crate::engine::throttle::Throttle;
+use crate::http::{PreparedRequest, execute_prepared, execute_request, jsonrpc};
+#[cfg(feature = "plugins")]
+use crate::plugin::{PluginHost, PluginRequest};
+#[cfg(feature = "plugins")]
+use crate::types::ErrorKind;
+use crate::types::{
+    BodyCompression, Check, CheckCondition, CsrfConfig, ExtractionSource, FormField,
+    NetworkProfile, RequestResult, Scenario, StdinInput, ThinkTime, TracePropagation,
+};
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand_regex::Regex as RandRegex;
+use reqwest::{Client, Method};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Semaphore, mpsc};
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+pub struct Worker {
+    id: u32,
+    client: Client,
+    url: String,
+    method: Method,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    scenarios: Arc<Vec<Scenario>>,
+    total_weight: u32,
+    results: ResultSink,
+    cancel_token: CancellationToken,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    ramp_permits: Arc<Semaphore>,
+    think_time: Option<ThinkTime>,
+    checks: Arc<Vec<Check>>,
+    check_tx: Option<mpsc::Sender<CheckResult>>,
+    form_fields: Arc<Vec<FormField>>,
+    basic_auth: Option<(String, Option<String>)>,
+    // v1.3.0 features
+    url_list: Option<Arc<Vec<String>>>,
+    body_lines: Option<Arc<Vec<String>>>,
+    rand_regex_generator: Option<RandRegex>,
+    jsonrpc: bool,
+    // Requests without placeholders, prebuilt once (None = build per request)
+    prepared: Option<PreparedRequest>,
+    prepared_scenarios: Vec<Option<PreparedRequest>>,
+    // Names results for the per-endpoint breakdown (None = single endpoint)
+    endpoints: Option<Arc<EndpointNamer>>,
+    // Weighted `[[target.hosts]]` requests are split across
+    hosts: Option<Arc<HostPicker>>,
+    trace: Option<TracePropagation>,
+    compression: Option<BodyCompression>,
+    id_header: Option<Arc<str>>,
+    #[cfg(feature = "plugins")]
+    plugins: Option<Arc<PluginHost>>,
+    chaos: Option<Arc<Chaos>>,
+    throttle: Option<Arc<Throttle>>,
+    connection_cap: Option<Arc<ConnectionCap>>,
+    duplicates: Option<Arc<Duplicates>>,
+    revalidation: Option<Arc<Revalidation>>,
+    outages: Option<Arc<Outages>>,
+    extraction_counts: Option<Arc<ExtractionCounts>>,
+    scenario_mix: Option<Arc<ScenarioMix>>,
+    health: Option<Arc<WorkerHealth>>,
+    network: Option<NetworkProfile>,
+    budget: Option<Budget>,
+    proxies: Option<Arc<ProxyPool>>,
+    csrf: Option<CsrfConfig>,
+    stdin: Option<Arc<LineStream>>,
+    seed: Option<u64>,
+}
+
+/// URL, method, headers and body of a request built per request
+pub(super) type BuiltRequest = (String, Method, Vec<(String, String)>, Option<String>);
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: String,
+    pub passed: bool,
+}
+
+impl Worker {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u32,
+        client: Client,
+        url: String,
+        method: Method,
+        headers: Vec<(String, String)>,
+        body: Option<String>,
+        scenarios: Arc<Vec<Scenario>>,
+        results: ResultSink,
+        cancel_token: CancellationToken,
+        rate_limiter: Option<Arc<RateLimiter>>,
+        ramp_permits: Arc<Semaphore>,
+        think_time: Option<ThinkTime>,
+        checks: Arc<Vec<Check>>,
+        check_tx: Option<mpsc::Sender<CheckResult>>,
+        form_fields: Arc<Vec<FormField>>,
+        basic_auth: Option<(String, Option<String>)>,
+        url_list: Option<Arc<Vec<String>>>,
+        body_lines: Option<Arc<Vec<String>>>,
+        rand_regex_pattern: Option<&str>,
+        jsonrpc: bool,
+    ) -> Self {
+        let total_weight: u32 = scenarios.iter().map(|s| s.weight).sum();
+
+        // Compile rand-regex pattern if provided
+        let rand_regex_generator = rand_regex_pattern.map(|pattern| {
+            RandRegex::compile(pattern, 100).expect("Invalid rand-regex-url pattern")
+        });
+
+        // The default target rotates through URL/body lists per request, so it
+        // can only be prebuilt when it is a single fixed request
+        let prepared =
+            if url_list.is_none() && body_lines.is_none() && rand_regex_generator.is_none() {
+                PreparedRequest::new(&method, &url, &headers, body.as_deref())
+            } else {
+                None
+            };
+        let prepared_scenarios = scenarios
+            .iter()
+            .map(PreparedRequest::for_scenario)
+            .collect();
+
+        Self {
+            id,
+            client,
+            url,
+            method,
+            headers,
+            body,
+            scenarios,
+            total_weight,
+            results,
+            cancel_token,
+            rate_limiter,
+            ramp_permits,
+            think_time,
+            checks,
+            check_tx,
+            form_fields,
+            basic_auth,
+            url_list,
+            body_lines,
+            rand_regex_generator,
+            jsonrpc,
+            prepared,
+            prepared_scenarios,
+            endpoints: None,
+            hosts: None,
+            trace: None,
+            compression: None,
+            id_header: None,
+            #[cfg(feature = "plugins")]
+            plugins: None,
+            chaos: None,
+            throttle: None,
+            connection_cap: None,
+            duplicates: None,
+            revalidation: None,
+            outages: None,
+            extraction_counts: None,
+            scenario_mix: None,
+            health: None,
+            network: None,
+            budget: None,
+            proxies: None,
+            csrf: None,
+            stdin: None,
+            seed: None,
+        }
+    }
+
+    pub fn with_endpoints(mut self, endpoints: Option<Arc<EndpointNamer>>) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Split requests across weighted hosts; routed requests are built per
+    /// request, since each may go to a different host
+    pub fn with_hosts(mut self, hosts: Option<Arc<HostPicker>>) -> Self {
+        if hosts.is_some() {
+            self.prepared = None;
+            self.prepared_scenarios.iter_mut().for_each(|p| *p = None);
+        }
+        self.hosts = hosts;
+        self
+    }
+
+    pub fn with_trace(mut self, trace: Option<TracePropagation>) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    /// Compress request bodies; prebuilt ones are compressed here, once
+    pub fn with_compression(mut self, compression: Option<BodyCompression>) -> Self {
+        self.prepared = self.prepared.take().map(|p| p.compressed(compression));
+        for prepared in self.prepared_scenarios.iter_mut() {
+            *prepared = prepared.take().map(|p| p.compressed(compression));
+        }
+        self.compression = compression;
+        self
+    }
+
+    /// Send a unique ID in this header and check responses echo it back
+    pub fn with_request_id(mut self, header: Option<Arc<str>>) -> Self {
+        self.id_header = header;
+        self
+    }
+
+    /// Run requests through WASM plugin hooks; hooked requests are built
+    /// per request, since a plugin can change any of them
+    #[cfg(feature = "plugins")]
+    pub fn with_plugins(mut self, plugins: Option<Arc<PluginHost>>) -> Self {
+        if plugins.is_some() {
+            self.prepared = None;
+            self.prepared_scenarios.iter_mut().for_each(|p| *p = None);
+        }
+        self.plugins = plugins;
+        self
+    }
+
+    pub fn with_chaos(mut self, chaos: Option<Arc<Chaos>>) -> Self {
+        self.chaos = chaos;
+        self
+    }
+
+    /// Share a capped set of connections with the other VUs
+    pub fn with_connection_cap(mut self, connection_cap: Option<Arc<ConnectionCap>>) -> Self {
+        self.connection_cap = connection_cap;
+        self
+    }
+
+    /// Back off after 429 and 503 responses (`honor_retry_after`)
+    pub fn with_throttle(mut self, throttle: Option<Arc<Throttle>>) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Send some requests twice under one idempotency key (`--duplicate-rate`);
+    /// those are built per request to carry the key
+    pub fn with_duplicates(mut self, duplicates: Option<Arc<Duplicates>>) -> Self {
+        if duplicates.is_some() {
+            self.prepared = None;
+            self.prepared_scenarios.iter_mut().for_each(|p| *p = None);
+        }
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Count how often each scenario extraction finds its value
+    pub fn with_extractions(mut self, counts: Option<Arc<ExtractionCounts>>) -> Self {
+        self.extraction_counts = counts;
+        self
+    }
+
+    /// Count the requests sent for each weighted scenario
+    pub fn with_scenario_mix(mut self, scenario_mix: Option<Arc<ScenarioMix>>) -> Self {
+        self.scenario_mix = scenario_mix;
+        self
+    }
+
+    /// Revalidate what earlier responses said to (`--conditional-requests`);
+    /// conditional headers vary per request, so nothing is prebuilt
+    pub fn with_revalidation(mut self, revalidation: Option<Arc<Revalidation>>) -> Self {
+        if revalidation.is_some() {
+            self.prepared = None;
+            self.prepared_scenarios.iter_mut().for_each(|p| *p = None);
+        }
+        self.revalidation = revalidation;
+        self
+    }
+
+    /// Hold off while the target is down (`--pause-on-errors`)
+    pub fn with_outages(mut self, outages: Option<Arc<Outages>>) -> Self {
+        self.outages = outages;
+        self
+    }
+
+    /// Count this worker's requests and failure streaks
+    pub fn with_health(mut self, health: Option<Arc<WorkerHealth>>) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Slow every response down to a client network (`--network-profile`)
+    pub fn with_network(mut self, network: Option<NetworkProfile>) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Retry failed requests within a total deadline (`--retries`, `--deadline`)
+    pub fn with_budget(mut self, budget: Option<Budget>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Carry a CSRF token from safe requests to mutating ones; requests are
+    /// then built per request, since the token changes
+    pub fn with_csrf(mut self, csrf: Option<CsrfConfig>) -> Self {
+        if csrf.is_some() {
+            self.prepared = None;
+            self.prepared_scenarios.iter_mut().for_each(|p| *p = None);
+        }
+        self.csrf = csrf;
+        self
+    }
+
+    /// Draw random choices from a generator seeded with `seed` (`--seed`)
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Take URLs or body lines from stdin, one per request, stopping when
+    /// it closes
+    pub fn with_stdin(mut self, stdin: Option<Arc<LineStream>>) -> Self {
+        if stdin.is_some() {
+            self.prepared = None;
+        }
+        self.stdin = stdin;
+        self
+    }
+
+    /// Send through `--proxy-list` and per-scenario proxies
+    pub fn with_proxies(mut self, proxies: Option<Arc<ProxyPool>>) -> Self {
+        self.proxies = proxies;
+        self
+    }
+
+    pub async fn run(self) {
+        // Wait for ramp-up activation
+        let _permit = self.ramp_permits.acquire().await.unwrap();
+        tracing::debug!("Worker {} activated", self.id);
+
+        let mut request_counter: u64 = 0;
+        let base_request_id = (self.id as u64) * 1_000_000_000;
+        let use_scenarios = !self.scenarios.is_empty();
+
+        // Determine if we need to capture body (for checks, extractions or JSON-RPC errors)
+        let has_body_checks = self.checks.iter().any(|c| {
+            matches!(
+                c.condition,
+                CheckCondition::BodyContains(_)
+                    | CheckCondition::BodyNotContains(_)
+                    | CheckCondition::BodyMatches(_)
+            )
+        });
+        let has_extractions =
+            use_scenarios && self.scenarios.iter().any(|s| !s.extractions.is_empty());
+        #[cfg(feature = "plugins")]
+        let has_body_checks = has_body_checks
+            || self
+                .plugins
+                .as_ref()
+                .is_some_and(|plugins| plugins.has_response_hooks());
+        let capture_body =
+            has_body_checks || has_extractions || self.jsonrpc || self.csrf.is_some();
+
+        // Per-worker extracted values storage
+        let mut extracted_values: HashMap<String, String> = HashMap::new();
+        let mut csrf = self.csrf.clone().map(CsrfState::new);
+        let mut validators = self
+            .revalidation
+            .as_ref()
+            .map(|_| ValidatorCache::default());
+        // Throttled responses in a row, for the backoff
+        let mut throttle_streak = 0;
+        let mut rng = seed::rng(self.seed, self.id as u64);
+
+        loop {
+            if self.cancel_token.is_cancelled() {
+                break;
+            }
+
+            if let Some(ref outages) = self.outages
+                && !outages.wait(&self.cancel_token).await
+            {
+                break;
+            }
+
+            // Acquire rate limit permit if configured
+            if let Some(ref limiter) = self.rate_limiter {
+                tokio::select! {
+                    _ = limiter.acquire() => {}
+                    _ = self.cancel_token.cancelled() => break,
+                }
+            }
+
+            if self.cancel_token.is_cancelled() {
+                break;
+            }
+
+            request_counter += 1;
+            let request_id = base_request_id + request_counter;
+            let timestamp_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+
+            let scenario = use_scenarios.then(|| self.select_scenario(request_counter));
+            let streamed = match (scenario, &self.stdin) {
+                (None, Some(stdin)) => match stdin.next().await {
+                    Some(line) => Some((stdin.input(), line)),
+                    None => break,
+                },
+                _ => None,
+            };
+            let prepared = match scenario {
+                Some(idx) => self.prepared_scenarios[idx].as_ref(),
+                None => self.prepared.as_ref(),
+            };
+            let extractions = scenario.map_or(&[][..], |idx| &self.scenarios[idx].extractions);
+            let client = match self.proxies {
+                Some(ref proxies) => proxies.client(Some(self.id), scenario, &self.client),
+                None => &self.client,
+            };
+
+            // Prepare form data and basic auth for the request
+            let form_data = if !self.form_fields.is_empty() {
+                Some(self.form_fields.as_slice())
+            } else {
+                None
+            };
+            let basic_auth_ref = self
+                .basic_auth
+                .as_ref()
+                .map(|(u, p)| (u.as_str(), p.as_deref()));
+
+            let fault = self
+                .chaos
+                .as_ref()
+                .map_or_else(Fault::default, |c| c.roll(&mut rng));
+            if let Some(delay) = fault.delay {
+                tokio::select! {
+                    _ = sleep(delay) => {}
+                    _ = self.cancel_token.cancelled() => break,
+                }
+            }
+
+            // With a connection cap, the request waits for one to be free
+            let connection = match self.connection_cap {
+                Some(ref cap) => match cap.acquire(&self.cancel_token).await {
+                    Some(permit) => Some(permit),
+                    None => break,
+                },
+                None => None,
+            };
+
+            // Second copies of duplicated requests, recorded after the first
+            let mut copies = Vec::new();
+            let outcome = if let Some(prepared) = prepared {
+                // Prebuilt requests have no placeholders, so the template is the URL
+                let (method, url) = match scenario {
+                    Some(idx) => (&self.scenarios[idx].method, &self.scenarios[idx].url),
+                    None => (&self.method, &self.url),
+                };
+                if fault.reset {
+                    self.reset_connection(method, url).await;
+                    None
+                } else {
+                    let request = || {
+                        execute_prepared(
+                            client,
+                            prepared,
+                            form_data,
+                            basic_auth_ref,
+                            capture_body,
+                            self.trace,
+                            self.id_header.as_deref(),
+                            None, // No latency correction for closed-loop mode
+                        )
+                    };
+                    self.attempt(fault.abort_after, request)
+                        .await
+                        .map(|mut result| {
+                            result.endpoint =
+                                self.endpoints.as_ref().map(|namer| namer.name(method, url));
+                            self.results.log_slow(
+                                &result,
+                                method,
+                                url,
+                                self.scenario_name(scenario),
+                            );
+                            (result, Vec::new())
+                        })
+                }
+            } else {
+                let mut request = self.build_request(
+                    &mut rng,
+                    scenario,
+                    request_counter,
+                    request_id,
+                    timestamp_ms,
+                    &extracted_values,
+                );
+                if let Some((input, line)) = streamed {
+                    let line = interpolate_vars(&line, request_id, timestamp_ms, &extracted_values);
+                    match input {
+                        StdinInput::Urls => request.0 = line,
+                        StdinInput::BodyLines => request.3 = Some(line),
+                    }
+                }
+                if let Some(ref csrf) = csrf {
+                    csrf.inject(&request.1, &mut request.2);
+                }
+                #[cfg(feature = "plugins")]
+                let request = match self.hook_request(request) {
+                    Ok(request) => request,
+                    Err(_) => {
+                        // The plugin has logged why; the request isn't sent
+                        if !self
+                            .results
+                            .send(&RequestResult::error(0, ErrorKind::Plugin))
+                        {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                let (url, method, mut headers, body) = request;
+                let (url, host) = self.route(url, &mut headers, &mut rng);
+                let duplicate = self.duplicate(&mut rng, &mut headers);
+                let conditional = validators
+                    .as_ref()
+                    .is_some_and(|cache| cache.inject(&method, &url, &mut headers));
+                if fault.reset {
+                    self.reset_connection(&method, &url).await;
+                    None
+                } else {
+                    let request = || {
+                        execute_request(
+                            client,
+                            &url,
+                            &method,
+                            &headers,
+                            body.as_deref(),
+                            self.compression,
+                            form_data,
+                            basic_auth_ref,
+                            capture_body || duplicate || validators.is_some(),
+                            self.trace,
+                            self.id_header.as_deref(),
+                            None, // No latency correction for closed-loop mode
+                        )
+                    };
+                    let result = if duplicate {
+                        // Both copies go out at once, like a client retrying
+                        // before the first answer arrived
+                        let (result, mut copy) =
+                            tokio::join!(self.attempt(fault.abort_after, &request), request());
+                        if let (Some(result), Some(duplicates)) = (&result, &self.duplicates) {
+                            duplicates.compare(result, &copy);
+                            copy.host = host.clone();
+                            copy.endpoint = self
+                                .endpoints
+                                .as_ref()
+                                .map(|namer| namer.name(&method, &url));
+                        }
+                        copies.push(copy);
+                        result
+                    } else {
+                        self.attempt(fault.abort_after, request).await
+                    };
+                    match result {
+                        Some(mut result) => {
+                            if let Some(ref mut csrf) = csrf {
+                                csrf.observe(&method, &result);
+                            }
+                            if let (Some(cache), Some(revalidation)) =
+                                (&mut validators, &self.revalidation)
+                            {
+                                cache.observe(&method, &url, &result);
+                                revalidation.record(conditional, &result);
+                            }
+                            result.host = host;
+                            result.endpoint = self
+                                .endpoints
+                                .as_ref()
+                                .map(|namer| namer.name(&method, &url));
+                            self.results.log_slow(
+                                &result,
+                                &method,
+                                &url,
+                                self.scenario_name(scenario),
+                            );
+                            #[cfg(feature = "plugins")]
+                            self.hook_response(&method, &url, &result).await;
+                            Some((result, headers))
+                        }
+                        None => None,
+                    }
+                }
+            };
+            drop(connection);
+            // Chaos reset or abandoned the request, so there is nothing to record
+            let Some((mut result, headers)) = outcome else {
+                if !self.think(scenario, &mut rng).await {
+                    break;
+                }
+                continue;
+            };
+            if !self.slow_down(&mut result).await {
+                break;
+            }
+            if self.jsonrpc {
+                jsonrpc::classify(&mut result);
+            }
+            if let (Some(mix), Some(idx)) = (&self.scenario_mix, scenario) {
+                mix.record(idx);
+            }
+
+            // Perform extractions if configured and request succeeded
+            if !extractions.is_empty() && result.status.is_some() {
+                let body_str = result.body.as_deref().unwrap_or("");
+                for (index, extraction) in extractions.iter().enumerate() {
+                    let value = extract_value(&extraction.source, body_str, &headers);
+                    if let (Some(counts), Some(idx)) = (&self.extraction_counts, scenario) {
+                        counts.record(idx, index, value.is_some());
+                    }
+                    if let Some(value) = value {
+                        extracted_values.insert(extraction.name.clone(), value);
+                    }
+                }
+            }
+
+            // Evaluate checks if configured
+            if !self.checks.is_empty()
+                && let Some(ref check_tx) = self.check_tx
+            {
+                let body_str = result.body.as_deref().unwrap_or("");
+                for check in self.checks.iter() {
+                    let passed = check.condition.evaluate(result.status, body_str);
+                    let _ = check_tx
+                        .send(CheckResult {
+                            name: check.name.clone(),
+                            passed,
+                        })
+                        .await;
+                }
+            }
+
+            if let Some(ref health) = self.health {
+                health.record(self.id, &result);
+            }
+            if let Some(ref outages) = self.outages {
+                outages.record(&result);
+            }
+
+            if !self.results.send(&result) {
+                break;
+            }
+            if !copies.iter().all(|copy| self.results.send(copy)) {
+                break;
+            }
+
+            if !self.back_off(&result, &mut throttle_streak).await {
+                break;
+            }
+
+            if !self.think(scenario, &mut rng).await {
+                break;
+            }
+        }
+
+        tracing::debug!("Worker {} stopped", self.id);
+    }
+
+    /// Whether to send this request twice, giving it the idempotency key both
+    /// copies share. A key the request already has is kept.
+    fn duplicate(&self, rng: &mut StdRng, headers: &mut Vec<(String, String)>) -> bool {
+        let Some(ref duplicates) = self.duplicates else {
+            return false;
+        };
+        let Some(key) = duplicates.roll(rng) else {
+            return false;
+        };
+        let header = duplicates.header();
+        if !headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case(header))
+        {
+            headers.push((header.to_string(), key));
+        }
+        true
+    }
+
+    /// Think time - pause between requests, the scenario's if it has its
+    /// own. False once cancelled.
+    async fn think(&self, scenario: Option<usize>, rng: &mut StdRng) -> bool {
+        let think_time = scenario
+            .and_then(|idx| self.scenarios[idx].think_time)
+            .or(self.think_time);
+        if let Some(ref think_time) = think_time {
+            tokio::select! {
+                _ = sleep(think::pause(think_time, rng)) => {}
+                _ = self.cancel_token.cancelled() => return false,
+            }
+        }
+        true
+    }
+
+    /// Hold the response as long as the network profile would have taken
+    /// longer, counting it in the latency. False once cancelled.
+    async fn slow_down(&self, result: &mut RequestResult) -> bool {
+        if let Some(ref network) = self.network
+            && result.status.is_some()
+        {
+            let delay = network.delay(result.bytes_sent, result.bytes_received);
+            tokio::select! {
+                _ = sleep(delay) => {}
+                _ = self.cancel_token.cancelled() => return false,
+            }
+            result.latency_us += delay.as_micros() as u64;
+        }
+        true
+    }
+
+    /// Wait as long as the server asked after throttling `result`. False
+    /// once cancelled.
+    async fn back_off(&self, result: &RequestResult, streak: &mut u32) -> bool {
+        let Some(ref throttle) = self.throttle else {
+            return true;
+        };
+        let Some(wait) = throttle.backoff(result, streak) else {
+            return true;
+        };
+        let started = Instant::now();
+        let finished = tokio::select! {
+            _ = sleep(wait) => true,
+            _ = self.cancel_token.cancelled() => false,
+        };
+        throttle.waited(started.elapsed());
+        finished
+    }
+
+    /// Send a request, retried within the budget if there is one
+    async fn attempt<Fut>(
+        &self,
+        abort_after: Option<Duration>,
+        mut request: impl FnMut() -> Fut,
+    ) -> Option<RequestResult>
+    where
+        Fut: Future<Output = RequestResult>,
+    {
+        match self.budget {
+            Some(ref budget) => {
+                budget
+                    .run(&self.cancel_token, || self.send(abort_after, request()))
+                    .await
+            }
+            None => self.send(abort_after, request()).await,
+        }
+    }
+
+    /// Await a request, unless chaos abandons it first
+    async fn send(
+        &self,
+        abort_after: Option<Duration>,
+        request: impl Future<Output = RequestResult>,
+    ) -> Option<RequestResult> {
+        match self.chaos {
+            Some(ref chaos) => chaos.run(abort_after, request).await,
+            None => Some(request.await),
+        }
+    }
+
+    async fn reset_connection(&self, method: &Method, url: &str) {
+        if let Some(ref chaos) = self.chaos {
+            chaos.reset(method, url).await;
+        }
+    }
+
+    /// Pass a built request through the plugins' `on_request` hooks
+    #[cfg(feature = "plugins")]
+    fn hook_request(&self, request: BuiltRequest) -> Result<BuiltRequest, String> {
+        let Some(ref plugins) = self.plugins else {
+            return Ok(request);
+        };
+        if !plugins.has_request_hooks() {
+            return Ok(request);
+        }
+        let (url, method, headers, body) = request;
+        let hooked = plugins.on_request(PluginRequest {
+            method: method.to_string(),
+            url,
+            headers,
+            body,
+        })?;
+        let method = Method::from_bytes(hooked.method.as_bytes()).map_err(|e| e.to_string())?;
+        Ok((hooked.url, method, hooked.headers, hooked.body))
+    }
+
+    /// Send the plugins' `on_response` verdicts on as checks
+    #[cfg(feature = "plugins")]
+    async fn hook_response(&self, method: &Method, url: &str, result: &RequestResult) {
+        if let Some(ref plugins) = self.plugins
+            && plugins.has_response_hooks()
+            && let Some(ref check_tx) = self.check_tx
+        {
+            for (name, passed) in plugins.on_response(method.as_str(), url, result) {
+                let _ = check_tx.send(CheckResult { name, passed }).await;
+            }
+        }
+    }
+
+    /// Interpolate the URL, headers and body for a request that couldn't be prebuilt
+    fn build_request(
+        &self,
+        rng: &mut StdRng,
+        scenario: Option<usize>,
+        request_counter: u64,
+        request_id: u64,
+        timestamp_ms: u128,
+        extracted_values: &HashMap<String, String>,
+    ) -> BuiltRequest {
+        let interpolate = |s: &str| interpolate_vars(s, request_id, timestamp_ms, extracted_values);
+
+        if let Some(idx) = scenario {
+            let scenario = &self.scenarios[idx];
+            let headers = scenario
+                .headers
+                .iter()
+                .map(|(k, v)| (k.clone(), interpolate(v)))
+                .collect();
+            return (
+                interpolate(&scenario.url),
+                scenario.method.clone(),
+                headers,
+                scenario.body.as_deref().map(interpolate),
+            );
+        }
+
+        // URL selection priority: rand_regex_generator > url_list > self.url
+        let url = if let Some(ref generator) = self.rand_regex_generator {
+            interpolate(&rng.sample::<String, _>(generator))
+        } else if let Some(ref urls) = self.url_list {
+            interpolate(&urls[(request_counter as usize - 1) % urls.len()])
+        } else {
+            interpolate(&self.url)
+        };
+
+        let headers = self
+            .headers
+            .iter()
+            .map(|(k, v)| (k.clone(), interpolate(v)))
+            .collect();
+
+        // Body selection: body_lines takes priority over self.body
+        let body = if let Some(ref lines) = self.body_lines {
+            Some(interpolate(
+                &lines[(request_counter as usize - 1) % lines.len()],
+            ))
+        } else {
+            self.body.as_deref().map(interpolate)
+        };
+
+        (url, self.method.clone(), headers, body)
+    }
+
+    /// `url` sent to one of the weighted hosts, if any, and the host's name
+    fn route(
+        &self,
+        url: String,
+        headers: &mut Vec<(String, String)>,
+        rng: &mut StdRng,
+    ) -> (String, Option<String>) {
+        match self.hosts {
+            Some(ref hosts) => {
+                let (url, host) = hosts.route(&url, headers, rng);
+                (url, Some(host.to_string()))
+            }
+            None => (url, None),
+        }
+    }
+
+    fn scenario_name(&self, scenario: Option<usize>) -> Option<&str> {
+        scenario.map(|idx| self.scenarios[idx].name.as_str())
+    }
+
+    fn select_scenario(&self, counter: u64) -> usize {
+        if self.scenarios.len() == 1 {
+            return 0;
+        }
+
+        // Simple weighted selection using counter as seed for deterministic distribution
+        let roll = (counter % self.total_weight as u64) as u32;
+        let mut cumulative = 0u32;
+
+        for (idx, scenario) in self.scenarios.iter().enumerate() {
+            cumulative += scenario.weight;
+            if roll < cumulative {
+                return idx;
+            }
+        }
+
+        // Fallback (shouldn't happen)
+        0
+    }
+}
+
+fn interpolate_vars(
+    s: &str,
+    request_id: u64,
+    timestamp_ms: u128,
+    extracted: &HashMap<String, String>,
+) -> String {
+    let mut result = s
+        .replace("${REQUEST_ID}", &request_id.to_string())
+        .replace("${TIMESTAMP_MS}", &timestamp_ms.to_string());
+
+    // Replace extracted variables
+    for (name, value) in extracted {
+        let pattern = format!("${{{}}}", name);
+        result = result.replace(&pattern, value);
+    }
+
+    result
+}
+
+pub(super) fn extract_value(
+    source: &ExtractionSource,
+    body: &str,
+    _headers: &[(String, String)],
+) -> Option<String> {
+    match source {
+        ExtractionSource::JsonPath(path) => {
+            use jsonpath_rust::JsonPath;
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(body) {
+                // Use the JsonPath trait method on Value
+                let results = json.query(path);
+                if let Ok(values) = results
+                    && let Some(first) = values.first()
+                {
+                    return match first {
+                        serde_json::Value::String(s) => Some(s.clone()),
+                        serde_json::Value::Number(n) => Some(n.to_string()),
+                        serde_json::Value::Bool(b) => Some(b.to_string()),
+                        serde_json::Value::Null => Some("null".to_string()),
+                        other => Some(other.to_string()),
+                    };
+                }
+            }
+            None
+        }
+        ExtractionSource::Header(name) => {
+            // Headers would need to be passed from execute_request
+            // For now, this is a placeholder
+            let _ = name;
+            None
+        }
+        ExtractionSource::Regex(pattern, group) => {
+            if let Ok(re) = regex_lite::Regex::new(pattern)
+                && let Some(caps) = re.captures(body)
+                && let Some(m) = caps.get(*group)
+            {
+                return Some(m.as_str().to_string());
+            }
+            None
+        }
+        ExtractionSource::Body => Some(body.to_string()),
+    }
+}
@@ -1,6 +1,6 @@
 use crate::engine::WsStats;
-use crate::types::{RunPhase, StatsSnapshot, WsMessageResult};
-use std::collections::HashMap;
+use crate::types::{RunPhase, StatsSnapshot, WsMessageResult, unix_ms_ago};
+use std::collections::{BTreeMap, HashMap};
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, watch};
 use tokio_util::sync::CancellationToken;
@@ -62,6 +62,9 @@ impl WsAggregator {
                             self.check_warmup_complete();
                             if self.warmup_complete {
                                 self.stats.record_message(&ws_result);
+                            } else if let Some(connect_time) = ws_result.connect_time_us {
+                                // Connections made during warmup stay open and are reported
+                                self.stats.record_connection(connect_time);
                             }
                         }
                         None => {
@@ -100,6 +103,7 @@ impl WsAggregator {
 
         StatsSnapshot {
             elapsed,
+            started_at_ms: unix_ms_ago(elapsed),
             is_websocket: true,
 
             // HTTP fields (zeroed for WS tests)
@@ -107,7 +111,10 @@ impl WsAggregator {
             successful: 0,
             failed: 0,
             bytes_received: 0,
+            bytes_sent: 0,
             rolling_rps: 0.0,
+            rolling_rx_bps: 0.0,
+            rolling_tx_bps: 0.0,
             requests_per_sec: 0.0,
             error_rate: 0.0,
             latency_min_us: 0,
@@ -120,8 +127,12 @@ impl WsAggregator {
             latency_p95_us: 0,
             latency_p99_us: 0,
             latency_p999_us: 0,
+            latency_percentiles: Vec::new(),
+            latency_buckets: Vec::new(),
+            latency_histogram: None,
             status_codes: HashMap::new(),
             errors: HashMap::new(),
+            jsonrpc_errors: HashMap::new(),
             timeline: Vec::new(),
             check_stats: HashMap::new(),
             overall_check_pass_rate: None,
@@ -129,6 +140,7 @@ impl WsAggregator {
             vus_active: 0,
             vus_max: 0,
             target_rate: 0,
+            results_dropped: 0,
 
             // Latency correction fields (not used for WS)
             latency_correction_enabled: false,
@@ -167,6 +179,44 @@ impl WsAggregator {
             ws_latency_p99_us: self.stats.message_latency_percentile(99.0),
             ws_connect_time_mean_us: self.stats.connect_time_mean(),
             ws_connect_time_p99_us: self.stats.connect_time_percentile(99.0),
+            ws_reconnects: self.stats.reconnects,
+            ws_reconnect_failures: self.stats.reconnect_failures,
+            ws_reconnect_time_mean_us: self.stats.reconnect_time_mean(),
+            ws_reconnect_time_p99_us: self.stats.reconnect_time_percentile(99.0),
+            ws_broadcast_expected: self.stats.broadcast_expected,
+            ws_broadcast_delivered: self.stats.broadcast_delivered,
+            ws_delivery_latency_p50_us: self.stats.delivery_latency_percentile(50.0),
+            ws_delivery_latency_p95_us: self.stats.delivery_latency_percentile(95.0),
+            ws_delivery_latency_p99_us: self.stats.delivery_latency_percentile(99.0),
+            ws_delivery_latency_max_us: self.stats.delivery_latency_max(),
+            ws_compression: self.stats.compression,
+            ws_close_codes: self.stats.close_codes.clone(),
+            quic: None,
+            endpoints: Vec::new(),
+            hosts: Vec::new(),
+            stages: Vec::new(),
+            upload: None,
+            request_ids: None,
+            failure_samples: Vec::new(),
+            slowest_traces: Vec::new(),
+            apdex: None,
+            connections: None,
+            connection_cap: None,
+            content_types: None,
+            chaos: None,
+            throttle: None,
+            bursts: None,
+            workers: None,
+            duplicates: None,
+            revalidation: None,
+            outages: Vec::new(),
+            failover: None,
+            extractions: Vec::new(),
+            scenario_mix: Vec::new(),
+            vu_scaling: None,
+            auto_warmup: None,
+            recent: None,
+            plugin_metrics: BTreeMap::new(),
         }
     }
 }
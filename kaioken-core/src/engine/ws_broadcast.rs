@@ -0,0 +1,304 @@
+use crate::engine::scheduler::message_rate_interval;
+use crate::types::{WsErrorKind, WsMessageResult};
+use crate::ws::{WsConnection, connect};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+/// Placeholder replaced with the delivery tag in broadcast message bodies
+pub const BROADCAST_TAG: &str = "${BROADCAST_TAG}";
+const TAG_PREFIX: &str = "kaioken-bc:";
+
+/// Connection settings shared by publishers and subscribers
+#[derive(Clone)]
+pub struct BroadcastTarget {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub subprotocols: Vec<String>,
+    pub compression: bool,
+    pub timeout: Duration,
+}
+
+/// Publishes tagged messages that subscribers use to measure delivery latency
+pub struct BroadcastPublisher {
+    id: u32,
+    target: BroadcastTarget,
+    message: String,
+    message_interval: Duration,
+    message_rate: Option<Arc<AtomicU32>>,
+    subscribers_connected: Arc<AtomicU32>,
+    result_tx: mpsc::Sender<WsMessageResult>,
+    cancel_token: CancellationToken,
+}
+
+/// Listens for tagged broadcasts and reports per-message delivery latency
+pub struct BroadcastSubscriber {
+    id: u32,
+    target: BroadcastTarget,
+    subscribers_connected: Arc<AtomicU32>,
+    result_tx: mpsc::Sender<WsMessageResult>,
+    cancel_token: CancellationToken,
+}
+
+impl BroadcastPublisher {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u32,
+        target: BroadcastTarget,
+        message: String,
+        message_interval: Duration,
+        message_rate: Option<Arc<AtomicU32>>,
+        subscribers_connected: Arc<AtomicU32>,
+        result_tx: mpsc::Sender<WsMessageResult>,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            id,
+            target,
+            message,
+            message_interval,
+            message_rate,
+            subscribers_connected,
+            result_tx,
+            cancel_token,
+        }
+    }
+
+    pub async fn run(self) {
+        tracing::debug!("BroadcastPublisher {} starting", self.id);
+
+        let mut connection: Option<WsConnection> = None;
+        let mut pending_connect_time: Option<u64> = None;
+        let mut seq: u64 = 0;
+
+        loop {
+            if self.cancel_token.is_cancelled() {
+                break;
+            }
+
+            if connection.is_none() {
+                match connect_target(&self.target).await {
+                    Ok(conn) => {
+                        pending_connect_time = Some(conn.connect_time_us);
+                        connection = Some(conn);
+                    }
+                    Err(e) => {
+                        if self
+                            .result_tx
+                            .send(WsMessageResult::error(e))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                        tokio::select! {
+                            _ = sleep(Duration::from_secs(1)) => {}
+                            _ = self.cancel_token.cancelled() => break,
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            // Message-rate stages: hold off sending while the staged rate is zero
+            let message_interval = match self.message_rate {
+                Some(ref rate) => match message_rate_interval(rate.load(Ordering::Relaxed)) {
+                    Some(interval) => interval,
+                    None => {
+                        tokio::select! {
+                            _ = sleep(Duration::from_millis(100)) => {}
+                            _ = self.cancel_token.cancelled() => break,
+                        }
+                        continue;
+                    }
+                },
+                None => self.message_interval,
+            };
+
+            let conn = connection.as_mut().unwrap();
+
+            seq += 1;
+            let tag = broadcast_tag(self.id, seq);
+            let message = self
+                .message
+                .replace(BROADCAST_TAG, &tag)
+                .replace("${MESSAGE_ID}", &seq.to_string());
+            // Every subscriber connected right now should receive this message
+            let fanout = self.subscribers_connected.load(Ordering::Relaxed);
+
+            let start = Instant::now();
+            let mut result = match conn.send(&message).await {
+                Ok(()) => WsMessageResult::success(
+                    start.elapsed().as_micros() as u64,
+                    message.len() as u64,
+                    0,
+                )
+                .with_fanout(fanout),
+                Err(e) => WsMessageResult::error(e),
+            }
+            .with_wire_bytes(conn.take_wire_bytes());
+            if let Some(connect_time) = pending_connect_time.take() {
+                result = result.with_connect_time(connect_time);
+            }
+
+            let connection_lost = matches!(
+                result.error,
+                Some(WsErrorKind::ConnectionClosed) | Some(WsErrorKind::SendFailed)
+            );
+
+            if self.result_tx.send(result).await.is_err() {
+                break;
+            }
+
+            if connection_lost {
+                connection = None;
+                continue;
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed < message_interval {
+                tokio::select! {
+                    _ = sleep(message_interval - elapsed) => {}
+                    _ = self.cancel_token.cancelled() => break,
+                }
+            }
+        }
+
+        if let Some(conn) = connection {
+            let _ = conn.close().await;
+        }
+
+        tracing::debug!("BroadcastPublisher {} stopped", self.id);
+    }
+}
+
+impl BroadcastSubscriber {
+    pub fn new(
+        id: u32,
+        target: BroadcastTarget,
+        subscribers_connected: Arc<AtomicU32>,
+        result_tx: mpsc::Sender<WsMessageResult>,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            id,
+            target,
+            subscribers_connected,
+            result_tx,
+            cancel_token,
+        }
+    }
+
+    pub async fn run(self) {
+        tracing::debug!("BroadcastSubscriber {} starting", self.id);
+
+        'outer: loop {
+            if self.cancel_token.is_cancelled() {
+                break;
+            }
+
+            let mut conn = match connect_target(&self.target).await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    if self
+                        .result_tx
+                        .send(WsMessageResult::error(e))
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                    tokio::select! {
+                        _ = sleep(Duration::from_secs(1)) => {}
+                        _ = self.cancel_token.cancelled() => break,
+                    }
+                    continue;
+                }
+            };
+
+            let subscribed = WsMessageResult::subscribed(conn.connect_time_us);
+            if self.result_tx.send(subscribed).await.is_err() {
+                break;
+            }
+            self.subscribers_connected.fetch_add(1, Ordering::Relaxed);
+
+            loop {
+                let received = tokio::select! {
+                    r = conn.receive(Duration::from_secs(1)) => r,
+                    _ = self.cancel_token.cancelled() => {
+                        self.subscribers_connected.fetch_sub(1, Ordering::Relaxed);
+                        let _ = conn.close().await;
+                        break 'outer;
+                    }
+                };
+
+                match received {
+                    Ok(text) => {
+                        let Some(sent_us) = parse_broadcast_tag(&text) else {
+                            continue;
+                        };
+                        let latency_us = now_unix_us().saturating_sub(sent_us);
+                        let result = WsMessageResult::delivery(latency_us, text.len() as u64)
+                            .with_wire_bytes(conn.take_wire_bytes());
+                        if self.result_tx.send(result).await.is_err() {
+                            self.subscribers_connected.fetch_sub(1, Ordering::Relaxed);
+                            break 'outer;
+                        }
+                    }
+                    // Idle subscribers are normal; keep waiting
+                    Err(WsErrorKind::Timeout) => continue,
+                    Err(e) => {
+                        self.subscribers_connected.fetch_sub(1, Ordering::Relaxed);
+                        let result = WsMessageResult::error(e).with_close(conn.take_close());
+                        if self.result_tx.send(result).await.is_err() {
+                            break 'outer;
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+
+        tracing::debug!("BroadcastSubscriber {} stopped", self.id);
+    }
+}
+
+async fn connect_target(target: &BroadcastTarget) -> Result<WsConnection, WsErrorKind> {
+    connect(
+        &target.url,
+        &target.headers,
+        &target.subprotocols,
+        target.compression,
+        target.timeout,
+    )
+    .await
+}
+
+/// Delivery tag carrying the publisher, sequence number and send time
+pub fn broadcast_tag(publisher: u32, seq: u64) -> String {
+    format!("{}{}:{}:{}", TAG_PREFIX, publisher, seq, now_unix_us())
+}
+
+pub fn now_unix_us() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or(0)
+}
+
+/// Extract the publish timestamp from `kaioken-bc:<publisher>:<seq>:<sent_us>`
+pub fn parse_broadcast_tag(text: &str) -> Option<u64> {
+    let start = text.find(TAG_PREFIX)? + TAG_PREFIX.len();
+    let mut parts = text[start..].splitn(3, ':');
+    let _publisher = parts.next()?;
+    let _seq = parts.next()?;
+    let sent: String = parts
+        .next()?
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    sent.parse().ok()
+}
@@ -1,4 +1,4 @@
-use crate::types::{WsErrorKind, WsMessageResult};
+use crate::types::{WsCloseStat, WsErrorKind, WsMessageResult, WsWireBytes};
 use hdrhistogram::Histogram;
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
@@ -8,6 +8,10 @@ pub struct WsStats {
     message_histogram: Histogram<u64>,
     // Connection time histogram
     connect_histogram: Histogram<u64>,
+    // Reconnect time histogram (churn reconnects only)
+    reconnect_histogram: Histogram<u64>,
+    // Broadcast delivery latency histogram (publish -> subscriber receive)
+    delivery_histogram: Histogram<u64>,
 
     pub total_messages_sent: u64,
     pub total_messages_received: u64,
@@ -17,8 +21,18 @@ pub struct WsStats {
     pub connections_established: u64,
     pub connection_errors: u64,
     pub disconnects: u64,
+    pub reconnects: u64,
+    pub reconnect_failures: u64,
+
+    pub broadcast_expected: u64,
+    pub broadcast_delivered: u64,
+
+    // permessage-deflate byte counts, once any connection negotiated it
+    pub compression: Option<WsWireBytes>,
 
     pub errors: HashMap<WsErrorKind, u64>,
+    // Server-initiated closes by close code
+    pub close_codes: HashMap<u16, WsCloseStat>,
 
     start_time: Instant,
     rolling_window: Vec<(Instant, u64)>,
@@ -31,10 +45,16 @@ impl WsStats {
             .expect("Failed to create message histogram");
         let connect_histogram = Histogram::<u64>::new_with_bounds(1, 60_000_000, 3)
             .expect("Failed to create connect histogram");
+        let reconnect_histogram = Histogram::<u64>::new_with_bounds(1, 60_000_000, 3)
+            .expect("Failed to create reconnect histogram");
+        let delivery_histogram = Histogram::<u64>::new_with_bounds(1, 60_000_000, 3)
+            .expect("Failed to create delivery histogram");
 
         Self {
             message_histogram,
             connect_histogram,
+            reconnect_histogram,
+            delivery_histogram,
             total_messages_sent: 0,
             total_messages_received: 0,
             total_bytes_sent: 0,
@@ -42,29 +62,63 @@ impl WsStats {
             connections_established: 0,
             connection_errors: 0,
             disconnects: 0,
+            reconnects: 0,
+            reconnect_failures: 0,
+            broadcast_expected: 0,
+            broadcast_delivered: 0,
+            compression: None,
             errors: HashMap::new(),
+            close_codes: HashMap::new(),
             start_time: Instant::now(),
             rolling_window: Vec::with_capacity(100),
         }
     }
 
+    /// Clear the warmup measurements. Connections stay open past warmup, so
+    /// how many were established and how long that took carry over
     pub fn reset(&mut self) {
         self.message_histogram.reset();
-        self.connect_histogram.reset();
+        self.reconnect_histogram.reset();
+        self.delivery_histogram.reset();
         self.total_messages_sent = 0;
         self.total_messages_received = 0;
         self.total_bytes_sent = 0;
         self.total_bytes_received = 0;
-        self.connections_established = 0;
         self.connection_errors = 0;
         self.disconnects = 0;
+        self.reconnects = 0;
+        self.reconnect_failures = 0;
+        self.broadcast_expected = 0;
+        self.broadcast_delivered = 0;
+        self.compression = None;
         self.errors.clear();
+        self.close_codes.clear();
         self.start_time = Instant::now();
         self.rolling_window.clear();
     }
 
     pub fn record_message(&mut self, result: &WsMessageResult) {
+        if let Some(ref wire_bytes) = result.wire_bytes {
+            self.compression.get_or_insert_default().add(wire_bytes);
+        }
+
+        if let Some(ref close) = result.close {
+            let stat = self.close_codes.entry(close.code).or_default();
+            stat.count += 1;
+            if !close.reason.is_empty() {
+                stat.reason = close.reason.clone();
+            }
+        }
+
+        if result.delivery {
+            self.record_delivery(result);
+            return;
+        }
+
         self.total_messages_sent += 1;
+        if let Some(fanout) = result.fanout {
+            self.broadcast_expected += fanout as u64;
+        }
         self.total_bytes_sent += result.bytes_sent;
 
         if result.is_success() {
@@ -81,6 +135,15 @@ impl WsStats {
             let connect_clamped = connect_time.min(60_000_000);
             let _ = self.connect_histogram.record(connect_clamped);
             self.connections_established += 1;
+
+            if result.reconnect {
+                let _ = self.reconnect_histogram.record(connect_clamped);
+                self.reconnects += 1;
+            }
+        }
+
+        if result.reconnect && result.connect_time_us.is_none() && result.error.is_some() {
+            self.reconnect_failures += 1;
         }
 
         if let Some(kind) = result.error {
@@ -103,6 +166,21 @@ impl WsStats {
             .retain(|(t, _)| now.duration_since(*t) < Duration::from_secs(1));
     }
 
+    /// Broadcast subscriber results: connects and received deliveries
+    fn record_delivery(&mut self, result: &WsMessageResult) {
+        if let Some(connect_time) = result.connect_time_us {
+            self.record_connection(connect_time);
+        }
+
+        if result.bytes_received > 0 {
+            let latency = result.message_latency_us.clamp(1, 60_000_000);
+            let _ = self.delivery_histogram.record(latency);
+            self.broadcast_delivered += 1;
+            self.total_messages_received += 1;
+            self.total_bytes_received += result.bytes_received;
+        }
+    }
+
     pub fn record_connection(&mut self, connect_time_us: u64) {
         let connect_clamped = connect_time_us.min(60_000_000);
         let _ = self.connect_histogram.record(connect_clamped);
@@ -181,6 +259,24 @@ impl WsStats {
     pub fn connect_time_percentile(&self, p: f64) -> u64 {
         self.connect_histogram.value_at_percentile(p)
     }
+
+    // Broadcast delivery latency metrics
+    pub fn delivery_latency_max(&self) -> u64 {
+        self.delivery_histogram.max()
+    }
+
+    pub fn delivery_latency_percentile(&self, p: f64) -> u64 {
+        self.delivery_histogram.value_at_percentile(p)
+    }
+
+    // Reconnect time metrics (churn)
+    pub fn reconnect_time_mean(&self) -> f64 {
+        self.reconnect_histogram.mean()
+    }
+
+    pub fn reconnect_time_percentile(&self, p: f64) -> u64 {
+        self.reconnect_histogram.value_at_percentile(p)
+    }
 }
 
 impl Default for WsStats {
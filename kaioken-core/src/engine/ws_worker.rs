@@ -0,0 +1,205 @@
+use crate::engine::scheduler::message_rate_interval;
+use crate::types::{WsErrorKind, WsMessageResult, WsMode};
+use crate::ws::{WsConnection, connect, execute_ws_message};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+pub struct WsWorker {
+    id: u32,
+    url: String,
+    headers: Vec<(String, String)>,
+    subprotocols: Vec<String>,
+    compression: bool,
+    message: String,
+    mode: WsMode,
+    message_interval: Duration,
+    message_rate: Option<Arc<AtomicU32>>,
+    timeout: Duration,
+    reconnect_every: Option<Duration>,
+    result_tx: mpsc::Sender<WsMessageResult>,
+    cancel_token: CancellationToken,
+}
+
+impl WsWorker {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u32,
+        url: String,
+        headers: Vec<(String, String)>,
+        subprotocols: Vec<String>,
+        compression: bool,
+        message: String,
+        mode: WsMode,
+        message_interval: Duration,
+        message_rate: Option<Arc<AtomicU32>>,
+        timeout: Duration,
+        reconnect_every: Option<Duration>,
+        result_tx: mpsc::Sender<WsMessageResult>,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            id,
+            url,
+            headers,
+            subprotocols,
+            compression,
+            message,
+            mode,
+            message_interval,
+            message_rate,
+            timeout,
+            reconnect_every,
+            result_tx,
+            cancel_token,
+        }
+    }
+
+    pub async fn run(self) {
+        tracing::debug!("WsWorker {} starting", self.id);
+
+        let mut connection: Option<WsConnection> = None;
+        let mut connected_at = Instant::now();
+        let mut pending_connect_time: Option<u64> = None;
+        let mut churning = false;
+        // The first connection closes at a random point in its lifetime, so
+        // connections reconnect spread out instead of all at once
+        let mut lifetime = self
+            .reconnect_every
+            .map(|every| every.mul_f64(1.0 - rand::random::<f64>()));
+        let mut message_counter: u64 = 0;
+        let base_message_id = (self.id as u64) * 1_000_000_000;
+
+        loop {
+            if self.cancel_token.is_cancelled() {
+                break;
+            }
+
+            // Churn: deliberately close the connection once it reaches its lifetime
+            if let Some(current) = lifetime
+                && connected_at.elapsed() >= current
+                && let Some(conn) = connection.take()
+            {
+                tracing::debug!("WsWorker {} closing connection for churn", self.id);
+                let _ = conn.close().await;
+                churning = true;
+                lifetime = self.reconnect_every;
+            }
+
+            // Ensure we have a connection
+            if connection.is_none() {
+                match connect(
+                    &self.url,
+                    &self.headers,
+                    &self.subprotocols,
+                    self.compression,
+                    self.timeout,
+                )
+                .await
+                {
+                    Ok(conn) => {
+                        tracing::debug!("WsWorker {} connected", self.id);
+                        // Record the connection time with the next message
+                        pending_connect_time = Some(conn.connect_time_us);
+                        connected_at = Instant::now();
+                        connection = Some(conn);
+                    }
+                    Err(e) => {
+                        // Send connection error result
+                        let mut result = WsMessageResult::error(e);
+                        if churning {
+                            result = result.with_reconnect();
+                        }
+                        if self.result_tx.send(result).await.is_err() {
+                            break;
+                        }
+                        // Wait before retry
+                        tokio::select! {
+                            _ = sleep(Duration::from_secs(1)) => {}
+                            _ = self.cancel_token.cancelled() => break,
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            // Message-rate stages: hold off sending while the staged rate is zero
+            let message_interval = match self.message_rate {
+                Some(ref rate) => match message_rate_interval(rate.load(Ordering::Relaxed)) {
+                    Some(interval) => interval,
+                    None => {
+                        tokio::select! {
+                            _ = sleep(Duration::from_millis(100)) => {}
+                            _ = self.cancel_token.cancelled() => break,
+                        }
+                        continue;
+                    }
+                },
+                None => self.message_interval,
+            };
+
+            let conn = connection.as_mut().unwrap();
+
+            message_counter += 1;
+            let _message_id = base_message_id + message_counter;
+            let timestamp_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+
+            // Interpolate message
+            let message = self
+                .message
+                .replace("${MESSAGE_ID}", &message_counter.to_string())
+                .replace("${TIMESTAMP_MS}", &timestamp_ms.to_string());
+
+            let start = Instant::now();
+            let mut result = execute_ws_message(conn, &message, self.mode, self.timeout).await;
+
+            // Include connect time on first message after each connect
+            if let Some(connect_time) = pending_connect_time.take() {
+                result = result.with_connect_time(connect_time);
+                if churning {
+                    result = result.with_reconnect();
+                    churning = false;
+                }
+            }
+
+            // Check for connection loss
+            let connection_lost = matches!(
+                result.error,
+                Some(WsErrorKind::ConnectionClosed) | Some(WsErrorKind::SendFailed)
+            );
+
+            if self.result_tx.send(result).await.is_err() {
+                break;
+            }
+
+            if connection_lost {
+                tracing::debug!("WsWorker {} connection lost, will reconnect", self.id);
+                connection = None;
+                continue;
+            }
+
+            // Wait for next message interval
+            let elapsed = start.elapsed();
+            if elapsed < message_interval {
+                let remaining = message_interval - elapsed;
+                tokio::select! {
+                    _ = sleep(remaining) => {}
+                    _ = self.cancel_token.cancelled() => break,
+                }
+            }
+        }
+
+        // Close connection gracefully
+        if let Some(conn) = connection {
+            let _ = conn.close().await;
+        }
+
+        tracing::debug!("WsWorker {} stopped", self.id);
+    }
+}
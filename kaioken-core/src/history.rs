@@ -0,0 +1,114 @@
+//! Run history database (`LoadConfig::db_url`): the engine creates a row in
+//! `runs` for each run and logs its snapshots against it. Reading runs back
+//! and storing the final result is left to the caller.
+
+use crate::types::{LoadConfig, StatsSnapshot};
+use chrono::Utc;
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+
+/// A run row that snapshots and the final result are attached to
+#[derive(Debug, Clone)]
+pub struct HistoryRun {
+    pub path: PathBuf,
+    pub id: i64,
+}
+
+/// Open the database, creating or upgrading the schema as needed
+pub fn open_db(path: &Path) -> Result<Connection, rusqlite::Error> {
+    let conn = Connection::open(path)?;
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp_ms INTEGER NOT NULL,
+            elapsed_secs REAL NOT NULL,
+            total_requests INTEGER NOT NULL,
+            successful INTEGER NOT NULL,
+            failed INTEGER NOT NULL,
+            rps REAL NOT NULL,
+            latency_p50_us INTEGER NOT NULL,
+            latency_p95_us INTEGER NOT NULL,
+            latency_p99_us INTEGER NOT NULL,
+            latency_p999_us INTEGER NOT NULL,
+            error_rate REAL NOT NULL,
+            bytes_received INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_snapshots_elapsed ON snapshots(elapsed_secs);
+
+        CREATE TABLE IF NOT EXISTS runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            started_at TEXT NOT NULL,
+            url TEXT NOT NULL,
+            finished_at TEXT,
+            exit_code INTEGER,
+            result_json TEXT
+        );",
+    )?;
+
+    // Databases written before run history have snapshots without a run
+    let has_run_id = conn
+        .prepare("SELECT 1 FROM pragma_table_info('snapshots') WHERE name = 'run_id'")?
+        .exists([])?;
+    if !has_run_id {
+        conn.execute_batch("ALTER TABLE snapshots ADD COLUMN run_id INTEGER REFERENCES runs(id);")?;
+    }
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_snapshots_run ON snapshots(run_id);")?;
+
+    Ok(conn)
+}
+
+/// Create the row for a run that's about to start
+pub fn begin_run(path: &Path, config: &LoadConfig) -> Result<HistoryRun, String> {
+    let conn = open_db(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    conn.execute(
+        "INSERT INTO runs (started_at, url) VALUES (?1, ?2)",
+        rusqlite::params![Utc::now().to_rfc3339(), config.url],
+    )
+    .map_err(|e| format!("Failed to record run: {}", e))?;
+
+    Ok(HistoryRun {
+        path: path.to_path_buf(),
+        id: conn.last_insert_rowid(),
+    })
+}
+
+/// Log a snapshot to SQLite database
+pub fn log_snapshot(
+    conn: &Connection,
+    run_id: Option<i64>,
+    snapshot: &StatsSnapshot,
+) -> Result<(), rusqlite::Error> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let timestamp_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+
+    conn.execute(
+        "INSERT INTO snapshots (
+            timestamp_ms, elapsed_secs, total_requests, successful, failed,
+            rps, latency_p50_us, latency_p95_us, latency_p99_us, latency_p999_us,
+            error_rate, bytes_received, run_id
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        rusqlite::params![
+            timestamp_ms,
+            snapshot.elapsed.as_secs_f64(),
+            snapshot.total_requests as i64,
+            snapshot.successful as i64,
+            snapshot.failed as i64,
+            snapshot.requests_per_sec,
+            snapshot.latency_p50_us as i64,
+            snapshot.latency_p95_us as i64,
+            snapshot.latency_p99_us as i64,
+            snapshot.latency_p999_us as i64,
+            snapshot.error_rate,
+            snapshot.bytes_received as i64,
+            run_id,
+        ],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use crate::net::SocketOptions;
+use crate::types::{SeededCookie, TlsBackend};
+use reqwest::Client;
+use reqwest::cookie::Jar;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tower::util::MapResponseLayer;
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_client(
+    concurrency: u32,
+    timeout: Duration,
+    connect_timeout: Duration,
+    insecure: bool,
+    tls_backend: TlsBackend,
+    http2: bool,
+    http1_only: bool,
+    cookie_jar: bool,
+    cookies: &[SeededCookie],
+    follow_redirects: bool,
+    disable_keepalive: bool,
+    proxy: Option<&str>,
+    client_cert: Option<&Path>,
+    client_key: Option<&Path>,
+    ca_cert: Option<&Path>,
+    connect_to: Option<(&str, SocketAddr)>,
+    socket: &SocketOptions,
+    new_connections: Option<Arc<AtomicU64>>,
+) -> Result<Client, Box<dyn std::error::Error + Send + Sync>> {
+    let mut builder = Client::builder()
+        .connect_timeout(connect_timeout)
+        .timeout(timeout)
+        .tcp_nodelay(socket.nodelay)
+        .gzip(true)
+        .brotli(true)
+        .user_agent(format!(
+            "kaioken/{} (load-testing-tool)",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .danger_accept_invalid_certs(insecure);
+
+    // Seeded cookies go into a jar of their own, which also keeps what the
+    // server sets from then on
+    builder = if cookies.is_empty() {
+        builder.cookie_store(cookie_jar)
+    } else {
+        builder.cookie_provider(Arc::new(seeded_jar(cookies)?))
+    };
+
+    builder = match tls_backend {
+        TlsBackend::Rustls => builder.use_rustls_tls(),
+        #[cfg(feature = "native-tls")]
+        TlsBackend::Native => builder.use_native_tls(),
+    };
+
+    // Configure connection pooling / keepalive
+    if disable_keepalive {
+        builder = builder
+            .pool_max_idle_per_host(0)
+            .pool_idle_timeout(Duration::ZERO);
+    } else {
+        builder = builder
+            .pool_max_idle_per_host(
+                socket
+                    .pool_max_idle_per_host
+                    .unwrap_or(concurrency as usize),
+            )
+            .pool_idle_timeout(socket.pool_idle_timeout)
+            .tcp_keepalive(Duration::from_secs(60));
+    }
+
+    if http2 {
+        builder = builder.http2_prior_knowledge();
+    } else if http1_only {
+        builder = builder.http1_only();
+    }
+
+    if !follow_redirects {
+        builder = builder.redirect(Policy::none());
+    }
+
+    // Configure proxy if specified; an empty one connects directly, ignoring
+    // proxy environment variables too
+    match proxy {
+        Some("") => builder = builder.no_proxy(),
+        Some(proxy_url) => builder = builder.proxy(reqwest::Proxy::all(proxy_url)?),
+        None => {}
+    }
+
+    // Configure custom CA certificate
+    if let Some(ca_path) = ca_cert {
+        let ca_pem = std::fs::read(ca_path)?;
+        let cert = reqwest::Certificate::from_pem(&ca_pem)?;
+        builder = builder.add_root_certificate(cert);
+    }
+
+    // Configure client identity for mTLS (cert + key)
+    if let (Some(cert_path), Some(key_path)) = (client_cert, client_key) {
+        let identity = match tls_backend {
+            TlsBackend::Rustls => {
+                // Combine cert and key into single PEM for Identity
+                let mut pem = std::fs::read(cert_path)?;
+                pem.push(b'\n');
+                pem.extend(std::fs::read(key_path)?);
+                reqwest::Identity::from_pem(&pem)?
+            }
+            #[cfg(feature = "native-tls")]
+            TlsBackend::Native => reqwest::Identity::from_pkcs8_pem(
+                &std::fs::read(cert_path)?,
+                &std::fs::read(key_path)?,
+            )?,
+        };
+        builder = builder.identity(identity);
+    }
+
+    // Configure DNS override (--connect-to)
+    if let Some((host, addr)) = connect_to {
+        builder = builder.resolve(host, addr);
+    }
+
+    // Hand hyper only the addresses of the chosen family (--ipv4 / --ipv6),
+    // so its happy eyeballs has nothing else to race
+    if socket.filters_addresses() {
+        builder = builder.dns_resolver(Arc::new(FamilyResolver(socket.clone())));
+    }
+
+    // Count every connection the pool had to open (TCP, plus TLS if any)
+    if let Some(counter) = new_connections {
+        builder = builder.connector_layer(MapResponseLayer::new(move |conn| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            conn
+        }));
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Cookie jar holding `cookies`, so the first requests already send them
+fn seeded_jar(cookies: &[SeededCookie]) -> Result<Jar, Box<dyn std::error::Error + Send + Sync>> {
+    let jar = Jar::default();
+    for cookie in cookies {
+        let scheme = if cookie.secure { "https" } else { "http" };
+        let url = format!("{}://{}{}", scheme, cookie.domain, cookie.path).parse()?;
+        let mut header = format!("{}={}; Path={}", cookie.name, cookie.value, cookie.path);
+        // Without a Domain attribute the cookie is host-only
+        if cookie.include_subdomains {
+            header.push_str("; Domain=");
+            header.push_str(&cookie.domain);
+        }
+        if cookie.secure {
+            header.push_str("; Secure");
+        }
+        jar.add_cookie_str(&header, &url);
+    }
+    Ok(jar)
+}
+
+/// System DNS, narrowed by `SocketOptions::select_addrs`
+struct FamilyResolver(SocketOptions);
+
+impl Resolve for FamilyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let socket = self.0.clone();
+        Box::pin(async move {
+            // The port is replaced by the URL's
+            let addrs = socket.resolve(&format!("{}:0", name.as_str())).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
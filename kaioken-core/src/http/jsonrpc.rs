@@ -0,0 +1,129 @@
+use crate::types::{ErrorKind, RequestResult};
+use serde_json::Value;
+
+/// Build a JSON-RPC 2.0 request body. The id is `${REQUEST_ID}` so every
+/// request carries a fresh, unique id once the worker interpolates it.
+pub fn envelope(method: &str, params: Option<&Value>) -> String {
+    let method = Value::String(method.to_string());
+    match params {
+        Some(params) => format!(
+            r#"{{"jsonrpc":"2.0","method":{},"params":{},"id":${{REQUEST_ID}}}}"#,
+            method, params
+        ),
+        None => format!(
+            r#"{{"jsonrpc":"2.0","method":{},"id":${{REQUEST_ID}}}}"#,
+            method
+        ),
+    }
+}
+
+/// Check params are a structured value (array or object), as JSON-RPC 2.0 requires
+pub fn validate_params(params: &Value) -> Result<(), String> {
+    if params.is_array() || params.is_object() {
+        Ok(())
+    } else {
+        Err(format!(
+            "JSON-RPC params must be an array or object, got: {}",
+            params
+        ))
+    }
+}
+
+/// Mark a 2xx response carrying an `error` member as failed and record its code
+pub fn classify(result: &mut RequestResult) {
+    if result.error.is_some() || !result.status.is_some_and(|s| (200..300).contains(&s)) {
+        return;
+    }
+    let Some(body) = result.body.as_deref() else {
+        return;
+    };
+    if let Some(code) = error_code(body) {
+        result.error = Some(ErrorKind::JsonRpc);
+        result.jsonrpc_error = code;
+    }
+}
+
+/// `Some(code)` when the response has a non-null `error` member; the inner
+/// value is `None` if the error object lacks an integer code
+fn error_code(body: &str) -> Option<Option<i64>> {
+    let response: Value = serde_json::from_str(body).ok()?;
+    let error = response.get("error").filter(|e| !e.is_null())?;
+    Some(error.get("code").and_then(Value::as_i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_envelope() {
+        assert_eq!(
+            envelope("eth_blockNumber", None),
+            r#"{"jsonrpc":"2.0","method":"eth_blockNumber","id":${REQUEST_ID}}"#
+        );
+        assert_eq!(
+            envelope("sum", Some(&json!([1, 2]))),
+            r#"{"jsonrpc":"2.0","method":"sum","params":[1,2],"id":${REQUEST_ID}}"#
+        );
+        assert_eq!(
+            envelope("say \"hi\"", Some(&json!({"to": "bob"}))),
+            r#"{"jsonrpc":"2.0","method":"say \"hi\"","params":{"to":"bob"},"id":${REQUEST_ID}}"#
+        );
+    }
+
+    #[test]
+    fn test_validate_params() {
+        assert!(validate_params(&json!([])).is_ok());
+        assert!(validate_params(&json!({"a": 1})).is_ok());
+        assert!(validate_params(&json!(42)).is_err());
+        assert!(validate_params(&json!("x")).is_err());
+    }
+
+    #[test]
+    fn test_classify() {
+        let mut ok = RequestResult::success(
+            100,
+            200,
+            40,
+            Some(r#"{"jsonrpc":"2.0","result":"0x10","id":1}"#.to_string()),
+        );
+        classify(&mut ok);
+        assert!(ok.is_success());
+        assert_eq!(ok.jsonrpc_error, None);
+
+        let mut failed = RequestResult::success(
+            100,
+            200,
+            80,
+            Some(
+                r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found"},"id":1}"#
+                    .to_string(),
+            ),
+        );
+        classify(&mut failed);
+        assert!(!failed.is_success());
+        assert_eq!(failed.error, Some(ErrorKind::JsonRpc));
+        assert_eq!(failed.jsonrpc_error, Some(-32601));
+
+        let mut null_error = RequestResult::success(
+            100,
+            200,
+            40,
+            Some(r#"{"jsonrpc":"2.0","result":1,"error":null,"id":1}"#.to_string()),
+        );
+        classify(&mut null_error);
+        assert!(null_error.is_success());
+
+        // HTTP-level failures keep their status as the failure reason
+        let mut http_error = RequestResult::success(
+            100,
+            500,
+            40,
+            Some(r#"{"error":{"code":-32603}}"#.to_string()),
+        );
+        classify(&mut http_error);
+        assert_eq!(http_error.error, None);
+        assert_eq!(http_error.jsonrpc_error, None);
+    }
+}
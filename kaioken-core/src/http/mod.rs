@@ -0,0 +1,13 @@
+mod client;
+mod compression;
+pub mod jsonrpc;
+mod request;
+mod request_id;
+mod sni;
+mod template;
+mod trace;
+
+pub use client::create_client;
+pub use request::{execute_prepared, execute_request, now_us};
+pub use sni::route as route_sni;
+pub use template::PreparedRequest;
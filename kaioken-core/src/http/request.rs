@@ -0,0 +1,415 @@
+use super::compression;
+use super::request_id;
+use super::template::PreparedRequest;
+use super::trace;
+use crate::types::{
+    BodyCompression, ErrorKind, FailureDetail, FormField, RequestResult, TracePropagation,
+};
+use bytes::Bytes;
+use futures_util::StreamExt;
+use reqwest::header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, HeaderMap, RETRY_AFTER};
+use reqwest::{Body, Client, Method, RequestBuilder};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Bodies larger than this are streamed in chunks of this size, so the time
+/// the connection takes the last chunk shows how long the upload took.
+/// Smaller bodies go out in one write and are sent as-is.
+const UPLOAD_CHUNK: usize = 64 * 1024;
+
+/// Get current time in microseconds since UNIX epoch
+pub fn now_us() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_request(
+    client: &Client,
+    url: &str,
+    method: &Method,
+    headers: &[(String, String)],
+    body: Option<&str>,
+    compression: Option<BodyCompression>,
+    form_data: Option<&[FormField]>,
+    basic_auth: Option<(&str, Option<&str>)>,
+    capture_body: bool,
+    trace: Option<TracePropagation>,
+    request_id: Option<&str>,
+    scheduled_at_us: Option<u64>, // For latency correction
+) -> RequestResult {
+    let started_at_us = now_us();
+    let start = Instant::now();
+
+    let mut request = client.request(method.clone(), url);
+
+    for (name, value) in headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let body = match (body, compression) {
+        (Some(body), Some(encoding)) => {
+            request = request.header(CONTENT_ENCODING, encoding.as_str());
+            Some(compression::compress(body.as_bytes(), encoding))
+        }
+        (body, _) => body.map(|b| Bytes::copy_from_slice(b.as_bytes())),
+    };
+
+    send(
+        request,
+        body,
+        form_data,
+        basic_auth,
+        capture_body,
+        trace,
+        request_id,
+        scheduled_at_us,
+        started_at_us,
+        start,
+    )
+    .await
+}
+
+/// Execute a request prebuilt at startup
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_prepared(
+    client: &Client,
+    prepared: &PreparedRequest,
+    form_data: Option<&[FormField]>,
+    basic_auth: Option<(&str, Option<&str>)>,
+    capture_body: bool,
+    trace: Option<TracePropagation>,
+    request_id: Option<&str>,
+    scheduled_at_us: Option<u64>,
+) -> RequestResult {
+    let started_at_us = now_us();
+    let start = Instant::now();
+
+    let request = client
+        .request(prepared.method.clone(), prepared.url.clone())
+        .headers(prepared.headers.clone());
+    let body = prepared.body.clone();
+
+    send(
+        request,
+        body,
+        form_data,
+        basic_auth,
+        capture_body,
+        trace,
+        request_id,
+        scheduled_at_us,
+        started_at_us,
+        start,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn send(
+    mut request: RequestBuilder,
+    body: Option<Bytes>,
+    form_data: Option<&[FormField]>,
+    basic_auth: Option<(&str, Option<&str>)>,
+    capture_body: bool,
+    trace: Option<TracePropagation>,
+    request_id: Option<&str>,
+    scheduled_at_us: Option<u64>,
+    started_at_us: u64,
+    start: Instant,
+) -> RequestResult {
+    // Apply basic auth if provided
+    if let Some((username, password)) = basic_auth {
+        request = request.basic_auth(username, password);
+    }
+
+    let mut trace_id = None;
+    if let Some(format) = trace {
+        let (traced, id) = trace::inject(request, format);
+        request = traced;
+        trace_id = Some(id);
+    }
+
+    // Time from `start` until the last chunk of a large body was taken
+    let upload_done = Arc::new(AtomicU64::new(0));
+
+    // Build multipart form if form_data provided
+    if let Some(fields) = form_data {
+        match build_multipart_form(fields, start, &upload_done).await {
+            Ok(form) => {
+                request = request.multipart(form);
+            }
+            Err(_) => {
+                let latency_us = start.elapsed().as_micros() as u64;
+                return RequestResult::error(latency_us, ErrorKind::Other);
+            }
+        }
+    } else if let Some(body) = body {
+        request = if body.len() > UPLOAD_CHUNK {
+            request.header(CONTENT_LENGTH, body.len()).body(timed_body(
+                body,
+                start,
+                upload_done.clone(),
+            ))
+        } else {
+            request.body(body)
+        };
+    }
+
+    let (client, request) = request.build_split();
+    let mut request = match request {
+        Ok(request) => request,
+        Err(err) => {
+            let latency_us = start.elapsed().as_micros() as u64;
+            return RequestResult::error(latency_us, ErrorKind::from_reqwest_error(&err));
+        }
+    };
+    let sent_id = request_id.and_then(|name| request_id::tag(&mut request, name));
+    let bytes_sent = request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .map(|body| body.len() as u64)
+        .or_else(|| {
+            request
+                .headers()
+                .get(CONTENT_LENGTH)?
+                .to_str()
+                .ok()?
+                .parse()
+                .ok()
+        })
+        .unwrap_or(0);
+
+    let method = request.method().clone();
+    let mut result = match client.execute(request).await {
+        Ok(response) => {
+            let status = response.status().as_u16();
+            let content_length = response.content_length().unwrap_or(0);
+            let failed_url = (status >= 400).then(|| response.url().to_string());
+            let retry_after = if matches!(status, 429 | 503) {
+                retry_after(response.headers(), SystemTime::now())
+            } else {
+                None
+            };
+            let id_echo = sent_id
+                .as_ref()
+                .map(|(name, id)| request_id::echo(response.headers(), name, id));
+            let content_type = media_type(response.headers());
+
+            let response_headers = capture_body.then(|| Box::new(response.headers().clone()));
+            let mut excerpt = None;
+            let response_body = if capture_body {
+                (response.text().await).ok()
+            } else {
+                // Consume body to allow connection reuse, keeping the start
+                // of error responses for the failure samples
+                let bytes = response.bytes().await;
+                if failed_url.is_some() {
+                    excerpt = bytes.ok().map(|b| FailureDetail::excerpt(&b));
+                }
+                None
+            };
+
+            let latency_us = start.elapsed().as_micros() as u64;
+            let mut result =
+                RequestResult::success(latency_us, status, content_length, response_body);
+            result.bytes_sent = bytes_sent;
+            result.retry_after = retry_after;
+            result.id_echo = id_echo;
+            result.content_type = content_type;
+            result.headers = response_headers;
+            result.upload_us = match upload_done.load(Ordering::Relaxed) {
+                0 => None,
+                upload_us => Some(upload_us),
+            };
+            if let Some(url) = failed_url {
+                let body = excerpt.or_else(|| {
+                    let body = result.body.as_deref()?;
+                    Some(FailureDetail::excerpt(body.as_bytes()))
+                });
+                result.failure = Some(Box::new(FailureDetail {
+                    request: format!("{} {}", method, url),
+                    message: None,
+                    body,
+                }));
+            }
+            result
+        }
+        Err(err) => {
+            let latency_us = start.elapsed().as_micros() as u64;
+            let kind = ErrorKind::from_reqwest_error(&err);
+            let mut result = RequestResult::error(latency_us, kind);
+            result.failure = Some(Box::new(FailureDetail {
+                request: match err.url() {
+                    Some(url) => format!("{} {}", method, url),
+                    None => method.to_string(),
+                },
+                message: Some(error_chain(&err)),
+                body: None,
+            }));
+            result
+        }
+    };
+
+    result.trace_id = trace_id;
+
+    // Apply timing info for latency correction if scheduled time was provided
+    if let Some(scheduled) = scheduled_at_us {
+        result.with_timing(scheduled, started_at_us)
+    } else {
+        result
+    }
+}
+
+/// The media type of a Content-Type header, lowercase without its
+/// parameters (`text/html; charset=utf-8` = `text/html`)
+fn media_type(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(CONTENT_TYPE)?.to_str().ok()?;
+    let media_type = value.split(';').next().unwrap_or(value).trim();
+    (!media_type.is_empty()).then(|| media_type.to_ascii_lowercase())
+}
+
+/// How long a Retry-After header asks to wait: delay-seconds, or an
+/// HTTP-date measured from `now` (zero once it has passed)
+fn retry_after(headers: &HeaderMap, now: SystemTime) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let now = chrono::DateTime::<chrono::Utc>::from(now);
+    Some((date.to_utc() - now).to_std().unwrap_or_default())
+}
+
+/// `err` and its causes, since reqwest's own message rarely says what went wrong
+fn error_chain(err: &(dyn std::error::Error + 'static)) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// Stream `bytes` in `UPLOAD_CHUNK` pieces, noting in `done` when the last
+/// piece was taken (the latest one, when a form has several large files)
+fn timed_body(bytes: Bytes, start: Instant, done: Arc<AtomicU64>) -> Body {
+    let len = bytes.len();
+    let chunks = futures_util::stream::iter((0..len).step_by(UPLOAD_CHUNK)).map(move |offset| {
+        let end = (offset + UPLOAD_CHUNK).min(len);
+        if end == len {
+            done.fetch_max(start.elapsed().as_micros().max(1) as u64, Ordering::Relaxed);
+        }
+        Ok::<_, std::io::Error>(bytes.slice(offset..end))
+    });
+    Body::wrap_stream(chunks)
+}
+
+/// Build a multipart form from FormField entries
+async fn build_multipart_form(
+    fields: &[FormField],
+    start: Instant,
+    upload_done: &Arc<AtomicU64>,
+) -> Result<reqwest::multipart::Form, Box<dyn std::error::Error + Send + Sync>> {
+    use reqwest::multipart::{Form, Part};
+
+    let mut form = Form::new();
+
+    for field in fields {
+        match field {
+            FormField::Text { name, value } => {
+                form = form.text(name.clone(), value.clone());
+            }
+            FormField::File {
+                name,
+                path,
+                filename,
+                mime_type,
+            } => {
+                let bytes = tokio::fs::read(path).await?;
+                let file_name = filename
+                    .clone()
+                    .or_else(|| {
+                        path.file_name()
+                            .and_then(|n| n.to_str())
+                            .map(|s| s.to_string())
+                    })
+                    .unwrap_or_else(|| "file".to_string());
+
+                let len = bytes.len();
+                let part = if len > UPLOAD_CHUNK {
+                    Part::stream_with_length(
+                        timed_body(bytes.into(), start, upload_done.clone()),
+                        len as u64,
+                    )
+                } else {
+                    Part::bytes(bytes)
+                };
+                let mut part = part.file_name(file_name);
+
+                if let Some(mime) = mime_type {
+                    part = part.mime_str(mime)?;
+                }
+
+                form = form.part(name.clone(), part);
+            }
+        }
+    }
+
+    Ok(form)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        // Sun, 06 Nov 1994 08:49:37 GMT
+        let now = UNIX_EPOCH + Duration::from_secs(784_111_777);
+        assert_eq!(
+            retry_after(&headers("120"), now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            retry_after(&headers("Sun, 06 Nov 1994 08:50:07 GMT"), now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            retry_after(&headers("Sun, 06 Nov 1994 08:00:00 GMT"), now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(retry_after(&headers("soon"), now), None);
+        assert_eq!(retry_after(&HeaderMap::new(), now), None);
+    }
+
+    #[test]
+    fn drops_content_type_parameters() {
+        let content_type = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_str(value).unwrap());
+            media_type(&headers)
+        };
+        assert_eq!(
+            content_type("Text/HTML; charset=utf-8").as_deref(),
+            Some("text/html")
+        );
+        assert_eq!(
+            content_type("application/json").as_deref(),
+            Some("application/json")
+        );
+        assert_eq!(content_type(" ; charset=utf-8"), None);
+        assert_eq!(media_type(&HeaderMap::new()), None);
+    }
+}
@@ -0,0 +1,79 @@
+//! Request ID echo checks (`--request-id-header`)
+//!
+//! Every request carries a unique ID, and the response is expected to send
+//! the same ID back. A response carrying another request's ID crossed over
+//! somewhere between kaioken and the server (a proxy mixing up connections
+//! under load), which status codes alone never show.
+
+use crate::types::IdEcho;
+use reqwest::Request;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+/// Give `request` an ID in the `name` header, unless it already has one
+/// (from the headers or a template); returns the header and the ID sent
+pub fn tag(request: &mut Request, name: &str) -> Option<(HeaderName, String)> {
+    let name = HeaderName::from_bytes(name.as_bytes()).ok()?;
+    if let Some(existing) = request.headers().get(&name) {
+        let id = existing.to_str().ok()?.to_string();
+        return Some((name, id));
+    }
+    let id = format!("{:032x}", rand::random::<u128>());
+    let value = HeaderValue::from_str(&id).ok()?;
+    request.headers_mut().insert(name.clone(), value);
+    Some((name, id))
+}
+
+/// Whether `headers` sent back the ID `sent`
+pub fn echo(headers: &HeaderMap, name: &HeaderName, sent: &str) -> IdEcho {
+    match headers.get(name) {
+        None => IdEcho::Missing,
+        Some(value) if value.as_bytes() == sent.as_bytes() => IdEcho::Matched,
+        Some(_) => IdEcho::Mismatched,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::Method;
+
+    fn request() -> Request {
+        Request::new(Method::GET, "http://localhost/".parse().unwrap())
+    }
+
+    #[test]
+    fn tags_requests_with_fresh_ids() {
+        let mut first = request();
+        let mut second = request();
+        let (name, a) = tag(&mut first, "X-Request-ID").unwrap();
+        let (_, b) = tag(&mut second, "X-Request-ID").unwrap();
+
+        assert_eq!(name.as_str(), "x-request-id");
+        assert_eq!(a.len(), 32);
+        assert_ne!(a, b);
+        assert_eq!(first.headers()[&name], a.as_str());
+    }
+
+    #[test]
+    fn keeps_an_id_the_request_already_has() {
+        let mut request = request();
+        request
+            .headers_mut()
+            .insert("x-request-id", HeaderValue::from_static("order-42"));
+
+        let (_, id) = tag(&mut request, "x-request-id").unwrap();
+        assert_eq!(id, "order-42");
+        assert_eq!(request.headers().get_all("x-request-id").iter().count(), 1);
+    }
+
+    #[test]
+    fn compares_the_echoed_id() {
+        let name = HeaderName::from_static("x-request-id");
+        let mut headers = HeaderMap::new();
+        assert_eq!(echo(&headers, &name, "abc"), IdEcho::Missing);
+
+        headers.insert(&name, HeaderValue::from_static("abc"));
+        assert_eq!(echo(&headers, &name, "abc"), IdEcho::Matched);
+        assert_eq!(echo(&headers, &name, "def"), IdEcho::Mismatched);
+    }
+}
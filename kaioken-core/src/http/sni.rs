@@ -0,0 +1,148 @@
+//! TLS server name override (`--sni`)
+//!
+//! CDNs and multi-tenant TLS terminators pick a certificate and a backend
+//! from the SNI before they see the `Host` header. reqwest takes the SNI,
+//! and the name the certificate is checked against, from the URL's host, so
+//! the target's URLs are moved onto the SNI name, that name is pinned to the
+//! target's address, and the real host is sent in the `Host` header. That
+//! only holds on HTTP/1.1: HTTP/2 takes the authority from the URL.
+
+use crate::types::LoadConfig;
+
+/// Point the target's requests at `config.sni`. A no-op without one.
+pub async fn route(config: &mut LoadConfig) -> Result<(), String> {
+    let Some(name) = config.sni.clone() else {
+        return Ok(());
+    };
+    let authority = config
+        .url
+        .strip_prefix("https://")
+        .map(|rest| rest.split(['/', '?', '#']).next().unwrap_or(rest))
+        .filter(|authority| !authority.is_empty())
+        .ok_or_else(|| format!("--sni needs an https:// target, not {}", config.url))?
+        .to_string();
+    let (host, port) = split_authority(&authority);
+
+    // The name only has to reach the target, so it's resolved to the
+    // target's address up front (or the one --connect-to gives it)
+    let addr = match config.connect_to {
+        Some((ref mapped, addr)) if mapped == host => addr,
+        _ => config
+            .socket
+            .resolve(&format!("{}:{}", host, port.unwrap_or("443")))
+            .await
+            .map_err(|e| format!("--sni: failed to resolve {}: {}", host, e))?[0],
+    };
+
+    let origin = format!("https://{}", authority);
+    let routed = match port {
+        Some(port) => format!("https://{}:{}", name, port),
+        None => format!("https://{}", name),
+    };
+    let rebase = |url: &mut String| match url.strip_prefix(&origin) {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '?', '#']) => {
+            *url = format!("{}{}", routed, rest);
+            Ok(())
+        }
+        _ => Err(format!(
+            "--sni only routes requests to {}, not {}",
+            origin, url
+        )),
+    };
+
+    rebase(&mut config.url)?;
+    with_host(&mut config.headers, &authority);
+    for url in config.url_list.iter_mut().flatten() {
+        rebase(url)?;
+    }
+    for scenario in &mut config.scenarios {
+        rebase(&mut scenario.url)?;
+        with_host(&mut scenario.headers, &authority);
+    }
+    config.connect_to = Some((name, addr));
+    Ok(())
+}
+
+/// Host and port of `host[:port]`, IPv6 literals keeping their brackets
+fn split_authority(authority: &str) -> (&str, Option<&str>) {
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => (host, Some(port)),
+        _ => (authority, None),
+    }
+}
+
+fn with_host(headers: &mut Vec<(String, String)>, authority: &str) {
+    if !headers.iter().any(|(h, _)| h.eq_ignore_ascii_case("host")) {
+        headers.push(("Host".to_string(), authority.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Scenario;
+
+    #[tokio::test]
+    async fn moves_target_urls_onto_the_sni_name() {
+        let addr = "127.0.0.1:8443".parse().unwrap();
+        let mut config = LoadConfig {
+            url: "https://origin.internal:8443/api?x=1".to_string(),
+            sni: Some("tenant.example.com".to_string()),
+            connect_to: Some(("origin.internal".to_string(), addr)),
+            url_list: Some(vec!["https://origin.internal:8443".to_string()]),
+            scenarios: vec![Scenario {
+                name: "item".to_string(),
+                url: "https://origin.internal:8443/{{id}}".to_string(),
+                method: reqwest::Method::GET,
+                headers: vec![("host".to_string(), "other".to_string())],
+                body: None,
+                weight: 1,
+                extractions: Vec::new(),
+                depends_on: None,
+                tags: Default::default(),
+                proxy: None,
+                think_time: None,
+            }],
+            ..LoadConfig::default()
+        };
+        route(&mut config).await.unwrap();
+
+        assert_eq!(config.url, "https://tenant.example.com:8443/api?x=1");
+        assert_eq!(
+            config.headers,
+            vec![("Host".to_string(), "origin.internal:8443".to_string())]
+        );
+        assert_eq!(
+            config.url_list.as_deref().unwrap(),
+            ["https://tenant.example.com:8443"]
+        );
+        assert_eq!(
+            config.scenarios[0].url,
+            "https://tenant.example.com:8443/{{id}}"
+        );
+        // A Host header the scenario sets itself wins
+        assert_eq!(config.scenarios[0].headers.len(), 1);
+        assert_eq!(
+            config.connect_to,
+            Some(("tenant.example.com".to_string(), addr))
+        );
+
+        // Requests to other hosts can't be given the name
+        let mut config = LoadConfig {
+            url: "https://origin.internal/".to_string(),
+            sni: Some("tenant.example.com".to_string()),
+            connect_to: Some(("origin.internal".to_string(), addr)),
+            url_list: Some(vec!["https://origin.internal.evil/".to_string()]),
+            ..LoadConfig::default()
+        };
+        assert!(route(&mut config).await.is_err());
+    }
+
+    #[test]
+    fn splits_ports_off_hosts() {
+        assert_eq!(split_authority("a.com"), ("a.com", None));
+        assert_eq!(split_authority("a.com:8443"), ("a.com", Some("8443")));
+        assert_eq!(split_authority("[::1]"), ("[::1]", None));
+        assert_eq!(split_authority("[::1]:8443"), ("[::1]", Some("8443")));
+    }
+}
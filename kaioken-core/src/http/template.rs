@@ -0,0 +1,131 @@
+use super::compression::compress;
+use crate::types::{BodyCompression, Scenario};
+use bytes::Bytes;
+use reqwest::header::{CONTENT_ENCODING, HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, Url};
+
+/// A request built once at startup: parsed URL, validated header map and body
+/// bytes are reused for every request instead of being rebuilt each time.
+///
+/// Only requests without `${...}` placeholders can be prebuilt; anything that
+/// needs per-request interpolation goes through `execute_request` instead.
+#[derive(Debug, Clone)]
+pub struct PreparedRequest {
+    pub(super) method: Method,
+    pub(super) url: Url,
+    pub(super) headers: HeaderMap,
+    pub(super) body: Option<Bytes>,
+}
+
+impl PreparedRequest {
+    /// `None` if any part needs interpolation or doesn't parse, in which case
+    /// the dynamic path builds (and reports errors for) the request as before
+    pub fn new(
+        method: &Method,
+        url: &str,
+        headers: &[(String, String)],
+        body: Option<&str>,
+    ) -> Option<Self> {
+        if has_placeholders(url)
+            || headers.iter().any(|(_, v)| has_placeholders(v))
+            || body.is_some_and(has_placeholders)
+        {
+            return None;
+        }
+
+        let url = Url::parse(url).ok()?;
+        let mut header_map = HeaderMap::with_capacity(headers.len());
+        for (name, value) in headers {
+            let name = HeaderName::try_from(name.as_str()).ok()?;
+            let value = HeaderValue::try_from(value.as_str()).ok()?;
+            header_map.append(name, value);
+        }
+
+        Some(Self {
+            method: method.clone(),
+            url,
+            headers: header_map,
+            body: body.map(|b| Bytes::copy_from_slice(b.as_bytes())),
+        })
+    }
+
+    /// Compress the body once here rather than on every request
+    pub fn compressed(mut self, compression: Option<BodyCompression>) -> Self {
+        if let (Some(body), Some(encoding)) = (&self.body, compression) {
+            self.body = Some(compress(body, encoding));
+            self.headers.insert(
+                CONTENT_ENCODING,
+                HeaderValue::from_static(encoding.as_str()),
+            );
+        }
+        self
+    }
+
+    pub fn for_scenario(scenario: &Scenario) -> Option<Self> {
+        Self::new(
+            &scenario.method,
+            &scenario.url,
+            &scenario.headers,
+            scenario.body.as_deref(),
+        )
+    }
+}
+
+fn has_placeholders(s: &str) -> bool {
+    s.contains("${")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prebuilds_static_requests() {
+        let headers = vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("X-Tag".to_string(), "a".to_string()),
+            ("X-Tag".to_string(), "b".to_string()),
+        ];
+        let prepared = PreparedRequest::new(
+            &Method::POST,
+            "http://localhost:8080/api?x=1",
+            &headers,
+            Some(r#"{"ok":true}"#),
+        )
+        .unwrap();
+
+        assert_eq!(prepared.method, Method::POST);
+        assert_eq!(prepared.url.as_str(), "http://localhost:8080/api?x=1");
+        assert_eq!(prepared.headers["content-type"], "application/json");
+        assert_eq!(prepared.headers.get_all("x-tag").iter().count(), 2);
+        assert_eq!(prepared.body.as_deref(), Some(&br#"{"ok":true}"#[..]));
+    }
+
+    #[test]
+    fn falls_back_for_placeholders_and_bad_input() {
+        let get = Method::GET;
+        assert!(PreparedRequest::new(&get, "http://h/${REQUEST_ID}", &[], None).is_none());
+        assert!(
+            PreparedRequest::new(
+                &get,
+                "http://h/",
+                &[("X-Id".to_string(), "${TIMESTAMP_MS}".to_string())],
+                None
+            )
+            .is_none()
+        );
+        assert!(
+            PreparedRequest::new(&get, "http://h/", &[], Some("{\"t\":\"${token}\"}")).is_none()
+        );
+        assert!(PreparedRequest::new(&get, "not a url", &[], None).is_none());
+        assert!(
+            PreparedRequest::new(
+                &get,
+                "http://h/",
+                &[("Bad Header".to_string(), "v".to_string())],
+                None
+            )
+            .is_none()
+        );
+    }
+}
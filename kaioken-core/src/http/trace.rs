@@ -0,0 +1,64 @@
+//! Trace context headers (`--trace-propagation`)
+//!
+//! Every request starts its own sampled trace with random IDs, so the
+//! slowest requests can be looked up in Jaeger or Tempo by the trace IDs
+//! kaioken reports.
+
+use crate::types::TracePropagation;
+use reqwest::RequestBuilder;
+
+/// Start a new trace on `request`; returns the request and its trace ID
+pub fn inject(request: RequestBuilder, format: TracePropagation) -> (RequestBuilder, u128) {
+    // All-zero IDs are invalid in both formats
+    let trace_id = rand::random::<u128>().max(1);
+    let span_id = rand::random::<u64>().max(1);
+    let request = headers(format, trace_id, span_id)
+        .into_iter()
+        .fold(request, |request, (name, value)| {
+            request.header(name, value)
+        });
+    (request, trace_id)
+}
+
+fn headers(format: TracePropagation, trace_id: u128, span_id: u64) -> Vec<(&'static str, String)> {
+    match format {
+        TracePropagation::W3c => vec![(
+            "traceparent",
+            format!("00-{:032x}-{:016x}-01", trace_id, span_id),
+        )],
+        TracePropagation::B3 => vec![
+            ("x-b3-traceid", format!("{:032x}", trace_id)),
+            ("x-b3-spanid", format!("{:016x}", span_id)),
+            ("x-b3-sampled", "1".to_string()),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_trace_headers() {
+        let trace_id = 0x4bf92f3577b34da6a3ce929d0e0e4736;
+        let span_id = 0x00f067aa0ba902b7;
+        assert_eq!(
+            headers(TracePropagation::W3c, trace_id, span_id),
+            [(
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string()
+            )]
+        );
+        assert_eq!(
+            headers(TracePropagation::B3, 1, 2),
+            [
+                (
+                    "x-b3-traceid",
+                    "00000000000000000000000000000001".to_string()
+                ),
+                ("x-b3-spanid", "0000000000000002".to_string()),
+                ("x-b3-sampled", "1".to_string()),
+            ]
+        );
+    }
+}
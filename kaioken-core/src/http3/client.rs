@@ -0,0 +1,569 @@
+//! HTTP/3 client using h3 + quinn
+
+use bytes::Buf;
+use h3::client::SendRequest;
+use h3_quinn::OpenStreams;
+use quinn::{ClientConfig, Endpoint};
+use reqwest::Url;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::net::{IpFamily, SocketOptions};
+use crate::types::{ErrorKind, LoadConfig, QuicHandshake, QuicSample, RequestResult};
+
+/// HTTP/3 client wrapper
+pub struct Http3Client {
+    endpoint: Endpoint,
+    connect_to: Option<(String, SocketAddr)>,
+    socket: SocketOptions,
+    resolved: Mutex<HashMap<(String, u16), SocketAddr>>,
+    cookies: Option<Mutex<CookieJar>>,
+    zero_rtt: bool,
+}
+
+/// HTTP/3 response data
+#[allow(dead_code)]
+pub struct Http3Response {
+    pub status: u16,
+    pub body: Vec<u8>,
+    pub latency_us: u64,
+}
+
+/// Where a request URL goes on the wire
+struct Target {
+    addr: SocketAddr,
+    host: String,
+    authority: String,
+    path: String,
+}
+
+impl Http3Client {
+    /// Create a new HTTP/3 client honoring the TLS, resolution and cookie
+    /// settings of the load config
+    pub fn new(config: &LoadConfig) -> Result<Self, String> {
+        let mut roots = Self::root_certs()?;
+        if let Some(ca_path) = &config.ca_cert {
+            for cert in load_certs(ca_path)? {
+                roots
+                    .add(cert)
+                    .map_err(|e| format!("Invalid CA certificate: {}", e))?;
+            }
+        }
+
+        let builder = rustls::ClientConfig::builder().with_root_certificates(roots);
+        let mut crypto = match (&config.client_cert, &config.client_key) {
+            (Some(cert_path), Some(key_path)) => builder
+                .with_client_auth_cert(load_certs(cert_path)?, load_key(key_path)?)
+                .map_err(|e| format!("Invalid client certificate: {}", e))?,
+            _ => builder.with_no_client_auth(),
+        };
+
+        if config.insecure {
+            crypto
+                .dangerous()
+                .set_certificate_verifier(Arc::new(InsecureVerifier));
+        }
+
+        crypto.alpn_protocols = vec![b"h3".to_vec()];
+        crypto.enable_early_data = config.http3_0rtt;
+
+        let client_config = ClientConfig::new(Arc::new(
+            quinn::crypto::rustls::QuicClientConfig::try_from(crypto)
+                .map_err(|e| format!("Failed to create QUIC config: {}", e))?,
+        ));
+
+        // One UDP socket carries every connection, so it picks the family
+        let local = match config.socket.ip_family {
+            IpFamily::V6 => "[::]:0",
+            IpFamily::V4 | IpFamily::Any => "0.0.0.0:0",
+        };
+        let mut endpoint = Endpoint::client(local.parse().unwrap())
+            .map_err(|e| format!("Failed to create endpoint: {}", e))?;
+        endpoint.set_default_client_config(client_config);
+
+        Ok(Self {
+            endpoint,
+            connect_to: config.connect_to.clone(),
+            socket: config.socket.clone(),
+            resolved: Mutex::new(HashMap::new()),
+            cookies: config.cookie_jar.then(|| Mutex::new(CookieJar::default())),
+            zero_rtt: config.http3_0rtt,
+        })
+    }
+
+    fn root_certs() -> Result<rustls::RootCertStore, String> {
+        let mut roots = rustls::RootCertStore::empty();
+        let result = rustls_native_certs::load_native_certs();
+
+        // Check for errors (but don't fail if some certs loaded)
+        if result.certs.is_empty() && !result.errors.is_empty() {
+            return Err(format!("Failed to load native certs: {:?}", result.errors));
+        }
+
+        for cert in result.certs {
+            roots.add(cert).ok();
+        }
+        Ok(roots)
+    }
+
+    /// Resolve a request URL to a socket address, honoring --connect-to.
+    /// Lookups are cached so DNS isn't hit on every request.
+    async fn target(&self, url: &str) -> Result<Target, String> {
+        let parsed = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+        let host = parsed.host_str().ok_or("Missing host in URL")?.to_string();
+        let port = parsed.port().unwrap_or(443);
+
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None if parsed.path().is_empty() => "/".to_string(),
+            None => parsed.path().to_string(),
+        };
+        let authority = match parsed.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.clone(),
+        };
+
+        let addr = match &self.connect_to {
+            Some((override_host, addr)) if *override_host == host => *addr,
+            _ => self.resolve(&host, port).await?,
+        };
+
+        Ok(Target {
+            addr,
+            host,
+            authority,
+            path,
+        })
+    }
+
+    async fn resolve(&self, host: &str, port: u16) -> Result<SocketAddr, String> {
+        let key = (host.to_string(), port);
+        if let Some(addr) = self.resolved.lock().unwrap().get(&key) {
+            return Ok(*addr);
+        }
+
+        let addr = self
+            .socket
+            .resolve(&format!("{}:{}", host, port))
+            .await
+            .map_err(|e| format!("Failed to resolve {}:{}: {}", host, port, e))?[0];
+
+        self.resolved.lock().unwrap().insert(key, addr);
+        Ok(addr)
+    }
+
+    /// Connect to a server and return a send request handle plus the
+    /// underlying QUIC connection (for path stats). With `early` set and a
+    /// resumption ticket cached, the connection is usable immediately as
+    /// 0-RTT and the returned future reports whether the server accepted it.
+    pub async fn connect(
+        &self,
+        addr: SocketAddr,
+        server_name: &str,
+        early: bool,
+    ) -> Result<
+        (
+            SendRequest<OpenStreams, bytes::Bytes>,
+            quinn::Connection,
+            Option<quinn::ZeroRttAccepted>,
+        ),
+        String,
+    > {
+        let connecting = self
+            .endpoint
+            .connect(addr, server_name)
+            .map_err(|e| format!("Failed to connect: {}", e))?;
+
+        let (connection, accepted) = match early {
+            true => match connecting.into_0rtt() {
+                Ok((connection, accepted)) => (connection, Some(accepted)),
+                // No resumption ticket yet: full handshake
+                Err(connecting) => (
+                    connecting
+                        .await
+                        .map_err(|e| format!("Connection failed: {}", e))?,
+                    None,
+                ),
+            },
+            false => (
+                connecting
+                    .await
+                    .map_err(|e| format!("Connection failed: {}", e))?,
+                None,
+            ),
+        };
+
+        let (mut driver, send_request) =
+            h3::client::new(h3_quinn::Connection::new(connection.clone()))
+                .await
+                .map_err(|e| format!("H3 handshake failed: {}", e))?;
+
+        // Spawn the connection driver
+        tokio::spawn(async move {
+            // poll_close drives the connection and returns when closed
+            let err = futures_util::future::poll_fn(|cx| driver.poll_close(cx)).await;
+            tracing::debug!("H3 connection closed: {:?}", err);
+        });
+
+        Ok((send_request, connection, accepted))
+    }
+
+    fn cookie_header(&self, host: &str) -> Option<String> {
+        self.cookies.as_ref()?.lock().unwrap().header(host)
+    }
+
+    fn store_cookies<'a>(&self, host: &str, set_cookies: impl Iterator<Item = &'a str>) {
+        if let Some(jar) = &self.cookies {
+            let mut jar = jar.lock().unwrap();
+            for set_cookie in set_cookies {
+                jar.store(host, set_cookie);
+            }
+        }
+    }
+}
+
+/// Minimal per-host cookie store shared by all HTTP/3 workers
+#[derive(Default)]
+struct CookieJar {
+    by_host: HashMap<String, Vec<(String, String)>>,
+}
+
+impl CookieJar {
+    fn header(&self, host: &str) -> Option<String> {
+        let cookies = self.by_host.get(host).filter(|c| !c.is_empty())?;
+        Some(
+            cookies
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+
+    fn store(&mut self, host: &str, set_cookie: &str) {
+        let pair = set_cookie.split(';').next().unwrap_or("");
+        let Some((name, value)) = pair.split_once('=') else {
+            return;
+        };
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+
+        let cookies = self.by_host.entry(host.to_string()).or_default();
+        cookies.retain(|(existing, _)| *existing != name);
+        if !value.trim().is_empty() {
+            cookies.push((name, value.trim().to_string()));
+        }
+    }
+}
+
+fn load_certs(path: &Path) -> Result<Vec<rustls::pki_types::CertificateDer<'static>>, String> {
+    let pem = std::fs::read(path)
+        .map_err(|e| format!("Failed to read certificate {}: {}", path.display(), e))?;
+    let certs = rustls_pemfile::certs(&mut pem.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Invalid certificate {}: {}", path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> Result<rustls::pki_types::PrivateKeyDer<'static>, String> {
+    let pem = std::fs::read(path)
+        .map_err(|e| format!("Failed to read private key {}: {}", path.display(), e))?;
+    rustls_pemfile::private_key(&mut pem.as_slice())
+        .map_err(|e| format!("Invalid private key {}: {}", path.display(), e))?
+        .ok_or_else(|| format!("No private key found in {}", path.display()))
+}
+
+/// Execute an HTTP/3 request and return the result
+pub async fn execute_http3_request(
+    client: &Http3Client,
+    url: &str,
+    method: &str,
+    headers: &[(String, String)],
+    body: Option<&str>,
+    timeout: Duration,
+) -> RequestResult {
+    let start = Instant::now();
+
+    let result = tokio::time::timeout(timeout, async {
+        let target = client.target(url).await?;
+
+        let mut attempt = send_once(client, &target, method, headers, body, client.zero_rtt).await;
+        if attempt.response.is_err()
+            && attempt.quic.handshake == Some(QuicHandshake::ZeroRttRejected)
+        {
+            // The server discarded the early data; resend over a full handshake
+            let retry = send_once(client, &target, method, headers, body, false).await;
+            attempt = Attempt {
+                response: retry.response,
+                quic: QuicSample {
+                    handshake: Some(QuicHandshake::ZeroRttRejected),
+                    ..retry.quic
+                },
+            };
+        }
+
+        let (status, body) = attempt.response?;
+        Ok::<_, String>((status, body, attempt.quic))
+    })
+    .await;
+
+    let latency_us = start.elapsed().as_micros() as u64;
+    let bytes_sent = body.map_or(0, |b| b.len() as u64);
+
+    match result {
+        Ok(Ok((status, body, quic))) => RequestResult {
+            status: Some(status),
+            latency_us,
+            bytes_received: body.len() as u64,
+            error: None,
+            body: Some(String::from_utf8_lossy(&body).to_string()),
+            scheduled_at_us: None,
+            started_at_us: None,
+            queue_time_us: None,
+            quic: Some(quic),
+            jsonrpc_error: None,
+            endpoint: None,
+            host: None,
+            bytes_sent,
+            upload_us: None,
+            failure: None,
+            trace_id: None,
+            id_echo: None,
+            retry_after: None,
+            content_type: None,
+            headers: None,
+        },
+        Ok(Err(_e)) => RequestResult {
+            status: None,
+            latency_us,
+            bytes_received: 0,
+            error: Some(ErrorKind::Other),
+            body: None,
+            scheduled_at_us: None,
+            started_at_us: None,
+            queue_time_us: None,
+            quic: None,
+            jsonrpc_error: None,
+            endpoint: None,
+            host: None,
+            bytes_sent: 0,
+            upload_us: None,
+            failure: None,
+            trace_id: None,
+            id_echo: None,
+            retry_after: None,
+            content_type: None,
+            headers: None,
+        },
+        Err(_) => RequestResult {
+            status: None,
+            latency_us,
+            bytes_received: 0,
+            error: Some(ErrorKind::Timeout),
+            body: None,
+            scheduled_at_us: None,
+            started_at_us: None,
+            queue_time_us: None,
+            quic: None,
+            jsonrpc_error: None,
+            endpoint: None,
+            host: None,
+            bytes_sent: 0,
+            upload_us: None,
+            failure: None,
+            trace_id: None,
+            id_echo: None,
+            retry_after: None,
+            content_type: None,
+            headers: None,
+        },
+    }
+}
+
+/// One request on a fresh connection, with the connection's QUIC stats
+struct Attempt {
+    response: Result<(u16, Vec<u8>), String>,
+    quic: QuicSample,
+}
+
+async fn send_once(
+    client: &Http3Client,
+    target: &Target,
+    method: &str,
+    headers: &[(String, String)],
+    body: Option<&str>,
+    early: bool,
+) -> Attempt {
+    let (mut send_request, connection, accepted) =
+        match client.connect(target.addr, &target.host, early).await {
+            Ok(connected) => connected,
+            Err(e) => {
+                return Attempt {
+                    response: Err(e),
+                    quic: QuicSample::default(),
+                };
+            }
+        };
+
+    let response = exchange(&mut send_request, client, target, method, headers, body).await;
+
+    let handshake = match accepted {
+        Some(accepted) => Some(if accepted.await {
+            QuicHandshake::ZeroRtt
+        } else {
+            QuicHandshake::ZeroRttRejected
+        }),
+        None if client.zero_rtt => Some(QuicHandshake::OneRtt),
+        None => None,
+    };
+
+    Attempt {
+        response,
+        quic: QuicSample {
+            handshake,
+            ..quic_sample(&connection.stats())
+        },
+    }
+}
+
+/// Send the request and read the full response
+async fn exchange(
+    send_request: &mut SendRequest<OpenStreams, bytes::Bytes>,
+    client: &Http3Client,
+    target: &Target,
+    method: &str,
+    headers: &[(String, String)],
+    body: Option<&str>,
+) -> Result<(u16, Vec<u8>), String> {
+    // Build request
+    let mut req = http::Request::builder()
+        .method(method)
+        .uri(format!("https://{}{}", target.authority, target.path));
+
+    for (name, value) in headers {
+        req = req.header(name.as_str(), value.as_str());
+    }
+    if let Some(cookie) = client.cookie_header(&target.host) {
+        req = req.header("cookie", cookie);
+    }
+
+    let req = req
+        .body(())
+        .map_err(|e| format!("Failed to build request: {}", e))?;
+
+    // Send request
+    let mut stream = send_request
+        .send_request(req)
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    // Send body if present
+    if let Some(body_data) = body {
+        stream
+            .send_data(bytes::Bytes::from(body_data.to_string()))
+            .await
+            .map_err(|e| format!("Failed to send body: {}", e))?;
+    }
+
+    stream
+        .finish()
+        .await
+        .map_err(|e| format!("Failed to finish stream: {}", e))?;
+
+    // Receive response
+    let response = stream
+        .recv_response()
+        .await
+        .map_err(|e| format!("Failed to receive response: {}", e))?;
+
+    let status = response.status().as_u16();
+    client.store_cookies(
+        &target.host,
+        response
+            .headers()
+            .get_all(http::header::SET_COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok()),
+    );
+
+    // Read response body
+    let mut body = Vec::new();
+    while let Some(chunk) = stream
+        .recv_data()
+        .await
+        .map_err(|e| format!("Failed to receive data: {}", e))?
+    {
+        body.extend_from_slice(chunk.chunk());
+    }
+
+    Ok((status, body))
+}
+
+fn quic_sample(stats: &quinn::ConnectionStats) -> QuicSample {
+    QuicSample {
+        rtt_us: stats.path.rtt.as_micros() as u64,
+        cwnd: stats.path.cwnd,
+        sent_packets: stats.path.sent_packets,
+        lost_packets: stats.path.lost_packets,
+        congestion_events: stats.path.congestion_events,
+        handshake: None,
+    }
+}
+
+/// Insecure certificate verifier for testing
+#[derive(Debug)]
+struct InsecureVerifier;
+
+impl rustls::client::danger::ServerCertVerifier for InsecureVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::pki_types::CertificateDer<'_>,
+        _intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        _server_name: &rustls::pki_types::ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: rustls::pki_types::UnixTime,
+    ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &rustls::pki_types::CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &rustls::pki_types::CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        vec![
+            rustls::SignatureScheme::RSA_PKCS1_SHA256,
+            rustls::SignatureScheme::RSA_PKCS1_SHA384,
+            rustls::SignatureScheme::RSA_PKCS1_SHA512,
+            rustls::SignatureScheme::ECDSA_NISTP256_SHA256,
+            rustls::SignatureScheme::ECDSA_NISTP384_SHA384,
+            rustls::SignatureScheme::ECDSA_NISTP521_SHA512,
+            rustls::SignatureScheme::RSA_PSS_SHA256,
+            rustls::SignatureScheme::RSA_PSS_SHA384,
+            rustls::SignatureScheme::RSA_PSS_SHA512,
+            rustls::SignatureScheme::ED25519,
+        ]
+    }
+}
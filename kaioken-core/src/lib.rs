@@ -0,0 +1,62 @@
+//! Load generation engine behind the `kaioken` CLI.
+//!
+//! Build a [`types::LoadConfig`], hand it to [`engine::Engine`] and await
+//! [`engine::Engine::run`]. While the run is going, the engine publishes a
+//! [`types::StatsSnapshot`] about ten times a second; the last one holds the
+//! final numbers.
+//!
+//! ```no_run
+//! use kaioken_core::engine::Engine;
+//! use kaioken_core::types::LoadConfig;
+//! use std::time::Duration;
+//!
+//! # async fn run() -> Result<(), String> {
+//! let config = LoadConfig {
+//!     url: "http://localhost:8080/health".to_string(),
+//!     concurrency: 10,
+//!     duration: Duration::from_secs(5),
+//!     ..Default::default()
+//! };
+//!
+//! let engine = Engine::new(config);
+//! let snapshots = engine.snapshot_rx();
+//! engine.run().await?;
+//!
+//! let snapshot = snapshots.borrow();
+//! println!(
+//!     "{} requests, {:.1} req/s, p99 {}µs",
+//!     snapshot.total_requests, snapshot.requests_per_sec, snapshot.latency_p99_us
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The URL scheme picks the protocol (`ws://`, `tcp://`, `udp://`,
+//! `redis://`, `mqtt://`), as do fields such as `LoadConfig::grpc_service`
+//! and `LoadConfig::http3`. Beyond HTTP/1.1, HTTP/2, WebSocket and raw
+//! TCP/UDP, each needs its cargo feature: `http3`, `grpc`, `socketio`,
+//! `mqtt` or `redis`. The `plugins` feature adds the `plugin` module: WASM
+//! hooks that rewrite requests and judge responses (`LoadConfig::plugins`).
+//! [`engine::evaluate_thresholds`] checks a finished snapshot against
+//! `LoadConfig::thresholds`.
+
+pub mod engine;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod history;
+pub mod http;
+#[cfg(feature = "http3")]
+pub mod http3;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod net;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+pub mod probe;
+pub mod raw;
+#[cfg(feature = "redis")]
+pub mod redis;
+#[cfg(feature = "socketio")]
+pub mod socketio;
+pub mod types;
+pub mod ws;
@@ -0,0 +1,18 @@
+//! MQTT client implementation (feature-gated)
+//!
+//! Enable with: cargo build --features mqtt
+//!
+//! Speaks MQTT 3.1.1 over plain TCP:
+//! - CONNECT / CONNACK with optional username and password
+//! - PUBLISH at QoS 0 (fire-and-forget) or QoS 1 (PUBACK latency)
+//! - SUBSCRIBE with wildcard filters, acknowledging QoS 1 deliveries
+//! - PINGREQ keepalive on idle connections
+
+mod client;
+mod packet;
+
+#[allow(unused_imports)]
+pub use client::{
+    Broker, MqttConnection, PUBLISHER_PLACEHOLDER, connect, parse_broker_url, publish_topic,
+    subscription_filter,
+};
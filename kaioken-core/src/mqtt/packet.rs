@@ -0,0 +1,313 @@
+//! MQTT 3.1.1 control packet encoding and decoding
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const PUBACK: u8 = 0x40;
+const SUBSCRIBE: u8 = 0x82;
+const SUBACK: u8 = 0x90;
+const PINGRESP: u8 = 0xD0;
+
+pub const PINGREQ: [u8; 2] = [0xC0, 0x00];
+pub const DISCONNECT: [u8; 2] = [0xE0, 0x00];
+
+/// Packets a client receives from the broker
+#[derive(Debug, PartialEq)]
+pub enum Packet {
+    ConnAck {
+        return_code: u8,
+    },
+    Publish {
+        qos: u8,
+        packet_id: Option<u16>,
+        topic: String,
+        payload: Vec<u8>,
+    },
+    PubAck {
+        packet_id: u16,
+    },
+    SubAck {
+        packet_id: u16,
+        return_codes: Vec<u8>,
+    },
+    PingResp,
+    /// Anything a QoS 0/1 client doesn't act on
+    Other(u8),
+}
+
+/// Human-readable CONNACK return code
+pub fn connack_reason(code: u8) -> &'static str {
+    match code {
+        0 => "accepted",
+        1 => "unacceptable protocol version",
+        2 => "identifier rejected",
+        3 => "server unavailable",
+        4 => "bad user name or password",
+        5 => "not authorized",
+        _ => "unknown",
+    }
+}
+
+pub fn encode_connect(
+    client_id: &str,
+    keep_alive_secs: u16,
+    username: Option<&str>,
+    password: Option<&str>,
+) -> Vec<u8> {
+    let mut flags = 0x02; // clean session
+    if username.is_some() {
+        flags |= 0x80;
+    }
+    if password.is_some() {
+        flags |= 0x40;
+    }
+
+    let mut body = Vec::with_capacity(16 + client_id.len());
+    put_str(&mut body, "MQTT");
+    body.push(4); // protocol level 3.1.1
+    body.push(flags);
+    body.extend_from_slice(&keep_alive_secs.to_be_bytes());
+    put_str(&mut body, client_id);
+    if let Some(username) = username {
+        put_str(&mut body, username);
+    }
+    if let Some(password) = password {
+        put_str(&mut body, password);
+    }
+
+    frame(CONNECT, &body)
+}
+
+pub fn encode_publish(topic: &str, payload: &[u8], qos: u8, packet_id: u16) -> Vec<u8> {
+    let mut body = Vec::with_capacity(4 + topic.len() + payload.len());
+    put_str(&mut body, topic);
+    if qos > 0 {
+        body.extend_from_slice(&packet_id.to_be_bytes());
+    }
+    body.extend_from_slice(payload);
+
+    frame(PUBLISH | (qos << 1), &body)
+}
+
+pub fn encode_puback(packet_id: u16) -> Vec<u8> {
+    frame(PUBACK, &packet_id.to_be_bytes())
+}
+
+pub fn encode_subscribe(packet_id: u16, filter: &str, qos: u8) -> Vec<u8> {
+    let mut body = Vec::with_capacity(5 + filter.len());
+    body.extend_from_slice(&packet_id.to_be_bytes());
+    put_str(&mut body, filter);
+    body.push(qos);
+
+    frame(SUBSCRIBE, &body)
+}
+
+/// Decode a packet body given its fixed-header byte
+pub fn decode(header: u8, body: &[u8]) -> Result<Packet, String> {
+    match header & 0xF0 {
+        CONNACK => {
+            if body.len() != 2 {
+                return Err("CONNACK must be 2 bytes".to_string());
+            }
+            Ok(Packet::ConnAck {
+                return_code: body[1],
+            })
+        }
+        PUBLISH => {
+            let qos = (header >> 1) & 0x03;
+            if qos > 2 {
+                return Err("PUBLISH with invalid QoS 3".to_string());
+            }
+            let (topic, mut rest) = take_str(body)?;
+            let packet_id = if qos > 0 {
+                let (id, tail) = take_u16(rest)?;
+                rest = tail;
+                Some(id)
+            } else {
+                None
+            };
+            Ok(Packet::Publish {
+                qos,
+                packet_id,
+                topic,
+                payload: rest.to_vec(),
+            })
+        }
+        PUBACK => {
+            let (packet_id, _) = take_u16(body)?;
+            Ok(Packet::PubAck { packet_id })
+        }
+        SUBACK => {
+            let (packet_id, rest) = take_u16(body)?;
+            Ok(Packet::SubAck {
+                packet_id,
+                return_codes: rest.to_vec(),
+            })
+        }
+        PINGRESP => Ok(Packet::PingResp),
+        other => Ok(Packet::Other(other)),
+    }
+}
+
+/// Append the variable-length remaining-length encoding of `len`
+pub fn encode_remaining_length(mut len: usize, buf: &mut Vec<u8>) {
+    loop {
+        let mut byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        buf.push(byte);
+        if len == 0 {
+            break;
+        }
+    }
+}
+
+/// Locate the first complete packet in `buf`.
+///
+/// Returns the body range and total frame length, or `None` if more bytes
+/// are needed.
+pub fn split_frame(buf: &[u8]) -> Result<Option<(std::ops::Range<usize>, usize)>, String> {
+    let mut len = 0usize;
+    for index in 0..4 {
+        let Some(&byte) = buf.get(1 + index) else {
+            return Ok(None);
+        };
+        len += ((byte & 0x7F) as usize) << (7 * index);
+        if byte & 0x80 == 0 {
+            let start = 2 + index;
+            let end = start + len;
+            if buf.len() < end {
+                return Ok(None);
+            }
+            return Ok(Some((start..end, end)));
+        }
+    }
+    Err("Malformed remaining length (more than 4 bytes)".to_string())
+}
+
+fn frame(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(body.len() + 5);
+    buf.push(header);
+    encode_remaining_length(body.len(), &mut buf);
+    buf.extend_from_slice(body);
+    buf
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn take_u16(buf: &[u8]) -> Result<(u16, &[u8]), String> {
+    if buf.len() < 2 {
+        return Err("Truncated packet".to_string());
+    }
+    Ok((u16::from_be_bytes([buf[0], buf[1]]), &buf[2..]))
+}
+
+fn take_str(buf: &[u8]) -> Result<(String, &[u8]), String> {
+    let (len, rest) = take_u16(buf)?;
+    let len = len as usize;
+    if rest.len() < len {
+        return Err("Truncated string".to_string());
+    }
+    let s = std::str::from_utf8(&rest[..len]).map_err(|_| "Topic is not valid UTF-8")?;
+    Ok((s.to_string(), &rest[len..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_length_round_trips() {
+        for len in [0, 127, 128, 16_383, 16_384, 2_097_151] {
+            let mut buf = vec![0x30];
+            encode_remaining_length(len, &mut buf);
+            let header_len = buf.len();
+            buf.resize(header_len + len, 0);
+            let (body, total) = split_frame(&buf).unwrap().unwrap();
+            assert_eq!(body, header_len..header_len + len);
+            assert_eq!(total, buf.len());
+        }
+        let mut buf = Vec::new();
+        encode_remaining_length(321, &mut buf);
+        assert_eq!(buf, vec![0xC1, 0x02]);
+        let mut buf = Vec::new();
+        // Largest value the 4-byte field can hold
+        encode_remaining_length(268_435_455, &mut buf);
+        assert_eq!(buf, vec![0xFF, 0xFF, 0xFF, 0x7F]);
+    }
+
+    #[test]
+    fn split_frame_waits_for_complete_packet() {
+        assert_eq!(split_frame(&[]).unwrap(), None);
+        assert_eq!(split_frame(&[0x30]).unwrap(), None);
+        assert_eq!(split_frame(&[0x30, 0x80]).unwrap(), None);
+        assert_eq!(split_frame(&[0x30, 3, 0, 1]).unwrap(), None);
+        assert_eq!(split_frame(&[0xD0, 0, 0x30]).unwrap(), Some((2..2, 2)));
+        assert!(split_frame(&[0x30, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]).is_err());
+    }
+
+    #[test]
+    fn connect_packet_layout() {
+        let packet = encode_connect("k1", 30, Some("u"), Some("p"));
+        assert_eq!(
+            packet,
+            vec![
+                0x10, 20, 0, 4, b'M', b'Q', b'T', b'T', 4, 0xC2, 0, 30, 0, 2, b'k', b'1', 0, 1,
+                b'u', 0, 1, b'p'
+            ]
+        );
+    }
+
+    #[test]
+    fn publish_round_trips() {
+        let packet = encode_publish("a/b", b"hi", 1, 7);
+        assert_eq!(packet[0], 0x32);
+        let decoded = decode(packet[0], &packet[2..]).unwrap();
+        assert_eq!(
+            decoded,
+            Packet::Publish {
+                qos: 1,
+                packet_id: Some(7),
+                topic: "a/b".to_string(),
+                payload: b"hi".to_vec(),
+            }
+        );
+
+        let packet = encode_publish("t", b"x", 0, 7);
+        assert_eq!(packet, vec![0x30, 4, 0, 1, b't', b'x']);
+    }
+
+    #[test]
+    fn decodes_acks() {
+        assert_eq!(
+            decode(0x20, &[0, 5]).unwrap(),
+            Packet::ConnAck { return_code: 5 }
+        );
+        assert_eq!(
+            decode(0x40, &[0, 9]).unwrap(),
+            Packet::PubAck { packet_id: 9 }
+        );
+        assert_eq!(
+            decode(0x90, &[0, 3, 0x80]).unwrap(),
+            Packet::SubAck {
+                packet_id: 3,
+                return_codes: vec![0x80],
+            }
+        );
+        assert_eq!(decode(0xD0, &[]).unwrap(), Packet::PingResp);
+        assert!(decode(0x40, &[0]).is_err());
+    }
+
+    #[test]
+    fn subscribe_packet_layout() {
+        assert_eq!(
+            encode_subscribe(1, "a/+", 1),
+            vec![0x82, 8, 0, 1, 0, 3, b'a', b'/', b'+', 1]
+        );
+    }
+}
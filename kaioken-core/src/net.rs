@@ -0,0 +1,169 @@
+//! Generator-side socket and connection pool tuning
+//!
+//! Lets tail-latency investigations rule out artifacts on the load generator:
+//! Nagle's algorithm, socket buffer sizes, connection pool churn and which
+//! address family a dual-stack target is reached over.
+
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::{TcpSocket, TcpStream, lookup_host};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SocketOptions {
+    /// TCP_NODELAY (disable Nagle's algorithm)
+    pub nodelay: bool,
+    /// SO_SNDBUF in bytes (None = OS default)
+    pub send_buffer: Option<u32>,
+    /// SO_RCVBUF in bytes (None = OS default)
+    pub recv_buffer: Option<u32>,
+    /// Idle HTTP connections kept per host (None = one per worker)
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long idle HTTP connections stay pooled
+    pub pool_idle_timeout: Duration,
+    /// Address family connections use (--ipv4 / --ipv6)
+    pub ip_family: IpFamily,
+    /// Race the other address family when the first is slow to connect
+    pub happy_eyeballs: bool,
+}
+
+/// Address family of the resolved addresses connections may use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpFamily {
+    #[default]
+    Any,
+    V4,
+    V6,
+}
+
+impl IpFamily {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IpFamily::Any => "any",
+            IpFamily::V4 => "ipv4",
+            IpFamily::V6 => "ipv6",
+        }
+    }
+
+    pub fn allows(&self, addr: &SocketAddr) -> bool {
+        match self {
+            IpFamily::Any => true,
+            IpFamily::V4 => addr.is_ipv4(),
+            IpFamily::V6 => addr.is_ipv6(),
+        }
+    }
+}
+
+impl Default for SocketOptions {
+    fn default() -> Self {
+        Self {
+            nodelay: true,
+            send_buffer: None,
+            recv_buffer: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: Duration::from_secs(30),
+            ip_family: IpFamily::Any,
+            happy_eyeballs: true,
+        }
+    }
+}
+
+impl SocketOptions {
+    /// Whether socket buffer sizes are set (the HTTP client can't apply them)
+    pub fn has_buffer_sizes(&self) -> bool {
+        self.send_buffer.is_some() || self.recv_buffer.is_some()
+    }
+
+    /// Whether resolved addresses have to be filtered before connecting
+    pub fn filters_addresses(&self) -> bool {
+        self.ip_family != IpFamily::Any || !self.happy_eyeballs
+    }
+
+    /// The addresses connections may use: only `ip_family`'s, and without
+    /// happy eyeballs only those of the first address's family, so there is
+    /// no other family to fall back to
+    pub fn select_addrs(&self, addrs: impl IntoIterator<Item = SocketAddr>) -> Vec<SocketAddr> {
+        let mut selected: Vec<SocketAddr> = addrs
+            .into_iter()
+            .filter(|a| self.ip_family.allows(a))
+            .collect();
+        if !self.happy_eyeballs
+            && let Some(first) = selected.first().copied()
+        {
+            selected.retain(|a| a.is_ipv4() == first.is_ipv4());
+        }
+        selected
+    }
+
+    /// Resolve `addr` (`host:port`) to the addresses connections may use
+    pub async fn resolve(&self, addr: &str) -> io::Result<Vec<SocketAddr>> {
+        let selected = self.select_addrs(lookup_host(addr).await?);
+        if selected.is_empty() {
+            let family = match self.ip_family {
+                IpFamily::V4 => "IPv4 ",
+                IpFamily::V6 => "IPv6 ",
+                IpFamily::Any => "",
+            };
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no {}addresses resolved for {}", family, addr),
+            ));
+        }
+        Ok(selected)
+    }
+
+    /// Resolve `addr` and connect with these options, trying each address in turn
+    pub async fn connect(&self, addr: &str) -> io::Result<TcpStream> {
+        let mut last_err = None;
+        for peer in self.resolve(addr).await? {
+            let socket = if peer.is_ipv4() {
+                TcpSocket::new_v4()?
+            } else {
+                TcpSocket::new_v6()?
+            };
+            if let Some(size) = self.send_buffer {
+                socket.set_send_buffer_size(size)?;
+            }
+            if let Some(size) = self.recv_buffer {
+                socket.set_recv_buffer_size(size)?;
+            }
+            match socket.connect(peer).await {
+                Ok(stream) => {
+                    stream.set_nodelay(self.nodelay)?;
+                    return Ok(stream);
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err
+            .unwrap_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no addresses resolved")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_addresses_by_family() {
+        let addrs: Vec<SocketAddr> = ["[2001:db8::1]:80", "192.0.2.1:80", "[2001:db8::2]:80"]
+            .iter()
+            .map(|a| a.parse().unwrap())
+            .collect();
+        let select = |ip_family, happy_eyeballs| {
+            let socket = SocketOptions {
+                ip_family,
+                happy_eyeballs,
+                ..SocketOptions::default()
+            };
+            socket.select_addrs(addrs.iter().copied())
+        };
+
+        assert_eq!(select(IpFamily::Any, true), addrs);
+        assert_eq!(select(IpFamily::V4, true), [addrs[1]]);
+        assert_eq!(select(IpFamily::V6, true), [addrs[0], addrs[2]]);
+        // Without happy eyeballs, the resolver's first family is the only one
+        assert_eq!(select(IpFamily::Any, false), [addrs[0], addrs[2]]);
+        assert_eq!(select(IpFamily::V4, false), [addrs[1]]);
+    }
+}
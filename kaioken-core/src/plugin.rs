@@ -0,0 +1,624 @@
+//! WASM plugins (`--plugin`): modules that rewrite requests, judge responses
+//! and report custom metrics, sandboxed with fuel and memory limits.
+//!
+//! A plugin exports `memory` and `kaioken_alloc(len: i32) -> i32`, plus one
+//! or both hooks:
+//!
+//! - `on_request(ptr: i32, len: i32) -> i64` gets the request as JSON,
+//!   `{"method", "url", "headers": [[name, value]], "body"}`. It returns 0 to
+//!   send the request as is, or `ptr << 32 | len` of a JSON object whose
+//!   fields replace the request's.
+//! - `on_response(ptr: i32, len: i32) -> i32` gets `{"method", "url",
+//!   "status", "error", "latency_us", "body"}` and returns 1 (pass) or 0
+//!   (fail), counted as the check `plugin:<name>`.
+//!
+//! Plugins may import `kaioken.metric(name_ptr, name_len, value: f64)` and
+//! `kaioken.log(ptr, len)`. If the plugin exports `kaioken_free(ptr, len)`,
+//! the host calls it on its input buffers once a hook returns.
+
+use crate::types::{PluginMetric, RequestResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use wasmi::{
+    Caller, Config, Engine, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder,
+    TypedFunc,
+};
+
+/// Fuel for one hook call, so a plugin stuck in a loop can't stall a worker
+const FUEL_PER_CALL: u64 = 10_000_000;
+/// Linear memory cap per plugin instance
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+
+/// The request a plugin's `on_request` hook sees and may rewrite
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PluginRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+/// Fields an `on_request` hook returned; missing ones stay as they were
+#[derive(Deserialize)]
+struct RequestChanges {
+    method: Option<String>,
+    url: Option<String>,
+    headers: Option<Vec<(String, String)>>,
+    body: Option<String>,
+}
+
+impl RequestChanges {
+    fn apply(self, request: &mut PluginRequest) {
+        if let Some(method) = self.method {
+            request.method = method;
+        }
+        if let Some(url) = self.url {
+            request.url = url;
+        }
+        if let Some(headers) = self.headers {
+            request.headers = headers;
+        }
+        if self.body.is_some() {
+            request.body = self.body;
+        }
+    }
+}
+
+/// What a plugin's `on_response` hook sees
+#[derive(Serialize)]
+struct PluginResponse<'a> {
+    method: &'a str,
+    url: &'a str,
+    status: Option<u16>,
+    error: Option<&'static str>,
+    latency_us: u64,
+    body: Option<&'a str>,
+}
+
+/// Values plugins reported through `kaioken.metric`, by name
+#[derive(Default)]
+pub struct PluginMetrics {
+    metrics: Mutex<HashMap<String, PluginMetric>>,
+}
+
+impl PluginMetrics {
+    fn record(&self, name: &str, value: f64) {
+        let mut metrics = self.metrics.lock().unwrap();
+        match metrics.get_mut(name) {
+            Some(metric) => metric.record(value),
+            None => {
+                let mut metric = PluginMetric::default();
+                metric.record(value);
+                metrics.insert(name.to_string(), metric);
+            }
+        }
+    }
+
+    /// Drop everything reported so far (end of warmup)
+    pub fn reset(&self) {
+        self.metrics.lock().unwrap().clear();
+    }
+
+    pub fn summary(&self) -> BTreeMap<String, PluginMetric> {
+        self.metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, metric)| (name.clone(), *metric))
+            .collect()
+    }
+}
+
+struct HostState {
+    limits: StoreLimits,
+    metrics: Arc<PluginMetrics>,
+    names: Arc<Vec<String>>,
+    // Plugin whose hook is running, for log lines
+    current: usize,
+}
+
+struct Plugin {
+    name: String,
+    module: Module,
+    has_on_request: bool,
+    has_on_response: bool,
+    // Only the first failure is a warning; the rest would flood the log
+    warned: AtomicBool,
+}
+
+/// One instance of a plugin and the exports the host calls
+struct Instance {
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    free: Option<TypedFunc<(i32, i32), ()>>,
+    on_request: Option<TypedFunc<(i32, i32), i64>>,
+    on_response: Option<TypedFunc<(i32, i32), i32>>,
+}
+
+/// An instance of every plugin in one store, used by one request at a time
+struct PluginSet {
+    store: Store<HostState>,
+    instances: Vec<Instance>,
+}
+
+impl PluginSet {
+    /// Copy `input` into plugin `idx`'s memory, returning its pointer
+    fn write_input(&mut self, idx: usize, input: &[u8]) -> Result<i32, String> {
+        let instance = &self.instances[idx];
+        let len = i32::try_from(input.len()).map_err(|_| "input too large".to_string())?;
+        let ptr = instance
+            .alloc
+            .call(&mut self.store, len)
+            .map_err(|e| e.to_string())?;
+        instance
+            .memory
+            .write(&mut self.store, ptr as u32 as usize, input)
+            .map_err(|e| format!("kaioken_alloc returned a bad pointer: {}", e))?;
+        Ok(ptr)
+    }
+
+    fn free_input(&mut self, idx: usize, ptr: i32, len: usize) -> Result<(), String> {
+        match self.instances[idx].free {
+            Some(free) => free
+                .call(&mut self.store, (ptr, len as i32))
+                .map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+
+    /// Run plugin `idx`'s `on_request`, returning the changes it asked for
+    fn on_request(
+        &mut self,
+        idx: usize,
+        request: &PluginRequest,
+    ) -> Result<Option<RequestChanges>, String> {
+        let Some(hook) = self.instances[idx].on_request else {
+            return Ok(None);
+        };
+        self.prepare(idx)?;
+        let input = serde_json::to_vec(request).map_err(|e| e.to_string())?;
+        let ptr = self.write_input(idx, &input)?;
+        let packed = hook
+            .call(&mut self.store, (ptr, input.len() as i32))
+            .map_err(|e| e.to_string())?;
+        let changes = if packed == 0 {
+            None
+        } else {
+            let output = self.read_output(idx, packed)?;
+            let changes: RequestChanges = serde_json::from_slice(&output)
+                .map_err(|e| format!("on_request returned invalid JSON: {}", e))?;
+            if let Some(ref method) = changes.method
+                && reqwest::Method::from_bytes(method.as_bytes()).is_err()
+            {
+                return Err(format!("on_request set an invalid method '{}'", method));
+            }
+            Some(changes)
+        };
+        self.free_input(idx, ptr, input.len())?;
+        Ok(changes)
+    }
+
+    /// Run plugin `idx`'s `on_response`, returning whether the response passed
+    fn on_response(&mut self, idx: usize, response: &PluginResponse) -> Result<bool, String> {
+        let Some(hook) = self.instances[idx].on_response else {
+            return Ok(true);
+        };
+        self.prepare(idx)?;
+        let input = serde_json::to_vec(response).map_err(|e| e.to_string())?;
+        let ptr = self.write_input(idx, &input)?;
+        let passed = hook
+            .call(&mut self.store, (ptr, input.len() as i32))
+            .map_err(|e| e.to_string())?;
+        self.free_input(idx, ptr, input.len())?;
+        Ok(passed != 0)
+    }
+
+    /// Refuel before a hook and point log lines at plugin `idx`
+    fn prepare(&mut self, idx: usize) -> Result<(), String> {
+        self.store.data_mut().current = idx;
+        self.store
+            .set_fuel(FUEL_PER_CALL)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Read the `ptr << 32 | len` buffer a hook returned
+    fn read_output(&self, idx: usize, packed: i64) -> Result<Vec<u8>, String> {
+        let ptr = (packed as u64 >> 32) as usize;
+        let len = (packed as u64 & 0xffff_ffff) as usize;
+        self.instances[idx]
+            .memory
+            .data(&self.store)
+            .get(ptr..ptr + len)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| "hook returned a buffer outside its memory".to_string())
+    }
+}
+
+/// Loaded plugins, shared by all workers. Each request checks out an idle
+/// set of instances (creating one if none is free), so plugins never run
+/// concurrently on the same memory.
+pub struct PluginHost {
+    engine: Engine,
+    linker: Linker<HostState>,
+    plugins: Vec<Plugin>,
+    names: Arc<Vec<String>>,
+    idle: Mutex<Vec<PluginSet>>,
+    metrics: Arc<PluginMetrics>,
+}
+
+impl PluginHost {
+    /// Compile and instantiate each module once, so a broken plugin fails
+    /// the run before it starts
+    pub fn load(paths: &[PathBuf]) -> Result<Self, String> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+
+        let mut linker = Linker::<HostState>::new(&engine);
+        linker
+            .func_wrap(
+                "kaioken",
+                "metric",
+                |caller: Caller<'_, HostState>, ptr: i32, len: i32, value: f64| {
+                    if let Some(name) = read_string(&caller, ptr, len) {
+                        caller.data().metrics.record(&name, value);
+                    }
+                },
+            )
+            .map_err(|e| e.to_string())?;
+        linker
+            .func_wrap(
+                "kaioken",
+                "log",
+                |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                    if let Some(message) = read_string(&caller, ptr, len) {
+                        let state = caller.data();
+                        tracing::info!("plugin {}: {}", state.names[state.current], message);
+                    }
+                },
+            )
+            .map_err(|e| e.to_string())?;
+
+        let mut plugins = Vec::with_capacity(paths.len());
+        for path in paths {
+            let bytes = std::fs::read(path)
+                .map_err(|e| format!("Failed to read plugin '{}': {}", path.display(), e))?;
+            let module = Module::new(&engine, &bytes[..])
+                .map_err(|e| format!("Invalid plugin '{}': {}", path.display(), e))?;
+            let exports = |name| module.get_export(name).is_some();
+            let (has_on_request, has_on_response) = (exports("on_request"), exports("on_response"));
+            if !has_on_request && !has_on_response {
+                return Err(format!(
+                    "Plugin '{}' exports neither on_request nor on_response",
+                    path.display()
+                ));
+            }
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string());
+            plugins.push(Plugin {
+                name,
+                module,
+                has_on_request,
+                has_on_response,
+                warned: AtomicBool::new(false),
+            });
+        }
+
+        let host = Self {
+            engine,
+            linker,
+            names: Arc::new(plugins.iter().map(|p| p.name.clone()).collect()),
+            plugins,
+            idle: Mutex::new(Vec::new()),
+            metrics: Arc::new(PluginMetrics::default()),
+        };
+        let set = host.instantiate()?;
+        host.checkin(set);
+        Ok(host)
+    }
+
+    pub fn has_request_hooks(&self) -> bool {
+        self.plugins.iter().any(|p| p.has_on_request)
+    }
+
+    pub fn has_response_hooks(&self) -> bool {
+        self.plugins.iter().any(|p| p.has_on_response)
+    }
+
+    pub fn metrics(&self) -> Arc<PluginMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Pass the request through every `on_request` hook in order. An error
+    /// means the request shouldn't be sent.
+    pub fn on_request(&self, mut request: PluginRequest) -> Result<PluginRequest, String> {
+        let mut set = self.checkout()?;
+        for (idx, plugin) in self.plugins.iter().enumerate() {
+            match set.on_request(idx, &request) {
+                Ok(Some(changes)) => changes.apply(&mut request),
+                Ok(None) => {}
+                Err(e) => {
+                    // A trap can leave the instance half-way through, so drop the set
+                    self.report_failure(plugin, "on_request", &e);
+                    return Err(format!("plugin {}: {}", plugin.name, e));
+                }
+            }
+        }
+        self.checkin(set);
+        Ok(request)
+    }
+
+    /// Run every `on_response` hook, returning `(check name, passed)` for
+    /// each. A hook that fails counts as a failed check.
+    pub fn on_response(
+        &self,
+        method: &str,
+        url: &str,
+        result: &RequestResult,
+    ) -> Vec<(String, bool)> {
+        let response = PluginResponse {
+            method,
+            url,
+            status: result.status,
+            error: result.error.map(|kind| kind.as_str()),
+            latency_us: result.latency_us,
+            body: result.body.as_deref(),
+        };
+        let mut set = match self.checkout() {
+            Ok(set) => Some(set),
+            Err(e) => {
+                tracing::debug!("{}", e);
+                None
+            }
+        };
+        let mut outcomes = Vec::new();
+        for (idx, plugin) in self.plugins.iter().enumerate() {
+            if !plugin.has_on_response {
+                continue;
+            }
+            let passed = match set.as_mut().map(|set| set.on_response(idx, &response)) {
+                Some(Ok(passed)) => passed,
+                Some(Err(e)) => {
+                    self.report_failure(plugin, "on_response", &e);
+                    set = None;
+                    false
+                }
+                None => false,
+            };
+            outcomes.push((format!("plugin:{}", plugin.name), passed));
+        }
+        if let Some(set) = set {
+            self.checkin(set);
+        }
+        outcomes
+    }
+
+    fn checkout(&self) -> Result<PluginSet, String> {
+        let idle = self.idle.lock().unwrap().pop();
+        match idle {
+            Some(set) => Ok(set),
+            None => self.instantiate(),
+        }
+    }
+
+    fn checkin(&self, set: PluginSet) {
+        self.idle.lock().unwrap().push(set);
+    }
+
+    fn instantiate(&self) -> Result<PluginSet, String> {
+        let state = HostState {
+            limits: StoreLimitsBuilder::new()
+                .memory_size(MAX_MEMORY_BYTES)
+                .build(),
+            metrics: self.metrics.clone(),
+            names: self.names.clone(),
+            current: 0,
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+
+        let mut instances = Vec::with_capacity(self.plugins.len());
+        for (idx, plugin) in self.plugins.iter().enumerate() {
+            store.data_mut().current = idx;
+            store.set_fuel(FUEL_PER_CALL).map_err(|e| e.to_string())?;
+            let instance = self
+                .linker
+                .instantiate(&mut store, &plugin.module)
+                .and_then(|pre| pre.start(&mut store))
+                .map_err(|e| format!("Plugin '{}' failed to start: {}", plugin.name, e))?;
+            let export = |e: wasmi::Error| format!("Plugin '{}': {}", plugin.name, e);
+            let memory = instance
+                .get_memory(&store, "memory")
+                .ok_or_else(|| format!("Plugin '{}' doesn't export its memory", plugin.name))?;
+            let alloc = instance
+                .get_typed_func(&store, "kaioken_alloc")
+                .map_err(export)?;
+            let free = match instance.get_export(&store, "kaioken_free") {
+                Some(_) => Some(
+                    instance
+                        .get_typed_func(&store, "kaioken_free")
+                        .map_err(export)?,
+                ),
+                None => None,
+            };
+            let on_request = match plugin.has_on_request {
+                true => Some(
+                    instance
+                        .get_typed_func(&store, "on_request")
+                        .map_err(export)?,
+                ),
+                false => None,
+            };
+            let on_response = match plugin.has_on_response {
+                true => Some(
+                    instance
+                        .get_typed_func(&store, "on_response")
+                        .map_err(export)?,
+                ),
+                false => None,
+            };
+            instances.push(Instance {
+                memory,
+                alloc,
+                free,
+                on_request,
+                on_response,
+            });
+        }
+        Ok(PluginSet { store, instances })
+    }
+
+    fn report_failure(&self, plugin: &Plugin, hook: &str, error: &str) {
+        if !plugin.warned.swap(true, Ordering::Relaxed) {
+            tracing::warn!(
+                "Plugin '{}' {} failed: {} (further failures are logged at debug level)",
+                plugin.name,
+                hook,
+                error
+            );
+        } else {
+            tracing::debug!("Plugin '{}' {} failed: {}", plugin.name, hook, error);
+        }
+    }
+}
+
+/// Read a UTF-8 string a plugin passed to a host function
+fn read_string(caller: &Caller<'_, HostState>, ptr: i32, len: i32) -> Option<String> {
+    let memory = caller.get_export("memory")?.into_memory()?;
+    let start = ptr as u32 as usize;
+    let end = start.checked_add(len as u32 as usize)?;
+    let bytes = memory.data(caller).get(start..end)?;
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ErrorKind;
+
+    // Bump allocator that resets on free, a request hook that signs the
+    // request and a response hook that passes 200s
+    const SIGNER: &str = r#"
+        (module
+          (import "kaioken" "metric" (func $metric (param i32 i32 f64)))
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (data (i32.const 0) "{\"headers\":[[\"x-signed\",\"yes\"]]}")
+          (data (i32.const 64) "signed")
+          (data (i32.const 96) "\"status\":200")
+          (func (export "kaioken_alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "kaioken_free") (param i32 i32)
+            (global.set $next (i32.const 1024)))
+          (func (export "on_request") (param i32 i32) (result i64)
+            (call $metric (i32.const 64) (i32.const 6) (f64.const 1))
+            (i64.const 32))
+          (func (export "on_response") (param $ptr i32) (param $len i32) (result i32)
+            (local $i i32)
+            (local $j i32)
+            (block $done
+              (loop $outer
+                (br_if $done (i32.gt_s (i32.add (local.get $i) (i32.const 12)) (local.get $len)))
+                (local.set $j (i32.const 0))
+                (block $mismatch
+                  (loop $inner
+                    (br_if $mismatch
+                      (i32.ne
+                        (i32.load8_u (i32.add (local.get $ptr) (i32.add (local.get $i) (local.get $j))))
+                        (i32.load8_u (i32.add (i32.const 96) (local.get $j)))))
+                    (local.set $j (i32.add (local.get $j) (i32.const 1)))
+                    (br_if $inner (i32.lt_s (local.get $j) (i32.const 12)))
+                    (return (i32.const 1))))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br $outer)))
+            (i32.const 0)))
+    "#;
+
+    const SPINNER: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "kaioken_alloc") (param i32) (result i32) (i32.const 0))
+          (func (export "on_request") (param i32 i32) (result i64)
+            (loop $forever (br $forever))
+            (i64.const 0)))
+    "#;
+
+    fn plugin_file(name: &str, wat: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("kaioken-plugin-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("{}.wasm", name));
+        std::fs::write(&path, wat::parse_str(wat).unwrap()).unwrap();
+        path
+    }
+
+    fn request() -> PluginRequest {
+        PluginRequest {
+            method: "GET".to_string(),
+            url: "http://localhost/".to_string(),
+            headers: vec![("accept".to_string(), "*/*".to_string())],
+            body: None,
+        }
+    }
+
+    #[test]
+    fn request_hook_rewrites_fields_it_returns() {
+        let host = PluginHost::load(&[plugin_file("signer", SIGNER)]).unwrap();
+        assert!(host.has_request_hooks() && host.has_response_hooks());
+
+        for _ in 0..3 {
+            let hooked = host.on_request(request()).unwrap();
+            assert_eq!(
+                hooked.headers,
+                vec![("x-signed".to_string(), "yes".to_string())]
+            );
+            assert_eq!(hooked.url, "http://localhost/");
+        }
+
+        let metrics = host.metrics().summary();
+        assert_eq!(metrics["signed"].count, 3);
+        assert_eq!(metrics["signed"].mean(), 1.0);
+    }
+
+    #[test]
+    fn response_hook_reports_a_check() {
+        let host = PluginHost::load(&[plugin_file("status", SIGNER)]).unwrap();
+        let ok = RequestResult::success(1000, 200, 2, Some("ok".to_string()));
+        let missing = RequestResult::success(1000, 404, 0, None);
+        let refused = RequestResult::error(0, ErrorKind::Connect);
+
+        for (result, passed) in [(ok, true), (missing, false), (refused, false)] {
+            assert_eq!(
+                host.on_response("GET", "http://localhost/", &result),
+                vec![("plugin:status".to_string(), passed)]
+            );
+        }
+    }
+
+    #[test]
+    fn runaway_hook_runs_out_of_fuel() {
+        let host = PluginHost::load(&[plugin_file("spinner", SPINNER)]).unwrap();
+        let err = host.on_request(request()).unwrap_err();
+        assert!(err.starts_with("plugin spinner:"), "{}", err);
+        // The trapped instance is dropped; the next request gets a fresh one
+        assert!(host.on_request(request()).is_err());
+    }
+
+    #[test]
+    fn module_without_hooks_is_rejected() {
+        let path = plugin_file("empty", r#"(module (memory (export "memory") 1))"#);
+        let err = PluginHost::load(&[path]).err().unwrap();
+        assert!(
+            err.contains("neither on_request nor on_response"),
+            "{}",
+            err
+        );
+    }
+}
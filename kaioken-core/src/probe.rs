@@ -0,0 +1,228 @@
+//! Infrastructure probes (`--probe dns|connect|tls`)
+//!
+//! When full requests are slow or failing, a probe times one layer of the
+//! way there on its own: resolving the host, opening a TCP connection to
+//! it, or the TLS handshake on top of one. Each probe is one result, with
+//! the layer's time as its latency and no HTTP request sent. The layers
+//! below the probed one are set up untimed.
+
+use crate::net::SocketOptions;
+use crate::types::{ErrorKind, LoadConfig, ProbeKind, RequestResult};
+use reqwest::Url;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{
+    ClientConfig, DigitallySignedStruct, Error as TlsError, RootCertStore, SignatureScheme,
+};
+
+pub struct Prober {
+    kind: ProbeKind,
+    socket: SocketOptions,
+    timeout: Duration,
+    connect_to: Option<(String, SocketAddr)>,
+    tls: Option<TlsConnector>,
+}
+
+impl Prober {
+    pub fn new(kind: ProbeKind, config: &LoadConfig) -> Result<Self, String> {
+        let tls = match kind {
+            ProbeKind::Tls => Some(TlsConnector::from(tls_config(config)?)),
+            ProbeKind::Dns | ProbeKind::Connect => None,
+        };
+        Ok(Self {
+            kind,
+            socket: config.socket.clone(),
+            timeout: config.timeout,
+            connect_to: config.connect_to.clone(),
+            tls,
+        })
+    }
+
+    /// Probe the host `url` points at
+    pub async fn probe(&self, url: &str) -> RequestResult {
+        let start = Instant::now();
+        match tokio::time::timeout(self.timeout, self.layer(url)).await {
+            Ok(Ok(latency)) => RequestResult::success(latency.as_micros() as u64, 200, 0, None),
+            Ok(Err(kind)) => RequestResult::error(start.elapsed().as_micros() as u64, kind),
+            Err(_) => RequestResult::error(start.elapsed().as_micros() as u64, ErrorKind::Timeout),
+        }
+    }
+
+    /// Time of the probed layer alone
+    async fn layer(&self, url: &str) -> Result<Duration, ErrorKind> {
+        let url = Url::parse(url).map_err(|_| ErrorKind::Other)?;
+        let host = url.host_str().ok_or(ErrorKind::Other)?;
+        let port = url.port_or_known_default().ok_or(ErrorKind::Other)?;
+
+        let start = Instant::now();
+        let addrs = match self.connect_to {
+            Some((ref name, addr)) if name == host => vec![addr],
+            _ => self
+                .socket
+                .resolve(&format!("{}:{}", host, port))
+                .await
+                .map_err(|_| ErrorKind::Dns)?,
+        };
+        if self.kind == ProbeKind::Dns {
+            return Ok(start.elapsed());
+        }
+
+        let start = Instant::now();
+        let stream = TcpStream::connect(&addrs[..])
+            .await
+            .map_err(connect_error)?;
+        let Some(ref tls) = self.tls else {
+            return Ok(start.elapsed());
+        };
+
+        let server_name = ServerName::try_from(host.trim_matches(['[', ']']).to_string())
+            .map_err(|_| ErrorKind::Tls)?;
+        let start = Instant::now();
+        tls.connect(server_name, stream)
+            .await
+            .map_err(|_| ErrorKind::Tls)?;
+        Ok(start.elapsed())
+    }
+}
+
+fn connect_error(err: io::Error) -> ErrorKind {
+    match err.kind() {
+        io::ErrorKind::ConnectionRefused => ErrorKind::Refused,
+        io::ErrorKind::ConnectionReset => ErrorKind::Reset,
+        _ => ErrorKind::Connect,
+    }
+}
+
+/// Roots the handshake is verified against: webpki's plus `--ca-cert`, or
+/// none at all with `--insecure`
+fn tls_config(config: &LoadConfig) -> Result<Arc<ClientConfig>, String> {
+    if config.insecure {
+        return Ok(Arc::new(
+            ClientConfig::builder()
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(AnyCertificate))
+                .with_no_client_auth(),
+        ));
+    }
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    if let Some(ref path) = config.ca_cert {
+        let certs = CertificateDer::pem_file_iter(path)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("Failed to read CA certificate {}: {}", path.display(), e))?;
+        roots.add_parsable_certificates(certs);
+    }
+    Ok(Arc::new(
+        ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    ))
+}
+
+/// Accepts any certificate (`--insecure`), still checking the handshake
+/// signatures so the probe does the same work as a verified one
+#[derive(Debug)]
+struct AnyCertificate;
+
+impl ServerCertVerifier for AnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, TlsError> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        tokio_rustls::rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &provider().signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        tokio_rustls::rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &provider().signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        provider()
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+fn provider() -> tokio_rustls::rustls::crypto::CryptoProvider {
+    tokio_rustls::rustls::crypto::ring::default_provider()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn prober(kind: ProbeKind) -> Prober {
+        let config = LoadConfig {
+            timeout: Duration::from_secs(2),
+            ..LoadConfig::default()
+        };
+        Prober::new(kind, &config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn probes_resolution_and_connects_without_sending_anything() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://localhost:{}/",
+            listener.local_addr().unwrap().port()
+        );
+
+        let resolved = prober(ProbeKind::Dns).probe(&url).await;
+        assert!(resolved.is_success(), "{:?}", resolved.error);
+
+        let connected = prober(ProbeKind::Connect).probe(&url).await;
+        assert!(connected.is_success(), "{:?}", connected.error);
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 1];
+        // The probe has closed the connection without writing to it
+        let read = tokio::io::AsyncReadExt::read(&mut stream, &mut buf).await;
+        assert_eq!(read.unwrap(), 0);
+
+        drop(listener);
+        let refused = prober(ProbeKind::Connect).probe(&url).await;
+        assert_eq!(refused.error, Some(ErrorKind::Refused));
+
+        let unresolved = prober(ProbeKind::Dns)
+            .probe("http://kaioken-probe.invalid/")
+            .await;
+        assert_eq!(unresolved.error, Some(ErrorKind::Dns));
+    }
+}
@@ -0,0 +1,284 @@
+use crate::net::SocketOptions;
+use crate::types::{RawExpect, WsErrorKind};
+use std::io::ErrorKind as IoErrorKind;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+
+/// Largest UDP datagram we accept in a response
+const MAX_DATAGRAM: usize = 65_536;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawProtocol {
+    Tcp,
+    Udp,
+}
+
+impl RawProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RawProtocol::Tcp => "tcp",
+            RawProtocol::Udp => "udp",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawTarget {
+    pub protocol: RawProtocol,
+    pub addr: String,
+}
+
+/// Parse `tcp://host:port` or `udp://host:port`
+pub fn parse_raw_url(url: &str) -> Result<RawTarget, String> {
+    let (protocol, rest) = if let Some(rest) = url.strip_prefix("tcp://") {
+        (RawProtocol::Tcp, rest)
+    } else if let Some(rest) = url.strip_prefix("udp://") {
+        (RawProtocol::Udp, rest)
+    } else {
+        return Err(format!(
+            "Raw mode URL must start with tcp:// or udp://: {}",
+            url
+        ));
+    };
+
+    let addr = rest.trim_end_matches('/');
+    let has_port = match addr.rfind(']') {
+        Some(end) => addr[end..].contains(':'),
+        None => addr.contains(':'),
+    };
+    if addr.is_empty() || addr.contains('/') || !has_port {
+        return Err(format!(
+            "Raw mode URL needs a host and port ({}://host:port): {}",
+            protocol.as_str(),
+            url
+        ));
+    }
+
+    Ok(RawTarget {
+        protocol,
+        addr: addr.to_string(),
+    })
+}
+
+pub enum RawConnection {
+    Tcp {
+        stream: TcpStream,
+        connect_time_us: u64,
+    },
+    Udp {
+        socket: UdpSocket,
+        connect_time_us: u64,
+    },
+}
+
+/// Open a TCP stream, or bind and connect a UDP socket (DNS lookup only)
+pub async fn connect(
+    target: &RawTarget,
+    socket: &SocketOptions,
+    timeout: Duration,
+) -> Result<RawConnection, WsErrorKind> {
+    let start = Instant::now();
+
+    let open = async {
+        match target.protocol {
+            RawProtocol::Tcp => {
+                let stream = socket
+                    .connect(&target.addr)
+                    .await
+                    .map_err(|_| WsErrorKind::ConnectFailed)?;
+                Ok(RawConnection::Tcp {
+                    stream,
+                    connect_time_us: 0,
+                })
+            }
+            RawProtocol::Udp => {
+                let peer = socket
+                    .resolve(&target.addr)
+                    .await
+                    .map_err(|_| WsErrorKind::ConnectFailed)?[0];
+                let local = if peer.is_ipv4() {
+                    "0.0.0.0:0"
+                } else {
+                    "[::]:0"
+                };
+                let socket = UdpSocket::bind(local)
+                    .await
+                    .map_err(|_| WsErrorKind::ConnectFailed)?;
+                socket
+                    .connect(peer)
+                    .await
+                    .map_err(|_| WsErrorKind::ConnectFailed)?;
+                Ok(RawConnection::Udp {
+                    socket,
+                    connect_time_us: 0,
+                })
+            }
+        }
+    };
+
+    match tokio::time::timeout(timeout, open).await {
+        Ok(Ok(mut conn)) => {
+            let elapsed = start.elapsed().as_micros() as u64;
+            match &mut conn {
+                RawConnection::Tcp {
+                    connect_time_us, ..
+                }
+                | RawConnection::Udp {
+                    connect_time_us, ..
+                } => *connect_time_us = elapsed,
+            }
+            Ok(conn)
+        }
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(WsErrorKind::Timeout),
+    }
+}
+
+impl RawConnection {
+    pub fn connect_time_us(&self) -> u64 {
+        match self {
+            RawConnection::Tcp {
+                connect_time_us, ..
+            }
+            | RawConnection::Udp {
+                connect_time_us, ..
+            } => *connect_time_us,
+        }
+    }
+
+    /// Send a payload and, with a matcher, wait until the response is complete.
+    /// Returns the number of response bytes read.
+    pub async fn exchange(
+        &mut self,
+        payload: &[u8],
+        expect: Option<&RawExpect>,
+        timeout: Duration,
+    ) -> Result<u64, WsErrorKind> {
+        let exchange = async {
+            match self {
+                RawConnection::Tcp { stream, .. } => {
+                    if expect.is_none() {
+                        discard_unread(stream)?;
+                    }
+                    stream
+                        .write_all(payload)
+                        .await
+                        .map_err(|e| io_error_kind(&e, WsErrorKind::SendFailed))?;
+                    let Some(expect) = expect else {
+                        return Ok(0);
+                    };
+
+                    let mut received = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    while !expect.is_complete(&received) {
+                        let n = stream
+                            .read(&mut chunk)
+                            .await
+                            .map_err(|e| io_error_kind(&e, WsErrorKind::ReceiveFailed))?;
+                        if n == 0 {
+                            return Err(WsErrorKind::ConnectionClosed);
+                        }
+                        received.extend_from_slice(&chunk[..n]);
+                    }
+                    Ok(received.len() as u64)
+                }
+                RawConnection::Udp { socket, .. } => {
+                    socket
+                        .send(payload)
+                        .await
+                        .map_err(|e| io_error_kind(&e, WsErrorKind::SendFailed))?;
+                    let Some(expect) = expect else {
+                        return Ok(0);
+                    };
+
+                    let mut received = Vec::new();
+                    let mut datagram = vec![0u8; MAX_DATAGRAM];
+                    while !expect.is_complete(&received) {
+                        let n = socket
+                            .recv(&mut datagram)
+                            .await
+                            .map_err(|e| io_error_kind(&e, WsErrorKind::ReceiveFailed))?;
+                        received.extend_from_slice(&datagram[..n]);
+                    }
+                    Ok(received.len() as u64)
+                }
+            }
+        };
+
+        match tokio::time::timeout(timeout, exchange).await {
+            Ok(result) => result,
+            Err(_) => Err(WsErrorKind::Timeout),
+        }
+    }
+
+    pub async fn close(self) {
+        if let RawConnection::Tcp { mut stream, .. } = self {
+            let _ = stream.shutdown().await;
+        }
+    }
+}
+
+/// Drop responses nobody waits for so the peer never blocks on a full window
+fn discard_unread(stream: &TcpStream) -> Result<(), WsErrorKind> {
+    let mut chunk = [0u8; 4096];
+    loop {
+        match stream.try_read(&mut chunk) {
+            Ok(0) => return Err(WsErrorKind::ConnectionClosed),
+            Ok(_) => continue,
+            Err(e) if e.kind() == IoErrorKind::WouldBlock => return Ok(()),
+            Err(e) => return Err(io_error_kind(&e, WsErrorKind::ReceiveFailed)),
+        }
+    }
+}
+
+fn io_error_kind(err: &std::io::Error, fallback: WsErrorKind) -> WsErrorKind {
+    match err.kind() {
+        IoErrorKind::ConnectionReset
+        | IoErrorKind::ConnectionAborted
+        | IoErrorKind::BrokenPipe
+        | IoErrorKind::UnexpectedEof => WsErrorKind::ConnectionClosed,
+        // ICMP port unreachable surfaces on the next UDP send/recv
+        IoErrorKind::ConnectionRefused => WsErrorKind::ConnectFailed,
+        _ => fallback,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_raw_url() {
+        assert_eq!(
+            parse_raw_url("tcp://localhost:9000").unwrap(),
+            RawTarget {
+                protocol: RawProtocol::Tcp,
+                addr: "localhost:9000".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_raw_url("udp://[::1]:514/").unwrap(),
+            RawTarget {
+                protocol: RawProtocol::Udp,
+                addr: "[::1]:514".to_string(),
+            }
+        );
+        assert!(parse_raw_url("tcp://localhost").is_err());
+        assert!(parse_raw_url("udp://[::1]").is_err());
+        assert!(parse_raw_url("tcp://host:1/path").is_err());
+        assert!(parse_raw_url("http://localhost:80").is_err());
+    }
+
+    #[test]
+    fn test_raw_expect() {
+        let bytes = RawExpect::Bytes(4);
+        assert!(!bytes.is_complete(b"abc"));
+        assert!(bytes.is_complete(b"abcd"));
+
+        let pattern = RawExpect::Pattern(regex_lite::Regex::new(r"^\+OK.*\r\n").unwrap());
+        assert!(!pattern.is_complete(b"+OK"));
+        assert!(pattern.is_complete(b"+OK ready\r\n"));
+        assert!(!pattern.is_complete(b"-ERR\r\n"));
+    }
+}
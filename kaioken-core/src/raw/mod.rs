@@ -0,0 +1,9 @@
+//! Raw TCP and UDP client for custom line protocols
+//!
+//! `tcp://host:port` keeps one stream per VU (or reconnects per message with
+//! --disable-keepalive); `udp://host:port` sends one datagram per message.
+//! Responses are optional and complete when a byte count or regex matches.
+
+mod client;
+
+pub use client::{RawConnection, RawTarget, connect, parse_raw_url};
@@ -0,0 +1,14 @@
+//! Redis client implementation (feature-gated)
+//!
+//! Enable with: cargo build --features redis
+//!
+//! Speaks RESP2 over plain TCP:
+//! - AUTH and SELECT from the `redis://` URL on connect
+//! - Command templates split into bulk-string arrays
+//! - Reply parsing for simple strings, errors, integers, bulk strings and arrays
+
+mod client;
+mod resp;
+
+#[allow(unused_imports)]
+pub use client::{RedisConnection, RedisTarget, parse_command_template, parse_redis_url};
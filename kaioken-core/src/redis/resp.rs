@@ -0,0 +1,186 @@
+//! RESP2 command encoding and reply parsing
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reply {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<Reply>>),
+}
+
+impl Reply {
+    pub fn is_error(&self) -> bool {
+        matches!(self, Reply::Error(_))
+    }
+
+    /// Text form used as the response body for checks
+    pub fn render(&self) -> String {
+        match self {
+            Reply::Simple(s) => s.clone(),
+            Reply::Error(e) => e.clone(),
+            Reply::Integer(i) => i.to_string(),
+            Reply::Bulk(Some(bytes)) => String::from_utf8_lossy(bytes).into_owned(),
+            Reply::Bulk(None) | Reply::Array(None) => "(nil)".to_string(),
+            Reply::Array(Some(items)) => items
+                .iter()
+                .map(Reply::render)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+/// Encode a command as a RESP array of bulk strings
+pub fn encode_command(args: &[String]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(16 + args.iter().map(|a| a.len() + 16).sum::<usize>());
+    buf.extend_from_slice(format!("*{}\r\n", args.len()).as_bytes());
+    for arg in args {
+        buf.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        buf.extend_from_slice(arg.as_bytes());
+        buf.extend_from_slice(b"\r\n");
+    }
+    buf
+}
+
+/// Parse one reply from the front of `buf`.
+///
+/// Returns the reply and the number of bytes consumed, or `None` if more
+/// bytes are needed.
+pub fn parse_reply(buf: &[u8]) -> Result<Option<(Reply, usize)>, String> {
+    let Some(line_end) = find_crlf(buf) else {
+        return Ok(None);
+    };
+    if line_end == 0 {
+        return Err("Empty RESP line".to_string());
+    }
+    let line = std::str::from_utf8(&buf[1..line_end]).map_err(|_| "RESP line is not UTF-8")?;
+    let after_line = line_end + 2;
+
+    match buf[0] {
+        b'+' => Ok(Some((Reply::Simple(line.to_string()), after_line))),
+        b'-' => Ok(Some((Reply::Error(line.to_string()), after_line))),
+        b':' => {
+            let value = parse_int(line)?;
+            Ok(Some((Reply::Integer(value), after_line)))
+        }
+        b'$' => {
+            let len = parse_int(line)?;
+            if len < 0 {
+                return Ok(Some((Reply::Bulk(None), after_line)));
+            }
+            let end = after_line + len as usize;
+            if buf.len() < end + 2 {
+                return Ok(None);
+            }
+            if &buf[end..end + 2] != b"\r\n" {
+                return Err("Bulk string missing CRLF terminator".to_string());
+            }
+            Ok(Some((
+                Reply::Bulk(Some(buf[after_line..end].to_vec())),
+                end + 2,
+            )))
+        }
+        b'*' => {
+            let count = parse_int(line)?;
+            if count < 0 {
+                return Ok(Some((Reply::Array(None), after_line)));
+            }
+            let mut items = Vec::with_capacity(count.min(1024) as usize);
+            let mut offset = after_line;
+            for _ in 0..count {
+                match parse_reply(&buf[offset..])? {
+                    Some((item, used)) => {
+                        items.push(item);
+                        offset += used;
+                    }
+                    None => return Ok(None),
+                }
+            }
+            Ok(Some((Reply::Array(Some(items)), offset)))
+        }
+        other => Err(format!("Unknown RESP type byte 0x{:02x}", other)),
+    }
+}
+
+fn find_crlf(buf: &[u8]) -> Option<usize> {
+    buf.windows(2).position(|w| w == b"\r\n")
+}
+
+fn parse_int(s: &str) -> Result<i64, String> {
+    s.parse()
+        .map_err(|_| format!("Invalid RESP integer: {}", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_command() {
+        let cmd = vec![
+            "SET".to_string(),
+            "k".to_string(),
+            "hello world".to_string(),
+        ];
+        assert_eq!(
+            encode_command(&cmd),
+            b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$11\r\nhello world\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn parses_scalar_replies() {
+        assert_eq!(
+            parse_reply(b"+OK\r\n").unwrap(),
+            Some((Reply::Simple("OK".to_string()), 5))
+        );
+        assert_eq!(
+            parse_reply(b"-ERR wrong type\r\n").unwrap(),
+            Some((Reply::Error("ERR wrong type".to_string()), 17))
+        );
+        assert_eq!(
+            parse_reply(b":42\r\n+next").unwrap(),
+            Some((Reply::Integer(42), 5))
+        );
+        assert_eq!(
+            parse_reply(b"$5\r\nhello\r\n").unwrap(),
+            Some((Reply::Bulk(Some(b"hello".to_vec())), 11))
+        );
+        assert_eq!(
+            parse_reply(b"$-1\r\n").unwrap(),
+            Some((Reply::Bulk(None), 5))
+        );
+    }
+
+    #[test]
+    fn parses_nested_arrays() {
+        let (reply, used) = parse_reply(b"*2\r\n$1\r\na\r\n*1\r\n:1\r\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(used, 19);
+        assert_eq!(
+            reply,
+            Reply::Array(Some(vec![
+                Reply::Bulk(Some(b"a".to_vec())),
+                Reply::Array(Some(vec![Reply::Integer(1)])),
+            ]))
+        );
+        assert_eq!(reply.render(), "a\n1");
+    }
+
+    #[test]
+    fn waits_for_partial_replies() {
+        assert_eq!(parse_reply(b"").unwrap(), None);
+        assert_eq!(parse_reply(b"+OK").unwrap(), None);
+        assert_eq!(parse_reply(b"$5\r\nhel").unwrap(), None);
+        assert_eq!(parse_reply(b"*2\r\n:1\r\n").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_malformed_replies() {
+        assert!(parse_reply(b"?x\r\n").is_err());
+        assert!(parse_reply(b":abc\r\n").is_err());
+        assert!(parse_reply(b"$2\r\nabcd\r\n").is_err());
+    }
+}
@@ -0,0 +1,327 @@
+use crate::types::{WsErrorKind, WsMessageResult};
+use crate::ws::{handshake_request, ws_error_to_kind};
+use futures_util::{SinkExt, StreamExt};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite::Message};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+pub struct SocketIoConnection {
+    stream: WsStream,
+    namespace: String,
+    next_ack_id: u64,
+    pub connect_time_us: u64,
+}
+
+/// Build the Engine.IO WebSocket endpoint from a target URL.
+///
+/// `http://host:3000` becomes `ws://host:3000/socket.io/?EIO=4&transport=websocket`.
+/// A custom path (e.g. `/realtime/`) replaces the default `/socket.io/`.
+pub fn socketio_url(url: &str) -> Result<String, String> {
+    let (scheme, rest) = if let Some(rest) = url.strip_prefix("https://") {
+        ("wss", rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        ("ws", rest)
+    } else if let Some(rest) = url.strip_prefix("wss://") {
+        ("wss", rest)
+    } else if let Some(rest) = url.strip_prefix("ws://") {
+        ("ws", rest)
+    } else {
+        return Err(format!("Unsupported Socket.IO URL scheme: {}", url));
+    };
+
+    let rest = rest.split('?').next().unwrap_or(rest);
+    let (host, path) = match rest.find('/') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, "/"),
+    };
+    if host.is_empty() {
+        return Err(format!("Missing host in Socket.IO URL: {}", url));
+    }
+
+    let path = if path == "/" {
+        "/socket.io/".to_string()
+    } else if path.ends_with('/') {
+        path.to_string()
+    } else {
+        format!("{}/", path)
+    };
+
+    Ok(format!(
+        "{}://{}{}?EIO=4&transport=websocket",
+        scheme, host, path
+    ))
+}
+
+/// Establish an Engine.IO session and connect to a Socket.IO namespace
+pub async fn connect(
+    url: &str,
+    namespace: &str,
+    headers: &[(String, String)],
+    timeout: Duration,
+) -> Result<SocketIoConnection, WsErrorKind> {
+    let request = handshake_request(url, headers, &[])?;
+
+    let start = Instant::now();
+
+    let handshake = async {
+        let (stream, _response) = connect_async(request)
+            .await
+            .map_err(|e| ws_error_to_kind(&e))?;
+
+        let mut conn = SocketIoConnection {
+            stream,
+            namespace: namespace.to_string(),
+            next_ack_id: 0,
+            connect_time_us: 0,
+        };
+
+        // Engine.IO open packet: 0{"sid":...,"pingInterval":...}
+        let open = conn.next_text().await?;
+        if !open.starts_with('0') {
+            return Err(WsErrorKind::HandshakeFailed);
+        }
+
+        // Socket.IO namespace connect
+        let connect_packet = format!("40{}", conn.namespace_prefix());
+        conn.send_text(connect_packet).await?;
+
+        loop {
+            let packet = conn.next_text().await?;
+            if let Some(rest) = packet.strip_prefix("40") {
+                if conn.strip_namespace(rest).is_some() {
+                    break;
+                }
+            } else if packet.starts_with("44") {
+                tracing::debug!("Socket.IO namespace connect refused: {}", packet);
+                return Err(WsErrorKind::HandshakeFailed);
+            }
+        }
+
+        Ok(conn)
+    };
+
+    match tokio::time::timeout(timeout, handshake).await {
+        Ok(Ok(mut conn)) => {
+            conn.connect_time_us = start.elapsed().as_micros() as u64;
+            Ok(conn)
+        }
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(WsErrorKind::Timeout),
+    }
+}
+
+impl SocketIoConnection {
+    /// Emit an event; with `ack` set, wait for the server acknowledgement and
+    /// report emit-to-ack latency
+    pub async fn emit(
+        &mut self,
+        event: &str,
+        data: &str,
+        ack: bool,
+        timeout: Duration,
+    ) -> WsMessageResult {
+        if let Err(e) = self.drain_pending().await {
+            return WsMessageResult::error(e);
+        }
+
+        let ack_id = if ack {
+            self.next_ack_id += 1;
+            Some(self.next_ack_id)
+        } else {
+            None
+        };
+        let packet = encode_event(&self.namespace_prefix(), ack_id, event, data);
+        let bytes_sent = packet.len() as u64;
+        let start = Instant::now();
+
+        if let Err(e) = self.send_text(packet).await {
+            return WsMessageResult::error(e);
+        }
+
+        let Some(ack_id) = ack_id else {
+            let latency_us = start.elapsed().as_micros() as u64;
+            return WsMessageResult::success(latency_us, bytes_sent, 0);
+        };
+
+        let wait_ack = async {
+            loop {
+                let packet = self.next_text().await?;
+                if let Some(rest) = packet.strip_prefix("43")
+                    && let Some(rest) = self.strip_namespace(rest)
+                    && parse_ack_id(rest) == Some(ack_id)
+                {
+                    return Ok(packet);
+                }
+            }
+        };
+
+        match tokio::time::timeout(timeout, wait_ack).await {
+            Ok(Ok(response)) => {
+                let latency_us = start.elapsed().as_micros() as u64;
+                WsMessageResult::success(latency_us, bytes_sent, response.len() as u64)
+                    .with_response(response)
+            }
+            Ok(Err(e)) => WsMessageResult::error(e),
+            Err(_) => WsMessageResult::error(WsErrorKind::Timeout),
+        }
+    }
+
+    pub async fn close(mut self) -> Result<(), WsErrorKind> {
+        let disconnect = format!("41{}", self.namespace_prefix());
+        let _ = self.send_text(disconnect).await;
+        self.stream
+            .close(None)
+            .await
+            .map_err(|e| ws_error_to_kind(&e))
+    }
+
+    /// `/chat,` for custom namespaces, empty for the default namespace
+    fn namespace_prefix(&self) -> String {
+        if self.namespace.is_empty() || self.namespace == "/" {
+            String::new()
+        } else {
+            format!("{},", self.namespace)
+        }
+    }
+
+    fn strip_namespace<'a>(&self, packet: &'a str) -> Option<&'a str> {
+        let prefix = self.namespace_prefix();
+        if prefix.is_empty() {
+            // Default namespace packets carry no prefix
+            if packet.starts_with('/') {
+                None
+            } else {
+                Some(packet)
+            }
+        } else {
+            packet.strip_prefix(prefix.as_str())
+        }
+    }
+
+    async fn send_text(&mut self, text: String) -> Result<(), WsErrorKind> {
+        self.stream
+            .send(Message::Text(text.into()))
+            .await
+            .map_err(|_| WsErrorKind::SendFailed)
+    }
+
+    /// Read the next Engine.IO packet, answering pings along the way
+    async fn next_text(&mut self) -> Result<String, WsErrorKind> {
+        loop {
+            match self.stream.next().await {
+                Some(Ok(Message::Text(text))) => {
+                    if let Some(text) = self.handle_engine_packet(text.as_str()).await? {
+                        return Ok(text);
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => return Err(WsErrorKind::ConnectionClosed),
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(ws_error_to_kind(&e)),
+            }
+        }
+    }
+
+    /// Answer pings that arrived since the last emit (fire-and-forget mode
+    /// otherwise never reads, and the server would time the session out)
+    async fn drain_pending(&mut self) -> Result<(), WsErrorKind> {
+        while let Ok(next) = tokio::time::timeout(Duration::ZERO, self.stream.next()).await {
+            match next {
+                Some(Ok(Message::Text(text))) => {
+                    self.handle_engine_packet(text.as_str()).await?;
+                }
+                Some(Ok(Message::Close(_))) | None => return Err(WsErrorKind::ConnectionClosed),
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(ws_error_to_kind(&e)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle Engine.IO control packets; returns Socket.IO payloads
+    async fn handle_engine_packet(&mut self, text: &str) -> Result<Option<String>, WsErrorKind> {
+        match text.as_bytes().first() {
+            // ping -> pong
+            Some(b'2') => {
+                self.send_text("3".to_string()).await?;
+                Ok(None)
+            }
+            // close
+            Some(b'1') => Err(WsErrorKind::ConnectionClosed),
+            // open (0) and Socket.IO message (4) are returned to the caller
+            Some(b'0') | Some(b'4') => Ok(Some(text.to_string())),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Encode a Socket.IO EVENT packet (Engine.IO message type `4` + Socket.IO type `2`)
+fn encode_event(namespace_prefix: &str, ack_id: Option<u64>, event: &str, data: &str) -> String {
+    let event_json = serde_json::to_string(event).unwrap_or_else(|_| "\"message\"".to_string());
+    // Use the body verbatim when it is JSON, otherwise send it as a string
+    let data_json = if serde_json::from_str::<serde_json::Value>(data).is_ok() {
+        data.to_string()
+    } else {
+        serde_json::to_string(data).unwrap_or_default()
+    };
+    let ack = ack_id.map(|id| id.to_string()).unwrap_or_default();
+    format!(
+        "42{}{}[{},{}]",
+        namespace_prefix, ack, event_json, data_json
+    )
+}
+
+fn parse_ack_id(packet: &str) -> Option<u64> {
+    let digits: String = packet.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socketio_url_default_path() {
+        assert_eq!(
+            socketio_url("http://localhost:3000").unwrap(),
+            "ws://localhost:3000/socket.io/?EIO=4&transport=websocket"
+        );
+        assert_eq!(
+            socketio_url("https://example.com/").unwrap(),
+            "wss://example.com/socket.io/?EIO=4&transport=websocket"
+        );
+    }
+
+    #[test]
+    fn test_socketio_url_custom_path() {
+        assert_eq!(
+            socketio_url("http://localhost:3000/realtime").unwrap(),
+            "ws://localhost:3000/realtime/?EIO=4&transport=websocket"
+        );
+    }
+
+    #[test]
+    fn test_socketio_url_rejects_unknown_scheme() {
+        assert!(socketio_url("ftp://localhost").is_err());
+    }
+
+    #[test]
+    fn test_encode_event_with_ack() {
+        assert_eq!(
+            encode_event("/chat,", Some(7), "msg", r#"{"a":1}"#),
+            r#"42/chat,7["msg",{"a":1}]"#
+        );
+    }
+
+    #[test]
+    fn test_encode_event_plain_text() {
+        assert_eq!(encode_event("", None, "msg", "hi"), r#"42["msg","hi"]"#);
+    }
+
+    #[test]
+    fn test_parse_ack_id() {
+        assert_eq!(parse_ack_id(r#"12["ok"]"#), Some(12));
+        assert_eq!(parse_ack_id(r#"["ok"]"#), None);
+    }
+}
@@ -0,0 +1,13 @@
+//! Socket.IO client implementation (feature-gated)
+//!
+//! Enable with: cargo build --features socketio
+//!
+//! Speaks Engine.IO v4 / Socket.IO v5 over the WebSocket transport:
+//! - Engine.IO open handshake and ping/pong keepalive
+//! - Namespace connect (`40`) with connect error detection (`44`)
+//! - Event emit (`42`) with optional acknowledgement (`43`) latency
+
+mod client;
+
+#[allow(unused_imports)]
+pub use client::{SocketIoConnection, connect, socketio_url};
@@ -0,0 +1,2571 @@
+use crate::net::SocketOptions;
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+// ============================================================================
+// Stages (v0.7)
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stage {
+    pub duration: Duration,
+    pub target: Option<u32>,      // VU-based target (constant VUs mode)
+    pub target_rate: Option<u32>, // RPS-based target (arrival rate mode)
+    pub target_message_rate: Option<u32>, // Per-connection messages/sec (WebSocket mode)
+}
+
+// ============================================================================
+// Checks & Thresholds (v0.6)
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub condition: CheckCondition,
+}
+
+#[derive(Debug, Clone)]
+pub enum CheckCondition {
+    StatusEquals(u16),
+    StatusIn(Vec<u16>),
+    StatusLt(u16),
+    StatusGt(u16),
+    BodyContains(String),
+    BodyNotContains(String),
+    BodyMatches(regex_lite::Regex),
+}
+
+impl CheckCondition {
+    pub fn evaluate(&self, status: Option<u16>, body: &str) -> bool {
+        match self {
+            CheckCondition::StatusEquals(expected) => status == Some(*expected),
+            CheckCondition::StatusIn(codes) => status.map(|s| codes.contains(&s)).unwrap_or(false),
+            CheckCondition::StatusLt(threshold) => status.map(|s| s < *threshold).unwrap_or(false),
+            CheckCondition::StatusGt(threshold) => status.map(|s| s > *threshold).unwrap_or(false),
+            CheckCondition::BodyContains(needle) => body.contains(needle),
+            CheckCondition::BodyNotContains(needle) => !body.contains(needle),
+            CheckCondition::BodyMatches(re) => re.is_match(body),
+        }
+    }
+}
+
+/// When a raw TCP/UDP exchange has received a complete response
+#[derive(Debug, Clone)]
+pub enum RawExpect {
+    /// At least this many bytes
+    Bytes(usize),
+    /// The received bytes (lossily decoded as UTF-8) match the pattern
+    Pattern(regex_lite::Regex),
+}
+
+impl RawExpect {
+    pub fn is_complete(&self, received: &[u8]) -> bool {
+        match self {
+            RawExpect::Bytes(n) => received.len() >= *n,
+            RawExpect::Pattern(re) => re.is_match(&String::from_utf8_lossy(received)),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            RawExpect::Bytes(n) => format!("{} bytes", n),
+            RawExpect::Pattern(re) => format!("/{}/", re.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Threshold {
+    pub metric: ThresholdMetric,
+    pub operator: ThresholdOp,
+    pub value: f64,
+}
+
+/// `p99_latency_ms_trend = "< +10% over 7d"`: how far a metric may move from
+/// the median of the runs stored in history (`--db-url`) within `window`
+#[derive(Debug, Clone)]
+pub struct TrendThreshold {
+    pub metric: ThresholdMetric,
+    pub operator: ThresholdOp,
+    /// Change from the median in percent; negative for a drop
+    pub change_pct: f64,
+    pub window: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThresholdMetric {
+    /// `pN_latency_ms`, for a percentile given in thousandths (99.9 = 99_900)
+    LatencyPercentileMs(u32),
+    MeanLatencyMs,
+    MaxLatencyMs,
+    ErrorRate,
+    Rps,
+    CheckPassRate,
+    Apdex,
+    ExtractionFailureRate,
+    UnexpectedContentType,
+}
+
+impl ThresholdMetric {
+    /// The fixed metrics, plus the default percentiles
+    pub const ALL: [ThresholdMetric; 14] = [
+        ThresholdMetric::LatencyPercentileMs(50_000),
+        ThresholdMetric::LatencyPercentileMs(75_000),
+        ThresholdMetric::LatencyPercentileMs(90_000),
+        ThresholdMetric::LatencyPercentileMs(95_000),
+        ThresholdMetric::LatencyPercentileMs(99_000),
+        ThresholdMetric::LatencyPercentileMs(99_900),
+        ThresholdMetric::MeanLatencyMs,
+        ThresholdMetric::MaxLatencyMs,
+        ThresholdMetric::ErrorRate,
+        ThresholdMetric::Rps,
+        ThresholdMetric::CheckPassRate,
+        ThresholdMetric::Apdex,
+        ThresholdMetric::ExtractionFailureRate,
+        ThresholdMetric::UnexpectedContentType,
+    ];
+
+    pub fn latency_percentile(p: f64) -> Self {
+        ThresholdMetric::LatencyPercentileMs((p * 1000.0).round() as u32)
+    }
+
+    /// The percentile a `pN_latency_ms` metric checks
+    pub fn percentile(&self) -> Option<f64> {
+        match self {
+            ThresholdMetric::LatencyPercentileMs(p) => Some(*p as f64 / 1000.0),
+            _ => None,
+        }
+    }
+
+    /// Parse a `[thresholds]` key; any `pN_latency_ms` parses, whether or
+    /// not that percentile is configured
+    pub fn parse(s: &str) -> Option<Self> {
+        if let Some(key) = s.strip_suffix("_latency_ms")
+            && let Some(p) = parse_percentile_key(key)
+        {
+            return Some(Self::latency_percentile(p));
+        }
+        Self::ALL
+            .into_iter()
+            .find(|m| m.percentile().is_none() && m.to_string() == s)
+    }
+}
+
+impl std::fmt::Display for ThresholdMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ThresholdMetric::LatencyPercentileMs(p) => {
+                return write!(f, "{}_latency_ms", percentile_key(*p as f64 / 1000.0));
+            }
+            ThresholdMetric::MeanLatencyMs => "mean_latency_ms",
+            ThresholdMetric::MaxLatencyMs => "max_latency_ms",
+            ThresholdMetric::ErrorRate => "error_rate",
+            ThresholdMetric::Rps => "rps",
+            ThresholdMetric::CheckPassRate => "check_pass_rate",
+            ThresholdMetric::Apdex => "apdex",
+            ThresholdMetric::ExtractionFailureRate => "extraction_failure_rate",
+            ThresholdMetric::UnexpectedContentType => "unexpected_content_type",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Latency percentiles reported when `percentiles` isn't configured
+pub const DEFAULT_PERCENTILES: [f64; 6] = [50.0, 75.0, 90.0, 95.0, 99.0, 99.9];
+
+/// What a percentile is called in JSON keys and threshold metrics: the
+/// number without its dot (99.9 = `p999`). Fractional percentiles keep two
+/// integer digits (5.5 = `p055`) so every key reads back the same way.
+pub fn percentile_key(p: f64) -> String {
+    let text = p.to_string();
+    match text.split_once('.') {
+        Some((int, frac)) => format!("p{:0>2}{}", int, frac),
+        None => format!("p{}", text),
+    }
+}
+
+/// Read a percentile back from `percentile_key` (with or without the `p`)
+pub fn parse_percentile_key(key: &str) -> Option<f64> {
+    let digits = key.strip_prefix('p').unwrap_or(key);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let p: f64 = if digits.len() <= 2 {
+        digits.parse().ok()?
+    } else {
+        format!("{}.{}", &digits[..2], &digits[2..]).parse().ok()?
+    };
+    (p > 0.0 && p < 100.0).then_some(p)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdOp {
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Eq,
+}
+
+impl ThresholdOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThresholdOp::Lt => "<",
+            ThresholdOp::Lte => "<=",
+            ThresholdOp::Gt => ">",
+            ThresholdOp::Gte => ">=",
+            ThresholdOp::Eq => "==",
+        }
+    }
+
+    pub fn evaluate(&self, actual: f64, expected: f64) -> bool {
+        match self {
+            ThresholdOp::Lt => actual < expected,
+            ThresholdOp::Lte => actual <= expected,
+            ThresholdOp::Gt => actual > expected,
+            ThresholdOp::Gte => actual >= expected,
+            ThresholdOp::Eq => (actual - expected).abs() < f64::EPSILON,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThresholdResult {
+    pub metric: String,
+    pub condition: String,
+    pub actual: f64,
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckStats {
+    pub total: u64,
+    pub passed: u64,
+    pub failed: u64,
+}
+
+impl CheckStats {
+    #[allow(dead_code)]
+    pub fn pass_rate(&self) -> f64 {
+        if self.total > 0 {
+            self.passed as f64 / self.total as f64
+        } else {
+            1.0
+        }
+    }
+}
+
+// ============================================================================
+// Error Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Timeout,
+    Dns,
+    Connect,
+    Tls,
+    Refused,
+    Reset,
+    Http,
+    Body,
+    /// JSON-RPC `error` member in an otherwise successful response
+    #[serde(rename = "jsonrpc")]
+    JsonRpc,
+    /// A plugin's `on_request` hook failed, so the request wasn't sent
+    Plugin,
+    /// The request outlasted `--deadline`, across all of its attempts
+    Deadline,
+    Other,
+}
+
+impl ErrorKind {
+    pub fn from_reqwest_error(err: &reqwest::Error) -> Self {
+        if err.is_timeout() {
+            ErrorKind::Timeout
+        } else if err.is_connect() {
+            if err.to_string().contains("dns") || err.to_string().contains("resolve") {
+                ErrorKind::Dns
+            } else if err.to_string().contains("refused") {
+                ErrorKind::Refused
+            } else if err.to_string().contains("reset") {
+                ErrorKind::Reset
+            } else {
+                ErrorKind::Connect
+            }
+        } else if err.is_request() {
+            ErrorKind::Http
+        } else if err.is_body() {
+            ErrorKind::Body
+        } else if err.to_string().contains("tls") || err.to_string().contains("certificate") {
+            ErrorKind::Tls
+        } else {
+            ErrorKind::Other
+        }
+    }
+
+    /// Inverse of `as_str`
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "timeout" => ErrorKind::Timeout,
+            "dns" => ErrorKind::Dns,
+            "connect" => ErrorKind::Connect,
+            "tls" => ErrorKind::Tls,
+            "refused" => ErrorKind::Refused,
+            "reset" => ErrorKind::Reset,
+            "http" => ErrorKind::Http,
+            "body" => ErrorKind::Body,
+            "jsonrpc" => ErrorKind::JsonRpc,
+            "plugin" => ErrorKind::Plugin,
+            "deadline" => ErrorKind::Deadline,
+            "other" => ErrorKind::Other,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::Dns => "dns",
+            ErrorKind::Connect => "connect",
+            ErrorKind::Tls => "tls",
+            ErrorKind::Refused => "refused",
+            ErrorKind::Reset => "reset",
+            ErrorKind::Http => "http",
+            ErrorKind::Body => "body",
+            ErrorKind::JsonRpc => "jsonrpc",
+            ErrorKind::Plugin => "plugin",
+            ErrorKind::Deadline => "deadline",
+            ErrorKind::Other => "other",
+        }
+    }
+
+    pub fn suggestion(&self) -> &'static str {
+        match self {
+            ErrorKind::Timeout => "try increasing --timeout",
+            ErrorKind::Dns => "check the hostname",
+            ErrorKind::Connect => "check network and firewall",
+            ErrorKind::Tls => "try --insecure to skip verification",
+            ErrorKind::Refused => "is the server running?",
+            ErrorKind::Reset => "server closed the connection",
+            ErrorKind::Http => "check request parameters",
+            ErrorKind::Body => "response body error",
+            ErrorKind::JsonRpc => "see JSON-RPC error codes",
+            ErrorKind::Plugin => "see the plugin warning in the log",
+            ErrorKind::Deadline => "request outlasted --deadline",
+            ErrorKind::Other => "",
+        }
+    }
+}
+
+// ============================================================================
+// WebSocket Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WsMode {
+    #[default]
+    Echo,
+    FireAndForget,
+    /// Publishers send, subscribers measure fan-out delivery latency
+    Broadcast,
+}
+
+/// What workers do when the aggregator falls behind and the result channel fills up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackpressurePolicy {
+    /// Wait for room; nothing is lost but workers stall
+    #[default]
+    Block,
+    /// Discard the batch and count it in `results_dropped`
+    Drop,
+    /// Queue batches on the worker until there is room (unbounded memory)
+    Grow,
+}
+
+impl BackpressurePolicy {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "block" => Some(Self::Block),
+            "drop" => Some(Self::Drop),
+            "grow" => Some(Self::Grow),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::Drop => "drop",
+            Self::Grow => "grow",
+        }
+    }
+}
+
+/// How VU workers spread their requests over `--proxy-list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProxyRotation {
+    /// Each VU sticks to one proxy, like a client behind one egress IP
+    #[default]
+    Vu,
+    /// Every request goes through the next proxy in turn
+    Request,
+}
+
+impl ProxyRotation {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "vu" => Some(Self::Vu),
+            "request" => Some(Self::Request),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Vu => "vu",
+            Self::Request => "request",
+        }
+    }
+}
+
+/// Trace context headers injected into each HTTP request (--trace-propagation)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TracePropagation {
+    /// W3C Trace Context `traceparent`
+    W3c,
+    /// Zipkin B3 `X-B3-TraceId` / `X-B3-SpanId` / `X-B3-Sampled`
+    B3,
+}
+
+impl TracePropagation {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "w3c" => Some(Self::W3c),
+            "b3" => Some(Self::B3),
+            _ => None,
+        }
+    }
+}
+
+/// Encoding request bodies are compressed with (--compress-body)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyCompression {
+    Gzip,
+    /// zlib-wrapped deflate, as HTTP's `deflate` coding is defined
+    Deflate,
+}
+
+impl BodyCompression {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "gzip" => Some(Self::Gzip),
+            "deflate" => Some(Self::Deflate),
+            _ => None,
+        }
+    }
+
+    /// `Content-Encoding` value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+        }
+    }
+}
+
+/// A client certificate and its key (PEM), for mTLS
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientIdentity {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+/// Layer a probe run times instead of sending HTTP requests (--probe)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeKind {
+    /// Resolving the target's host
+    Dns,
+    /// Opening a TCP connection to it
+    Connect,
+    /// The TLS handshake on a new connection
+    Tls,
+}
+
+impl ProbeKind {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "dns" => Some(Self::Dns),
+            "connect" => Some(Self::Connect),
+            "tls" => Some(Self::Tls),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dns => "dns",
+            Self::Connect => "connect",
+            Self::Tls => "tls",
+        }
+    }
+}
+
+/// Pause after each of a VU's requests (`--think-time`, or a scenario's
+/// `think_time`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThinkTime {
+    /// `"500ms"`
+    Fixed(Duration),
+    /// `"1s..3s"`, drawn uniformly from the range
+    Uniform(Duration, Duration),
+    /// `"normal(2s, 500ms)"`: mean and standard deviation, cut off at zero
+    Normal(Duration, Duration),
+    /// `"exp(2s)"`: exponentially distributed around the mean, the way
+    /// independent users' pauses are
+    Exponential(Duration),
+}
+
+impl std::fmt::Display for ThinkTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fixed(pause) => write!(f, "{:?}", pause),
+            Self::Uniform(min, max) => write!(f, "{:?}..{:?}", min, max),
+            Self::Normal(mean, std_dev) => write!(f, "normal({:?}, {:?})", mean, std_dev),
+            Self::Exponential(mean) => write!(f, "exp({:?})", mean),
+        }
+    }
+}
+
+/// TLS implementation used by the HTTP client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TlsBackend {
+    /// Pure-Rust rustls with bundled webpki roots
+    #[default]
+    Rustls,
+    /// Platform TLS and trust store (requires --features native-tls)
+    #[cfg(feature = "native-tls")]
+    Native,
+}
+
+impl TlsBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TlsBackend::Rustls => "rustls",
+            #[cfg(feature = "native-tls")]
+            TlsBackend::Native => "native",
+        }
+    }
+}
+
+/// How --protocol-race runs the HTTP/2 and HTTP/3 legs
+#[cfg(feature = "http3")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolRace {
+    /// Both protocols at once, each with half the VUs
+    Split,
+    /// HTTP/2 for the full duration, then HTTP/3
+    Sequential,
+}
+
+#[cfg(feature = "http3")]
+impl ProtocolRace {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProtocolRace::Split => "split",
+            ProtocolRace::Sequential => "sequential",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WsErrorKind {
+    ConnectFailed,
+    HandshakeFailed,
+    Timeout,
+    ConnectionClosed,
+    SendFailed,
+    ReceiveFailed,
+    ProtocolError,
+    Tls,
+    Other,
+}
+
+#[allow(dead_code)]
+impl WsErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WsErrorKind::ConnectFailed => "connect_failed",
+            WsErrorKind::HandshakeFailed => "handshake_failed",
+            WsErrorKind::Timeout => "timeout",
+            WsErrorKind::ConnectionClosed => "connection_closed",
+            WsErrorKind::SendFailed => "send_failed",
+            WsErrorKind::ReceiveFailed => "receive_failed",
+            WsErrorKind::ProtocolError => "protocol_error",
+            WsErrorKind::Tls => "tls",
+            WsErrorKind::Other => "other",
+        }
+    }
+
+    pub fn suggestion(&self) -> &'static str {
+        match self {
+            WsErrorKind::ConnectFailed => "check server is running and URL is correct",
+            WsErrorKind::HandshakeFailed => "server may not support WebSocket",
+            WsErrorKind::Timeout => "try increasing --timeout",
+            WsErrorKind::ConnectionClosed => "server closed connection unexpectedly",
+            WsErrorKind::SendFailed => "failed to send message",
+            WsErrorKind::ReceiveFailed => "failed to receive response",
+            WsErrorKind::ProtocolError => "WebSocket protocol error",
+            WsErrorKind::Tls => "try ws:// instead of wss://",
+            WsErrorKind::Other => "",
+        }
+    }
+}
+
+/// A close frame received from the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WsClose {
+    pub code: u16,
+    pub reason: String,
+}
+
+impl WsClose {
+    /// No close frame arrived before the connection dropped (RFC 6455 1006)
+    pub fn abnormal() -> Self {
+        Self {
+            code: 1006,
+            reason: String::new(),
+        }
+    }
+}
+
+/// Standard name for a WebSocket close code
+pub fn ws_close_code_name(code: u16) -> &'static str {
+    match code {
+        1000 => "Normal Closure",
+        1001 => "Going Away",
+        1002 => "Protocol Error",
+        1003 => "Unsupported Data",
+        1005 => "No Status Received",
+        1006 => "Abnormal Closure",
+        1007 => "Invalid Payload",
+        1008 => "Policy Violation",
+        1009 => "Message Too Big",
+        1010 => "Mandatory Extension",
+        1011 => "Internal Error",
+        1012 => "Service Restart",
+        1013 => "Try Again Later",
+        1014 => "Bad Gateway",
+        1015 => "TLS Handshake",
+        3000..=3999 => "Registered",
+        4000..=4999 => "Application",
+        _ => "Unknown",
+    }
+}
+
+/// Server-initiated closes with one code: how many, and the latest reason text
+#[derive(Debug, Clone, Default)]
+pub struct WsCloseStat {
+    pub count: u64,
+    pub reason: String,
+}
+
+/// permessage-deflate payload bytes before (raw) and after (wire) compression
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WsWireBytes {
+    pub raw_sent: u64,
+    pub wire_sent: u64,
+    pub raw_received: u64,
+    pub wire_received: u64,
+}
+
+impl WsWireBytes {
+    pub fn add(&mut self, other: &WsWireBytes) {
+        self.raw_sent += other.raw_sent;
+        self.wire_sent += other.wire_sent;
+        self.raw_received += other.raw_received;
+        self.wire_received += other.wire_received;
+    }
+
+    /// Wire bytes as a fraction of raw bytes, both directions combined
+    pub fn ratio(&self) -> f64 {
+        let raw = self.raw_sent + self.raw_received;
+        if raw == 0 {
+            return 1.0;
+        }
+        (self.wire_sent + self.wire_received) as f64 / raw as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WsMessageResult {
+    pub message_latency_us: u64,
+    pub connect_time_us: Option<u64>, // Only set on first message after connect
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub response: Option<String>,
+    pub error: Option<WsErrorKind>,
+    /// Set when this result follows a deliberate churn reconnect
+    pub reconnect: bool,
+    /// Broadcast mode: subscribers connected when a publisher sent this message
+    pub fanout: Option<u32>,
+    /// Broadcast mode: result reported by a subscriber rather than a sender
+    pub delivery: bool,
+    /// Compression: bytes counted on the connection since the previous result
+    pub wire_bytes: Option<WsWireBytes>,
+    /// Set when the server closed the connection
+    pub close: Option<WsClose>,
+}
+
+impl WsMessageResult {
+    pub fn success(message_latency_us: u64, bytes_sent: u64, bytes_received: u64) -> Self {
+        Self {
+            message_latency_us,
+            connect_time_us: None,
+            bytes_sent,
+            bytes_received,
+            response: None,
+            error: None,
+            reconnect: false,
+            fanout: None,
+            delivery: false,
+            wire_bytes: None,
+            close: None,
+        }
+    }
+
+    pub fn with_connect_time(mut self, connect_time_us: u64) -> Self {
+        self.connect_time_us = Some(connect_time_us);
+        self
+    }
+
+    pub fn with_response(mut self, response: String) -> Self {
+        self.response = Some(response);
+        self
+    }
+
+    pub fn error(error: WsErrorKind) -> Self {
+        Self {
+            message_latency_us: 0,
+            connect_time_us: None,
+            bytes_sent: 0,
+            bytes_received: 0,
+            response: None,
+            error: Some(error),
+            reconnect: false,
+            fanout: None,
+            delivery: false,
+            wire_bytes: None,
+            close: None,
+        }
+    }
+
+    /// A broadcast message received by a subscriber
+    pub fn delivery(delivery_latency_us: u64, bytes_received: u64) -> Self {
+        Self {
+            message_latency_us: delivery_latency_us,
+            connect_time_us: None,
+            bytes_sent: 0,
+            bytes_received,
+            response: None,
+            error: None,
+            reconnect: false,
+            fanout: None,
+            delivery: true,
+            wire_bytes: None,
+            close: None,
+        }
+    }
+
+    /// A broadcast subscriber connected (no message received yet)
+    pub fn subscribed(connect_time_us: u64) -> Self {
+        Self {
+            connect_time_us: Some(connect_time_us),
+            ..Self::delivery(0, 0)
+        }
+    }
+
+    pub fn with_fanout(mut self, fanout: u32) -> Self {
+        self.fanout = Some(fanout);
+        self
+    }
+
+    pub fn with_reconnect(mut self) -> Self {
+        self.reconnect = true;
+        self
+    }
+
+    pub fn with_wire_bytes(mut self, wire_bytes: Option<WsWireBytes>) -> Self {
+        self.wire_bytes = wire_bytes;
+        self
+    }
+
+    pub fn with_close(mut self, close: Option<WsClose>) -> Self {
+        self.close = close;
+        self
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+// ============================================================================
+// Multipart Form Fields (v1.2)
+// ============================================================================
+
+/// A parsed form field for multipart requests
+#[derive(Debug, Clone)]
+pub enum FormField {
+    /// Simple text field: name=value
+    Text { name: String, value: String },
+    /// File field: name=@filepath with optional filename and mime type
+    File {
+        name: String,
+        path: PathBuf,
+        filename: Option<String>,
+        mime_type: Option<String>,
+    },
+}
+
+impl FormField {
+    /// Parse a form field string in curl-like format
+    /// - "name=value" -> Text field
+    /// - "name=@/path/to/file" -> File field
+    /// - "name=@/path;filename=custom.txt" -> File with custom filename
+    /// - "name=@/path;type=application/json" -> File with custom mime type
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let eq_pos = s.find('=').ok_or("Form field must contain '='")?;
+        let name = s[..eq_pos].to_string();
+        let value = &s[eq_pos + 1..];
+
+        if name.is_empty() {
+            return Err("Form field name cannot be empty".to_string());
+        }
+
+        if let Some(file_spec) = value.strip_prefix('@') {
+            // File field: parse path and optional modifiers
+            let parts: Vec<&str> = file_spec.split(';').collect();
+            let path = PathBuf::from(parts[0]);
+            let mut filename = None;
+            let mut mime_type = None;
+
+            for part in parts.iter().skip(1) {
+                if let Some(fname) = part.strip_prefix("filename=") {
+                    filename = Some(fname.to_string());
+                } else if let Some(mtype) = part.strip_prefix("type=") {
+                    mime_type = Some(mtype.to_string());
+                }
+            }
+
+            Ok(FormField::File {
+                name,
+                path,
+                filename,
+                mime_type,
+            })
+        } else {
+            // Text field
+            Ok(FormField::Text {
+                name,
+                value: value.to_string(),
+            })
+        }
+    }
+}
+
+// ============================================================================
+// HTTP Request Result
+// ============================================================================
+
+/// QUIC path statistics sampled from a quinn connection once its request completes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuicSample {
+    pub rtt_us: u64,
+    pub cwnd: u64,
+    pub sent_packets: u64,
+    pub lost_packets: u64,
+    pub congestion_events: u64,
+    /// How the request's handshake went; None unless --http3-0rtt is on
+    pub handshake: Option<QuicHandshake>,
+}
+
+/// Handshake outcome of an HTTP/3 connection in 0-RTT mode
+#[allow(dead_code)] // Constructed only by the http3 client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuicHandshake {
+    /// Full handshake (no resumption ticket yet)
+    OneRtt,
+    /// Request sent as 0-RTT early data and accepted
+    ZeroRtt,
+    /// Server rejected the early data; request was resent over 1-RTT
+    ZeroRttRejected,
+}
+
+/// 0-RTT vs 1-RTT request counts and latency (--http3-0rtt)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZeroRttStats {
+    pub zero_rtt_requests: u64,
+    pub one_rtt_requests: u64,
+    pub rejected: u64,
+    pub zero_rtt_p50_us: u64,
+    pub zero_rtt_p99_us: u64,
+    pub one_rtt_p50_us: u64,
+    pub one_rtt_p99_us: u64,
+}
+
+/// Requests, errors and latency for one endpoint group (`GET /users/{id}`)
+#[derive(Debug, Clone, Default)]
+pub struct EndpointStats {
+    pub name: String,
+    pub requests: u64,
+    pub failed: u64,
+    pub latency_p50_us: u64,
+    pub latency_p95_us: u64,
+    pub latency_p99_us: u64,
+    pub latency_max_us: u64,
+}
+
+/// Requests, errors and latency while one `[[stages]]` entry was running
+#[derive(Debug, Clone, Default)]
+pub struct StageStats {
+    /// Position in `[[stages]]`, from 0
+    pub index: usize,
+    /// VUs, or requests per second for `target_rate` stages, the stage ramps to
+    pub target: u32,
+    /// Time measured in the stage; warmup isn't counted
+    pub elapsed: Duration,
+    pub requests: u64,
+    pub failed: u64,
+    pub latency_p50_us: u64,
+    pub latency_p95_us: u64,
+    pub latency_p99_us: u64,
+    pub latency_max_us: u64,
+}
+
+impl StageStats {
+    /// Requests per second completed in the stage
+    pub fn rps(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.requests as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        if self.requests > 0 {
+            self.failed as f64 / self.requests as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Apdex for a run with `apdex_t` set: satisfied requests finished within T,
+/// tolerating ones within 4T, and the rest (including failures) frustrated
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApdexStats {
+    pub t_us: u64,
+    pub satisfied: u64,
+    pub tolerating: u64,
+    pub frustrated: u64,
+}
+
+impl ApdexStats {
+    /// (satisfied + tolerating / 2) / total, from 0 to 1
+    pub fn score(&self) -> f64 {
+        let total = self.satisfied + self.tolerating + self.frustrated;
+        if total == 0 {
+            return 1.0;
+        }
+        (self.satisfied as f64 + self.tolerating as f64 / 2.0) / total as f64
+    }
+
+    /// Standard rating band for the score
+    pub fn rating(&self) -> &'static str {
+        match self.score() {
+            s if s >= 0.94 => "excellent",
+            s if s >= 0.85 => "good",
+            s if s >= 0.70 => "fair",
+            s if s >= 0.50 => "poor",
+            _ => "unacceptable",
+        }
+    }
+}
+
+/// Request-body upload stats, for runs that send bodies
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UploadStats {
+    pub bytes_sent: u64,
+    /// Requests whose upload was timed (bodies over 64 KiB)
+    pub timed_requests: u64,
+    pub time_mean_us: f64,
+    pub time_p50_us: u64,
+    pub time_p95_us: u64,
+    pub time_p99_us: u64,
+    pub time_max_us: u64,
+    /// Timed body bytes over the time spent writing them
+    pub throughput_bytes_per_sec: f64,
+}
+
+/// What a response sent back in the `--request-id-header` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdEcho {
+    Matched,
+    /// Another request's ID: the response crossed over from another request
+    Mismatched,
+    Missing,
+}
+
+/// Responses checked for the request ID they were sent (`--request-id-header`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestIdStats {
+    pub responses: u64,
+    pub matched: u64,
+    pub mismatched: u64,
+    pub missing: u64,
+}
+
+impl RequestIdStats {
+    pub fn record(&mut self, echo: IdEcho) {
+        self.responses += 1;
+        match echo {
+            IdEcho::Matched => self.matched += 1,
+            IdEcho::Mismatched => self.mismatched += 1,
+            IdEcho::Missing => self.missing += 1,
+        }
+    }
+
+    pub fn add(&mut self, other: &RequestIdStats) {
+        self.responses += other.responses;
+        self.matched += other.matched;
+        self.mismatched += other.mismatched;
+        self.missing += other.missing;
+    }
+}
+
+/// How warmup ended under --auto-warmup
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AutoWarmup {
+    /// Time from the start of the run to the start of measurement
+    pub length: Duration,
+    /// False if the numbers never settled and the cap (`warmup`) ended it
+    pub settled: bool,
+}
+
+/// Throughput and latency over the last `RECENT_WINDOW` of the run, which
+/// cumulative numbers blur with the ramp-up
+#[derive(Debug, Clone, Default)]
+pub struct RecentStats {
+    /// Time covered: whole seconds, so up to a second over `RECENT_WINDOW`,
+    /// or the whole run while it is shorter
+    pub window: Duration,
+    pub requests: u64,
+    pub requests_per_sec: f64,
+    /// (percentile, latency in µs) for the configured percentiles
+    pub latency_percentiles: Vec<(f64, u64)>,
+}
+
+/// Values a plugin reported under one name with `kaioken.metric`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PluginMetric {
+    pub count: u64,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl PluginMetric {
+    pub fn record(&mut self, value: f64) {
+        if self.count == 0 || value < self.min {
+            self.min = value;
+        }
+        if self.count == 0 || value > self.max {
+            self.max = value;
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count > 0 {
+            self.sum / self.count as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// How far back `RecentStats` looks
+pub const RECENT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Connection pool activity, for HTTP runs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConnectionStats {
+    /// Connections the client had to open (TCP, plus the TLS handshake)
+    pub opened: u64,
+    /// Requests that went out on an already open connection
+    pub reused: u64,
+    pub opened_per_sec: f64,
+    /// Connections opened over the last second
+    pub rolling_opened_per_sec: f64,
+}
+
+impl ConnectionStats {
+    /// Fraction of requests that did not need a new connection
+    pub fn reuse_rate(&self) -> f64 {
+        let total = self.opened + self.reused;
+        if total == 0 {
+            return 0.0;
+        }
+        self.reused as f64 / total as f64
+    }
+}
+
+/// What a failed request sent and got back, for the failure samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureDetail {
+    /// `METHOD URL` (the final URL when redirects were followed)
+    pub request: String,
+    /// Transport error text, when no response came back
+    pub message: Option<String>,
+    /// Start of the response body
+    pub body: Option<String>,
+}
+
+impl FailureDetail {
+    /// Longest body excerpt kept, in bytes
+    pub const BODY_LIMIT: usize = 1024;
+
+    /// The first `BODY_LIMIT` bytes of `body` as text
+    pub fn excerpt(body: &[u8]) -> String {
+        String::from_utf8_lossy(&body[..body.len().min(Self::BODY_LIMIT)]).into_owned()
+    }
+}
+
+/// Where `--slow-log` writes requests that took at least `threshold`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlowLogConfig {
+    pub threshold: Duration,
+    pub path: String,
+}
+
+/// Client-side faults the VU workers inject (`[chaos]`). Rates are
+/// fractions of requests; durations are picked uniformly from the range.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChaosConfig {
+    /// Extra delay before a request is sent
+    pub latency: Option<(Duration, Duration)>,
+    pub latency_rate: f64,
+    /// Requests given up on if no response arrived within `abort_after`
+    pub abort_rate: f64,
+    pub abort_after: (Duration, Duration),
+    /// Requests replaced by a connection the client resets (RST) mid-request
+    pub reset_rate: f64,
+}
+
+/// `--duplicate-rate`: a share of the requests is sent twice at once with
+/// the same idempotency key, to check both copies get the same response
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateConfig {
+    pub rate: f64,
+    /// Header the idempotency key is sent in
+    pub header: String,
+}
+
+/// Where the rate stages came from with `--rate-profile`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateProfile {
+    pub path: String,
+    pub points: usize,
+    /// Time from the profile's first point to its last
+    pub span: Duration,
+    /// How long the replay takes
+    pub window: Duration,
+}
+
+/// `--vu-autoscale`: arrival-rate VUs are added a step at a time when an
+/// iteration finds none free, and shed again once they sit unused, instead
+/// of growing to a fixed `max_vus`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VuAutoscale {
+    /// VUs added at once
+    pub step: u32,
+    /// Least time between two scale-ups; iterations finding no VU in between are dropped
+    pub cooldown: Duration,
+    /// VUs never grow past this
+    pub cap: u32,
+    /// Unused VUs are shed, a step at a time, after this long
+    pub idle: Duration,
+}
+
+/// Client network the VUs are slowed to (`--network-profile`): each
+/// response takes one more round trip, plus the time its bytes need at the
+/// profile's bandwidth
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkProfile {
+    pub name: String,
+    /// Bits per second
+    pub down_bps: u64,
+    pub up_bps: u64,
+    pub rtt: Duration,
+}
+
+impl NetworkProfile {
+    /// Built-in profiles, after the WebPageTest connection presets
+    pub fn preset(name: &str) -> Option<Self> {
+        let (down_bps, up_bps, rtt_ms) = match name {
+            "3g" => (1_600_000, 768_000, 300),
+            "4g" => (9_000_000, 9_000_000, 170),
+            "dsl" => (1_500_000, 384_000, 50),
+            _ => return None,
+        };
+        Some(Self {
+            name: name.to_string(),
+            down_bps,
+            up_bps,
+            rtt: Duration::from_millis(rtt_ms),
+        })
+    }
+
+    /// Extra time a request that sent and received these bytes takes
+    pub fn delay(&self, bytes_sent: u64, bytes_received: u64) -> Duration {
+        let transfer = |bytes: u64, bps: u64| {
+            Duration::from_micros((bytes * 8).saturating_mul(1_000_000) / bps.max(1))
+        };
+        self.rtt + transfer(bytes_sent, self.up_bps) + transfer(bytes_received, self.down_bps)
+    }
+}
+
+/// Faults injected under `[chaos]`, after warmup
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChaosStats {
+    pub delayed: u64,
+    pub aborted: u64,
+    pub reset: u64,
+}
+
+/// 429 and 503 responses the VU workers backed off after
+/// (`honor_retry_after`), after warmup
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThrottleStats {
+    pub throttled: u64,
+    /// Of those, how many said how long to wait with Retry-After
+    pub retry_after: u64,
+    /// Time workers spent waiting instead of sending, summed over workers
+    pub waited: Duration,
+}
+
+/// Requests that waited for a connection under `--max-connections`, after
+/// warmup
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ConnectionCapStats {
+    pub limit: u32,
+    /// Requests that found every connection busy
+    pub waited: u64,
+    /// Of those waits
+    pub wait_mean_us: u64,
+    pub wait_max_us: u64,
+}
+
+/// Responses per Content-Type, after warmup
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContentTypeStats {
+    /// Busiest first; `none` counts responses without a Content-Type
+    pub types: Vec<ContentTypeCount>,
+    /// Types `--expect-content-type` allows (empty = any)
+    pub expected: Vec<String>,
+}
+
+impl ContentTypeStats {
+    /// Successful responses of a type that wasn't expected
+    pub fn unexpected(&self) -> u64 {
+        self.types.iter().map(|t| t.unexpected).sum()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContentTypeCount {
+    /// Media type, lowercase without parameters
+    pub content_type: String,
+    pub responses: u64,
+    /// Successful responses of this type, when it isn't one expected
+    pub unexpected: u64,
+}
+
+/// How often one scenario's `[scenarios.extract]` entry found its value
+#[derive(Debug, Clone, Default)]
+pub struct ExtractionStats {
+    pub scenario: String,
+    pub name: String,
+    pub attempts: u64,
+    /// JSONPath not found, regex without a match, ...
+    pub failed: u64,
+}
+
+/// Requests one weighted scenario got against its share of the weights
+#[derive(Debug, Clone, Default)]
+pub struct ScenarioShare {
+    pub scenario: String,
+    pub weight: u32,
+    pub requests: u64,
+    /// Its weight over all weights, 0 to 1
+    pub expected: f64,
+    /// Its requests over all scenario requests, 0 to 1
+    pub actual: f64,
+}
+
+impl ScenarioShare {
+    /// How far the achieved share is off the configured one, in percent of it
+    pub fn drift_pct(&self) -> f64 {
+        if self.expected == 0.0 {
+            return 0.0;
+        }
+        (self.actual - self.expected) / self.expected * 100.0
+    }
+}
+
+/// Arrival-rate VUs as `--vu-autoscale` grew and shed them, after warmup
+#[derive(Debug, Clone, Default)]
+pub struct VuScalingStats {
+    /// VUs in the pool now
+    pub allocated: u32,
+    pub peak: u32,
+    pub cap: u32,
+    pub events: Vec<VuScaleEvent>,
+}
+
+impl VuScalingStats {
+    pub fn scale_ups(&self) -> usize {
+        self.events.iter().filter(|e| e.to > e.from).count()
+    }
+
+    pub fn scale_downs(&self) -> usize {
+        self.events.iter().filter(|e| e.to < e.from).count()
+    }
+
+    /// VUs in the pool at `elapsed_ms` into the run
+    pub fn allocated_at(&self, elapsed_ms: u64) -> u32 {
+        let mut vus = self.events.first().map_or(self.allocated, |e| e.from);
+        for event in self
+            .events
+            .iter()
+            .take_while(|e| e.elapsed_ms <= elapsed_ms)
+        {
+            vus = event.to;
+        }
+        vus
+    }
+}
+
+/// One change in the VU pool size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VuScaleEvent {
+    pub elapsed_ms: u64,
+    pub from: u32,
+    pub to: u32,
+}
+
+/// Duplicated requests and whether both copies got the same response
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DuplicateStats {
+    pub pairs: u64,
+    pub consistent: u64,
+    /// Copies answered with different status codes
+    pub status_mismatch: u64,
+    /// Same status, different body
+    pub body_mismatch: u64,
+    /// A copy failed without a response, so there was nothing to compare
+    pub incomplete: u64,
+}
+
+impl DuplicateStats {
+    pub fn inconsistent(&self) -> u64 {
+        self.status_mismatch + self.body_mismatch
+    }
+}
+
+/// Conditional requests and how they were answered (`--conditional-requests`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RevalidationStats {
+    /// Requests sent with If-None-Match or If-Modified-Since
+    pub conditional: u64,
+    /// 304 responses
+    pub not_modified: u64,
+    /// 2xx responses, conditional or not
+    pub full_fetches: u64,
+    pub not_modified_mean_us: u64,
+    pub full_fetch_mean_us: u64,
+}
+
+impl RevalidationStats {
+    /// Share of conditional requests answered with a 304
+    pub fn not_modified_ratio(&self) -> f64 {
+        if self.conditional == 0 {
+            0.0
+        } else {
+            self.not_modified as f64 / self.conditional as f64
+        }
+    }
+}
+
+/// A stretch where every request failed and the run paused (`--pause-on-errors`)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OutageWindow {
+    /// Since the run started, from the first failure
+    pub start: Duration,
+    /// Until the health check passed, or until now if it hasn't
+    pub duration: Duration,
+    pub health_checks: u32,
+    pub recovered: bool,
+}
+
+/// Availability over a failover drill (`--failover`), by timeline bucket
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FailoverStats {
+    /// Timeline bucket width
+    pub interval: Duration,
+    /// Share of requests that succeeded in each bucket, 0 where none completed
+    pub availability: Vec<f64>,
+    pub incidents: Vec<FailoverIncident>,
+}
+
+impl FailoverStats {
+    /// Time the target was down for, across incidents
+    pub fn downtime(&self) -> Duration {
+        self.incidents.iter().map(|i| i.downtime).sum()
+    }
+
+    pub fn longest_burst(&self) -> Duration {
+        self.incidents
+            .iter()
+            .map(|i| i.error_burst)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Share of buckets without a failure
+    pub fn uptime_ratio(&self) -> f64 {
+        if self.availability.is_empty() {
+            return 1.0;
+        }
+        let clean = self.availability.iter().filter(|&&a| a >= 1.0).count();
+        clean as f64 / self.availability.len() as f64
+    }
+}
+
+/// A run of timeline buckets with failures in them (`--failover`)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FailoverIncident {
+    /// Since the measured run started, at the first failing bucket
+    pub start: Duration,
+    /// Until the first bucket without failures, or the end of the run
+    pub error_burst: Duration,
+    /// Buckets in which most requests failed
+    pub downtime: Duration,
+    /// From the first bucket that was down (or the first failing one, if
+    /// none was) to the first clean one. None if the run
+    /// ended before the target recovered.
+    pub time_to_recovery: Option<Duration>,
+}
+
+/// Bursts sent in burst mode, after warmup
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BurstStats {
+    pub bursts: u64,
+    /// Smallest and largest burst sent
+    pub min_size: u32,
+    pub max_size: u32,
+    /// Time from a burst's first request to its last response
+    pub completion_mean: Duration,
+    pub completion_max: Duration,
+    /// Most requests in flight at once
+    pub max_concurrent: u32,
+}
+
+/// Failures in a row after which a worker is reported as unhealthy
+pub const WORKER_FAILURE_STREAK: u32 = 50;
+
+/// Per-worker request counts, after warmup
+#[derive(Debug, Clone, Default)]
+pub struct WorkerHealthStats {
+    pub workers: u32,
+    /// Fewest and most requests sent by a single worker
+    pub requests_min: u64,
+    pub requests_max: u64,
+    /// Workers that failed `WORKER_FAILURE_STREAK` requests in a row
+    pub unhealthy: Vec<UnhealthyWorker>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UnhealthyWorker {
+    pub id: u32,
+    pub requests: u64,
+    pub failed: u64,
+    /// Failures in a row so far (0 once the worker recovered)
+    pub streak: u32,
+    pub longest_streak: u32,
+    /// `timeout`, `status 401`, ...
+    pub last_error: String,
+}
+
+impl WorkerHealthStats {
+    /// Workers still failing every request
+    pub fn failing(&self) -> impl Iterator<Item = &UnhealthyWorker> {
+        self.unhealthy
+            .iter()
+            .filter(|w| w.streak >= WORKER_FAILURE_STREAK)
+    }
+}
+
+/// An example failure, kept for each error kind or status (`--error-samples`)
+#[derive(Debug, Clone, Default)]
+pub struct FailureSample {
+    /// `timeout`, `status 503`, ...
+    pub kind: String,
+    pub request: Option<String>,
+    pub status: Option<u16>,
+    pub message: Option<String>,
+    pub body: Option<String>,
+    pub latency_us: u64,
+    pub timestamp_ms: u64,
+}
+
+/// One of the slowest requests of a traced run, to look up by trace ID
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TraceSample {
+    pub latency_us: u64,
+    pub trace_id: u128,
+    pub status: Option<u16>,
+    /// When the request started (epoch ms)
+    pub timestamp_ms: u64,
+}
+
+/// QUIC connection statistics aggregated across HTTP/3 connections
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuicStats {
+    pub connections: u64,
+    pub rtt_mean_us: f64,
+    pub rtt_p50_us: u64,
+    pub rtt_p99_us: u64,
+    pub cwnd_mean_bytes: f64,
+    pub sent_packets: u64,
+    pub lost_packets: u64,
+    pub congestion_events: u64,
+    pub zero_rtt: Option<ZeroRttStats>,
+}
+
+impl QuicStats {
+    /// Lost packets as a fraction of packets sent (lost packets get retransmitted)
+    pub fn loss_rate(&self) -> f64 {
+        if self.sent_packets == 0 {
+            return 0.0;
+        }
+        self.lost_packets as f64 / self.sent_packets as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestResult {
+    pub latency_us: u64,
+    pub status: Option<u16>,
+    pub error: Option<ErrorKind>,
+    pub bytes_received: u64,
+    pub body: Option<String>,
+    // Latency correction fields (v1.1)
+    pub scheduled_at_us: Option<u64>, // When request was supposed to start (epoch us)
+    pub started_at_us: Option<u64>,   // When request actually started (epoch us)
+    pub queue_time_us: Option<u64>,   // Time spent waiting for a VU (started - scheduled)
+    // QUIC path stats for HTTP/3 requests
+    pub quic: Option<QuicSample>,
+    // `error.code` from a JSON-RPC error response
+    pub jsonrpc_error: Option<i64>,
+    // Grouped `METHOD /path` for the per-endpoint breakdown
+    pub endpoint: Option<String>,
+    // Name of the `[[target.hosts]]` entry the request went to
+    pub host: Option<String>,
+    // Request body bytes sent, and how long writing a large body took
+    pub bytes_sent: u64,
+    pub upload_us: Option<u64>,
+    // Request line and response excerpt of a failed HTTP request
+    pub failure: Option<Box<FailureDetail>>,
+    // Trace ID sent in the trace context headers (--trace-propagation)
+    pub trace_id: Option<u128>,
+    // Whether the response echoed the request ID sent (--request-id-header)
+    pub id_echo: Option<IdEcho>,
+    // Retry-After of a 429 or 503 response
+    pub retry_after: Option<Duration>,
+    // Response headers, kept along with the body when it is captured
+    pub headers: Option<Box<reqwest::header::HeaderMap>>,
+    // Media type of the response's Content-Type, lowercase without parameters
+    pub content_type: Option<String>,
+}
+
+impl RequestResult {
+    pub fn success(
+        latency_us: u64,
+        status: u16,
+        bytes_received: u64,
+        body: Option<String>,
+    ) -> Self {
+        Self {
+            latency_us,
+            status: Some(status),
+            error: None,
+            bytes_received,
+            body,
+            scheduled_at_us: None,
+            started_at_us: None,
+            queue_time_us: None,
+            quic: None,
+            jsonrpc_error: None,
+            endpoint: None,
+            host: None,
+            bytes_sent: 0,
+            upload_us: None,
+            failure: None,
+            trace_id: None,
+            id_echo: None,
+            retry_after: None,
+            headers: None,
+            content_type: None,
+        }
+    }
+
+    pub fn error(latency_us: u64, kind: ErrorKind) -> Self {
+        Self {
+            latency_us,
+            status: None,
+            error: Some(kind),
+            bytes_received: 0,
+            body: None,
+            scheduled_at_us: None,
+            started_at_us: None,
+            queue_time_us: None,
+            quic: None,
+            jsonrpc_error: None,
+            endpoint: None,
+            host: None,
+            bytes_sent: 0,
+            upload_us: None,
+            failure: None,
+            trace_id: None,
+            id_echo: None,
+            retry_after: None,
+            headers: None,
+            content_type: None,
+        }
+    }
+
+    /// Set latency correction timing info
+    pub fn with_timing(mut self, scheduled_at_us: u64, started_at_us: u64) -> Self {
+        let queue_time = started_at_us.saturating_sub(scheduled_at_us);
+        self.scheduled_at_us = Some(scheduled_at_us);
+        self.started_at_us = Some(started_at_us);
+        self.queue_time_us = Some(queue_time);
+        self
+    }
+
+    /// Get corrected latency (actual server time, excluding queue wait)
+    pub fn corrected_latency_us(&self) -> Option<u64> {
+        self.queue_time_us
+            .map(|q| self.latency_us.saturating_sub(q))
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.error.is_none() && self.status.map(|s| s < 400).unwrap_or(false)
+    }
+
+    #[allow(dead_code)]
+    pub fn is_error(&self) -> bool {
+        self.error.is_some() || self.status.map(|s| s >= 400).unwrap_or(false)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct StatsSnapshot {
+    pub elapsed: Duration,
+    /// Wall clock the measured run started, Unix ms; timeline buckets are
+    /// offsets from it (0 when unknown)
+    pub started_at_ms: u64,
+    pub total_requests: u64,
+    pub successful: u64,
+    pub failed: u64,
+    pub bytes_received: u64,
+    pub bytes_sent: u64,
+
+    pub rolling_rps: f64,
+    /// Network throughput over the last second, in bits/sec
+    pub rolling_rx_bps: f64,
+    pub rolling_tx_bps: f64,
+    pub requests_per_sec: f64,
+    pub error_rate: f64,
+
+    pub latency_min_us: u64,
+    pub latency_max_us: u64,
+    pub latency_mean_us: f64,
+    pub latency_stddev_us: f64,
+    pub latency_p50_us: u64,
+    pub latency_p75_us: u64,
+    pub latency_p90_us: u64,
+    pub latency_p95_us: u64,
+    pub latency_p99_us: u64,
+    pub latency_p999_us: u64,
+    /// The configured percentiles (`percentiles`), in ascending order
+    pub latency_percentiles: Vec<(f64, u64)>,
+    /// Requests at or under each Prometheus histogram bound (ms), cumulative;
+    /// empty unless Prometheus export is on
+    pub latency_buckets: Vec<(f64, u64)>,
+    /// Full latency histogram (µs); only set for `--runner-stream`, so a
+    /// coordinator can merge runners' percentiles exactly
+    pub latency_histogram: Option<Arc<Histogram<u64>>>,
+
+    pub status_codes: HashMap<u16, u64>,
+    pub errors: HashMap<ErrorKind, u64>,
+    // JSON-RPC mode: error responses by `error.code`
+    pub jsonrpc_errors: HashMap<i64, u64>,
+
+    pub timeline: Vec<TimelineBucket>,
+
+    pub check_stats: HashMap<String, CheckStats>,
+    pub overall_check_pass_rate: Option<f64>,
+
+    // Arrival rate metrics
+    pub dropped_iterations: u64,
+    pub vus_active: u32,
+    pub vus_max: u32,
+    pub target_rate: u32, // Target RPS (0 = not in arrival rate mode)
+
+    // Results discarded under the `drop` backpressure policy
+    pub results_dropped: u64,
+
+    // Latency correction metrics (v1.1)
+    pub latency_correction_enabled: bool,
+    pub corrected_latency_min_us: Option<u64>,
+    pub corrected_latency_max_us: Option<u64>,
+    pub corrected_latency_mean_us: Option<f64>,
+    pub corrected_latency_p50_us: Option<u64>,
+    pub corrected_latency_p75_us: Option<u64>,
+    pub corrected_latency_p90_us: Option<u64>,
+    pub corrected_latency_p95_us: Option<u64>,
+    pub corrected_latency_p99_us: Option<u64>,
+    pub corrected_latency_p999_us: Option<u64>,
+    pub queue_time_mean_us: Option<f64>,
+    pub queue_time_p99_us: Option<u64>,
+    pub total_queue_time_us: u64,
+
+    // WebSocket metrics (v1.2)
+    pub is_websocket: bool,
+    pub ws_messages_sent: u64,
+    pub ws_messages_received: u64,
+    pub ws_bytes_sent: u64,
+    pub ws_bytes_received: u64,
+    pub ws_connections_active: u32,
+    pub ws_connections_established: u64,
+    pub ws_connection_errors: u64,
+    pub ws_disconnects: u64,
+    pub ws_messages_per_sec: f64,
+    pub ws_rolling_mps: f64,
+    pub ws_error_rate: f64,
+    pub ws_errors: HashMap<WsErrorKind, u64>,
+    // Message latency (RTT in echo mode)
+    pub ws_latency_min_us: u64,
+    pub ws_latency_max_us: u64,
+    pub ws_latency_mean_us: f64,
+    pub ws_latency_stddev_us: f64,
+    pub ws_latency_p50_us: u64,
+    pub ws_latency_p95_us: u64,
+    pub ws_latency_p99_us: u64,
+    // Connection time
+    pub ws_connect_time_mean_us: f64,
+    pub ws_connect_time_p99_us: u64,
+    // Connection churn (--ws-reconnect-every)
+    pub ws_reconnects: u64,
+    pub ws_reconnect_failures: u64,
+    pub ws_reconnect_time_mean_us: f64,
+    pub ws_reconnect_time_p99_us: u64,
+    // Broadcast fan-out (mode = "broadcast")
+    pub ws_broadcast_expected: u64,
+    pub ws_broadcast_delivered: u64,
+    pub ws_delivery_latency_p50_us: u64,
+    pub ws_delivery_latency_p95_us: u64,
+    pub ws_delivery_latency_p99_us: u64,
+    pub ws_delivery_latency_max_us: u64,
+    // permessage-deflate (--ws-compression); None when never negotiated
+    pub ws_compression: Option<WsWireBytes>,
+    // Server-initiated closes by close code
+    pub ws_close_codes: HashMap<u16, WsCloseStat>,
+
+    // QUIC connection stats (HTTP/3 only)
+    pub quic: Option<QuicStats>,
+
+    // Per-endpoint breakdown, busiest first (empty unless tracked)
+    pub endpoints: Vec<EndpointStats>,
+
+    // Per-host breakdown for `[[target.hosts]]`, busiest first
+    pub hosts: Vec<EndpointStats>,
+
+    // Per-stage breakdown for `[[stages]]`, in the order they ran
+    pub stages: Vec<StageStats>,
+
+    // Request-body upload stats (None when no bodies were sent)
+    pub upload: Option<UploadStats>,
+
+    // Echoed request IDs (None without --request-id-header)
+    pub request_ids: Option<RequestIdStats>,
+
+    // Example failures, grouped by error kind or status
+    pub failure_samples: Vec<FailureSample>,
+
+    // Slowest traced requests, slowest first (--trace-propagation)
+    pub slowest_traces: Vec<TraceSample>,
+
+    // Apdex (None unless apdex_t is set)
+    pub apdex: Option<ApdexStats>,
+
+    // Connection reuse (None when the transport doesn't report it)
+    pub connections: Option<ConnectionStats>,
+
+    // Waits for a connection under --max-connections
+    pub connection_cap: Option<ConnectionCapStats>,
+
+    // Response Content-Types (None when no HTTP responses were seen)
+    pub content_types: Option<ContentTypeStats>,
+
+    // Faults injected under [chaos] (None without it)
+    pub chaos: Option<ChaosStats>,
+
+    // Server throttling honored (None without honor_retry_after)
+    pub throttle: Option<ThrottleStats>,
+
+    // Burst sizes and timings (None outside burst mode)
+    pub bursts: Option<BurstStats>,
+
+    // Extraction attempts and failures, per scenario and name
+    pub extractions: Vec<ExtractionStats>,
+
+    // Requests per weighted scenario against the weights (2+ scenarios)
+    pub scenario_mix: Vec<ScenarioShare>,
+
+    // Duplicated requests and how their responses compared (--duplicate-rate)
+    pub duplicates: Option<DuplicateStats>,
+
+    // Conditional requests and 304s (--conditional-requests)
+    pub revalidation: Option<RevalidationStats>,
+
+    // Outages the run paused through (--pause-on-errors)
+    pub outages: Vec<OutageWindow>,
+
+    // Downtime and recovery by timeline bucket (--failover)
+    pub failover: Option<FailoverStats>,
+
+    // Per-worker request counts and failure streaks (VU modes)
+    pub workers: Option<WorkerHealthStats>,
+
+    // VU pool size changes (None without --vu-autoscale)
+    pub vu_scaling: Option<VuScalingStats>,
+
+    // Detected warmup (None without --auto-warmup, or while still warming up)
+    pub auto_warmup: Option<AutoWarmup>,
+
+    // Last RECENT_WINDOW of the run (None when nothing completed in it)
+    pub recent: Option<RecentStats>,
+
+    // Custom metrics reported by WASM plugins, by name
+    pub plugin_metrics: BTreeMap<String, PluginMetric>,
+}
+
+impl StatsSnapshot {
+    /// Latency at percentile `p`, if it was computed for this run
+    pub fn latency_percentile_us(&self, p: f64) -> Option<u64> {
+        if let Some(&(_, value)) = self.latency_percentiles.iter().find(|(q, _)| *q == p) {
+            return Some(value);
+        }
+        // Snapshots read back from results that predate `percentiles`
+        if !self.latency_percentiles.is_empty() {
+            return None;
+        }
+        match p {
+            50.0 => Some(self.latency_p50_us),
+            75.0 => Some(self.latency_p75_us),
+            90.0 => Some(self.latency_p90_us),
+            95.0 => Some(self.latency_p95_us),
+            99.0 => Some(self.latency_p99_us),
+            99.9 => Some(self.latency_p999_us),
+            _ => None,
+        }
+    }
+
+    /// Failed extractions over all attempts, 0 without any
+    pub fn extraction_failure_rate(&self) -> f64 {
+        let attempts: u64 = self.extractions.iter().map(|e| e.attempts).sum();
+        let failed: u64 = self.extractions.iter().map(|e| e.failed).sum();
+        if attempts == 0 {
+            return 0.0;
+        }
+        failed as f64 / attempts as f64
+    }
+
+    /// Largest drift of a scenario's requests from its weight, in percent
+    pub fn scenario_mix_drift_pct(&self) -> f64 {
+        self.scenario_mix
+            .iter()
+            .map(|share| share.drift_pct().abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Average ingress throughput over the run, in megabits/sec
+    pub fn rx_mbps(&self) -> f64 {
+        megabits_per_sec(self.bytes_received, self.elapsed)
+    }
+
+    /// Average egress throughput over the run, in megabits/sec
+    pub fn tx_mbps(&self) -> f64 {
+        megabits_per_sec(self.bytes_sent, self.elapsed)
+    }
+
+    /// True for pure WebSocket runs and mixed HTTP + WebSocket runs
+    pub fn has_websocket_stats(&self) -> bool {
+        self.is_websocket || self.ws_connections_established > 0 || self.ws_messages_sent > 0
+    }
+
+    /// Fraction of expected broadcast deliveries that never arrived
+    pub fn ws_broadcast_loss_rate(&self) -> f64 {
+        if self.ws_broadcast_expected == 0 {
+            return 0.0;
+        }
+        let delivered = self.ws_broadcast_delivered.min(self.ws_broadcast_expected);
+        1.0 - delivered as f64 / self.ws_broadcast_expected as f64
+    }
+
+    /// Copy the WebSocket stat family from a WS snapshot (mixed HTTP + WS runs)
+    pub fn merge_websocket(&mut self, ws: &StatsSnapshot) {
+        self.ws_messages_sent = ws.ws_messages_sent;
+        self.ws_messages_received = ws.ws_messages_received;
+        self.ws_bytes_sent = ws.ws_bytes_sent;
+        self.ws_bytes_received = ws.ws_bytes_received;
+        self.ws_connections_active = ws.ws_connections_active;
+        self.ws_connections_established = ws.ws_connections_established;
+        self.ws_connection_errors = ws.ws_connection_errors;
+        self.ws_disconnects = ws.ws_disconnects;
+        self.ws_messages_per_sec = ws.ws_messages_per_sec;
+        self.ws_rolling_mps = ws.ws_rolling_mps;
+        self.ws_error_rate = ws.ws_error_rate;
+        self.ws_errors = ws.ws_errors.clone();
+        self.ws_latency_min_us = ws.ws_latency_min_us;
+        self.ws_latency_max_us = ws.ws_latency_max_us;
+        self.ws_latency_mean_us = ws.ws_latency_mean_us;
+        self.ws_latency_stddev_us = ws.ws_latency_stddev_us;
+        self.ws_latency_p50_us = ws.ws_latency_p50_us;
+        self.ws_latency_p95_us = ws.ws_latency_p95_us;
+        self.ws_latency_p99_us = ws.ws_latency_p99_us;
+        self.ws_connect_time_mean_us = ws.ws_connect_time_mean_us;
+        self.ws_connect_time_p99_us = ws.ws_connect_time_p99_us;
+        self.ws_reconnects = ws.ws_reconnects;
+        self.ws_reconnect_failures = ws.ws_reconnect_failures;
+        self.ws_reconnect_time_mean_us = ws.ws_reconnect_time_mean_us;
+        self.ws_reconnect_time_p99_us = ws.ws_reconnect_time_p99_us;
+        self.ws_broadcast_expected = ws.ws_broadcast_expected;
+        self.ws_broadcast_delivered = ws.ws_broadcast_delivered;
+        self.ws_delivery_latency_p50_us = ws.ws_delivery_latency_p50_us;
+        self.ws_delivery_latency_p95_us = ws.ws_delivery_latency_p95_us;
+        self.ws_delivery_latency_p99_us = ws.ws_delivery_latency_p99_us;
+        self.ws_delivery_latency_max_us = ws.ws_delivery_latency_max_us;
+        self.ws_compression = ws.ws_compression;
+        self.ws_close_codes = ws.ws_close_codes.clone();
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimelineBucket {
+    pub elapsed_secs: u32,
+    pub requests: u64,
+    pub errors: u64,
+    /// Bucket start; `elapsed_secs` rounded down, for sub-second buckets
+    #[serde(default)]
+    pub elapsed_ms: u64,
+    #[serde(default)]
+    pub bytes_received: u64,
+    #[serde(default)]
+    pub bytes_sent: u64,
+    #[serde(default)]
+    pub latency_p50_us: u64,
+    #[serde(default)]
+    pub latency_p95_us: u64,
+    #[serde(default)]
+    pub latency_p99_us: u64,
+    /// (passed, total) of each check evaluated in the bucket
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub checks: BTreeMap<String, (u64, u64)>,
+}
+
+impl TimelineBucket {
+    /// Pass rate of all the checks evaluated in the bucket, None without any
+    pub fn check_pass_rate(&self) -> Option<f64> {
+        let (passed, total) = self
+            .checks
+            .values()
+            .fold((0, 0), |(p, t), (passed, total)| (p + passed, t + total));
+        (total > 0).then(|| passed as f64 / total as f64)
+    }
+}
+
+/// Unix ms of the moment `elapsed` ago
+pub fn unix_ms_ago(elapsed: Duration) -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|now| now.saturating_sub(elapsed).as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Throughput in megabits/sec for `bytes` moved over `elapsed`
+pub fn megabits_per_sec(bytes: u64, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        bytes as f64 * 8.0 / secs / 1_000_000.0
+    } else {
+        0.0
+    }
+}
+
+// ============================================================================
+// Extraction (v0.8)
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct Extraction {
+    pub name: String,
+    pub source: ExtractionSource,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExtractionSource {
+    JsonPath(String),     // json:$.access_token
+    Header(String),       // header:X-Request-Id
+    Regex(String, usize), // regex:token=(\w+):1
+    Body,                 // body (entire response)
+}
+
+impl ExtractionSource {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if let Some(path) = s.strip_prefix("json:") {
+            Ok(ExtractionSource::JsonPath(path.to_string()))
+        } else if let Some(header) = s.strip_prefix("header:") {
+            Ok(ExtractionSource::Header(header.to_string()))
+        } else if let Some(rest) = s.strip_prefix("regex:") {
+            // Format: regex:pattern:group
+            let parts: Vec<&str> = rest.rsplitn(2, ':').collect();
+            if parts.len() == 2 {
+                let group: usize = parts[0].parse().map_err(|_| "Invalid regex group number")?;
+                let pattern = parts[1].to_string();
+                Ok(ExtractionSource::Regex(pattern, group))
+            } else {
+                Ok(ExtractionSource::Regex(rest.to_string(), 0))
+            }
+        } else if s == "body" {
+            Ok(ExtractionSource::Body)
+        } else {
+            Err(format!(
+                "Unknown extraction source: '{}'. Expected json:, header:, regex:, or body",
+                s
+            ))
+        }
+    }
+}
+
+/// Where `[csrf]` finds the token in a response to a safe request
+#[derive(Debug, Clone)]
+pub enum CsrfSource {
+    /// `cookie:NAME`, set by the response's Set-Cookie headers
+    Cookie(String),
+    /// `header:NAME`, a response header
+    Header(String),
+    /// `json:` or `regex:`, from the response body
+    Body(ExtractionSource),
+}
+
+impl CsrfSource {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if let Some(name) = s.strip_prefix("cookie:") {
+            return Ok(CsrfSource::Cookie(name.to_string()));
+        }
+        match ExtractionSource::parse(s) {
+            Ok(ExtractionSource::Header(name)) => Ok(CsrfSource::Header(name)),
+            Ok(ExtractionSource::Body) | Err(_) => Err(format!(
+                "Unknown CSRF token source: '{}'. Expected cookie:, header:, json: or regex:",
+                s
+            )),
+            Ok(source) => Ok(CsrfSource::Body(source)),
+        }
+    }
+}
+
+/// `[csrf]`: the token a VU echoes back on its mutating requests
+#[derive(Debug, Clone)]
+pub struct CsrfConfig {
+    pub source: CsrfSource,
+    /// Request header the token is sent in
+    pub header: String,
+}
+
+/// Input streamed from stdin when its file is `-`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StdinInput {
+    /// `--urls-from-file -`
+    Urls,
+    /// `--body-lines -`
+    BodyLines,
+}
+
+/// Lines read from stdin, one per request, until it closes
+#[derive(Debug, Clone)]
+pub struct StdinStream {
+    pub input: StdinInput,
+    /// Line read while loading the config, sent first
+    pub first: String,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub name: String,
+    pub url: String,
+    pub method: reqwest::Method,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+    pub weight: u32,
+    pub extractions: Vec<Extraction>,
+    pub depends_on: Option<String>,
+    pub tags: HashMap<String, String>,
+    /// Proxy for this scenario instead of the run's (`Some("")` = direct)
+    pub proxy: Option<String>,
+    /// Pause after this scenario's requests instead of the run's think time
+    pub think_time: Option<ThinkTime>,
+}
+
+impl Scenario {
+    /// WebSocket scenarios run on dedicated VUs in a mixed HTTP + WS test
+    pub fn is_websocket(&self) -> bool {
+        self.url.starts_with("ws://") || self.url.starts_with("wss://")
+    }
+}
+
+/// A cookie every VU starts with (`--cookie-file`, `[target.cookies]`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeededCookie {
+    pub name: String,
+    pub value: String,
+    /// Host the cookie is sent to
+    pub domain: String,
+    /// Also send it to subdomains of `domain`
+    pub include_subdomains: bool,
+    pub path: String,
+    /// Only send it over HTTPS
+    pub secure: bool,
+}
+
+/// One of several base URLs or virtual hosts a run splits its HTTP requests across
+#[derive(Debug, Clone)]
+pub struct TargetHost {
+    pub name: String,
+    /// Scheme and authority, e.g. `https://eu.example.com` (None = the request's own)
+    pub url: Option<String>,
+    /// Host header sent instead of the URL's, for virtual hosts behind one address
+    pub host_header: Option<String>,
+    pub weight: u32,
+}
+
+/// Rewrites matching URL paths to one endpoint name, e.g. `/users/\d+` -> `/users/{id}`
+#[derive(Debug, Clone)]
+pub struct EndpointGroup {
+    pub pattern: regex_lite::Regex,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadConfig {
+    pub url: String,
+    pub method: reqwest::Method,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+    pub scenarios: Vec<Scenario>,
+    pub concurrency: u32,
+    /// Independent worker runtimes (None = share the main runtime, 0 = one per core)
+    pub threads: Option<u32>,
+    pub duration: Duration,
+    pub max_requests: u64,
+    pub rate: u32,
+    /// Requests `rate` lets through at once after idling (None = one second's worth)
+    pub rate_burst: Option<u32>,
+    pub ramp_up: Duration,
+    pub warmup: Duration,
+    /// End warmup once throughput and latency settle, `warmup` at the latest
+    pub auto_warmup: bool,
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    pub insecure: bool,
+    pub http2: bool,
+    #[cfg(feature = "http3")]
+    pub http3: bool,
+    #[cfg(feature = "http3")]
+    pub http3_0rtt: bool,
+    #[cfg(feature = "http3")]
+    pub protocol_race: Option<ProtocolRace>,
+    #[cfg(feature = "grpc")]
+    pub grpc_service: Option<String>,
+    #[cfg(feature = "grpc")]
+    pub grpc_method: Option<String>,
+    #[cfg(feature = "grpc")]
+    pub body_bytes: Option<Vec<u8>>,
+    #[cfg(feature = "socketio")]
+    pub socketio_event: Option<String>,
+    #[cfg(feature = "socketio")]
+    pub socketio_namespace: String,
+    #[cfg(feature = "mqtt")]
+    pub mqtt_topic: String,
+    #[cfg(feature = "mqtt")]
+    pub mqtt_qos: u8,
+    #[cfg(feature = "mqtt")]
+    pub mqtt_publish_rate: Option<u32>,
+    /// Connections that subscribe; the rest publish
+    #[cfg(feature = "mqtt")]
+    pub mqtt_subscribers: u32,
+    /// Tokenized command templates for redis:// targets
+    #[cfg(feature = "redis")]
+    pub redis_commands: Vec<Vec<String>>,
+    #[cfg(feature = "redis")]
+    pub redis_keyspace: u64,
+    pub cookie_jar: bool,
+    /// Cookies seeded into the cookie jar before the first request
+    pub cookies: Vec<SeededCookie>,
+    /// CSRF token carried from safe requests to mutating ones (`[csrf]`)
+    pub csrf: Option<CsrfConfig>,
+    pub follow_redirects: bool,
+    pub disable_keepalive: bool,
+    pub socket: SocketOptions,
+    pub thresholds: Vec<Threshold>,
+    pub trend_thresholds: Vec<TrendThreshold>,
+    pub checks: Vec<Check>,
+    pub stages: Vec<Stage>,
+    pub think_time: Option<ThinkTime>,
+    pub timeline_interval: Duration,
+    /// Example failures kept per error kind or status
+    pub error_samples: usize,
+    /// Apdex target time T (None = no Apdex)
+    pub apdex_t: Option<Duration>,
+    /// Latency percentiles to compute and report, ascending
+    pub percentiles: Vec<f64>,
+    /// Carry the full latency histogram in snapshots (`--runner-stream`)
+    pub latency_histogram: bool,
+    /// Log requests slower than a threshold to a file (--slow-log)
+    pub slow_log: Option<SlowLogConfig>,
+    /// Client-side faults to inject (`[chaos]`)
+    pub chaos: Option<ChaosConfig>,
+    /// Client network to approximate (`--network-profile`)
+    pub network: Option<NetworkProfile>,
+    /// Back off after 429 and 503 responses, for as long as Retry-After says
+    pub honor_retry_after: bool,
+    /// Requests sent twice with the same idempotency key (`--duplicate-rate`)
+    pub duplicates: Option<DuplicateConfig>,
+    /// Revalidate responses with their ETag and Last-Modified (`--conditional-requests`)
+    pub conditional_requests: bool,
+    /// Pause once every request has failed for this long (`--pause-on-errors`)
+    pub pause_on_errors: Option<Duration>,
+    /// Report availability, downtime and recovery per timeline bucket (`--failover`)
+    pub failover: bool,
+    /// Requests in flight at once, and so HTTP/1.1 connections, across all
+    /// VUs (`--max-connections`)
+    pub max_connections: Option<u32>,
+    /// Media types a successful response may have, `type/*` matching a
+    /// whole type (empty = any)
+    pub expect_content_types: Vec<String>,
+    /// Extra attempts after a transport failure, 5xx or 429 (`--retries`)
+    pub retries: u32,
+    /// Budget for a request across its attempts (`--deadline`)
+    pub deadline: Option<Duration>,
+    /// WASM plugins to load (--plugin), run in order
+    #[cfg(feature = "plugins")]
+    pub plugins: Vec<PathBuf>,
+    pub fail_fast: bool,
+    pub arrival_rate: Option<u32>, // Requests per second
+    pub max_vus: Option<u32>,      // Max concurrent requests
+    /// Production traffic shape the rate stages replay (`--rate-profile`)
+    pub rate_profile: Option<RateProfile>,
+    /// Grow and shed VUs on demand instead of up to `max_vus` (`--vu-autoscale`)
+    pub vu_autoscale: Option<VuAutoscale>,
+    pub latency_correction: bool, // Enable latency correction (auto for arrival_rate)
+    /// What workers do when the result channel is full
+    pub backpressure: BackpressurePolicy,
+    // WebSocket options
+    pub ws_mode: WsMode,
+    pub ws_message_interval: Duration,
+    /// Subprotocols requested via Sec-WebSocket-Protocol
+    pub ws_subprotocols: Vec<String>,
+    /// Close and re-establish each connection after this long (churn testing)
+    pub ws_reconnect_every: Option<Duration>,
+    /// Offer permessage-deflate compression on the handshake
+    pub ws_compression: bool,
+    /// Broadcast mode: number of publisher connections (rest subscribe)
+    pub ws_publishers: u32,
+    /// Response matcher for tcp:// and udp:// targets (None = don't wait)
+    pub raw_expect: Option<RawExpect>,
+    /// JSON-RPC mode: method wrapped into the body envelope; responses with an
+    /// `error` member count as failures
+    pub jsonrpc_method: Option<String>,
+    // Proxy and auth options (v1.2)
+    pub proxy: Option<String>,
+    /// Proxies requests are spread over (--proxy-list), instead of `proxy`
+    pub proxy_list: Vec<String>,
+    pub proxy_rotation: ProxyRotation,
+    pub basic_auth: Option<(String, Option<String>)>, // (username, optional password)
+    // mTLS options (v1.2)
+    pub client_cert: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+    pub ca_cert: Option<PathBuf>,
+    /// Client certificates dealt out to the VUs (`--client-cert-pool`)
+    pub client_cert_pool: Vec<ClientIdentity>,
+    /// TLS server name sent instead of the URL's host (`--sni`)
+    pub sni: Option<String>,
+    pub tls_backend: TlsBackend,
+    pub trace_propagation: Option<TracePropagation>,
+    /// Compress request bodies and send them with `Content-Encoding`
+    pub compress_body: Option<BodyCompression>,
+    /// Time DNS, TCP connects or TLS handshakes instead of requests (`--probe`)
+    pub probe: Option<ProbeKind>,
+    /// Header to send a unique ID in and expect back in the response
+    pub request_id_header: Option<String>,
+    /// Slowest traced requests kept for the results
+    pub trace_slowest: usize,
+    // Multipart form data (v1.2)
+    pub form_fields: Vec<FormField>,
+    // v1.3 features
+    /// Generate random URLs from regex pattern
+    pub rand_regex_url: Option<String>,
+    /// Seed for the workers' random choices (None = different every run)
+    pub seed: Option<u64>,
+    /// URLs loaded from file (round-robin)
+    pub url_list: Option<Vec<String>>,
+    /// Body lines loaded from file (round-robin)
+    pub body_lines: Option<Vec<String>>,
+    /// URLs or body lines streamed from stdin instead of a file
+    pub stdin: Option<StdinStream>,
+    /// DNS override (host, socket_addr)
+    pub connect_to: Option<(String, std::net::SocketAddr)>,
+    /// Burst mode configuration
+    pub burst_config: Option<BurstConfig>,
+    /// SQLite database path for logging snapshots
+    pub db_url: Option<PathBuf>,
+    /// Prometheus metrics export configuration
+    pub prometheus: Option<PrometheusConfig>,
+    /// Path rewrites applied before per-endpoint stats are bucketed
+    pub endpoint_groups: Vec<EndpointGroup>,
+    /// Weighted base URLs requests are split across (empty = just `url`)
+    pub hosts: Vec<TargetHost>,
+}
+
+impl LoadConfig {
+    /// Requests may carry a Host header other than their URL's authority,
+    /// which only HTTP/1.1 sends as the authority
+    pub fn overrides_host(&self) -> bool {
+        self.sni.is_some() || self.hosts.iter().any(|h| h.host_header.is_some())
+    }
+}
+
+/// Burst mode configuration - send N requests, wait, repeat
+#[derive(Debug, Clone)]
+pub struct BurstConfig {
+    pub requests_per_burst: u32,
+    pub delay_between_bursts: Duration,
+    /// Requests per burst ramp linearly from `requests_per_burst` to this by the end of the run
+    pub ramp_to: Option<u32>,
+    /// Each delay is drawn uniformly from `delay_between_bursts` ± this
+    pub jitter: Duration,
+}
+
+/// Prometheus metrics export configuration
+#[derive(Debug, Clone)]
+pub struct PrometheusConfig {
+    pub export: PrometheusExport,
+    /// `instance` label (defaults to the target URL); also the Pushgateway grouping key
+    pub instance: Option<String>,
+    /// Extra const labels on every metric, e.g. run id, environment, git sha
+    pub labels: Vec<(String, String)>,
+    /// Upper bounds (ms) of the `kaioken_latency_ms` histogram, ascending
+    pub buckets: Vec<f64>,
+}
+
+/// Default `kaioken_latency_ms` histogram buckets (ms)
+pub const DEFAULT_PROMETHEUS_BUCKETS: [f64; 14] = [
+    1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0, 30000.0,
+];
+
+/// Where Prometheus metrics go
+#[derive(Debug, Clone)]
+pub enum PrometheusExport {
+    /// Push metrics to a Pushgateway URL
+    Pushgateway { url: String },
+    /// Expose metrics on a local HTTP endpoint
+    Endpoint { port: u16 },
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            method: reqwest::Method::GET,
+            headers: Vec::new(),
+            body: None,
+            scenarios: Vec::new(),
+            concurrency: 50,
+            threads: None,
+            duration: Duration::from_secs(10),
+            max_requests: 0,
+            rate: 0,
+            rate_burst: None,
+            ramp_up: Duration::ZERO,
+            warmup: Duration::ZERO,
+            auto_warmup: false,
+            timeout: Duration::from_secs(5),
+            connect_timeout: Duration::from_secs(2),
+            insecure: false,
+            http2: false,
+            #[cfg(feature = "http3")]
+            http3: false,
+            #[cfg(feature = "http3")]
+            http3_0rtt: false,
+            #[cfg(feature = "http3")]
+            protocol_race: None,
+            #[cfg(feature = "grpc")]
+            grpc_service: None,
+            #[cfg(feature = "grpc")]
+            grpc_method: None,
+            #[cfg(feature = "grpc")]
+            body_bytes: None,
+            #[cfg(feature = "socketio")]
+            socketio_event: None,
+            #[cfg(feature = "socketio")]
+            socketio_namespace: "/".to_string(),
+            #[cfg(feature = "mqtt")]
+            mqtt_topic: "kaioken/${PUBLISHER}".to_string(),
+            #[cfg(feature = "mqtt")]
+            mqtt_qos: 0,
+            #[cfg(feature = "mqtt")]
+            mqtt_publish_rate: None,
+            #[cfg(feature = "mqtt")]
+            mqtt_subscribers: 0,
+            #[cfg(feature = "redis")]
+            redis_commands: vec![vec!["PING".to_string()]],
+            #[cfg(feature = "redis")]
+            redis_keyspace: 10000,
+            cookie_jar: false,
+            cookies: Vec::new(),
+            csrf: None,
+            follow_redirects: true,
+            disable_keepalive: false,
+            socket: SocketOptions::default(),
+            thresholds: Vec::new(),
+            trend_thresholds: Vec::new(),
+            checks: Vec::new(),
+            stages: Vec::new(),
+            think_time: None,
+            timeline_interval: Duration::from_secs(1),
+            error_samples: 3,
+            apdex_t: None,
+            percentiles: DEFAULT_PERCENTILES.to_vec(),
+            latency_histogram: false,
+            slow_log: None,
+            chaos: None,
+            network: None,
+            honor_retry_after: false,
+            duplicates: None,
+            conditional_requests: false,
+            pause_on_errors: None,
+            failover: false,
+            max_connections: None,
+            expect_content_types: Vec::new(),
+            retries: 0,
+            deadline: None,
+            #[cfg(feature = "plugins")]
+            plugins: Vec::new(),
+            fail_fast: false,
+            arrival_rate: None,
+            max_vus: None,
+            vu_autoscale: None,
+            rate_profile: None,
+            latency_correction: false,
+            backpressure: BackpressurePolicy::default(),
+            ws_mode: WsMode::default(),
+            ws_message_interval: Duration::from_millis(100),
+            ws_subprotocols: Vec::new(),
+            ws_reconnect_every: None,
+            ws_compression: false,
+            ws_publishers: 1,
+            raw_expect: None,
+            jsonrpc_method: None,
+            proxy: None,
+            proxy_list: Vec::new(),
+            proxy_rotation: ProxyRotation::Vu,
+            basic_auth: None,
+            client_cert: None,
+            client_key: None,
+            ca_cert: None,
+            client_cert_pool: Vec::new(),
+            sni: None,
+            tls_backend: TlsBackend::default(),
+            trace_propagation: None,
+            compress_body: None,
+            probe: None,
+            request_id_header: None,
+            trace_slowest: 10,
+            form_fields: Vec::new(),
+            rand_regex_url: None,
+            url_list: None,
+            body_lines: None,
+            stdin: None,
+            seed: None,
+            connect_to: None,
+            burst_config: None,
+            db_url: None,
+            prometheus: None,
+            endpoint_groups: Vec::new(),
+            hosts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunPhase {
+    Warmup,
+    Running,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunState {
+    Initializing,
+    Running,
+    Paused,
+    Stopping,
+    Completed,
+    Cancelled,
+    Error,
+}
+
+impl RunState {
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            RunState::Completed | RunState::Cancelled | RunState::Error
+        )
+    }
+}
@@ -0,0 +1,306 @@
+use crate::types::{WsClose, WsErrorKind, WsWireBytes};
+use crate::ws::deflate::{DEFLATE_OFFER, DeflateCounters, DeflateStream};
+use futures_util::{SinkExt, StreamExt};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore, pki_types::ServerName};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, client_async, connect_async,
+    tungstenite::{
+        Error as WsError, Message,
+        client::IntoClientRequest,
+        error::{ProtocolError, TlsError, UrlError},
+        handshake::client::Request,
+        http::{
+            HeaderName, HeaderValue,
+            header::{SEC_WEBSOCKET_EXTENSIONS, SEC_WEBSOCKET_PROTOCOL},
+        },
+    },
+};
+
+pub type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+pub type WsDeflateStream = WebSocketStream<DeflateStream<MaybeTlsStream<TcpStream>>>;
+
+enum Stream {
+    Plain(WsStream),
+    Deflate(WsDeflateStream),
+}
+
+impl Stream {
+    async fn send(&mut self, message: Message) -> Result<(), WsError> {
+        match self {
+            Stream::Plain(s) => s.send(message).await,
+            Stream::Deflate(s) => s.send(message).await,
+        }
+    }
+
+    async fn next(&mut self) -> Option<Result<Message, WsError>> {
+        match self {
+            Stream::Plain(s) => s.next().await,
+            Stream::Deflate(s) => s.next().await,
+        }
+    }
+
+    async fn close(&mut self) -> Result<(), WsError> {
+        match self {
+            Stream::Plain(s) => s.close(None).await,
+            Stream::Deflate(s) => s.close(None).await,
+        }
+    }
+}
+
+pub struct WsConnection {
+    stream: Stream,
+    deflate: Option<Arc<DeflateCounters>>,
+    close: Option<WsClose>,
+    pub connect_time_us: u64,
+}
+
+impl WsConnection {
+    pub fn new(stream: WsStream, connect_time_us: u64) -> Self {
+        Self {
+            stream: Stream::Plain(stream),
+            deflate: None,
+            close: None,
+            connect_time_us,
+        }
+    }
+
+    fn with_deflate(
+        stream: WsDeflateStream,
+        counters: Arc<DeflateCounters>,
+        connect_time_us: u64,
+    ) -> Self {
+        Self {
+            stream: Stream::Deflate(stream),
+            deflate: Some(counters),
+            close: None,
+            connect_time_us,
+        }
+    }
+
+    /// Compressed vs raw bytes since the last call, if the server accepted
+    /// permessage-deflate on this connection
+    pub fn take_wire_bytes(&self) -> Option<WsWireBytes> {
+        let counters = self.deflate.as_ref()?;
+        if !counters.negotiated.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(WsWireBytes {
+            raw_sent: counters.raw_sent.swap(0, Ordering::Relaxed),
+            wire_sent: counters.wire_sent.swap(0, Ordering::Relaxed),
+            raw_received: counters.raw_received.swap(0, Ordering::Relaxed),
+            wire_received: counters.wire_received.swap(0, Ordering::Relaxed),
+        })
+    }
+
+    /// How the server closed the connection, once `receive` has seen it go away
+    pub fn take_close(&mut self) -> Option<WsClose> {
+        self.close.take()
+    }
+
+    pub async fn send(&mut self, message: &str) -> Result<(), WsErrorKind> {
+        self.stream
+            .send(Message::Text(message.into()))
+            .await
+            .map_err(|e| ws_error_to_kind(&e))
+    }
+
+    pub async fn receive(&mut self, timeout: Duration) -> Result<String, WsErrorKind> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(WsErrorKind::Timeout);
+            }
+
+            match tokio::time::timeout(remaining, self.stream.next()).await {
+                Ok(Some(Ok(msg))) => match msg {
+                    Message::Text(text) => return Ok(text.to_string()),
+                    Message::Binary(data) => return Ok(String::from_utf8_lossy(&data).to_string()),
+                    Message::Close(frame) => {
+                        self.close = Some(match frame {
+                            Some(frame) => WsClose {
+                                code: frame.code.into(),
+                                reason: frame.reason.to_string(),
+                            },
+                            None => WsClose {
+                                code: 1005,
+                                reason: String::new(),
+                            },
+                        });
+                        return Err(WsErrorKind::ConnectionClosed);
+                    }
+                    // Skip control frames, continue loop
+                    Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => continue,
+                },
+                Ok(Some(Err(e))) => {
+                    if matches!(
+                        e,
+                        WsError::Protocol(ProtocolError::ResetWithoutClosingHandshake)
+                    ) {
+                        self.close = Some(WsClose::abnormal());
+                    }
+                    return Err(ws_error_to_kind(&e));
+                }
+                Ok(None) => {
+                    self.close = Some(WsClose::abnormal());
+                    return Err(WsErrorKind::ConnectionClosed);
+                }
+                Err(_) => return Err(WsErrorKind::Timeout),
+            }
+        }
+    }
+
+    pub async fn close(mut self) -> Result<(), WsErrorKind> {
+        self.stream.close().await.map_err(|e| ws_error_to_kind(&e))
+    }
+}
+
+/// Establish a new WebSocket connection, sending custom headers and
+/// `Sec-WebSocket-Protocol` values with the upgrade request. With
+/// `compression`, permessage-deflate is offered to the server.
+pub async fn connect(
+    url: &str,
+    headers: &[(String, String)],
+    subprotocols: &[String],
+    compression: bool,
+    timeout: Duration,
+) -> Result<WsConnection, WsErrorKind> {
+    let mut request = handshake_request(url, headers, subprotocols)?;
+
+    let start = Instant::now();
+
+    if compression {
+        request.headers_mut().insert(
+            SEC_WEBSOCKET_EXTENSIONS,
+            HeaderValue::from_static(DEFLATE_OFFER),
+        );
+        let result = tokio::time::timeout(timeout, connect_deflate(request)).await;
+        return match result {
+            Ok(Ok((stream, counters))) => {
+                let connect_time_us = start.elapsed().as_micros() as u64;
+                Ok(WsConnection::with_deflate(
+                    stream,
+                    counters,
+                    connect_time_us,
+                ))
+            }
+            Ok(Err(e)) => Err(ws_error_to_kind(&e)),
+            Err(_) => Err(WsErrorKind::Timeout),
+        };
+    }
+
+    let result = tokio::time::timeout(timeout, connect_async(request)).await;
+
+    match result {
+        Ok(Ok((stream, _response))) => {
+            let connect_time_us = start.elapsed().as_micros() as u64;
+            Ok(WsConnection::new(stream, connect_time_us))
+        }
+        Ok(Err(e)) => Err(ws_error_to_kind(&e)),
+        Err(_) => Err(WsErrorKind::Timeout),
+    }
+}
+
+/// Connect with a `DeflateStream` between the WebSocket codec and the
+/// (possibly TLS) socket, since the compression has to see plaintext frames
+async fn connect_deflate(
+    request: Request,
+) -> Result<(WsDeflateStream, Arc<DeflateCounters>), WsError> {
+    let uri = request.uri();
+    let tls = match uri.scheme_str() {
+        Some("wss") => true,
+        Some("ws") => false,
+        _ => return Err(WsError::Url(UrlError::UnsupportedUrlScheme)),
+    };
+    let host = uri
+        .host()
+        .ok_or(WsError::Url(UrlError::NoHostName))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = uri.port_u16().unwrap_or(if tls { 443 } else { 80 });
+
+    let socket = TcpStream::connect((host.as_str(), port)).await?;
+    let socket = if tls {
+        let server_name =
+            ServerName::try_from(host).map_err(|_| WsError::Tls(TlsError::InvalidDnsName))?;
+        let connector = TlsConnector::from(tls_config());
+        MaybeTlsStream::Rustls(connector.connect(server_name, socket).await?)
+    } else {
+        MaybeTlsStream::Plain(socket)
+    };
+
+    let counters = Arc::new(DeflateCounters::default());
+    let (stream, _response) =
+        client_async(request, DeflateStream::new(socket, counters.clone())).await?;
+    Ok((stream, counters))
+}
+
+fn tls_config() -> Arc<ClientConfig> {
+    static CONFIG: OnceLock<Arc<ClientConfig>> = OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            let mut roots = RootCertStore::empty();
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+            Arc::new(
+                ClientConfig::builder()
+                    .with_root_certificates(roots)
+                    .with_no_client_auth(),
+            )
+        })
+        .clone()
+}
+
+/// Build the upgrade request with custom headers and subprotocols
+pub fn handshake_request(
+    url: &str,
+    headers: &[(String, String)],
+    subprotocols: &[String],
+) -> Result<Request, WsErrorKind> {
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| ws_error_to_kind(&e))?;
+
+    for (name, value) in headers {
+        let name =
+            HeaderName::from_bytes(name.as_bytes()).map_err(|_| WsErrorKind::HandshakeFailed)?;
+        let value = HeaderValue::from_str(value).map_err(|_| WsErrorKind::HandshakeFailed)?;
+        request.headers_mut().append(name, value);
+    }
+
+    if !subprotocols.is_empty() {
+        let value = HeaderValue::from_str(&subprotocols.join(", "))
+            .map_err(|_| WsErrorKind::HandshakeFailed)?;
+        request.headers_mut().insert(SEC_WEBSOCKET_PROTOCOL, value);
+    }
+
+    Ok(request)
+}
+
+pub fn ws_error_to_kind(err: &WsError) -> WsErrorKind {
+    match err {
+        WsError::ConnectionClosed => WsErrorKind::ConnectionClosed,
+        WsError::AlreadyClosed => WsErrorKind::ConnectionClosed,
+        WsError::Io(io_err) => {
+            let msg = io_err.to_string().to_lowercase();
+            if msg.contains("refused") {
+                WsErrorKind::ConnectFailed
+            } else if msg.contains("tls") || msg.contains("certificate") {
+                WsErrorKind::Tls
+            } else {
+                WsErrorKind::ConnectFailed
+            }
+        }
+        WsError::Tls(_) => WsErrorKind::Tls,
+        WsError::Protocol(_) => WsErrorKind::ProtocolError,
+        WsError::Http(_) | WsError::HttpFormat(_) => WsErrorKind::HandshakeFailed,
+        WsError::Url(_) => WsErrorKind::ConnectFailed,
+        _ => WsErrorKind::Other,
+    }
+}
@@ -0,0 +1,566 @@
+//! permessage-deflate (RFC 7692) support.
+//!
+//! tungstenite rejects frames with RSV1 set, so compression happens in a stream
+//! adapter between the WebSocket codec and the socket. `DeflateStream` watches
+//! the upgrade response for the negotiated extension, then compresses outgoing
+//! data frames and inflates compressed frames from the server before tungstenite
+//! sees them, counting raw and on-the-wire payload bytes along the way.
+
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress, Status};
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Value sent in `Sec-WebSocket-Extensions` when compression is requested
+pub const DEFLATE_OFFER: &str = "permessage-deflate";
+
+/// Trailer removed from each compressed message (RFC 7692 section 7.2.1)
+const DEFLATE_TRAILER: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+
+/// Stop accepting writes while this much transformed data waits for the socket
+const MAX_PENDING_WRITE: usize = 64 * 1024;
+
+/// Raw vs compressed payload bytes for one connection
+#[derive(Debug, Default)]
+pub struct DeflateCounters {
+    pub negotiated: AtomicBool,
+    pub raw_sent: AtomicU64,
+    pub wire_sent: AtomicU64,
+    pub raw_received: AtomicU64,
+    pub wire_received: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FrameHeader {
+    fin: bool,
+    rsv1: bool,
+    opcode: u8,
+    mask: Option<[u8; 4]>,
+    len: usize,
+}
+
+impl FrameHeader {
+    /// Parse a frame header, returning it with its encoded length
+    fn parse(buf: &[u8]) -> Option<(Self, usize)> {
+        if buf.len() < 2 {
+            return None;
+        }
+        let fin = buf[0] & 0x80 != 0;
+        let rsv1 = buf[0] & 0x40 != 0;
+        let opcode = buf[0] & 0x0f;
+        let masked = buf[1] & 0x80 != 0;
+
+        let (len, mut pos) = match buf[1] & 0x7f {
+            126 => {
+                if buf.len() < 4 {
+                    return None;
+                }
+                (u16::from_be_bytes([buf[2], buf[3]]) as usize, 4)
+            }
+            127 => {
+                if buf.len() < 10 {
+                    return None;
+                }
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&buf[2..10]);
+                (u64::from_be_bytes(bytes) as usize, 10)
+            }
+            n => (n as usize, 2),
+        };
+
+        let mask = if masked {
+            if buf.len() < pos + 4 {
+                return None;
+            }
+            let mut key = [0u8; 4];
+            key.copy_from_slice(&buf[pos..pos + 4]);
+            pos += 4;
+            Some(key)
+        } else {
+            None
+        };
+
+        Some((
+            Self {
+                fin,
+                rsv1,
+                opcode,
+                mask,
+                len,
+            },
+            pos,
+        ))
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        let mut first = self.opcode & 0x0f;
+        if self.fin {
+            first |= 0x80;
+        }
+        if self.rsv1 {
+            first |= 0x40;
+        }
+        out.push(first);
+
+        let mask_bit = if self.mask.is_some() { 0x80 } else { 0 };
+        if self.len < 126 {
+            out.push(mask_bit | self.len as u8);
+        } else if self.len <= u16::MAX as usize {
+            out.push(mask_bit | 126);
+            out.extend_from_slice(&(self.len as u16).to_be_bytes());
+        } else {
+            out.push(mask_bit | 127);
+            out.extend_from_slice(&(self.len as u64).to_be_bytes());
+        }
+
+        if let Some(key) = self.mask {
+            out.extend_from_slice(&key);
+        }
+    }
+}
+
+fn apply_mask(payload: &mut [u8], key: [u8; 4]) {
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= key[i % 4];
+    }
+}
+
+fn invalid_data(err: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+/// Extension parameters accepted by the server, or `None` if it declined
+fn parse_extension_response(response: &[u8]) -> Option<bool> {
+    let text = String::from_utf8_lossy(response);
+    for line in text.split("\r\n").skip(1) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !name.trim().eq_ignore_ascii_case("sec-websocket-extensions") {
+            continue;
+        }
+        for extension in value.split(',') {
+            let mut params = extension.split(';').map(str::trim);
+            if params.next() == Some(DEFLATE_OFFER) {
+                let client_no_context_takeover =
+                    params.any(|p| p.eq_ignore_ascii_case("client_no_context_takeover"));
+                return Some(client_no_context_takeover);
+            }
+        }
+    }
+    None
+}
+
+pub struct DeflateStream<S> {
+    inner: S,
+    counters: Arc<DeflateCounters>,
+    handshake_done: bool,
+    negotiated: bool,
+    client_no_context_takeover: bool,
+    // Read side
+    read_raw: Vec<u8>,
+    read_out: Vec<u8>,
+    read_pos: usize,
+    inflating: bool,
+    decompress: Decompress,
+    // Write side
+    write_raw: Vec<u8>,
+    write_out: Vec<u8>,
+    fragmented_send: bool,
+    compress: Compress,
+}
+
+impl<S> DeflateStream<S> {
+    pub fn new(inner: S, counters: Arc<DeflateCounters>) -> Self {
+        Self {
+            inner,
+            counters,
+            handshake_done: false,
+            negotiated: false,
+            client_no_context_takeover: false,
+            read_raw: Vec::new(),
+            read_out: Vec::new(),
+            read_pos: 0,
+            inflating: false,
+            decompress: Decompress::new(false),
+            write_raw: Vec::new(),
+            write_out: Vec::new(),
+            fragmented_send: false,
+            compress: Compress::new(Compression::default(), false),
+        }
+    }
+
+    /// Move complete units from `read_raw` into `read_out`
+    fn process_read(&mut self) -> io::Result<()> {
+        if !self.handshake_done {
+            let Some(end) = self.read_raw.windows(4).position(|w| w == b"\r\n\r\n") else {
+                return Ok(());
+            };
+            let response: Vec<u8> = self.read_raw.drain(..end + 4).collect();
+            if let Some(client_no_context_takeover) = parse_extension_response(&response) {
+                self.negotiated = true;
+                self.client_no_context_takeover = client_no_context_takeover;
+                self.counters.negotiated.store(true, Ordering::Relaxed);
+            }
+            self.read_out.extend_from_slice(&response);
+            self.handshake_done = true;
+        }
+
+        if !self.negotiated {
+            self.read_out.append(&mut self.read_raw);
+            return Ok(());
+        }
+
+        while let Some((header, header_len)) = FrameHeader::parse(&self.read_raw) {
+            if self.read_raw.len() < header_len + header.len {
+                break;
+            }
+            let frame: Vec<u8> = self.read_raw.drain(..header_len + header.len).collect();
+            let mut payload = frame[header_len..].to_vec();
+            if let Some(key) = header.mask {
+                apply_mask(&mut payload, key);
+            }
+
+            let is_data = matches!(header.opcode, OPCODE_TEXT | OPCODE_BINARY);
+            let compressed = (is_data && header.rsv1)
+                || (header.opcode == OPCODE_CONTINUATION && self.inflating);
+
+            if compressed {
+                self.inflating = !header.fin;
+                let wire_len = payload.len() as u64;
+                if header.fin {
+                    payload.extend_from_slice(&DEFLATE_TRAILER);
+                }
+                payload = self.inflate(&payload)?;
+                self.counters
+                    .wire_received
+                    .fetch_add(wire_len, Ordering::Relaxed);
+                self.counters
+                    .raw_received
+                    .fetch_add(payload.len() as u64, Ordering::Relaxed);
+            } else if is_data || header.opcode == OPCODE_CONTINUATION {
+                let len = payload.len() as u64;
+                self.counters
+                    .wire_received
+                    .fetch_add(len, Ordering::Relaxed);
+                self.counters.raw_received.fetch_add(len, Ordering::Relaxed);
+            }
+
+            FrameHeader {
+                rsv1: false,
+                mask: None,
+                len: payload.len(),
+                ..header
+            }
+            .write(&mut self.read_out);
+            self.read_out.extend_from_slice(&payload);
+        }
+
+        Ok(())
+    }
+
+    /// Move complete frames from `write_raw` into `write_out`, compressing data frames
+    fn process_write(&mut self) -> io::Result<()> {
+        while let Some((header, header_len)) = FrameHeader::parse(&self.write_raw) {
+            if self.write_raw.len() < header_len + header.len {
+                break;
+            }
+            let frame: Vec<u8> = self.write_raw.drain(..header_len + header.len).collect();
+            let is_data = matches!(header.opcode, OPCODE_TEXT | OPCODE_BINARY);
+
+            // Fragmented messages are rare from tungstenite; send them uncompressed
+            if !is_data || !header.fin || self.fragmented_send {
+                if is_data || header.opcode == OPCODE_CONTINUATION {
+                    self.fragmented_send = !header.fin;
+                    let len = header.len as u64;
+                    self.counters.raw_sent.fetch_add(len, Ordering::Relaxed);
+                    self.counters.wire_sent.fetch_add(len, Ordering::Relaxed);
+                }
+                self.write_out.extend_from_slice(&frame);
+                continue;
+            }
+
+            let mut payload = frame[header_len..].to_vec();
+            if let Some(key) = header.mask {
+                apply_mask(&mut payload, key);
+            }
+            let mut compressed = self.deflate(&payload)?;
+            if let Some(key) = header.mask {
+                apply_mask(&mut compressed, key);
+            }
+
+            self.counters
+                .raw_sent
+                .fetch_add(payload.len() as u64, Ordering::Relaxed);
+            self.counters
+                .wire_sent
+                .fetch_add(compressed.len() as u64, Ordering::Relaxed);
+
+            FrameHeader {
+                rsv1: true,
+                len: compressed.len(),
+                ..header
+            }
+            .write(&mut self.write_out);
+            self.write_out.extend_from_slice(&compressed);
+        }
+
+        Ok(())
+    }
+
+    fn inflate(&mut self, input: &[u8]) -> io::Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(input.len() * 4 + 64);
+        let mut consumed = 0;
+        loop {
+            if out.len() == out.capacity() {
+                out.reserve(out.capacity());
+            }
+            let before = self.decompress.total_in();
+            let produced = out.len();
+            let status = self
+                .decompress
+                .decompress_vec(&input[consumed..], &mut out, FlushDecompress::Sync)
+                .map_err(invalid_data)?;
+            let read = (self.decompress.total_in() - before) as usize;
+            consumed += read;
+            if status == Status::StreamEnd {
+                // A final block ends the stream; the next message starts fresh
+                self.decompress.reset(false);
+                break;
+            }
+            if consumed >= input.len() && out.len() < out.capacity() {
+                break;
+            }
+            if read == 0 && out.len() == produced && out.len() < out.capacity() {
+                return Err(invalid_data("permessage-deflate: inflate made no progress"));
+            }
+        }
+        Ok(out)
+    }
+
+    fn deflate(&mut self, input: &[u8]) -> io::Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(input.len() + 64);
+        let mut consumed = 0;
+        loop {
+            if out.len() == out.capacity() {
+                out.reserve(out.capacity());
+            }
+            let before = self.compress.total_in();
+            self.compress
+                .compress_vec(&input[consumed..], &mut out, FlushCompress::Sync)
+                .map_err(invalid_data)?;
+            consumed += (self.compress.total_in() - before) as usize;
+            if consumed >= input.len() && out.len() < out.capacity() {
+                break;
+            }
+        }
+        if out.ends_with(&DEFLATE_TRAILER) {
+            out.truncate(out.len() - DEFLATE_TRAILER.len());
+        }
+        if self.client_no_context_takeover {
+            self.compress.reset();
+        }
+        Ok(out)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> DeflateStream<S> {
+    /// Write pending transformed bytes to the socket
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.write_out.is_empty() {
+            let n = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.write_out))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.write_out.drain(..n);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for DeflateStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.read_pos < this.read_out.len() {
+                let n = buf.remaining().min(this.read_out.len() - this.read_pos);
+                buf.put_slice(&this.read_out[this.read_pos..this.read_pos + n]);
+                this.read_pos += n;
+                if this.read_pos == this.read_out.len() {
+                    this.read_out.clear();
+                    this.read_pos = 0;
+                }
+                return Poll::Ready(Ok(()));
+            }
+
+            let mut chunk = [0u8; 8192];
+            let mut chunk_buf = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk_buf))?;
+            if chunk_buf.filled().is_empty() {
+                // EOF: hand over anything left so the codec can report it
+                this.read_out.append(&mut this.read_raw);
+                if this.read_out.is_empty() {
+                    return Poll::Ready(Ok(()));
+                }
+                continue;
+            }
+            this.read_raw.extend_from_slice(chunk_buf.filled());
+            this.process_read()?;
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for DeflateStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        if !this.handshake_done || !this.negotiated {
+            ready!(this.poll_drain(cx))?;
+            return Pin::new(&mut this.inner).poll_write(cx, buf);
+        }
+
+        if this.write_out.len() >= MAX_PENDING_WRITE {
+            ready!(this.poll_drain(cx))?;
+        }
+        this.write_raw.extend_from_slice(buf);
+        this.process_write()?;
+        // Opportunistically push data out; flush finishes the job
+        if let Poll::Ready(Err(e)) = this.poll_drain(cx) {
+            return Poll::Ready(Err(e));
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(opcode: u8, rsv1: bool, mask: Option<[u8; 4]>, payload: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        FrameHeader {
+            fin: true,
+            rsv1,
+            opcode,
+            mask,
+            len: payload.len(),
+        }
+        .write(&mut out);
+        let mut payload = payload.to_vec();
+        if let Some(key) = mask {
+            apply_mask(&mut payload, key);
+        }
+        out.extend_from_slice(&payload);
+        out
+    }
+
+    fn negotiated_stream() -> DeflateStream<tokio::io::Empty> {
+        let mut stream = DeflateStream::new(tokio::io::empty(), Arc::default());
+        stream.handshake_done = true;
+        stream.negotiated = true;
+        stream
+    }
+
+    #[test]
+    fn test_frame_header_roundtrip() {
+        for len in [0usize, 125, 126, 65535, 65536] {
+            let mut out = Vec::new();
+            let header = FrameHeader {
+                fin: true,
+                rsv1: true,
+                opcode: OPCODE_TEXT,
+                mask: Some([1, 2, 3, 4]),
+                len,
+            };
+            header.write(&mut out);
+            let (parsed, header_len) = FrameHeader::parse(&out).unwrap();
+            assert_eq!(header_len, out.len());
+            assert_eq!(parsed.len, len);
+            assert!(parsed.fin && parsed.rsv1);
+            assert_eq!(parsed.mask, Some([1, 2, 3, 4]));
+        }
+    }
+
+    #[test]
+    fn test_parse_extension_response() {
+        let accepted = b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nSec-WebSocket-Extensions: permessage-deflate; client_no_context_takeover\r\n\r\n";
+        assert_eq!(parse_extension_response(accepted), Some(true));
+
+        let plain = b"HTTP/1.1 101 Switching Protocols\r\nsec-websocket-extensions: permessage-deflate\r\n\r\n";
+        assert_eq!(parse_extension_response(plain), Some(false));
+
+        let declined = b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\r\n";
+        assert_eq!(parse_extension_response(declined), None);
+    }
+
+    #[test]
+    fn test_outgoing_frames_compress_and_inflate() {
+        let message = "hello hello hello hello hello hello hello hello".repeat(10);
+        let mut client = negotiated_stream();
+        client.write_raw = frame(OPCODE_TEXT, false, Some([9, 8, 7, 6]), message.as_bytes());
+        client.process_write().unwrap();
+
+        let (header, header_len) = FrameHeader::parse(&client.write_out).unwrap();
+        assert!(header.rsv1);
+        assert!(header.len < message.len());
+
+        // Feed the compressed payload back as an unmasked server frame
+        let mut payload = client.write_out[header_len..].to_vec();
+        apply_mask(&mut payload, header.mask.unwrap());
+        let mut server = negotiated_stream();
+        server.read_raw = frame(OPCODE_TEXT, true, None, &payload);
+        server.process_read().unwrap();
+
+        let (header, header_len) = FrameHeader::parse(&server.read_out).unwrap();
+        assert!(!header.rsv1);
+        assert_eq!(&server.read_out[header_len..], message.as_bytes());
+
+        let counters = &client.counters;
+        assert_eq!(
+            counters.raw_sent.load(Ordering::Relaxed),
+            message.len() as u64
+        );
+        assert!(counters.wire_sent.load(Ordering::Relaxed) < message.len() as u64);
+        assert_eq!(
+            server.counters.raw_received.load(Ordering::Relaxed),
+            message.len() as u64
+        );
+    }
+
+    #[test]
+    fn test_control_frames_pass_through() {
+        let mut client = negotiated_stream();
+        let ping = frame(0x9, false, Some([1, 1, 1, 1]), b"ping");
+        client.write_raw = ping.clone();
+        client.process_write().unwrap();
+        assert_eq!(client.write_out, ping);
+        assert_eq!(client.counters.raw_sent.load(Ordering::Relaxed), 0);
+    }
+}
@@ -14,10 +14,10 @@ pub async fn execute_ws_message(
 
     // Send the message
     if let Err(e) = conn.send(message).await {
-        return WsMessageResult::error(e);
+        return WsMessageResult::error(e).with_close(conn.take_close());
     }
 
-    match mode {
+    let result = match mode {
         WsMode::Echo => {
             // Wait for response
             match conn.receive(timeout).await {
@@ -30,10 +30,14 @@ pub async fn execute_ws_message(
                 Err(e) => WsMessageResult::error(e),
             }
         }
-        WsMode::FireAndForget => {
+        WsMode::FireAndForget | WsMode::Broadcast => {
             // Don't wait for response
             let latency_us = start.elapsed().as_micros() as u64;
             WsMessageResult::success(latency_us, bytes_sent, 0)
         }
-    }
+    };
+
+    result
+        .with_wire_bytes(conn.take_wire_bytes())
+        .with_close(conn.take_close())
 }
@@ -1,5 +1,8 @@
 mod client;
+mod deflate;
 mod message;
 
 pub use client::{WsConnection, connect};
+#[allow(unused_imports)]
+pub use client::{handshake_request, ws_error_to_kind};
 pub use message::execute_ws_message;
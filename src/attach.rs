@@ -0,0 +1,274 @@
+//! `kaioken attach`: watch a `kaioken serve` run in the local TUI
+//!
+//! The generator box streams the run's status and its result so far
+//! (`GET /api/stream`), the same JSON result runners send their coordinator.
+//! Each frame is turned back into a snapshot, so the TUI draws the remote
+//! run as it would a local one. Quitting detaches; the run carries on.
+
+use crate::cli::AttachArgs;
+use crate::engine::print_threshold_results;
+use crate::output::json::JsonOutput;
+use crate::output::schema::result_from_value;
+use crate::output::{Progress, report_progress};
+use crate::serve::parse_state;
+use crate::tui::App;
+use crate::types::{LoadConfig, RunPhase, RunState, StatsSnapshot};
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+/// How often `--no-tui` prints a progress line
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+struct Status {
+    state: String,
+    duration_secs: f64,
+    #[serde(default)]
+    warmup: bool,
+    live: Live,
+    exit_code: Option<i32>,
+    error: Option<String>,
+}
+
+/// What the result layout doesn't carry: rates over the last few seconds
+#[derive(Deserialize)]
+struct Live {
+    rps: f64,
+    rx_mbps: f64,
+    tx_mbps: f64,
+    #[serde(default)]
+    vus_active: u32,
+    #[serde(default)]
+    vus_max: u32,
+    #[serde(default)]
+    target_rate: u32,
+}
+
+struct Frame {
+    status: Status,
+    result: JsonOutput,
+}
+
+impl Frame {
+    fn parse(data: &str) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        struct Raw {
+            status: Status,
+            result: Value,
+        }
+        let raw: Raw = serde_json::from_str(data).map_err(|e| e.to_string())?;
+        let (result, _) = result_from_value(raw.result)?;
+        Ok(Self {
+            status: raw.status,
+            result,
+        })
+    }
+
+    fn state(&self) -> RunState {
+        parse_state(&self.status.state).unwrap_or(RunState::Running)
+    }
+
+    fn phase(&self) -> RunPhase {
+        if self.status.warmup {
+            RunPhase::Warmup
+        } else {
+            RunPhase::Running
+        }
+    }
+
+    fn snapshot(&self) -> StatsSnapshot {
+        let live = &self.status.live;
+        StatsSnapshot {
+            rolling_rps: live.rps,
+            rolling_rx_bps: live.rx_mbps * 1_000_000.0,
+            rolling_tx_bps: live.tx_mbps * 1_000_000.0,
+            vus_active: live.vus_active,
+            vus_max: live.vus_max,
+            target_rate: live.target_rate,
+            ..self.result.to_snapshot()
+        }
+    }
+
+    /// The run is over and this is its final result
+    fn is_final(&self) -> bool {
+        self.status.exit_code.is_some() || self.status.error.is_some()
+    }
+}
+
+/// Server-sent events of `/api/stream`
+struct Frames {
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+    waiting: bool,
+}
+
+impl Frames {
+    /// The next frame; None once the server ends the stream
+    async fn next(&mut self, server: &str) -> Result<Option<Frame>, String> {
+        loop {
+            if let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+                let event: Vec<u8> = self.buffer.drain(..end + 2).collect();
+                let event = String::from_utf8_lossy(&event);
+                if let Some(data) = event.lines().find_map(|l| l.strip_prefix("data: ")) {
+                    return Frame::parse(data)
+                        .map(Some)
+                        .map_err(|e| format!("Unreadable frame from {}: {}", server, e));
+                }
+                // A comment while there's no run to stream yet
+                if !self.waiting {
+                    self.waiting = true;
+                    eprintln!("No run on {} yet; waiting for one to start", server);
+                }
+                continue;
+            }
+            match self.response.chunk().await {
+                Ok(Some(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Ok(None) => return Ok(None),
+                Err(e) => return Err(format!("Lost connection to {}: {}", server, e)),
+            }
+        }
+    }
+}
+
+pub async fn run_attach(args: &AttachArgs) -> Result<i32, String> {
+    let server = if args.server.contains("://") {
+        args.server.trim_end_matches('/').to_string()
+    } else {
+        format!("http://{}", args.server)
+    };
+
+    let mut request = reqwest::Client::new().get(format!("{}/api/stream", server));
+    if let Some(ref token) = args.token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect to {}: {}", server, e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body: Value = response
+            .bytes()
+            .await
+            .ok()
+            .and_then(|body| serde_json::from_slice(&body).ok())
+            .unwrap_or_default();
+        return Err(format!(
+            "{} answered {}: {}",
+            server,
+            status,
+            body["error"]
+                .as_str()
+                .unwrap_or("not a kaioken serve instance?")
+        ));
+    }
+
+    let mut frames = Frames {
+        response,
+        buffer: Vec::new(),
+        waiting: false,
+    };
+    let first = frames
+        .next(&server)
+        .await?
+        .ok_or_else(|| format!("{} ended the stream", server))?;
+
+    let config = LoadConfig {
+        duration: Duration::from_secs_f64(first.status.duration_secs.max(0.0)),
+        ..first.result.to_config()
+    };
+    let (snapshot_tx, snapshot_rx) = watch::channel(first.snapshot());
+    let (state_tx, state_rx) = watch::channel(first.state());
+    let (phase_tx, phase_rx) = watch::channel(first.phase());
+    let cancel = CancellationToken::new();
+
+    let reader = {
+        let cancel = cancel.clone();
+        let server = server.clone();
+        tokio::spawn(async move {
+            let mut last = first;
+            while !last.is_final() {
+                match frames.next(&server).await {
+                    Ok(Some(frame)) => {
+                        let _ = snapshot_tx.send(frame.snapshot());
+                        let _ = phase_tx.send(frame.phase());
+                        let _ = state_tx.send(frame.state());
+                        last = frame;
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        cancel.cancel();
+                        return Err(e);
+                    }
+                }
+            }
+            // The TUI stops by itself at the end; not if the server went away
+            if !last.is_final() {
+                cancel.cancel();
+            }
+            Ok(last)
+        })
+    };
+
+    if args.no_tui {
+        let progress = tokio::spawn(report_progress(
+            PROGRESS_INTERVAL,
+            Progress::Lines,
+            snapshot_rx,
+            phase_rx,
+        ));
+        let last = reader.await;
+        progress.abort();
+        return finish(last, &server, args.serious);
+    }
+
+    App::new(
+        config,
+        snapshot_rx,
+        state_rx.clone(),
+        phase_rx,
+        cancel,
+        args.serious,
+        None,
+    )
+    .run()
+    .await
+    .map_err(|e| format!("TUI error: {}", e))?;
+
+    // Quitting before the end only detaches
+    if !reader.is_finished() && !state_rx.borrow().is_terminal() {
+        reader.abort();
+        eprintln!("Detached from {}; the run continues there", server);
+        return Ok(0);
+    }
+    finish(reader.await, &server, args.serious)
+}
+
+/// Print the final result as `kaioken run` would and take its exit code
+fn finish(
+    last: Result<Result<Frame, String>, tokio::task::JoinError>,
+    server: &str,
+    serious: bool,
+) -> Result<i32, String> {
+    let last = last.map_err(|e| format!("Stream reader failed: {}", e))??;
+    if let Some(ref e) = last.status.error {
+        return Err(format!("Run on {} failed: {}", server, e));
+    }
+    let Some(exit_code) = last.status.exit_code else {
+        return Err(format!(
+            "Lost connection to {} before the run finished",
+            server
+        ));
+    };
+
+    crate::print_summary(&last.snapshot(), serious);
+    print_threshold_results(last.result.threshold_results());
+    let check_stats = last.result.check_stats();
+    if !check_stats.is_empty() {
+        crate::print_check_results(&check_stats);
+    }
+    Ok(exit_code)
+}
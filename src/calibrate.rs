@@ -0,0 +1,388 @@
+//! Generator self-calibration (`kaioken calibrate`)
+//!
+//! Runs the real engine against a built-in loopback server that answers
+//! instantly, so whatever ceiling it hits belongs to this machine and config,
+//! not a target. The result is saved and `run` warns when a test asks for more.
+
+use crate::cli::CalibrateArgs;
+use crate::engine::Engine;
+use crate::types::LoadConfig;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// First concurrency step; each following step doubles it
+const START_CONCURRENCY: u32 = 8;
+/// A step must beat the best RPS so far by this much to keep climbing
+const MIN_GAIN: f64 = 1.05;
+/// Steps failing more than this are past what the generator can sustain
+const MAX_ERROR_RATE: f64 = 0.01;
+
+const RESPONSE: &[u8] =
+    b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\ncontent-type: text/plain\r\n\r\nok";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationStep {
+    pub concurrency: u32,
+    pub rps: f64,
+    pub error_rate: f64,
+    pub latency_p99_us: u64,
+    /// Most connections the dummy server saw open at once
+    pub peak_connections: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Calibration {
+    pub version: String,
+    pub measured_at: DateTime<Utc>,
+    pub cores: usize,
+    pub threads: Option<u32>,
+    /// Highest RPS the generator reached
+    pub max_rps: f64,
+    /// Concurrency at which `max_rps` was reached
+    pub best_concurrency: u32,
+    /// Most connections held by a step that stayed under the error budget
+    pub max_connections: u32,
+    /// Whether the next step up failed, making `max_connections` a real limit
+    /// rather than just where RPS stopped improving
+    pub connection_limited: bool,
+    pub steps: Vec<CalibrationStep>,
+}
+
+/// Where calibration results are kept between runs
+pub fn calibration_path() -> Option<PathBuf> {
+    let cache = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))?;
+    Some(cache.join("kaioken").join("calibration.json"))
+}
+
+pub fn load_calibration() -> Option<Calibration> {
+    let content = std::fs::read_to_string(calibration_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_calibration(calibration: &Calibration) -> Result<PathBuf, String> {
+    let path = calibration_path().ok_or("Cannot determine cache directory")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(calibration)
+        .map_err(|e| format!("Failed to serialize calibration: {}", e))?;
+    std::fs::write(&path, json)
+        .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Warnings for a test that asks for more than the saved calibration reached
+pub fn capacity_warnings(config: &LoadConfig) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if !config.url.starts_with("http://") && !config.url.starts_with("https://") {
+        return warnings;
+    }
+    let Some(calibration) = load_calibration() else {
+        return warnings;
+    };
+
+    if let Some(rps) = requested_rps(config)
+        && rps as f64 > calibration.max_rps
+    {
+        warnings.push(format!(
+            "Requested {} RPS exceeds this machine's calibrated ceiling of ~{:.0} RPS; \
+             results may reflect generator saturation rather than the target",
+            rps, calibration.max_rps
+        ));
+    }
+
+    let requested_connections = requested_connections(config);
+    if calibration.connection_limited && requested_connections > calibration.max_connections {
+        warnings.push(format!(
+            "Requested {} connections exceeds the {} this machine held during calibration",
+            requested_connections, calibration.max_connections
+        ));
+    }
+
+    warnings
+}
+
+/// Peak request rate a test asks for, if it is rate-limited at all
+pub fn requested_rps(config: &LoadConfig) -> Option<u32> {
+    let stage_rate = config.stages.iter().filter_map(|s| s.target_rate).max();
+    config
+        .arrival_rate
+        .or(stage_rate)
+        .or((config.rate > 0).then_some(config.rate))
+}
+
+/// Peak number of connections a test will hold open
+pub fn requested_connections(config: &LoadConfig) -> u32 {
+    let open_model =
+        config.arrival_rate.is_some() || config.stages.iter().any(|s| s.target_rate.is_some());
+    if open_model {
+        config.max_vus.unwrap_or(config.concurrency)
+    } else {
+        config
+            .stages
+            .iter()
+            .filter_map(|s| s.target)
+            .max()
+            .unwrap_or(0)
+            .max(config.concurrency)
+    }
+}
+
+pub async fn run_calibrate(args: &CalibrateArgs) -> Result<i32, String> {
+    if args.max_concurrency < START_CONCURRENCY {
+        return Err(format!(
+            "--max-concurrency must be at least {}",
+            START_CONCURRENCY
+        ));
+    }
+
+    let server = DummyServer::start().await?;
+    let url = format!("http://{}/", server.addr);
+
+    if !args.json {
+        eprintln!(
+            "Calibrating against built-in server on {} ({} per step)\n",
+            server.addr,
+            humantime::format_duration(args.duration)
+        );
+        println!(
+            "{:>12} {:>12} {:>10} {:>10} {:>12}",
+            "Concurrency", "RPS", "Errors", "p99", "Connections"
+        );
+    }
+
+    let mut steps: Vec<CalibrationStep> = Vec::new();
+    let mut best: Option<CalibrationStep> = None;
+    let mut max_connections = 0;
+    let mut connection_limited = false;
+    let mut concurrency = START_CONCURRENCY;
+
+    while concurrency <= args.max_concurrency {
+        let step = run_step(&server, &url, concurrency, args).await?;
+        if !args.json {
+            println!(
+                "{:>12} {:>12.0} {:>9.2}% {:>8.2}ms {:>12}",
+                step.concurrency,
+                step.rps,
+                step.error_rate * 100.0,
+                step.latency_p99_us as f64 / 1000.0,
+                step.peak_connections
+            );
+        }
+
+        let failing = step.error_rate > MAX_ERROR_RATE;
+        let improved = best.as_ref().is_none_or(|b| step.rps >= b.rps * MIN_GAIN);
+        if !failing {
+            max_connections = max_connections.max(step.peak_connections);
+            if best.as_ref().is_none_or(|b| step.rps > b.rps) {
+                best = Some(step.clone());
+            }
+        }
+        steps.push(step);
+
+        if failing || !improved {
+            connection_limited = failing;
+            break;
+        }
+        concurrency = concurrency.saturating_mul(2);
+    }
+
+    let best = best.ok_or("Calibration failed: every step exceeded the error budget")?;
+    let calibration = Calibration {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        measured_at: Utc::now(),
+        cores: std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        threads: args.threads,
+        max_rps: best.rps,
+        best_concurrency: best.concurrency,
+        max_connections,
+        connection_limited,
+        steps,
+    };
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&calibration)
+            .map_err(|e| format!("Failed to serialize calibration: {}", e))?;
+        println!("{}", json);
+    } else {
+        println!();
+        println!(
+            "Max RPS:         ~{:.0} (at concurrency {})",
+            calibration.max_rps, calibration.best_concurrency
+        );
+        println!(
+            "Max connections: {}{}",
+            calibration.max_connections,
+            if calibration.connection_limited {
+                ""
+            } else {
+                " (RPS plateaued first; not a hard limit)"
+            }
+        );
+    }
+
+    if !args.no_save {
+        let path = save_calibration(&calibration)?;
+        if !args.json {
+            eprintln!(
+                "\nSaved to {}; `run` will warn when a test asks for more",
+                path.display()
+            );
+        }
+    }
+
+    Ok(0)
+}
+
+async fn run_step(
+    server: &DummyServer,
+    url: &str,
+    concurrency: u32,
+    args: &CalibrateArgs,
+) -> Result<CalibrationStep, String> {
+    server.reset_peak();
+    let config = LoadConfig {
+        url: url.to_string(),
+        concurrency,
+        threads: args.threads,
+        duration: args.duration,
+        ..LoadConfig::default()
+    };
+    let stats = Engine::new(config).run().await?;
+
+    Ok(CalibrationStep {
+        concurrency,
+        rps: stats.requests_per_sec(),
+        error_rate: stats.error_rate(),
+        latency_p99_us: stats.latency_percentile(99.0),
+        peak_connections: server.peak() as u32,
+    })
+}
+
+/// Minimal HTTP/1.1 keep-alive server that answers every request with a
+/// fixed 200, tracking how many connections are open
+struct DummyServer {
+    addr: std::net::SocketAddr,
+    connections: Arc<ConnectionCount>,
+    handle: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct ConnectionCount {
+    open: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl DummyServer {
+    async fn start() -> Result<Self, String> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| format!("Failed to start calibration server: {}", e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to start calibration server: {}", e))?;
+        let connections = Arc::new(ConnectionCount::default());
+
+        let counts = connections.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    // Out of file descriptors; let connections close
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    continue;
+                };
+                let counts = counts.clone();
+                tokio::spawn(async move {
+                    let open = counts.open.fetch_add(1, Ordering::Relaxed) + 1;
+                    counts.peak.fetch_max(open, Ordering::Relaxed);
+                    serve(stream).await;
+                    counts.open.fetch_sub(1, Ordering::Relaxed);
+                });
+            }
+        });
+
+        Ok(Self {
+            addr,
+            connections,
+            handle,
+        })
+    }
+
+    fn reset_peak(&self) {
+        let open = self.connections.open.load(Ordering::Relaxed);
+        self.connections.peak.store(open, Ordering::Relaxed);
+    }
+
+    fn peak(&self) -> usize {
+        self.connections.peak.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for DummyServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn serve(mut stream: TcpStream) {
+    let _ = stream.set_nodelay(true);
+    let mut buf = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 8192];
+    loop {
+        while let Some(len) = request_len(&buf) {
+            buf.drain(..len);
+            if stream.write_all(RESPONSE).await.is_err() {
+                return;
+            }
+        }
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    }
+}
+
+/// Length of the first complete request in `buf` (head plus Content-Length body)
+fn request_len(buf: &[u8]) -> Option<usize> {
+    let head = buf.windows(4).position(|w| w == b"\r\n\r\n")? + 4;
+    let body = std::str::from_utf8(&buf[..head])
+        .ok()?
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    (buf.len() >= head + body).then_some(head + body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_request_boundaries() {
+        assert_eq!(request_len(b"GET / HTTP/1.1\r\nHost: x"), None);
+        assert_eq!(
+            request_len(b"GET / HTTP/1.1\r\nHost: x\r\n\r\nGET"),
+            Some(27)
+        );
+
+        let post = b"POST / HTTP/1.1\r\nContent-Length: 4\r\n\r\nab";
+        assert_eq!(request_len(post), None);
+        let post = b"POST / HTTP/1.1\r\ncontent-length: 4\r\n\r\nabcd";
+        assert_eq!(request_len(post), Some(post.len()));
+    }
+}
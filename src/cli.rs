@@ -56,9 +56,42 @@ pub enum Commands {
     /// Generate a starter config file
     Init(InitArgs),
 
+    /// Lint config files without running them (deeper than --dry-run)
+    Validate(ValidateArgs),
+
     /// Import scenarios from external formats (HAR, Postman, OpenAPI)
     Import(ImportArgs),
 
+    /// Measure how much load this machine can generate
+    Calibrate(CalibrateArgs),
+
+    /// Check OS limits and target DNS before a run
+    Doctor(DoctorArgs),
+
+    /// Serve a web dashboard and REST API for driving load tests remotely
+    Serve(ServeArgs),
+
+    /// Record traffic through a proxy into a config with weighted scenarios
+    Record(RecordArgs),
+
+    /// Browse past runs stored with --db-url
+    History(HistoryArgs),
+
+    /// Control a running test started with --control-port
+    Ctl(CtlArgs),
+
+    /// Watch a run on a `kaioken serve` box in the local TUI
+    Attach(AttachArgs),
+
+    /// Re-render a saved JSON result in another format
+    Report(ReportArgs),
+
+    /// Generate Kubernetes manifests that run a config inside a cluster
+    K8s(K8sArgs),
+
+    /// Print the JSON Schema of result files written with -o results.json
+    Schema,
+
     /// Generate shell completions
     Completions(CompletionsArgs),
 
@@ -72,7 +105,7 @@ impl Default for Commands {
     }
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 pub struct RunArgs {
     /// Target URL to load test
     #[arg(required_unless_present_any = ["config", "rand_regex_url", "urls_from_file"])]
@@ -86,10 +119,18 @@ pub struct RunArgs {
     #[arg(long, value_name = "FILE", conflicts_with = "rand_regex_url")]
     pub urls_from_file: Option<PathBuf>,
 
+    /// Group URLs for per-endpoint stats (REGEX=NAME, repeatable, e.g. '/users/[^/]+=/users/{user}')
+    #[arg(long = "endpoint-group", value_name = "REGEX=NAME")]
+    pub endpoint_groups: Vec<String>,
+
     /// Number of concurrent workers
     #[arg(short = 'c', long, default_value = "50")]
     pub concurrency: u32,
 
+    /// Run HTTP workers on N independent runtimes, each pinned to a core with its own client (0 = one per core)
+    #[arg(long, value_name = "N")]
+    pub threads: Option<u32>,
+
     /// Test duration (e.g., 10s, 1m, 30s)
     #[arg(short = 'd', long, default_value = "10s", value_parser = parse_duration)]
     pub duration: Duration,
@@ -98,6 +139,10 @@ pub struct RunArgs {
     #[arg(short = 'r', long, default_value = "0")]
     pub rate: u32,
 
+    /// Requests --rate lets through at once after idling (default: one second's worth)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub rate_burst: Option<u32>,
+
     /// Requests per burst (enables burst mode)
     #[arg(
         long,
@@ -111,6 +156,14 @@ pub struct RunArgs {
     #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "burst_rate")]
     pub burst_delay: Option<Duration>,
 
+    /// Ramp requests per burst linearly from --burst-rate to N by the end of the run
+    #[arg(long, value_name = "N", requires = "burst_rate")]
+    pub burst_ramp_to: Option<u32>,
+
+    /// Randomize each delay between bursts by up to ± DURATION (e.g., 200ms)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "burst_rate")]
+    pub burst_jitter: Option<Duration>,
+
     /// Ramp-up time to reach full concurrency (e.g., 5s)
     #[arg(long, default_value = "0s", value_parser = parse_duration)]
     pub ramp_up: Duration,
@@ -119,9 +172,65 @@ pub struct RunArgs {
     #[arg(long, default_value = "0s", value_parser = parse_duration)]
     pub warmup: Duration,
 
-    /// Think time between requests (e.g., 500ms)
+    /// Start measuring once throughput and latency settle (--warmup caps the wait, default 60s)
+    #[arg(long)]
+    pub auto_warmup: bool,
+
+    /// Think time between requests: fixed (500ms), a range (1s..3s), normal(2s, 500ms) or exp(2s)
+    #[arg(long, value_name = "DURATION")]
+    pub think_time: Option<String>,
+
+    /// Back off after 429 and 503 responses for as long as Retry-After says, and report the time spent throttled
+    #[arg(long)]
+    pub honor_retry_after: bool,
+
+    /// Send this share of requests (0-1) twice at once with the same idempotency key, and check both copies get the same response
+    #[arg(long, value_name = "RATE")]
+    pub duplicate_rate: Option<f64>,
+
+    /// Header duplicated requests carry their idempotency key in (default Idempotency-Key)
+    #[arg(long, value_name = "HEADER", requires = "duplicate_rate")]
+    pub idempotency_header: Option<String>,
+
+    /// Revalidate responses with If-None-Match/If-Modified-Since from their ETag and Last-Modified, and report the 304 ratio
+    #[arg(long)]
+    pub conditional_requests: bool,
+
+    /// Pause the load once every request has failed for this long (default 10s), health check the target once a second, and resume when it answers
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, num_args = 0..=1, default_missing_value = "10s")]
+    pub pause_on_errors: Option<Duration>,
+
+    /// Failover drill: report availability per timeline bucket, and the error bursts, downtime and time to recovery of each incident
+    #[arg(long)]
+    pub failover: bool,
+
+    /// Retry a request up to N times after a transport error, 5xx or 429
+    #[arg(long, default_value = "0", value_name = "N")]
+    pub retries: u32,
+
+    /// Total time a request may take across retries and redirects (e.g., 2s); overruns count as deadline errors
     #[arg(long, value_parser = parse_duration)]
-    pub think_time: Option<Duration>,
+    pub deadline: Option<Duration>,
+
+    /// Seed random URLs, host picks, chaos faults and Redis keys so runs repeat the same requests
+    #[arg(long, value_name = "N")]
+    pub seed: Option<u64>,
+
+    /// Approximate a client network: 3g, 4g, dsl, or custom with --network-down, --network-up and --network-rtt
+    #[arg(long, value_name = "PROFILE")]
+    pub network_profile: Option<String>,
+
+    /// Download bandwidth for --network-profile (e.g., 5mbit, 768kbit)
+    #[arg(long, value_name = "RATE")]
+    pub network_down: Option<String>,
+
+    /// Upload bandwidth for --network-profile (e.g., 1mbit)
+    #[arg(long, value_name = "RATE")]
+    pub network_up: Option<String>,
+
+    /// Round trip added to each request by --network-profile (e.g., 150ms)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub network_rtt: Option<Duration>,
 
     /// Request timeout (e.g., 5s)
     #[arg(long, default_value = "5s", value_parser = parse_duration)]
@@ -151,6 +260,14 @@ pub struct RunArgs {
     #[arg(short = 'Z', long = "body-lines", value_name = "FILE", conflicts_with_all = ["body", "body_file"])]
     pub body_lines_file: Option<PathBuf>,
 
+    /// Compress request bodies and send them with Content-Encoding: gzip or deflate
+    #[arg(long, value_name = "ENCODING")]
+    pub compress_body: Option<String>,
+
+    /// Time only a layer under the requests, sending no HTTP: dns, connect or tls. Runs at --arrival-rate
+    #[arg(long, value_name = "LAYER")]
+    pub probe: Option<String>,
+
     /// Max requests to send (0 = unlimited). Supports k/m suffixes (e.g., 10k, 1m)
     #[arg(short = 'n', long, default_value = "0", value_parser = parse_number_with_suffix)]
     pub max_requests: u64,
@@ -164,6 +281,16 @@ pub struct RunArgs {
     #[arg(long)]
     pub http3: bool,
 
+    /// Attempt 0-RTT resumption on HTTP/3 reconnects and report 0-RTT vs 1-RTT latency
+    #[cfg(feature = "http3")]
+    #[arg(long = "http3-0rtt")]
+    pub http3_0rtt: bool,
+
+    /// Run the workload over HTTP/2 and HTTP/3 and compare them (split, sequential)
+    #[cfg(feature = "http3")]
+    #[arg(long, value_name = "MODE")]
+    pub protocol_race: Option<String>,
+
     /// gRPC service name (e.g., "helloworld.Greeter") - requires --features grpc
     #[cfg(feature = "grpc")]
     #[arg(long)]
@@ -174,10 +301,62 @@ pub struct RunArgs {
     #[arg(long)]
     pub grpc_method: Option<String>,
 
+    /// Socket.IO event to emit (enables Socket.IO mode) - requires --features socketio
+    #[cfg(feature = "socketio")]
+    #[arg(long)]
+    pub socketio_event: Option<String>,
+
+    /// Socket.IO namespace to connect to - requires --features socketio
+    #[cfg(feature = "socketio")]
+    #[arg(long, default_value = "/")]
+    pub socketio_namespace: String,
+
+    /// MQTT topic template for mqtt:// targets; ${PUBLISHER} is replaced with the publisher index - requires --features mqtt
+    #[cfg(feature = "mqtt")]
+    #[arg(long, value_name = "TEMPLATE", default_value = "kaioken/${PUBLISHER}")]
+    pub mqtt_topic: String,
+
+    /// MQTT QoS level for publishes and subscriptions (0 or 1) - requires --features mqtt
+    #[cfg(feature = "mqtt")]
+    #[arg(long, default_value = "0", value_parser = clap::value_parser!(u8).range(0..=1))]
+    pub mqtt_qos: u8,
+
+    /// MQTT messages per second per publisher (default: --ws-message-interval) - requires --features mqtt
+    #[cfg(feature = "mqtt")]
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub mqtt_publish_rate: Option<u32>,
+
+    /// Fraction of MQTT connections that subscribe instead of publish - requires --features mqtt
+    #[cfg(feature = "mqtt")]
+    #[arg(long, value_name = "RATIO", default_value = "0.5")]
+    pub mqtt_subscriber_ratio: f64,
+
+    /// Redis command template for redis:// targets, e.g. 'GET user:${RANDOM_KEY}' (repeatable, VUs cycle through them) - requires --features redis
+    #[cfg(feature = "redis")]
+    #[arg(long = "redis-command", value_name = "COMMAND")]
+    pub redis_commands: Vec<String>,
+
+    /// Number of distinct values ${RANDOM_KEY} draws from - requires --features redis
+    #[cfg(feature = "redis")]
+    #[arg(long, value_name = "N", default_value = "10000", value_parser = clap::value_parser!(u64).range(1..))]
+    pub redis_keyspace: u64,
+
     /// Enable cookie jar for automatic session handling
     #[arg(long)]
     pub cookie_jar: bool,
 
+    /// Carry a CSRF token from safe requests to mutating ones: cookie:NAME, header:NAME, json:PATH or regex:PATTERN:GROUP
+    #[arg(long, value_name = "SOURCE")]
+    pub csrf: Option<String>,
+
+    /// Request header --csrf sends the token in
+    #[arg(long, value_name = "NAME")]
+    pub csrf_header: Option<String>,
+
+    /// Cookies every VU starts with, from a Netscape-format cookies.txt (turns on the cookie jar)
+    #[arg(long, value_name = "FILE")]
+    pub cookie_file: Option<PathBuf>,
+
     /// Target arrival rate in requests/second (enables arrival rate mode)
     #[arg(long)]
     pub arrival_rate: Option<u32>,
@@ -186,10 +365,42 @@ pub struct RunArgs {
     #[arg(long, default_value = "100")]
     pub max_vus: u32,
 
+    /// Replay a traffic shape from a file of 'offset,rate' lines as ramping arrival-rate stages
+    #[arg(long, value_name = "FILE", conflicts_with = "arrival_rate")]
+    pub rate_profile: Option<String>,
+
+    /// Squeeze the --rate-profile replay into this long (e.g., 1h for a day's profile)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "rate_profile")]
+    pub rate_profile_window: Option<Duration>,
+
+    /// Grow arrival-rate VUs a step at a time as iterations need them and shed idle ones, instead of a fixed --max-vus
+    #[arg(long)]
+    pub vu_autoscale: bool,
+
+    /// VUs added per scale-up with --vu-autoscale (default: 10)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), requires = "vu_autoscale")]
+    pub vu_step: Option<u32>,
+
+    /// Least time between scale-ups with --vu-autoscale (default: 1s)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "vu_autoscale")]
+    pub vu_cooldown: Option<Duration>,
+
+    /// Most VUs --vu-autoscale grows to (default: 1000)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), requires = "vu_autoscale")]
+    pub vu_cap: Option<u32>,
+
+    /// Shed VUs left unused this long with --vu-autoscale (default: 10s)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "vu_autoscale")]
+    pub vu_idle: Option<Duration>,
+
     /// Disable latency correction (normally auto-enabled for arrival rate mode)
     #[arg(long)]
     pub no_latency_correction: bool,
 
+    /// When the aggregator falls behind: block workers, drop results (counted), or grow a queue (block, drop, grow)
+    #[arg(long, value_name = "POLICY")]
+    pub backpressure: Option<String>,
+
     /// Disable following HTTP redirects
     #[arg(long)]
     pub no_follow_redirects: bool,
@@ -198,14 +409,63 @@ pub struct RunArgs {
     #[arg(short = 'f', long = "config")]
     pub config: Option<PathBuf>,
 
+    /// Test from the config's [[tests]] to run, or "all" to run them in sequence
+    #[arg(long, value_name = "NAME", requires = "config")]
+    pub test: Option<String>,
+
     /// Output file path for results
     #[arg(short = 'o', long)]
     pub output: Option<String>,
 
+    /// Width of each results timeline bucket (e.g., 100ms, 5s; default 1s)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub timeline_interval: Option<Duration>,
+
+    /// Example failures kept per error kind or status (default 3, 0 = off)
+    #[arg(long, value_name = "N")]
+    pub error_samples: Option<usize>,
+
+    /// Write the failure samples to this file (JSON Lines)
+    #[arg(long, value_name = "PATH")]
+    pub errors_out: Option<String>,
+
+    /// Apdex target time T: requests within T satisfy, within 4T tolerate (e.g., 300ms)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub apdex_t: Option<Duration>,
+
+    /// Latency percentiles to compute and report (e.g., 50,90,99,99.99)
+    #[arg(long, value_name = "LIST", value_delimiter = ',')]
+    pub percentiles: Option<Vec<f64>>,
+
+    /// Log requests taking at least DURATION to PATH as JSON Lines (e.g., 500ms slow.ndjson)
+    #[arg(long, num_args = 2, value_names = ["DURATION", "PATH"])]
+    pub slow_log: Option<Vec<String>>,
+
+    /// Run requests through a WASM plugin's hooks (repeatable) - requires --features plugins
+    #[cfg(feature = "plugins")]
+    #[arg(long = "plugin", value_name = "PATH")]
+    pub plugins: Vec<PathBuf>,
+
     /// Write summary snapshots to SQLite database
     #[arg(long, value_name = "PATH")]
     pub db_url: Option<PathBuf>,
 
+    /// Repeat the test on this interval for continuous monitoring (e.g., 1h)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub every: Option<Duration>,
+
+    /// Stop after N scheduled runs (default: until Ctrl+C)
+    #[arg(long, value_name = "N", requires = "every")]
+    pub times: Option<u32>,
+
+    /// Judge each scheduled run against the median of this many previous runs
+    #[arg(long, value_name = "N", default_value = "5", requires = "every")]
+    pub trend_window: usize,
+
+    /// Flag a scheduled run whose p99, error rate or RPS is this many percent worse than the trend
+    #[arg(long, value_name = "PCT", default_value = "20", requires = "every")]
+    pub trend_threshold: f64,
+
     /// Push metrics to Prometheus Pushgateway URL
     #[arg(long, value_name = "URL", conflicts_with = "prometheus_port")]
     pub prometheus_pushgateway: Option<String>,
@@ -214,14 +474,71 @@ pub struct RunArgs {
     #[arg(long, value_name = "PORT", conflicts_with = "prometheus_pushgateway")]
     pub prometheus_port: Option<u16>,
 
-    /// Output format (json, csv, md)
+    /// `instance` label on Prometheus metrics (default: the target URL)
+    #[arg(long, value_name = "NAME")]
+    pub prometheus_instance: Option<String>,
+
+    /// Extra label on every Prometheus metric, e.g. 'env=staging' (repeatable)
+    #[arg(long = "prometheus-label", value_name = "NAME=VALUE")]
+    pub prometheus_labels: Vec<String>,
+
+    /// Bucket bounds in ms for the kaioken_latency_ms histogram (e.g., 5,10,25,50,100)
+    #[arg(long, value_name = "LIST", value_delimiter = ',')]
+    pub prometheus_buckets: Option<Vec<f64>>,
+
+    /// Accept `kaioken ctl` commands on this localhost port while the test runs
+    #[arg(long, value_name = "PORT")]
+    pub control_port: Option<u16>,
+
+    /// Run the test on each of these hosts over SSH and merge the results (ssh://user@host[:port],...)
+    #[arg(
+        long,
+        value_name = "URLS",
+        value_delimiter = ',',
+        conflicts_with_all = [
+            "every",
+            "control_port",
+            "debug",
+            "errors_out",
+            "db_url",
+            "prometheus_port",
+            "prometheus_pushgateway"
+        ]
+    )]
+    pub runners: Vec<String>,
+
+    /// Path to kaioken on the runners (default: copy this binary over)
+    #[arg(long, value_name = "PATH", requires = "runners")]
+    pub runner_bin: Option<String>,
+
+    /// Print the JSON result on stdout every second, for a --runners coordinator
+    #[arg(long, hide = true)]
+    pub runner_stream: bool,
+
+    /// Start at this Unix time in milliseconds, as set by a --runners coordinator
+    #[arg(long, hide = true, value_name = "UNIX_MS")]
+    pub start_at: Option<u64>,
+
+    /// Output format (json, csv, md, html, template)
     #[arg(long, default_value = "json")]
     pub format: String,
 
+    /// Tera template rendering the result for --format template
+    #[arg(long, value_name = "FILE")]
+    pub template: Option<PathBuf>,
+
     /// Disable TUI, print summary only
     #[arg(long)]
     pub no_tui: bool,
 
+    /// Without the TUI, print a one-line progress summary to stderr this often (e.g., 10s)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub summary_interval: Option<Duration>,
+
+    /// Without the TUI, show progress as summary lines or a single redrawn bar with ETA
+    #[arg(long, value_enum, value_name = "STYLE")]
+    pub progress: Option<ProgressStyle>,
+
     /// Shorthand for --no-tui --format json (outputs JSON to stdout)
     #[arg(long)]
     pub json: bool,
@@ -246,6 +563,14 @@ pub struct RunArgs {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// Threshold the run must pass, as in [thresholds] (repeatable, e.g. 'p99_latency_ms<500')
+    #[arg(long = "threshold", value_name = "METRIC<VALUE")]
+    pub thresholds: Vec<String>,
+
+    /// Media type a successful response should have; others count as unexpected (repeatable, 'text/*' for a whole type)
+    #[arg(long = "expect-content-type", value_name = "TYPE")]
+    pub expect_content_types: Vec<String>,
+
     /// Abort immediately when any threshold fails
     #[arg(long)]
     pub fail_fast: bool,
@@ -258,6 +583,42 @@ pub struct RunArgs {
     #[arg(long)]
     pub disable_keepalive: bool,
 
+    /// Disable TCP_NODELAY so Nagle's algorithm can coalesce small writes
+    #[arg(long)]
+    pub no_tcp_nodelay: bool,
+
+    /// Socket send buffer size in bytes (SO_SNDBUF) for tcp://, redis:// and mqtt:// targets
+    #[arg(long, value_name = "BYTES")]
+    pub send_buffer: Option<u32>,
+
+    /// Socket receive buffer size in bytes (SO_RCVBUF) for tcp://, redis:// and mqtt:// targets
+    #[arg(long, value_name = "BYTES")]
+    pub recv_buffer: Option<u32>,
+
+    /// Idle HTTP connections kept per host (default: one per worker)
+    #[arg(long, value_name = "N")]
+    pub pool_max_idle_per_host: Option<usize>,
+
+    /// How long idle HTTP connections stay pooled (default: 30s)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub pool_idle_timeout: Option<Duration>,
+
+    /// Cap the HTTP/1.1 connections all VUs share at N: at most N requests are in flight, and VUs wait for a free connection
+    #[arg(long, value_name = "N")]
+    pub max_connections: Option<u32>,
+
+    /// Connect over IPv4 only
+    #[arg(short = '4', long, conflicts_with = "ipv6")]
+    pub ipv4: bool,
+
+    /// Connect over IPv6 only
+    #[arg(short = '6', long)]
+    pub ipv6: bool,
+
+    /// Stick to the first address family DNS returns instead of racing the other one
+    #[arg(long)]
+    pub no_happy_eyeballs: bool,
+
     // WebSocket options
     /// WebSocket message send interval (e.g., 100ms)
     #[arg(long, default_value = "100ms", value_parser = parse_duration)]
@@ -267,6 +628,44 @@ pub struct RunArgs {
     #[arg(long)]
     pub ws_fire_and_forget: bool,
 
+    /// WebSocket subprotocol to request during the handshake (repeatable)
+    #[arg(long = "ws-subprotocol", value_name = "PROTOCOL")]
+    pub ws_subprotocols: Vec<String>,
+
+    /// WebSocket broadcast mode: N publisher connections, the rest subscribe and measure delivery latency
+    #[arg(long, value_name = "N", conflicts_with = "ws_fire_and_forget")]
+    pub ws_publishers: Option<u32>,
+
+    /// Close and re-establish each WebSocket connection at this interval (e.g., 30s)
+    #[arg(long, value_parser = parse_duration)]
+    pub ws_reconnect_every: Option<Duration>,
+
+    /// Close and re-establish this percentage of WebSocket connections each second (e.g., 5), spread evenly
+    #[arg(long, value_name = "PERCENT", conflicts_with = "ws_reconnect_every")]
+    pub ws_churn: Option<f64>,
+
+    /// Offer permessage-deflate compression and report compressed vs raw bytes
+    #[arg(long)]
+    pub ws_compression: bool,
+
+    // Raw TCP/UDP options (tcp:// and udp:// targets)
+    /// Wait for a response matching this regex after each send
+    #[arg(long, value_name = "REGEX")]
+    pub raw_expect: Option<String>,
+
+    /// Wait for at least this many response bytes after each send
+    #[arg(long, value_name = "N", conflicts_with = "raw_expect")]
+    pub raw_expect_bytes: Option<usize>,
+
+    // JSON-RPC options
+    /// JSON-RPC 2.0 method; wraps requests in the envelope and counts `error` replies as failures
+    #[arg(long, value_name = "METHOD", conflicts_with_all = ["body", "body_file", "body_lines_file", "form"])]
+    pub jsonrpc_method: Option<String>,
+
+    /// JSON-RPC params as a JSON array or object (e.g., '["0xabc", "latest"]')
+    #[arg(long, value_name = "JSON", requires = "jsonrpc_method")]
+    pub jsonrpc_params: Option<String>,
+
     // Authentication and security options
     /// Basic authentication credentials (user:password)
     #[arg(short = 'a', long = "basic-auth", value_name = "USER:PASS")]
@@ -276,6 +675,18 @@ pub struct RunArgs {
     #[arg(short = 'x', long)]
     pub proxy: Option<String>,
 
+    /// File of proxy URLs, one per line, to spread requests over
+    #[arg(long, value_name = "FILE", conflicts_with = "proxy")]
+    pub proxy_list: Option<PathBuf>,
+
+    /// How --proxy-list is rotated: vu (each VU keeps one proxy) or request
+    #[arg(long, value_name = "MODE")]
+    pub proxy_rotation: Option<String>,
+
+    /// File of Host headers, one per line, to spread requests over while connecting to the target URL (virtual hosts, reported per host)
+    #[arg(long, value_name = "FILE")]
+    pub host_header_list: Option<PathBuf>,
+
     /// Client certificate file path (PEM format) for mTLS
     #[arg(long, value_name = "FILE")]
     pub cert: Option<PathBuf>,
@@ -288,6 +699,34 @@ pub struct RunArgs {
     #[arg(long, value_name = "FILE")]
     pub cacert: Option<PathBuf>,
 
+    /// Client certificates for mTLS dealt out to the VUs: a directory of NAME.crt/NAME.key pairs, or a file listing CERT KEY per line
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["cert", "key"])]
+    pub client_cert_pool: Option<PathBuf>,
+
+    /// How VUs get pool certificates: round-robin, or unique (one each, needs a certificate per VU)
+    #[arg(long, value_name = "MODE", requires = "client_cert_pool")]
+    pub client_cert_assign: Option<String>,
+
+    /// TLS server name (SNI) to send instead of the URL's host, which moves to the Host header; requests go over HTTP/1.1
+    #[arg(long, value_name = "NAME")]
+    pub sni: Option<String>,
+
+    /// TLS implementation for HTTP: rustls (default) or native (platform TLS and trust store, requires --features native-tls)
+    #[arg(long, value_name = "BACKEND")]
+    pub tls_backend: Option<String>,
+
+    /// Send trace context headers with fresh IDs on each request: w3c (traceparent) or b3
+    #[arg(long, value_name = "FORMAT")]
+    pub trace_propagation: Option<String>,
+
+    /// Send a unique ID in this header and count responses that don't echo it back (e.g. X-Request-ID)
+    #[arg(long, value_name = "HEADER")]
+    pub request_id_header: Option<String>,
+
+    /// Slowest traced requests whose trace IDs are listed in the results (default 10)
+    #[arg(long, value_name = "N")]
+    pub trace_slowest: Option<usize>,
+
     /// Override host resolution (HOST:PORT:TARGET_HOST:TARGET_PORT)
     #[arg(long, value_name = "MAPPING")]
     pub connect_to: Option<String>,
@@ -303,14 +742,41 @@ impl Default for RunArgs {
             url: None,
             rand_regex_url: None,
             urls_from_file: None,
+            endpoint_groups: Vec::new(),
             concurrency: 50,
+            threads: None,
             duration: Duration::from_secs(10),
             rate: 0,
+            rate_burst: None,
             burst_rate: None,
             burst_delay: None,
+            burst_ramp_to: None,
+            burst_jitter: None,
             ramp_up: Duration::ZERO,
             warmup: Duration::ZERO,
+            auto_warmup: false,
             think_time: None,
+            honor_retry_after: false,
+            duplicate_rate: None,
+            idempotency_header: None,
+            conditional_requests: false,
+            pause_on_errors: None,
+            failover: false,
+            retries: 0,
+            seed: None,
+            deadline: None,
+            network_profile: None,
+            network_down: None,
+            network_up: None,
+            network_rtt: None,
+            timeline_interval: None,
+            error_samples: None,
+            errors_out: None,
+            apdex_t: None,
+            percentiles: None,
+            slow_log: None,
+            #[cfg(feature = "plugins")]
+            plugins: Vec::new(),
             timeout: Duration::from_secs(5),
             connect_timeout: Duration::from_secs(2),
             method: "GET".to_string(),
@@ -318,44 +784,123 @@ impl Default for RunArgs {
             body: None,
             body_file: None,
             body_lines_file: None,
+            compress_body: None,
+            probe: None,
             max_requests: 0,
             http2: false,
             #[cfg(feature = "http3")]
             http3: false,
+            #[cfg(feature = "http3")]
+            http3_0rtt: false,
+            #[cfg(feature = "http3")]
+            protocol_race: None,
             #[cfg(feature = "grpc")]
             grpc_service: None,
             #[cfg(feature = "grpc")]
             grpc_method: None,
+            #[cfg(feature = "socketio")]
+            socketio_event: None,
+            #[cfg(feature = "socketio")]
+            socketio_namespace: "/".to_string(),
+            #[cfg(feature = "mqtt")]
+            mqtt_topic: "kaioken/${PUBLISHER}".to_string(),
+            #[cfg(feature = "mqtt")]
+            mqtt_qos: 0,
+            #[cfg(feature = "mqtt")]
+            mqtt_publish_rate: None,
+            #[cfg(feature = "mqtt")]
+            mqtt_subscriber_ratio: 0.5,
+            #[cfg(feature = "redis")]
+            redis_commands: Vec::new(),
+            #[cfg(feature = "redis")]
+            redis_keyspace: 10000,
             cookie_jar: false,
+            csrf: None,
+            csrf_header: None,
+            cookie_file: None,
             arrival_rate: None,
             max_vus: 100,
+            rate_profile: None,
+            rate_profile_window: None,
+            vu_autoscale: false,
+            vu_step: None,
+            vu_cooldown: None,
+            vu_cap: None,
+            vu_idle: None,
             no_latency_correction: false,
+            backpressure: None,
             no_follow_redirects: false,
             config: None,
+            test: None,
             output: None,
             db_url: None,
+            every: None,
+            times: None,
+            trend_window: 5,
+            trend_threshold: 20.0,
             format: "json".to_string(),
+            template: None,
             no_tui: false,
+            summary_interval: None,
+            progress: None,
             json: false,
             quiet: false,
             serious: false,
             insecure: false,
             yes: false,
             dry_run: false,
+            thresholds: Vec::new(),
             fail_fast: false,
             debug: false,
             disable_keepalive: false,
+            no_tcp_nodelay: false,
+            send_buffer: None,
+            recv_buffer: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            max_connections: None,
+            expect_content_types: Vec::new(),
+            ipv4: false,
+            ipv6: false,
+            no_happy_eyeballs: false,
             ws_message_interval: Duration::from_millis(100),
             ws_fire_and_forget: false,
+            ws_subprotocols: Vec::new(),
+            ws_publishers: None,
+            ws_reconnect_every: None,
+            ws_churn: None,
+            ws_compression: false,
+            raw_expect: None,
+            raw_expect_bytes: None,
+            jsonrpc_method: None,
+            jsonrpc_params: None,
             basic_auth: None,
             proxy: None,
+            proxy_list: None,
+            host_header_list: None,
+            proxy_rotation: None,
             cert: None,
             key: None,
             cacert: None,
+            client_cert_pool: None,
+            client_cert_assign: None,
+            sni: None,
+            tls_backend: None,
+            trace_propagation: None,
+            request_id_header: None,
+            trace_slowest: None,
             connect_to: None,
             form: Vec::new(),
             prometheus_pushgateway: None,
             prometheus_port: None,
+            prometheus_instance: None,
+            prometheus_labels: Vec::new(),
+            prometheus_buckets: None,
+            control_port: None,
+            runners: Vec::new(),
+            runner_bin: None,
+            runner_stream: false,
+            start_at: None,
         }
     }
 }
@@ -397,6 +942,254 @@ pub struct CompareArgs {
     pub force: bool,
 }
 
+#[derive(Parser, Debug)]
+pub struct CalibrateArgs {
+    /// Duration of each concurrency step (e.g., 3s)
+    #[arg(short = 'd', long, default_value = "3s", value_parser = parse_duration)]
+    pub duration: Duration,
+
+    /// Highest concurrency to try (steps double from 8)
+    #[arg(long, default_value = "1024")]
+    pub max_concurrency: u32,
+
+    /// Run workers on N independent runtimes, as `run --threads` would
+    #[arg(long, value_name = "N")]
+    pub threads: Option<u32>,
+
+    /// Output as JSON instead of table
+    #[arg(long)]
+    pub json: bool,
+
+    /// Print results without saving them for `run` to check against
+    #[arg(long)]
+    pub no_save: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct ServeArgs {
+    /// Address to listen on (use 0.0.0.0:PORT to accept remote connections)
+    #[arg(short, long, default_value = "127.0.0.1:8080")]
+    pub bind: String,
+
+    /// Require this bearer token on API requests (`Authorization: Bearer TOKEN` or `?token=TOKEN`)
+    #[arg(long, value_name = "TOKEN")]
+    pub token: Option<String>,
+
+    /// Host name clients reach the server by, besides the bind address (repeatable)
+    #[arg(long = "allow-host", value_name = "HOST")]
+    pub allow_hosts: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct AttachArgs {
+    /// `kaioken serve` address (HOST:PORT or a URL)
+    pub server: String,
+
+    /// Bearer token the server was started with (`serve --token`)
+    #[arg(long, value_name = "TOKEN")]
+    pub token: Option<String>,
+
+    /// Print progress lines instead of the TUI
+    #[arg(long)]
+    pub no_tui: bool,
+
+    /// Disable DBZ flavor (serious mode)
+    #[arg(long)]
+    pub serious: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct DoctorArgs {
+    /// Target URL to resolve
+    pub url: Option<String>,
+
+    /// Config file to take the target and load settings from
+    #[arg(short = 'f', long = "config")]
+    pub config: Option<PathBuf>,
+
+    /// Number of concurrent workers the run will use
+    #[arg(short = 'c', long, default_value = "50")]
+    pub concurrency: u32,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct ValidateArgs {
+    /// Config files to check
+    #[arg(required = true, value_name = "CONFIG")]
+    pub configs: Vec<PathBuf>,
+
+    /// Output findings as JSON (for pre-commit hooks and CI)
+    #[arg(long)]
+    pub json: bool,
+
+    /// Fail on warnings too
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct ReportArgs {
+    /// Saved result file (from `--format json` / `-o results.json`)
+    pub input: PathBuf,
+
+    /// Output format (summary, json, csv, md, html, template)
+    #[arg(short, long, default_value = "summary")]
+    pub format: String,
+
+    /// Tera template rendering the result for --format template
+    #[arg(long, value_name = "FILE")]
+    pub template: Option<PathBuf>,
+
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Disable DBZ flavor (serious mode)
+    #[arg(long)]
+    pub serious: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct HistoryArgs {
+    /// SQLite database runs were stored in with `run --db-url`
+    #[arg(long, value_name = "PATH", global = true)]
+    pub db_url: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: HistoryCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryCommand {
+    /// List stored runs, newest first, with key metrics
+    List {
+        /// Number of runs to show
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: usize,
+
+        /// Output as JSON instead of table
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show one stored run
+    Show {
+        /// Run ID (from `history list`)
+        id: i64,
+
+        /// Print the stored JSON result (same layout as `--format json`)
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Compare two stored runs, as `compare` does for result files
+    Compare(HistoryCompareArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct CtlArgs {
+    /// Control port of the running test (`run --control-port`)
+    #[arg(short, long)]
+    pub port: u16,
+
+    /// Output as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: CtlCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CtlCommand {
+    /// Show progress and current metrics
+    Status,
+
+    /// Stop the test early; results are written as for Ctrl+C
+    Stop,
+
+    /// Keep the test running longer (e.g., 5m)
+    Extend {
+        #[arg(value_parser = parse_duration)]
+        duration: Duration,
+    },
+
+    /// Change the target request rate (runs started with --rate or --arrival-rate)
+    SetRate { rate: u32 },
+}
+
+#[derive(Parser, Debug)]
+pub struct HistoryCompareArgs {
+    /// Baseline run ID
+    pub baseline: i64,
+
+    /// Current run ID to compare against baseline
+    pub current: i64,
+
+    /// p99 latency regression threshold (percentage, default: 10)
+    #[arg(long, default_value = "10.0")]
+    pub threshold_p99: f64,
+
+    /// p999 latency regression threshold (percentage, default: 15)
+    #[arg(long, default_value = "15.0")]
+    pub threshold_p999: f64,
+
+    /// Error rate regression threshold (percentage, default: 50)
+    #[arg(long, default_value = "50.0")]
+    pub threshold_error_rate: f64,
+
+    /// RPS regression threshold (percentage, default: 10)
+    #[arg(long, default_value = "10.0")]
+    pub threshold_rps: f64,
+
+    /// Disable DBZ flavor (serious mode)
+    #[arg(long)]
+    pub serious: bool,
+
+    /// Output as JSON instead of table
+    #[arg(long)]
+    pub json: bool,
+
+    /// Force comparison even if load models differ (open vs closed)
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct RecordArgs {
+    /// Address to listen on; point a browser or app at it as an HTTP proxy
+    #[arg(short, long, default_value = "127.0.0.1:8888")]
+    pub bind: String,
+
+    /// Act as a reverse proxy for this base URL instead (needed to record HTTPS targets)
+    #[arg(long, value_name = "URL")]
+    pub target: Option<String>,
+
+    /// Output file path (default: kaioken.toml)
+    #[arg(short, long, default_value = "kaioken.toml")]
+    pub output: PathBuf,
+
+    /// Only record requests whose URL matches this pattern (regex)
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Stop after recording N requests (default: until Ctrl+C)
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Skip TLS certificate verification when forwarding
+    #[arg(short = 'k', long)]
+    pub insecure: bool,
+
+    /// Overwrite existing output file
+    #[arg(long)]
+    pub force: bool,
+}
+
 #[derive(Parser, Debug)]
 pub struct ImportArgs {
     /// Input file to import (HAR, Postman collection, or OpenAPI spec)
@@ -419,6 +1212,14 @@ pub struct ImportArgs {
     pub force: bool,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProgressStyle {
+    /// A summary line every --summary-interval (default 10s)
+    Lines,
+    /// One line redrawn in place every --summary-interval (default 1s)
+    Bar,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum ImportFormat {
     /// HAR (HTTP Archive) format from browser DevTools
@@ -447,6 +1248,50 @@ impl RunArgs {
     }
 }
 
+#[derive(Parser, Debug)]
+pub struct K8sArgs {
+    #[command(subcommand)]
+    pub command: K8sCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum K8sCommand {
+    /// Print a ConfigMap holding the config and a Job that runs it
+    Generate {
+        /// Config file to embed (TOML)
+        #[arg(short = 'f', long = "config")]
+        config: PathBuf,
+
+        /// Container image with kaioken as its entrypoint
+        #[arg(long)]
+        image: String,
+
+        /// Name of the Job; the ConfigMap is NAME-config
+        #[arg(long, default_value = "kaioken")]
+        name: String,
+
+        /// Namespace to create both in (default: the current one)
+        #[arg(short = 'n', long)]
+        namespace: Option<String>,
+
+        /// Pods that each run the full test at the same time
+        #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
+        parallelism: u32,
+
+        /// Secret whose keys become environment variables in the pods, for ${VAR}s in the config (repeatable)
+        #[arg(long = "secret", value_name = "NAME")]
+        secrets: Vec<String>,
+
+        /// Write the manifest here instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Extra `kaioken run` flags for the pods (after --)
+        #[arg(last = true, value_name = "RUN_ARGS")]
+        run_args: Vec<String>,
+    },
+}
+
 #[derive(Parser, Debug)]
 pub struct InitArgs {
     /// Output file path (default: kaioken.toml)
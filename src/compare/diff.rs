@@ -1,5 +1,6 @@
 use crate::cli::CompareArgs;
 use crate::output::json::JsonOutput;
+use crate::output::schema::{SCHEMA_VERSION, read_result};
 use serde::Serialize;
 use std::fs;
 use std::path::Path;
@@ -36,9 +37,26 @@ pub struct Regression {
 }
 
 pub fn compare_results(args: &CompareArgs) -> Result<CompareResult, String> {
-    let baseline = load_json(&args.baseline)?;
-    let current = load_json(&args.current)?;
+    let (baseline, baseline_version) = load_json(&args.baseline)?;
+    let (current, current_version) = load_json(&args.current)?;
+    let mut result = compare_outputs(&baseline, &current, args)?;
+    for (file, version) in [("Baseline", baseline_version), ("Current", current_version)] {
+        if version < SCHEMA_VERSION {
+            result.warnings.push(format!(
+                "{} is schema version {}, upgraded to {}",
+                file, version, SCHEMA_VERSION
+            ));
+        }
+    }
+    Ok(result)
+}
 
+/// Compare two in-memory results; `args` supplies labels and thresholds
+pub fn compare_outputs(
+    baseline: &JsonOutput,
+    current: &JsonOutput,
+    args: &CompareArgs,
+) -> Result<CompareResult, String> {
     let mut metrics = Vec::new();
     let mut regressions = Vec::new();
     let mut warnings = Vec::new();
@@ -325,10 +343,10 @@ fn compare_metric(
     }
 }
 
-fn load_json(path: &Path) -> Result<JsonOutput, String> {
+/// A result file and the schema version it was written under
+fn load_json(path: &Path) -> Result<(JsonOutput, u32), String> {
     let content = fs::read_to_string(path)
         .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
 
-    serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse '{}': {}", path.display(), e))
+    read_result(&content).map_err(|e| format!("Failed to parse '{}': {}", path.display(), e))
 }
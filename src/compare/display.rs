@@ -1,6 +1,30 @@
 use crate::compare::CompareResult;
 
 pub fn print_comparison(result: &CompareResult, serious: bool) {
+    print_comparison_table(result, serious);
+
+    // Summary
+    println!();
+    if result.has_regressions {
+        let msg = if serious {
+            "RESULT: Regressions detected. Exiting with code 3."
+        } else {
+            "RESULT: Power level decreased! Senzu bean required. Exit code 3."
+        };
+        println!("{}", msg);
+    } else {
+        let msg = if serious {
+            "RESULT: No regressions detected."
+        } else {
+            "RESULT: Power levels stable. You may proceed."
+        };
+        println!("{}", msg);
+    }
+    println!();
+}
+
+/// The metrics table without the pass/fail verdict
+pub fn print_comparison_table(result: &CompareResult, serious: bool) {
     let title = if serious {
         "Comparison Results"
     } else {
@@ -93,25 +117,6 @@ pub fn print_comparison(result: &CompareResult, serious: bool) {
     }
 
     println!("└{:─^70}┘", "");
-
-    // Summary
-    println!();
-    if result.has_regressions {
-        let msg = if serious {
-            "RESULT: Regressions detected. Exiting with code 3."
-        } else {
-            "RESULT: Power level decreased! Senzu bean required. Exit code 3."
-        };
-        println!("{}", msg);
-    } else {
-        let msg = if serious {
-            "RESULT: No regressions detected."
-        } else {
-            "RESULT: Power levels stable. You may proceed."
-        };
-        println!("{}", msg);
-    }
-    println!();
 }
 
 pub fn print_comparison_json(result: &CompareResult) -> Result<(), String> {
@@ -1,5 +1,7 @@
 mod diff;
 pub mod display;
 
-pub use diff::{CompareResult, compare_results};
+pub use diff::{CompareResult, compare_outputs, compare_results};
 pub use display::print_comparison;
+#[cfg(feature = "http3")]
+pub use display::print_comparison_table;
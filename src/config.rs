@@ -1,14 +1,21 @@
 use crate::cli::RunArgs;
+use crate::net::{IpFamily, SocketOptions};
 use crate::types::{
-    BurstConfig, Check, CheckCondition, Extraction, ExtractionSource, FormField, LoadConfig,
-    Scenario, Stage, Threshold, ThresholdMetric, ThresholdOp,
+    BodyCompression, BurstConfig, ChaosConfig, Check, CheckCondition, ClientIdentity, CsrfConfig,
+    CsrfSource, DEFAULT_PERCENTILES, Extraction, ExtractionSource, FormField, LoadConfig,
+    NetworkProfile, ProbeKind, ProxyRotation, Scenario, SeededCookie, SlowLogConfig, Stage,
+    StdinInput, StdinStream, ThinkTime, Threshold, ThresholdMetric, ThresholdOp, TrendThreshold,
 };
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+/// Longest `--auto-warmup` waits for stable numbers when `--warmup` isn't set
+const DEFAULT_AUTO_WARMUP_MAX: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Deserialize, Default)]
 pub struct TomlConfig {
     #[serde(default)]
@@ -25,34 +32,151 @@ pub struct TomlConfig {
     pub checks: Vec<CheckConfig>,
     #[serde(default)]
     pub stages: Vec<StageConfig>,
+    pub jsonrpc: Option<JsonRpcConfig>,
+    /// URL rewrites for the per-endpoint breakdown
+    #[serde(default)]
+    pub endpoint_groups: Vec<EndpointGroupConfig>,
+    /// Named test variants (`[[tests]]`), expanded by `load_tests`
+    #[serde(default)]
+    pub tests: Vec<toml::Table>,
+    /// Base config this file builds on (path relative to this file)
+    pub extends: Option<String>,
+    /// Config files merged underneath this one, in order
+    #[serde(default)]
+    pub include: Vec<String>,
+    pub chaos: Option<ChaosSettings>,
+    pub network: Option<NetworkSettings>,
+    pub csrf: Option<CsrfSettings>,
+}
+
+impl TomlConfig {
+    /// Whether this config pulls in other files with `extends` / `include`
+    pub fn has_includes(&self) -> bool {
+        self.extends.is_some() || !self.include.is_empty()
+    }
+}
+
+/// One `[[tests]]` entry with the file's top-level sections as defaults
+#[derive(Debug)]
+pub struct NamedTest {
+    pub name: String,
+    pub config: TomlConfig,
+}
+
+/// `[jsonrpc]` section: method and params wrapped into the JSON-RPC 2.0 envelope
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct JsonRpcConfig {
+    pub method: String,
+    /// Array or object; omitted from the envelope when unset
+    pub params: Option<toml::Value>,
+}
+
+/// `[[endpoint_groups]]`: matches of `pattern` in the path are replaced by `name`
+#[derive(Debug, Deserialize, Clone)]
+pub struct EndpointGroupConfig {
+    pub pattern: String,
+    pub name: String,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct StageConfig {
     #[serde(with = "humantime_serde")]
     pub duration: Duration,
-    pub target: Option<u32>,      // VU-based (constant VUs mode)
-    pub target_rate: Option<u32>, // RPS-based (arrival rate mode)
+    pub target: Option<u32>,              // VU-based (constant VUs mode)
+    pub target_rate: Option<u32>,         // RPS-based (arrival rate mode)
+    pub target_message_rate: Option<u32>, // Per-connection messages/sec (WebSocket mode)
 }
 
 /// Threshold configuration - unknown fields are rejected.
-/// Valid metrics: p50_latency_ms, p75_latency_ms, p90_latency_ms, p95_latency_ms,
-/// p99_latency_ms, p999_latency_ms, mean_latency_ms, max_latency_ms, error_rate,
-/// rps, check_pass_rate
-#[derive(Debug, Deserialize, Default)]
-#[serde(deny_unknown_fields)]
+/// Valid metrics: pN_latency_ms for each entry in `percentiles` (p50, p75,
+/// p90, p95, p99 and p999 by default), mean_latency_ms, max_latency_ms,
+/// error_rate, rps, check_pass_rate, apdex, extraction_failure_rate,
+/// unexpected_content_type. Each also has a `<metric>_trend` form.
+#[derive(Debug, Default)]
 pub struct ThresholdsConfig {
-    pub p50_latency_ms: Option<String>,
-    pub p75_latency_ms: Option<String>,
-    pub p90_latency_ms: Option<String>,
-    pub p95_latency_ms: Option<String>,
-    pub p99_latency_ms: Option<String>,
-    pub p999_latency_ms: Option<String>,
-    pub mean_latency_ms: Option<String>,
-    pub max_latency_ms: Option<String>,
-    pub error_rate: Option<String>,
-    pub rps: Option<String>,
-    pub check_pass_rate: Option<String>,
+    values: BTreeMap<ThresholdMetric, String>,
+    /// `<metric>_trend` keys
+    trends: BTreeMap<ThresholdMetric, String>,
+}
+
+impl<'de> Deserialize<'de> for ThresholdsConfig {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        const METRICS: &[&str] = &[
+            "p50_latency_ms",
+            "p75_latency_ms",
+            "p90_latency_ms",
+            "p95_latency_ms",
+            "p99_latency_ms",
+            "p999_latency_ms",
+            "mean_latency_ms",
+            "max_latency_ms",
+            "error_rate",
+            "rps",
+            "check_pass_rate",
+            "apdex",
+            "extraction_failure_rate",
+            "unexpected_content_type",
+        ];
+        let mut config = Self::default();
+        for (key, expr) in BTreeMap::<String, String>::deserialize(deserializer)? {
+            if let Some(metric) = key.strip_suffix("_trend").and_then(ThresholdMetric::parse) {
+                config.trends.insert(metric, expr);
+            } else if let Some(metric) = ThresholdMetric::parse(&key) {
+                config.values.insert(metric, expr);
+            } else {
+                return Err(serde::de::Error::unknown_field(&key, METRICS));
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// `slow_log = { threshold = "500ms", path = "slow.ndjson" }`
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SlowLogSettings {
+    #[serde(with = "humantime_serde")]
+    pub threshold: Duration,
+    pub path: String,
+}
+
+/// `[chaos]`: client-side faults injected into a share of the requests
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ChaosSettings {
+    /// Extra delay before sending, fixed ("50ms") or a range ("10ms..200ms")
+    pub latency: Option<String>,
+    /// Share of requests delayed; every request when unset
+    pub latency_rate: Option<f64>,
+    #[serde(default)]
+    pub abort_rate: f64,
+    /// When aborted requests are given up on; anywhere up to the timeout when unset
+    pub abort_after: Option<String>,
+    #[serde(default)]
+    pub reset_rate: f64,
+}
+
+/// `[network]`: the client network responses are slowed down to
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkSettings {
+    /// 3g, 4g, dsl or custom
+    pub profile: Option<String>,
+    /// Bandwidths like "5mbit" or "768kbit", overriding the profile's
+    pub down: Option<String>,
+    pub up: Option<String>,
+    #[serde(default, with = "humantime_serde::option")]
+    pub rtt: Option<Duration>,
+}
+
+/// `[csrf]`: where each VU finds its CSRF token, and the header it's sent in
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CsrfSettings {
+    /// cookie:NAME, header:NAME, json:PATH or regex:PATTERN:GROUP
+    pub from: Option<String>,
+    pub header: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -71,13 +195,125 @@ pub struct ScenarioConfig {
     pub headers: HashMap<String, String>,
     pub body: Option<String>,
     pub body_file: Option<String>,
-    #[serde(default = "default_weight")]
-    pub weight: u32,
+    #[serde(default)]
+    pub weight: ScenarioWeight,
     #[serde(default)]
     pub extract: HashMap<String, String>,
     pub depends_on: Option<String>,
     #[serde(default)]
     pub tags: HashMap<String, String>,
+    /// Proxy for this scenario instead of the run's ("" = direct)
+    pub proxy: Option<String>,
+    /// Pause after this scenario's requests, in `--think-time` syntax
+    pub think_time: Option<String>,
+}
+
+/// A scenario's share of the traffic: a ratio to the other weights (`3`)
+/// or a percentage (`"35%"`). Percentages have to add up to 100.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(try_from = "RawWeight")]
+pub enum ScenarioWeight {
+    Ratio(u32),
+    Percent(f64),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawWeight {
+    Ratio(u32),
+    Text(String),
+}
+
+impl TryFrom<RawWeight> for ScenarioWeight {
+    type Error = String;
+
+    fn try_from(raw: RawWeight) -> Result<Self, String> {
+        let text = match raw {
+            RawWeight::Ratio(n) => return Ok(ScenarioWeight::Ratio(n)),
+            RawWeight::Text(text) => text,
+        };
+        let percent = text
+            .trim()
+            .strip_suffix('%')
+            .and_then(|n| n.trim().parse::<f64>().ok())
+            .ok_or_else(|| {
+                format!(
+                    "invalid weight '{}': expected a whole number or a percentage like \"35%\"",
+                    text
+                )
+            })?;
+        if !(0.0..=100.0).contains(&percent) {
+            return Err(format!("weight '{}' is not between 0% and 100%", text));
+        }
+        if (percent * 100.0).fract().abs() > 1e-6 {
+            return Err(format!(
+                "weight '{}' has more than two decimal places",
+                text
+            ));
+        }
+        Ok(ScenarioWeight::Percent(percent))
+    }
+}
+
+impl Default for ScenarioWeight {
+    fn default() -> Self {
+        ScenarioWeight::Ratio(1)
+    }
+}
+
+impl ScenarioWeight {
+    /// Dependency-only scenarios (`weight = 0`) are never picked
+    pub fn is_zero(&self) -> bool {
+        match *self {
+            ScenarioWeight::Ratio(n) => n == 0,
+            ScenarioWeight::Percent(p) => p == 0.0,
+        }
+    }
+}
+
+/// Scenario weights as the ratios the engine picks by. Percentages are
+/// counted in hundredths of a percent, then reduced, so 35% and 65% pick
+/// like 7 and 13.
+fn scenario_weights(configs: &[ScenarioConfig]) -> Result<Vec<u32>, String> {
+    let percentages = configs
+        .iter()
+        .any(|c| matches!(c.weight, ScenarioWeight::Percent(_)));
+    let mut weights = Vec::with_capacity(configs.len());
+    for (i, cfg) in configs.iter().enumerate() {
+        weights.push(match (cfg.weight, percentages) {
+            (ScenarioWeight::Ratio(n), false) => n,
+            (ScenarioWeight::Percent(p), _) => (p * 100.0).round() as u32,
+            // Dependency-only scenarios keep weight 0 among percentages
+            (ScenarioWeight::Ratio(0), true) => 0,
+            (ScenarioWeight::Ratio(_), true) => {
+                let name = cfg
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("scenario_{}", i + 1));
+                return Err(format!(
+                    "Scenario '{}' needs a percentage weight (or 0) like the other scenarios",
+                    name
+                ));
+            }
+        });
+    }
+    if !percentages {
+        return Ok(weights);
+    }
+
+    let total: u32 = weights.iter().sum();
+    if total != 100 * 100 {
+        return Err(format!(
+            "Scenario weights add up to {}%, not 100%",
+            total as f64 / 100.0
+        ));
+    }
+    let divisor = weights.iter().fold(0, |a, &b| gcd(a, b));
+    Ok(weights.iter().map(|w| w / divisor).collect())
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
 }
 
 fn default_method() -> String {
@@ -106,12 +342,21 @@ pub struct TargetConfig {
     pub body_file: Option<String>,
     /// Body lines from file (one per request, round-robin)
     pub body_lines_file: Option<String>,
+    /// Compress request bodies (gzip, deflate)
+    pub compress_body: Option<String>,
+    /// Time DNS lookups, TCP connects or TLS handshakes instead of requests
+    pub probe: Option<String>,
     #[serde(default)]
     pub insecure: bool,
     #[serde(default)]
     pub http2: bool,
     #[serde(default)]
     pub cookie_jar: bool,
+    /// Netscape-format cookies.txt to seed the cookie jar from
+    pub cookie_file: Option<String>,
+    /// Cookies for the target host to seed the cookie jar with
+    #[serde(default)]
+    pub cookies: BTreeMap<String, String>,
     #[serde(default = "default_true")]
     pub follow_redirects: bool,
     /// HTTP/HTTPS/SOCKS5 proxy URL
@@ -124,44 +369,176 @@ pub struct TargetConfig {
     pub key: Option<String>,
     /// CA certificate file path (PEM format) for custom root CA
     pub cacert: Option<String>,
+    /// Directory of NAME.crt/NAME.key pairs, or a file listing CERT KEY per line
+    pub client_cert_pool: Option<String>,
+    /// How VUs get pool certificates (round-robin, unique)
+    pub client_cert_assign: Option<String>,
+    /// TLS server name sent instead of the URL's host
+    pub sni: Option<String>,
+    /// TLS implementation for HTTP (rustls, native)
+    pub tls_backend: Option<String>,
+    /// Trace context headers sent with each request (w3c, b3)
+    pub trace_propagation: Option<String>,
+    /// Header a unique ID is sent in and expected back in the response
+    pub request_id_header: Option<String>,
+    /// Slowest traced requests listed in the results
+    pub trace_slowest: Option<usize>,
     /// Multipart form fields (name=value or name=@filepath for files)
     #[serde(default)]
     pub form_data: Vec<String>,
     /// Disable HTTP keepalive (new connection per request)
     #[serde(default)]
     pub disable_keepalive: bool,
+    /// TCP_NODELAY (default: true)
+    pub tcp_nodelay: Option<bool>,
+    /// SO_SNDBUF in bytes for tcp://, redis:// and mqtt:// targets
+    pub send_buffer: Option<u32>,
+    /// SO_RCVBUF in bytes for tcp://, redis:// and mqtt:// targets
+    pub recv_buffer: Option<u32>,
+    /// Idle HTTP connections kept per host
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long idle HTTP connections stay pooled
+    #[serde(default, with = "humantime_serde::option")]
+    pub pool_idle_timeout: Option<Duration>,
+    /// Requests in flight at once across all VUs, and so HTTP/1.1 connections
+    pub max_connections: Option<u32>,
+    /// Media types a successful response should have (`type/*` for a whole type)
+    #[serde(default)]
+    pub expect_content_types: Vec<String>,
+    /// Address family to connect over (any, ipv4, ipv6)
+    pub ip_family: Option<String>,
+    /// Race the other address family when the first is slow (default: true)
+    pub happy_eyeballs: Option<bool>,
     /// Generate random URLs from regex pattern
     pub rand_regex_url: Option<String>,
     /// Read URLs from file (one per line, round-robin)
     pub urls_from_file: Option<String>,
     /// Override host resolution (HOST:PORT:TARGET_HOST:TARGET_PORT)
     pub connect_to: Option<String>,
+    /// Weighted base URLs to split requests across
+    #[serde(default)]
+    pub hosts: Vec<HostConfig>,
+    /// File of Host headers to spread requests over (one per line)
+    pub host_header_list: Option<String>,
+    /// File of proxy URLs to spread requests over (one per line)
+    pub proxy_list: Option<String>,
+    /// How the proxy list is rotated: vu or request
+    pub proxy_rotation: Option<String>,
+}
+
+/// `[[target.hosts]]`: a base URL, or a virtual host on the target's, that
+/// gets `weight` shares of the requests
+#[derive(Debug, Deserialize, Clone)]
+pub struct HostConfig {
+    pub url: Option<String>,
+    /// Host header to send instead of the URL's
+    pub host_header: Option<String>,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    /// Label in the per-host breakdown (default: the URL's host and port, or the Host header)
+    pub name: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
 pub struct LoadSettings {
     pub concurrency: Option<u32>,
+    /// Independent worker runtimes (0 = one per core)
+    pub threads: Option<u32>,
     #[serde(default, with = "humantime_serde::option")]
     pub duration: Option<Duration>,
     pub max_requests: Option<u64>,
     pub rate: Option<u32>,
+    /// Token bucket capacity for `rate`
+    pub rate_burst: Option<u32>,
     #[serde(default, with = "humantime_serde::option")]
     pub ramp_up: Option<Duration>,
     #[serde(default, with = "humantime_serde::option")]
     pub warmup: Option<Duration>,
+    /// End warmup once throughput and latency settle
+    #[serde(default)]
+    pub auto_warmup: bool,
+    /// Fixed ("500ms"), a range ("1s..3s"), "normal(2s, 500ms)" or "exp(2s)"
+    pub think_time: Option<String>,
+    /// Back off after 429 and 503 responses, for as long as Retry-After says
+    #[serde(default)]
+    pub honor_retry_after: bool,
+    /// Share of requests sent twice at once with the same idempotency key
+    pub duplicate_rate: Option<f64>,
+    /// Header the idempotency key is sent in
+    pub idempotency_header: Option<String>,
+    /// Revalidate responses with the ETag and Last-Modified they came with
+    #[serde(default)]
+    pub conditional_requests: bool,
+    /// Pause once every request has failed for this long, until the target recovers
+    #[serde(default, with = "humantime_serde::option")]
+    pub pause_on_errors: Option<Duration>,
+    /// Report availability, downtime and recovery per timeline bucket
+    #[serde(default)]
+    pub failover: bool,
+    /// Retry a request after a transport error, 5xx or 429, up to this many times
+    pub retries: Option<u32>,
+    /// Budget for a request across its retries and redirects
+    #[serde(default, with = "humantime_serde::option")]
+    pub deadline: Option<Duration>,
+    /// Seed for repeatable random choices
+    pub seed: Option<u64>,
+    /// Width of each results timeline bucket
     #[serde(default, with = "humantime_serde::option")]
-    pub think_time: Option<Duration>,
+    pub timeline_interval: Option<Duration>,
+    /// Example failures kept per error kind or status (0 = off)
+    pub error_samples: Option<usize>,
+    /// Apdex target time T; requests within T satisfy, within 4T tolerate
+    #[serde(default, with = "humantime_serde::option")]
+    pub apdex_t: Option<Duration>,
+    /// Latency percentiles to compute and report (e.g., [50, 90, 99, 99.99])
+    pub percentiles: Option<Vec<f64>>,
+    /// Log requests slower than a threshold as JSON Lines
+    pub slow_log: Option<SlowLogSettings>,
+    /// WASM plugins to run requests through, in order
+    pub plugins: Option<Vec<std::path::PathBuf>>,
     pub arrival_rate: Option<u32>,
     pub max_vus: Option<u32>,
+    /// File of 'offset,rate' points replayed as rate stages
+    pub rate_profile: Option<String>,
+    /// How long the rate profile replay takes
+    #[serde(default, with = "humantime_serde::option")]
+    pub rate_profile_window: Option<Duration>,
+    /// Grow and shed arrival-rate VUs on demand instead of up to max_vus
+    #[serde(default)]
+    pub vu_autoscale: bool,
+    /// VUs added per scale-up
+    pub vu_step: Option<u32>,
+    /// Least time between scale-ups
+    #[serde(default, with = "humantime_serde::option")]
+    pub vu_cooldown: Option<Duration>,
+    /// Most VUs autoscaling grows to
+    pub vu_cap: Option<u32>,
+    /// Shed VUs left unused this long
+    #[serde(default, with = "humantime_serde::option")]
+    pub vu_idle: Option<Duration>,
+    /// Result channel backpressure policy (block, drop, grow)
+    pub backpressure: Option<String>,
     /// Requests per burst (enables burst mode)
     pub burst_rate: Option<u32>,
     /// Delay between bursts
     #[serde(default, with = "humantime_serde::option")]
     pub burst_delay: Option<Duration>,
+    /// Requests per burst to ramp to by the end of the run
+    pub burst_ramp_to: Option<u32>,
+    /// Random ± spread on each delay between bursts
+    #[serde(default, with = "humantime_serde::option")]
+    pub burst_jitter: Option<Duration>,
     /// Push metrics to Prometheus Pushgateway URL
     pub prometheus_pushgateway: Option<String>,
     /// Expose Prometheus metrics on this port
     pub prometheus_port: Option<u16>,
+    /// `instance` label on Prometheus metrics
+    pub prometheus_instance: Option<String>,
+    /// Extra labels on every Prometheus metric
+    #[serde(default)]
+    pub prometheus_labels: BTreeMap<String, String>,
+    /// Bucket bounds in ms for the latency histogram
+    pub prometheus_buckets: Option<Vec<f64>>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -169,16 +546,42 @@ pub struct WebSocketConfig {
     /// Message send interval (e.g., "100ms")
     #[serde(default, with = "humantime_serde::option")]
     pub message_interval: Option<Duration>,
-    /// Mode: "echo" (default) or "fire_and_forget"
+    /// Mode: "echo" (default), "fire_and_forget", or "broadcast"
     #[serde(default)]
     pub mode: Option<String>,
+    /// Broadcast mode: number of publisher connections (default 1)
+    #[serde(default)]
+    pub publishers: Option<u32>,
+    /// Subprotocols sent as Sec-WebSocket-Protocol during the handshake
+    #[serde(default)]
+    pub subprotocols: Vec<String>,
+    /// Close and re-establish each connection at this interval (e.g., "30s")
+    #[serde(default, with = "humantime_serde::option")]
+    pub reconnect_every: Option<Duration>,
+    /// Percentage of connections to close and re-establish each second
+    #[serde(default)]
+    pub churn: Option<f64>,
+    /// Offer permessage-deflate compression
+    #[serde(default)]
+    pub compression: Option<bool>,
 }
 
 pub fn load_config(path: &Path) -> Result<TomlConfig, String> {
     let content =
         fs::read_to_string(path).map_err(|e| format!("Failed to read config file: {}", e))?;
+    let config = parse_config(&content)?;
+    if !config.has_includes() {
+        return Ok(config);
+    }
+
+    toml::Value::Table(load_table(path)?)
+        .try_into()
+        .map_err(|e| format!("Failed to parse config file: {}", e))
+}
 
-    let content = interpolate_env_vars(&content)?;
+/// Parse config file contents (environment variables are interpolated first)
+pub fn parse_config(content: &str) -> Result<TomlConfig, String> {
+    let content = interpolate_env_vars(content)?;
 
     toml::from_str(&content).map_err(|e| {
         let err_str = e.to_string();
@@ -188,7 +591,8 @@ pub fn load_config(path: &Path) -> Result<TomlConfig, String> {
                 "Unknown threshold metric in config file.\n\
                  Valid metrics: p50_latency_ms, p75_latency_ms, p90_latency_ms, p95_latency_ms,\n\
                  p99_latency_ms, p999_latency_ms, mean_latency_ms, max_latency_ms,\n\
-                 error_rate, rps, check_pass_rate\n\n\
+                 error_rate, rps, check_pass_rate, apdex, extraction_failure_rate,\n\
+                 unexpected_content_type\n\n\
                  Error: {}",
                 e
             )
@@ -198,8 +602,177 @@ pub fn load_config(path: &Path) -> Result<TomlConfig, String> {
     })
 }
 
+/// Read a config file's `[[tests]]`, each merged over the top-level sections
+pub fn load_tests(path: &Path) -> Result<Vec<NamedTest>, String> {
+    test_tables(load_table(path)?)?
+        .into_iter()
+        .map(|(name, table)| {
+            let config = toml::Value::Table(table)
+                .try_into()
+                .map_err(|e| format!("Invalid config for test '{}': {}", name, e))?;
+            Ok(NamedTest { name, config })
+        })
+        .collect()
+}
+
+/// Split `[[tests]]` out of a parsed config and layer each over the rest.
+/// Tables merge key by key (a test can override one threshold); anything
+/// else, including arrays like `scenarios`, is replaced outright.
+pub(crate) fn test_tables(mut base: toml::Table) -> Result<Vec<(String, toml::Table)>, String> {
+    let tests = match base.remove("tests") {
+        Some(toml::Value::Array(tests)) => tests,
+        Some(_) => return Err("'tests' must be an array of tables ([[tests]])".to_string()),
+        None => return Ok(Vec::new()),
+    };
+
+    let mut expanded: Vec<(String, toml::Table)> = Vec::with_capacity(tests.len());
+    for (i, test) in tests.into_iter().enumerate() {
+        let toml::Value::Table(mut test) = test else {
+            return Err(format!("tests[{}] must be a table", i));
+        };
+        let name = match test.remove("name") {
+            Some(toml::Value::String(name)) if !name.is_empty() => name,
+            _ => return Err(format!("tests[{}] needs a name", i)),
+        };
+        if name == "all" {
+            return Err("'all' is reserved for running every test".to_string());
+        }
+        if expanded.iter().any(|(n, _)| *n == name) {
+            return Err(format!("Duplicate test name '{}'", name));
+        }
+        let mut merged = base.clone();
+        merge_tables(&mut merged, test);
+        expanded.push((name, merged));
+    }
+    Ok(expanded)
+}
+
+/// Parse a config file with the files it names in `extends` / `include`
+/// layered underneath it. Include paths are relative to the including file.
+pub(crate) fn load_table(path: &Path) -> Result<toml::Table, String> {
+    load_table_inner(path, &mut Vec::new())
+}
+
+fn load_table_inner(
+    path: &Path,
+    chain: &mut Vec<std::path::PathBuf>,
+) -> Result<toml::Table, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read config file '{}': {}", path.display(), e))?;
+    let table: toml::Table = interpolate_env_vars(&content)?
+        .parse()
+        .map_err(|e| format!("Failed to parse config file '{}': {}", path.display(), e))?;
+
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if chain.contains(&canonical) {
+        return Err(format!(
+            "Config include cycle: '{}' is already being included",
+            path.display()
+        ));
+    }
+    chain.push(canonical);
+    let resolved = resolve_includes(table, path, chain);
+    chain.pop();
+    resolved
+}
+
+/// Replace `extends` / `include` in a parsed config with the files they name
+pub(crate) fn resolve_includes(
+    mut table: toml::Table,
+    path: &Path,
+    chain: &mut Vec<std::path::PathBuf>,
+) -> Result<toml::Table, String> {
+    let mut includes = Vec::new();
+    match table.remove("extends") {
+        Some(toml::Value::String(base)) => includes.push(base),
+        Some(_) => return Err("'extends' must be a file path".to_string()),
+        None => {}
+    }
+    match table.remove("include") {
+        Some(toml::Value::Array(files)) => {
+            for file in files {
+                match file {
+                    toml::Value::String(file) => includes.push(file),
+                    _ => return Err("'include' must be a list of file paths".to_string()),
+                }
+            }
+        }
+        Some(_) => return Err("'include' must be a list of file paths".to_string()),
+        None => {}
+    }
+    if includes.is_empty() {
+        return Ok(table);
+    }
+
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut merged = toml::Table::new();
+    for include in includes {
+        let included = load_table_inner(&dir.join(&include), chain)
+            .map_err(|e| format!("In '{}': {}", path.display(), e))?;
+        layer_tables(&mut merged, included);
+    }
+    layer_tables(&mut merged, table);
+    Ok(merged)
+}
+
+/// Sections whose entries accumulate across included files instead of being replaced
+const ACCUMULATED_SECTIONS: &[&str] = &["scenarios", "checks", "tests"];
+
+/// Merge an including file over what it includes: like `merge_tables`, except
+/// scenarios, checks and tests add to the included ones
+fn layer_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Array(existing)), toml::Value::Array(value))
+                if ACCUMULATED_SECTIONS.contains(&key.as_str()) =>
+            {
+                existing.extend(value)
+            }
+            (Some(toml::Value::Table(existing)), toml::Value::Table(value)) => {
+                merge_tables(existing, value)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(value)) => {
+                merge_tables(existing, value)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Variables substituted per request by the workers, not from the environment
+pub(crate) const RUNTIME_VARS: &[&str] = &["REQUEST_ID", "TIMESTAMP_MS"];
+
+/// Whether `${name}` is filled in at request time (built-ins and extracted values)
+pub(crate) fn is_runtime_var(name: &str) -> bool {
+    // Extracted values use lowercase names; env vars are typically UPPER_CASE
+    RUNTIME_VARS.contains(&name) || name.chars().all(|c| c.is_lowercase() || c == '_')
+}
+
 fn interpolate_env_vars(content: &str) -> Result<String, String> {
+    let (result, missing) = substitute_env_vars(content);
+    match missing.first() {
+        Some(name) => Err(format!("Environment variable '{}' not set", name)),
+        None => Ok(result),
+    }
+}
+
+/// Substitute `${VAR}` and `${VAR:-default}` from the environment. Unset
+/// variables without a default are left in place and returned by name.
+pub(crate) fn substitute_env_vars(content: &str) -> (String, Vec<String>) {
     let mut result = content.to_string();
+    let mut missing = Vec::new();
     let re = regex_lite::Regex::new(r"\$\{([^}]+)\}").unwrap();
 
     for cap in re.captures_iter(content) {
@@ -212,9 +785,7 @@ fn interpolate_env_vars(content: &str) -> Result<String, String> {
             (var_expr, None)
         };
 
-        // Skip runtime variables (lowercase names like extracted values)
-        // Only substitute env vars (typically UPPER_CASE)
-        if var_name.chars().all(|c| c.is_lowercase() || c == '_') {
+        if is_runtime_var(var_name) {
             continue; // Leave runtime variables unchanged
         }
 
@@ -222,14 +793,19 @@ fn interpolate_env_vars(content: &str) -> Result<String, String> {
             Ok(v) => v,
             Err(_) => match default {
                 Some(d) => d.to_string(),
-                None => return Err(format!("Environment variable '{}' not set", var_name)),
+                None => {
+                    if !missing.iter().any(|m| m == var_name) {
+                        missing.push(var_name.to_string());
+                    }
+                    continue;
+                }
             },
         };
 
         result = result.replace(full_match, &value);
     }
 
-    Ok(result)
+    (result, missing)
 }
 
 #[allow(clippy::manual_map)]
@@ -237,6 +813,8 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
     let toml = toml.unwrap_or_default();
 
     let has_scenarios = !toml.scenarios.is_empty();
+    let stdin = stdin_stream(args, &toml.target)?;
+    let streams = |input| stdin.as_ref().is_some_and(|s| s.input == input);
 
     // URL can come from: regular URL arg, rand_regex_url, first line of urls_from_file, or config
     let url = args
@@ -251,6 +829,12 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
                     .and_then(|content| content.lines().next().map(String::from))
             })
         })
+        .or_else(|| {
+            stdin
+                .as_ref()
+                .filter(|s| s.input == StdinInput::Urls)
+                .map(|s| s.first.clone())
+        })
         .or(toml.target.url)
         .or(toml.target.rand_regex_url.clone())
         .or_else(|| {
@@ -325,6 +909,7 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
     } else {
         toml.load.concurrency.unwrap_or(50)
     };
+    let threads = args.threads.or(toml.load.threads);
 
     let duration = if args.duration != Duration::from_secs(10) {
         args.duration
@@ -344,6 +929,16 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
         toml.load.rate.unwrap_or(0)
     };
 
+    let rate_burst = args.rate_burst.or(toml.load.rate_burst);
+    if let Some(burst) = rate_burst {
+        if rate == 0 {
+            return Err("--rate-burst needs a rate limit (--rate)".to_string());
+        }
+        if burst == 0 {
+            return Err("rate_burst must be at least 1".to_string());
+        }
+    }
+
     let ramp_up = if args.ramp_up != Duration::ZERO {
         args.ramp_up
     } else {
@@ -374,19 +969,116 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
     let http2 = args.http2 || toml.target.http2;
     #[cfg(feature = "http3")]
     let http3 = args.http3;
+    #[cfg(feature = "http3")]
+    let http3_0rtt = args.http3_0rtt;
+    #[cfg(feature = "http3")]
+    let protocol_race = match args.protocol_race.as_deref() {
+        None => None,
+        Some("split") => Some(crate::types::ProtocolRace::Split),
+        Some("sequential") => Some(crate::types::ProtocolRace::Sequential),
+        Some(other) => {
+            return Err(format!(
+                "Invalid --protocol-race mode '{}' (expected split or sequential)",
+                other
+            ));
+        }
+    };
     #[cfg(feature = "grpc")]
     let grpc_service = args.grpc_service.clone();
     #[cfg(feature = "grpc")]
     let grpc_method = args.grpc_method.clone();
+    #[cfg(feature = "socketio")]
+    let socketio_event = args.socketio_event.clone();
+    #[cfg(feature = "socketio")]
+    let socketio_namespace = args.socketio_namespace.clone();
     let cookie_jar = args.cookie_jar || toml.target.cookie_jar;
     let follow_redirects = !args.no_follow_redirects && toml.target.follow_redirects;
     let disable_keepalive = args.disable_keepalive || toml.target.disable_keepalive;
 
+    // Socket and connection pool tuning
+    let mut socket = SocketOptions {
+        nodelay: !args.no_tcp_nodelay && toml.target.tcp_nodelay.unwrap_or(true),
+        send_buffer: args.send_buffer.or(toml.target.send_buffer),
+        recv_buffer: args.recv_buffer.or(toml.target.recv_buffer),
+        pool_max_idle_per_host: args
+            .pool_max_idle_per_host
+            .or(toml.target.pool_max_idle_per_host),
+        pool_idle_timeout: args
+            .pool_idle_timeout
+            .or(toml.target.pool_idle_timeout)
+            .unwrap_or(Duration::from_secs(30)),
+        ip_family: if args.ipv4 {
+            IpFamily::V4
+        } else if args.ipv6 {
+            IpFamily::V6
+        } else {
+            match toml.target.ip_family.as_deref() {
+                None | Some("any") => IpFamily::Any,
+                Some("ipv4") => IpFamily::V4,
+                Some("ipv6") => IpFamily::V6,
+                Some(other) => {
+                    return Err(format!(
+                        "Invalid ip_family '{}' (expected any, ipv4 or ipv6)",
+                        other
+                    ));
+                }
+            }
+        },
+        happy_eyeballs: !args.no_happy_eyeballs && toml.target.happy_eyeballs.unwrap_or(true),
+    };
+    if socket.send_buffer == Some(0) || socket.recv_buffer == Some(0) {
+        return Err("Socket buffer sizes must be at least 1 byte".to_string());
+    }
+    // reqwest has no hook for SO_SNDBUF/SO_RCVBUF, so only sockets kaioken
+    // dials itself can take them
+    if socket.has_buffer_sizes()
+        && !["tcp://", "redis://", "mqtt://"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme))
+    {
+        return Err(
+            "--send-buffer and --recv-buffer apply to tcp://, redis:// and mqtt:// targets; the HTTP client does not expose socket buffer sizes"
+                .to_string(),
+        );
+    }
+    if disable_keepalive
+        && (socket.pool_max_idle_per_host.is_some()
+            || args.pool_idle_timeout.is_some()
+            || toml.target.pool_idle_timeout.is_some())
+    {
+        return Err("Connection pool settings have no effect with --disable-keepalive".to_string());
+    }
+    // A capped client never has more connections to keep idle than the cap
+    let max_connections = args.max_connections.or(toml.target.max_connections);
+    if let Some(limit) = max_connections
+        && !disable_keepalive
+    {
+        socket.pool_max_idle_per_host.get_or_insert(limit as usize);
+    }
+
     // Validate HTTP/3 requires HTTPS
     #[cfg(feature = "http3")]
     if http3 && !url.starts_with("https://") {
         return Err("HTTP/3 requires HTTPS URL (https://)".to_string());
     }
+    #[cfg(feature = "http3")]
+    if http3_0rtt && !http3 {
+        return Err("--http3-0rtt requires --http3".to_string());
+    }
+    #[cfg(feature = "http3")]
+    if let Some(race) = protocol_race {
+        if http2 || http3 {
+            return Err(
+                "--protocol-race runs both HTTP/2 and HTTP/3; drop --http2/--http3".to_string(),
+            );
+        }
+        if !url.starts_with("https://") {
+            return Err("--protocol-race requires an HTTPS URL (https://)".to_string());
+        }
+        if race == crate::types::ProtocolRace::Split && concurrency < 2 {
+            return Err("--protocol-race split needs at least 2 VUs (-c)".to_string());
+        }
+    }
 
     // Validate gRPC configuration
     #[cfg(feature = "grpc")]
@@ -417,6 +1109,21 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
         }
     }
 
+    // Validate Socket.IO configuration
+    #[cfg(feature = "socketio")]
+    if let Some(ref event) = socketio_event {
+        if event.is_empty() {
+            return Err("--socketio-event cannot be empty".to_string());
+        }
+        if !socketio_namespace.starts_with('/') {
+            return Err(format!(
+                "Socket.IO namespace must start with '/': {}",
+                socketio_namespace
+            ));
+        }
+        crate::socketio::socketio_url(&url)?;
+    }
+
     // Detect protocol conflicts (HTTP/3 + gRPC)
     #[cfg(all(feature = "http3", feature = "grpc"))]
     {
@@ -427,6 +1134,9 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
         if http3 && has_grpc {
             return Err("Cannot use --http3 with --grpc-service. Choose one protocol.".to_string());
         }
+        if protocol_race.is_some() && has_grpc {
+            return Err("Cannot use --protocol-race with --grpc-service".to_string());
+        }
     }
 
     // Load body as binary bytes for gRPC mode (supports binary protobuf)
@@ -463,17 +1173,99 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
     // Process scenarios
     let scenarios = process_scenarios(&toml.scenarios)?;
 
-    // Process thresholds
-    let thresholds = parse_thresholds(&toml.thresholds)?;
+    // Process thresholds; a --threshold replaces the config's for its metric
+    let (mut cli_thresholds, mut cli_trends) = (Vec::new(), Vec::new());
+    for threshold in &args.thresholds {
+        match parse_cli_threshold(threshold)? {
+            CliThreshold::Value(t) => cli_thresholds.push(t),
+            CliThreshold::Trend(t) => cli_trends.push(t),
+        }
+    }
+    let mut thresholds = parse_thresholds(&toml.thresholds)?;
+    thresholds.retain(|t| !cli_thresholds.iter().any(|c| c.metric == t.metric));
+    thresholds.extend(cli_thresholds);
+    let mut trend_thresholds = parse_trend_thresholds(&toml.thresholds)?;
+    trend_thresholds.retain(|t| !cli_trends.iter().any(|c| c.metric == t.metric));
+    trend_thresholds.extend(cli_trends);
 
     // Process checks
     let checks = parse_checks(&toml.checks)?;
 
     // Process stages
-    let stages = process_stages(&toml.stages)?;
+    let window = args.rate_profile_window.or(toml.load.rate_profile_window);
+    let (stages, rate_profile) = match args
+        .rate_profile
+        .as_ref()
+        .or(toml.load.rate_profile.as_ref())
+    {
+        Some(path) => {
+            if !toml.stages.is_empty() {
+                return Err("A rate profile replaces [[stages]]; use one or the other".to_string());
+            }
+            if window.is_some_and(|w| w.is_zero()) {
+                return Err("rate_profile_window must be greater than zero".to_string());
+            }
+            let (stages, profile) = read_rate_profile(path, window)?;
+            (stages, Some(profile))
+        }
+        None => {
+            if window.is_some() {
+                return Err("rate_profile_window needs a rate_profile".to_string());
+            }
+            (process_stages(&toml.stages)?, None)
+        }
+    };
 
     // Think time - CLI takes precedence
-    let think_time = args.think_time.or(toml.load.think_time);
+    let think_time = args
+        .think_time
+        .as_deref()
+        .or(toml.load.think_time.as_deref())
+        .map(parse_think_time)
+        .transpose()?;
+
+    // Results are collected every 100ms, so finer buckets would be mostly empty
+    let timeline_interval = args
+        .timeline_interval
+        .or(toml.load.timeline_interval)
+        .unwrap_or(Duration::from_secs(1));
+    if timeline_interval < Duration::from_millis(100) {
+        return Err(format!(
+            "Timeline interval must be at least 100ms, got {}",
+            humantime::format_duration(timeline_interval)
+        ));
+    }
+
+    let error_samples = args.error_samples.or(toml.load.error_samples).unwrap_or(3);
+
+    let apdex_t = args.apdex_t.or(toml.load.apdex_t);
+    if apdex_t.is_some_and(|t| t.is_zero()) {
+        return Err("apdex_t must be greater than zero".to_string());
+    }
+    if apdex_t.is_none()
+        && thresholds
+            .iter()
+            .any(|t| t.metric == ThresholdMetric::Apdex)
+    {
+        return Err(
+            "The apdex threshold needs a target time: set apdex_t or --apdex-t".to_string(),
+        );
+    }
+
+    let percentiles = match args.percentiles.clone().or(toml.load.percentiles.clone()) {
+        Some(list) => parse_percentiles(list)?,
+        None => DEFAULT_PERCENTILES.to_vec(),
+    };
+    for threshold in &thresholds {
+        if let Some(p) = threshold.metric.percentile()
+            && !percentiles.contains(&p)
+        {
+            return Err(format!(
+                "The {} threshold needs {} in percentiles",
+                threshold.metric, p
+            ));
+        }
+    }
 
     // Fail fast
     let fail_fast = args.fail_fast;
@@ -486,6 +1278,24 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
         toml.load.max_vus.or(Some(100))
     };
 
+    let backpressure = match args
+        .backpressure
+        .as_deref()
+        .or(toml.load.backpressure.as_deref())
+    {
+        None => crate::types::BackpressurePolicy::default(),
+        Some(s) => crate::types::BackpressurePolicy::parse(s).ok_or_else(|| {
+            format!(
+                "Invalid backpressure policy '{}' (expected block, drop or grow)",
+                s
+            )
+        })?,
+    };
+
+    if rate_profile.is_some() && arrival_rate.is_some() {
+        return Err("A rate profile sets the arrival rate; drop --arrival-rate".to_string());
+    }
+
     // Validate: can't use arrival_rate with VU-based stages
     if arrival_rate.is_some() && !stages.is_empty() && stages.iter().any(|s| s.target.is_some()) {
         return Err(
@@ -494,31 +1304,148 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
         );
     }
 
-    // Auto-enable latency correction for arrival rate mode (unless explicitly disabled)
-    let latency_correction = !args.no_latency_correction
-        && (arrival_rate.is_some() || stages.iter().any(|s| s.target_rate.is_some()));
-
-    // WebSocket config - CLI takes precedence
-    let ws_message_interval = if args.ws_message_interval != Duration::from_millis(100) {
-        args.ws_message_interval
-    } else {
-        toml.websocket
+    let vu_autoscale = if args.vu_autoscale || toml.load.vu_autoscale {
+        if arrival_rate.is_none() && !stages.iter().any(|s| s.target_rate.is_some()) {
+            return Err(
+                "--vu-autoscale needs arrival rate mode (--arrival-rate or stages with target_rate)"
+                    .to_string(),
+            );
+        }
+        let step = args.vu_step.or(toml.load.vu_step).unwrap_or(10);
+        let cap = args.vu_cap.or(toml.load.vu_cap).unwrap_or(1000);
+        if step == 0 || cap == 0 {
+            return Err("vu_step and vu_cap must be at least 1".to_string());
+        }
+        Some(crate::types::VuAutoscale {
+            step,
+            cooldown: args
+                .vu_cooldown
+                .or(toml.load.vu_cooldown)
+                .unwrap_or(Duration::from_secs(1)),
+            cap,
+            idle: args
+                .vu_idle
+                .or(toml.load.vu_idle)
+                .unwrap_or(Duration::from_secs(10)),
+        })
+    } else {
+        if toml.load.vu_step.is_some()
+            || toml.load.vu_cooldown.is_some()
+            || toml.load.vu_cap.is_some()
+            || toml.load.vu_idle.is_some()
+        {
+            return Err(
+                "vu_step, vu_cooldown, vu_cap and vu_idle need vu_autoscale = true".to_string(),
+            );
+        }
+        None
+    };
+
+    // Auto-enable latency correction for arrival rate mode (unless explicitly disabled)
+    let latency_correction = !args.no_latency_correction
+        && (arrival_rate.is_some() || stages.iter().any(|s| s.target_rate.is_some()));
+
+    // WebSocket config - CLI takes precedence
+    let ws_message_interval = if args.ws_message_interval != Duration::from_millis(100) {
+        args.ws_message_interval
+    } else {
+        toml.websocket
             .message_interval
             .unwrap_or(Duration::from_millis(100))
     };
 
     let ws_mode = if args.ws_fire_and_forget {
         crate::types::WsMode::FireAndForget
+    } else if args.ws_publishers.is_some() {
+        crate::types::WsMode::Broadcast
     } else {
         match toml.websocket.mode.as_deref() {
             Some("fire_and_forget") => crate::types::WsMode::FireAndForget,
+            Some("broadcast") => crate::types::WsMode::Broadcast,
             _ => crate::types::WsMode::Echo,
         }
     };
 
+    // Broadcast mode: publishers send tagged messages, everyone else subscribes
+    let ws_publishers = args
+        .ws_publishers
+        .or(toml.websocket.publishers)
+        .unwrap_or(1);
+    if ws_mode == crate::types::WsMode::Broadcast {
+        if ws_publishers == 0 || ws_publishers >= concurrency {
+            return Err(format!(
+                "Broadcast mode needs between 1 and {} publishers (concurrency {}) so at least one connection subscribes",
+                concurrency.saturating_sub(1),
+                concurrency
+            ));
+        }
+        if let Some(ref body) = body
+            && !body.contains("${BROADCAST_TAG}")
+        {
+            return Err(
+                "Broadcast mode requires ${BROADCAST_TAG} in the message body so subscribers can match deliveries"
+                    .to_string(),
+            );
+        }
+    }
+
+    let ws_subprotocols = if !args.ws_subprotocols.is_empty() {
+        args.ws_subprotocols.clone()
+    } else {
+        toml.websocket.subprotocols
+    };
+
+    // Churn is set as an interval or a percentage per second; flags replace
+    // whichever the config file sets
+    let (ws_reconnect_every, ws_churn) =
+        if args.ws_reconnect_every.is_some() || args.ws_churn.is_some() {
+            (args.ws_reconnect_every, args.ws_churn)
+        } else {
+            (toml.websocket.reconnect_every, toml.websocket.churn)
+        };
+    if ws_reconnect_every.is_some() && ws_churn.is_some() {
+        return Err("Set either ws_reconnect_every or ws_churn, not both".to_string());
+    }
+    if ws_reconnect_every.is_some_and(|d| d.is_zero()) {
+        return Err("ws_reconnect_every must be greater than zero".to_string());
+    }
+    if ws_churn.is_some_and(|percent| !(percent > 0.0 && percent <= 100.0)) {
+        return Err("ws_churn must be a percentage above 0 and at most 100".to_string());
+    }
+    // Each connection lives 100 / percent seconds, so that share of them
+    // reconnects every second
+    let ws_reconnect_every = ws_reconnect_every
+        .or_else(|| ws_churn.map(|percent| Duration::from_secs_f64(100.0 / percent)));
+
+    let ws_compression = args.ws_compression || toml.websocket.compression.unwrap_or(false);
+
+    // Message-rate stages ramp the per-connection send rate of a WebSocket target
+    if stages.iter().any(|s| s.target_message_rate.is_some())
+        && !(url.starts_with("ws://") || url.starts_with("wss://"))
+    {
+        return Err(
+            "Message-rate stages (target_message_rate) require a ws:// or wss:// target URL"
+                .to_string(),
+        );
+    }
+
     // Proxy - CLI takes precedence
     let proxy = args.proxy.clone().or(toml.target.proxy);
 
+    // QUIC runs over UDP, which HTTP and SOCKS proxies can't tunnel
+    #[cfg(feature = "http3")]
+    if http3 || protocol_race.is_some() {
+        if proxy.is_some() {
+            return Err("--proxy is not supported with --http3 (QUIC cannot be tunnelled through HTTP/SOCKS proxies)".to_string());
+        }
+        if let Some(scenario) = scenarios.iter().find(|s| !s.url.starts_with("https://")) {
+            return Err(format!(
+                "Scenario '{}': HTTP/3 requires HTTPS URL (https://)",
+                scenario.name
+            ));
+        }
+    }
+
     // Basic auth - CLI takes precedence
     let basic_auth = if let Some(ref auth_str) = args.basic_auth {
         Some(parse_basic_auth(auth_str)?)
@@ -567,6 +1494,97 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
         return Err(format!("CA certificate file not found: {}", path.display()));
     }
 
+    // TLS backend - CLI takes precedence
+    let tls_backend = match args
+        .tls_backend
+        .as_deref()
+        .or(toml.target.tls_backend.as_deref())
+    {
+        None | Some("rustls") => crate::types::TlsBackend::Rustls,
+        #[cfg(feature = "native-tls")]
+        Some("native") => crate::types::TlsBackend::Native,
+        #[cfg(not(feature = "native-tls"))]
+        Some("native") => {
+            return Err(
+                "--tls-backend native requires kaioken built with --features native-tls"
+                    .to_string(),
+            );
+        }
+        Some(other) => {
+            return Err(format!(
+                "Invalid --tls-backend '{}' (expected rustls or native)",
+                other
+            ));
+        }
+    };
+    // Trace propagation - CLI takes precedence
+    let trace_propagation =
+        match args
+            .trace_propagation
+            .as_deref()
+            .or(toml.target.trace_propagation.as_deref())
+        {
+            None => None,
+            Some(s) => Some(crate::types::TracePropagation::parse(s).ok_or_else(|| {
+                format!("Invalid --trace-propagation '{}' (expected w3c or b3)", s)
+            })?),
+        };
+    if trace_propagation.is_some() && !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("--trace-propagation only applies to http:// and https:// targets".to_string());
+    }
+    #[cfg(feature = "http3")]
+    if trace_propagation.is_some() && (http3 || protocol_race.is_some()) {
+        return Err("--trace-propagation is not supported with --http3".to_string());
+    }
+    #[cfg(feature = "grpc")]
+    if trace_propagation.is_some() && grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+        return Err("--trace-propagation is not supported with --grpc-service".to_string());
+    }
+    let request_id_header = args
+        .request_id_header
+        .clone()
+        .or(toml.target.request_id_header.clone());
+    if let Some(ref name) = request_id_header {
+        if reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err() {
+            return Err(format!("Invalid --request-id-header '{}'", name));
+        }
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(
+                "--request-id-header only applies to http:// and https:// targets".to_string(),
+            );
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--request-id-header is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--request-id-header is not supported with --grpc-service".to_string());
+        }
+    }
+    let trace_slowest = args
+        .trace_slowest
+        .or(toml.target.trace_slowest)
+        .unwrap_or(10);
+
+    let native_tls = tls_backend != crate::types::TlsBackend::Rustls;
+    if native_tls && !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(
+            "--tls-backend native only applies to http:// and https:// targets".to_string(),
+        );
+    }
+    #[cfg(feature = "http3")]
+    if native_tls && (http3 || protocol_race.is_some()) {
+        return Err(
+            "--tls-backend native is not supported with --http3 (QUIC always uses rustls)"
+                .to_string(),
+        );
+    }
+    #[cfg(feature = "grpc")]
+    if native_tls && grpc_service.is_some() {
+        return Err("--tls-backend native is not supported in gRPC mode".to_string());
+    }
+
     // Multipart form fields - combine CLI args and config
     let mut form_fields = Vec::new();
     for field_str in &args.form {
@@ -583,6 +1601,35 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
         return Err("--form and --body are mutually exclusive. Use one or the other.".to_string());
     }
 
+    // HTTP/3 requests are built without reqwest's multipart and auth support
+    #[cfg(feature = "http3")]
+    if http3 && (!form_fields.is_empty() || basic_auth.is_some()) {
+        return Err("--form and --basic-auth are not supported with --http3".to_string());
+    }
+
+    let compress_body = match args
+        .compress_body
+        .as_deref()
+        .or(toml.target.compress_body.as_deref())
+    {
+        None => None,
+        Some(s) => Some(BodyCompression::parse(s).ok_or_else(|| {
+            format!("Invalid --compress-body '{}' (expected gzip or deflate)", s)
+        })?),
+    };
+    if compress_body.is_some() {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("--compress-body only applies to http:// and https:// targets".to_string());
+        }
+        if !form_fields.is_empty() {
+            return Err("--compress-body can't be combined with --form".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--compress-body is not supported with --http3".to_string());
+        }
+    }
+
     // Validate file paths in form fields exist
     for field in &form_fields {
         if let FormField::File { path, name, .. } = field
@@ -602,7 +1649,9 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
     let rand_regex_url = args.rand_regex_url.clone().or(toml.target.rand_regex_url);
 
     // Load URLs from file
-    let url_list: Option<Vec<String>> = if let Some(ref path) = args.urls_from_file {
+    let url_list: Option<Vec<String>> = if streams(StdinInput::Urls) {
+        None
+    } else if let Some(ref path) = args.urls_from_file {
         let content = fs::read_to_string(path)
             .map_err(|e| format!("Failed to read URLs file '{}': {}", path.display(), e))?;
         let urls: Vec<String> = content
@@ -633,12 +1682,14 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
     };
 
     // Validate: rand_regex_url and urls_from_file are mutually exclusive
-    if rand_regex_url.is_some() && url_list.is_some() {
+    if rand_regex_url.is_some() && (url_list.is_some() || streams(StdinInput::Urls)) {
         return Err("--rand-regex-url and --urls-from-file are mutually exclusive".to_string());
     }
 
     // Load body lines from file
-    let body_lines: Option<Vec<String>> = if let Some(ref path) = args.body_lines_file {
+    let body_lines: Option<Vec<String>> = if streams(StdinInput::BodyLines) {
+        None
+    } else if let Some(ref path) = args.body_lines_file {
         let content = fs::read_to_string(path)
             .map_err(|e| format!("Failed to read body lines file '{}': {}", path.display(), e))?;
         let lines: Vec<String> = content.lines().map(String::from).collect();
@@ -659,10 +1710,70 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
     };
 
     // Validate: body_lines and body/body_file are mutually exclusive
-    if body_lines.is_some() && body.is_some() {
+    if (body_lines.is_some() || streams(StdinInput::BodyLines)) && body.is_some() {
         return Err("-Z/--body-lines and --body/--body-file are mutually exclusive".to_string());
     }
 
+    // JSON-RPC mode: wrap method/params into the 2.0 envelope and POST it as JSON
+    let jsonrpc = if let Some(ref method) = args.jsonrpc_method {
+        let params = args
+            .jsonrpc_params
+            .as_deref()
+            .map(serde_json::from_str::<serde_json::Value>)
+            .transpose()
+            .map_err(|e| format!("Invalid --jsonrpc-params JSON: {}", e))?;
+        Some((method.clone(), params))
+    } else if let Some(ref section) = toml.jsonrpc {
+        let params = section
+            .params
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| format!("Invalid [jsonrpc] params: {}", e))?;
+        Some((section.method.clone(), params))
+    } else {
+        None
+    };
+    let (method, body, jsonrpc_method) = if let Some((rpc_method, params)) = jsonrpc {
+        if rpc_method.is_empty() {
+            return Err("JSON-RPC method cannot be empty".to_string());
+        }
+        if let Some(ref params) = params {
+            crate::http::jsonrpc::validate_params(params)?;
+        }
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("JSON-RPC mode requires an http:// or https:// target".to_string());
+        }
+        if body.is_some() || body_lines.is_some() || !form_fields.is_empty() {
+            return Err(
+                "JSON-RPC mode builds the request body; drop --body, --body-file, --body-lines and --form"
+                    .to_string(),
+            );
+        }
+        if !scenarios.is_empty() {
+            return Err("JSON-RPC mode does not support [[scenarios]]".to_string());
+        }
+        if is_grpc_mode {
+            return Err("Cannot use JSON-RPC mode with --grpc-service".to_string());
+        }
+        if method != reqwest::Method::GET && method != reqwest::Method::POST {
+            return Err(format!(
+                "JSON-RPC requests are sent with POST, not {}",
+                method
+            ));
+        }
+        if !headers
+            .iter()
+            .any(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        {
+            headers.push(("Content-Type".to_string(), "application/json".to_string()));
+        }
+        let body = crate::http::jsonrpc::envelope(&rpc_method, params.as_ref());
+        (reqwest::Method::POST, Some(body), Some(rpc_method))
+    } else {
+        (method, body, None)
+    };
+
     // Parse connect_to (HOST:PORT:TARGET_HOST:TARGET_PORT or HOST:TARGET_IP:TARGET_PORT)
     let connect_to: Option<(String, std::net::SocketAddr)> =
         if let Some(ref mapping) = args.connect_to {
@@ -673,6 +1784,55 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
             None
         };
 
+    // Address family: kaioken resolves for HTTP, HTTP/3 and its own sockets,
+    // but not for WebSocket, Socket.IO or gRPC connections
+    if socket.filters_addresses() {
+        let flag = match socket.ip_family {
+            IpFamily::V4 => "--ipv4",
+            IpFamily::V6 => "--ipv6",
+            IpFamily::Any => "--no-happy-eyeballs",
+        };
+        if url.starts_with("ws://")
+            || url.starts_with("wss://")
+            || scenarios.iter().any(|s| s.is_websocket())
+        {
+            return Err(format!("{} is not supported for WebSocket targets", flag));
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err(format!("{} is not supported with --grpc-service", flag));
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err(format!("{} is not supported with --socketio-event", flag));
+        }
+        // IP literals and --connect-to skip DNS, so they have to match already
+        let literal = url
+            .split_once("://")
+            .and_then(|(_, rest)| rest.split(['/', '?', '#']).next())
+            .and_then(|authority| {
+                authority
+                    .parse::<std::net::SocketAddr>()
+                    .map(|a| a.ip())
+                    .or_else(|_| {
+                        authority
+                            .trim_start_matches('[')
+                            .trim_end_matches(']')
+                            .parse::<std::net::IpAddr>()
+                    })
+                    .ok()
+            });
+        let addrs = literal
+            .map(|ip| std::net::SocketAddr::new(ip, 0))
+            .into_iter()
+            .chain(connect_to.as_ref().map(|(_, addr)| *addr));
+        for addr in addrs {
+            if !socket.ip_family.allows(&addr) {
+                return Err(format!("{} conflicts with the address {}", flag, addr.ip()));
+            }
+        }
+    }
+
     // Burst mode configuration
     let burst_config = if let Some(burst_rate) = args.burst_rate {
         let burst_delay = args
@@ -681,6 +1841,8 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
         Some(BurstConfig {
             requests_per_burst: burst_rate,
             delay_between_bursts: burst_delay,
+            ramp_to: args.burst_ramp_to,
+            jitter: args.burst_jitter.unwrap_or_default(),
         })
     } else if let Some(burst_rate) = toml.load.burst_rate {
         let burst_delay = toml
@@ -690,31 +1852,1083 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
         Some(BurstConfig {
             requests_per_burst: burst_rate,
             delay_between_bursts: burst_delay,
+            ramp_to: toml.load.burst_ramp_to,
+            jitter: toml.load.burst_jitter.unwrap_or_default(),
         })
     } else {
-        None
+        None
+    };
+    if let Some(ref burst) = burst_config
+        && burst.jitter > burst.delay_between_bursts
+    {
+        return Err(format!(
+            "Burst jitter ({:?}) can't be longer than the delay between bursts ({:?})",
+            burst.jitter, burst.delay_between_bursts
+        ));
+    }
+
+    // Validate: burst mode is incompatible with arrival rate
+    if burst_config.is_some() && arrival_rate.is_some() {
+        return Err("Burst mode (--burst-rate) is incompatible with --arrival-rate".to_string());
+    }
+
+    // Auto warmup watches the HTTP aggregator, in the VU and arrival-rate executors
+    let auto_warmup = args.auto_warmup || toml.load.auto_warmup;
+    if auto_warmup {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("--auto-warmup only applies to http:// and https:// targets".to_string());
+        }
+        if burst_config.is_some() || !stages.is_empty() {
+            return Err("--auto-warmup is not supported with burst mode or stages".to_string());
+        }
+        if scenarios.iter().any(|s| s.is_websocket()) {
+            return Err("--auto-warmup is not supported with WebSocket scenarios".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--auto-warmup is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--auto-warmup is not supported with --grpc-service".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err("--auto-warmup is not supported with --socketio-event".to_string());
+        }
+    }
+    // The slow log is written by the HTTP VU and arrival-rate executors
+    let slow_log = match args.slow_log.as_deref() {
+        Some([threshold, path]) => Some(SlowLogConfig {
+            threshold: humantime::parse_duration(threshold)
+                .map_err(|e| format!("Invalid --slow-log threshold '{}': {}", threshold, e))?,
+            path: path.clone(),
+        }),
+        Some(_) => return Err("--slow-log takes a threshold and a path".to_string()),
+        None => toml.load.slow_log.clone().map(|s| SlowLogConfig {
+            threshold: s.threshold,
+            path: s.path,
+        }),
+    };
+    if slow_log.is_some() {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("--slow-log only applies to http:// and https:// targets".to_string());
+        }
+        if burst_config.is_some() {
+            return Err("--slow-log is not supported with burst mode".to_string());
+        }
+        // Closed-model HTTP/3 runs have their own workers
+        #[cfg(feature = "http3")]
+        if (http3 && arrival_rate.is_none() && !stages.iter().any(|s| s.target_rate.is_some()))
+            || protocol_race.is_some()
+        {
+            return Err(
+                "--slow-log with --http3 needs an arrival rate (--arrival-rate)".to_string(),
+            );
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--slow-log is not supported with --grpc-service".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err("--slow-log is not supported with --socketio-event".to_string());
+        }
+    }
+
+    // Plugins hook the HTTP VU and arrival-rate executors, like the slow log
+    #[cfg(feature = "plugins")]
+    let plugins = if args.plugins.is_empty() {
+        toml.load.plugins.clone().unwrap_or_default()
+    } else {
+        args.plugins.clone()
+    };
+    #[cfg(feature = "plugins")]
+    if !plugins.is_empty() {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("--plugin only applies to http:// and https:// targets".to_string());
+        }
+        if burst_config.is_some() {
+            return Err("--plugin is not supported with burst mode".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if (http3 && arrival_rate.is_none() && !stages.iter().any(|s| s.target_rate.is_some()))
+            || protocol_race.is_some()
+        {
+            return Err("--plugin with --http3 needs an arrival rate (--arrival-rate)".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--plugin is not supported with --grpc-service".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err("--plugin is not supported with --socketio-event".to_string());
+        }
+    }
+    #[cfg(not(feature = "plugins"))]
+    if toml.load.plugins.as_ref().is_some_and(|p| !p.is_empty()) {
+        return Err("plugins require kaioken built with --features plugins".to_string());
+    }
+
+    // Runners report HTTP results only
+    if !args.runners.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("--runners only applies to http:// and https:// targets".to_string());
+    }
+
+    // Chaos is injected by the HTTP VU workers only
+    let chaos = toml
+        .chaos
+        .as_ref()
+        .map(|c| parse_chaos(c, timeout))
+        .transpose()?;
+    if let Some(ref chaos) = chaos {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("[chaos] only applies to http:// and https:// targets".to_string());
+        }
+        if arrival_rate.is_some() || stages.iter().any(|s| s.target_rate.is_some()) {
+            return Err("[chaos] is not supported with an arrival rate".to_string());
+        }
+        if burst_config.is_some() {
+            return Err("[chaos] is not supported with burst mode".to_string());
+        }
+        if chaos.reset_rate > 0.0 && proxy.is_some() {
+            return Err("[chaos] reset_rate is not supported with --proxy".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("[chaos] is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("[chaos] is not supported with --grpc-service".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err("[chaos] is not supported with --socketio-event".to_string());
+        }
+    }
+
+    // Streamed lines are taken by the HTTP VU workers' default target
+    if stdin.is_some() {
+        if has_scenarios {
+            return Err("Reading stdin ('-') is not supported with [[scenarios]]".to_string());
+        }
+        if arrival_rate.is_some() || stages.iter().any(|s| s.target_rate.is_some()) {
+            return Err("Reading stdin ('-') is not supported with an arrival rate".to_string());
+        }
+        if burst_config.is_some() {
+            return Err("Reading stdin ('-') is not supported with burst mode".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("Reading stdin ('-') is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("Reading stdin ('-') is not supported with --grpc-service".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err("Reading stdin ('-') is not supported with --socketio-event".to_string());
+        }
+    }
+
+    // The token is per VU, so CSRF handling is left to the HTTP VU workers
+    let csrf = parse_csrf(args, toml.csrf.as_ref())?;
+    if csrf.is_some() {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("--csrf only applies to http:// and https:// targets".to_string());
+        }
+        if arrival_rate.is_some() || stages.iter().any(|s| s.target_rate.is_some()) {
+            return Err(
+                "--csrf is not supported with an arrival rate (tokens are kept per VU)".to_string(),
+            );
+        }
+        if burst_config.is_some() {
+            return Err("--csrf is not supported with burst mode".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--csrf is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--csrf is not supported with --grpc-service".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err("--csrf is not supported with --socketio-event".to_string());
+        }
+    }
+
+    // Network profiles slow down the responses of the HTTP VU and arrival-rate executors
+    let network = parse_network(args, toml.network.as_ref())?;
+    if network.is_some() {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(
+                "--network-profile only applies to http:// and https:// targets".to_string(),
+            );
+        }
+        if burst_config.is_some() {
+            return Err("--network-profile is not supported with burst mode".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--network-profile is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--network-profile is not supported with --grpc-service".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err("--network-profile is not supported with --socketio-event".to_string());
+        }
+    }
+
+    // Backing off holds a VU, so throttling is honored by the HTTP VU workers only
+    let honor_retry_after = args.honor_retry_after || toml.load.honor_retry_after;
+    if honor_retry_after {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(
+                "--honor-retry-after only applies to http:// and https:// targets".to_string(),
+            );
+        }
+        if arrival_rate.is_some() || stages.iter().any(|s| s.target_rate.is_some()) {
+            return Err(
+                "--honor-retry-after is not supported with an arrival rate (it sends on schedule)"
+                    .to_string(),
+            );
+        }
+        if burst_config.is_some() {
+            return Err("--honor-retry-after is not supported with burst mode".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--honor-retry-after is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--honor-retry-after is not supported with --grpc-service".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err("--honor-retry-after is not supported with --socketio-event".to_string());
+        }
+    }
+
+    // Copies are sent side by side from a VU, so duplicates are HTTP VU only too
+    let duplicates = match args.duplicate_rate.or(toml.load.duplicate_rate) {
+        None => {
+            if toml.load.idempotency_header.is_some() {
+                return Err("idempotency_header needs a duplicate_rate".to_string());
+            }
+            None
+        }
+        Some(rate) => {
+            if !(rate > 0.0 && rate <= 1.0) {
+                return Err(format!(
+                    "--duplicate-rate must be above 0 and at most 1, got {}",
+                    rate
+                ));
+            }
+            let header = args
+                .idempotency_header
+                .clone()
+                .or(toml.load.idempotency_header.clone())
+                .unwrap_or_else(|| "Idempotency-Key".to_string());
+            if reqwest::header::HeaderName::from_bytes(header.as_bytes()).is_err() {
+                return Err(format!("Invalid --idempotency-header '{}'", header));
+            }
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(
+                    "--duplicate-rate only applies to http:// and https:// targets".to_string(),
+                );
+            }
+            if arrival_rate.is_some() || stages.iter().any(|s| s.target_rate.is_some()) {
+                return Err("--duplicate-rate is not supported with an arrival rate".to_string());
+            }
+            if burst_config.is_some() {
+                return Err("--duplicate-rate is not supported with burst mode".to_string());
+            }
+            #[cfg(feature = "http3")]
+            if http3 || protocol_race.is_some() {
+                return Err("--duplicate-rate is not supported with --http3".to_string());
+            }
+            #[cfg(feature = "grpc")]
+            if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+                return Err("--duplicate-rate is not supported with --grpc-service".to_string());
+            }
+            #[cfg(feature = "socketio")]
+            if socketio_event.is_some() {
+                return Err("--duplicate-rate is not supported with --socketio-event".to_string());
+            }
+            Some(crate::types::DuplicateConfig { rate, header })
+        }
+    };
+
+    // Validators are kept per VU, so conditional requests are HTTP VU only
+    let conditional_requests = args.conditional_requests || toml.load.conditional_requests;
+    if conditional_requests {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(
+                "--conditional-requests only applies to http:// and https:// targets".to_string(),
+            );
+        }
+        if arrival_rate.is_some() || stages.iter().any(|s| s.target_rate.is_some()) {
+            return Err("--conditional-requests is not supported with an arrival rate".to_string());
+        }
+        if burst_config.is_some() {
+            return Err("--conditional-requests is not supported with burst mode".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--conditional-requests is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--conditional-requests is not supported with --grpc-service".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err(
+                "--conditional-requests is not supported with --socketio-event".to_string(),
+            );
+        }
+    }
+
+    // VU workers hold off while paused; arrival-rate iterations would pile up
+    let pause_on_errors = args.pause_on_errors.or(toml.load.pause_on_errors);
+    if let Some(window) = pause_on_errors {
+        if window.is_zero() {
+            return Err("--pause-on-errors must be greater than zero".to_string());
+        }
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(
+                "--pause-on-errors only applies to http:// and https:// targets".to_string(),
+            );
+        }
+        if arrival_rate.is_some() || stages.iter().any(|s| s.target_rate.is_some()) {
+            return Err("--pause-on-errors is not supported with an arrival rate".to_string());
+        }
+        if burst_config.is_some() {
+            return Err("--pause-on-errors is not supported with burst mode".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--pause-on-errors is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--pause-on-errors is not supported with --grpc-service".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err("--pause-on-errors is not supported with --socketio-event".to_string());
+        }
+    }
+
+    // Failover results come from the timeline of the HTTP-style aggregator;
+    // burst mode's timeline is idle between bursts
+    let failover = args.failover || toml.load.failover;
+    if failover {
+        if ["ws://", "wss://", "tcp://", "udp://", "mqtt://"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme))
+            || scenarios.iter().any(|s| s.is_websocket())
+        {
+            return Err(
+                "--failover is not supported for WebSocket, raw socket or MQTT targets".to_string(),
+            );
+        }
+        if burst_config.is_some() {
+            return Err("--failover is not supported with burst mode".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err("--failover is not supported with --socketio-event".to_string());
+        }
+    }
+
+    // Probes are paced by the arrival-rate executors and go straight to the target
+    let probe = match args.probe.as_deref().or(toml.target.probe.as_deref()) {
+        None => None,
+        Some(s) => Some(
+            ProbeKind::parse(s)
+                .ok_or_else(|| format!("Invalid --probe '{}' (expected dns, connect or tls)", s))?,
+        ),
+    };
+    if let Some(kind) = probe {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("--probe only applies to http:// and https:// targets".to_string());
+        }
+        if kind == ProbeKind::Tls && !url.starts_with("https://") {
+            return Err("--probe tls needs an https:// target".to_string());
+        }
+        if arrival_rate.is_none() && !stages.iter().any(|s| s.target_rate.is_some()) {
+            return Err(
+                "--probe runs at an arrival rate; set --arrival-rate or target_rate stages"
+                    .to_string(),
+            );
+        }
+        if proxy.is_some() {
+            return Err(
+                "--probe connects directly, so it can't be combined with --proxy".to_string(),
+            );
+        }
+        if kind == ProbeKind::Dns && connect_to.is_some() {
+            return Err("--probe dns can't be combined with --connect-to".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--probe is not supported with --http3".to_string());
+        }
+    }
+
+    // The cap holds requests, not connections, so it only caps connections
+    // where each one carries a single request at a time
+    if let Some(limit) = max_connections {
+        if limit == 0 {
+            return Err("--max-connections must be at least 1".to_string());
+        }
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(
+                "--max-connections only applies to http:// and https:// targets".to_string(),
+            );
+        }
+        if http2 {
+            return Err(
+                "--max-connections caps HTTP/1.1 connections; --http2 sends every request over one"
+                    .to_string(),
+            );
+        }
+        if burst_config.is_some() {
+            return Err("--max-connections is not supported with burst mode".to_string());
+        }
+        if probe.is_some() {
+            return Err("--max-connections is not supported with --probe".to_string());
+        }
+        if duplicates.is_some() {
+            return Err(
+                "--max-connections is not supported with --duplicate-rate, whose copies go out side by side"
+                    .to_string(),
+            );
+        }
+        if scenarios.iter().any(|s| s.is_websocket()) {
+            return Err("--max-connections is not supported with WebSocket scenarios".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--max-connections is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--max-connections is not supported with --grpc-service".to_string());
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err("--max-connections is not supported with --socketio-event".to_string());
+        }
+    }
+
+    // Content-Types are read off the responses of the HTTP/1.1 and HTTP/2 client
+    let expect_content_types = if !args.expect_content_types.is_empty() {
+        &args.expect_content_types
+    } else {
+        &toml.target.expect_content_types
+    };
+    let expect_content_types = expect_content_types
+        .iter()
+        .map(|s| parse_content_type(s))
+        .collect::<Result<Vec<_>, _>>()?;
+    let expects_content_types = !expect_content_types.is_empty();
+    if expects_content_types && !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(
+            "--expect-content-type only applies to http:// and https:// targets".to_string(),
+        );
+    }
+    #[cfg(feature = "http3")]
+    if expects_content_types && (http3 || protocol_race.is_some()) {
+        return Err("--expect-content-type is not supported with --http3".to_string());
+    }
+    #[cfg(feature = "grpc")]
+    if expects_content_types && grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+        return Err("--expect-content-type is not supported with --grpc-service".to_string());
+    }
+    #[cfg(feature = "socketio")]
+    if expects_content_types && socketio_event.is_some() {
+        return Err("--expect-content-type is not supported with --socketio-event".to_string());
+    }
+
+    // Retries hold a VU like backing off does, so they're left to the HTTP VU workers
+    let retries = if args.retries > 0 {
+        args.retries
+    } else {
+        toml.load.retries.unwrap_or(0)
+    };
+    let deadline = args.deadline.or(toml.load.deadline);
+    if deadline.is_some_and(|d| d.is_zero()) {
+        return Err("--deadline must be greater than zero".to_string());
+    }
+    if retries > 0 || deadline.is_some() {
+        let flag = if retries > 0 {
+            "--retries"
+        } else {
+            "--deadline"
+        };
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!(
+                "{flag} only applies to http:// and https:// targets"
+            ));
+        }
+        if arrival_rate.is_some() || stages.iter().any(|s| s.target_rate.is_some()) {
+            return Err(format!("{flag} is not supported with an arrival rate"));
+        }
+        if burst_config.is_some() {
+            return Err(format!("{flag} is not supported with burst mode"));
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err(format!("{flag} is not supported with --http3"));
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err(format!("{flag} is not supported with --grpc-service"));
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err(format!("{flag} is not supported with --socketio-event"));
+        }
+    }
+
+    // Each proxy gets its own reqwest client in the VU and arrival-rate executors
+    let proxy_list = match args.proxy_list {
+        Some(ref path) => read_proxy_list(&path.display().to_string())?,
+        None => match toml.target.proxy_list {
+            Some(ref path) => read_proxy_list(path)?,
+            None => Vec::new(),
+        },
+    };
+    let proxy_rotation = match args
+        .proxy_rotation
+        .as_deref()
+        .or(toml.target.proxy_rotation.as_deref())
+    {
+        Some(s) => ProxyRotation::parse(s)
+            .ok_or_else(|| format!("Invalid proxy rotation '{}' (expected vu or request)", s))?,
+        None => ProxyRotation::Vu,
+    };
+    if proxy_list.is_empty() && args.proxy_rotation.is_some() {
+        return Err("--proxy-rotation requires --proxy-list".to_string());
+    }
+    let scenario_proxies = scenarios.iter().any(|s| s.proxy.is_some());
+    if !proxy_list.is_empty() && proxy.is_some() {
+        return Err("--proxy and --proxy-list are mutually exclusive".to_string());
+    }
+    for scenario in &scenarios {
+        match scenario.proxy.as_deref() {
+            Some(_) if scenario.is_websocket() => {
+                return Err(format!(
+                    "Scenario '{}': proxy only applies to HTTP scenarios",
+                    scenario.name
+                ));
+            }
+            Some(proxy) if !proxy.is_empty() => {
+                check_proxy(proxy).map_err(|e| format!("Scenario '{}': {}", scenario.name, e))?
+            }
+            _ => {}
+        }
+    }
+    if !proxy_list.is_empty() || scenario_proxies {
+        let what = if proxy_list.is_empty() {
+            "Scenario proxies are"
+        } else {
+            "--proxy-list is"
+        };
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!(
+                "{what} only supported with http:// and https:// targets"
+            ));
+        }
+        if burst_config.is_some() {
+            return Err(format!("{what} not supported with burst mode"));
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err(format!("{what} not supported with --http3"));
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err(format!("{what} not supported with --grpc-service"));
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err(format!("{what} not supported with --socketio-event"));
+        }
+        if chaos.as_ref().is_some_and(|c| c.reset_rate > 0.0) {
+            return Err("[chaos] reset_rate is not supported with proxies".to_string());
+        }
+    }
+
+    // One client per certificate, dealt out to the HTTP VU workers
+    let client_cert_pool = match args
+        .client_cert_pool
+        .clone()
+        .or_else(|| toml.target.client_cert_pool.as_ref().map(PathBuf::from))
+    {
+        Some(path) => read_client_cert_pool(&path)?,
+        None => Vec::new(),
+    };
+    let client_cert_assign = args
+        .client_cert_assign
+        .as_deref()
+        .or(toml.target.client_cert_assign.as_deref());
+    if client_cert_pool.is_empty() {
+        if client_cert_assign.is_some() {
+            return Err("--client-cert-assign requires --client-cert-pool".to_string());
+        }
+    } else {
+        let vus = stages
+            .iter()
+            .filter_map(|s| s.target)
+            .max()
+            .unwrap_or(concurrency);
+        match client_cert_assign {
+            None | Some("round-robin") => {}
+            Some("unique") if client_cert_pool.len() < vus as usize => {
+                return Err(format!(
+                    "--client-cert-assign unique needs a certificate per VU: {} for {} VUs",
+                    client_cert_pool.len(),
+                    vus
+                ));
+            }
+            Some("unique") => {}
+            Some(other) => {
+                return Err(format!(
+                    "Invalid --client-cert-assign '{}' (expected round-robin or unique)",
+                    other
+                ));
+            }
+        }
+        if client_cert.is_some() {
+            return Err("--client-cert-pool and --cert are mutually exclusive".to_string());
+        }
+        if !url.starts_with("https://") {
+            return Err("--client-cert-pool needs an https:// target".to_string());
+        }
+        if !proxy_list.is_empty() || scenario_proxies {
+            return Err("--client-cert-pool can't be combined with proxy rotation".to_string());
+        }
+        if arrival_rate.is_some() || stages.iter().any(|s| s.target_rate.is_some()) {
+            return Err(
+                "--client-cert-pool assigns certificates per VU, so it is not supported with an arrival rate"
+                    .to_string(),
+            );
+        }
+        if burst_config.is_some() {
+            return Err("--client-cert-pool is not supported with burst mode".to_string());
+        }
+        if threads.is_some() {
+            return Err("--client-cert-pool is not supported with --threads".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--client-cert-pool is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--client-cert-pool is not supported with --grpc-service".to_string());
+        }
+    }
+
+    // Seeded cookies live in the reqwest cookie jar, which the other clients lack
+    let mut cookies = match args.cookie_file {
+        Some(ref path) => read_cookie_file(&path.display().to_string())?,
+        None => match toml.target.cookie_file {
+            Some(ref path) => read_cookie_file(path)?,
+            None => Vec::new(),
+        },
+    };
+    if !toml.target.cookies.is_empty() {
+        let domain = reqwest::Url::parse(&url)
+            .ok()
+            .and_then(|u| u.host_str().map(String::from))
+            .ok_or("[target.cookies] needs a target URL with a host")?;
+        for (name, value) in &toml.target.cookies {
+            cookies.push(SeededCookie {
+                name: name.clone(),
+                value: value.clone(),
+                domain: domain.clone(),
+                include_subdomains: false,
+                path: "/".to_string(),
+                secure: false,
+            });
+        }
+    }
+    if !cookies.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("Seeded cookies only apply to http:// and https:// targets".to_string());
+    }
+    #[cfg(feature = "http3")]
+    if !cookies.is_empty() && (http3 || protocol_race.is_some()) {
+        return Err("Seeded cookies are not supported with --http3".to_string());
+    }
+    #[cfg(feature = "grpc")]
+    if !cookies.is_empty() && grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+        return Err("Seeded cookies are not supported with --grpc-service".to_string());
+    }
+    #[cfg(feature = "socketio")]
+    if !cookies.is_empty() && socketio_event.is_some() {
+        return Err("Seeded cookies are not supported with --socketio-event".to_string());
+    }
+
+    // Hosts rewrite the URLs (or Host headers) of HTTP requests built by the
+    // VU and arrival-rate executors
+    let host_header_list = args
+        .host_header_list
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .or_else(|| toml.target.host_header_list.clone());
+    let mut host_configs = toml.target.hosts.clone();
+    if let Some(ref path) = host_header_list {
+        host_configs.extend(read_host_header_list(path)?);
+    }
+    let hosts = parse_hosts(&host_configs)?;
+    if !hosts.is_empty() {
+        let what = if toml.target.hosts.is_empty() {
+            "--host-header-list"
+        } else {
+            "[[target.hosts]]"
+        };
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!(
+                "{what} only applies to http:// and https:// targets"
+            ));
+        }
+        if burst_config.is_some() {
+            return Err(format!("{what} is not supported with burst mode"));
+        }
+        if http2 && hosts.iter().any(|h| h.host_header.is_some()) {
+            return Err(
+                "Host header overrides need HTTP/1.1, so they can't be combined with --http2"
+                    .to_string(),
+            );
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err(format!("{what} is not supported with --http3"));
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err(format!("{what} is not supported with --grpc-service"));
+        }
+        #[cfg(feature = "socketio")]
+        if socketio_event.is_some() {
+            return Err(format!("{what} is not supported with --socketio-event"));
+        }
+    }
+
+    // The reqwest executors move the target's URLs onto the SNI name at startup
+    let sni = args.sni.clone().or_else(|| toml.target.sni.clone());
+    if let Some(ref name) = sni {
+        if name.is_empty()
+            || name.starts_with(['.', '-'])
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        {
+            return Err(format!("Invalid --sni '{}' (expected a DNS name)", name));
+        }
+        if !url.starts_with("https://") {
+            return Err("--sni needs an https:// target".to_string());
+        }
+        if http2 {
+            return Err(
+                "--sni sends the URL's host in the Host header, which needs HTTP/1.1, so it can't be combined with --http2"
+                    .to_string(),
+            );
+        }
+        if proxy.is_some() || !proxy_list.is_empty() || scenario_proxies {
+            return Err(
+                "--sni connects directly, so it can't be combined with proxies".to_string(),
+            );
+        }
+        if let Some((ref host, _)) = connect_to
+            && reqwest::Url::parse(&url)
+                .ok()
+                .and_then(|u| u.host_str().map(|h| h != host))
+                .unwrap_or(true)
+        {
+            return Err("--sni only combines with a --connect-to for the target host".to_string());
+        }
+        if !hosts.is_empty() {
+            return Err(
+                "--sni is not supported with [[target.hosts]] or --host-header-list".to_string(),
+            );
+        }
+        if rand_regex_url.is_some() || streams(StdinInput::Urls) {
+            return Err("--sni needs fixed URLs, so it is not supported with --rand-regex-url or URLs from stdin".to_string());
+        }
+        if scenarios.iter().any(|s| s.is_websocket()) {
+            return Err("--sni is not supported with WebSocket scenarios".to_string());
+        }
+        if probe.is_some() {
+            return Err("--sni is not supported with --probe".to_string());
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--sni is not supported with --http3".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.as_ref().is_some_and(|s| !s.is_empty()) {
+            return Err("--sni is not supported with --grpc-service".to_string());
+        }
+    }
+
+    // With auto warmup, --warmup is the longest it may take
+    let warmup = if auto_warmup && warmup.is_zero() {
+        DEFAULT_AUTO_WARMUP_MAX
+    } else {
+        warmup
+    };
+
+    // Validate: JSON-RPC ids are interpolated by the VU and arrival-rate executors only
+    if jsonrpc_method.is_some() && burst_config.is_some() {
+        return Err("JSON-RPC mode is not supported with burst mode".to_string());
+    }
+    #[cfg(feature = "http3")]
+    if jsonrpc_method.is_some() && (http3 || protocol_race.is_some()) {
+        return Err("JSON-RPC mode is not supported with --http3 or --protocol-race".to_string());
+    }
+
+    // Validate: worker runtimes only host the constant-VU HTTP workers
+    if threads.is_some() {
+        let http_target = url.starts_with("http://") || url.starts_with("https://");
+        if !http_target
+            || arrival_rate.is_some()
+            || burst_config.is_some()
+            || stages.iter().any(|s| s.target_rate.is_some())
+        {
+            return Err(
+                "--threads only applies to HTTP targets with constant VUs (no arrival rate or burst mode)"
+                    .to_string(),
+            );
+        }
+        #[cfg(feature = "http3")]
+        if http3 || protocol_race.is_some() {
+            return Err("--threads is not supported with --http3 or --protocol-race".to_string());
+        }
+        #[cfg(feature = "grpc")]
+        if grpc_service.is_some() {
+            return Err("--threads is not supported in gRPC mode".to_string());
+        }
+    }
+
+    // Validate: WebSocket scenarios only run on constant VUs
+    if scenarios.iter().any(|s| s.is_websocket())
+        && (arrival_rate.is_some()
+            || burst_config.is_some()
+            || stages.iter().any(|s| s.target_rate.is_some()))
+    {
+        return Err(
+            "WebSocket scenarios (ws:// or wss:// URLs) are not supported with arrival rate or burst mode"
+                .to_string(),
+        );
+    }
+
+    // Mixed HTTP + WebSocket runs give each side VUs of its own
+    if scenarios.iter().any(|s| s.is_websocket()) && scenarios.iter().any(|s| !s.is_websocket()) {
+        let vus = stages
+            .iter()
+            .filter_map(|s| s.target)
+            .max()
+            .unwrap_or(concurrency);
+        if vus < 2 {
+            return Err(
+                "Mixed HTTP and WebSocket scenarios need at least 2 VUs (-c), one for each"
+                    .to_string(),
+            );
+        }
+    }
+
+    // WebSocket VUs pace themselves with the message rate
+    if let Some(s) = scenarios
+        .iter()
+        .find(|s| s.is_websocket() && s.think_time.is_some())
+    {
+        return Err(format!(
+            "Scenario '{}': think_time only applies to HTTP scenarios",
+            s.name
+        ));
+    }
+
+    // Raw TCP/UDP mode: send the body and optionally wait for a matching response
+    let raw_expect = match (&args.raw_expect, args.raw_expect_bytes) {
+        (Some(pattern), _) => Some(crate::types::RawExpect::Pattern(
+            regex_lite::Regex::new(pattern)
+                .map_err(|e| format!("Invalid --raw-expect regex: {}", e))?,
+        )),
+        (None, Some(0)) => return Err("--raw-expect-bytes must be at least 1".to_string()),
+        (None, Some(n)) => Some(crate::types::RawExpect::Bytes(n)),
+        (None, None) => None,
+    };
+    if url.starts_with("tcp://") || url.starts_with("udp://") {
+        crate::raw::parse_raw_url(&url)?;
+        if arrival_rate.is_some() || burst_config.is_some() || !stages.is_empty() {
+            return Err(
+                "Raw TCP/UDP mode runs a fixed set of connections; arrival rate, burst mode and stages are not supported"
+                    .to_string(),
+            );
+        }
+        if !scenarios.is_empty() {
+            return Err("Raw TCP/UDP mode does not support [[scenarios]]".to_string());
+        }
+    } else if raw_expect.is_some() {
+        return Err(
+            "--raw-expect and --raw-expect-bytes require a tcp:// or udp:// target".to_string(),
+        );
+    }
+
+    // Redis mode: redis:// targets run command templates over RESP
+    #[cfg(feature = "redis")]
+    let redis_commands = if url.starts_with("redis://") {
+        crate::redis::parse_redis_url(&url)?;
+        if arrival_rate.is_some() || burst_config.is_some() || !stages.is_empty() {
+            return Err(
+                "Redis mode runs a fixed set of connections; arrival rate, burst mode and stages are not supported"
+                    .to_string(),
+            );
+        }
+        if !scenarios.is_empty() {
+            return Err("Redis mode does not support [[scenarios]]".to_string());
+        }
+        if args.redis_commands.is_empty() {
+            vec![vec!["PING".to_string()]]
+        } else {
+            args.redis_commands
+                .iter()
+                .map(|c| crate::redis::parse_command_template(c))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    } else if !args.redis_commands.is_empty() {
+        return Err("--redis-command requires a redis:// target".to_string());
+    } else {
+        Vec::new()
+    };
+
+    // MQTT mode: mqtt:// targets split connections into publishers and subscribers
+    #[cfg(feature = "mqtt")]
+    let mqtt_subscribers = if url.starts_with("mqtt://") {
+        crate::mqtt::parse_broker_url(&url)?;
+        crate::mqtt::subscription_filter(&args.mqtt_topic)?;
+        if !(0.0..1.0).contains(&args.mqtt_subscriber_ratio) {
+            return Err(
+                "--mqtt-subscriber-ratio must be at least 0.0 and below 1.0 so at least one connection publishes"
+                    .to_string(),
+            );
+        }
+        if arrival_rate.is_some() || burst_config.is_some() || !stages.is_empty() {
+            return Err(
+                "MQTT mode runs a fixed set of connections; arrival rate, burst mode and stages are not supported"
+                    .to_string(),
+            );
+        }
+        if !scenarios.is_empty() {
+            return Err("MQTT mode does not support [[scenarios]]".to_string());
+        }
+        let subscribers = ((concurrency as f64 * args.mqtt_subscriber_ratio).round() as u32)
+            .min(concurrency.saturating_sub(1));
+        if subscribers > 0
+            && let Some(ref body) = body
+            && !body.contains("${BROADCAST_TAG}")
+        {
+            return Err(
+                "MQTT subscribers need ${BROADCAST_TAG} in the message body to match deliveries"
+                    .to_string(),
+            );
+        }
+        subscribers
+    } else {
+        0
     };
 
-    // Validate: burst mode is incompatible with arrival rate
-    if burst_config.is_some() && arrival_rate.is_some() {
-        return Err("Burst mode (--burst-rate) is incompatible with --arrival-rate".to_string());
+    // Endpoint grouping - CLI rules are tried before the config file's
+    let mut endpoint_groups = Vec::new();
+    for group in &args.endpoint_groups {
+        let (pattern, name) = group.rsplit_once('=').ok_or_else(|| {
+            format!(
+                "Invalid endpoint group '{}'. Expected 'REGEX=NAME' (e.g. '/users/[^/]+=/users/{{user}}')",
+                group
+            )
+        })?;
+        endpoint_groups.push(parse_endpoint_group(pattern, name)?);
+    }
+    for group in &toml.endpoint_groups {
+        endpoint_groups.push(parse_endpoint_group(&group.pattern, &group.name)?);
     }
 
     // db_url for SQLite logging
     let db_url = args.db_url.clone();
 
     // Prometheus metrics export
-    let prometheus = if let Some(ref url) = args.prometheus_pushgateway {
-        Some(crate::types::PrometheusConfig::Pushgateway { url: url.clone() })
+    let prometheus_export = if let Some(ref url) = args.prometheus_pushgateway {
+        Some(crate::types::PrometheusExport::Pushgateway { url: url.clone() })
     } else if let Some(port) = args.prometheus_port {
-        Some(crate::types::PrometheusConfig::Endpoint { port })
+        Some(crate::types::PrometheusExport::Endpoint { port })
     } else if let Some(ref url) = toml.load.prometheus_pushgateway {
-        Some(crate::types::PrometheusConfig::Pushgateway { url: url.clone() })
+        Some(crate::types::PrometheusExport::Pushgateway { url: url.clone() })
     } else if let Some(port) = toml.load.prometheus_port {
-        Some(crate::types::PrometheusConfig::Endpoint { port })
+        Some(crate::types::PrometheusExport::Endpoint { port })
     } else {
         None
     };
+    let prometheus_instance = args
+        .prometheus_instance
+        .clone()
+        .or_else(|| toml.load.prometheus_instance.clone());
+    let mut prometheus_labels = toml.load.prometheus_labels.clone();
+    for label in &args.prometheus_labels {
+        let (name, value) = label.split_once('=').ok_or_else(|| {
+            format!(
+                "Invalid --prometheus-label '{}'. Expected 'NAME=VALUE' (e.g. 'env=staging')",
+                label
+            )
+        })?;
+        prometheus_labels.insert(name.trim().to_string(), value.to_string());
+    }
+    let prometheus_buckets = args
+        .prometheus_buckets
+        .clone()
+        .or_else(|| toml.load.prometheus_buckets.clone());
+    let prometheus = match prometheus_export {
+        Some(export) => {
+            if let Some(ref instance) = prometheus_instance {
+                validate_prometheus_instance(instance)?;
+            }
+            for name in prometheus_labels.keys() {
+                validate_prometheus_label(name)?;
+            }
+            Some(crate::types::PrometheusConfig {
+                export,
+                instance: prometheus_instance,
+                labels: prometheus_labels.into_iter().collect(),
+                buckets: match prometheus_buckets {
+                    Some(list) => parse_prometheus_buckets(list)?,
+                    None => crate::types::DEFAULT_PROMETHEUS_BUCKETS.to_vec(),
+                },
+            })
+        }
+        None if prometheus_instance.is_some()
+            || !prometheus_labels.is_empty()
+            || prometheus_buckets.is_some() =>
+        {
+            return Err(
+                "Prometheus labels, instance and buckets need --prometheus-pushgateway or --prometheus-port"
+                    .to_string(),
+            );
+        }
+        None => None,
+    };
 
     Ok(LoadConfig {
         url,
@@ -723,52 +2937,468 @@ pub fn merge_config(args: &RunArgs, toml: Option<TomlConfig>) -> Result<LoadConf
         body,
         scenarios,
         concurrency,
+        threads,
         duration,
         max_requests,
         rate,
+        rate_burst,
         ramp_up,
         warmup,
+        auto_warmup,
         timeout,
         connect_timeout,
         insecure,
         http2,
         #[cfg(feature = "http3")]
         http3,
+        #[cfg(feature = "http3")]
+        http3_0rtt,
+        #[cfg(feature = "http3")]
+        protocol_race,
         #[cfg(feature = "grpc")]
         grpc_service,
         #[cfg(feature = "grpc")]
         grpc_method,
         #[cfg(feature = "grpc")]
         body_bytes,
+        #[cfg(feature = "socketio")]
+        socketio_event,
+        #[cfg(feature = "socketio")]
+        socketio_namespace,
+        #[cfg(feature = "mqtt")]
+        mqtt_topic: args.mqtt_topic.clone(),
+        #[cfg(feature = "mqtt")]
+        mqtt_qos: args.mqtt_qos,
+        #[cfg(feature = "mqtt")]
+        mqtt_publish_rate: args.mqtt_publish_rate,
+        #[cfg(feature = "mqtt")]
+        mqtt_subscribers,
+        #[cfg(feature = "redis")]
+        redis_commands,
+        #[cfg(feature = "redis")]
+        redis_keyspace: args.redis_keyspace,
         cookie_jar,
+        cookies,
+        csrf,
         follow_redirects,
         disable_keepalive,
+        socket,
         thresholds,
+        trend_thresholds,
         checks,
         stages,
         think_time,
+        timeline_interval,
+        error_samples,
+        apdex_t,
+        percentiles,
+        latency_histogram: args.runner_stream,
+        slow_log,
+        #[cfg(feature = "plugins")]
+        plugins,
+        chaos,
+        network,
+        honor_retry_after,
+        duplicates,
+        conditional_requests,
+        pause_on_errors,
+        failover,
+        max_connections,
+        expect_content_types,
+        retries,
+        seed: args.seed.or(toml.load.seed),
+        deadline,
         fail_fast,
         arrival_rate,
         max_vus,
+        vu_autoscale,
+        rate_profile,
+        backpressure,
         latency_correction,
         ws_mode,
         ws_message_interval,
+        ws_subprotocols,
+        ws_reconnect_every,
+        ws_compression,
+        ws_publishers,
+        raw_expect,
+        jsonrpc_method,
         proxy,
+        proxy_list,
+        proxy_rotation,
         basic_auth,
         client_cert,
         client_key,
         ca_cert,
+        client_cert_pool,
+        sni,
+        tls_backend,
+        trace_propagation,
+        compress_body,
+        probe,
+        request_id_header,
+        trace_slowest,
         form_fields,
         rand_regex_url,
         url_list,
         body_lines,
+        stdin,
         connect_to,
         burst_config,
         db_url,
         prometheus,
+        endpoint_groups,
+        hosts,
+    })
+}
+
+fn parse_hosts(hosts: &[HostConfig]) -> Result<Vec<crate::types::TargetHost>, String> {
+    let mut parsed: Vec<crate::types::TargetHost> = Vec::with_capacity(hosts.len());
+    for host in hosts {
+        let authority = match host.url {
+            Some(ref url) => Some(
+                url.strip_prefix("http://")
+                    .or_else(|| url.strip_prefix("https://"))
+                    .map(|rest| rest.trim_end_matches('/'))
+                    .filter(|authority| {
+                        !authority.is_empty() && !authority.contains(['/', '?', '#'])
+                    })
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid host '{}': use a base URL like https://eu.example.com, without a path",
+                            url
+                        )
+                    })?,
+            ),
+            None => None,
+        };
+        if let Some(ref header) = host.host_header
+            && (header.is_empty() || header.contains(char::is_whitespace))
+        {
+            return Err(format!("Invalid Host header '{}'", header));
+        }
+        let Some(label) = authority.or(host.host_header.as_deref()) else {
+            return Err("[[target.hosts]] entries need a url or a host_header".to_string());
+        };
+        if host.weight == 0 {
+            return Err(format!("Host '{}' has weight 0", label));
+        }
+        let name = host.name.clone().unwrap_or_else(|| label.to_string());
+        if parsed.iter().any(|h| h.name == name) {
+            return Err(format!(
+                "Duplicate host name '{}' in [[target.hosts]] (set name to tell them apart)",
+                name
+            ));
+        }
+        parsed.push(crate::types::TargetHost {
+            name,
+            url: host
+                .url
+                .as_ref()
+                .map(|url| url.trim_end_matches('/').to_string()),
+            host_header: host.host_header.clone(),
+            weight: host.weight,
+        });
+    }
+    Ok(parsed)
+}
+
+/// Virtual hosts from a file, one Host header per line, sharing the requests evenly
+fn read_host_header_list(path: &str) -> Result<Vec<HostConfig>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read host header list '{}': {}", path, e))?;
+    let hosts: Vec<HostConfig> = content
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|host| HostConfig {
+            url: None,
+            host_header: Some(host.to_string()),
+            weight: 1,
+            name: None,
+        })
+        .collect();
+    if hosts.is_empty() {
+        return Err(format!("Host header list '{}' is empty", path));
+    }
+    Ok(hosts)
+}
+
+fn parse_chaos(settings: &ChaosSettings, timeout: Duration) -> Result<ChaosConfig, String> {
+    let rate = |name: &str, rate: f64| {
+        if (0.0..=1.0).contains(&rate) {
+            Ok(rate)
+        } else {
+            Err(format!(
+                "[chaos] {} must be between 0 and 1, got {}",
+                name, rate
+            ))
+        }
+    };
+    let latency = settings
+        .latency
+        .as_deref()
+        .map(|s| parse_duration_range("latency", s))
+        .transpose()?;
+    if latency.is_none() && settings.latency_rate.is_some() {
+        return Err("[chaos] latency_rate requires latency".to_string());
+    }
+    let abort_rate = rate("abort_rate", settings.abort_rate)?;
+    let reset_rate = rate("reset_rate", settings.reset_rate)?;
+    if abort_rate + reset_rate > 1.0 {
+        return Err("[chaos] abort_rate and reset_rate add up to more than 1".to_string());
+    }
+    let abort_after = match settings.abort_after.as_deref() {
+        Some(s) => parse_duration_range("abort_after", s)?,
+        None => (Duration::ZERO, timeout),
+    };
+    Ok(ChaosConfig {
+        latency,
+        latency_rate: rate("latency_rate", settings.latency_rate.unwrap_or(1.0))?,
+        abort_rate,
+        abort_after,
+        reset_rate,
+    })
+}
+
+/// `--urls-from-file -` or `--body-lines -` (CLI over `[target]`). The first
+/// line is read now, so an empty stream fails before the run and can stand in
+/// as the target URL.
+fn stdin_stream(args: &RunArgs, target: &TargetConfig) -> Result<Option<StdinStream>, String> {
+    let from_stdin = |cli: &Option<std::path::PathBuf>, toml: &Option<String>| match cli {
+        Some(path) => path.as_os_str() == "-",
+        None => toml.as_deref() == Some("-"),
+    };
+    let urls = from_stdin(&args.urls_from_file, &target.urls_from_file);
+    let body_lines = from_stdin(&args.body_lines_file, &target.body_lines_file);
+    let input = match (urls, body_lines) {
+        (true, true) => {
+            return Err("--urls-from-file and --body-lines can't both read stdin".to_string());
+        }
+        (true, false) => StdinInput::Urls,
+        (false, true) => StdinInput::BodyLines,
+        (false, false) => return Ok(None),
+    };
+
+    for line in std::io::stdin().lock().lines() {
+        let line = line.map_err(|e| format!("Failed to read stdin: {}", e))?;
+        let first = match input {
+            StdinInput::Urls => line.trim(),
+            StdinInput::BodyLines => line.as_str(),
+        };
+        if input == StdinInput::Urls && (first.is_empty() || first.starts_with('#')) {
+            continue;
+        }
+        return Ok(Some(StdinStream {
+            input,
+            first: first.to_string(),
+        }));
+    }
+    Err("stdin ('-') is empty".to_string())
+}
+
+/// `--csrf` and `--csrf-header`, falling back to `[csrf]`
+fn parse_csrf(
+    args: &RunArgs,
+    settings: Option<&CsrfSettings>,
+) -> Result<Option<CsrfConfig>, String> {
+    let settings = settings.cloned().unwrap_or_default();
+    let header = args.csrf_header.clone().or(settings.header);
+    let Some(from) = args.csrf.clone().or(settings.from) else {
+        if header.is_some() {
+            return Err("--csrf-header requires --csrf".to_string());
+        }
+        return Ok(None);
+    };
+    let source = CsrfSource::parse(&from)?;
+    if let CsrfSource::Body(ExtractionSource::Regex(ref pattern, _)) = source {
+        regex_lite::Regex::new(pattern)
+            .map_err(|e| format!("Invalid CSRF token regex '{}': {}", pattern, e))?;
+    }
+    let header = header.unwrap_or_else(|| "X-CSRF-Token".to_string());
+    reqwest::header::HeaderName::from_bytes(header.as_bytes())
+        .map_err(|_| format!("Invalid CSRF header name '{}'", header))?;
+    Ok(Some(CsrfConfig { source, header }))
+}
+
+/// `--network-profile` and its overrides, falling back to `[network]`
+fn parse_network(
+    args: &RunArgs,
+    settings: Option<&NetworkSettings>,
+) -> Result<Option<NetworkProfile>, String> {
+    let settings = settings.cloned().unwrap_or_default();
+    let Some(name) = args.network_profile.clone().or(settings.profile) else {
+        if args.network_down.is_some() || args.network_up.is_some() || args.network_rtt.is_some() {
+            return Err(
+                "--network-down, --network-up and --network-rtt require --network-profile"
+                    .to_string(),
+            );
+        }
+        return Ok(None);
+    };
+    let custom = name == "custom";
+    let mut profile = match name.as_str() {
+        "custom" => NetworkProfile {
+            name,
+            down_bps: 0,
+            up_bps: 0,
+            rtt: Duration::ZERO,
+        },
+        _ => NetworkProfile::preset(&name).ok_or_else(|| {
+            format!(
+                "Invalid network profile '{}' (expected 3g, 4g, dsl or custom)",
+                name
+            )
+        })?,
+    };
+    match args.network_down.as_deref().or(settings.down.as_deref()) {
+        Some(rate) => profile.down_bps = parse_bandwidth(rate)?,
+        None if custom => {
+            return Err("A custom network profile needs a download bandwidth".to_string());
+        }
+        None => {}
+    }
+    match args.network_up.as_deref().or(settings.up.as_deref()) {
+        Some(rate) => profile.up_bps = parse_bandwidth(rate)?,
+        // Symmetric unless said otherwise
+        None if custom => profile.up_bps = profile.down_bps,
+        None => {}
+    }
+    if let Some(rtt) = args.network_rtt.or(settings.rtt) {
+        profile.rtt = rtt;
+    }
+    Ok(Some(profile))
+}
+
+/// `"5mbit"`, `"768kbit"` or `"1.5gbit"`, in bits per second
+fn parse_bandwidth(rate: &str) -> Result<u64, String> {
+    let lower = rate.trim().to_ascii_lowercase();
+    let (number, scale) = [("kbit", 1e3), ("mbit", 1e6), ("gbit", 1e9)]
+        .iter()
+        .find_map(|(unit, scale)| lower.strip_suffix(unit).map(|n| (n.trim(), *scale)))
+        .ok_or_else(|| {
+            format!(
+                "Invalid bandwidth '{}' (expected e.g. 768kbit, 5mbit or 1gbit)",
+                rate
+            )
+        })?;
+    let bps = number
+        .parse::<f64>()
+        .ok()
+        .map(|n| n * scale)
+        .filter(|bps| bps.is_finite() && *bps >= 1.0)
+        .ok_or_else(|| format!("Invalid bandwidth '{}'", rate))?;
+    Ok(bps as u64)
+}
+
+/// `type/subtype` or `type/*`, lowercased
+fn parse_content_type(s: &str) -> Result<String, String> {
+    let content_type = s.trim().to_ascii_lowercase();
+    match content_type.split_once('/') {
+        Some((kind, subtype))
+            if !kind.is_empty()
+                && !subtype.is_empty()
+                && !subtype.contains('/')
+                && !content_type.contains([';', ' '])
+                && (kind != "*" || subtype == "*") =>
+        {
+            Ok(content_type)
+        }
+        _ => Err(format!(
+            "Invalid content type '{}': expected type/subtype or type/*, without parameters",
+            s
+        )),
+    }
+}
+
+/// `"500ms"`, `"1s..3s"`, `"normal(2s, 500ms)"` or `"exp(2s)"`
+fn parse_think_time(s: &str) -> Result<ThinkTime, String> {
+    let invalid =
+        |reason: &dyn std::fmt::Display| format!("Invalid think time '{}': {}", s, reason);
+    let duration = |d: &str| humantime::parse_duration(d.trim()).map_err(|e| invalid(&e));
+    let text = s.trim();
+    if let Some(args) = text
+        .strip_prefix("normal(")
+        .and_then(|a| a.strip_suffix(')'))
+    {
+        let (mean, std_dev) = args
+            .split_once(',')
+            .ok_or_else(|| invalid(&"expected normal(MEAN, STDDEV)"))?;
+        return Ok(ThinkTime::Normal(duration(mean)?, duration(std_dev)?));
+    }
+    if let Some(mean) = text.strip_prefix("exp(").and_then(|a| a.strip_suffix(')')) {
+        return Ok(ThinkTime::Exponential(duration(mean)?));
+    }
+    match text.split_once("..") {
+        Some((min, max)) => {
+            let (min, max) = (duration(min)?, duration(max)?);
+            if min > max {
+                return Err(invalid(&"range is backwards"));
+            }
+            Ok(ThinkTime::Uniform(min, max))
+        }
+        None => Ok(ThinkTime::Fixed(duration(text)?)),
+    }
+}
+
+/// `"50ms"` or `"10ms..200ms"`
+fn parse_duration_range(name: &str, s: &str) -> Result<(Duration, Duration), String> {
+    let parse = |d: &str| {
+        humantime::parse_duration(d.trim())
+            .map_err(|e| format!("Invalid [chaos] {} '{}': {}", name, s, e))
+    };
+    let (min, max) = match s.split_once("..") {
+        Some((min, max)) => (parse(min)?, parse(max)?),
+        None => (parse(s)?, parse(s)?),
+    };
+    if min > max {
+        return Err(format!(
+            "Invalid [chaos] {} '{}': range is backwards",
+            name, s
+        ));
+    }
+    Ok((min, max))
+}
+
+fn parse_endpoint_group(pattern: &str, name: &str) -> Result<crate::types::EndpointGroup, String> {
+    let regex = regex_lite::Regex::new(pattern)
+        .map_err(|e| format!("Invalid endpoint group pattern '{}': {}", pattern, e))?;
+    Ok(crate::types::EndpointGroup {
+        pattern: regex,
+        name: name.to_string(),
     })
 }
 
+/// Prometheus label names are `[a-zA-Z_][a-zA-Z0-9_]*`; `__` is reserved and
+/// `instance` has its own flag
+fn validate_prometheus_label(name: &str) -> Result<(), String> {
+    let valid = name
+        .chars()
+        .enumerate()
+        .all(|(i, c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+    if name.is_empty() || !valid || name.starts_with("__") {
+        return Err(format!(
+            "Invalid Prometheus label name '{}'. Use letters, digits and '_' (not starting with '__')",
+            name
+        ));
+    }
+    if name == "instance" {
+        return Err("Set the Prometheus 'instance' label with --prometheus-instance".to_string());
+    }
+    Ok(())
+}
+
+/// The instance name becomes a Pushgateway URL path segment
+fn validate_prometheus_instance(instance: &str) -> Result<(), String> {
+    if instance.is_empty() || instance.contains('/') {
+        return Err(format!(
+            "Invalid Prometheus instance '{}'. It must be non-empty and can't contain '/'",
+            instance
+        ));
+    }
+    Ok(())
+}
+
 /// Parse connect_to mapping string
 /// Format: "HOST:PORT:TARGET_IP:TARGET_PORT" or "HOST:TARGET_IP:TARGET_PORT"
 fn parse_connect_to(mapping: &str) -> Result<(String, std::net::SocketAddr), String> {
@@ -819,6 +3449,7 @@ fn parse_basic_auth(s: &str) -> Result<(String, Option<String>), String> {
 
 fn process_scenarios(configs: &[ScenarioConfig]) -> Result<Vec<Scenario>, String> {
     let mut scenarios = Vec::with_capacity(configs.len());
+    let weights = scenario_weights(configs)?;
 
     for (i, cfg) in configs.iter().enumerate() {
         let name = cfg
@@ -858,76 +3489,400 @@ fn process_scenarios(configs: &[ScenarioConfig]) -> Result<Vec<Scenario>, String
             });
         }
 
+        let think_time = cfg
+            .think_time
+            .as_deref()
+            .map(parse_think_time)
+            .transpose()
+            .map_err(|e| format!("Scenario '{}': {}", name, e))?;
+
         scenarios.push(Scenario {
             name,
             url: cfg.url.clone(),
             method,
             headers,
             body,
-            weight: cfg.weight,
+            weight: weights[i],
             extractions,
             depends_on: cfg.depends_on.clone(),
             tags: cfg.tags.clone(),
+            proxy: cfg.proxy.clone(),
+            think_time,
         });
     }
 
     Ok(scenarios)
 }
 
-fn parse_thresholds(config: &ThresholdsConfig) -> Result<Vec<Threshold>, String> {
-    let mut thresholds = Vec::new();
+/// A `--rate-profile` as ramping arrival-rate stages. Each line is an
+/// offset and a rate (`3600,250`), as exported from production RPS metrics;
+/// offsets count from the first line, and a header line and `#` comments
+/// are skipped. The replay starts at the first rate and ramps linearly
+/// between points, squeezed into `window` when one is given.
+fn read_rate_profile(
+    path: &str,
+    window: Option<Duration>,
+) -> Result<(Vec<Stage>, crate::types::RateProfile), String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read rate profile '{}': {}", path, e))?;
+    let mut points: Vec<(f64, u32)> = Vec::new();
+    let mut header = false;
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|f| !f.is_empty())
+            .collect();
+        let point = match fields[..] {
+            [offset, rate] => parse_profile_offset(offset).zip(
+                rate.parse::<f64>()
+                    .ok()
+                    .filter(|r| r.is_finite() && *r >= 0.0),
+            ),
+            _ => None,
+        };
+        let Some((offset, rate)) = point else {
+            if points.is_empty() && !header {
+                header = true;
+                continue;
+            }
+            return Err(format!(
+                "Rate profile '{}' line {}: expected 'offset,rate'",
+                path,
+                i + 1
+            ));
+        };
+        if points.last().is_some_and(|&(last, _)| offset <= last) {
+            return Err(format!(
+                "Rate profile '{}' line {}: offsets must increase",
+                path,
+                i + 1
+            ));
+        }
+        points.push((offset, rate.round() as u32));
+    }
+    let (Some(&(first, start_rate)), Some(&(last, _))) = (points.first(), points.last()) else {
+        return Err(format!("Rate profile '{}' has no points", path));
+    };
+    if points.len() < 2 {
+        return Err(format!("Rate profile '{}' needs at least two points", path));
+    }
+
+    let span = Duration::from_secs_f64(last - first);
+    let window = window.unwrap_or(span);
+    let scale = window.as_secs_f64() / span.as_secs_f64();
+    // A zero-length first stage starts the replay at the first rate
+    // instead of ramping up to it from 0
+    let mut stages = vec![Stage {
+        duration: Duration::ZERO,
+        target: None,
+        target_rate: Some(start_rate),
+        target_message_rate: None,
+    }];
+    stages.extend(points.windows(2).map(|pair| Stage {
+        duration: Duration::from_secs_f64((pair[1].0 - pair[0].0) * scale),
+        target: None,
+        target_rate: Some(pair[1].1),
+        target_message_rate: None,
+    }));
+    let profile = crate::types::RateProfile {
+        path: path.to_string(),
+        points: points.len(),
+        span,
+        window,
+    };
+    Ok((stages, profile))
+}
 
-    let entries: Vec<(ThresholdMetric, &Option<String>)> = vec![
-        (ThresholdMetric::P50LatencyMs, &config.p50_latency_ms),
-        (ThresholdMetric::P75LatencyMs, &config.p75_latency_ms),
-        (ThresholdMetric::P90LatencyMs, &config.p90_latency_ms),
-        (ThresholdMetric::P95LatencyMs, &config.p95_latency_ms),
-        (ThresholdMetric::P99LatencyMs, &config.p99_latency_ms),
-        (ThresholdMetric::P999LatencyMs, &config.p999_latency_ms),
-        (ThresholdMetric::MeanLatencyMs, &config.mean_latency_ms),
-        (ThresholdMetric::MaxLatencyMs, &config.max_latency_ms),
-        (ThresholdMetric::ErrorRate, &config.error_rate),
-        (ThresholdMetric::Rps, &config.rps),
-        (ThresholdMetric::CheckPassRate, &config.check_pass_rate),
-    ];
+/// A rate profile offset: seconds (`90`, or a Unix timestamp), a time of
+/// day (`13:30`, `13:30:15`) or a duration (`1h30m`)
+fn parse_profile_offset(s: &str) -> Option<f64> {
+    if let Ok(secs) = s.parse::<f64>() {
+        return (secs.is_finite() && secs >= 0.0).then_some(secs);
+    }
+    if s.contains(':') {
+        let parts: Vec<u64> = s
+            .split(':')
+            .map(|p| p.parse().ok())
+            .collect::<Option<_>>()?;
+        return match parts[..] {
+            [h, m] if m < 60 => Some((h * 3600 + m * 60) as f64),
+            [h, m, sec] if m < 60 && sec < 60 => Some((h * 3600 + m * 60 + sec) as f64),
+            _ => None,
+        };
+    }
+    humantime::parse_duration(s).ok().map(|d| d.as_secs_f64())
+}
 
-    for (metric, value) in entries {
-        if let Some(expr) = value {
-            let threshold = parse_threshold_expr(metric, expr)?;
-            thresholds.push(threshold);
+/// Cookies from a Netscape-format cookies.txt, as written by curl and
+/// browser extensions. Expired cookies are skipped.
+fn read_cookie_file(path: &str) -> Result<Vec<SeededCookie>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read cookie file '{}': {}", path, e))?;
+    let now = chrono::Utc::now().timestamp();
+    let mut cookies = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        // curl marks HttpOnly cookies with a prefix on an otherwise comment-like line
+        let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [
+            domain,
+            subdomains,
+            cookie_path,
+            secure,
+            expires,
+            name,
+            value,
+        ] = fields[..]
+        else {
+            return Err(format!(
+                "Cookie file '{}' line {}: expected 7 tab-separated fields",
+                path,
+                i + 1
+            ));
+        };
+        let expires: i64 = expires.parse().map_err(|_| {
+            format!(
+                "Cookie file '{}' line {}: invalid expiry '{}'",
+                path,
+                i + 1,
+                expires
+            )
+        })?;
+        if expires != 0 && expires < now {
+            continue;
         }
+        cookies.push(SeededCookie {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: domain.trim_start_matches('.').to_string(),
+            include_subdomains: subdomains.eq_ignore_ascii_case("TRUE"),
+            path: cookie_path.to_string(),
+            secure: secure.eq_ignore_ascii_case("TRUE"),
+        });
+    }
+    if cookies.is_empty() {
+        return Err(format!("Cookie file '{}' has no unexpired cookies", path));
     }
+    Ok(cookies)
+}
 
-    Ok(thresholds)
+/// Proxy URLs from a file, one per line (blank lines and `#` comments skipped)
+fn read_proxy_list(path: &str) -> Result<Vec<String>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read proxy list '{}': {}", path, e))?;
+    let proxies: Vec<String> = content
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+        .collect();
+    if proxies.is_empty() {
+        return Err(format!("Proxy list '{}' is empty", path));
+    }
+    for proxy in &proxies {
+        check_proxy(proxy).map_err(|e| format!("Proxy list '{}': {}", path, e))?;
+    }
+    Ok(proxies)
 }
 
-fn parse_threshold_expr(metric: ThresholdMetric, expr: &str) -> Result<Threshold, String> {
-    let expr = expr.trim();
+/// Certificates in a pool directory (each `NAME.crt` or `NAME.pem` with its
+/// `NAME.key`) or listed in a file (`CERT KEY` per line, relative to the file)
+fn read_client_cert_pool(path: &Path) -> Result<Vec<ClientIdentity>, String> {
+    let context = |e: std::io::Error| {
+        format!(
+            "Failed to read client certificate pool '{}': {}",
+            path.display(),
+            e
+        )
+    };
+    let mut pool = Vec::new();
+    if path.is_dir() {
+        let mut certs: Vec<PathBuf> = fs::read_dir(path)
+            .map_err(context)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.extension()
+                    .is_some_and(|ext| ext == "crt" || ext == "pem")
+            })
+            .collect();
+        certs.sort();
+        for cert in certs {
+            let key = cert.with_extension("key");
+            if !key.exists() {
+                return Err(format!(
+                    "Client certificate pool: no key for {} (expected {})",
+                    cert.display(),
+                    key.display()
+                ));
+            }
+            pool.push(ClientIdentity { cert, key });
+        }
+    } else {
+        let base = path.parent().unwrap_or(Path::new("."));
+        let content = fs::read_to_string(path).map_err(context)?;
+        for line in content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        {
+            let mut files = line.split_whitespace();
+            let (Some(cert), Some(key), None) = (files.next(), files.next(), files.next()) else {
+                return Err(format!(
+                    "Client certificate pool '{}': expected 'CERT KEY', got '{}'",
+                    path.display(),
+                    line
+                ));
+            };
+            let (cert, key) = (base.join(cert), base.join(key));
+            for file in [&cert, &key] {
+                if !file.exists() {
+                    return Err(format!(
+                        "Client certificate pool '{}': file not found: {}",
+                        path.display(),
+                        file.display()
+                    ));
+                }
+            }
+            pool.push(ClientIdentity { cert, key });
+        }
+    }
+    if pool.is_empty() {
+        return Err(format!(
+            "Client certificate pool '{}' has no certificates",
+            path.display()
+        ));
+    }
+    Ok(pool)
+}
+
+fn check_proxy(proxy: &str) -> Result<(), String> {
+    reqwest::Proxy::all(proxy)
+        .map(|_| ())
+        .map_err(|e| format!("invalid proxy '{}': {}", proxy, e))
+}
+
+/// Check and sort histogram bucket bounds (ms)
+fn parse_prometheus_buckets(list: Vec<f64>) -> Result<Vec<f64>, String> {
+    if list.is_empty() {
+        return Err("prometheus_buckets must list at least one bound".to_string());
+    }
+    let mut buckets = Vec::with_capacity(list.len());
+    for bound in list {
+        if !(bound.is_finite() && bound > 0.0) {
+            return Err(format!(
+                "Prometheus bucket {} must be a positive number of ms",
+                bound
+            ));
+        }
+        buckets.push(bound);
+    }
+    buckets.sort_by(f64::total_cmp);
+    buckets.dedup();
+    Ok(buckets)
+}
+
+/// Check and sort a percentile list; each may have up to 3 decimal places
+fn parse_percentiles(list: Vec<f64>) -> Result<Vec<f64>, String> {
+    if list.is_empty() {
+        return Err("percentiles must list at least one percentile".to_string());
+    }
+    let mut percentiles = Vec::with_capacity(list.len());
+    for p in list {
+        if !(p > 0.0 && p < 100.0) {
+            return Err(format!("Percentile {} must be between 0 and 100", p));
+        }
+        let thousandths = p * 1000.0;
+        if (thousandths - thousandths.round()).abs() > 1e-6 {
+            return Err(format!("Percentile {} has more than 3 decimal places", p));
+        }
+        percentiles.push(thousandths.round() / 1000.0);
+    }
+    percentiles.sort_by(f64::total_cmp);
+    percentiles.dedup();
+    Ok(percentiles)
+}
+
+fn parse_thresholds(config: &ThresholdsConfig) -> Result<Vec<Threshold>, String> {
+    config
+        .values
+        .iter()
+        .map(|(metric, expr)| parse_threshold_expr(*metric, expr))
+        .collect()
+}
+
+fn parse_trend_thresholds(config: &ThresholdsConfig) -> Result<Vec<TrendThreshold>, String> {
+    config
+        .trends
+        .iter()
+        .map(|(metric, expr)| parse_trend_threshold_expr(*metric, expr))
+        .collect()
+}
+
+enum CliThreshold {
+    Value(Threshold),
+    Trend(TrendThreshold),
+}
+
+/// `--threshold p99_latency_ms<500`: a `[thresholds]` entry on one line
+fn parse_cli_threshold(threshold: &str) -> Result<CliThreshold, String> {
+    let split = threshold.find(['<', '>', '=']).ok_or_else(|| {
+        format!(
+            "Invalid --threshold '{}'. Expected METRIC<VALUE, e.g. 'p99_latency_ms<500'",
+            threshold
+        )
+    })?;
+    let (name, expr) = threshold.split_at(split);
+    let name = name.trim();
+    if let Some(metric) = name.strip_suffix("_trend").and_then(ThresholdMetric::parse) {
+        return parse_trend_threshold_expr(metric, expr).map(CliThreshold::Trend);
+    }
+    let metric = ThresholdMetric::parse(name)
+        .ok_or_else(|| format!("Unknown threshold metric '{}'", name))?;
+    parse_threshold_expr(metric, expr).map(CliThreshold::Value)
+}
 
-    // Parse operator and value: "< 500", "<= 500", "> 100", ">= 100", "== 500"
-    let (operator, value_str) = if let Some(rest) = expr.strip_prefix("<=") {
-        (ThresholdOp::Lte, rest.trim())
+/// "< 500", "<= 500", "> 100", ">= 100", "== 500" into the operator and the rest
+fn split_threshold_op(expr: &str) -> Option<(ThresholdOp, &str)> {
+    let expr = expr.trim();
+    let (operator, rest) = if let Some(rest) = expr.strip_prefix("<=") {
+        (ThresholdOp::Lte, rest)
     } else if let Some(rest) = expr.strip_prefix(">=") {
-        (ThresholdOp::Gte, rest.trim())
+        (ThresholdOp::Gte, rest)
     } else if let Some(rest) = expr.strip_prefix("==") {
-        (ThresholdOp::Eq, rest.trim())
+        (ThresholdOp::Eq, rest)
     } else if let Some(rest) = expr.strip_prefix('<') {
-        (ThresholdOp::Lt, rest.trim())
+        (ThresholdOp::Lt, rest)
     } else if let Some(rest) = expr.strip_prefix('>') {
-        (ThresholdOp::Gt, rest.trim())
+        (ThresholdOp::Gt, rest)
     } else {
-        return Err(format!(
-            "Invalid threshold expression for '{}': '{}'. Expected format: '< 500' or '>= 100'",
-            metric.as_str(),
-            expr
-        ));
+        return None;
     };
+    Some((operator, rest.trim()))
+}
+
+pub(crate) fn parse_threshold_expr(
+    metric: ThresholdMetric,
+    expr: &str,
+) -> Result<Threshold, String> {
+    let (operator, value_str) = split_threshold_op(expr).ok_or_else(|| {
+        format!(
+            "Invalid threshold expression for '{}': '{}'. Expected format: '< 500' or '>= 100'",
+            metric,
+            expr.trim()
+        )
+    })?;
 
     let value: f64 = value_str.parse().map_err(|_| {
         format!(
             "Invalid threshold value for '{}': '{}'. Expected a number.",
-            metric.as_str(),
-            value_str
+            metric, value_str
         )
     })?;
 
@@ -938,6 +3893,39 @@ fn parse_threshold_expr(metric: ThresholdMetric, expr: &str) -> Result<Threshold
     })
 }
 
+/// `< +10% over 7d`: the change from the median of the runs in history
+/// within the window, in percent
+pub(crate) fn parse_trend_threshold_expr(
+    metric: ThresholdMetric,
+    expr: &str,
+) -> Result<TrendThreshold, String> {
+    let invalid = || {
+        format!(
+            "Invalid trend threshold for '{}_trend': '{}'. Expected format: '< +10% over 7d'",
+            metric,
+            expr.trim()
+        )
+    };
+    let (operator, rest) = split_threshold_op(expr).ok_or_else(invalid)?;
+    let (change, window) = rest.split_once(" over ").ok_or_else(invalid)?;
+    let change_pct: f64 = change
+        .trim()
+        .strip_suffix('%')
+        .and_then(|pct| pct.trim().parse().ok())
+        .filter(|pct: &f64| pct.is_finite())
+        .ok_or_else(invalid)?;
+    let window = humantime::parse_duration(window.trim())
+        .ok()
+        .filter(|window| !window.is_zero())
+        .ok_or_else(invalid)?;
+    Ok(TrendThreshold {
+        metric,
+        operator,
+        change_pct,
+        window,
+    })
+}
+
 fn parse_checks(configs: &[CheckConfig]) -> Result<Vec<Check>, String> {
     let mut checks = Vec::with_capacity(configs.len());
 
@@ -954,7 +3942,7 @@ fn parse_checks(configs: &[CheckConfig]) -> Result<Vec<Check>, String> {
     Ok(checks)
 }
 
-fn parse_check_condition(expr: &str) -> Result<CheckCondition, String> {
+pub(crate) fn parse_check_condition(expr: &str) -> Result<CheckCondition, String> {
     let expr = expr.trim();
 
     // status == 200
@@ -1033,10 +4021,18 @@ fn process_stages(configs: &[StageConfig]) -> Result<Vec<Stage>, String> {
             ));
         }
 
+        if cfg.target_message_rate.is_some() && (cfg.target.is_some() || cfg.target_rate.is_some())
+        {
+            return Err(format!(
+                "Stage {} cannot combine 'target_message_rate' with 'target' or 'target_rate'",
+                i + 1
+            ));
+        }
+
         // Validate: must have at least one
-        if cfg.target.is_none() && cfg.target_rate.is_none() {
+        if cfg.target.is_none() && cfg.target_rate.is_none() && cfg.target_message_rate.is_none() {
             return Err(format!(
-                "Stage {} must have either 'target' (VUs) or 'target_rate' (RPS)",
+                "Stage {} must have either 'target' (VUs), 'target_rate' (RPS) or 'target_message_rate' (WebSocket messages/sec)",
                 i + 1
             ));
         }
@@ -1045,17 +4041,25 @@ fn process_stages(configs: &[StageConfig]) -> Result<Vec<Stage>, String> {
             duration: cfg.duration,
             target: cfg.target,
             target_rate: cfg.target_rate,
+            target_message_rate: cfg.target_message_rate,
         });
     }
 
     // Validate: all stages must use the same mode
     let has_vu_stages = stages.iter().any(|s| s.target.is_some());
     let has_rate_stages = stages.iter().any(|s| s.target_rate.is_some());
+    let has_message_rate_stages = stages.iter().any(|s| s.target_message_rate.is_some());
     if has_vu_stages && has_rate_stages {
         return Err(
             "Cannot mix VU-based stages (target) with rate-based stages (target_rate)".to_string(),
         );
     }
+    if has_message_rate_stages && (has_vu_stages || has_rate_stages) {
+        return Err(
+            "Cannot mix message-rate stages (target_message_rate) with target or target_rate stages"
+                .to_string(),
+        );
+    }
 
     Ok(stages)
 }
@@ -0,0 +1,220 @@
+//! Control socket for running tests (`run --control-port`, `kaioken ctl`)
+//!
+//! A running test listens on a localhost port for one-line commands and
+//! answers each with one line of JSON, so a long soak can be checked on,
+//! extended, re-rated or stopped without killing the process.
+
+use crate::cli::{CtlArgs, CtlCommand};
+use crate::engine::RunControl;
+use crate::serve::{live_json, state_str};
+use crate::types::{RunState, StatsSnapshot};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// Longest command line accepted
+const MAX_COMMAND_LEN: u64 = 1024;
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Handles of the running test that commands act on
+#[derive(Clone)]
+pub struct ControlTarget {
+    pub control: Arc<RunControl>,
+    pub cancel: CancellationToken,
+    pub snapshot_rx: watch::Receiver<StatsSnapshot>,
+    pub state_rx: watch::Receiver<RunState>,
+}
+
+/// Bind the control port on localhost and serve commands until aborted
+pub async fn start_server(port: u16, target: ControlTarget) -> Result<JoinHandle<()>, String> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to bind control port {}: {}", port, e))?;
+
+    Ok(tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let target = target.clone();
+                    tokio::spawn(async move { handle(stream, &target).await });
+                }
+                Err(e) => tracing::warn!("Failed to accept control connection: {}", e),
+            }
+        }
+    }))
+}
+
+async fn handle(stream: TcpStream, target: &ControlTarget) {
+    let (read, mut write) = stream.into_split();
+    let mut line = String::new();
+    if BufReader::new(read.take(MAX_COMMAND_LEN))
+        .read_line(&mut line)
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    let reply = match execute(line.trim(), target) {
+        Ok(Value::Object(mut fields)) => {
+            fields.insert("ok".to_string(), Value::Bool(true));
+            Value::Object(fields)
+        }
+        Ok(other) => json!({ "ok": true, "result": other }),
+        Err(e) => json!({ "ok": false, "error": e }),
+    };
+    let _ = write.write_all(format!("{}\n", reply).as_bytes()).await;
+}
+
+fn execute(command: &str, target: &ControlTarget) -> Result<Value, String> {
+    let mut parts = command.split_whitespace();
+    let (name, arg) = (parts.next(), parts.next());
+    if parts.next().is_some() {
+        return Err(format!("Too many arguments: '{}'", command));
+    }
+
+    let state = *target.state_rx.borrow();
+    if name != Some("status") && state.is_terminal() {
+        return Err(format!("The test has already {}", state_str(state)));
+    }
+
+    match (name, arg) {
+        (Some("status"), None) => Ok(status(target)),
+        (Some("stop"), None) => {
+            target.cancel.cancel();
+            Ok(json!({ "message": "Stopping; results will be written as for Ctrl+C" }))
+        }
+        (Some("extend"), Some(by)) => {
+            let by = humantime::parse_duration(by)
+                .map_err(|e| format!("Invalid duration '{}': {}", by, e))?;
+            let total = target.control.extend(by)?;
+            Ok(json!({
+                "message": format!(
+                    "Extended by {} ({} added in total)",
+                    humantime::format_duration(by),
+                    humantime::format_duration(total)
+                ),
+            }))
+        }
+        (Some("set-rate"), Some(rate)) => {
+            let rate: u32 = rate
+                .parse()
+                .map_err(|_| format!("Invalid rate '{}'", rate))?;
+            target.control.set_rate(rate)?;
+            Ok(json!({ "message": format!("Target rate set to {} req/s", rate) }))
+        }
+        _ => Err(format!(
+            "Unknown command '{}' (expected status, stop, extend DURATION or set-rate RPS)",
+            command
+        )),
+    }
+}
+
+fn status(target: &ControlTarget) -> Value {
+    let snapshot = target.snapshot_rx.borrow().clone();
+    let plan = target.control.plan();
+    let mut status = live_json(&snapshot);
+    status["state"] = json!(state_str(*target.state_rx.borrow()));
+    if !plan.duration.is_zero() {
+        let total = plan.duration + plan.extended.unwrap_or_default();
+        status["duration_secs"] = json!(total.as_secs_f64());
+        status["remaining_secs"] = json!(total.saturating_sub(snapshot.elapsed).as_secs_f64());
+    }
+    status["extended_secs"] = json!(plan.extended.map(|d| d.as_secs_f64()));
+    status["rate"] = json!(plan.rate);
+    status
+}
+
+pub async fn run_ctl(args: &CtlArgs) -> Result<i32, String> {
+    let command = match args.command {
+        CtlCommand::Status => "status".to_string(),
+        CtlCommand::Stop => "stop".to_string(),
+        CtlCommand::Extend { duration } => format!("extend {}ms", duration.as_millis()),
+        CtlCommand::SetRate { rate } => format!("set-rate {}", rate),
+    };
+
+    let reply = tokio::time::timeout(CLIENT_TIMEOUT, send(args.port, &command))
+        .await
+        .map_err(|_| format!("Control port {} did not answer", args.port))??;
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&reply)
+                .map_err(|e| format!("Failed to serialize reply: {}", e))?
+        );
+        return Ok(if reply["ok"] == true { 0 } else { 1 });
+    }
+
+    if reply["ok"] != true {
+        return Err(reply["error"]
+            .as_str()
+            .unwrap_or("Command failed")
+            .to_string());
+    }
+    match args.command {
+        CtlCommand::Status => print_status(&reply),
+        _ => println!("{}", reply["message"].as_str().unwrap_or("OK")),
+    }
+    Ok(0)
+}
+
+async fn send(port: u16, command: &str) -> Result<Value, String> {
+    let mut stream = TcpStream::connect(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("No test is listening on control port {}: {}", port, e))?;
+    stream
+        .write_all(format!("{}\n", command).as_bytes())
+        .await
+        .map_err(|e| format!("Failed to send command: {}", e))?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .await
+        .map_err(|e| format!("Failed to read reply: {}", e))?;
+    serde_json::from_str(&line).map_err(|e| format!("Invalid reply from control port: {}", e))
+}
+
+fn print_status(status: &Value) {
+    let secs = |v: &Value| {
+        humantime::format_duration(Duration::from_secs(v.as_f64().unwrap_or(0.0).round() as u64))
+            .to_string()
+    };
+
+    println!(
+        "State:     {}",
+        status["state"].as_str().unwrap_or("unknown")
+    );
+    let mut progress = secs(&status["elapsed_secs"]);
+    if !status["duration_secs"].is_null() {
+        progress.push_str(&format!(
+            " of {} ({} left",
+            secs(&status["duration_secs"]),
+            secs(&status["remaining_secs"])
+        ));
+        if status["extended_secs"].as_f64().is_some_and(|s| s > 0.0) {
+            progress.push_str(&format!(", {} extended", secs(&status["extended_secs"])));
+        }
+        progress.push(')');
+    }
+    println!("Elapsed:   {}", progress);
+    println!(
+        "Requests:  {} ({:.1} req/s, {:.2}% errors)",
+        status["total_requests"].as_u64().unwrap_or(0),
+        status["rps"].as_f64().unwrap_or(0.0),
+        status["error_rate"].as_f64().unwrap_or(0.0) * 100.0
+    );
+    println!(
+        "p99:       {:.2}ms",
+        status["latency_ms"]["p99"].as_f64().unwrap_or(0.0)
+    );
+    if let Some(rate) = status["rate"].as_u64() {
+        println!("Rate:      {} req/s", rate);
+    }
+}
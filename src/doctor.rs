@@ -0,0 +1,443 @@
+//! Environment diagnostics (`kaioken doctor`)
+//!
+//! Checks the OS limits a load test runs into before it does: running out of
+//! file descriptors or ephemeral ports shows up mid-run as a burst of connect
+//! errors that looks like the target failing. `run` repeats the cheap checks
+//! as pre-flight warnings.
+
+use crate::calibrate::{requested_connections, requested_rps};
+use crate::cli::{DoctorArgs, RunArgs};
+use crate::config::{load_config, merge_config};
+use crate::types::LoadConfig;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Descriptors kaioken needs besides connections (stdio, runtime, output files)
+const FD_OVERHEAD: u64 = 64;
+/// Rough memory per open connection: socket buffers, pooled client state, task
+const BYTES_PER_CONNECTION: u64 = 64 * 1024;
+/// How long a closed client socket holds its port in TIME_WAIT on Linux
+const TIME_WAIT_SECS: u64 = 60;
+const DNS_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+    Skip,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, detail: String) -> Self {
+        Self {
+            name,
+            status,
+            detail,
+        }
+    }
+
+    fn unknown(name: &'static str) -> Self {
+        Self::new(
+            name,
+            Status::Skip,
+            "not available on this system".to_string(),
+        )
+    }
+}
+
+pub async fn run_doctor(args: &DoctorArgs) -> Result<i32, String> {
+    let config = if args.url.is_some() || args.config.is_some() {
+        let toml = args.config.as_deref().map(load_config).transpose()?;
+        let run_args = RunArgs {
+            url: args.url.clone(),
+            concurrency: args.concurrency,
+            config: args.config.clone(),
+            ..Default::default()
+        };
+        Some(merge_config(&run_args, toml)?)
+    } else {
+        None
+    };
+
+    let connections = config
+        .as_ref()
+        .map(requested_connections)
+        .unwrap_or(args.concurrency);
+    let rps = config.as_ref().and_then(requested_rps);
+    let keepalive = !config.as_ref().is_some_and(|c| c.disable_keepalive);
+    let target_host = config.as_ref().and_then(|c| target_host(&c.url));
+
+    let checks = vec![
+        check_open_files(connections),
+        check_ephemeral_ports(connections, rps, keepalive),
+        check_somaxconn(connections, target_host.as_ref()),
+        check_memory(connections),
+        check_dns(target_host.as_ref()).await,
+    ];
+    let ok = !checks.iter().any(|c| c.status == Status::Fail);
+
+    if args.json {
+        let output = serde_json::json!({
+            "ok": ok,
+            "target": config.as_ref().map(|c| c.url.as_str()),
+            "connections": connections,
+            "rps": rps,
+            "checks": checks,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&output)
+                .map_err(|e| format!("Failed to serialize report: {}", e))?
+        );
+    } else {
+        match config {
+            Some(ref config) => println!(
+                "kaioken doctor: {} connections to {}\n",
+                connections, config.url
+            ),
+            None => println!("kaioken doctor: {} connections\n", connections),
+        }
+        for check in &checks {
+            let icon = match check.status {
+                Status::Ok => "✓",
+                Status::Warn => "⚠",
+                Status::Fail => "✗",
+                Status::Skip => "-",
+            };
+            println!("  {} {:<16} {}", icon, check.name, check.detail);
+        }
+        if !ok {
+            println!("\nFix the failed checks above before running, or lower --concurrency.");
+        }
+    }
+
+    Ok(if ok { 0 } else { 1 })
+}
+
+/// Pre-flight warnings for `run`: limits the test is certain to hit
+pub fn limit_warnings(config: &LoadConfig) -> Vec<String> {
+    let connections = requested_connections(config);
+    [
+        check_open_files(connections),
+        check_ephemeral_ports(
+            connections,
+            requested_rps(config),
+            !config.disable_keepalive,
+        ),
+    ]
+    .into_iter()
+    .filter(|c| c.status == Status::Fail)
+    .map(|c| c.detail)
+    .collect()
+}
+
+fn check_open_files(connections: u32) -> Check {
+    let Some(limit) = open_files_limit() else {
+        return Check::unknown("open files");
+    };
+    let needed = connections as u64 + FD_OVERHEAD;
+    if limit < needed {
+        Check::new(
+            "open files",
+            Status::Fail,
+            format!(
+                "open files limit is {} but {} connections need ~{}; raise it with `ulimit -n {}`",
+                limit,
+                connections,
+                needed,
+                needed.next_power_of_two()
+            ),
+        )
+    } else {
+        Check::new(
+            "open files",
+            Status::Ok,
+            format!("limit {} (need ~{})", format_limit(limit), needed),
+        )
+    }
+}
+
+fn check_ephemeral_ports(connections: u32, rps: Option<u32>, keepalive: bool) -> Check {
+    let Some((low, high)) = ephemeral_port_range() else {
+        return Check::unknown("ephemeral ports");
+    };
+    let ports = (high as u64).saturating_sub(low as u64) + 1;
+
+    if connections as u64 > ports {
+        return Check::new(
+            "ephemeral ports",
+            Status::Fail,
+            format!(
+                "{} connections to one target exceed the {} local ports in {}-{}; widen net.ipv4.ip_local_port_range",
+                connections, ports, low, high
+            ),
+        );
+    }
+
+    // Without keepalive every request takes a fresh port that then sits in TIME_WAIT
+    if !keepalive {
+        let sustainable = ports / TIME_WAIT_SECS;
+        match rps {
+            Some(rps) if rps as u64 > sustainable => {
+                return Check::new(
+                    "ephemeral ports",
+                    Status::Fail,
+                    format!(
+                        "{} new connections/s without keepalive exhaust {} ports in TIME_WAIT (~{}/s sustainable)",
+                        rps, ports, sustainable
+                    ),
+                );
+            }
+            None => {
+                return Check::new(
+                    "ephemeral ports",
+                    Status::Warn,
+                    format!(
+                        "{} ports ({}-{}); without keepalive an unthrottled run exhausts them above ~{} requests/s",
+                        ports, low, high, sustainable
+                    ),
+                );
+            }
+            Some(_) => {}
+        }
+    }
+
+    Check::new(
+        "ephemeral ports",
+        Status::Ok,
+        format!("{} ports ({}-{})", ports, low, high),
+    )
+}
+
+fn check_somaxconn(connections: u32, target: Option<&(String, u16)>) -> Check {
+    let Some(somaxconn) = somaxconn() else {
+        return Check::unknown("somaxconn");
+    };
+    // The listen backlog only matters here when the server runs on this machine
+    let local = target.is_some_and(|(host, _)| is_local(host));
+    if local && connections as u64 > somaxconn {
+        Check::new(
+            "somaxconn",
+            Status::Warn,
+            format!(
+                "{} for a local target; {} connections opening at once can overflow the accept queue (raise net.core.somaxconn)",
+                somaxconn, connections
+            ),
+        )
+    } else {
+        Check::new("somaxconn", Status::Ok, somaxconn.to_string())
+    }
+}
+
+fn check_memory(connections: u32) -> Check {
+    let Some(available) = available_memory() else {
+        return Check::unknown("memory");
+    };
+    let estimate = connections as u64 * BYTES_PER_CONNECTION;
+    let status = if estimate > available / 2 {
+        Status::Warn
+    } else {
+        Status::Ok
+    };
+    Check::new(
+        "memory",
+        status,
+        format!(
+            "{} available, ~{} estimated for {} connections",
+            format_bytes(available),
+            format_bytes(estimate),
+            connections
+        ),
+    )
+}
+
+async fn check_dns(target: Option<&(String, u16)>) -> Check {
+    let Some((host, port)) = target else {
+        return Check::new("dns", Status::Skip, "no target given".to_string());
+    };
+    let start = Instant::now();
+    match tokio::time::timeout(DNS_TIMEOUT, tokio::net::lookup_host((host.as_str(), *port))).await {
+        Ok(Ok(addrs)) => {
+            let mut addrs: Vec<String> = addrs.map(|a| a.ip().to_string()).collect();
+            addrs.dedup();
+            if addrs.is_empty() {
+                return Check::new(
+                    "dns",
+                    Status::Fail,
+                    format!("{} resolved to no addresses", host),
+                );
+            }
+            Check::new(
+                "dns",
+                Status::Ok,
+                format!(
+                    "{} -> {} ({:.1}ms)",
+                    host,
+                    addrs.join(", "),
+                    start.elapsed().as_secs_f64() * 1000.0
+                ),
+            )
+        }
+        Ok(Err(e)) => Check::new(
+            "dns",
+            Status::Fail,
+            format!("failed to resolve {}: {}", host, e),
+        ),
+        Err(_) => Check::new(
+            "dns",
+            Status::Fail,
+            format!(
+                "resolving {} timed out after {}s",
+                host,
+                DNS_TIMEOUT.as_secs()
+            ),
+        ),
+    }
+}
+
+/// Host and port to resolve, for any URL scheme kaioken targets
+fn target_host(url: &str) -> Option<(String, u16)> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let host = parsed.host_str()?.trim_matches(['[', ']']).to_string();
+    Some((host, parsed.port_or_known_default().unwrap_or(0)))
+}
+
+fn is_local(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback() || ip.is_unspecified())
+}
+
+fn format_limit(limit: u64) -> String {
+    if limit == u64::MAX {
+        "unlimited".to_string()
+    } else {
+        limit.to_string()
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const MIB: f64 = 1024.0 * 1024.0;
+    let mib = bytes as f64 / MIB;
+    if mib >= 1024.0 {
+        format!("{:.1} GiB", mib / 1024.0)
+    } else {
+        format!("{:.0} MiB", mib)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn open_files_limit() -> Option<u64> {
+    // "Max open files            1024                 1048576              files"
+    let limits = std::fs::read_to_string("/proc/self/limits").ok()?;
+    let line = limits.lines().find(|l| l.starts_with("Max open files"))?;
+    let soft = line
+        .trim_start_matches("Max open files")
+        .split_whitespace()
+        .next()?;
+    if soft == "unlimited" {
+        Some(u64::MAX)
+    } else {
+        soft.parse().ok()
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn open_files_limit() -> Option<u64> {
+    let output = std::process::Command::new("sh")
+        .args(["-c", "ulimit -n"])
+        .output()
+        .ok()?;
+    let soft = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if soft == "unlimited" {
+        Some(u64::MAX)
+    } else {
+        soft.parse().ok()
+    }
+}
+
+#[cfg(not(unix))]
+fn open_files_limit() -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn ephemeral_port_range() -> Option<(u16, u16)> {
+    let range = std::fs::read_to_string("/proc/sys/net/ipv4/ip_local_port_range").ok()?;
+    let mut parts = range.split_whitespace();
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+#[cfg(target_os = "macos")]
+fn ephemeral_port_range() -> Option<(u16, u16)> {
+    Some((
+        sysctl("net.inet.ip.portrange.first")?.parse().ok()?,
+        sysctl("net.inet.ip.portrange.last")?.parse().ok()?,
+    ))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn ephemeral_port_range() -> Option<(u16, u16)> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn somaxconn() -> Option<u64> {
+    std::fs::read_to_string("/proc/sys/net/core/somaxconn")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(target_os = "macos")]
+fn somaxconn() -> Option<u64> {
+    sysctl("kern.ipc.somaxconn")?.parse().ok()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn somaxconn() -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn available_memory() -> Option<u64> {
+    // "MemAvailable:   12345678 kB"
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|l| l.starts_with("MemAvailable:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(target_os = "macos")]
+fn available_memory() -> Option<u64> {
+    // macOS has no cheap "available" figure; report physical memory instead
+    sysctl("hw.memsize")?.parse().ok()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn available_memory() -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn sysctl(name: &str) -> Option<String> {
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", name])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -1,19 +0,0 @@
-mod aggregator;
-mod arrival_rate;
-pub mod prometheus;
-mod runner;
-mod scheduler;
-mod snapshot;
-mod stats;
-mod thresholds;
-mod worker;
-mod ws_aggregator;
-mod ws_stats;
-mod ws_worker;
-
-pub use runner::Engine;
-
-pub use snapshot::{create_snapshot, create_snapshot_with_arrival_rate};
-pub use stats::Stats;
-pub use thresholds::{evaluate_thresholds, print_threshold_results};
-pub use ws_stats::WsStats;
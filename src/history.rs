@@ -0,0 +1,339 @@
+//! Run history stored in the `--db-url` SQLite database (`kaioken history`)
+//!
+//! Every run gets a row in `runs`; the aggregator logs its snapshots against
+//! that row, and the final JSON result is stored once the run finishes so
+//! runs can be shown and compared later without keeping result files around.
+
+use crate::cli::{CompareArgs, HistoryArgs, HistoryCommand, HistoryCompareArgs};
+use crate::compare::{compare_outputs, display::print_comparison_json, print_comparison};
+use crate::output::json::JsonOutput;
+use crate::output::schema::read_result;
+use chrono::{DateTime, Utc};
+use kaioken_core::history::{HistoryRun, open_db};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Store the final result and exit code of a run
+pub fn finish_run(run: &HistoryRun, output: &JsonOutput, exit_code: i32) -> Result<(), String> {
+    let result_json =
+        serde_json::to_string(output).map_err(|e| format!("Failed to serialize result: {}", e))?;
+    let conn =
+        open_db(&run.path).map_err(|e| format!("Failed to open {}: {}", run.path.display(), e))?;
+    conn.execute(
+        "UPDATE runs SET finished_at = ?1, exit_code = ?2, result_json = ?3 WHERE id = ?4",
+        rusqlite::params![Utc::now().to_rfc3339(), exit_code, result_json, run.id],
+    )
+    .map_err(|e| format!("Failed to record run result: {}", e))?;
+    Ok(())
+}
+
+/// Results of the latest `limit` finished runs against `url`, oldest first
+pub fn recent_results(path: &Path, url: &str, limit: usize) -> Result<Vec<JsonOutput>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = open_db(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT result_json FROM runs
+             WHERE url = ?1 AND result_json IS NOT NULL
+             ORDER BY id DESC LIMIT ?2",
+        )
+        .map_err(|e| format!("Failed to read runs: {}", e))?;
+    let rows = stmt
+        .query_map(rusqlite::params![url, limit as i64], |row| {
+            row.get::<_, String>(0)
+        })
+        .map_err(|e| format!("Failed to read runs: {}", e))?;
+
+    let mut results = Vec::new();
+    for json in rows {
+        let json = json.map_err(|e| format!("Failed to read runs: {}", e))?;
+        // Results too old or too new to read just don't count
+        if let Ok((result, _)) = read_result(&json) {
+            results.push(result);
+        }
+    }
+    results.reverse();
+    Ok(results)
+}
+
+/// Results of the finished runs against `url` started since `since`, with
+/// their start times
+pub fn results_since(
+    path: &Path,
+    url: &str,
+    since: DateTime<Utc>,
+) -> Result<Vec<(DateTime<Utc>, JsonOutput)>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = open_db(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT started_at, result_json FROM runs
+             WHERE url = ?1 AND result_json IS NOT NULL
+             ORDER BY id",
+        )
+        .map_err(|e| format!("Failed to read runs: {}", e))?;
+    let rows = stmt
+        .query_map(rusqlite::params![url], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| format!("Failed to read runs: {}", e))?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        let (started_at, json) = row.map_err(|e| format!("Failed to read runs: {}", e))?;
+        let Ok(started_at) = DateTime::parse_from_rfc3339(&started_at) else {
+            continue;
+        };
+        let started_at = started_at.with_timezone(&Utc);
+        if started_at < since {
+            continue;
+        }
+        if let Ok((result, _)) = read_result(&json) {
+            results.push((started_at, result));
+        }
+    }
+    Ok(results)
+}
+
+/// A stored run with metrics from its last snapshot
+#[derive(Debug, Serialize)]
+struct RunRow {
+    id: i64,
+    started_at: String,
+    finished_at: Option<String>,
+    url: String,
+    exit_code: Option<i32>,
+    snapshots: u64,
+    elapsed_secs: Option<f64>,
+    total_requests: Option<u64>,
+    requests_per_sec: Option<f64>,
+    error_rate: Option<f64>,
+    latency_p50_us: Option<u64>,
+    latency_p95_us: Option<u64>,
+    latency_p99_us: Option<u64>,
+    latency_p999_us: Option<u64>,
+    bytes_received: Option<u64>,
+}
+
+const RUN_QUERY: &str = "SELECT r.id, r.started_at, r.finished_at, r.url, r.exit_code,
+        (SELECT COUNT(*) FROM snapshots WHERE run_id = r.id),
+        s.elapsed_secs, s.total_requests, s.rps, s.error_rate,
+        s.latency_p50_us, s.latency_p95_us, s.latency_p99_us, s.latency_p999_us,
+        s.bytes_received
+    FROM runs r
+    LEFT JOIN snapshots s ON s.id = (SELECT MAX(id) FROM snapshots WHERE run_id = r.id)";
+
+fn run_row(row: &rusqlite::Row) -> rusqlite::Result<RunRow> {
+    Ok(RunRow {
+        id: row.get(0)?,
+        started_at: row.get(1)?,
+        finished_at: row.get(2)?,
+        url: row.get(3)?,
+        exit_code: row.get(4)?,
+        snapshots: row.get(5)?,
+        elapsed_secs: row.get(6)?,
+        total_requests: row.get(7)?,
+        requests_per_sec: row.get(8)?,
+        error_rate: row.get(9)?,
+        latency_p50_us: row.get(10)?,
+        latency_p95_us: row.get(11)?,
+        latency_p99_us: row.get(12)?,
+        latency_p999_us: row.get(13)?,
+        bytes_received: row.get(14)?,
+    })
+}
+
+pub fn run_history(args: &HistoryArgs) -> Result<i32, String> {
+    let Some(ref path) = args.db_url else {
+        return Err(
+            "No database given. Pass the database runs were stored in: --db-url PATH".to_string(),
+        );
+    };
+    if !path.exists() {
+        return Err(format!("Database '{}' does not exist", path.display()));
+    }
+    let conn = open_db(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+    match args.command {
+        HistoryCommand::List { limit, json } => list_runs(&conn, limit, json),
+        HistoryCommand::Show { id, json } => show_run(&conn, id, json),
+        HistoryCommand::Compare(ref compare) => compare_runs(&conn, compare),
+    }
+}
+
+fn list_runs(conn: &Connection, limit: usize, json: bool) -> Result<i32, String> {
+    let runs = conn
+        .prepare(&format!("{} ORDER BY r.id DESC LIMIT ?1", RUN_QUERY))
+        .and_then(|mut stmt| {
+            stmt.query_map([limit as i64], run_row)?
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| format!("Failed to read runs: {}", e))?;
+
+    if json {
+        let out = serde_json::to_string_pretty(&runs)
+            .map_err(|e| format!("Failed to serialize runs: {}", e))?;
+        println!("{}", out);
+        return Ok(0);
+    }
+
+    if runs.is_empty() {
+        println!("No runs stored yet");
+        return Ok(0);
+    }
+
+    println!(
+        "{:>5}  {:<20}  {:>10}  {:>10}  {:>10}  {:>7}  {:>6}  URL",
+        "ID", "STARTED", "REQUESTS", "RPS", "P99 (ms)", "ERRORS", "EXIT"
+    );
+    for run in &runs {
+        println!(
+            "{:>5}  {:<20}  {:>10}  {:>10}  {:>10}  {:>7}  {:>6}  {}",
+            run.id,
+            format_started(&run.started_at),
+            run.total_requests
+                .map_or("-".to_string(), |n| n.to_string()),
+            run.requests_per_sec
+                .map_or("-".to_string(), |r| format!("{:.1}", r)),
+            run.latency_p99_us
+                .map_or("-".to_string(), |us| format!("{:.2}", us as f64 / 1000.0)),
+            run.error_rate
+                .map_or("-".to_string(), |e| format!("{:.2}%", e * 100.0)),
+            run.exit_code.map_or("-".to_string(), |c| c.to_string()),
+            run.url
+        );
+    }
+    Ok(0)
+}
+
+fn show_run(conn: &Connection, id: i64, json: bool) -> Result<i32, String> {
+    let run = conn
+        .query_row(&format!("{} WHERE r.id = ?1", RUN_QUERY), [id], run_row)
+        .optional()
+        .map_err(|e| format!("Failed to read run {}: {}", id, e))?
+        .ok_or_else(|| format!("No run with ID {}", id))?;
+
+    if json {
+        // The full result when the run finished, otherwise what was logged
+        let out = match load_result(conn, id)? {
+            Some(result) => serde_json::to_string_pretty(&result),
+            None => serde_json::to_string_pretty(&run),
+        }
+        .map_err(|e| format!("Failed to serialize run: {}", e))?;
+        println!("{}", out);
+        return Ok(0);
+    }
+
+    let ms =
+        |us: Option<u64>| us.map_or("-".to_string(), |us| format!("{:.2}ms", us as f64 / 1000.0));
+
+    println!("\nRun #{}", run.id);
+    println!("  URL:          {}", run.url);
+    println!("  Started:      {}", format_started(&run.started_at));
+    match (&run.finished_at, run.exit_code) {
+        (Some(finished), Some(code)) => {
+            println!("  Finished:     {}", format_started(finished));
+            println!("  Exit code:    {}", code);
+        }
+        _ => println!("  Finished:     - (interrupted or still running)"),
+    }
+    println!("  Snapshots:    {}", run.snapshots);
+
+    if run.total_requests.is_none() {
+        return Ok(0);
+    }
+    println!();
+    println!(
+        "  Duration:     {:.1}s",
+        run.elapsed_secs.unwrap_or_default()
+    );
+    println!("  Requests:     {}", run.total_requests.unwrap_or_default());
+    println!(
+        "  RPS:          {:.1}",
+        run.requests_per_sec.unwrap_or_default()
+    );
+    println!(
+        "  Error rate:   {:.2}%",
+        run.error_rate.unwrap_or_default() * 100.0
+    );
+    println!("  Latency p50:  {}", ms(run.latency_p50_us));
+    println!("  Latency p95:  {}", ms(run.latency_p95_us));
+    println!("  Latency p99:  {}", ms(run.latency_p99_us));
+    println!("  Latency p999: {}", ms(run.latency_p999_us));
+    println!(
+        "  Received:     {} bytes",
+        run.bytes_received.unwrap_or_default()
+    );
+    Ok(0)
+}
+
+fn compare_runs(conn: &Connection, args: &HistoryCompareArgs) -> Result<i32, String> {
+    let result_for = |id: i64| {
+        load_result(conn, id)?.ok_or_else(|| {
+            format!(
+                "Run {} has no stored result (it was interrupted or is still running)",
+                id
+            )
+        })
+    };
+    let baseline = result_for(args.baseline)?;
+    let current = result_for(args.current)?;
+
+    let compare_args = CompareArgs {
+        baseline: PathBuf::from(format!("run #{}", args.baseline)),
+        current: PathBuf::from(format!("run #{}", args.current)),
+        threshold_p99: args.threshold_p99,
+        threshold_p999: args.threshold_p999,
+        threshold_error_rate: args.threshold_error_rate,
+        threshold_rps: args.threshold_rps,
+        serious: args.serious,
+        json: args.json,
+        force: args.force,
+    };
+    let result = match compare_outputs(&baseline, &current, &compare_args) {
+        Ok(r) => r,
+        Err(e) if e.contains("Cannot compare") && e.contains("vs") => {
+            eprintln!("Error: {}", e);
+            return Ok(5); // Exit code 5 for load model mismatch
+        }
+        Err(e) => return Err(e),
+    };
+
+    if args.json {
+        print_comparison_json(&result)?;
+    } else {
+        let serious = args.serious || !std::io::IsTerminal::is_terminal(&std::io::stdout());
+        print_comparison(&result, serious);
+    }
+
+    Ok(if result.has_regressions { 3 } else { 0 })
+}
+
+/// The stored JSON result of a run, if it finished
+fn load_result(conn: &Connection, id: i64) -> Result<Option<JsonOutput>, String> {
+    let json: Option<Option<String>> = conn
+        .query_row("SELECT result_json FROM runs WHERE id = ?1", [id], |row| {
+            row.get(0)
+        })
+        .optional()
+        .map_err(|e| format!("Failed to read run {}: {}", id, e))?;
+    match json {
+        None => Err(format!("No run with ID {}", id)),
+        Some(None) => Ok(None),
+        Some(Some(json)) => read_result(&json)
+            .map(|(output, _)| Some(output))
+            .map_err(|e| format!("Failed to parse stored result of run {}: {}", id, e)),
+    }
+}
+
+/// RFC 3339 timestamp shortened to `YYYY-MM-DD HH:MM:SS` (UTC)
+fn format_started(ts: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|_| ts.to_string())
+}
@@ -18,43 +18,43 @@ struct HarLog {
 }
 
 #[derive(Debug, Deserialize)]
-struct HarEntry {
-    request: HarRequest,
+pub(crate) struct HarEntry {
+    pub(crate) request: HarRequest,
     #[serde(default)]
     #[allow(dead_code)]
-    response: Option<HarResponse>,
+    pub(crate) response: Option<HarResponse>,
 }
 
 #[derive(Debug, Deserialize)]
-struct HarRequest {
-    method: String,
-    url: String,
+pub(crate) struct HarRequest {
+    pub(crate) method: String,
+    pub(crate) url: String,
     #[serde(default)]
-    headers: Vec<HarHeader>,
+    pub(crate) headers: Vec<HarHeader>,
     #[serde(default)]
     #[serde(rename = "postData")]
-    post_data: Option<HarPostData>,
+    pub(crate) post_data: Option<HarPostData>,
 }
 
 #[derive(Debug, Deserialize)]
-struct HarHeader {
-    name: String,
-    value: String,
+pub(crate) struct HarHeader {
+    pub(crate) name: String,
+    pub(crate) value: String,
 }
 
 #[derive(Debug, Deserialize)]
-struct HarPostData {
+pub(crate) struct HarPostData {
     #[serde(default)]
-    text: Option<String>,
+    pub(crate) text: Option<String>,
     #[serde(default)]
     #[serde(rename = "mimeType")]
     #[allow(dead_code)]
-    mime_type: Option<String>,
+    pub(crate) mime_type: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
-struct HarResponse {
+pub(crate) struct HarResponse {
     status: u16,
 }
 
@@ -95,10 +95,23 @@ pub fn import_har(path: &Path, filter: Option<&Regex>) -> Result<String, String>
         return Err("HAR file contains no requests".to_string());
     }
 
+    let header = format!(
+        "# Generated from HAR file by kaioken import\n# Source: {}\n",
+        path.display()
+    );
+    entries_to_config(&har.log.entries, filter, &header)
+        .ok_or_else(|| "No matching HTTP requests found in HAR file".to_string())
+}
+
+/// Build a TOML config from captured requests (`None` if none match).
+/// `header` is the comment block the config starts with.
+pub(crate) fn entries_to_config(
+    entries: &[HarEntry],
+    filter: Option<&Regex>,
+    header: &str,
+) -> Option<String> {
     // Filter and deduplicate entries
-    let entries: Vec<&HarEntry> = har
-        .log
-        .entries
+    let entries: Vec<&HarEntry> = entries
         .iter()
         .filter(|e| {
             // Skip non-HTTP requests (data URLs, etc.)
@@ -114,7 +127,7 @@ pub fn import_har(path: &Path, filter: Option<&Regex>) -> Result<String, String>
         .collect();
 
     if entries.is_empty() {
-        return Err("No matching HTTP requests found in HAR file".to_string());
+        return None;
     }
 
     // Group by URL pattern to detect if we need scenarios
@@ -127,8 +140,7 @@ pub fn import_har(path: &Path, filter: Option<&Regex>) -> Result<String, String>
 
     // Build TOML config
     let mut config = String::new();
-    config.push_str("# Generated from HAR file by kaioken import\n");
-    config.push_str(&format!("# Source: {}\n", path.display()));
+    config.push_str(header);
     config.push_str(&format!("# Entries: {} requests\n\n", entries.len()));
 
     if use_scenarios {
@@ -229,7 +241,7 @@ pub fn import_har(path: &Path, filter: Option<&Regex>) -> Result<String, String>
     config.push_str("# p99_latency_ms = \"< 500\"\n");
     config.push_str("# error_rate = \"< 0.01\"\n");
 
-    Ok(config)
+    Some(config)
 }
 
 /// Filter out browser-specific headers
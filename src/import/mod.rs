@@ -1,4 +1,4 @@
-mod har;
+pub(crate) mod har;
 
 pub use har::import_har;
 
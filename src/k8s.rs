@@ -0,0 +1,229 @@
+//! `kaioken k8s generate`: Kubernetes manifests that run a config in-cluster
+//!
+//! The config goes into a ConfigMap mounted into the pods, so the manifest
+//! is all `kubectl apply -f` needs. With `--parallelism`, an Indexed Job
+//! starts that many pods, each running the full test.
+
+use crate::cli::{K8sArgs, K8sCommand, RunArgs};
+use crate::config::{load_config, load_table, merge_config, parse_config, substitute_env_vars};
+use clap::Parser;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Where the ConfigMap is mounted in the pods
+const CONFIG_DIR: &str = "/etc/kaioken";
+const CONFIG_FILE: &str = "kaioken.toml";
+
+pub fn run_k8s(args: &K8sArgs) -> Result<i32, String> {
+    match args.command {
+        K8sCommand::Generate {
+            ref config,
+            ref image,
+            ref name,
+            ref namespace,
+            parallelism,
+            ref secrets,
+            ref output,
+            ref run_args,
+        } => {
+            let manifest = generate(&Manifest {
+                config,
+                image,
+                name,
+                namespace: namespace.as_deref(),
+                parallelism,
+                secrets,
+                run_args,
+            })?;
+            match output {
+                Some(path) => {
+                    fs::write(path, manifest)
+                        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+                    eprintln!("Manifest written to: {}", path.display());
+                    eprintln!("\nRun with: kubectl apply -f {}", path.display());
+                }
+                None => print!("{}", manifest),
+            }
+            Ok(0)
+        }
+    }
+}
+
+struct Manifest<'a> {
+    config: &'a Path,
+    image: &'a str,
+    name: &'a str,
+    namespace: Option<&'a str>,
+    parallelism: u32,
+    secrets: &'a [String],
+    run_args: &'a [String],
+}
+
+fn generate(manifest: &Manifest) -> Result<String, String> {
+    validate_name(manifest.name)?;
+    if let Some(namespace) = manifest.namespace {
+        validate_name(namespace)?;
+    }
+    for secret in manifest.secrets {
+        validate_name(secret)?;
+    }
+    if manifest
+        .run_args
+        .iter()
+        .any(|a| a == "-f" || a == "--config" || a.starts_with("--config="))
+    {
+        return Err("Pass the config with -f before --, not in the run flags".to_string());
+    }
+
+    let raw = fs::read_to_string(manifest.config)
+        .map_err(|e| format!("Failed to read config file: {}", e))?;
+    let (substituted, missing) = substitute_env_vars(&raw);
+
+    // Check the config and flags the way the pods will load them, unless
+    // they need variables only the pods have (from --secret)
+    if missing.is_empty() {
+        let mut argv = vec!["run".to_string(), "-f".to_string()];
+        argv.push(manifest.config.display().to_string());
+        argv.extend(manifest.run_args.iter().cloned());
+        let run_args =
+            RunArgs::try_parse_from(&argv).map_err(|e| format!("Invalid run flags: {}", e))?;
+        merge_config(&run_args, Some(load_config(manifest.config)?))?;
+    } else {
+        eprintln!(
+            "Not validated: {} set only in the pods",
+            missing
+                .iter()
+                .map(|name| format!("${{{}}}", name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    // Embed the file as written, so ${VAR}s are filled in from the pod's
+    // environment; included files have to be merged in here instead
+    let content = if parse_config(&substituted).is_ok_and(|c| c.has_includes()) {
+        toml::to_string(&load_table(manifest.config)?)
+            .map_err(|e| format!("Failed to serialize merged config: {}", e))?
+    } else {
+        raw
+    };
+
+    let mut args = vec![
+        "run".to_string(),
+        "-f".to_string(),
+        format!("{}/{}", CONFIG_DIR, CONFIG_FILE),
+        "--no-tui".to_string(),
+        "-y".to_string(),
+    ];
+    args.extend(manifest.run_args.iter().cloned());
+
+    let config_map = format!("{}-config", manifest.name);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# Generated by `kaioken k8s generate` from {}",
+        manifest.config.display()
+    );
+    out.push_str("apiVersion: v1\nkind: ConfigMap\n");
+    metadata(&mut out, &config_map, manifest);
+    let _ = writeln!(out, "data:\n  {}: {}", CONFIG_FILE, block_scalar(&content));
+    for line in content.lines() {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            let _ = writeln!(out, "    {}", line);
+        }
+    }
+
+    out.push_str("---\napiVersion: batch/v1\nkind: Job\n");
+    metadata(&mut out, manifest.name, manifest);
+    out.push_str("spec:\n  backoffLimit: 0\n");
+    if manifest.parallelism > 1 {
+        let _ = writeln!(
+            out,
+            "  completions: {0}\n  parallelism: {0}\n  completionMode: Indexed",
+            manifest.parallelism
+        );
+    }
+    out.push_str("  template:\n    metadata:\n      labels:\n");
+    labels(&mut out, manifest.name, "        ");
+    out.push_str("    spec:\n      restartPolicy: Never\n      containers:\n");
+    out.push_str("        - name: kaioken\n");
+    let _ = writeln!(out, "          image: {}", quote(manifest.image));
+    if !manifest.secrets.is_empty() {
+        out.push_str("          envFrom:\n");
+        for secret in manifest.secrets {
+            let _ = writeln!(
+                out,
+                "            - secretRef:\n                name: {}",
+                secret
+            );
+        }
+    }
+    let _ = writeln!(
+        out,
+        "          args: [{}]",
+        args.iter().map(|a| quote(a)).collect::<Vec<_>>().join(", ")
+    );
+    out.push_str("          volumeMounts:\n            - name: config\n");
+    let _ = writeln!(out, "              mountPath: {}", CONFIG_DIR);
+    out.push_str("              readOnly: true\n      volumes:\n        - name: config\n");
+    let _ = writeln!(
+        out,
+        "          configMap:\n            name: {}",
+        config_map
+    );
+    Ok(out)
+}
+
+fn metadata(out: &mut String, name: &str, manifest: &Manifest) {
+    let _ = writeln!(out, "metadata:\n  name: {}", name);
+    if let Some(namespace) = manifest.namespace {
+        let _ = writeln!(out, "  namespace: {}", namespace);
+    }
+    out.push_str("  labels:\n");
+    labels(out, manifest.name, "    ");
+}
+
+fn labels(out: &mut String, instance: &str, indent: &str) {
+    let _ = writeln!(out, "{}app.kubernetes.io/name: kaioken", indent);
+    let _ = writeln!(out, "{}app.kubernetes.io/instance: {}", indent, instance);
+}
+
+/// `|`, or `|2` when the first line is indented and YAML would otherwise
+/// take that as the block's indentation
+fn block_scalar(content: &str) -> &'static str {
+    let first = content.lines().find(|l| !l.trim().is_empty());
+    if first.is_some_and(|l| l.starts_with([' ', '\t'])) {
+        "|2"
+    } else {
+        "|"
+    }
+}
+
+/// A double-quoted YAML scalar (JSON strings are valid YAML)
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// Kubernetes object names: lowercase letters, digits and '-', starting and
+/// ending alphanumeric. 52 characters leaves room for the `-config` suffix
+/// and the pod suffixes a Job adds.
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 52
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !name.starts_with('-')
+        && !name.ends_with('-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid name '{}': use up to 52 lowercase letters, digits and '-'",
+            name
+        ))
+    }
+}
@@ -1,26 +1,42 @@
+mod attach;
+mod calibrate;
 mod cli;
 mod compare;
 mod config;
-mod engine;
-#[cfg(feature = "grpc")]
-mod grpc;
-mod http;
-#[cfg(feature = "http3")]
-mod http3;
+mod ctl;
+mod doctor;
+mod history;
 mod import;
+mod k8s;
 mod output;
+#[cfg(feature = "http3")]
+mod race;
+mod record;
+mod reload;
+mod runners;
+mod schedule;
+mod serve;
 mod tui;
-mod types;
-mod ws;
+mod validate;
+
+// The engine and protocol clients live in kaioken-core
+#[cfg(feature = "mqtt")]
+use kaioken_core::mqtt;
+#[cfg(feature = "redis")]
+use kaioken_core::redis;
+#[cfg(feature = "socketio")]
+use kaioken_core::socketio;
+use kaioken_core::{engine, http, net, raw, types};
 
 use clap::Parser;
 use cli::{Cli, Commands, RunArgs};
 use compare::{compare_results, print_comparison};
 use config::{load_config, merge_config};
-use engine::{Engine, evaluate_thresholds, print_threshold_results};
+use engine::{Engine, apply_check_stats, evaluate_thresholds, print_threshold_results};
 use output::{
-    print_csv, print_html, print_json, print_markdown, write_csv, write_html, write_json,
-    write_markdown,
+    Progress, finish_progress, load_template, print_csv, print_html, print_json, print_markdown,
+    print_template, report_progress, write_csv, write_error_samples, write_html, write_json,
+    write_markdown, write_template,
 };
 use std::io::{self, Write};
 use std::sync::atomic::Ordering;
@@ -53,6 +69,16 @@ async fn run() -> Result<i32, String> {
         Commands::Run(args) => run_load_test(&args).await,
         Commands::Compare(args) => run_compare(&args),
         Commands::Init(args) => run_init(&args),
+        Commands::Validate(args) => validate::run_validate(&args),
+        Commands::Calibrate(args) => calibrate::run_calibrate(&args).await,
+        Commands::Doctor(args) => doctor::run_doctor(&args).await,
+        Commands::Serve(args) => serve::run_serve(&args).await,
+        Commands::Record(args) => record::run_record(&args).await,
+        Commands::History(args) => history::run_history(&args),
+        Commands::Ctl(args) => ctl::run_ctl(&args).await,
+        Commands::Attach(args) => attach::run_attach(&args).await,
+        Commands::Report(args) => run_report(&args),
+        Commands::K8s(args) => k8s::run_k8s(&args),
         Commands::Import(args) => {
             import::run_import(&args)?;
             Ok(0)
@@ -61,6 +87,10 @@ async fn run() -> Result<i32, String> {
             cli::generate_completions(args.shell);
             Ok(0)
         }
+        Commands::Schema => {
+            print!("{}", output::schema::SCHEMA);
+            Ok(0)
+        }
         Commands::Man => {
             cli::generate_man_page().map_err(|e| format!("Failed to generate man page: {}", e))?;
             Ok(0)
@@ -94,6 +124,11 @@ timeout = "5s"
 connect_timeout = "2s"
 # http2 = false
 # insecure = false
+# tcp_nodelay = true
+# pool_max_idle_per_host = 50   # default: one per worker
+# pool_idle_timeout = "30s"
+# ip_family = "any"     # ipv4 or ipv6 to pin a dual-stack target to one family
+# happy_eyeballs = true # race the other family when the first is slow
 
 # Headers (uncomment and modify as needed)
 # [target.headers]
@@ -103,6 +138,7 @@ connect_timeout = "2s"
 # Request body (for POST/PUT/PATCH)
 # body = '{{"key": "value"}}'
 # body_file = "payload.json"
+# compress_body = "gzip"   # or deflate, sent with Content-Encoding
 
 [load]
 concurrency = 50
@@ -111,6 +147,29 @@ duration = "30s"
 # rate = 0              # requests/sec, 0 = unlimited
 # ramp_up = "0s"        # time to reach full concurrency
 # warmup = "0s"         # warmup period (not measured)
+# auto_warmup = false   # end warmup once throughput and latency settle (warmup caps it)
+# honor_retry_after = false  # back off after 429/503 as Retry-After says
+# retries = 0           # retry transport errors, 5xx and 429 up to N times
+# deadline = "2s"       # budget per request across retries and redirects
+# seed = 42             # repeat the same random URLs, hosts and chaos faults
+# threads = 0           # independent worker runtimes pinned to cores, 0 = one per core
+# backpressure = "block" # when results back up: block, drop (counted) or grow
+# percentiles = [50, 75, 90, 95, 99, 99.9]  # latency percentiles to report
+# slow_log = {{ threshold = "500ms", path = "slow.ndjson" }}  # log slow requests as JSON Lines
+# plugins = ["sign.wasm"]  # WASM request/response hooks (needs --features plugins)
+
+# Client-side chaos (optional) - faults the workers inject into some requests
+# [chaos]
+# latency = "10ms..200ms"  # extra delay before sending, fixed or a range
+# latency_rate = 0.1       # share of requests delayed (default: all)
+# abort_rate = 0.01        # requests given up on mid-flight
+# abort_after = "0s..1s"   # when to give up (default: up to the timeout)
+# reset_rate = 0.01        # requests replaced by a connection reset (RST)
+
+# Client network (optional) - slow responses down to a 3g, 4g, dsl or custom link
+# [network]
+# profile = "3g"
+# rtt = "300ms"            # overrides the profile's; also down/up = "1.6mbit"
 
 # Variable interpolation available in URL, headers, and body:
 #   ${{REQUEST_ID}}    - unique ID per request
@@ -174,7 +233,81 @@ fn run_compare(args: &cli::CompareArgs) -> Result<i32, String> {
     }
 }
 
+/// `--template` goes with `--format template`, and a broken template fails
+/// before the run rather than after it
+fn check_template(format: &str, template: Option<&std::path::Path>) -> Result<(), String> {
+    match template {
+        Some(template) if format.eq_ignore_ascii_case("template") => {
+            load_template(template).map(|_| ())
+        }
+        None if format.eq_ignore_ascii_case("template") => {
+            Err("--format template needs --template FILE".to_string())
+        }
+        Some(_) => Err("--template only applies with --format template".to_string()),
+        None => Ok(()),
+    }
+}
+
+fn run_report(args: &cli::ReportArgs) -> Result<i32, String> {
+    let content = std::fs::read_to_string(&args.input)
+        .map_err(|e| format!("Failed to read '{}': {}", args.input.display(), e))?;
+    let (result, _) = output::schema::read_result(&content)
+        .map_err(|e| format!("Failed to parse '{}': {}", args.input.display(), e))?;
+    check_template(&args.format, args.template.as_deref())?;
+
+    let snapshot = result.to_snapshot();
+    let config = result.to_config();
+    let format = args.format.to_lowercase();
+
+    match (format.as_str(), &args.output) {
+        ("csv", Some(path)) => write_csv(&snapshot, &config, path),
+        ("csv", None) => print_csv(&snapshot, &config),
+        ("md" | "markdown", Some(path)) => write_markdown(&snapshot, &config, path),
+        ("md" | "markdown", None) => print_markdown(&snapshot, &config),
+        ("html", Some(path)) => write_html(&snapshot, &config, path),
+        ("html", None) => print_html(&snapshot, &config),
+        ("template", Some(path)) => write_template(&result, args.template.as_deref(), path),
+        ("template", None) => print_template(&result, args.template.as_deref()),
+        // Re-emit as saved: re-creating it would restamp the run's metadata
+        ("json", Some(path)) => std::fs::File::create(path).and_then(|file| {
+            serde_json::to_writer_pretty(io::BufWriter::new(file), &result).map_err(io::Error::from)
+        }),
+        ("json", None) => serde_json::to_string_pretty(&result)
+            .map(|json| println!("{}", json))
+            .map_err(io::Error::from),
+        ("summary", None) => {
+            print_summary(&snapshot, args.serious);
+            print_threshold_results(result.threshold_results());
+            let check_stats = result.check_stats();
+            if !check_stats.is_empty() {
+                print_check_results(&check_stats);
+            }
+            Ok(())
+        }
+        ("summary", Some(_)) => {
+            return Err(
+                "The summary format prints to the terminal; drop -o or pick another format"
+                    .to_string(),
+            );
+        }
+        (other, _) => {
+            return Err(format!(
+                "Unknown format '{}' (expected summary, json, csv, md, html or template)",
+                other
+            ));
+        }
+    }
+    .map_err(|e| format!("Failed to write {}: {}", format, e))?;
+
+    if let Some(ref path) = args.output {
+        eprintln!("Report written to: {}", path);
+    }
+    Ok(0)
+}
+
 async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
+    check_template(&args.format, args.template.as_deref())?;
+
     // Load TOML config if specified
     let toml_config = if let Some(ref path) = args.config {
         Some(load_config(path)?)
@@ -182,12 +315,392 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
         None
     };
 
+    let has_tests = toml_config.as_ref().is_some_and(|t| !t.tests.is_empty());
+    if !args.runners.is_empty() && has_tests {
+        return Err("--runners can't be combined with [[tests]] suites".to_string());
+    }
+    // Only the config file itself is copied to the runners
+    if !args.runners.is_empty() && toml_config.as_ref().is_some_and(|t| t.has_includes()) {
+        return Err("--runners doesn't support configs with extends or include".to_string());
+    }
+    if let Some(every) = args.every {
+        if has_tests {
+            return Err("--every can't be combined with [[tests]] suites".to_string());
+        }
+        return run_schedule(args, every, toml_config).await;
+    }
+    if let Some(ref path) = args.config
+        && has_tests
+    {
+        return run_suite(args, path).await;
+    }
+    if args.test.is_some() {
+        return Err("--test needs a config file with [[tests]]".to_string());
+    }
+
     // Merge CLI args with config file
     let config = merge_config(args, toml_config)?;
 
+    let runners = runners::parse_runners(&args.runners)?;
+    if !runners.is_empty() && !args.dry_run {
+        if !is_localhost(&config.url) {
+            confirm_remote_target(args, &config.url)?;
+        }
+        return runners::run_on_runners(args, config, runners).await;
+    }
+
+    run_test(args, config, None)
+        .await
+        .map(|(exit_code, _)| exit_code)
+}
+
+/// Run the `[[tests]]` selected with `--test`, one after another, then
+/// print a combined report
+async fn run_suite(args: &RunArgs, path: &std::path::Path) -> Result<i32, String> {
+    let tests = config::load_tests(path)?;
+    let selected: Vec<config::NamedTest> = match args.test.as_deref() {
+        Some("all") => tests,
+        Some(name) => {
+            let names: Vec<String> = tests.iter().map(|t| t.name.clone()).collect();
+            let test = tests.into_iter().find(|t| t.name == name).ok_or_else(|| {
+                format!(
+                    "No test named '{}' in {} (available: {})",
+                    name,
+                    path.display(),
+                    names.join(", ")
+                )
+            })?;
+            vec![test]
+        }
+        None => {
+            let names: Vec<&str> = tests.iter().map(|t| t.name.as_str()).collect();
+            return Err(format!(
+                "{} defines tests ({}); pick one with --test NAME or run them all with --test all",
+                path.display(),
+                names.join(", ")
+            ));
+        }
+    };
+
+    // Merge every test up front so a broken one fails before any load is sent
+    let mut configs = Vec::with_capacity(selected.len());
+    for test in selected {
+        let config = merge_config(args, Some(test.config))
+            .map_err(|e| format!("Test '{}': {}", test.name, e))?;
+        configs.push((test.name, config));
+    }
+
+    if configs.len() == 1 {
+        let (_, config) = configs.remove(0);
+        return run_test(args, config, None)
+            .await
+            .map(|(exit_code, _)| exit_code);
+    }
+
+    if let Some((_, config)) = configs.iter().find(|(_, c)| !is_localhost(&c.url)) {
+        confirm_remote_target(args, &config.url)?;
+    }
+
+    // Ctrl+C stops the current test (as in a single run) and the rest of the suite
+    let interrupted = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let interrupted_flag = interrupted.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            interrupted_flag.store(true, Ordering::Relaxed);
+        }
+    });
+
+    let total = configs.len();
+    let mut results = Vec::with_capacity(total);
+    for (i, (name, config)) in configs.into_iter().enumerate() {
+        if interrupted.load(Ordering::Relaxed) {
+            eprintln!("Interrupted: skipping remaining tests");
+            break;
+        }
+        if !args.quiet {
+            eprintln!("\n▶ Test {}/{}: {}", i + 1, total, name);
+        }
+        let suite = BatchRun {
+            output: args
+                .output
+                .as_deref()
+                .map(|path| batch_output_path(path, &name)),
+            errors_out: args
+                .errors_out
+                .as_deref()
+                .map(|path| batch_output_path(path, &name)),
+        };
+        let (exit_code, output) = run_test(args, config, Some(&suite)).await?;
+        results.push((name, exit_code, output));
+    }
+
+    let exit_code = results
+        .iter()
+        .map(|(_, code, _)| *code)
+        .find(|code| *code != 0)
+        .unwrap_or(0);
+
+    if args.dry_run {
+        return Ok(exit_code);
+    }
+
+    // Same rule as a single run: headless JSON goes to stdout, otherwise a summary
+    if args.json || (args.no_tui && args.format.eq_ignore_ascii_case("json")) {
+        let tests: Vec<serde_json::Value> = results
+            .iter()
+            .map(|(name, code, output)| {
+                serde_json::json!({
+                    "name": name,
+                    "exit_code": code,
+                    "result": output,
+                })
+            })
+            .collect();
+        let combined = serde_json::json!({ "exit_code": exit_code, "tests": tests });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&combined)
+                .map_err(|e| format!("Failed to write JSON: {}", e))?
+        );
+    } else {
+        print_suite_summary(&results);
+    }
+
+    Ok(exit_code)
+}
+
+/// Run the test every `every` until `--times` runs or Ctrl+C, checking each
+/// result against the trend of the runs before it
+async fn run_schedule(
+    args: &RunArgs,
+    every: std::time::Duration,
+    toml_config: Option<config::TomlConfig>,
+) -> Result<i32, String> {
+    if args.trend_window == 0 {
+        return Err("--trend-window must be at least 1".to_string());
+    }
+    let config = merge_config(args, toml_config)?;
+    if args.dry_run {
+        return run_test(args, config, None)
+            .await
+            .map(|(exit_code, _)| exit_code);
+    }
+    if !is_localhost(&config.url) {
+        confirm_remote_target(args, &config.url)?;
+    }
+
+    // Pick up the trend where the last schedule against this URL left off
+    let mut window = match args.db_url {
+        Some(ref path) => history::recent_results(path, &config.url, args.trend_window)?,
+        None => Vec::new(),
+    };
+    if !args.quiet && !window.is_empty() {
+        eprintln!(
+            "Trend baseline: {} previous runs from history",
+            window.len()
+        );
+    }
+
+    let interrupted = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let interrupted_flag = interrupted.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            interrupted_flag.store(true, Ordering::Relaxed);
+        }
+    });
+
+    let ndjson = args.json || (args.no_tui && args.format.eq_ignore_ascii_case("json"));
+    let mut exit_code = 0;
+    let mut run: u32 = 0;
+    loop {
+        run += 1;
+        let started = tokio::time::Instant::now();
+        if !args.quiet {
+            let of = args.times.map(|t| format!("/{}", t)).unwrap_or_default();
+            eprintln!(
+                "\n▶ Scheduled run {}{} at {}",
+                run,
+                of,
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+            );
+        }
+
+        // Re-read the config each time so edits apply from the next run
+        let toml_config = args.config.as_deref().map(load_config).transpose()?;
+        let config = merge_config(args, toml_config)?;
+        let batch = BatchRun {
+            output: args
+                .output
+                .as_deref()
+                .map(|path| batch_output_path(path, &run.to_string())),
+            errors_out: args
+                .errors_out
+                .as_deref()
+                .map(|path| batch_output_path(path, &run.to_string())),
+        };
+        let (mut code, output) = run_test(args, config, Some(&batch)).await?;
+
+        let checks = output
+            .as_ref()
+            .map(|o| schedule::evaluate_trend(&window, o, args.trend_threshold))
+            .unwrap_or_default();
+        if code == 0 && checks.iter().any(|c| c.regressed) {
+            code = 3; // Same as a compare regression
+        }
+        if ndjson {
+            let line = serde_json::json!({
+                "run": run,
+                "exit_code": code,
+                "trend": checks,
+                "result": output,
+            });
+            println!(
+                "{}",
+                serde_json::to_string(&line).map_err(|e| format!("Failed to write JSON: {}", e))?
+            );
+        } else if let Some(ref output) = output {
+            schedule::print_run(run, output, &checks, code);
+        }
+        if let Some(output) = output {
+            window.push(output);
+            if window.len() > args.trend_window {
+                window.remove(0);
+            }
+        }
+        if exit_code == 0 {
+            exit_code = code;
+        }
+
+        if args.times.is_some_and(|times| run >= times) || interrupted.load(Ordering::Relaxed) {
+            break;
+        }
+        if !args.quiet {
+            eprintln!(
+                "Next run in {}",
+                humantime::format_duration(std::time::Duration::from_millis(
+                    (started + every)
+                        .saturating_duration_since(tokio::time::Instant::now())
+                        .as_millis() as u64
+                ))
+            );
+        }
+        tokio::select! {
+            _ = tokio::time::sleep_until(started + every) => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    Ok(exit_code)
+}
+
+/// A run that is one of several (a suite test or a scheduled run)
+struct BatchRun {
+    /// Per-run output file derived from `-o`
+    output: Option<String>,
+    /// Per-run failure samples file derived from `--errors-out`
+    errors_out: Option<String>,
+}
+
+/// `results.json` -> `results-smoke.json`
+fn batch_output_path(path: &str, test: &str) -> String {
+    let path = std::path::Path::new(path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file = match path.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, test, ext.to_string_lossy()),
+        None => format!("{}-{}", stem, test),
+    };
+    path.with_file_name(file).to_string_lossy().into_owned()
+}
+
+fn print_suite_summary(results: &[(String, i32, Option<output::json::JsonOutput>)]) {
+    let name_width = results
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+
+    println!("\nTest suite: {} tests", results.len());
+    println!(
+        "  {:<width$}  {:>10}  {:>10}  {:>10}  {:>7}  RESULT",
+        "TEST",
+        "REQUESTS",
+        "RPS",
+        "P99 (ms)",
+        "ERRORS",
+        width = name_width
+    );
+    for (name, exit_code, output) in results {
+        let result = match exit_code {
+            0 => "✓ pass".to_string(),
+            4 => "✗ thresholds failed (exit 4)".to_string(),
+            code => format!("✗ failed (exit {})", code),
+        };
+        match output {
+            Some(output) => println!(
+                "  {:<width$}  {:>10}  {:>10.1}  {:>10.2}  {:>6.2}%  {}",
+                name,
+                output.summary.total_requests,
+                output.summary.requests_per_sec,
+                output.latency_us.p99 as f64 / 1000.0,
+                output.summary.error_rate * 100.0,
+                result,
+                width = name_width
+            ),
+            None => println!(
+                "  {:<width$}  {:>10}  {:>10}  {:>10}  {:>7}  {}",
+                name,
+                "-",
+                "-",
+                "-",
+                "-",
+                result,
+                width = name_width
+            ),
+        }
+    }
+}
+
+fn confirm_remote_target(args: &RunArgs, url: &str) -> Result<(), String> {
+    if args.yes || args.quiet || args.no_tui || args.json {
+        return Ok(());
+    }
+    eprintln!(
+        "\n⚠️  WARNING: Target is remote ({})",
+        extract_host(url).unwrap_or(url)
+    );
+    eprintln!("    High concurrency may impact production systems.");
+    eprint!("    Press Enter to continue or Ctrl+C to abort... ");
+    io::stderr().flush().ok();
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| format!("Failed to read input: {}", e))?;
+    Ok(())
+}
+
+/// Run one merged config; also returns the JSON result when it ran to completion
+async fn run_test(
+    args: &RunArgs,
+    config: types::LoadConfig,
+    batch: Option<&BatchRun>,
+) -> Result<(i32, Option<output::json::JsonOutput>), String> {
+    if !args.quiet {
+        for warning in calibrate::capacity_warnings(&config)
+            .into_iter()
+            .chain(doctor::limit_warnings(&config))
+        {
+            eprintln!("⚠️  {}", warning);
+        }
+    }
+
     // Debug mode - send single request and exit
     if args.debug {
-        return run_debug_request(&config).await;
+        return run_debug_request(&config).await.map(|code| (code, None));
     }
 
     // Dry run - validate and exit
@@ -201,9 +714,41 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
             let total_weight: u32 = config.scenarios.iter().map(|s| s.weight).sum();
             for s in &config.scenarios {
                 let pct = (s.weight as f64 / total_weight as f64) * 100.0;
+                let method = if s.is_websocket() {
+                    "WS".to_string()
+                } else {
+                    s.method.to_string()
+                };
+                // Proxy URLs may carry credentials, so only say whether one is set
+                let proxy = match s.proxy.as_deref() {
+                    Some("") => " direct",
+                    Some(_) => " own proxy",
+                    None => "",
+                };
+                let think = s
+                    .think_time
+                    .map(|t| format!(" think={}", t))
+                    .unwrap_or_default();
+                eprintln!(
+                    "  - {} ({} {}) weight={} ({:.0}%){}{}",
+                    s.name, method, s.url, s.weight, pct, proxy, think
+                );
+            }
+        }
+        if !config.hosts.is_empty() {
+            eprintln!("Hosts:       {} defined", config.hosts.len());
+            let total_weight: u32 = config.hosts.iter().map(|h| h.weight).sum();
+            for h in &config.hosts {
+                let pct = (h.weight as f64 / total_weight as f64) * 100.0;
+                let target = match (&h.url, &h.host_header) {
+                    (Some(url), Some(host)) => format!("{}, Host: {}", url, host),
+                    (Some(url), None) => url.clone(),
+                    (None, Some(host)) => format!("Host: {}", host),
+                    (None, None) => config.url.clone(),
+                };
                 eprintln!(
-                    "  - {} ({} {}) weight={} ({:.0}%)",
-                    s.name, s.method, s.url, s.weight, pct
+                    "  - {} ({}) weight={} ({:.0}%)",
+                    h.name, target, h.weight, pct
                 );
             }
         }
@@ -213,44 +758,273 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
             if let Some(rate) = config.arrival_rate {
                 eprintln!("Target RPS:  {}", rate);
             }
-            eprintln!("Max VUs:     {}", config.max_vus.unwrap_or(100));
+            match config.vu_autoscale {
+                Some(policy) => eprintln!(
+                    "VUs:         autoscaled {} at a time up to {}, {:?} apart, shed after {:?} idle",
+                    policy.step, policy.cap, policy.cooldown, policy.idle
+                ),
+                None => eprintln!("Max VUs:     {}", config.max_vus.unwrap_or(100)),
+            }
         } else {
             eprintln!("Load Model:  Closed (VU-driven)");
             eprintln!("Concurrency: {}", config.concurrency);
+            match config.threads {
+                Some(0) => eprintln!("Runtimes:    one per core"),
+                Some(n) => eprintln!("Runtimes:    {}", n),
+                None => {}
+            }
         }
         eprintln!("Duration:    {:?}", config.duration);
+        if !args.runners.is_empty() {
+            eprintln!(
+                "Runners:     {} (each runs the full test)",
+                args.runners.join(", ")
+            );
+        }
+        if config.tls_backend != types::TlsBackend::default() {
+            eprintln!("TLS:         {}", config.tls_backend.as_str());
+        }
+        if config.backpressure != types::BackpressurePolicy::Block {
+            eprintln!("Backpressure: {}", config.backpressure.as_str());
+        }
         if config.max_requests > 0 {
             eprintln!("Max Reqs:    {}", config.max_requests);
         }
         if config.rate > 0 {
-            eprintln!("Rate Limit:  {} req/s", config.rate);
+            match config.rate_burst {
+                Some(burst) => eprintln!("Rate Limit:  {} req/s, bursts of {}", config.rate, burst),
+                None => eprintln!("Rate Limit:  {} req/s", config.rate),
+            }
         }
         if !config.ramp_up.is_zero() {
             eprintln!("Ramp Up:     {:?}", config.ramp_up);
         }
-        if !config.warmup.is_zero() {
+        if config.auto_warmup {
+            eprintln!("Warmup:      auto (up to {:?})", config.warmup);
+        } else if !config.warmup.is_zero() {
             eprintln!("Warmup:      {:?}", config.warmup);
         }
         if let Some(think_time) = config.think_time {
-            eprintln!("Think time:  {:?}", think_time);
+            eprintln!("Think time:  {}", think_time);
+        }
+        if config.honor_retry_after {
+            eprintln!("Throttling:  back off after 429/503 (Retry-After)");
+        }
+        if let Some(ref duplicates) = config.duplicates {
+            eprintln!(
+                "Duplicates:  {}% of requests sent twice, same {}",
+                duplicates.rate * 100.0,
+                duplicates.header
+            );
+        }
+        if config.conditional_requests {
+            eprintln!("Caching:     revalidate with If-None-Match/If-Modified-Since");
+        }
+        if let Some(window) = config.pause_on_errors {
+            eprintln!(
+                "Outages:     pause after {:?} of failures, resume on a passing health check",
+                window
+            );
+        }
+        if config.failover {
+            eprintln!(
+                "Failover:    availability every {:?}, downtime and recovery per incident",
+                config.timeline_interval
+            );
+        }
+        if let Some(ref stdin) = config.stdin {
+            let input = match stdin.input {
+                types::StdinInput::Urls => "URLs",
+                types::StdinInput::BodyLines => "Body lines",
+            };
+            eprintln!("Input:       {} streamed from stdin", input);
+        }
+        if let Some(ref csrf) = config.csrf {
+            eprintln!("CSRF:        token sent in {}", csrf.header);
+        }
+        if let Some(ref header) = config.request_id_header {
+            eprintln!("Request IDs: sent in {}, checked in responses", header);
+        }
+        if let Some(encoding) = config.compress_body {
+            eprintln!("Compression: bodies sent {}-encoded", encoding.as_str());
+        }
+        if let Some(kind) = config.probe {
+            eprintln!("Probe:       {} only, no HTTP requests", kind.as_str());
+        }
+        if !config.cookies.is_empty() {
+            eprintln!("Cookies:     {} seeded", config.cookies.len());
+        }
+        if !config.proxy_list.is_empty() {
+            eprintln!(
+                "Proxy list:  {} proxies, rotated per {}",
+                config.proxy_list.len(),
+                config.proxy_rotation.as_str()
+            );
+        }
+        if !config.client_cert_pool.is_empty() {
+            eprintln!(
+                "Cert pool:   {} client certificates, dealt out per VU",
+                config.client_cert_pool.len()
+            );
+        }
+        if let Some(ref name) = config.sni {
+            eprintln!("SNI:         {}, URL host sent as Host over HTTP/1.1", name);
+        }
+        if config.retries > 0 {
+            eprintln!("Retries:     {}", config.retries);
+        }
+        if let Some(deadline) = config.deadline {
+            eprintln!("Deadline:    {:?} per request", deadline);
+        }
+        if let Some(seed) = config.seed {
+            eprintln!("Seed:        {}", seed);
+        }
+        if let Some(ref prometheus) = config.prometheus {
+            let export = match prometheus.export {
+                types::PrometheusExport::Pushgateway { ref url } => format!("push to {}", url),
+                types::PrometheusExport::Endpoint { port } => format!("serve on :{}", port),
+            };
+            eprintln!(
+                "Prometheus:  {}, instance {}, {} latency buckets",
+                export,
+                prometheus.instance.as_deref().unwrap_or(&config.url),
+                prometheus.buckets.len()
+            );
+            for (name, value) in &prometheus.labels {
+                eprintln!("  - {}=\"{}\"", name, value);
+            }
+        }
+        if let Some(ref network) = config.network {
+            eprintln!(
+                "Network:     {} ({} down, {} up, +{:?} RTT)",
+                network.name,
+                format_bandwidth(network.down_bps),
+                format_bandwidth(network.up_bps),
+                network.rtt
+            );
         }
         if config.http2 {
             eprintln!("HTTP/2:      enabled");
         }
+        if !config.socket.nodelay {
+            eprintln!("TCP_NODELAY: disabled");
+        }
+        if config.socket.ip_family != net::IpFamily::Any {
+            eprintln!("IP family:   {}", config.socket.ip_family.as_str());
+        }
+        if !config.socket.happy_eyeballs {
+            eprintln!("Happy eyeballs: off");
+        }
+        if let Some(size) = config.socket.send_buffer {
+            eprintln!("SO_SNDBUF:   {} bytes", size);
+        }
+        if let Some(size) = config.socket.recv_buffer {
+            eprintln!("SO_RCVBUF:   {} bytes", size);
+        }
+        if let Some(idle) = config.socket.pool_max_idle_per_host {
+            eprintln!(
+                "Pool:        {} idle per host, {:?} idle timeout",
+                idle, config.socket.pool_idle_timeout
+            );
+        }
+        if let Some(limit) = config.max_connections {
+            eprintln!("Connections: at most {} at once, shared by all VUs", limit);
+        }
+        if !config.expect_content_types.is_empty() {
+            eprintln!(
+                "Content:     {}; others count as unexpected",
+                config.expect_content_types.join(", ")
+            );
+        }
+        #[cfg(feature = "http3")]
+        if let Some(race) = config.protocol_race {
+            eprintln!("Protocol:    HTTP/2 vs HTTP/3 race ({})", race.as_str());
+        }
         if !config.headers.is_empty() {
             eprintln!("Headers:     {} custom", config.headers.len());
         }
         if config.body.is_some() {
             eprintln!("Body:        present");
         }
+        if let Some(ref method) = config.jsonrpc_method {
+            eprintln!("JSON-RPC:    method={}", method);
+        }
+        if !config.ws_subprotocols.is_empty() {
+            eprintln!("Subprotocol: {}", config.ws_subprotocols.join(", "));
+        }
+        #[cfg(feature = "socketio")]
+        if let Some(ref event) = config.socketio_event {
+            eprintln!(
+                "Socket.IO:   event={} namespace={}",
+                event, config.socketio_namespace
+            );
+        }
+        if let Ok(target) = raw::parse_raw_url(&config.url) {
+            eprintln!(
+                "Raw:         {} {} (response: {})",
+                target.protocol.as_str(),
+                target.addr,
+                config
+                    .raw_expect
+                    .as_ref()
+                    .map(|e| e.describe())
+                    .unwrap_or_else(|| "not awaited".to_string())
+            );
+        }
+        #[cfg(feature = "redis")]
+        if config.url.starts_with("redis://") {
+            eprintln!(
+                "Redis:       {} command template(s)",
+                config.redis_commands.len()
+            );
+            for command in &config.redis_commands {
+                eprintln!("  - {}", command.join(" "));
+            }
+        }
+        #[cfg(feature = "mqtt")]
+        if config.url.starts_with("mqtt://") {
+            eprintln!(
+                "MQTT:        topic={} qos={} ({} publishers, {} subscribers)",
+                config.mqtt_topic,
+                config.mqtt_qos,
+                config.concurrency - config.mqtt_subscribers,
+                config.mqtt_subscribers
+            );
+            if let Some(rate) = config.mqtt_publish_rate {
+                eprintln!("Publish rate: {}/s per publisher", rate);
+            }
+        }
+        if config.ws_mode == types::WsMode::Broadcast {
+            eprintln!(
+                "WS Mode:     broadcast ({} publishers, {} subscribers)",
+                config.ws_publishers,
+                config.concurrency - config.ws_publishers
+            );
+        }
+        if let Some(every) = config.ws_reconnect_every {
+            eprintln!("Reconnect:   every {:?}", every);
+        }
+        if config.ws_compression {
+            eprintln!("Compression: permessage-deflate");
+        }
         if !config.thresholds.is_empty() {
             eprintln!("Thresholds:  {} defined", config.thresholds.len());
             for t in &config.thresholds {
+                eprintln!("  - {} {} {}", t.metric, t.operator.as_str(), t.value);
+            }
+        }
+        if !config.trend_thresholds.is_empty() {
+            eprintln!(
+                "Trends:      {} defined, against runs stored with --db-url",
+                config.trend_thresholds.len()
+            );
+            for t in &config.trend_thresholds {
                 eprintln!(
-                    "  - {} {} {}",
-                    t.metric.as_str(),
+                    "  - {}_trend {} {:+}% over {}",
+                    t.metric,
                     t.operator.as_str(),
-                    t.value
+                    t.change_pct,
+                    humantime::format_duration(t.window)
                 );
             }
         }
@@ -269,7 +1043,28 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
                 .max()
                 .unwrap_or(0);
             let max_rate = config.stages.iter().filter_map(|s| s.target_rate).max();
-            if let Some(rate) = max_rate {
+            let max_message_rate = config
+                .stages
+                .iter()
+                .filter_map(|s| s.target_message_rate)
+                .max();
+            if let Some(ref profile) = config.rate_profile {
+                eprintln!(
+                    "Rate profile: {} ({} points, {:?} replayed over {:?}, max rate: {} RPS)",
+                    profile.path,
+                    profile.points,
+                    profile.span,
+                    profile.window,
+                    max_rate.unwrap_or(0)
+                );
+            } else if let Some(rate) = max_message_rate {
+                eprintln!(
+                    "Stages:      {} defined (total: {:?}, max rate: {} msg/s per connection)",
+                    config.stages.len(),
+                    total,
+                    rate
+                );
+            } else if let Some(rate) = max_rate {
                 eprintln!(
                     "Stages:      {} defined (total: {:?}, max rate: {} RPS)",
                     config.stages.len(),
@@ -284,31 +1079,35 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
                     max_target
                 );
             }
-            for (i, s) in config.stages.iter().enumerate() {
+            // A profile's stages are its points; there can be thousands
+            let listed = if config.rate_profile.is_some() {
+                0
+            } else {
+                config.stages.len()
+            };
+            for (i, s) in config.stages.iter().take(listed).enumerate() {
                 if let Some(target) = s.target {
                     eprintln!("  {}. {:?} -> {} workers", i + 1, s.duration, target);
                 } else if let Some(rate) = s.target_rate {
                     eprintln!("  {}. {:?} -> {} RPS", i + 1, s.duration, rate);
+                } else if let Some(rate) = s.target_message_rate {
+                    eprintln!("  {}. {:?} -> {} msg/s", i + 1, s.duration, rate);
                 }
             }
         }
-        return Ok(0);
+        return Ok((0, None));
     }
 
-    // Safety warning for remote targets
-    if !args.yes && !is_localhost(&config.url) && !args.quiet && !args.no_tui && !args.json {
-        eprintln!(
-            "\n⚠️  WARNING: Target is remote ({})",
-            extract_host(&config.url).unwrap_or(&config.url)
-        );
-        eprintln!("    High concurrency may impact production systems.");
-        eprint!("    Press Enter to continue or Ctrl+C to abort... ");
-        io::stderr().flush().ok();
+    // Safety warning for remote targets (a batch asks once, up front)
+    if batch.is_none() && !is_localhost(&config.url) {
+        confirm_remote_target(args, &config.url)?;
+    }
 
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .map_err(|e| format!("Failed to read input: {}", e))?;
+    #[cfg(feature = "http3")]
+    if let Some(race) = config.protocol_race {
+        return race::run_protocol_race(args, config, race)
+            .await
+            .map(|code| (code, None));
     }
 
     let engine = Engine::new(config.clone());
@@ -318,27 +1117,84 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
     let phase_rx = engine.phase_rx();
     let fail_fast_flag = engine.threshold_failed_flag();
     let check_stats_ref = engine.check_stats_ref();
+    let slow_logged = engine.slow_logged();
+    let history_run = engine.history_run();
 
-    let use_tui = !args.no_tui && !args.json;
+    let use_tui = !args.no_tui && !args.json && !args.runner_stream;
     let output_json = args.json;
     let format = args.format.to_lowercase();
+    let output_path = match batch {
+        Some(batch) => batch.output.clone(),
+        None => args.output.clone(),
+    };
+    let errors_out_path = match batch {
+        Some(batch) => batch.errors_out.clone(),
+        None => args.errors_out.clone(),
+    };
+    // A batch prints its own JSON once each run finishes
+    let print_json_result = batch.is_none() && (output_json || format == "json");
+
+    // SIGHUP, or `r` in the TUI, re-plans the run from the edited config
+    let reload_requests = std::sync::Arc::new(tokio::sync::Notify::new());
+    let (reload_tx, reload_rx) = tokio::sync::watch::channel(String::new());
+    let reload_handle = args.config.clone().map(|path| {
+        tokio::spawn(reload::watch_reloads(
+            args.clone(),
+            path,
+            engine.control(),
+            reload_requests.clone(),
+            reload_tx,
+            args.quiet || use_tui,
+        ))
+    });
 
     let tui_handle = if use_tui {
-        let app = App::new(
+        let mut app = App::new(
             config.clone(),
             snapshot_rx.clone(),
             state_rx.clone(),
             phase_rx,
             cancel_token.clone(),
             args.serious,
-            args.output.clone(),
+            output_path.clone(),
         );
+        if reload_handle.is_some() {
+            app = app.with_reload(reload_requests, reload_rx);
+        }
 
         Some(tokio::spawn(async move { app.run().await }))
     } else {
         None
     };
 
+    // Headless runs can still show progress, for CI logs
+    let progress = match args.progress {
+        Some(style) => Some(Progress::new(style, &config)),
+        None => args.summary_interval.map(|_| Progress::Lines),
+    };
+    let progress_handle = match progress {
+        Some(progress) if !use_tui => {
+            let every = args
+                .summary_interval
+                .unwrap_or_else(|| progress.default_interval());
+            let handle = tokio::spawn(report_progress(
+                every,
+                progress,
+                snapshot_rx.clone(),
+                engine.phase_rx(),
+            ));
+            Some((handle, progress))
+        }
+        _ => None,
+    };
+    let stream_handle = args.runner_stream.then(|| {
+        tokio::spawn(runners::stream_snapshots(
+            snapshot_rx.clone(),
+            config.clone(),
+            cancel_token.clone(),
+        ))
+    });
+
     let ctrl_c_token = cancel_token.clone();
     tokio::spawn(async move {
         if tokio::signal::ctrl_c().await.is_ok() {
@@ -346,7 +1202,44 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
         }
     });
 
-    let stats = engine.run().await?;
+    let control_server = match args.control_port {
+        Some(port) => {
+            let target = ctl::ControlTarget {
+                control: engine.control(),
+                cancel: cancel_token.clone(),
+                snapshot_rx: snapshot_rx.clone(),
+                state_rx: state_rx.clone(),
+            };
+            let server = ctl::start_server(port, target).await?;
+            if !args.quiet && !use_tui {
+                eprintln!(
+                    "Control:     kaioken ctl -p {} status|stop|extend|set-rate",
+                    port
+                );
+            }
+            Some(server)
+        }
+        None => None,
+    };
+
+    if let Some(start_at) = args.start_at {
+        runners::wait_until(start_at).await;
+    }
+    let stats = engine.run().await;
+    if let Some(server) = control_server {
+        server.abort();
+    }
+    if let Some(handle) = reload_handle {
+        handle.abort();
+    }
+    if let Some((handle, progress)) = progress_handle {
+        handle.abort();
+        finish_progress(progress);
+    }
+    if let Some(handle) = stream_handle {
+        handle.abort();
+    }
+    let stats = stats?;
 
     if let Some(handle) = tui_handle {
         let _ = handle.await;
@@ -356,18 +1249,11 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
 
     // Merge check stats into snapshot for threshold evaluation
     let check_stats = check_stats_ref.lock().unwrap().clone();
-    if !check_stats.is_empty() {
-        let (total_passed, total_checks): (u64, u64) = check_stats
-            .values()
-            .fold((0, 0), |(p, t), (passed, total)| (p + passed, t + total));
-        if total_checks > 0 {
-            final_snapshot.overall_check_pass_rate =
-                Some(total_passed as f64 / total_checks as f64);
-        }
-    }
+    apply_check_stats(&mut final_snapshot, &check_stats);
 
-    // Evaluate thresholds
-    let threshold_results = evaluate_thresholds(&config.thresholds, &final_snapshot);
+    // Evaluate thresholds, then trend thresholds against the stored runs
+    let mut threshold_results = evaluate_thresholds(&config.thresholds, &final_snapshot);
+    threshold_results.extend(schedule::check_trend_thresholds(&config, &final_snapshot));
     let thresholds_passed = threshold_results.iter().all(|r| r.passed);
     let threshold_results_opt = if threshold_results.is_empty() {
         None
@@ -383,14 +1269,24 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
     };
 
     // Print output to stdout if in headless mode
-    if output_json {
-        print_json(
+    if args.runner_stream {
+        let output = output::json::create_output(
             &final_snapshot,
             &config,
             threshold_results_opt,
             check_stats_opt,
-        )
-        .map_err(|e| format!("Failed to write JSON: {}", e))?;
+        );
+        runners::write_line(&output).map_err(|e| format!("Failed to write JSON: {}", e))?;
+    } else if output_json || (!use_tui && format == "json") {
+        if print_json_result {
+            print_json(
+                &final_snapshot,
+                &config,
+                threshold_results_opt,
+                check_stats_opt,
+            )
+            .map_err(|e| format!("Failed to write JSON: {}", e))?;
+        }
     } else if !use_tui {
         match format.as_str() {
             "csv" => print_csv(&final_snapshot, &config)
@@ -399,23 +1295,36 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
                 .map_err(|e| format!("Failed to write Markdown: {}", e))?,
             "html" => print_html(&final_snapshot, &config)
                 .map_err(|e| format!("Failed to write HTML: {}", e))?,
-            "json" => print_json(
-                &final_snapshot,
-                &config,
-                threshold_results_opt,
-                check_stats_opt,
+            "template" => print_template(
+                &output::json::create_output(
+                    &final_snapshot,
+                    &config,
+                    threshold_results_opt,
+                    check_stats_opt,
+                ),
+                args.template.as_deref(),
             )
-            .map_err(|e| format!("Failed to write JSON: {}", e))?,
+            .map_err(|e| format!("Failed to write template: {}", e))?,
             _ => print_summary(&final_snapshot, args.serious),
         }
     }
 
     // Write to file if specified
-    if let Some(path) = &args.output {
+    if let Some(path) = &output_path {
         let write_result = match format.as_str() {
             "csv" => write_csv(&final_snapshot, &config, path),
             "md" | "markdown" => write_markdown(&final_snapshot, &config, path),
             "html" => write_html(&final_snapshot, &config, path),
+            "template" => write_template(
+                &output::json::create_output(
+                    &final_snapshot,
+                    &config,
+                    threshold_results_opt,
+                    check_stats_opt,
+                ),
+                args.template.as_deref(),
+                path,
+            ),
             _ => write_json(
                 &final_snapshot,
                 &config,
@@ -431,11 +1340,35 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
         }
     }
 
-    // Print threshold results to console (for non-JSON formats)
-    if !threshold_results.is_empty() && !use_tui && !output_json && format != "json" {
-        print_threshold_results(&threshold_results);
-    }
-
+    if let Some(path) = &errors_out_path {
+        write_error_samples(&final_snapshot.failure_samples, path)
+            .map_err(|e| format!("Failed to write error samples: {}", e))?;
+        if !args.quiet && !use_tui {
+            eprintln!(
+                "Error samples written to: {} ({} samples)",
+                path,
+                final_snapshot.failure_samples.len()
+            );
+        }
+    }
+
+    if let Some(slow_log) = &config.slow_log
+        && !args.quiet
+        && !use_tui
+    {
+        eprintln!(
+            "Slow requests written to: {} ({} over {})",
+            slow_log.path,
+            slow_logged.load(Ordering::Relaxed),
+            humantime::format_duration(slow_log.threshold)
+        );
+    }
+
+    // Print threshold results to console (for non-JSON formats)
+    if !threshold_results.is_empty() && !use_tui && !output_json && format != "json" {
+        print_threshold_results(&threshold_results);
+    }
+
     // Print check results (check_stats already obtained above)
     if !check_stats.is_empty() && !use_tui && !output_json && format != "json" {
         print_check_results(&check_stats);
@@ -443,19 +1376,43 @@ async fn run_load_test(args: &RunArgs) -> Result<i32, String> {
 
     // Determine exit code
     let fail_fast_triggered = fail_fast_flag.load(Ordering::Relaxed);
-    if !thresholds_passed || fail_fast_triggered {
-        Ok(4) // Thresholds failed
-    } else if stats.failed > 0 && stats.error_rate() > 0.5 {
-        Ok(1) // High error rate
+    let exit_code = if !thresholds_passed || fail_fast_triggered {
+        4 // Thresholds failed
+    } else if stats.failed > 0 && stats.error_rate() > 0.5 && !args.runner_stream {
+        // A `--runners` coordinator judges the merged error rate itself and
+        // takes any other exit status for a failed runner
+        1 // High error rate
     } else {
-        Ok(0) // Success
+        0 // Success
+    };
+
+    let output = (history_run.is_some() || batch.is_some()).then(|| {
+        output::json::create_output(
+            &final_snapshot,
+            &config,
+            threshold_results_opt,
+            check_stats_opt,
+        )
+    });
+
+    // Store the result for `kaioken history`
+    if let (Some(run), Some(output)) = (&history_run, &output)
+        && let Err(e) = history::finish_run(run, output, exit_code)
+    {
+        tracing::warn!("{}", e);
     }
+
+    Ok((exit_code, output.filter(|_| batch.is_some())))
 }
 
 async fn run_debug_request(config: &types::LoadConfig) -> Result<i32, String> {
     use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
     use std::time::Instant;
 
+    let mut config = config.clone();
+    http::route_sni(&mut config).await?;
+    let config = &config;
+
     let separator = "=".repeat(80);
 
     println!("{}", separator);
@@ -522,21 +1479,33 @@ async fn run_debug_request(config: &types::LoadConfig) -> Result<i32, String> {
         }
     }
 
-    // Build client
+    // Build client; with a certificate pool, the first VU's certificate
+    let identity = match config.client_cert_pool.first() {
+        Some(identity) => Some((identity.cert.as_path(), identity.key.as_path())),
+        None => config
+            .client_cert
+            .as_deref()
+            .zip(config.client_key.as_deref()),
+    };
     let client = http::create_client(
         1,
         config.timeout,
         config.connect_timeout,
         config.insecure,
+        config.tls_backend,
         config.http2,
+        config.overrides_host(),
         config.cookie_jar,
+        &config.cookies,
         config.follow_redirects,
         config.disable_keepalive,
         config.proxy.as_deref(),
-        config.client_cert.as_deref(),
-        config.client_key.as_deref(),
+        identity.map(|(cert, _)| cert),
+        identity.map(|(_, key)| key),
         config.ca_cert.as_deref(),
         config.connect_to.as_ref().map(|(h, a)| (h.as_str(), *a)),
+        &config.socket,
+        None,
     )
     .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
@@ -668,6 +1637,15 @@ fn print_body_text(body: &str) {
     }
 }
 
+/// `1.6 Mbit/s` for 1_600_000 bits per second
+fn format_bandwidth(bps: u64) -> String {
+    if bps >= 1_000_000 {
+        format!("{} Mbit/s", bps as f64 / 1e6)
+    } else {
+        format!("{} kbit/s", bps as f64 / 1e3)
+    }
+}
+
 fn is_localhost(url: &str) -> bool {
     let url_lower = url.to_lowercase();
     url_lower.contains("localhost")
@@ -684,6 +1662,15 @@ fn extract_host(url: &str) -> Option<&str> {
     without_scheme.split('/').next()
 }
 
+/// Endpoint rows shown in the text summary; JSON output has all of them
+const SUMMARY_ENDPOINTS: usize = 10;
+
+/// Characters of an error sample's message or body shown in the text summary
+const SUMMARY_SAMPLE_CHARS: usize = 80;
+
+/// Drift of a scenario from its weight, in percent, the summary warns about
+const MIX_DRIFT_WARN_PCT: f64 = 10.0;
+
 fn print_summary(snapshot: &types::StatsSnapshot, serious: bool) {
     let title = if serious {
         "Load Test Results"
@@ -695,12 +1682,41 @@ fn print_summary(snapshot: &types::StatsSnapshot, serious: bool) {
     println!("{:^50}", title);
     println!("{}", "=".repeat(50));
 
+    if snapshot.is_websocket {
+        print_ws_summary(snapshot);
+        println!("\n{}", "=".repeat(50));
+        return;
+    }
+
+    if let Some(warmup) = snapshot.auto_warmup {
+        println!(
+            "\nWarmup: {:.1}s ({})",
+            warmup.length.as_secs_f64(),
+            if warmup.settled {
+                "settled"
+            } else {
+                "did not settle, hit the cap"
+            }
+        );
+    }
+
     println!("\nThroughput:");
     println!("  Total Requests:  {:>12}", snapshot.total_requests);
     println!("  Successful:      {:>12}", snapshot.successful);
     println!("  Failed:          {:>12}", snapshot.failed);
     println!("  Requests/sec:    {:>12.2}", snapshot.requests_per_sec);
     println!("  Error Rate:      {:>11.2}%", snapshot.error_rate * 100.0);
+    if let Some(apdex) = snapshot.apdex {
+        println!(
+            "  Apdex (T={}ms): {:>8.2} ({})",
+            apdex.t_us / 1000,
+            apdex.score(),
+            apdex.rating()
+        );
+    }
+    if snapshot.results_dropped > 0 {
+        println!("  Results Dropped: {:>12}", snapshot.results_dropped);
+    }
 
     println!("\nLatency (ms):");
     println!(
@@ -715,26 +1731,116 @@ fn print_summary(snapshot: &types::StatsSnapshot, serious: bool) {
         "  Mean:            {:>12.2}",
         snapshot.latency_mean_us / 1000.0
     );
-    println!(
-        "  p50:             {:>12.2}",
-        snapshot.latency_p50_us as f64 / 1000.0
-    );
-    println!(
-        "  p90:             {:>12.2}",
-        snapshot.latency_p90_us as f64 / 1000.0
-    );
-    println!(
-        "  p95:             {:>12.2}",
-        snapshot.latency_p95_us as f64 / 1000.0
-    );
-    println!(
-        "  p99:             {:>12.2}",
-        snapshot.latency_p99_us as f64 / 1000.0
-    );
-    println!(
-        "  p99.9:           {:>12.2}",
-        snapshot.latency_p999_us as f64 / 1000.0
-    );
+    for &(p, latency_us) in &snapshot.latency_percentiles {
+        println!(
+            "  {:<17}{:>12.2}",
+            format!("p{}:", p),
+            latency_us as f64 / 1000.0
+        );
+    }
+
+    // Shorter runs are all inside the window, so this would repeat the above
+    if let Some(ref recent) = snapshot.recent
+        && snapshot.elapsed > types::RECENT_WINDOW
+    {
+        println!("\nLast {:.1}s:", recent.window.as_secs_f64());
+        println!("  Requests/sec:    {:>12.2}", recent.requests_per_sec);
+        for &(p, latency_us) in &recent.latency_percentiles {
+            println!(
+                "  {:<17}{:>12.2}",
+                format!("p{} (ms):", p),
+                latency_us as f64 / 1000.0
+            );
+        }
+    }
+
+    println!("\nNetwork:");
+    println!("  Bytes Received:  {:>12}", snapshot.bytes_received);
+    println!("  Ingress (Mbps):  {:>12.2}", snapshot.rx_mbps());
+    if snapshot.bytes_sent > 0 {
+        println!("  Bytes Sent:      {:>12}", snapshot.bytes_sent);
+        println!("  Egress (Mbps):   {:>12.2}", snapshot.tx_mbps());
+    }
+
+    if let Some(conns) = snapshot.connections {
+        println!("\nConnections:");
+        println!("  Opened:          {:>12}", conns.opened);
+        println!("  Opened/sec:      {:>12.2}", conns.opened_per_sec);
+        println!("  Reused:          {:>11.2}%", conns.reuse_rate() * 100.0);
+    }
+
+    if let Some(cap) = snapshot.connection_cap {
+        println!("\nConnection Cap:");
+        println!("  Limit:           {:>12}", cap.limit);
+        println!("  Waited:          {:>12}", cap.waited);
+        println!(
+            "  Mean wait (ms):  {:>12.2}",
+            cap.wait_mean_us as f64 / 1000.0
+        );
+        println!(
+            "  Max wait (ms):   {:>12.2}",
+            cap.wait_max_us as f64 / 1000.0
+        );
+    }
+
+    if let Some(ref upload) = snapshot.upload {
+        println!("\nUpload:");
+        println!("  Bytes Sent:      {:>12}", upload.bytes_sent);
+        println!(
+            "  Send Rate (MB/s):{:>12.2}",
+            upload.bytes_sent as f64 / snapshot.elapsed.as_secs_f64().max(0.001) / 1_000_000.0
+        );
+        if upload.timed_requests > 0 {
+            println!("  Timed Uploads:   {:>12}", upload.timed_requests);
+            println!(
+                "  Time p50 (ms):   {:>12.2}",
+                upload.time_p50_us as f64 / 1000.0
+            );
+            println!(
+                "  Time p99 (ms):   {:>12.2}",
+                upload.time_p99_us as f64 / 1000.0
+            );
+            println!(
+                "  Per-Request MB/s:{:>12.2}",
+                upload.throughput_bytes_per_sec / 1_000_000.0
+            );
+        }
+    }
+
+    if let Some(ref quic) = snapshot.quic {
+        println!("\nQUIC:");
+        println!("  Connections:     {:>12}", quic.connections);
+        println!("  RTT mean (ms):   {:>12.2}", quic.rtt_mean_us / 1000.0);
+        println!(
+            "  RTT p99 (ms):    {:>12.2}",
+            quic.rtt_p99_us as f64 / 1000.0
+        );
+        println!("  Cwnd (bytes):    {:>12.0}", quic.cwnd_mean_bytes);
+        println!("  Packets Sent:    {:>12}", quic.sent_packets);
+        println!(
+            "  Packets Lost:    {:>12}  ({:.2}%, retransmitted)",
+            quic.lost_packets,
+            quic.loss_rate() * 100.0
+        );
+        println!("  Congestion Evts: {:>12}", quic.congestion_events);
+
+        if let Some(ref zero_rtt) = quic.zero_rtt {
+            println!("\n0-RTT Resumption:");
+            println!(
+                "  0-RTT:           {:>12}  (p50 {:.2}ms, p99 {:.2}ms)",
+                zero_rtt.zero_rtt_requests,
+                zero_rtt.zero_rtt_p50_us as f64 / 1000.0,
+                zero_rtt.zero_rtt_p99_us as f64 / 1000.0
+            );
+            println!(
+                "  1-RTT:           {:>12}  (p50 {:.2}ms, p99 {:.2}ms)",
+                zero_rtt.one_rtt_requests,
+                zero_rtt.one_rtt_p50_us as f64 / 1000.0,
+                zero_rtt.one_rtt_p99_us as f64 / 1000.0
+            );
+            println!("  Rejected:        {:>12}", zero_rtt.rejected);
+        }
+    }
 
     if !snapshot.status_codes.is_empty() {
         println!("\nStatus Codes:");
@@ -745,6 +1851,29 @@ fn print_summary(snapshot: &types::StatsSnapshot, serious: bool) {
         }
     }
 
+    if let Some(ref content_types) = snapshot.content_types {
+        println!("\nContent Types:");
+        for t in &content_types.types {
+            if t.unexpected > 0 {
+                println!(
+                    "  {:<17}{:>12}  ({} unexpected)",
+                    format!("{}:", t.content_type),
+                    t.responses,
+                    t.unexpected
+                );
+            } else {
+                println!(
+                    "  {:<17}{:>12}",
+                    format!("{}:", t.content_type),
+                    t.responses
+                );
+            }
+        }
+        if !content_types.expected.is_empty() {
+            println!("  Unexpected:      {:>12}", content_types.unexpected());
+        }
+    }
+
     if !snapshot.errors.is_empty() {
         println!("\nErrors:");
         for (kind, count) in &snapshot.errors {
@@ -762,9 +1891,436 @@ fn print_summary(snapshot: &types::StatsSnapshot, serious: bool) {
         }
     }
 
+    if !snapshot.jsonrpc_errors.is_empty() {
+        println!("\nJSON-RPC Errors:");
+        let mut codes: Vec<_> = snapshot.jsonrpc_errors.iter().collect();
+        codes.sort_by_key(|(code, _)| *code);
+        for (code, count) in codes {
+            println!("  {:15} {:>12}", format!("{}:", code), count);
+        }
+    }
+
+    if !snapshot.stages.is_empty() {
+        println!("\nStages:");
+        for stage in &snapshot.stages {
+            println!(
+                "  {:12} {:>10}  {:>8.1}/s  {:>6.2}% err  p50 {:>8.2}ms  p99 {:>8.2}ms",
+                format!("{} (→{})", stage.index + 1, stage.target),
+                stage.requests,
+                stage.rps(),
+                stage.error_rate() * 100.0,
+                stage.latency_p50_us as f64 / 1000.0,
+                stage.latency_p99_us as f64 / 1000.0
+            );
+        }
+    }
+
+    if !snapshot.hosts.is_empty() {
+        println!("\nHosts:");
+        for host in &snapshot.hosts {
+            println!(
+                "  {:32} {:>10}  {:>6.2}% err  p50 {:>8.2}ms  p99 {:>8.2}ms",
+                host.name,
+                host.requests,
+                host.failed as f64 * 100.0 / host.requests.max(1) as f64,
+                host.latency_p50_us as f64 / 1000.0,
+                host.latency_p99_us as f64 / 1000.0
+            );
+        }
+    }
+
+    if !snapshot.endpoints.is_empty() {
+        println!("\nEndpoints:");
+        for endpoint in snapshot.endpoints.iter().take(SUMMARY_ENDPOINTS) {
+            println!(
+                "  {:32} {:>10}  {:>6.2}% err  p50 {:>8.2}ms  p99 {:>8.2}ms",
+                endpoint.name,
+                endpoint.requests,
+                endpoint.failed as f64 * 100.0 / endpoint.requests.max(1) as f64,
+                endpoint.latency_p50_us as f64 / 1000.0,
+                endpoint.latency_p99_us as f64 / 1000.0
+            );
+        }
+        if snapshot.endpoints.len() > SUMMARY_ENDPOINTS {
+            println!(
+                "  ... {} more (see JSON output)",
+                snapshot.endpoints.len() - SUMMARY_ENDPOINTS
+            );
+        }
+    }
+
+    if !snapshot.plugin_metrics.is_empty() {
+        println!("\nPlugin Metrics:");
+        for (name, metric) in &snapshot.plugin_metrics {
+            println!(
+                "  {:32} {:>10}  mean {:>10.2}  min {:>10.2}  max {:>10.2}",
+                name,
+                metric.count,
+                metric.mean(),
+                metric.min,
+                metric.max
+            );
+        }
+    }
+
+    if let Some(chaos) = snapshot.chaos {
+        println!("\nChaos:");
+        println!("  Delayed:         {:>12}", chaos.delayed);
+        println!("  Aborted:         {:>12}  (not measured)", chaos.aborted);
+        println!("  Reset:           {:>12}  (not measured)", chaos.reset);
+    }
+
+    if let Some(throttle) = snapshot.throttle {
+        println!("\nThrottled:");
+        println!("  429/503:         {:>12}", throttle.throttled);
+        println!("  Retry-After:     {:>12}", throttle.retry_after);
+        println!(
+            "  VUs waited:      {:>12}",
+            format!("{:.1}s", throttle.waited.as_secs_f64())
+        );
+    }
+
+    if let Some(duplicates) = snapshot.duplicates {
+        println!("\nDuplicates:");
+        println!("  Pairs:           {:>12}", duplicates.pairs);
+        println!("  Consistent:      {:>12}", duplicates.consistent);
+        println!("  Status differed: {:>12}", duplicates.status_mismatch);
+        println!("  Body differed:   {:>12}", duplicates.body_mismatch);
+        if duplicates.incomplete > 0 {
+            println!(
+                "  Incomplete:      {:>12}  (a copy failed without a response)",
+                duplicates.incomplete
+            );
+        }
+    }
+
+    if let Some(revalidation) = snapshot.revalidation {
+        println!("\nRevalidation:");
+        println!("  Conditional:     {:>12}", revalidation.conditional);
+        println!(
+            "  304 Not Modified:{:>12}  ({:.1}%)",
+            revalidation.not_modified,
+            revalidation.not_modified_ratio() * 100.0
+        );
+        println!("  Full fetches:    {:>12}", revalidation.full_fetches);
+        println!(
+            "  304 mean (ms):   {:>12.2}",
+            revalidation.not_modified_mean_us as f64 / 1000.0
+        );
+        println!(
+            "  Full mean (ms):  {:>12.2}",
+            revalidation.full_fetch_mean_us as f64 / 1000.0
+        );
+    }
+
+    if !snapshot.outages.is_empty() {
+        println!("\nOutages:");
+        for outage in &snapshot.outages {
+            println!(
+                "  at {:>8.1}s  {:>8.1}s down  {:>4} health checks{}",
+                outage.start.as_secs_f64(),
+                outage.duration.as_secs_f64(),
+                outage.health_checks,
+                if outage.recovered {
+                    ""
+                } else {
+                    "  (not recovered)"
+                }
+            );
+        }
+    }
+
+    if let Some(ref failover) = snapshot.failover {
+        println!("\nFailover:");
+        println!(
+            "  Uptime:          {:>11.1}%",
+            failover.uptime_ratio() * 100.0
+        );
+        println!("  Incidents:       {:>12}", failover.incidents.len());
+        println!(
+            "  Downtime:        {:>11.1}s",
+            failover.downtime().as_secs_f64()
+        );
+        println!(
+            "  Longest burst:   {:>11.1}s",
+            failover.longest_burst().as_secs_f64()
+        );
+        for incident in &failover.incidents {
+            println!(
+                "  at {:>8.1}s  {:>8.1}s of errors  {:>8.1}s down  {}",
+                incident.start.as_secs_f64(),
+                incident.error_burst.as_secs_f64(),
+                incident.downtime.as_secs_f64(),
+                match incident.time_to_recovery {
+                    Some(ttr) => format!("recovered in {:.1}s", ttr.as_secs_f64()),
+                    None => "not recovered".to_string(),
+                }
+            );
+        }
+    }
+
+    if !snapshot.extractions.is_empty() {
+        println!("\nExtractions:");
+        for extraction in &snapshot.extractions {
+            println!(
+                "  {:<17}{:>12}  ({} of {} found nothing)",
+                format!("{}.{}:", extraction.scenario, extraction.name),
+                format!(
+                    "{:.1}%",
+                    extraction.failed as f64 / extraction.attempts as f64 * 100.0
+                ),
+                extraction.failed,
+                extraction.attempts
+            );
+        }
+    }
+
+    if !snapshot.scenario_mix.is_empty() {
+        println!("\nScenario Mix:");
+        for share in &snapshot.scenario_mix {
+            println!(
+                "  {:<17}{:>12}  ({} requests, weight {:.1}%, {:+.1}%)",
+                format!("{}:", share.scenario),
+                format!("{:.1}%", share.actual * 100.0),
+                share.requests,
+                share.expected * 100.0,
+                share.drift_pct()
+            );
+        }
+        let drift = snapshot.scenario_mix_drift_pct();
+        if drift > MIX_DRIFT_WARN_PCT {
+            let requests: u64 = snapshot.scenario_mix.iter().map(|s| s.requests).sum();
+            println!(
+                "  \x1b[33m⚠\x1b[0m Up to {:.1}% off the weights over {} requests; a longer run gets closer to the configured mix",
+                drift, requests
+            );
+        }
+    }
+
+    if let Some(ids) = snapshot.request_ids {
+        println!("\nRequest IDs:");
+        println!("  Checked:         {:>12}", ids.responses);
+        println!("  Echoed:          {:>12}", ids.matched);
+        println!("  Missing:         {:>12}", ids.missing);
+        let note = if ids.mismatched > 0 {
+            "  (responses carried another request's ID)"
+        } else {
+            ""
+        };
+        println!("  Mismatched:      {:>12}{}", ids.mismatched, note);
+    }
+
+    if let Some(bursts) = snapshot.bursts {
+        println!("\nBursts:");
+        println!("  Sent:            {:>12}", bursts.bursts);
+        println!(
+            "  Size:            {:>12}",
+            format!("{}-{}", bursts.min_size, bursts.max_size)
+        );
+        println!(
+            "  Completion:      {:>12}  (max {:.1}ms)",
+            format!("{:.1}ms", bursts.completion_mean.as_secs_f64() * 1000.0),
+            bursts.completion_max.as_secs_f64() * 1000.0
+        );
+        println!("  Max concurrent:  {:>12}", bursts.max_concurrent);
+    }
+
+    if let Some(ref scaling) = snapshot.vu_scaling {
+        println!("\nVU Autoscaling:");
+        println!("  Scale-ups:       {:>12}", scaling.scale_ups());
+        println!("  Scale-downs:     {:>12}", scaling.scale_downs());
+        println!(
+            "  Peak VUs:        {:>12}  (cap {})",
+            scaling.peak, scaling.cap
+        );
+        println!("  Final VUs:       {:>12}", scaling.allocated);
+    }
+
+    if let Some(ref workers) = snapshot.workers
+        && !workers.unhealthy.is_empty()
+    {
+        println!(
+            "\nUnhealthy Workers: ({} of {}, failed {}+ requests in a row)",
+            workers.unhealthy.len(),
+            workers.workers,
+            types::WORKER_FAILURE_STREAK
+        );
+        for worker in &workers.unhealthy {
+            let state = if worker.streak >= types::WORKER_FAILURE_STREAK {
+                format!("still failing, {} in a row", worker.streak)
+            } else {
+                format!("recovered, {} in a row at worst", worker.longest_streak)
+            };
+            println!(
+                "  worker {:<5} {:>8} req {:>8} failed  last {}  ({})",
+                worker.id, worker.requests, worker.failed, worker.last_error, state
+            );
+        }
+    }
+
+    if !snapshot.failure_samples.is_empty() {
+        println!("\nError Samples:");
+        let mut last_kind = None;
+        for sample in &snapshot.failure_samples {
+            // One example per kind; the rest are in the JSON output
+            if last_kind == Some(&sample.kind) {
+                continue;
+            }
+            last_kind = Some(&sample.kind);
+            println!(
+                "  {:15} {}",
+                format!("{}:", sample.kind),
+                sample.request.as_deref().unwrap_or("-")
+            );
+            // The innermost cause of a transport error says the most
+            let message = sample
+                .message
+                .as_deref()
+                .map(|m| m.rsplit(": ").next().unwrap_or(m));
+            if let Some(detail) = message.or(sample.body.as_deref()) {
+                let line: String = detail
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+                    .chars()
+                    .take(SUMMARY_SAMPLE_CHARS)
+                    .collect();
+                println!("  {:15} {}", "", line);
+            }
+        }
+    }
+
+    if !snapshot.slowest_traces.is_empty() {
+        println!("\nSlowest Traces:");
+        for trace in &snapshot.slowest_traces {
+            println!(
+                "  {:032x} {:>10.2}ms  {}",
+                trace.trace_id,
+                trace.latency_us as f64 / 1000.0,
+                trace
+                    .status
+                    .map_or_else(|| "error".to_string(), |s| s.to_string())
+            );
+        }
+    }
+
+    // Mixed HTTP + WebSocket runs report both stat families
+    if snapshot.has_websocket_stats() {
+        print_ws_summary(snapshot);
+    }
+
     println!("\n{}", "=".repeat(50));
 }
 
+fn print_ws_summary(snapshot: &types::StatsSnapshot) {
+    println!("\nWebSocket:");
+    println!("  Messages Sent:   {:>12}", snapshot.ws_messages_sent);
+    println!("  Messages Recv:   {:>12}", snapshot.ws_messages_received);
+    println!("  Messages/sec:    {:>12.2}", snapshot.ws_messages_per_sec);
+    println!(
+        "  Error Rate:      {:>11.2}%",
+        snapshot.ws_error_rate * 100.0
+    );
+
+    println!("\nConnections:");
+    println!(
+        "  Established:     {:>12}",
+        snapshot.ws_connections_established
+    );
+    println!("  Errors:          {:>12}", snapshot.ws_connection_errors);
+    println!("  Disconnects:     {:>12}", snapshot.ws_disconnects);
+    println!(
+        "  Connect p99:     {:>12.2}",
+        snapshot.ws_connect_time_p99_us as f64 / 1000.0
+    );
+    if snapshot.ws_reconnects > 0 || snapshot.ws_reconnect_failures > 0 {
+        println!("  Reconnects:      {:>12}", snapshot.ws_reconnects);
+        println!("  Reconnect Fails: {:>12}", snapshot.ws_reconnect_failures);
+        println!(
+            "  Reconnect p99:   {:>12.2}",
+            snapshot.ws_reconnect_time_p99_us as f64 / 1000.0
+        );
+    }
+
+    if !snapshot.ws_close_codes.is_empty() {
+        println!("\nServer Closes:");
+        let mut codes: Vec<_> = snapshot.ws_close_codes.iter().collect();
+        codes.sort_by(|a, b| b.1.count.cmp(&a.1.count).then(a.0.cmp(b.0)));
+        for (code, stat) in codes {
+            let pct = if snapshot.ws_connections_established > 0 {
+                stat.count as f64 / snapshot.ws_connections_established as f64 * 100.0
+            } else {
+                0.0
+            };
+            let label = format!("{} {}:", code, types::ws_close_code_name(*code));
+            if stat.reason.is_empty() {
+                println!("  {:22} {:>8} ({:.2}%)", label, stat.count, pct);
+            } else {
+                println!(
+                    "  {:22} {:>8} ({:.2}%)  \"{}\"",
+                    label, stat.count, pct, stat.reason
+                );
+            }
+        }
+    }
+
+    if snapshot.ws_broadcast_expected > 0 {
+        println!("\nBroadcast Delivery:");
+        println!("  Expected:        {:>12}", snapshot.ws_broadcast_expected);
+        println!("  Delivered:       {:>12}", snapshot.ws_broadcast_delivered);
+        println!(
+            "  Loss Rate:       {:>11.2}%",
+            snapshot.ws_broadcast_loss_rate() * 100.0
+        );
+        println!(
+            "  p50 (ms):        {:>12.2}",
+            snapshot.ws_delivery_latency_p50_us as f64 / 1000.0
+        );
+        println!(
+            "  p95 (ms):        {:>12.2}",
+            snapshot.ws_delivery_latency_p95_us as f64 / 1000.0
+        );
+        println!(
+            "  p99 (ms):        {:>12.2}",
+            snapshot.ws_delivery_latency_p99_us as f64 / 1000.0
+        );
+    }
+
+    if let Some(ref bytes) = snapshot.ws_compression {
+        println!("\nCompression (permessage-deflate):");
+        println!(
+            "  Sent:            {:>12} -> {} bytes",
+            bytes.raw_sent, bytes.wire_sent
+        );
+        println!(
+            "  Received:        {:>12} -> {} bytes",
+            bytes.raw_received, bytes.wire_received
+        );
+        println!("  Wire/Raw:        {:>11.1}%", bytes.ratio() * 100.0);
+    }
+
+    println!("\nMessage Latency (ms):");
+    println!(
+        "  p50:             {:>12.2}",
+        snapshot.ws_latency_p50_us as f64 / 1000.0
+    );
+    println!(
+        "  p95:             {:>12.2}",
+        snapshot.ws_latency_p95_us as f64 / 1000.0
+    );
+    println!(
+        "  p99:             {:>12.2}",
+        snapshot.ws_latency_p99_us as f64 / 1000.0
+    );
+
+    if !snapshot.ws_errors.is_empty() {
+        println!("\nErrors:");
+        for (kind, count) in &snapshot.ws_errors {
+            println!("  {:15} {:>12}", format!("{}:", kind.as_str()), count);
+        }
+    }
+}
+
 fn print_check_results(check_stats: &std::collections::HashMap<String, (u64, u64)>) {
     println!("\n{}", "=".repeat(60));
     println!("CHECKS");
@@ -1,4 +1,4 @@
-use crate::types::{LoadConfig, StatsSnapshot};
+use crate::types::{LoadConfig, StatsSnapshot, percentile_key};
 use std::fs::File;
 use std::io::{self, BufWriter, Write};
 
@@ -27,6 +27,12 @@ fn write_csv_content<W: Write>(
     writeln!(writer, "method,{}", config.method)?;
     writeln!(writer, "concurrency,{}", config.concurrency)?;
     writeln!(writer, "duration_secs,{}", snapshot.elapsed.as_secs())?;
+    if snapshot.started_at_ms > 0
+        && let Some(started_at) =
+            chrono::DateTime::from_timestamp_millis(snapshot.started_at_ms as i64)
+    {
+        writeln!(writer, "started_at,{}", started_at.to_rfc3339())?;
+    }
 
     // Summary
     writeln!(writer, "total_requests,{}", snapshot.total_requests)?;
@@ -34,6 +40,10 @@ fn write_csv_content<W: Write>(
     writeln!(writer, "failed,{}", snapshot.failed)?;
     writeln!(writer, "requests_per_sec,{:.2}", snapshot.requests_per_sec)?;
     writeln!(writer, "error_rate,{:.6}", snapshot.error_rate)?;
+    writeln!(writer, "bytes_received,{}", snapshot.bytes_received)?;
+    writeln!(writer, "bytes_sent,{}", snapshot.bytes_sent)?;
+    writeln!(writer, "rx_mbps,{:.3}", snapshot.rx_mbps())?;
+    writeln!(writer, "tx_mbps,{:.3}", snapshot.tx_mbps())?;
 
     // Latency (ms)
     writeln!(
@@ -51,31 +61,14 @@ fn write_csv_content<W: Write>(
         "latency_mean_ms,{:.2}",
         snapshot.latency_mean_us / 1000.0
     )?;
-    writeln!(
-        writer,
-        "latency_p50_ms,{:.2}",
-        snapshot.latency_p50_us as f64 / 1000.0
-    )?;
-    writeln!(
-        writer,
-        "latency_p90_ms,{:.2}",
-        snapshot.latency_p90_us as f64 / 1000.0
-    )?;
-    writeln!(
-        writer,
-        "latency_p95_ms,{:.2}",
-        snapshot.latency_p95_us as f64 / 1000.0
-    )?;
-    writeln!(
-        writer,
-        "latency_p99_ms,{:.2}",
-        snapshot.latency_p99_us as f64 / 1000.0
-    )?;
-    writeln!(
-        writer,
-        "latency_p999_ms,{:.2}",
-        snapshot.latency_p999_us as f64 / 1000.0
-    )?;
+    for &(p, latency_us) in &snapshot.latency_percentiles {
+        writeln!(
+            writer,
+            "latency_{}_ms,{:.2}",
+            percentile_key(p),
+            latency_us as f64 / 1000.0
+        )?;
+    }
 
     // Status codes
     let mut codes: Vec<_> = snapshot.status_codes.iter().collect();
@@ -1,4 +1,4 @@
-use crate::output::json::{ArrivalRateSummary, Latency, Summary};
+use crate::output::json::{ArrivalRateSummary, Summary};
 use crate::types::{LoadConfig, StatsSnapshot};
 use std::fs::File;
 use std::io::{self, BufWriter, Write};
@@ -27,30 +27,23 @@ fn render_html<W: Write>(
         error_rate: snapshot.error_rate,
         requests_per_sec: snapshot.requests_per_sec,
         bytes_received: snapshot.bytes_received,
+        bytes_sent: snapshot.bytes_sent,
+        rx_mbps: snapshot.rx_mbps(),
+        tx_mbps: snapshot.tx_mbps(),
         arrival_rate: if config.arrival_rate.is_some() {
             Some(ArrivalRateSummary {
                 target_rps: config.arrival_rate.unwrap_or(0),
                 achieved_rps: snapshot.rolling_rps,
                 achieved_rps_window_secs: 1,
-                max_vus: config.max_vus.unwrap_or(0),
+                max_vus: config
+                    .vu_autoscale
+                    .map_or(config.max_vus.unwrap_or(0), |policy| policy.cap),
                 dropped_iterations: snapshot.dropped_iterations,
             })
         } else {
             None
         },
-    };
-
-    let latency = Latency {
-        min: snapshot.latency_min_us,
-        max: snapshot.latency_max_us,
-        mean: snapshot.latency_mean_us,
-        stddev: Some(snapshot.latency_stddev_us),
-        p50: snapshot.latency_p50_us,
-        p75: snapshot.latency_p75_us,
-        p90: snapshot.latency_p90_us,
-        p95: snapshot.latency_p95_us,
-        p99: snapshot.latency_p99_us,
-        p999: snapshot.latency_p999_us,
+        results_dropped: (snapshot.results_dropped > 0).then_some(snapshot.results_dropped),
     };
 
     let status_codes_html = snapshot
@@ -84,13 +77,41 @@ fn render_html<W: Write>(
         .collect::<Vec<_>>()
         .join("\n");
 
+    let apdex_html = snapshot
+        .apdex
+        .map(|a| {
+            format!(
+                r#"<div class="stat-item"><span class="stat-label">Apdex (T={}ms)</span><span class="stat-value">{:.2} ({})</span></div>"#,
+                a.t_us / 1000,
+                a.score(),
+                a.rating()
+            )
+        })
+        .unwrap_or_default();
+
+    let interval_secs = config.timeline_interval.as_secs_f64().max(0.001);
     let timeline_data: Vec<String> = snapshot
         .timeline
         .iter()
-        .map(|t| format!("{}", t.requests))
+        .map(|t| format!("{:.2}", t.requests as f64 / interval_secs))
         .collect();
     let timeline_json = format!("[{}]", timeline_data.join(","));
 
+    // [p50, p95, p99] in ms per bucket
+    let latency_data: Vec<String> = snapshot
+        .timeline
+        .iter()
+        .map(|t| {
+            format!(
+                "[{:.3},{:.3},{:.3}]",
+                t.latency_p50_us as f64 / 1000.0,
+                t.latency_p95_us as f64 / 1000.0,
+                t.latency_p99_us as f64 / 1000.0
+            )
+        })
+        .collect();
+    let latency_json = format!("[{}]", latency_data.join(","));
+
     write!(
         w,
         r##"<!DOCTYPE html>
@@ -206,6 +227,13 @@ fn render_html<W: Write>(
             border-radius: 2px 2px 0 0;
             min-height: 2px;
         }}
+        .latency-timeline {{
+            width: 100%;
+            height: 120px;
+            margin: 1rem 0 0.5rem;
+        }}
+        .legend {{ color: var(--text-secondary); font-size: 0.9rem; }}
+        .legend span {{ margin-right: 1.5rem; }}
         .footer {{
             text-align: center;
             margin-top: 2rem;
@@ -251,6 +279,11 @@ fn render_html<W: Write>(
                         <span class="stat-label">Error Rate</span>
                         <span class="stat-value">{error_rate:.2}%</span>
                     </div>
+                    <div class="stat-item">
+                        <span class="stat-label">Network (Mbps)</span>
+                        <span class="stat-value">{rx_mbps:.2} in / {tx_mbps:.2} out</span>
+                    </div>
+                    {apdex}
                 </div>
             </div>
 
@@ -277,6 +310,17 @@ fn render_html<W: Write>(
             <div class="timeline" id="timeline"></div>
         </div>
 
+        <div class="card">
+            <h2>Latency over time (ms)</h2>
+            <svg class="latency-timeline" id="latency-timeline" viewBox="0 0 1000 100" preserveAspectRatio="none"></svg>
+            <div class="legend">
+                <span style="color: var(--accent-green)">p50</span>
+                <span style="color: var(--accent-yellow)">p95</span>
+                <span style="color: var(--accent-red)">p99</span>
+                <span>peak {latency_peak:.2} ms</span>
+            </div>
+        </div>
+
         <div class="card">
             <h2>Configuration</h2>
             <div class="stat-item">
@@ -308,6 +352,23 @@ fn render_html<W: Write>(
             bar.style.height = (val / max * 100) + '%';
             timeline.appendChild(bar);
         }});
+
+        const latency = {latency_data};
+        const peak = Math.max(...latency.flat(), 0.001);
+        const chart = document.getElementById('latency-timeline');
+        const colors = ['var(--accent-green)', 'var(--accent-yellow)', 'var(--accent-red)'];
+        colors.forEach((color, i) => {{
+            const points = latency.map((p, x) =>
+                (x / Math.max(latency.length - 1, 1) * 1000) + ',' + (100 - p[i] / peak * 100)
+            ).join(' ');
+            const line = document.createElementNS('http://www.w3.org/2000/svg', 'polyline');
+            line.setAttribute('points', points);
+            line.setAttribute('fill', 'none');
+            line.setAttribute('stroke', color);
+            line.setAttribute('stroke-width', '2');
+            line.setAttribute('vector-effect', 'non-scaling-stroke');
+            chart.appendChild(line);
+        }});
     </script>
 </body>
 </html>
@@ -319,7 +380,10 @@ fn render_html<W: Write>(
         successful = summary.successful,
         failed = summary.failed,
         error_rate = summary.error_rate * 100.0,
-        latency_bars = render_latency_bars(&latency),
+        apdex = apdex_html,
+        rx_mbps = summary.rx_mbps,
+        tx_mbps = summary.tx_mbps,
+        latency_bars = render_latency_bars(&snapshot.latency_percentiles),
         status_codes = if status_codes_html.is_empty() {
             "<p style=\"color: var(--text-secondary)\">No data</p>".to_string()
         } else {
@@ -335,22 +399,24 @@ fn render_html<W: Write>(
         timeout = config.timeout.as_millis(),
         version = env!("CARGO_PKG_VERSION"),
         timeline_data = timeline_json,
+        latency_data = latency_json,
+        latency_peak = snapshot
+            .timeline
+            .iter()
+            .map(|t| t.latency_p99_us)
+            .max()
+            .unwrap_or(0) as f64
+            / 1000.0,
     )
 }
 
-fn render_latency_bars(latency: &Latency) -> String {
-    let max_latency = latency.p999 as f64;
-    let percentiles = [
-        ("p50", latency.p50),
-        ("p90", latency.p90),
-        ("p95", latency.p95),
-        ("p99", latency.p99),
-        ("p999", latency.p999),
-    ];
+fn render_latency_bars(percentiles: &[(f64, u64)]) -> String {
+    let max_latency = percentiles.last().map_or(0, |&(_, value)| value) as f64;
 
     percentiles
         .iter()
-        .map(|(label, value)| {
+        .map(|(p, value)| {
+            let label = format!("p{}", p);
             let pct = if max_latency > 0.0 {
                 (*value as f64 / max_latency * 100.0).min(100.0)
             } else {
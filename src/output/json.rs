@@ -1,12 +1,30 @@
-use crate::types::{LoadConfig, StatsSnapshot, ThresholdResult};
+use crate::net::IpFamily;
+use crate::output::schema::SCHEMA_VERSION;
+use crate::types::{
+    ApdexStats, AutoWarmup, BurstStats, ChaosStats, ConnectionCapStats, ConnectionStats,
+    ContentTypeCount, ContentTypeStats, DuplicateStats, EndpointStats, ErrorKind, ExtractionStats,
+    FailoverIncident, FailoverStats, FailureSample, LoadConfig, NetworkProfile, OutageWindow,
+    PluginMetric, RecentStats, RequestIdStats, RevalidationStats, ScenarioShare, StageStats,
+    StatsSnapshot, ThresholdResult, ThrottleStats, TimelineBucket, TraceSample, UnhealthyWorker,
+    UploadStats, VuScaleEvent, VuScalingStats, WorkerHealthStats, WsMode, megabits_per_sec,
+    parse_percentile_key, percentile_key, ws_close_code_name,
+};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use chrono::{DateTime, Utc};
+use hdrhistogram::Histogram;
+use hdrhistogram::serialization::{Deserializer, Serializer, V2DeflateSerializer};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
-use std::io::{self, BufWriter};
+use std::io::{self, BufWriter, Write};
+use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Serialize, Deserialize)]
 pub struct JsonOutput {
+    /// Version of `schemas/results.schema.json` the file follows
+    pub schema_version: u32,
     pub metadata: Metadata,
     pub summary: Summary,
     pub latency_us: Latency,
@@ -16,6 +34,8 @@ pub struct JsonOutput {
     pub queue_time_us: Option<QueueTime>,
     pub status_codes: HashMap<String, u64>,
     pub errors: HashMap<String, u64>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub jsonrpc_errors: HashMap<String, u64>,
     pub timeline: Vec<TimelineEntry>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thresholds: Option<ThresholdsOutput>,
@@ -25,6 +45,464 @@ pub struct JsonOutput {
     pub scenarios: Option<Vec<ScenarioOutput>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub websocket: Option<WebSocketOutput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quic: Option<QuicOutput>,
+    /// Per-endpoint breakdown, busiest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub endpoints: Vec<EndpointOutput>,
+    /// Per-host breakdown for `[[target.hosts]]`, busiest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<EndpointOutput>,
+    /// Per-stage breakdown for `[[stages]]`, in the order they ran
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<StageOutput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload: Option<UploadOutput>,
+    /// Example failures per error kind or status
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub error_samples: Vec<ErrorSampleOutput>,
+    /// Slowest traced requests, slowest first (--trace-propagation)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slowest_traces: Vec<TraceOutput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apdex: Option<ApdexOutput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connections: Option<ConnectionsOutput>,
+    /// Waits for a free connection (`--max-connections`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_cap: Option<ConnectionCapOutput>,
+    /// Responses per Content-Type, and those `--expect-content-type` didn't allow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_types: Option<ContentTypesOutput>,
+    /// The last 10s of the run, for steady-state numbers after ramp-up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recent: Option<RecentOutput>,
+    /// Values WASM plugins reported with `kaioken.metric`, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub plugin_metrics: BTreeMap<String, PluginMetricOutput>,
+    /// Faults `[chaos]` injected; reset and aborted requests aren't in the stats
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chaos: Option<ChaosOutput>,
+    /// 429 and 503 responses backed off after (`--honor-retry-after`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throttle: Option<ThrottleOutput>,
+    /// Per-burst stats in burst mode (`--burst-rate`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bursts: Option<BurstsOutput>,
+    /// Per-VU request spread and workers that kept failing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workers: Option<WorkersOutput>,
+    /// Responses checked for the ID they were sent (`--request-id-header`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_ids: Option<RequestIdsOutput>,
+    /// Requests sent twice with one idempotency key (`--duplicate-rate`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicates: Option<DuplicatesOutput>,
+    /// Conditional requests and the 304s they got (`--conditional-requests`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revalidation: Option<RevalidationOutput>,
+    /// Outages the run paused through (`--pause-on-errors`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outages: Vec<OutageOutput>,
+    /// Availability by timeline bucket and the incidents in it (`--failover`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failover: Option<FailoverOutput>,
+    /// Attempts and failures per scenario extraction
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extractions: Vec<ExtractionOutput>,
+    /// Requests per weighted scenario against its share of the weights
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scenario_mix: Vec<ScenarioMixOutput>,
+    /// How `--vu-autoscale` grew and shed the arrival-rate VUs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vu_scaling: Option<VuScalingOutput>,
+    /// Base64 of the V2-deflate serialized latency histogram (µs), so a
+    /// `--runners` coordinator can merge percentiles; `--runner-stream` only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_histogram: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ChaosOutput {
+    pub delayed: u64,
+    pub aborted: u64,
+    pub reset: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ThrottleOutput {
+    pub throttled: u64,
+    /// Of those, how many had a Retry-After header
+    pub retry_after: u64,
+    /// Time VUs spent waiting instead of sending, summed over VUs
+    pub waited_secs: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BurstsOutput {
+    pub bursts: u64,
+    pub min_size: u32,
+    pub max_size: u32,
+    /// Time from a burst's first request to its last response
+    pub completion_mean_ms: f64,
+    pub completion_max_ms: f64,
+    pub max_concurrent: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DuplicatesOutput {
+    pub pairs: u64,
+    pub consistent: u64,
+    /// The two copies got different status codes
+    pub status_mismatch: u64,
+    /// Same status, different body
+    pub body_mismatch: u64,
+    /// A copy failed without a response
+    pub incomplete: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VuScalingOutput {
+    /// VUs in the pool at the end
+    pub allocated: u32,
+    pub peak: u32,
+    pub cap: u32,
+    pub scale_ups: usize,
+    pub scale_downs: usize,
+    pub events: Vec<VuScaleEventOutput>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VuScaleEventOutput {
+    pub elapsed_ms: u64,
+    pub from: u32,
+    pub to: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExtractionOutput {
+    pub scenario: String,
+    pub name: String,
+    pub attempts: u64,
+    /// The JSONPath, regex or header found nothing
+    pub failed: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ScenarioMixOutput {
+    pub scenario: String,
+    pub weight: u32,
+    pub requests: u64,
+    /// Its weight over all weights
+    pub expected_pct: f64,
+    /// Its requests over all scenario requests
+    pub actual_pct: f64,
+    /// How far actual is off expected, in percent of expected
+    pub drift_pct: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RevalidationOutput {
+    pub conditional: u64,
+    pub not_modified: u64,
+    /// Share of conditional requests answered with a 304
+    pub not_modified_ratio: f64,
+    /// 2xx responses, conditional or not
+    pub full_fetches: u64,
+    pub not_modified_mean_us: u64,
+    pub full_fetch_mean_us: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OutageOutput {
+    /// Seconds into the run of the first failure
+    pub start_secs: f64,
+    pub duration_secs: f64,
+    pub health_checks: u32,
+    /// False if the run ended before the target came back
+    pub recovered: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FailoverOutput {
+    /// Width of the buckets `availability` is given for
+    pub interval_secs: f64,
+    /// Share of requests that succeeded in each bucket
+    pub availability: Vec<f64>,
+    /// Share of buckets without a failure
+    pub uptime_ratio: f64,
+    pub downtime_secs: f64,
+    pub longest_burst_secs: f64,
+    pub incidents: Vec<FailoverIncidentOutput>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FailoverIncidentOutput {
+    /// Seconds into the run of the first failing bucket
+    pub start_secs: f64,
+    pub error_burst_secs: f64,
+    pub downtime_secs: f64,
+    /// None if the run ended before the target recovered
+    pub time_to_recovery_secs: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RequestIdsOutput {
+    pub responses: u64,
+    pub matched: u64,
+    /// Responses that carried another request's ID
+    pub mismatched: u64,
+    pub missing: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WorkersOutput {
+    pub workers: u32,
+    /// Fewest and most requests sent by a single VU
+    pub requests_min: u64,
+    pub requests_max: u64,
+    /// VUs that failed 50 requests in a row at some point
+    pub unhealthy: Vec<UnhealthyWorkerOutput>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UnhealthyWorkerOutput {
+    pub worker: u32,
+    pub requests: u64,
+    pub failed: u64,
+    /// Failures in a row at the end of the run (0 once it recovered)
+    pub streak: u32,
+    pub longest_streak: u32,
+    pub last_error: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PluginMetricOutput {
+    pub count: u64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RecentOutput {
+    /// Time covered: 10-11s, or the whole run if shorter
+    pub window_secs: f64,
+    pub requests: u64,
+    pub requests_per_sec: f64,
+    /// Latency at the configured `percentiles`, keyed like `latency_us.percentiles`
+    pub latency_us: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConnectionsOutput {
+    /// New TCP (and TLS) connections the client opened
+    pub opened: u64,
+    /// Requests sent on an already open connection
+    pub reused: u64,
+    pub reuse_rate: f64,
+    pub opened_per_sec: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConnectionCapOutput {
+    pub limit: u32,
+    /// Requests that found every connection busy
+    pub waited: u64,
+    pub wait_mean_ms: f64,
+    pub wait_max_ms: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ContentTypesOutput {
+    /// Successful responses of a type that wasn't expected
+    pub unexpected: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expected: Vec<String>,
+    /// Busiest first
+    pub types: Vec<ContentTypeOutput>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ContentTypeOutput {
+    pub content_type: String,
+    pub responses: u64,
+    pub unexpected: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ApdexOutput {
+    pub t_ms: f64,
+    pub score: f64,
+    pub rating: String,
+    pub satisfied: u64,
+    pub tolerating: u64,
+    pub frustrated: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TraceOutput {
+    /// 32 hex digits, as sent in the trace context headers
+    pub trace_id: String,
+    pub latency_us: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    pub started_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ErrorSampleOutput {
+    /// `timeout`, `status 503`, ...
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Start of the response body (first 1 KiB)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    pub latency_us: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<&FailureSample> for ErrorSampleOutput {
+    fn from(sample: &FailureSample) -> Self {
+        Self {
+            kind: sample.kind.clone(),
+            request: sample.request.clone(),
+            status: sample.status,
+            error: sample.message.clone(),
+            body: sample.body.clone(),
+            latency_us: sample.latency_us,
+            timestamp: DateTime::from_timestamp_millis(sample.timestamp_ms as i64)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UploadOutput {
+    pub bytes_sent: u64,
+    pub bytes_sent_per_sec: f64,
+    /// Requests with bodies over 64 KiB, whose write time was measured
+    pub timed_requests: u64,
+    pub time_us: UploadTime,
+    /// Timed body bytes over the time spent writing them
+    pub throughput_bytes_per_sec: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UploadTime {
+    pub mean: f64,
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EndpointOutput {
+    pub name: String,
+    pub requests: u64,
+    pub failed: u64,
+    pub error_rate: f64,
+    pub latency_us: EndpointLatency,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EndpointLatency {
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+impl From<&EndpointStats> for EndpointOutput {
+    fn from(e: &EndpointStats) -> Self {
+        Self {
+            name: e.name.clone(),
+            requests: e.requests,
+            failed: e.failed,
+            error_rate: if e.requests > 0 {
+                e.failed as f64 / e.requests as f64
+            } else {
+                0.0
+            },
+            latency_us: EndpointLatency {
+                p50: e.latency_p50_us,
+                p95: e.latency_p95_us,
+                p99: e.latency_p99_us,
+                max: e.latency_max_us,
+            },
+        }
+    }
+}
+
+impl From<&EndpointOutput> for EndpointStats {
+    fn from(e: &EndpointOutput) -> Self {
+        Self {
+            name: e.name.clone(),
+            requests: e.requests,
+            failed: e.failed,
+            latency_p50_us: e.latency_us.p50,
+            latency_p95_us: e.latency_us.p95,
+            latency_p99_us: e.latency_us.p99,
+            latency_max_us: e.latency_us.max,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct StageOutput {
+    /// Position in `[[stages]]`, from 1
+    pub stage: usize,
+    /// VUs, or requests per second for `target_rate` stages
+    pub target: u32,
+    /// Time measured in the stage, after warmup
+    pub duration_secs: f64,
+    pub requests: u64,
+    pub failed: u64,
+    pub requests_per_sec: f64,
+    pub error_rate: f64,
+    pub latency_us: EndpointLatency,
+}
+
+impl From<&StageStats> for StageOutput {
+    fn from(s: &StageStats) -> Self {
+        Self {
+            stage: s.index + 1,
+            target: s.target,
+            duration_secs: s.elapsed.as_secs_f64(),
+            requests: s.requests,
+            failed: s.failed,
+            requests_per_sec: s.rps(),
+            error_rate: s.error_rate(),
+            latency_us: EndpointLatency {
+                p50: s.latency_p50_us,
+                p95: s.latency_p95_us,
+                p99: s.latency_p99_us,
+                max: s.latency_max_us,
+            },
+        }
+    }
+}
+
+impl From<&StageOutput> for StageStats {
+    fn from(s: &StageOutput) -> Self {
+        Self {
+            index: s.stage.saturating_sub(1),
+            target: s.target,
+            elapsed: Duration::from_secs_f64(s.duration_secs),
+            requests: s.requests,
+            failed: s.failed,
+            latency_p50_us: s.latency_us.p50,
+            latency_p95_us: s.latency_us.p95,
+            latency_p99_us: s.latency_us.p99,
+            latency_max_us: s.latency_us.max,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -52,7 +530,7 @@ pub struct ScenarioOutput {
     pub url: String,
     pub method: String,
     pub weight: u32,
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub tags: HashMap<String, String>,
 }
 
@@ -73,6 +551,12 @@ pub struct Target {
     pub url: String,
     pub method: String,
     pub headers: Vec<String>,
+    /// Address family forced with --ipv4 / --ipv6
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_family: Option<String>,
+    /// False with --no-happy-eyeballs
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub happy_eyeballs: bool,
 }
 
 #[derive(Serialize, Deserialize, Default)]
@@ -80,6 +564,9 @@ pub struct Target {
 pub struct Load {
     pub concurrency: u32,
     pub rate: u32,
+    /// Token bucket capacity for `rate` (--rate-burst)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_burst: Option<u32>,
     pub ramp_up_secs: u64,
     pub warmup_secs: u64,
     pub timeout_ms: u64,
@@ -89,6 +576,38 @@ pub struct Load {
     pub arrival_rate: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_vus: Option<u32>,
+    /// Width of each `timeline` entry
+    pub timeline_interval_ms: u64,
+    /// Where --auto-warmup ended warmup (`warmup_secs` is then the cap)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_warmup: Option<AutoWarmupOutput>,
+    /// Client network responses were slowed to (--network-profile)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_profile: Option<NetworkProfileOutput>,
+    /// Attempts allowed after the first (--retries)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    /// Budget per request across retries and redirects (--deadline)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline_ms: Option<u64>,
+    /// Seed the run's random choices came from (--seed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NetworkProfileOutput {
+    pub name: String,
+    pub down_bps: u64,
+    pub up_bps: u64,
+    pub rtt_ms: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AutoWarmupOutput {
+    pub detected_ms: u64,
+    /// False if the numbers never settled and the cap ended warmup
+    pub settled: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -106,8 +625,18 @@ pub struct Summary {
     pub error_rate: f64,
     pub requests_per_sec: f64,
     pub bytes_received: u64,
+    #[serde(default)]
+    pub bytes_sent: u64,
+    /// Average network throughput over the run, in megabits/sec
+    #[serde(default)]
+    pub rx_mbps: f64,
+    #[serde(default)]
+    pub tx_mbps: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arrival_rate: Option<ArrivalRateSummary>,
+    /// Results discarded under the drop backpressure policy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub results_dropped: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -132,6 +661,37 @@ pub struct Latency {
     pub p95: u64,
     pub p99: u64,
     pub p999: u64,
+    /// The configured `percentiles`, keyed like `p9999` for 99.99
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub percentiles: BTreeMap<String, u64>,
+}
+
+/// QUIC path stats for HTTP/3 runs
+#[derive(Serialize, Deserialize)]
+pub struct QuicOutput {
+    pub connections: u64,
+    pub rtt_mean_us: f64,
+    pub rtt_p50_us: u64,
+    pub rtt_p99_us: u64,
+    pub cwnd_mean_bytes: f64,
+    pub packets_sent: u64,
+    pub packets_lost: u64,
+    pub loss_rate: f64,
+    pub congestion_events: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zero_rtt: Option<ZeroRttOutput>,
+}
+
+/// 0-RTT vs 1-RTT breakdown (--http3-0rtt)
+#[derive(Serialize, Deserialize)]
+pub struct ZeroRttOutput {
+    pub zero_rtt_requests: u64,
+    pub one_rtt_requests: u64,
+    pub rejected: u64,
+    pub zero_rtt_p50_us: u64,
+    pub zero_rtt_p99_us: u64,
+    pub one_rtt_p50_us: u64,
+    pub one_rtt_p99_us: u64,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -156,6 +716,14 @@ pub struct WebSocketOutput {
     pub errors: HashMap<String, u64>,
     pub latency_us: WsLatency,
     pub connect_time_us: WsConnectTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub churn: Option<WsChurn>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub broadcast: Option<WsBroadcast>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<WsCompression>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub close_codes: HashMap<String, WsCloseCode>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -175,11 +743,96 @@ pub struct WsConnectTime {
     pub p99: u64,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct WsChurn {
+    pub reconnect_every_secs: f64,
+    pub reconnects: u64,
+    pub reconnect_failures: u64,
+    pub reconnect_time_us: WsConnectTime,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WsBroadcast {
+    pub publishers: u32,
+    pub expected_deliveries: u64,
+    pub deliveries: u64,
+    pub loss_rate: f64,
+    pub delivery_latency_us: WsDeliveryLatency,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WsCloseCode {
+    pub name: String,
+    pub count: u64,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WsCompression {
+    pub negotiated: bool,
+    pub raw_bytes_sent: u64,
+    pub wire_bytes_sent: u64,
+    pub raw_bytes_received: u64,
+    pub wire_bytes_received: u64,
+    pub ratio: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WsDeliveryLatency {
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct TimelineEntry {
     pub elapsed_secs: u32,
     pub requests: u64,
     pub errors: u64,
+    /// Bucket start, for sub-second `--timeline-interval`
+    #[serde(default)]
+    pub elapsed_ms: u64,
+    #[serde(default)]
+    pub bytes_received: u64,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub bytes_sent: u64,
+    /// Bucket throughput in megabits/sec
+    #[serde(default)]
+    pub rx_mbps: f64,
+    #[serde(default)]
+    pub tx_mbps: f64,
+    #[serde(default)]
+    pub latency_us: TimelineLatency,
+    /// Arrival-rate VUs at the bucket's end (`--vu-autoscale`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vus: Option<u32>,
+    /// Wall clock of the bucket start, to line up with server logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Checks evaluated in the bucket
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub checks: BTreeMap<String, CheckResultOutput>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct TimelineLatency {
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
 }
 
 fn redact_header(header: &str) -> String {
@@ -206,7 +859,10 @@ pub fn create_output(
     check_stats: Option<&HashMap<String, (u64, u64)>>,
 ) -> JsonOutput {
     let now = Utc::now();
-    let started_at = now - chrono::Duration::from_std(snapshot.elapsed).unwrap_or_default();
+    let started_at = match snapshot.started_at_ms {
+        0 => now - chrono::Duration::from_std(snapshot.elapsed).unwrap_or_default(),
+        ms => DateTime::from_timestamp_millis(ms as i64).unwrap_or(now),
+    };
 
     let headers: Vec<String> = config
         .headers
@@ -226,6 +882,12 @@ pub fn create_output(
         .map(|(k, v)| (k.as_str().to_string(), *v))
         .collect();
 
+    let jsonrpc_errors: HashMap<String, u64> = snapshot
+        .jsonrpc_errors
+        .iter()
+        .map(|(k, v)| (k.to_string(), *v))
+        .collect();
+
     let timeline: Vec<TimelineEntry> = snapshot
         .timeline
         .iter()
@@ -233,6 +895,32 @@ pub fn create_output(
             elapsed_secs: b.elapsed_secs,
             requests: b.requests,
             errors: b.errors,
+            elapsed_ms: b.elapsed_ms,
+            bytes_received: b.bytes_received,
+            bytes_sent: b.bytes_sent,
+            rx_mbps: megabits_per_sec(b.bytes_received, config.timeline_interval),
+            tx_mbps: megabits_per_sec(b.bytes_sent, config.timeline_interval),
+            latency_us: TimelineLatency {
+                p50: b.latency_p50_us,
+                p95: b.latency_p95_us,
+                p99: b.latency_p99_us,
+            },
+            vus: snapshot.vu_scaling.as_ref().map(|scaling| {
+                scaling.allocated_at(b.elapsed_ms + config.timeline_interval.as_millis() as u64)
+            }),
+            timestamp: Some(started_at + chrono::Duration::milliseconds(b.elapsed_ms as i64)),
+            checks: b
+                .checks
+                .iter()
+                .map(|(name, &(passed, total))| {
+                    let output = CheckResultOutput {
+                        passed,
+                        total,
+                        pass_rate: passed as f64 / total.max(1) as f64,
+                    };
+                    (name.clone(), output)
+                })
+                .collect(),
         })
         .collect();
 
@@ -274,6 +962,7 @@ pub fn create_output(
     });
 
     JsonOutput {
+        schema_version: SCHEMA_VERSION,
         metadata: Metadata {
             tool: "kaioken".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
@@ -284,10 +973,14 @@ pub fn create_output(
                 url: config.url.clone(),
                 method: config.method.to_string(),
                 headers,
+                ip_family: (config.socket.ip_family != IpFamily::Any)
+                    .then(|| config.socket.ip_family.as_str().to_string()),
+                happy_eyeballs: config.socket.happy_eyeballs,
             },
             load: Load {
                 concurrency: config.concurrency,
                 rate: config.rate,
+                rate_burst: config.rate_burst,
                 ramp_up_secs: config.ramp_up.as_secs(),
                 warmup_secs: config.warmup.as_secs(),
                 timeout_ms: config.timeout.as_millis() as u64,
@@ -298,6 +991,20 @@ pub fn create_output(
                 },
                 arrival_rate: config.arrival_rate,
                 max_vus: config.max_vus,
+                timeline_interval_ms: config.timeline_interval.as_millis() as u64,
+                auto_warmup: snapshot.auto_warmup.map(|w| AutoWarmupOutput {
+                    detected_ms: w.length.as_millis() as u64,
+                    settled: w.settled,
+                }),
+                network_profile: config.network.as_ref().map(|n| NetworkProfileOutput {
+                    name: n.name.clone(),
+                    down_bps: n.down_bps,
+                    up_bps: n.up_bps,
+                    rtt_ms: n.rtt.as_millis() as u64,
+                }),
+                retries: (config.retries > 0).then_some(config.retries),
+                deadline_ms: config.deadline.map(|d| d.as_millis() as u64),
+                seed: config.seed,
             },
             env: Environment {
                 hostname: hostname::get()
@@ -314,17 +1021,23 @@ pub fn create_output(
             error_rate: snapshot.error_rate,
             requests_per_sec: snapshot.requests_per_sec,
             bytes_received: snapshot.bytes_received,
+            bytes_sent: snapshot.bytes_sent,
+            rx_mbps: snapshot.rx_mbps(),
+            tx_mbps: snapshot.tx_mbps(),
             arrival_rate: if config.arrival_rate.is_some() {
                 Some(ArrivalRateSummary {
                     target_rps: config.arrival_rate.unwrap_or(0),
                     achieved_rps: snapshot.rolling_rps,
                     achieved_rps_window_secs: 1,
-                    max_vus: config.max_vus.unwrap_or(0),
+                    max_vus: config
+                        .vu_autoscale
+                        .map_or(config.max_vus.unwrap_or(0), |policy| policy.cap),
                     dropped_iterations: snapshot.dropped_iterations,
                 })
             } else {
                 None
             },
+            results_dropped: (snapshot.results_dropped > 0).then_some(snapshot.results_dropped),
         },
         latency_us: Latency {
             min: snapshot.latency_min_us,
@@ -337,6 +1050,11 @@ pub fn create_output(
             p95: snapshot.latency_p95_us,
             p99: snapshot.latency_p99_us,
             p999: snapshot.latency_p999_us,
+            percentiles: snapshot
+                .latency_percentiles
+                .iter()
+                .map(|&(p, latency_us)| (percentile_key(p), latency_us))
+                .collect(),
         },
         corrected_latency_us: if snapshot.latency_correction_enabled
             && snapshot.corrected_latency_p50_us.is_some()
@@ -352,6 +1070,7 @@ pub fn create_output(
                 p95: snapshot.corrected_latency_p95_us.unwrap_or(0),
                 p99: snapshot.corrected_latency_p99_us.unwrap_or(0),
                 p999: snapshot.corrected_latency_p999_us.unwrap_or(0),
+                percentiles: BTreeMap::new(),
             })
         } else {
             None
@@ -369,6 +1088,7 @@ pub fn create_output(
         },
         status_codes,
         errors,
+        jsonrpc_errors,
         timeline,
         thresholds: threshold_results.map(|results| ThresholdsOutput {
             passed: results.iter().all(|r| r.passed),
@@ -392,7 +1112,7 @@ pub fn create_output(
                     .collect(),
             )
         },
-        websocket: if snapshot.is_websocket {
+        websocket: if snapshot.has_websocket_stats() {
             let ws_errors: HashMap<String, u64> = snapshot
                 .ws_errors
                 .iter()
@@ -423,10 +1143,716 @@ pub fn create_output(
                     mean: snapshot.ws_connect_time_mean_us,
                     p99: snapshot.ws_connect_time_p99_us,
                 },
+                churn: config.ws_reconnect_every.map(|every| WsChurn {
+                    reconnect_every_secs: every.as_secs_f64(),
+                    reconnects: snapshot.ws_reconnects,
+                    reconnect_failures: snapshot.ws_reconnect_failures,
+                    reconnect_time_us: WsConnectTime {
+                        mean: snapshot.ws_reconnect_time_mean_us,
+                        p99: snapshot.ws_reconnect_time_p99_us,
+                    },
+                }),
+                broadcast: if config.ws_mode == WsMode::Broadcast {
+                    Some(WsBroadcast {
+                        publishers: config.ws_publishers,
+                        expected_deliveries: snapshot.ws_broadcast_expected,
+                        deliveries: snapshot.ws_broadcast_delivered,
+                        loss_rate: snapshot.ws_broadcast_loss_rate(),
+                        delivery_latency_us: WsDeliveryLatency {
+                            p50: snapshot.ws_delivery_latency_p50_us,
+                            p95: snapshot.ws_delivery_latency_p95_us,
+                            p99: snapshot.ws_delivery_latency_p99_us,
+                            max: snapshot.ws_delivery_latency_max_us,
+                        },
+                    })
+                } else {
+                    None
+                },
+                compression: if config.ws_compression {
+                    let bytes = snapshot.ws_compression.unwrap_or_default();
+                    Some(WsCompression {
+                        negotiated: snapshot.ws_compression.is_some(),
+                        raw_bytes_sent: bytes.raw_sent,
+                        wire_bytes_sent: bytes.wire_sent,
+                        raw_bytes_received: bytes.raw_received,
+                        wire_bytes_received: bytes.wire_received,
+                        ratio: bytes.ratio(),
+                    })
+                } else {
+                    None
+                },
+                close_codes: snapshot
+                    .ws_close_codes
+                    .iter()
+                    .map(|(code, stat)| {
+                        (
+                            code.to_string(),
+                            WsCloseCode {
+                                name: ws_close_code_name(*code).to_string(),
+                                count: stat.count,
+                                reason: stat.reason.clone(),
+                            },
+                        )
+                    })
+                    .collect(),
             })
         } else {
             None
         },
+        quic: snapshot.quic.map(|quic| QuicOutput {
+            connections: quic.connections,
+            rtt_mean_us: quic.rtt_mean_us,
+            rtt_p50_us: quic.rtt_p50_us,
+            rtt_p99_us: quic.rtt_p99_us,
+            cwnd_mean_bytes: quic.cwnd_mean_bytes,
+            packets_sent: quic.sent_packets,
+            packets_lost: quic.lost_packets,
+            loss_rate: quic.loss_rate(),
+            congestion_events: quic.congestion_events,
+            zero_rtt: quic.zero_rtt.map(|z| ZeroRttOutput {
+                zero_rtt_requests: z.zero_rtt_requests,
+                one_rtt_requests: z.one_rtt_requests,
+                rejected: z.rejected,
+                zero_rtt_p50_us: z.zero_rtt_p50_us,
+                zero_rtt_p99_us: z.zero_rtt_p99_us,
+                one_rtt_p50_us: z.one_rtt_p50_us,
+                one_rtt_p99_us: z.one_rtt_p99_us,
+            }),
+        }),
+        endpoints: snapshot
+            .endpoints
+            .iter()
+            .map(EndpointOutput::from)
+            .collect(),
+        hosts: snapshot.hosts.iter().map(EndpointOutput::from).collect(),
+        stages: snapshot.stages.iter().map(StageOutput::from).collect(),
+        upload: snapshot.upload.map(|upload| UploadOutput {
+            bytes_sent: upload.bytes_sent,
+            bytes_sent_per_sec: upload.bytes_sent as f64
+                / snapshot.elapsed.as_secs_f64().max(0.001),
+            timed_requests: upload.timed_requests,
+            time_us: UploadTime {
+                mean: upload.time_mean_us,
+                p50: upload.time_p50_us,
+                p95: upload.time_p95_us,
+                p99: upload.time_p99_us,
+                max: upload.time_max_us,
+            },
+            throughput_bytes_per_sec: upload.throughput_bytes_per_sec,
+        }),
+        error_samples: snapshot
+            .failure_samples
+            .iter()
+            .map(ErrorSampleOutput::from)
+            .collect(),
+        slowest_traces: snapshot
+            .slowest_traces
+            .iter()
+            .map(|t| TraceOutput {
+                trace_id: format!("{:032x}", t.trace_id),
+                latency_us: t.latency_us,
+                status: t.status,
+                started_at: DateTime::from_timestamp_millis(t.timestamp_ms as i64)
+                    .unwrap_or_default(),
+            })
+            .collect(),
+        apdex: snapshot.apdex.map(|a| ApdexOutput {
+            t_ms: a.t_us as f64 / 1000.0,
+            score: a.score(),
+            rating: a.rating().to_string(),
+            satisfied: a.satisfied,
+            tolerating: a.tolerating,
+            frustrated: a.frustrated,
+        }),
+        connections: snapshot.connections.map(|c| ConnectionsOutput {
+            opened: c.opened,
+            reused: c.reused,
+            reuse_rate: c.reuse_rate(),
+            opened_per_sec: c.opened_per_sec,
+        }),
+        connection_cap: snapshot.connection_cap.map(|c| ConnectionCapOutput {
+            limit: c.limit,
+            waited: c.waited,
+            wait_mean_ms: c.wait_mean_us as f64 / 1000.0,
+            wait_max_ms: c.wait_max_us as f64 / 1000.0,
+        }),
+        content_types: snapshot.content_types.as_ref().map(|c| ContentTypesOutput {
+            unexpected: c.unexpected(),
+            expected: c.expected.clone(),
+            types: c
+                .types
+                .iter()
+                .map(|t| ContentTypeOutput {
+                    content_type: t.content_type.clone(),
+                    responses: t.responses,
+                    unexpected: t.unexpected,
+                })
+                .collect(),
+        }),
+        recent: snapshot.recent.as_ref().map(|r| RecentOutput {
+            window_secs: r.window.as_secs_f64(),
+            requests: r.requests,
+            requests_per_sec: r.requests_per_sec,
+            latency_us: r
+                .latency_percentiles
+                .iter()
+                .map(|&(p, latency_us)| (percentile_key(p), latency_us))
+                .collect(),
+        }),
+        plugin_metrics: snapshot
+            .plugin_metrics
+            .iter()
+            .map(|(name, m)| {
+                let output = PluginMetricOutput {
+                    count: m.count,
+                    mean: m.mean(),
+                    min: m.min,
+                    max: m.max,
+                };
+                (name.clone(), output)
+            })
+            .collect(),
+        chaos: snapshot.chaos.map(|c| ChaosOutput {
+            delayed: c.delayed,
+            aborted: c.aborted,
+            reset: c.reset,
+        }),
+        throttle: snapshot.throttle.map(|t| ThrottleOutput {
+            throttled: t.throttled,
+            retry_after: t.retry_after,
+            waited_secs: t.waited.as_secs_f64(),
+        }),
+        bursts: snapshot.bursts.map(|b| BurstsOutput {
+            bursts: b.bursts,
+            min_size: b.min_size,
+            max_size: b.max_size,
+            completion_mean_ms: b.completion_mean.as_secs_f64() * 1000.0,
+            completion_max_ms: b.completion_max.as_secs_f64() * 1000.0,
+            max_concurrent: b.max_concurrent,
+        }),
+        workers: snapshot.workers.as_ref().map(|w| WorkersOutput {
+            workers: w.workers,
+            requests_min: w.requests_min,
+            requests_max: w.requests_max,
+            unhealthy: w
+                .unhealthy
+                .iter()
+                .map(|u| UnhealthyWorkerOutput {
+                    worker: u.id,
+                    requests: u.requests,
+                    failed: u.failed,
+                    streak: u.streak,
+                    longest_streak: u.longest_streak,
+                    last_error: u.last_error.clone(),
+                })
+                .collect(),
+        }),
+        request_ids: snapshot.request_ids.map(|ids| RequestIdsOutput {
+            responses: ids.responses,
+            matched: ids.matched,
+            mismatched: ids.mismatched,
+            missing: ids.missing,
+        }),
+        duplicates: snapshot.duplicates.map(|d| DuplicatesOutput {
+            pairs: d.pairs,
+            consistent: d.consistent,
+            status_mismatch: d.status_mismatch,
+            body_mismatch: d.body_mismatch,
+            incomplete: d.incomplete,
+        }),
+        revalidation: snapshot.revalidation.map(|r| RevalidationOutput {
+            conditional: r.conditional,
+            not_modified: r.not_modified,
+            not_modified_ratio: r.not_modified_ratio(),
+            full_fetches: r.full_fetches,
+            not_modified_mean_us: r.not_modified_mean_us,
+            full_fetch_mean_us: r.full_fetch_mean_us,
+        }),
+        outages: snapshot
+            .outages
+            .iter()
+            .map(|o| OutageOutput {
+                start_secs: o.start.as_secs_f64(),
+                duration_secs: o.duration.as_secs_f64(),
+                health_checks: o.health_checks,
+                recovered: o.recovered,
+            })
+            .collect(),
+        failover: snapshot.failover.as_ref().map(|f| FailoverOutput {
+            interval_secs: f.interval.as_secs_f64(),
+            availability: f.availability.clone(),
+            uptime_ratio: f.uptime_ratio(),
+            downtime_secs: f.downtime().as_secs_f64(),
+            longest_burst_secs: f.longest_burst().as_secs_f64(),
+            incidents: f
+                .incidents
+                .iter()
+                .map(|i| FailoverIncidentOutput {
+                    start_secs: i.start.as_secs_f64(),
+                    error_burst_secs: i.error_burst.as_secs_f64(),
+                    downtime_secs: i.downtime.as_secs_f64(),
+                    time_to_recovery_secs: i.time_to_recovery.map(|d| d.as_secs_f64()),
+                })
+                .collect(),
+        }),
+        extractions: snapshot
+            .extractions
+            .iter()
+            .map(|e| ExtractionOutput {
+                scenario: e.scenario.clone(),
+                name: e.name.clone(),
+                attempts: e.attempts,
+                failed: e.failed,
+            })
+            .collect(),
+        scenario_mix: snapshot
+            .scenario_mix
+            .iter()
+            .map(|share| ScenarioMixOutput {
+                scenario: share.scenario.clone(),
+                weight: share.weight,
+                requests: share.requests,
+                expected_pct: share.expected * 100.0,
+                actual_pct: share.actual * 100.0,
+                drift_pct: share.drift_pct(),
+            })
+            .collect(),
+        vu_scaling: snapshot.vu_scaling.as_ref().map(|v| VuScalingOutput {
+            allocated: v.allocated,
+            peak: v.peak,
+            cap: v.cap,
+            scale_ups: v.scale_ups(),
+            scale_downs: v.scale_downs(),
+            events: v
+                .events
+                .iter()
+                .map(|e| VuScaleEventOutput {
+                    elapsed_ms: e.elapsed_ms,
+                    from: e.from,
+                    to: e.to,
+                })
+                .collect(),
+        }),
+        latency_histogram: snapshot
+            .latency_histogram
+            .as_deref()
+            .and_then(encode_histogram),
+    }
+}
+
+fn encode_histogram(histogram: &Histogram<u64>) -> Option<String> {
+    let mut bytes = Vec::new();
+    V2DeflateSerializer::new()
+        .serialize(histogram, &mut bytes)
+        .ok()?;
+    Some(BASE64.encode(bytes))
+}
+
+fn decode_histogram(encoded: &str) -> Option<Histogram<u64>> {
+    let bytes = BASE64.decode(encoded).ok()?;
+    Deserializer::new().deserialize(&mut bytes.as_slice()).ok()
+}
+
+impl JsonOutput {
+    /// Rebuild the final snapshot a saved result was created from, so other
+    /// formats can be rendered after the fact. Per-request data that never
+    /// made it into the JSON (histograms, raw samples) stays empty.
+    pub fn to_snapshot(&self) -> StatsSnapshot {
+        let mut snapshot = StatsSnapshot {
+            elapsed: (self.metadata.ended_at - self.metadata.started_at)
+                .to_std()
+                .unwrap_or(Duration::from_secs(self.metadata.duration_secs)),
+            started_at_ms: self.metadata.started_at.timestamp_millis().max(0) as u64,
+            total_requests: self.summary.total_requests,
+            successful: self.summary.successful,
+            failed: self.summary.failed,
+            bytes_received: self.summary.bytes_received,
+            bytes_sent: self.summary.bytes_sent,
+            rolling_rps: self
+                .summary
+                .arrival_rate
+                .as_ref()
+                .map_or(self.summary.requests_per_sec, |a| a.achieved_rps),
+            requests_per_sec: self.summary.requests_per_sec,
+            error_rate: self.summary.error_rate,
+            latency_min_us: self.latency_us.min,
+            latency_max_us: self.latency_us.max,
+            latency_mean_us: self.latency_us.mean,
+            latency_stddev_us: self.latency_us.stddev.unwrap_or_default(),
+            latency_p50_us: self.latency_us.p50,
+            latency_p75_us: self.latency_us.p75,
+            latency_p90_us: self.latency_us.p90,
+            latency_p95_us: self.latency_us.p95,
+            latency_p99_us: self.latency_us.p99,
+            latency_p999_us: self.latency_us.p999,
+            latency_percentiles: self
+                .latency_us
+                .percentiles
+                .iter()
+                .filter_map(|(key, &latency_us)| Some((parse_percentile_key(key)?, latency_us)))
+                .collect(),
+            latency_histogram: self
+                .latency_histogram
+                .as_deref()
+                .and_then(decode_histogram)
+                .map(Arc::new),
+            status_codes: self
+                .status_codes
+                .iter()
+                .filter_map(|(code, count)| Some((code.parse().ok()?, *count)))
+                .collect(),
+            errors: self
+                .errors
+                .iter()
+                .map(|(kind, count)| (ErrorKind::parse(kind).unwrap_or(ErrorKind::Other), *count))
+                .collect(),
+            jsonrpc_errors: self
+                .jsonrpc_errors
+                .iter()
+                .filter_map(|(code, count)| Some((code.parse().ok()?, *count)))
+                .collect(),
+            timeline: self
+                .timeline
+                .iter()
+                .map(|e| TimelineBucket {
+                    elapsed_secs: e.elapsed_secs,
+                    requests: e.requests,
+                    errors: e.errors,
+                    elapsed_ms: e.elapsed_ms,
+                    bytes_received: e.bytes_received,
+                    bytes_sent: e.bytes_sent,
+                    latency_p50_us: e.latency_us.p50,
+                    latency_p95_us: e.latency_us.p95,
+                    latency_p99_us: e.latency_us.p99,
+                    checks: e
+                        .checks
+                        .iter()
+                        .map(|(name, c)| (name.clone(), (c.passed, c.total)))
+                        .collect(),
+                })
+                .collect(),
+            overall_check_pass_rate: self.checks.as_ref().map(|c| c.overall_pass_rate),
+            dropped_iterations: self
+                .summary
+                .arrival_rate
+                .as_ref()
+                .map_or(0, |a| a.dropped_iterations),
+            results_dropped: self.summary.results_dropped.unwrap_or(0),
+            is_websocket: self.metadata.target.url.starts_with("ws://")
+                || self.metadata.target.url.starts_with("wss://"),
+            endpoints: self.endpoints.iter().map(EndpointStats::from).collect(),
+            hosts: self.hosts.iter().map(EndpointStats::from).collect(),
+            stages: self.stages.iter().map(StageStats::from).collect(),
+            upload: self.upload.as_ref().map(|upload| UploadStats {
+                bytes_sent: upload.bytes_sent,
+                timed_requests: upload.timed_requests,
+                time_mean_us: upload.time_us.mean,
+                time_p50_us: upload.time_us.p50,
+                time_p95_us: upload.time_us.p95,
+                time_p99_us: upload.time_us.p99,
+                time_max_us: upload.time_us.max,
+                throughput_bytes_per_sec: upload.throughput_bytes_per_sec,
+            }),
+            failure_samples: self
+                .error_samples
+                .iter()
+                .map(|e| FailureSample {
+                    kind: e.kind.clone(),
+                    request: e.request.clone(),
+                    status: e.status,
+                    message: e.error.clone(),
+                    body: e.body.clone(),
+                    latency_us: e.latency_us,
+                    timestamp_ms: e.timestamp.timestamp_millis() as u64,
+                })
+                .collect(),
+            slowest_traces: self
+                .slowest_traces
+                .iter()
+                .filter_map(|t| {
+                    Some(TraceSample {
+                        latency_us: t.latency_us,
+                        trace_id: u128::from_str_radix(&t.trace_id, 16).ok()?,
+                        status: t.status,
+                        timestamp_ms: t.started_at.timestamp_millis() as u64,
+                    })
+                })
+                .collect(),
+            apdex: self.apdex.as_ref().map(|a| ApdexStats {
+                t_us: (a.t_ms * 1000.0) as u64,
+                satisfied: a.satisfied,
+                tolerating: a.tolerating,
+                frustrated: a.frustrated,
+            }),
+            connections: self.connections.as_ref().map(|c| ConnectionStats {
+                opened: c.opened,
+                reused: c.reused,
+                opened_per_sec: c.opened_per_sec,
+                rolling_opened_per_sec: 0.0,
+            }),
+            connection_cap: self.connection_cap.as_ref().map(|c| ConnectionCapStats {
+                limit: c.limit,
+                waited: c.waited,
+                wait_mean_us: (c.wait_mean_ms * 1000.0) as u64,
+                wait_max_us: (c.wait_max_ms * 1000.0) as u64,
+            }),
+            content_types: self.content_types.as_ref().map(|c| ContentTypeStats {
+                types: c
+                    .types
+                    .iter()
+                    .map(|t| ContentTypeCount {
+                        content_type: t.content_type.clone(),
+                        responses: t.responses,
+                        unexpected: t.unexpected,
+                    })
+                    .collect(),
+                expected: c.expected.clone(),
+            }),
+            recent: self.recent.as_ref().map(|r| RecentStats {
+                window: Duration::from_secs_f64(r.window_secs),
+                requests: r.requests,
+                requests_per_sec: r.requests_per_sec,
+                latency_percentiles: r
+                    .latency_us
+                    .iter()
+                    .filter_map(|(key, &latency_us)| Some((parse_percentile_key(key)?, latency_us)))
+                    .collect(),
+            }),
+            plugin_metrics: self
+                .plugin_metrics
+                .iter()
+                .map(|(name, m)| {
+                    let metric = PluginMetric {
+                        count: m.count,
+                        sum: m.mean * m.count as f64,
+                        min: m.min,
+                        max: m.max,
+                    };
+                    (name.clone(), metric)
+                })
+                .collect(),
+            chaos: self.chaos.as_ref().map(|c| ChaosStats {
+                delayed: c.delayed,
+                aborted: c.aborted,
+                reset: c.reset,
+            }),
+            throttle: self.throttle.as_ref().map(|t| ThrottleStats {
+                throttled: t.throttled,
+                retry_after: t.retry_after,
+                waited: Duration::from_secs_f64(t.waited_secs),
+            }),
+            bursts: self.bursts.as_ref().map(|b| BurstStats {
+                bursts: b.bursts,
+                min_size: b.min_size,
+                max_size: b.max_size,
+                completion_mean: Duration::from_secs_f64(b.completion_mean_ms / 1000.0),
+                completion_max: Duration::from_secs_f64(b.completion_max_ms / 1000.0),
+                max_concurrent: b.max_concurrent,
+            }),
+            workers: self.workers.as_ref().map(|w| WorkerHealthStats {
+                workers: w.workers,
+                requests_min: w.requests_min,
+                requests_max: w.requests_max,
+                unhealthy: w
+                    .unhealthy
+                    .iter()
+                    .map(|u| UnhealthyWorker {
+                        id: u.worker,
+                        requests: u.requests,
+                        failed: u.failed,
+                        streak: u.streak,
+                        longest_streak: u.longest_streak,
+                        last_error: u.last_error.clone(),
+                    })
+                    .collect(),
+            }),
+            request_ids: self.request_ids.as_ref().map(|ids| RequestIdStats {
+                responses: ids.responses,
+                matched: ids.matched,
+                mismatched: ids.mismatched,
+                missing: ids.missing,
+            }),
+            duplicates: self.duplicates.as_ref().map(|d| DuplicateStats {
+                pairs: d.pairs,
+                consistent: d.consistent,
+                status_mismatch: d.status_mismatch,
+                body_mismatch: d.body_mismatch,
+                incomplete: d.incomplete,
+            }),
+            revalidation: self.revalidation.as_ref().map(|r| RevalidationStats {
+                conditional: r.conditional,
+                not_modified: r.not_modified,
+                full_fetches: r.full_fetches,
+                not_modified_mean_us: r.not_modified_mean_us,
+                full_fetch_mean_us: r.full_fetch_mean_us,
+            }),
+            outages: self
+                .outages
+                .iter()
+                .map(|o| OutageWindow {
+                    start: Duration::from_secs_f64(o.start_secs),
+                    duration: Duration::from_secs_f64(o.duration_secs),
+                    health_checks: o.health_checks,
+                    recovered: o.recovered,
+                })
+                .collect(),
+            failover: self.failover.as_ref().map(|f| FailoverStats {
+                interval: Duration::from_secs_f64(f.interval_secs),
+                availability: f.availability.clone(),
+                incidents: f
+                    .incidents
+                    .iter()
+                    .map(|i| FailoverIncident {
+                        start: Duration::from_secs_f64(i.start_secs),
+                        error_burst: Duration::from_secs_f64(i.error_burst_secs),
+                        downtime: Duration::from_secs_f64(i.downtime_secs),
+                        time_to_recovery: i.time_to_recovery_secs.map(Duration::from_secs_f64),
+                    })
+                    .collect(),
+            }),
+            extractions: self
+                .extractions
+                .iter()
+                .map(|e| ExtractionStats {
+                    scenario: e.scenario.clone(),
+                    name: e.name.clone(),
+                    attempts: e.attempts,
+                    failed: e.failed,
+                })
+                .collect(),
+            scenario_mix: self
+                .scenario_mix
+                .iter()
+                .map(|m| ScenarioShare {
+                    scenario: m.scenario.clone(),
+                    weight: m.weight,
+                    requests: m.requests,
+                    expected: m.expected_pct / 100.0,
+                    actual: m.actual_pct / 100.0,
+                })
+                .collect(),
+            vu_scaling: self.vu_scaling.as_ref().map(|v| VuScalingStats {
+                allocated: v.allocated,
+                peak: v.peak,
+                cap: v.cap,
+                events: v
+                    .events
+                    .iter()
+                    .map(|e| VuScaleEvent {
+                        elapsed_ms: e.elapsed_ms,
+                        from: e.from,
+                        to: e.to,
+                    })
+                    .collect(),
+            }),
+            auto_warmup: self.metadata.load.auto_warmup.as_ref().map(|w| AutoWarmup {
+                length: Duration::from_millis(w.detected_ms),
+                settled: w.settled,
+            }),
+            ..Default::default()
+        };
+
+        if let Some(ref corrected) = self.corrected_latency_us {
+            snapshot.latency_correction_enabled = true;
+            snapshot.corrected_latency_min_us = Some(corrected.min);
+            snapshot.corrected_latency_max_us = Some(corrected.max);
+            snapshot.corrected_latency_mean_us = Some(corrected.mean);
+            snapshot.corrected_latency_p50_us = Some(corrected.p50);
+            snapshot.corrected_latency_p75_us = Some(corrected.p75);
+            snapshot.corrected_latency_p90_us = Some(corrected.p90);
+            snapshot.corrected_latency_p95_us = Some(corrected.p95);
+            snapshot.corrected_latency_p99_us = Some(corrected.p99);
+            snapshot.corrected_latency_p999_us = Some(corrected.p999);
+        }
+        if let Some(ref queue) = self.queue_time_us {
+            snapshot.queue_time_mean_us = Some(queue.mean);
+            snapshot.queue_time_p99_us = Some(queue.p99);
+            snapshot.total_queue_time_us = queue.total;
+        }
+
+        if let Some(ref ws) = self.websocket {
+            snapshot.ws_messages_sent = ws.messages_sent;
+            snapshot.ws_messages_received = ws.messages_received;
+            snapshot.ws_bytes_sent = ws.bytes_sent;
+            snapshot.ws_bytes_received = ws.bytes_received;
+            snapshot.ws_messages_per_sec = ws.messages_per_sec;
+            snapshot.ws_connections_active = ws.connections_active;
+            snapshot.ws_connections_established = ws.connections_established;
+            snapshot.ws_connection_errors = ws.connection_errors;
+            snapshot.ws_disconnects = ws.disconnects;
+            snapshot.ws_error_rate = ws.error_rate;
+            snapshot.ws_latency_min_us = ws.latency_us.min;
+            snapshot.ws_latency_max_us = ws.latency_us.max;
+            snapshot.ws_latency_mean_us = ws.latency_us.mean;
+            snapshot.ws_latency_stddev_us = ws.latency_us.stddev;
+            snapshot.ws_latency_p50_us = ws.latency_us.p50;
+            snapshot.ws_latency_p95_us = ws.latency_us.p95;
+            snapshot.ws_latency_p99_us = ws.latency_us.p99;
+            snapshot.ws_connect_time_mean_us = ws.connect_time_us.mean;
+            snapshot.ws_connect_time_p99_us = ws.connect_time_us.p99;
+        }
+
+        snapshot
+    }
+
+    /// The parts of the original config that the renderers show
+    pub fn to_config(&self) -> LoadConfig {
+        let load = &self.metadata.load;
+        LoadConfig {
+            url: self.metadata.target.url.clone(),
+            method: self
+                .metadata
+                .target
+                .method
+                .parse()
+                .unwrap_or(reqwest::Method::GET),
+            // Sensitive values were redacted when the result was saved
+            headers: self
+                .metadata
+                .target
+                .headers
+                .iter()
+                .filter_map(|h| h.split_once(':'))
+                .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+                .collect(),
+            concurrency: load.concurrency,
+            rate: load.rate,
+            rate_burst: load.rate_burst,
+            ramp_up: Duration::from_secs(load.ramp_up_secs),
+            warmup: Duration::from_secs(load.warmup_secs),
+            auto_warmup: load.auto_warmup.is_some(),
+            timeout: Duration::from_millis(load.timeout_ms),
+            duration: Duration::from_secs(self.metadata.duration_secs),
+            arrival_rate: load.arrival_rate,
+            max_vus: load.max_vus,
+            network: load.network_profile.as_ref().map(|n| NetworkProfile {
+                name: n.name.clone(),
+                down_bps: n.down_bps,
+                up_bps: n.up_bps,
+                rtt: Duration::from_millis(n.rtt_ms),
+            }),
+            retries: load.retries.unwrap_or(0),
+            deadline: load.deadline_ms.map(Duration::from_millis),
+            seed: load.seed,
+            timeline_interval: Duration::from_millis(load.timeline_interval_ms),
+            ..Default::default()
+        }
+    }
+
+    /// Threshold results stored with the run (empty if none were configured)
+    pub fn threshold_results(&self) -> &[ThresholdResult] {
+        self.thresholds
+            .as_ref()
+            .map_or(&[], |t| t.results.as_slice())
+    }
+
+    /// Check counts as (passed, total), the form the engine reports them in
+    pub fn check_stats(&self) -> HashMap<String, (u64, u64)> {
+        self.checks
+            .iter()
+            .flat_map(|c| c.results.iter())
+            .map(|(name, r)| (name.clone(), (r.passed, r.total)))
+            .collect()
     }
 }
 
@@ -444,6 +1870,16 @@ pub fn write_json(
     Ok(())
 }
 
+/// Write the failure samples as JSON Lines, one sample per line
+pub fn write_error_samples(samples: &[FailureSample], path: &str) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for sample in samples {
+        serde_json::to_writer(&mut writer, &ErrorSampleOutput::from(sample))?;
+        writeln!(writer)?;
+    }
+    writer.flush()
+}
+
 pub fn print_json(
     snapshot: &StatsSnapshot,
     config: &LoadConfig,
@@ -33,6 +33,21 @@ fn write_markdown_content<W: Write>(
     writeln!(writer, "| Duration | {}s |", snapshot.elapsed.as_secs())?;
     if config.rate > 0 {
         writeln!(writer, "| Rate Limit | {} req/s |", config.rate)?;
+        if let Some(burst) = config.rate_burst {
+            writeln!(writer, "| Rate Burst | {burst} requests |")?;
+        }
+    }
+    if let Some(warmup) = snapshot.auto_warmup {
+        writeln!(
+            writer,
+            "| Warmup (auto) | {:.1}s{} |",
+            warmup.length.as_secs_f64(),
+            if warmup.settled {
+                ""
+            } else {
+                " (cap, never settled)"
+            }
+        )?;
     }
     writeln!(writer)?;
 
@@ -54,6 +69,114 @@ fn write_markdown_content<W: Write>(
         "| Error Rate | {:.2}% |",
         snapshot.error_rate * 100.0
     )?;
+    writeln!(
+        writer,
+        "| Throughput (Mbps) | {:.2} in / {:.2} out |",
+        snapshot.rx_mbps(),
+        snapshot.tx_mbps()
+    )?;
+    if let Some(conns) = snapshot.connections {
+        writeln!(
+            writer,
+            "| New Connections | {} ({:.2}/s, {:.1}% of requests reused one) |",
+            conns.opened,
+            conns.opened_per_sec,
+            conns.reuse_rate() * 100.0
+        )?;
+    }
+    if let Some(cap) = snapshot.connection_cap {
+        writeln!(
+            writer,
+            "| Connection Cap | {} shared; {} requests waited for one ({:.2}ms mean, {:.2}ms max) |",
+            cap.limit,
+            cap.waited,
+            cap.wait_mean_us as f64 / 1000.0,
+            cap.wait_max_us as f64 / 1000.0
+        )?;
+    }
+    if let Some(apdex) = snapshot.apdex {
+        writeln!(
+            writer,
+            "| Apdex (T={}ms) | {:.2} ({}) |",
+            apdex.t_us / 1000,
+            apdex.score(),
+            apdex.rating()
+        )?;
+    }
+    if let Some(duplicates) = snapshot.duplicates {
+        writeln!(
+            writer,
+            "| Duplicates | {} pairs, {} consistent, {} status differed, {} body differed |",
+            duplicates.pairs,
+            duplicates.consistent,
+            duplicates.status_mismatch,
+            duplicates.body_mismatch
+        )?;
+    }
+    if let Some(ref scaling) = snapshot.vu_scaling {
+        writeln!(
+            writer,
+            "| VU Autoscaling | {} up, {} down, peak {} of {} |",
+            scaling.scale_ups(),
+            scaling.scale_downs(),
+            scaling.peak,
+            scaling.cap
+        )?;
+    }
+    if let Some(revalidation) = snapshot.revalidation {
+        writeln!(
+            writer,
+            "| Revalidation | {:.1}% of {} conditional requests got a 304 ({:.2}ms vs {:.2}ms full fetch) |",
+            revalidation.not_modified_ratio() * 100.0,
+            revalidation.conditional,
+            revalidation.not_modified_mean_us as f64 / 1000.0,
+            revalidation.full_fetch_mean_us as f64 / 1000.0
+        )?;
+    }
+    for outage in &snapshot.outages {
+        writeln!(
+            writer,
+            "| Outage | {:.1}s at {:.1}s, {} ({} health checks) |",
+            outage.duration.as_secs_f64(),
+            outage.start.as_secs_f64(),
+            if outage.recovered {
+                "recovered"
+            } else {
+                "not recovered"
+            },
+            outage.health_checks
+        )?;
+    }
+    if let Some(ref failover) = snapshot.failover {
+        writeln!(
+            writer,
+            "| Failover | {:.1}% uptime, {} incidents, {:.1}s down, longest error burst {:.1}s |",
+            failover.uptime_ratio() * 100.0,
+            failover.incidents.len(),
+            failover.downtime().as_secs_f64(),
+            failover.longest_burst().as_secs_f64()
+        )?;
+        for incident in &failover.incidents {
+            writeln!(
+                writer,
+                "| Incident | {:.1}s of errors at {:.1}s, {:.1}s down, {} |",
+                incident.error_burst.as_secs_f64(),
+                incident.start.as_secs_f64(),
+                incident.downtime.as_secs_f64(),
+                match incident.time_to_recovery {
+                    Some(ttr) => format!("recovered in {:.1}s", ttr.as_secs_f64()),
+                    None => "not recovered".to_string(),
+                }
+            )?;
+        }
+    }
+    if let Some(ids) = snapshot.request_ids {
+        writeln!(
+            writer,
+            "| Request IDs | {} echoed, {} missing, {} mismatched |",
+            ids.matched, ids.missing, ids.mismatched
+        )?;
+    }
     writeln!(writer)?;
 
     // Latency
@@ -66,31 +189,9 @@ fn write_markdown_content<W: Write>(
         "| Min | {:.2} |",
         snapshot.latency_min_us as f64 / 1000.0
     )?;
-    writeln!(
-        writer,
-        "| p50 | {:.2} |",
-        snapshot.latency_p50_us as f64 / 1000.0
-    )?;
-    writeln!(
-        writer,
-        "| p90 | {:.2} |",
-        snapshot.latency_p90_us as f64 / 1000.0
-    )?;
-    writeln!(
-        writer,
-        "| p95 | {:.2} |",
-        snapshot.latency_p95_us as f64 / 1000.0
-    )?;
-    writeln!(
-        writer,
-        "| p99 | {:.2} |",
-        snapshot.latency_p99_us as f64 / 1000.0
-    )?;
-    writeln!(
-        writer,
-        "| p99.9 | {:.2} |",
-        snapshot.latency_p999_us as f64 / 1000.0
-    )?;
+    for &(p, latency_us) in &snapshot.latency_percentiles {
+        writeln!(writer, "| p{} | {:.2} |", p, latency_us as f64 / 1000.0)?;
+    }
     writeln!(
         writer,
         "| Max | {:.2} |",
@@ -112,6 +213,147 @@ fn write_markdown_content<W: Write>(
         writeln!(writer)?;
     }
 
+    // Content Types
+    if let Some(ref content_types) = snapshot.content_types {
+        writeln!(writer, "## Content Types")?;
+        writeln!(writer)?;
+        if content_types.expected.is_empty() {
+            writeln!(writer, "| Content-Type | Responses |")?;
+            writeln!(writer, "|--------------|-----------|")?;
+            for t in &content_types.types {
+                writeln!(writer, "| `{}` | {} |", t.content_type, t.responses)?;
+            }
+        } else {
+            writeln!(writer, "| Content-Type | Responses | Unexpected |")?;
+            writeln!(writer, "|--------------|-----------|------------|")?;
+            for t in &content_types.types {
+                writeln!(
+                    writer,
+                    "| `{}` | {} | {} |",
+                    t.content_type, t.responses, t.unexpected
+                )?;
+            }
+        }
+        writeln!(writer)?;
+    }
+
+    // Endpoints
+    if !snapshot.endpoints.is_empty() {
+        writeln!(writer, "## Endpoints")?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "| Endpoint | Requests | Failed | p50 (ms) | p99 (ms) |"
+        )?;
+        writeln!(
+            writer,
+            "|----------|----------|--------|----------|----------|"
+        )?;
+        for endpoint in &snapshot.endpoints {
+            writeln!(
+                writer,
+                "| `{}` | {} | {} | {:.2} | {:.2} |",
+                endpoint.name,
+                endpoint.requests,
+                endpoint.failed,
+                endpoint.latency_p50_us as f64 / 1000.0,
+                endpoint.latency_p99_us as f64 / 1000.0
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    // Stages
+    if !snapshot.stages.is_empty() {
+        writeln!(writer, "## Stages")?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "| Stage | Target | Requests | RPS | Failed | p50 (ms) | p99 (ms) |"
+        )?;
+        writeln!(
+            writer,
+            "|-------|--------|----------|-----|--------|----------|----------|"
+        )?;
+        for stage in &snapshot.stages {
+            writeln!(
+                writer,
+                "| {} | {} | {} | {:.1} | {} | {:.2} | {:.2} |",
+                stage.index + 1,
+                stage.target,
+                stage.requests,
+                stage.rps(),
+                stage.failed,
+                stage.latency_p50_us as f64 / 1000.0,
+                stage.latency_p99_us as f64 / 1000.0
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    // Hosts
+    if !snapshot.hosts.is_empty() {
+        writeln!(writer, "## Hosts")?;
+        writeln!(writer)?;
+        writeln!(writer, "| Host | Requests | Failed | p50 (ms) | p99 (ms) |")?;
+        writeln!(writer, "|------|----------|--------|----------|----------|")?;
+        for host in &snapshot.hosts {
+            writeln!(
+                writer,
+                "| `{}` | {} | {} | {:.2} | {:.2} |",
+                host.name,
+                host.requests,
+                host.failed,
+                host.latency_p50_us as f64 / 1000.0,
+                host.latency_p99_us as f64 / 1000.0
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    // Extractions
+    if !snapshot.extractions.is_empty() {
+        writeln!(writer, "## Extractions")?;
+        writeln!(writer)?;
+        writeln!(writer, "| Scenario | Extraction | Attempts | Failed |")?;
+        writeln!(writer, "|----------|------------|----------|--------|")?;
+        for extraction in &snapshot.extractions {
+            writeln!(
+                writer,
+                "| {} | `{}` | {} | {} |",
+                extraction.scenario, extraction.name, extraction.attempts, extraction.failed
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    // Scenario mix
+    if !snapshot.scenario_mix.is_empty() {
+        writeln!(writer, "## Scenario Mix")?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "| Scenario | Weight | Requests | Expected | Actual | Drift |"
+        )?;
+        writeln!(
+            writer,
+            "|----------|--------|----------|----------|--------|-------|"
+        )?;
+        for share in &snapshot.scenario_mix {
+            writeln!(
+                writer,
+                "| {} | {} | {} | {:.1}% | {:.1}% | {:+.1}% |",
+                share.scenario,
+                share.weight,
+                share.requests,
+                share.expected * 100.0,
+                share.actual * 100.0,
+                share.drift_pct()
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
     // Errors
     if !snapshot.errors.is_empty() {
         writeln!(writer, "## Errors")?;
@@ -124,5 +366,34 @@ fn write_markdown_content<W: Write>(
         writeln!(writer)?;
     }
 
+    // Unhealthy workers
+    if let Some(ref workers) = snapshot.workers
+        && !workers.unhealthy.is_empty()
+    {
+        writeln!(writer, "## Unhealthy Workers")?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "| Worker | Requests | Failed | Longest Streak | Current Streak | Last Error |"
+        )?;
+        writeln!(
+            writer,
+            "|--------|----------|--------|----------------|----------------|------------|"
+        )?;
+        for worker in &workers.unhealthy {
+            writeln!(
+                writer,
+                "| {} | {} | {} | {} | {} | {} |",
+                worker.id,
+                worker.requests,
+                worker.failed,
+                worker.longest_streak,
+                worker.streak,
+                worker.last_error
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
     writer.flush()
 }
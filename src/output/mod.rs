@@ -2,8 +2,15 @@ mod csv;
 mod html;
 pub mod json;
 mod markdown;
+mod progress;
+pub mod schema;
+mod template;
 
 pub use csv::{print_csv, write_csv};
 pub use html::{print_html, write_html};
-pub use json::{print_json, write_json};
+pub use json::{print_json, write_error_samples, write_json};
 pub use markdown::{print_markdown, write_markdown};
+pub use progress::{
+    Progress, Projection, clock, finish_progress, planned_duration, report_progress,
+};
+pub use template::{load_template, print_template, write_template};
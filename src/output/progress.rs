@@ -0,0 +1,242 @@
+use crate::cli::ProgressStyle;
+use crate::types::{LoadConfig, RunPhase, StatsSnapshot};
+use std::io::Write;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Cells in the `--progress bar` bar
+const BAR_WIDTH: usize = 20;
+
+/// How headless progress is shown
+#[derive(Debug, Clone, Copy)]
+pub enum Progress {
+    /// A summary line per tick, for logs
+    Lines,
+    /// One line redrawn in place with `\r`, with an ETA from the planned
+    /// `duration` and `max_requests` (0 = none)
+    Bar {
+        duration: Duration,
+        max_requests: u64,
+    },
+}
+
+impl Progress {
+    /// `--progress` for a run of `config`
+    pub fn new(style: ProgressStyle, config: &LoadConfig) -> Self {
+        match style {
+            ProgressStyle::Lines => Self::Lines,
+            ProgressStyle::Bar => Self::Bar {
+                duration: planned_duration(config),
+                max_requests: config.max_requests,
+            },
+        }
+    }
+
+    /// How often to report without `--summary-interval`
+    pub fn default_interval(self) -> Duration {
+        match self {
+            Self::Lines => Duration::from_secs(10),
+            Self::Bar { .. } => Duration::from_secs(1),
+        }
+    }
+}
+
+/// How long a run of `config` is set to measure: its duration, or its stages'
+pub fn planned_duration(config: &LoadConfig) -> Duration {
+    if config.stages.is_empty() {
+        config.duration
+    } else {
+        config.stages.iter().map(|s| s.duration).sum()
+    }
+}
+
+/// Where a run is headed at its current rates
+#[derive(Debug, Clone, Copy)]
+pub struct Projection {
+    /// 0 to 1
+    pub done: f64,
+    pub eta: Duration,
+    /// Requests (messages for WebSocket runs) by the end
+    pub total: u64,
+    /// Bytes received and sent by the end
+    pub bytes: u64,
+}
+
+impl Projection {
+    /// Project `snapshot` to the end of a run of `duration`, or of
+    /// `max_requests` (0 = none) when the cap comes first
+    pub fn new(snapshot: &StatsSnapshot, duration: Duration, max_requests: u64) -> Self {
+        let (count, _, rate, _, _) = tally(snapshot);
+        let elapsed = snapshot.elapsed;
+        let mut done = elapsed.as_secs_f64() / duration.as_secs_f64().max(0.001);
+        let mut eta = duration.saturating_sub(elapsed);
+        // A request cap can end the run first
+        if max_requests > 0 {
+            done = done.max(snapshot.total_requests as f64 / max_requests as f64);
+            let left = max_requests.saturating_sub(snapshot.total_requests);
+            if rate > 0.0 {
+                eta = eta.min(Duration::from_secs_f64(left as f64 / rate));
+            }
+        }
+        let left = eta.as_secs_f64();
+        let mut total = count + (rate * left) as u64;
+        if max_requests > 0 && !snapshot.is_websocket {
+            total = total.min(max_requests.max(count));
+        }
+        let bytes_per_sec = (snapshot.rolling_rx_bps + snapshot.rolling_tx_bps).max(0.0) / 8.0;
+        Self {
+            done: done.clamp(0.0, 1.0),
+            eta,
+            total,
+            bytes: snapshot.bytes_received + snapshot.bytes_sent + (bytes_per_sec * left) as u64,
+        }
+    }
+}
+
+/// `--summary-interval` / `--progress`: report progress to stderr every
+/// `every`, for CI logs and other runs without the TUI. Runs until aborted;
+/// a bar's line is left open, so end it with [`finish_progress`].
+pub async fn report_progress(
+    every: Duration,
+    progress: Progress,
+    snapshot_rx: watch::Receiver<StatsSnapshot>,
+    phase_rx: watch::Receiver<RunPhase>,
+) {
+    let mut ticks = tokio::time::interval(every);
+    ticks.tick().await;
+    let mut drawn = 0;
+    loop {
+        ticks.tick().await;
+        // Warmup results aren't in the snapshots, so there is nothing to show yet
+        let warmup = *phase_rx.borrow() == RunPhase::Warmup;
+        match progress {
+            Progress::Lines if warmup => eprintln!("[warmup] waiting for warmup to finish"),
+            Progress::Lines => eprintln!("{}", progress_line(&snapshot_rx.borrow())),
+            Progress::Bar {
+                duration,
+                max_requests,
+            } => {
+                let line = if warmup {
+                    "warming up...".to_string()
+                } else {
+                    bar_line(&snapshot_rx.borrow(), duration, max_requests)
+                };
+                // Pad over the end of a longer previous line
+                let mut stderr = std::io::stderr().lock();
+                let _ = write!(stderr, "\r{:<drawn$}", line);
+                let _ = stderr.flush();
+                drawn = line.len();
+            }
+        }
+    }
+}
+
+/// End the line a bar was drawn on
+pub fn finish_progress(progress: Progress) {
+    if let Progress::Bar { .. } = progress {
+        eprintln!();
+    }
+}
+
+/// `[   10s] 1234 requests | 123.4/s | p99 45.21ms | errors 3 (0.24%)`
+fn progress_line(snapshot: &StatsSnapshot) -> String {
+    let (count, unit, rate, p99_us, errors) = tally(snapshot);
+    let error_rate = if count > 0 {
+        errors as f64 / count as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    format!(
+        "[{:>5.0}s] {} {} | {:.1}/s | p99 {:.2}ms | errors {} ({:.2}%)",
+        snapshot.elapsed.as_secs_f64(),
+        count,
+        unit,
+        rate,
+        p99_us as f64 / 1000.0,
+        errors,
+        error_rate
+    )
+}
+
+/// Count, its unit, rate, p99 latency and errors, for requests or messages
+fn tally(snapshot: &StatsSnapshot) -> (u64, &'static str, f64, u64, u64) {
+    if snapshot.is_websocket {
+        (
+            snapshot.ws_messages_sent,
+            "messages",
+            snapshot.ws_messages_sent as f64 / snapshot.elapsed.as_secs_f64().max(0.001),
+            snapshot.ws_latency_p99_us,
+            snapshot.ws_errors.values().sum::<u64>(),
+        )
+    } else {
+        (
+            snapshot.total_requests,
+            "requests",
+            snapshot.rolling_rps.max(0.0),
+            snapshot.latency_p99_us,
+            snapshot.failed,
+        )
+    }
+}
+
+/// `[#########-----------]  45% 00:27 ETA 00:33 | 1234 requests | 123.4/s | errors 3`
+fn bar_line(snapshot: &StatsSnapshot, duration: Duration, max_requests: u64) -> String {
+    let (count, unit, rate, _, errors) = tally(snapshot);
+    let Projection { done, eta, .. } = Projection::new(snapshot, duration, max_requests);
+    let filled = (done * BAR_WIDTH as f64).round() as usize;
+
+    format!(
+        "[{}{}] {:>3.0}% {} ETA {} | {} {} | {:.1}/s | errors {}",
+        "#".repeat(filled),
+        "-".repeat(BAR_WIDTH - filled),
+        done * 100.0,
+        clock(snapshot.elapsed),
+        clock(eta),
+        count,
+        unit,
+        rate,
+        errors
+    )
+}
+
+/// `01:05`, or `1:01:05` past an hour
+pub fn clock(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projects_totals_to_the_end_of_the_run() {
+        let snapshot = StatsSnapshot {
+            elapsed: Duration::from_secs(15),
+            total_requests: 1500,
+            rolling_rps: 100.0,
+            bytes_received: 1_000_000,
+            bytes_sent: 500_000,
+            // 100 KB/s
+            rolling_rx_bps: 800_000.0,
+            ..Default::default()
+        };
+
+        let projection = Projection::new(&snapshot, Duration::from_secs(60), 0);
+        assert_eq!(projection.eta, Duration::from_secs(45));
+        assert_eq!(projection.done, 0.25);
+        assert_eq!(projection.total, 6000);
+        assert_eq!(projection.bytes, 6_000_000);
+
+        // The request cap ends the run before the duration does
+        let projection = Projection::new(&snapshot, Duration::from_secs(60), 2000);
+        assert_eq!(projection.eta, Duration::from_secs(5));
+        assert_eq!(projection.done, 0.75);
+        assert_eq!(projection.total, 2000);
+    }
+}
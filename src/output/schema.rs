@@ -0,0 +1,280 @@
+//! Result file schema (`schemas/results.schema.json`, `kaioken schema`)
+//!
+//! Tooling that reads result files gets a documented contract: fields are
+//! only added within a schema version, and renaming or removing one, or
+//! changing what it means, bumps `SCHEMA_VERSION`. Every reader goes through
+//! `read_result`, which upgrades files written under older versions, so
+//! `compare`, `report`, history and runners keep reading old results, and
+//! then checks them against the schema, so a file that doesn't follow it is
+//! refused rather than read with defaults filled in.
+
+use crate::output::json::JsonOutput;
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+/// Written as `schema_version`; files without one are version 1
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// The JSON Schema of result files
+pub const SCHEMA: &str = include_str!("../../schemas/results.schema.json");
+
+/// Parse a result file, upgrading it to the current schema. Also returns
+/// the version it was written under.
+pub fn read_result(content: &str) -> Result<(JsonOutput, u32), String> {
+    result_from_value(serde_json::from_str(content).map_err(|e| e.to_string())?)
+}
+
+/// `read_result` for a result embedded in other JSON
+pub fn result_from_value(mut value: Value) -> Result<(JsonOutput, u32), String> {
+    let version = upgrade(&mut value)?;
+    validate(&value, schema(), "")
+        .map_err(|e| format!("not a kaioken result (schema version {}): {}", version, e))?;
+    let output = serde_json::from_value(value)
+        .map_err(|e| format!("not a kaioken result (schema version {}): {}", version, e))?;
+    Ok((output, version))
+}
+
+fn upgrade(value: &mut Value) -> Result<u32, String> {
+    let Some(result) = value.as_object_mut() else {
+        return Err("not a kaioken result: expected a JSON object".to_string());
+    };
+    let version = match result.get("schema_version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|&v| v >= 1)
+            .ok_or_else(|| format!("invalid schema_version {}", version))?,
+    };
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "written under result schema version {}, newer than this kaioken reads ({}); upgrade kaioken",
+            version, SCHEMA_VERSION
+        ));
+    }
+
+    if version < 2 {
+        v1_to_v2(result);
+    }
+    result.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+    Ok(version)
+}
+
+/// Version 1 results may predate configurable timeline buckets: their
+/// interval is 0 for the one second it always was, and their entries only
+/// have `elapsed_secs`. Older ones also lack the upload byte counts and the
+/// timeline latencies, which were zero then.
+fn v1_to_v2(result: &mut Map<String, Value>) {
+    if let Some(summary) = result.get_mut("summary").and_then(Value::as_object_mut) {
+        for key in ["bytes_sent", "rx_mbps", "tx_mbps"] {
+            summary.entry(key).or_insert(0.into());
+        }
+    }
+
+    if let Some(load) = result
+        .get_mut("metadata")
+        .and_then(|metadata| metadata.get_mut("load"))
+        .and_then(Value::as_object_mut)
+        && load
+            .get("timeline_interval_ms")
+            .and_then(Value::as_u64)
+            .unwrap_or(0)
+            == 0
+    {
+        load.insert("timeline_interval_ms".to_string(), 1000.into());
+    }
+
+    let entries = result.get_mut("timeline").and_then(Value::as_array_mut);
+    for entry in entries.into_iter().flatten() {
+        if let Some(entry) = entry.as_object_mut() {
+            entry
+                .entry("latency_us")
+                .or_insert_with(|| serde_json::json!({ "p50": 0, "p95": 0, "p99": 0 }));
+        }
+        if let Some(entry) = entry.as_object_mut()
+            && entry.get("elapsed_ms").and_then(Value::as_u64).unwrap_or(0) == 0
+        {
+            let secs = entry
+                .get("elapsed_secs")
+                .and_then(Value::as_u64)
+                .unwrap_or(0);
+            entry.insert("elapsed_ms".to_string(), (secs * 1000).into());
+        }
+    }
+}
+
+fn schema() -> &'static Value {
+    static PARSED: OnceLock<Value> = OnceLock::new();
+    PARSED.get_or_init(|| serde_json::from_str(SCHEMA).expect("results.schema.json is valid JSON"))
+}
+
+/// Check `value` against `schema`, naming the path of the first mismatch.
+/// Covers the keywords `results.schema.json` uses; `format` and the
+/// descriptions are annotations only.
+fn validate(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    let at = |path: &str| {
+        if path.is_empty() {
+            "the result".to_string()
+        } else {
+            path.to_string()
+        }
+    };
+
+    if let Some(types) = schema.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            other => other.as_str().into_iter().collect(),
+        };
+        if !allowed.iter().any(|&t| has_type(value, t)) {
+            return Err(format!(
+                "{}: expected {}, found {}",
+                at(path),
+                allowed.join(" or "),
+                type_name(value)
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const")
+        && value != expected
+    {
+        return Err(format!(
+            "{}: expected {}, found {}",
+            at(path),
+            expected,
+            value
+        ));
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array)
+        && !options.contains(value)
+    {
+        return Err(format!(
+            "{}: {} isn't one of {}",
+            at(path),
+            value,
+            Value::from(options.clone())
+        ));
+    }
+    if let Some(number) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64)
+            && number < min
+        {
+            return Err(format!(
+                "{}: {} is below the minimum {}",
+                at(path),
+                number,
+                min
+            ));
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64)
+            && number > max
+        {
+            return Err(format!(
+                "{}: {} is above the maximum {}",
+                at(path),
+                number,
+                max
+            ));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        for required in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(key) = required.as_str()
+                && !object.contains_key(key)
+            {
+                return Err(format!("{}: missing {}", at(path), key));
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (key, field) in object {
+            let field_path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            match properties.and_then(|p| p.get(key)) {
+                Some(field_schema) => validate(field, field_schema, &field_path)?,
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        return Err(format!("{}: not allowed", field_path));
+                    }
+                    Some(extra) if extra.is_object() => validate(field, extra, &field_path)?,
+                    // Readers ignore fields they don't know
+                    _ => {}
+                },
+            }
+        }
+    }
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in array.iter().enumerate() {
+            validate(item, items, &format!("{}[{}]", path, i))?;
+        }
+    }
+    Ok(())
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        // 1.0 is an integer too, as JSON Schema counts them
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::json::create_output;
+    use crate::types::{LoadConfig, StatsSnapshot};
+
+    #[test]
+    fn refuses_results_that_break_the_schema() {
+        let output = create_output(
+            &StatsSnapshot::default(),
+            &LoadConfig::default(),
+            None,
+            None,
+        );
+        let value = serde_json::to_value(&output).unwrap();
+        assert!(result_from_value(value.clone()).is_ok());
+
+        let mut wrong_type = value.clone();
+        wrong_type["latency_us"]["p99"] = "fast".into();
+        let e = result_from_value(wrong_type).err().unwrap();
+        assert!(e.contains("latency_us.p99: expected integer"), "{}", e);
+
+        let mut missing = value.clone();
+        missing["timeline"] = serde_json::json!([{ "elapsed_secs": 0 }]);
+        let e = result_from_value(missing).err().unwrap();
+        assert!(e.contains("timeline[0]: missing"), "{}", e);
+
+        let mut negative = value;
+        negative["summary"]["total_requests"] = (-1).into();
+        let e = result_from_value(negative).err().unwrap();
+        assert!(e.contains("summary.total_requests"), "{}", e);
+    }
+}
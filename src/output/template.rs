@@ -0,0 +1,66 @@
+//! User templates (`--format template --template report.tera`)
+//!
+//! Reports in a team's own format (Confluence markup, a chat message, the
+//! input of an internal dashboard) are rendered with Tera from the JSON
+//! result, so a template sees the same fields `kaioken schema` documents:
+//! `{{ summary.requests_per_sec }}`, `{{ latency_us.p99 }}`,
+//! `{% for t in thresholds.results %}`. Nothing is HTML-escaped.
+
+use crate::output::json::JsonOutput;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use tera::{Context, Tera};
+
+const NAME: &str = "report";
+
+/// Read and compile a template, so a broken one fails before the run does
+pub fn load_template(path: &Path) -> Result<Tera, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read template '{}': {}", path.display(), e))?;
+    let mut tera = Tera::default();
+    tera.add_raw_template(NAME, &content)
+        .map_err(|e| format!("Invalid template '{}': {}", path.display(), chain(&e)))?;
+    Ok(tera)
+}
+
+pub fn write_template(output: &JsonOutput, template: Option<&Path>, path: &str) -> io::Result<()> {
+    let rendered = render(output, template)?;
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(rendered.as_bytes())?;
+    writer.flush()
+}
+
+pub fn print_template(output: &JsonOutput, template: Option<&Path>) -> io::Result<()> {
+    let rendered = render(output, template)?;
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(rendered.as_bytes())?;
+    stdout.flush()
+}
+
+fn render(output: &JsonOutput, template: Option<&Path>) -> io::Result<String> {
+    let path =
+        template.ok_or_else(|| io::Error::other("--format template needs --template FILE"))?;
+    let tera = load_template(path).map_err(io::Error::other)?;
+    let context = Context::from_serialize(output).map_err(|e| io::Error::other(chain(&e)))?;
+    tera.render(NAME, &context).map_err(|e| {
+        io::Error::other(format!(
+            "template '{}' failed: {}",
+            path.display(),
+            chain(&e)
+        ))
+    })
+}
+
+/// Tera puts the useful part (the line, the missing variable) in the sources
+fn chain(err: &tera::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+        source = err.source();
+    }
+    message
+}
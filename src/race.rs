@@ -0,0 +1,130 @@
+//! HTTP/2 vs HTTP/3 A/B runs (--protocol-race)
+
+use crate::cli::{CompareArgs, RunArgs};
+use crate::compare::{CompareResult, compare_outputs, print_comparison_table};
+use crate::engine::Engine;
+use crate::output::json::{JsonOutput, create_output};
+use crate::types::{LoadConfig, ProtocolRace, StatsSnapshot};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Serialize)]
+struct RaceOutput {
+    mode: &'static str,
+    http2: JsonOutput,
+    http3: JsonOutput,
+    comparison: CompareResult,
+}
+
+/// Run the same workload over HTTP/2 and HTTP/3 and compare the results
+pub async fn run_protocol_race(
+    args: &RunArgs,
+    config: LoadConfig,
+    race: ProtocolRace,
+) -> Result<i32, String> {
+    let mut h2_config = config.clone();
+    h2_config.protocol_race = None;
+    h2_config.http2 = true;
+    let mut h3_config = h2_config.clone();
+    h3_config.http2 = false;
+    h3_config.http3 = true;
+
+    if race == ProtocolRace::Split {
+        // HTTP/2 gets the odd VU out
+        h3_config.concurrency = config.concurrency / 2;
+        h2_config.concurrency = config.concurrency - h3_config.concurrency;
+    }
+
+    if !args.quiet {
+        eprintln!(
+            "Protocol race ({}): HTTP/2 vs HTTP/3 against {}",
+            race.as_str(),
+            config.url
+        );
+    }
+
+    let h2_engine = Engine::new(h2_config.clone());
+    let h3_engine = Engine::new(h3_config.clone());
+    let h2_rx = h2_engine.snapshot_rx();
+    let h3_rx = h3_engine.snapshot_rx();
+
+    let h2_cancel = h2_engine.cancel_token();
+    let h3_cancel = h3_engine.cancel_token();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            h2_cancel.cancel();
+            h3_cancel.cancel();
+        }
+    });
+
+    match race {
+        ProtocolRace::Split => {
+            let (h2, h3) = tokio::join!(h2_engine.run(), h3_engine.run());
+            h2?;
+            h3?;
+        }
+        ProtocolRace::Sequential => {
+            let h3_cancel = h3_engine.cancel_token();
+            h2_engine.run().await?;
+            if !h3_cancel.is_cancelled() {
+                h3_engine.run().await?;
+            }
+        }
+    }
+
+    let h2_snapshot: StatsSnapshot = h2_rx.borrow().clone();
+    let h3_snapshot: StatsSnapshot = h3_rx.borrow().clone();
+
+    let h2_output = create_output(&h2_snapshot, &h2_config, None, None);
+    let h3_output = create_output(&h3_snapshot, &h3_config, None, None);
+
+    let compare_args = CompareArgs {
+        baseline: PathBuf::from("HTTP/2"),
+        current: PathBuf::from("HTTP/3"),
+        threshold_p99: 10.0,
+        threshold_p999: 15.0,
+        threshold_error_rate: 50.0,
+        threshold_rps: 10.0,
+        serious: args.serious,
+        json: args.json,
+        force: false,
+    };
+    let comparison = compare_outputs(&h2_output, &h3_output, &compare_args)?;
+
+    let output = RaceOutput {
+        mode: race.as_str(),
+        http2: h2_output,
+        http3: h3_output,
+        comparison,
+    };
+
+    if args.json || (args.no_tui && args.format.eq_ignore_ascii_case("json")) {
+        serde_json::to_writer_pretty(std::io::stdout(), &output)
+            .map_err(|e| format!("Failed to write JSON: {}", e))?;
+        println!();
+    } else {
+        println!("\nHTTP/2");
+        crate::print_summary(&h2_snapshot, args.serious);
+        println!("\nHTTP/3");
+        crate::print_summary(&h3_snapshot, args.serious);
+        print_comparison_table(&output.comparison, args.serious);
+        println!();
+    }
+
+    if let Some(path) = &args.output {
+        let file = std::fs::File::create(path)
+            .map_err(|e| format!("Failed to write output file: {}", e))?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &output)
+            .map_err(|e| format!("Failed to write output file: {}", e))?;
+        if !args.quiet {
+            eprintln!("Results written to: {}", path);
+        }
+    }
+
+    let high_error_rate = |s: &StatsSnapshot| s.failed > 0 && s.error_rate > 0.5;
+    if high_error_rate(&h2_snapshot) || high_error_rate(&h3_snapshot) {
+        Ok(1)
+    } else {
+        Ok(0)
+    }
+}
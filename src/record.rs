@@ -0,0 +1,321 @@
+//! Proxy-based scenario recorder (`kaioken record`)
+//!
+//! Forwards traffic from a browser or app pointed at it and captures each
+//! request. When recording stops, the captures go through the same
+//! conversion as `kaioken import`, so repeated requests become weighted
+//! scenarios. Plain HTTP is recorded in proxy mode; HTTPS needs `--target`
+//! (reverse proxy), since CONNECT tunnels are encrypted end to end.
+
+use crate::cli::RecordArgs;
+use crate::import::har::{HarEntry, HarHeader, HarPostData, HarRequest, entries_to_config};
+use crate::serve::http::{Request, Response, read_request};
+use regex_lite::Regex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Notify;
+
+/// Connection-scoped headers that must not be forwarded or recorded
+const HOP_BY_HOP: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+struct Recorder {
+    client: reqwest::Client,
+    target: Option<String>,
+    filter: Option<Regex>,
+    limit: Option<usize>,
+    entries: Mutex<Vec<HarEntry>>,
+    /// Signalled when `limit` requests have been recorded
+    done: Notify,
+    warned_connect: AtomicBool,
+}
+
+pub async fn run_record(args: &RecordArgs) -> Result<i32, String> {
+    if args.output.exists() && !args.force {
+        return Err(format!(
+            "Output file '{}' already exists. Use --force to overwrite.",
+            args.output.display()
+        ));
+    }
+
+    let target = args
+        .target
+        .as_deref()
+        .map(|t| {
+            if t.starts_with("http://") || t.starts_with("https://") {
+                Ok(t.trim_end_matches('/').to_string())
+            } else {
+                Err(format!(
+                    "--target must be an http:// or https:// URL: {}",
+                    t
+                ))
+            }
+        })
+        .transpose()?;
+
+    let filter = args
+        .filter
+        .as_ref()
+        .map(|f| Regex::new(f).map_err(|e| format!("Invalid filter regex: {}", e)))
+        .transpose()?;
+
+    // Pass responses through untouched: no decompression, no redirects
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .no_gzip()
+        .no_brotli()
+        .danger_accept_invalid_certs(args.insecure)
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let listener = TcpListener::bind(&args.bind)
+        .await
+        .map_err(|e| format!("Failed to bind {}: {}", args.bind, e))?;
+    let addr = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to bind {}: {}", args.bind, e))?;
+
+    match target {
+        Some(ref target) => eprintln!(
+            "Recording: point your client at http://{} (forwarding to {})",
+            addr, target
+        ),
+        None => eprintln!("Recording: set http://{} as your HTTP proxy", addr),
+    }
+    eprintln!("Press Ctrl+C to stop and write {}", args.output.display());
+
+    let recorder = Arc::new(Recorder {
+        client,
+        target: target.clone(),
+        filter,
+        limit: args.limit,
+        entries: Mutex::new(Vec::new()),
+        done: Notify::new(),
+        warned_connect: AtomicBool::new(false),
+    });
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = recorder.done.notified() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let recorder = recorder.clone();
+                    tokio::spawn(async move { recorder.handle(stream).await });
+                }
+                Err(e) => tracing::warn!("Failed to accept connection: {}", e),
+            }
+        }
+    }
+
+    let entries = std::mem::take(&mut *recorder.entries.lock().unwrap_or_else(|e| e.into_inner()));
+    if entries.is_empty() {
+        return Err("No requests recorded".to_string());
+    }
+
+    let source = match target {
+        Some(ref target) => format!("reverse proxy for {}", target),
+        None => format!("HTTP proxy on {}", addr),
+    };
+    let header = format!("# Recorded by kaioken record\n# Source: {}\n", source);
+    // Entries were filtered as they were recorded
+    let config = entries_to_config(&entries, None, &header)
+        .ok_or_else(|| "No HTTP requests recorded".to_string())?;
+
+    std::fs::write(&args.output, config)
+        .map_err(|e| format!("Failed to write output file: {}", e))?;
+
+    eprintln!(
+        "\nRecorded {} requests to: {}",
+        entries.len(),
+        args.output.display()
+    );
+    Ok(0)
+}
+
+impl Recorder {
+    async fn handle(&self, mut stream: TcpStream) {
+        let request = match read_request(&mut stream).await {
+            Ok(Some(request)) => request,
+            _ => return,
+        };
+
+        if request.method == "CONNECT" {
+            return self.tunnel(stream, &request.path).await;
+        }
+
+        let Some(url) = self.upstream_url(&request) else {
+            let _ = Response::text(
+                400,
+                "Not a proxy request: configure this address as an HTTP proxy, or start kaioken record with --target",
+            )
+            .write(&mut stream)
+            .await;
+            return;
+        };
+
+        let headers: Vec<HarHeader> = request
+            .headers
+            .iter()
+            .filter(|(name, _)| {
+                let name = name.to_ascii_lowercase();
+                !HOP_BY_HOP.contains(&name.as_str()) && name != "host" && name != "content-length"
+            })
+            .map(|(name, value)| HarHeader {
+                name: name.clone(),
+                value: value.clone(),
+            })
+            .collect();
+
+        match self.forward(&request, &url, &headers).await {
+            Ok(response) => {
+                if stream.write_all(&response.head).await.is_err()
+                    || stream.write_all(&response.body).await.is_err()
+                {
+                    return;
+                }
+                eprintln!("  {} {} -> {}", request.method, url, response.status);
+            }
+            Err(e) => {
+                let _ = Response::text(502, &format!("Upstream request failed: {}", e))
+                    .write(&mut stream)
+                    .await;
+                eprintln!("  {} {} -> error: {}", request.method, url, e);
+            }
+        }
+
+        // Record even when the upstream failed: the client still made the call
+        self.record(&request, url, headers);
+    }
+
+    /// Absolute URL the request is for, from `--target` or the proxy-form request line
+    fn upstream_url(&self, request: &Request) -> Option<String> {
+        let mut url = match self.target {
+            Some(ref target) if request.path.starts_with('/') => {
+                format!("{}{}", target, request.path)
+            }
+            None if request.path.starts_with("http://") => request.path.clone(),
+            _ => return None,
+        };
+        if !request.query.is_empty() {
+            url.push('?');
+            url.push_str(&request.query);
+        }
+        Some(url)
+    }
+
+    async fn forward(
+        &self,
+        request: &Request,
+        url: &str,
+        headers: &[HarHeader],
+    ) -> Result<ForwardedResponse, String> {
+        let method = reqwest::Method::from_bytes(request.method.as_bytes())
+            .map_err(|_| format!("Invalid method: {}", request.method))?;
+
+        let mut builder = self.client.request(method, url);
+        for header in headers {
+            builder = builder.header(header.name.as_str(), header.value.as_str());
+        }
+        if !request.body.is_empty() {
+            builder = builder.body(request.body.clone());
+        }
+
+        let response = builder.send().await.map_err(|e| e.to_string())?;
+        let status = response.status();
+
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\n",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or("")
+        );
+        for (name, value) in response.headers() {
+            let name = name.as_str();
+            if HOP_BY_HOP.contains(&name) || name == "content-length" {
+                continue;
+            }
+            head.push_str(name);
+            head.push_str(": ");
+            head.push_str(&String::from_utf8_lossy(value.as_bytes()));
+            head.push_str("\r\n");
+        }
+
+        let body = response.bytes().await.map_err(|e| e.to_string())?;
+        head.push_str(&format!(
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        ));
+
+        Ok(ForwardedResponse {
+            status: status.as_u16(),
+            head: head.into_bytes(),
+            body,
+        })
+    }
+
+    fn record(&self, request: &Request, url: String, headers: Vec<HarHeader>) {
+        if self.filter.as_ref().is_some_and(|re| !re.is_match(&url)) {
+            return;
+        }
+
+        let post_data = (!request.body.is_empty()).then(|| HarPostData {
+            // Binary bodies can't be written into a TOML config
+            text: String::from_utf8(request.body.clone()).ok(),
+            mime_type: request.header("content-type").map(str::to_string),
+        });
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.push(HarEntry {
+            request: HarRequest {
+                method: request.method.clone(),
+                url,
+                headers,
+                post_data,
+            },
+            response: None,
+        });
+        if self.limit.is_some_and(|limit| entries.len() >= limit) {
+            self.done.notify_one();
+        }
+    }
+
+    /// Pass CONNECT tunnels through unrecorded so HTTPS browsing keeps working
+    async fn tunnel(&self, mut stream: TcpStream, authority: &str) {
+        if !self.warned_connect.swap(true, Ordering::Relaxed) {
+            eprintln!(
+                "⚠️  HTTPS traffic through the proxy is encrypted and can't be recorded; use --target https://HOST instead"
+            );
+        }
+        let Ok(mut upstream) = TcpStream::connect(authority).await else {
+            let _ = Response::text(502, "Failed to connect upstream")
+                .write(&mut stream)
+                .await;
+            return;
+        };
+        if stream
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await
+            .is_ok()
+        {
+            let _ = tokio::io::copy_bidirectional(&mut stream, &mut upstream).await;
+        }
+    }
+}
+
+struct ForwardedResponse {
+    status: u16,
+    head: Vec<u8>,
+    body: bytes::Bytes,
+}
@@ -0,0 +1,91 @@
+//! Live config reload: SIGHUP, or `r` in the TUI, re-reads the config file
+//! and re-plans the rest of the run without resetting its stats
+//!
+//! Only the load plan can change mid-run: the stages of a staged run, or
+//! the rate of a `--rate` / `--arrival-rate` run. Flags given on the command
+//! line still override the file.
+
+use crate::cli::RunArgs;
+use crate::config::{load_config, merge_config};
+use kaioken_core::engine::RunControl;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Notify, watch};
+
+/// Reload whenever SIGHUP arrives or `requests` is notified, until aborted.
+/// Each outcome is sent to `status` and printed unless `quiet`.
+pub async fn watch_reloads(
+    args: RunArgs,
+    path: PathBuf,
+    control: Arc<RunControl>,
+    requests: Arc<Notify>,
+    status: watch::Sender<String>,
+    quiet: bool,
+) {
+    #[cfg(unix)]
+    let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()).ok();
+
+    loop {
+        #[cfg(unix)]
+        tokio::select! {
+            Some(()) = async {
+                match hangup.as_mut() {
+                    Some(signal) => signal.recv().await,
+                    None => None,
+                }
+            } => {}
+            _ = requests.notified() => {}
+        }
+        #[cfg(not(unix))]
+        requests.notified().await;
+
+        let message = match reload(&args, &path, &control) {
+            Ok(change) => format!("Config reloaded: {}", change),
+            Err(e) => format!("Config reload failed: {}", e),
+        };
+        if !quiet {
+            eprintln!("{}", message);
+        }
+        let _ = status.send(message);
+    }
+}
+
+/// Apply the config file's current stages or rate to the run, returning
+/// what changed
+fn reload(args: &RunArgs, path: &Path, control: &RunControl) -> Result<String, String> {
+    let toml = load_config(path)?;
+    if !toml.tests.is_empty() {
+        return Err("[[tests]] suites can't be reloaded".to_string());
+    }
+    let config = merge_config(args, Some(toml))?;
+
+    let stages = control.stages();
+    if !stages.is_empty() {
+        if config.stages == stages {
+            return Ok("stages unchanged".to_string());
+        }
+        control.reload_stages(config.stages.clone())?;
+        let total: Duration = config.stages.iter().map(|s| s.duration).sum();
+        return Ok(format!(
+            "{} stages, {} in total",
+            config.stages.len(),
+            humantime::format_duration(total)
+        ));
+    }
+
+    match control.plan().rate {
+        Some(current) => {
+            let rate = config.arrival_rate.unwrap_or(config.rate);
+            if rate == current {
+                return Ok("rate unchanged".to_string());
+            }
+            control.set_rate(rate)?;
+            Ok(format!("rate {} -> {} req/s", current, rate))
+        }
+        None => Err(
+            "only stages, or the rate of a --rate or --arrival-rate run, can change mid-run"
+                .to_string(),
+        ),
+    }
+}
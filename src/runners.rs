@@ -0,0 +1,1009 @@
+//! `run --runners`: run the same test on remote hosts over SSH and merge
+//! their results
+//!
+//! Each runner gets a copy of this binary (unless `--runner-bin` names one
+//! already installed there) and of the config file, then runs the test with
+//! `--runner-stream`, which prints the JSON result once a second on stdout.
+//! All runners start at the same wall-clock time, so their clocks need to
+//! agree (NTP). Runners stream their full latency histogram, so merged
+//! percentiles are exact; if one doesn't (an older `--runner-bin`), they
+//! fall back to a request-weighted mean and percentile thresholds are
+//! skipped.
+
+use crate::cli::RunArgs;
+use crate::output::json::{JsonOutput, create_output};
+use crate::output::schema::read_result;
+use crate::output::{Progress, finish_progress, report_progress};
+use crate::types::{
+    LoadConfig, RunPhase, StatsSnapshot, Threshold, ThresholdResult, TimelineBucket,
+};
+use clap::CommandFactory;
+use hdrhistogram::Histogram;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
+
+/// Time between launching the runners and their common start, so every SSH
+/// session is up before the first one starts
+const START_DELAY: Duration = Duration::from_secs(3);
+
+/// Flags only the coordinator acts on
+const LOCAL_FLAGS: &[&str] = &[
+    "--runners",
+    "--runner-bin",
+    "-o",
+    "--output",
+    "--format",
+    "--summary-interval",
+    "--json",
+    "--no-tui",
+    "-q",
+    "--quiet",
+    "-y",
+    "--yes",
+];
+
+/// One `ssh://[user@]host[:port]` runner
+#[derive(Debug, Clone, PartialEq)]
+pub struct Runner {
+    pub user: Option<String>,
+    pub host: String,
+    pub port: u16,
+}
+
+impl Runner {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let url = reqwest::Url::parse(s).map_err(|e| format!("Invalid runner '{}': {}", s, e))?;
+        if url.scheme() != "ssh" {
+            return Err(format!("Invalid runner '{}': expected ssh://", s));
+        }
+        let host = url
+            .host_str()
+            .filter(|h| !h.is_empty())
+            .ok_or_else(|| format!("Invalid runner '{}': no host", s))?;
+        if !matches!(url.path(), "" | "/") {
+            return Err(format!("Invalid runner '{}': unexpected path", s));
+        }
+        Ok(Self {
+            user: (!url.username().is_empty()).then(|| url.username().to_string()),
+            host: host.trim_matches(['[', ']']).to_string(),
+            port: url.port().unwrap_or(22),
+        })
+    }
+
+    /// `user@host`, as ssh takes it
+    fn destination(&self) -> String {
+        match self.user {
+            Some(ref user) => format!("{}@{}", user, self.host),
+            None => self.host.clone(),
+        }
+    }
+
+    /// `user@host:path`, as scp takes it (IPv6 hosts bracketed)
+    fn scp_target(&self, path: &str) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        match self.user {
+            Some(ref user) => format!("{}@{}:{}", user, host, path),
+            None => format!("{}:{}", host, path),
+        }
+    }
+
+    async fn copy(&self, local: &str, remote: &str) -> Result<(), String> {
+        let output = Command::new("scp")
+            .args(["-q", "-p", "-P", &self.port.to_string()])
+            .args(["-o", "BatchMode=yes", local, &self.scp_target(remote)])
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run scp: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Copying {} to runner {} failed: {}",
+                local,
+                self,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+
+    fn ssh(&self, command: &str) -> Command {
+        let mut ssh = Command::new("ssh");
+        ssh.args(["-p", &self.port.to_string(), "-o", "BatchMode=yes"])
+            .arg(self.destination())
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        ssh
+    }
+}
+
+impl std::fmt::Display for Runner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.destination())?;
+        if self.port != 22 {
+            write!(f, ":{}", self.port)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_runners(list: &[String]) -> Result<Vec<Runner>, String> {
+    list.iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(Runner::parse)
+        .collect()
+}
+
+/// How a runner's session ended
+struct Finished {
+    runner: Runner,
+    exit_code: Option<i32>,
+    stderr: String,
+    /// Its last JSON result line, the final numbers
+    result: Option<JsonOutput>,
+}
+
+impl Finished {
+    /// Why this runner's result can't be used: it sent none, or its session
+    /// ended other than with the run's own exit code (0, or 4 for failed
+    /// thresholds). Ctrl+C kills the sessions, leaving them no exit status.
+    fn failure(&self, cancelled: bool) -> Option<String> {
+        let last_line = self.stderr.lines().last();
+        if self.result.is_none() {
+            return Some(last_line.unwrap_or("no output").to_string());
+        }
+        let status = match self.exit_code {
+            Some(0 | 4) => return None,
+            None if cancelled => return None,
+            Some(code) => format!("exited with status {}", code),
+            None => "session ended without an exit status".to_string(),
+        };
+        Some(match last_line {
+            Some(line) => format!("{}: {}", status, line),
+            None => status,
+        })
+    }
+}
+
+/// Run the test on every runner, show merged progress, then report the
+/// merged result like a local run would
+pub async fn run_on_runners(
+    args: &RunArgs,
+    config: LoadConfig,
+    runners: Vec<Runner>,
+) -> Result<i32, String> {
+    // Everything after `run`, minus what only the coordinator handles
+    let argv: Vec<String> = std::env::args()
+        .skip_while(|arg| arg != "run")
+        .skip(1)
+        .collect();
+
+    let id = std::process::id();
+    let remote_bin = match args.runner_bin {
+        Some(ref bin) => bin.clone(),
+        None => format!("/tmp/kaioken-runner-{}", id),
+    };
+    let remote_config = args
+        .config
+        .as_ref()
+        .map(|_| format!("/tmp/kaioken-runner-{}.toml", id));
+    let local_bin = std::env::current_exe()
+        .map_err(|e| format!("Can't find the kaioken binary to copy: {}", e))?;
+
+    if !args.quiet {
+        eprintln!("Preparing {} runner(s)...", runners.len());
+    }
+    let mut copies = tokio::task::JoinSet::new();
+    for runner in &runners {
+        let runner = runner.clone();
+        let binary = args
+            .runner_bin
+            .is_none()
+            .then(|| (local_bin.to_string_lossy().into_owned(), remote_bin.clone()));
+        let config = args
+            .config
+            .as_ref()
+            .zip(remote_config.clone())
+            .map(|(local, remote)| (local.to_string_lossy().into_owned(), remote));
+        copies.spawn(async move {
+            for (local, remote) in binary.into_iter().chain(config) {
+                runner.copy(&local, &remote).await?;
+            }
+            Ok::<_, String>(())
+        });
+    }
+    while let Some(copied) = copies.join_next().await {
+        copied.map_err(|e| format!("Copy task failed: {}", e))??;
+    }
+
+    let start_at = SystemTime::now() + START_DELAY;
+    let start_ms = start_at
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let mut command = vec![shell_quote(&remote_bin), "run".to_string()];
+    command.extend(
+        remote_args(&argv, remote_config.as_deref())
+            .iter()
+            .map(|arg| shell_quote(arg)),
+    );
+    command.push(format!(
+        "--no-tui -y --runner-stream --start-at {}",
+        start_ms
+    ));
+    let mut command = command.join(" ");
+    // Leave nothing behind that this run copied over
+    let copied: Vec<String> = args
+        .runner_bin
+        .is_none()
+        .then(|| remote_bin.clone())
+        .into_iter()
+        .chain(remote_config.clone())
+        .map(|path| shell_quote(&path))
+        .collect();
+    if !copied.is_empty() {
+        command = format!(
+            "{}; status=$?; rm -f {}; exit $status",
+            command,
+            copied.join(" ")
+        );
+    }
+
+    let cancel = CancellationToken::new();
+    let ctrl_c_token = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            ctrl_c_token.cancel();
+        }
+    });
+
+    let (update_tx, mut update_rx) = mpsc::channel::<(usize, JsonOutput)>(64);
+    let mut sessions = Vec::with_capacity(runners.len());
+    for (index, runner) in runners.iter().enumerate() {
+        let child = runner
+            .ssh(&command)
+            .spawn()
+            .map_err(|e| format!("Failed to run ssh: {}", e))?;
+        sessions.push(tokio::spawn(stream_runner(
+            index,
+            runner.clone(),
+            child,
+            update_tx.clone(),
+            cancel.clone(),
+        )));
+    }
+    drop(update_tx);
+
+    if !args.quiet {
+        eprintln!(
+            "Runners:     {} (starting in {}s)",
+            runners
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            START_DELAY.as_secs()
+        );
+    }
+
+    // Merge whatever each runner reported last into one live snapshot
+    let (snapshot_tx, snapshot_rx) = watch::channel(StatsSnapshot::default());
+    let (_phase_tx, phase_rx) = watch::channel(RunPhase::Running);
+    let progress = (!args.quiet).then(|| {
+        let style = args
+            .progress
+            .map_or(Progress::Lines, |style| Progress::new(style, &config));
+        let every = args
+            .summary_interval
+            .or(args.progress.map(|_| style.default_interval()))
+            .unwrap_or(Duration::from_secs(5));
+        let handle = tokio::spawn(report_progress(every, style, snapshot_rx, phase_rx));
+        (handle, style)
+    });
+    let mut latest: Vec<Option<JsonOutput>> = (0..runners.len()).map(|_| None).collect();
+    while let Some((index, output)) = update_rx.recv().await {
+        latest[index] = Some(output);
+        let parts: Vec<StatsSnapshot> = latest.iter().flatten().map(|o| o.to_snapshot()).collect();
+        let _ = snapshot_tx.send(merge_snapshots(&parts));
+    }
+    if let Some((handle, style)) = progress {
+        handle.abort();
+        finish_progress(style);
+    }
+
+    let mut finished = Vec::with_capacity(sessions.len());
+    for (session, result) in sessions.into_iter().zip(latest) {
+        let mut done = session
+            .await
+            .map_err(|e| format!("Runner task failed: {}", e))?;
+        done.result = result;
+        finished.push(done);
+    }
+    for done in &finished {
+        if let Some(reason) = done.failure(cancel.is_cancelled()) {
+            return Err(format!("Runner {} failed: {}", done.runner, reason));
+        }
+    }
+
+    report(args, &config, &finished)
+}
+
+/// Read one runner's stdout until its session ends; every line is a full
+/// JSON result, the last one final
+async fn stream_runner(
+    index: usize,
+    runner: Runner,
+    mut child: tokio::process::Child,
+    updates: mpsc::Sender<(usize, JsonOutput)>,
+    cancel: CancellationToken,
+) -> Finished {
+    let mut lines = BufReader::new(child.stdout.take().expect("piped stdout")).lines();
+    let mut stderr_pipe = child.stderr.take().expect("piped stderr");
+    let stderr_task = tokio::spawn(async move {
+        let mut stderr = String::new();
+        let _ = stderr_pipe.read_to_string(&mut stderr).await;
+        stderr
+    });
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => match read_result(&line) {
+                    Ok((output, _)) => {
+                        let _ = updates.send((index, output)).await;
+                    }
+                    Err(e) => tracing::debug!("Runner {} sent an unreadable line: {}", runner, e),
+                },
+                _ => break,
+            },
+            // Ending the session stops the remote run at its next line
+            _ = cancel.cancelled() => {
+                let _ = child.start_kill();
+                break;
+            }
+        }
+    }
+
+    let exit_code = child.wait().await.ok().and_then(|status| status.code());
+    let stderr = stderr_task.await.unwrap_or_default();
+    Finished {
+        runner,
+        exit_code,
+        stderr,
+        result: None,
+    }
+}
+
+/// Print and write the merged result, and pick the exit code
+fn report(args: &RunArgs, config: &LoadConfig, finished: &[Finished]) -> Result<i32, String> {
+    let outputs: Vec<&JsonOutput> = finished.iter().filter_map(|f| f.result.as_ref()).collect();
+    let mut snapshot =
+        merge_snapshots(&outputs.iter().map(|o| o.to_snapshot()).collect::<Vec<_>>());
+    let mut check_stats: HashMap<String, (u64, u64)> = HashMap::new();
+    for output in &outputs {
+        for (name, (passed, total)) in output.check_stats() {
+            let entry = check_stats.entry(name).or_default();
+            entry.0 += passed;
+            entry.1 += total;
+        }
+    }
+    crate::apply_check_stats(&mut snapshot, &check_stats);
+
+    // Averaged percentiles can understate the tail, so don't judge them
+    let exact_percentiles = snapshot.latency_histogram.take().is_some();
+    let thresholds: Vec<Threshold> = config
+        .thresholds
+        .iter()
+        .filter(|t| exact_percentiles || t.metric.percentile().is_none())
+        .cloned()
+        .collect();
+    if !exact_percentiles && !args.quiet {
+        eprintln!(
+            "Warning: a runner sent no latency histogram (older --runner-bin?); merged \
+             percentiles are approximate and percentile thresholds were skipped"
+        );
+    }
+    let threshold_results = crate::evaluate_thresholds(&thresholds, &snapshot);
+    let threshold_results_opt: Option<&[ThresholdResult]> =
+        (!threshold_results.is_empty()).then_some(threshold_results.as_slice());
+    let check_stats_opt = (!check_stats.is_empty()).then_some(&check_stats);
+
+    if !args.quiet {
+        eprintln!("\nRunners:");
+        for done in finished {
+            if let Some(ref output) = done.result {
+                eprintln!(
+                    "  {:32} {:>10} requests  {:>10.1}/s  p99 {:>8.2}ms  exit {}",
+                    done.runner.to_string(),
+                    output.summary.total_requests,
+                    output.summary.requests_per_sec,
+                    output.latency_us.p99 as f64 / 1000.0,
+                    done.exit_code
+                        .map_or_else(|| "-".to_string(), |c| c.to_string())
+                );
+            }
+        }
+    }
+
+    let format = args.format.to_lowercase();
+    let output_json = args.json || format == "json" && args.no_tui;
+    if output_json {
+        crate::print_json(&snapshot, config, threshold_results_opt, check_stats_opt)
+            .map_err(|e| format!("Failed to write JSON: {}", e))?;
+    } else {
+        match format.as_str() {
+            "csv" => crate::print_csv(&snapshot, config)
+                .map_err(|e| format!("Failed to write CSV: {}", e))?,
+            "md" | "markdown" => crate::print_markdown(&snapshot, config)
+                .map_err(|e| format!("Failed to write Markdown: {}", e))?,
+            "html" => crate::print_html(&snapshot, config)
+                .map_err(|e| format!("Failed to write HTML: {}", e))?,
+            "template" => crate::print_template(
+                &create_output(&snapshot, config, threshold_results_opt, check_stats_opt),
+                args.template.as_deref(),
+            )
+            .map_err(|e| format!("Failed to write template: {}", e))?,
+            _ => crate::print_summary(&snapshot, args.serious),
+        }
+        if let Some(results) = threshold_results_opt {
+            crate::print_threshold_results(results);
+        }
+        if !check_stats.is_empty() {
+            crate::print_check_results(&check_stats);
+        }
+    }
+
+    if let Some(ref path) = args.output {
+        let written = match format.as_str() {
+            "csv" => crate::write_csv(&snapshot, config, path),
+            "md" | "markdown" => crate::write_markdown(&snapshot, config, path),
+            "html" => crate::write_html(&snapshot, config, path),
+            "template" => crate::write_template(
+                &create_output(&snapshot, config, threshold_results_opt, check_stats_opt),
+                args.template.as_deref(),
+                path,
+            ),
+            _ => crate::write_json(
+                &snapshot,
+                config,
+                path,
+                threshold_results_opt,
+                check_stats_opt,
+            ),
+        };
+        written.map_err(|e| format!("Failed to write output file: {}", e))?;
+        if !args.quiet {
+            eprintln!("Results written to: {}", path);
+        }
+    }
+
+    let exit_code = if threshold_results.iter().any(|r| !r.passed) {
+        4 // Thresholds failed
+    } else if snapshot.failed > 0 && snapshot.error_rate > 0.5 {
+        1 // High error rate
+    } else {
+        0
+    };
+    Ok(exit_code)
+}
+
+/// `--runner-stream`: print the JSON result on one stdout line every second
+/// until aborted. Cancels the run once stdout is gone, which is how a
+/// runner notices the coordinator went away.
+pub async fn stream_snapshots(
+    snapshot_rx: watch::Receiver<StatsSnapshot>,
+    config: LoadConfig,
+    cancel: CancellationToken,
+) {
+    let mut ticks = tokio::time::interval(Duration::from_secs(1));
+    ticks.tick().await;
+    loop {
+        ticks.tick().await;
+        let output = create_output(&snapshot_rx.borrow(), &config, None, None);
+        if write_line(&output).is_err() {
+            cancel.cancel();
+            return;
+        }
+    }
+}
+
+/// Print one compact JSON result line for the coordinator
+pub fn write_line(output: &JsonOutput) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer(&mut stdout, output)?;
+    writeln!(stdout)?;
+    stdout.flush()
+}
+
+/// `--start-at`: sleep until the coordinator's start time
+pub async fn wait_until(unix_ms: u64) {
+    let start = UNIX_EPOCH + Duration::from_millis(unix_ms);
+    if let Ok(wait) = start.duration_since(SystemTime::now()) {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// `run`'s arguments for the runners: coordinator-only flags dropped and
+/// the config path replaced by the copy on the runner. Whether a flag takes
+/// a value comes from clap's definition of `run`, so short flags are read
+/// the way clap reads them (`-yq`, `-oFILE`, `-f=PATH`) and values are never
+/// mistaken for flags.
+fn remote_args(args: &[String], config: Option<&str>) -> Vec<String> {
+    let command = RunArgs::command();
+    let takes_value = |flag: &str| {
+        command.get_arguments().any(|arg| {
+            let named = match flag.strip_prefix("--") {
+                Some(long) => {
+                    arg.get_long() == Some(long)
+                        || arg.get_all_aliases().is_some_and(|a| a.contains(&long))
+                }
+                None => flag
+                    .chars()
+                    .nth(1)
+                    .is_some_and(|c| arg.get_short() == Some(c)),
+            };
+            named && arg.get_action().takes_values()
+        })
+    };
+
+    let mut remote = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            remote.extend(std::iter::once(arg).chain(args).cloned());
+            break;
+        }
+        // The flags this argument holds, with values given inline
+        let flags: Vec<(String, Option<String>)> = if arg.starts_with("--") {
+            match arg.split_once('=') {
+                Some((name, value)) => vec![(name.to_string(), Some(value.to_string()))],
+                None => vec![(arg.clone(), None)],
+            }
+        } else if arg.len() > 1 && arg.starts_with('-') {
+            split_short_flags(arg, &takes_value)
+        } else {
+            remote.push(arg.clone());
+            continue;
+        };
+
+        for (flag, inline) in flags {
+            let inline_given = inline.is_some();
+            let value = match inline {
+                Some(value) => Some(value),
+                None if takes_value(&flag) => args.next().cloned(),
+                None => None,
+            };
+            if flag == "-f" || flag == "--config" {
+                if let Some(config) = config {
+                    remote.extend(["--config".to_string(), config.to_string()]);
+                }
+            } else if !LOCAL_FLAGS.contains(&flag.as_str()) {
+                // Inline values stay inline, so ones starting with `-` still parse
+                match (inline_given, value) {
+                    (true, Some(value)) if flag.starts_with("--") => {
+                        remote.push(format!("{}={}", flag, value))
+                    }
+                    (true, Some(value)) => remote.push(format!("{}{}", flag, value)),
+                    (_, value) => {
+                        remote.push(flag);
+                        remote.extend(value);
+                    }
+                }
+            }
+        }
+    }
+    remote
+}
+
+/// `-yqc10` as `-y`, `-q` and `-c` with `10`: a flag that takes a value
+/// takes the rest of the argument, without a leading `=`
+fn split_short_flags(
+    arg: &str,
+    takes_value: &impl Fn(&str) -> bool,
+) -> Vec<(String, Option<String>)> {
+    let mut flags = Vec::new();
+    for (i, c) in arg.char_indices().skip(1) {
+        let flag = format!("-{}", c);
+        if takes_value(&flag) {
+            let rest = &arg[i + c.len_utf8()..];
+            let rest = rest.strip_prefix('=').unwrap_or(rest);
+            flags.push((flag, (!rest.is_empty()).then(|| rest.to_string())));
+            break;
+        }
+        flags.push((flag, None));
+    }
+    flags
+}
+
+/// Quote for the remote shell
+fn shell_quote(s: &str) -> String {
+    if !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@,+".contains(c))
+    {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Combine the runners' snapshots: counts and rates add up, latency
+/// percentiles come from the runners' histograms added together, or are
+/// weighted by each runner's requests when one has no histogram
+pub fn merge_snapshots(parts: &[StatsSnapshot]) -> StatsSnapshot {
+    let mut merged = StatsSnapshot::default();
+    let total: u64 = parts.iter().map(|p| p.total_requests).sum();
+    let weight = |p: &StatsSnapshot| {
+        if total > 0 {
+            p.total_requests as f64 / total as f64
+        } else {
+            1.0 / parts.len() as f64
+        }
+    };
+    let weighted = |value: fn(&StatsSnapshot) -> u64| {
+        parts
+            .iter()
+            .map(|p| value(p) as f64 * weight(p))
+            .sum::<f64>()
+            .round() as u64
+    };
+
+    for part in parts {
+        merged.elapsed = merged.elapsed.max(part.elapsed);
+        merged.total_requests += part.total_requests;
+        merged.successful += part.successful;
+        merged.failed += part.failed;
+        merged.bytes_received += part.bytes_received;
+        merged.bytes_sent += part.bytes_sent;
+        merged.rolling_rps += part.rolling_rps;
+        merged.rolling_rx_bps += part.rolling_rx_bps;
+        merged.rolling_tx_bps += part.rolling_tx_bps;
+        merged.requests_per_sec += part.requests_per_sec;
+        merged.dropped_iterations += part.dropped_iterations;
+        merged.vus_active += part.vus_active;
+        merged.vus_max += part.vus_max;
+        merged.target_rate += part.target_rate;
+        merged.results_dropped += part.results_dropped;
+        merged.latency_max_us = merged.latency_max_us.max(part.latency_max_us);
+        for (code, count) in &part.status_codes {
+            *merged.status_codes.entry(*code).or_default() += count;
+        }
+        for (kind, count) in &part.errors {
+            *merged.errors.entry(*kind).or_default() += count;
+        }
+        for (code, count) in &part.jsonrpc_errors {
+            *merged.jsonrpc_errors.entry(*code).or_default() += count;
+        }
+    }
+    if merged.total_requests > 0 {
+        merged.error_rate = merged.failed as f64 / merged.total_requests as f64;
+    }
+    merged.latency_min_us = parts
+        .iter()
+        .filter(|p| p.total_requests > 0)
+        .map(|p| p.latency_min_us)
+        .min()
+        .unwrap_or(0);
+    merged.latency_mean_us = parts.iter().map(|p| p.latency_mean_us * weight(p)).sum();
+    // Pooled over the runners: E[x²] - E[x]²
+    let second_moment: f64 = parts
+        .iter()
+        .map(|p| (p.latency_stddev_us.powi(2) + p.latency_mean_us.powi(2)) * weight(p))
+        .sum();
+    merged.latency_stddev_us = (second_moment - merged.latency_mean_us.powi(2))
+        .max(0.0)
+        .sqrt();
+    merged.timeline = merge_timelines(parts);
+
+    let histograms: Option<Vec<&Histogram<u64>>> = parts
+        .iter()
+        .map(|p| p.latency_histogram.as_deref())
+        .collect();
+    if let Some((first, rest)) = histograms.as_deref().and_then(|h| h.split_first()) {
+        let mut histogram = (*first).clone();
+        for h in rest {
+            // Only fails past the histogram's bounds, which runners share
+            let _ = histogram.add(*h);
+        }
+        let at = |p: f64| histogram.value_at_percentile(p);
+        merged.latency_p50_us = at(50.0);
+        merged.latency_p75_us = at(75.0);
+        merged.latency_p90_us = at(90.0);
+        merged.latency_p95_us = at(95.0);
+        merged.latency_p99_us = at(99.0);
+        merged.latency_p999_us = at(99.9);
+        merged.latency_percentiles = parts[0]
+            .latency_percentiles
+            .iter()
+            .map(|&(percentile, _)| (percentile, at(percentile)))
+            .collect();
+        merged.latency_histogram = Some(Arc::new(histogram));
+        return merged;
+    }
+
+    merged.latency_p50_us = weighted(|p| p.latency_p50_us);
+    merged.latency_p75_us = weighted(|p| p.latency_p75_us);
+    merged.latency_p90_us = weighted(|p| p.latency_p90_us);
+    merged.latency_p95_us = weighted(|p| p.latency_p95_us);
+    merged.latency_p99_us = weighted(|p| p.latency_p99_us);
+    merged.latency_p999_us = weighted(|p| p.latency_p999_us);
+    if let Some(first) = parts.first() {
+        merged.latency_percentiles = first
+            .latency_percentiles
+            .iter()
+            .enumerate()
+            .map(|(i, &(percentile, _))| {
+                let latency = parts
+                    .iter()
+                    .filter_map(|p| Some(p.latency_percentiles.get(i)?.1 as f64 * weight(p)))
+                    .sum::<f64>();
+                (percentile, latency.round() as u64)
+            })
+            .collect();
+    }
+    merged
+}
+
+/// Add up the runners' timeline buckets by start time
+fn merge_timelines(parts: &[StatsSnapshot]) -> Vec<TimelineBucket> {
+    let mut buckets: BTreeMap<u64, Vec<&TimelineBucket>> = BTreeMap::new();
+    for bucket in parts.iter().flat_map(|p| &p.timeline) {
+        buckets.entry(bucket.elapsed_ms).or_default().push(bucket);
+    }
+    buckets
+        .into_values()
+        .map(|same| {
+            let requests: u64 = same.iter().map(|b| b.requests).sum();
+            let weighted = |value: fn(&TimelineBucket) -> u64| {
+                if requests == 0 {
+                    return 0;
+                }
+                let sum: f64 = same
+                    .iter()
+                    .map(|b| value(b) as f64 * b.requests as f64)
+                    .sum();
+                (sum / requests as f64).round() as u64
+            };
+            TimelineBucket {
+                elapsed_secs: same[0].elapsed_secs,
+                elapsed_ms: same[0].elapsed_ms,
+                requests,
+                errors: same.iter().map(|b| b.errors).sum(),
+                bytes_received: same.iter().map(|b| b.bytes_received).sum(),
+                bytes_sent: same.iter().map(|b| b.bytes_sent).sum(),
+                latency_p50_us: weighted(|b| b.latency_p50_us),
+                latency_p95_us: weighted(|b| b.latency_p95_us),
+                latency_p99_us: weighted(|b| b.latency_p99_us),
+                checks: same.iter().flat_map(|b| &b.checks).fold(
+                    BTreeMap::new(),
+                    |mut checks, (name, (passed, total))| {
+                        let counts: &mut (u64, u64) = checks.entry(name.clone()).or_default();
+                        counts.0 += passed;
+                        counts.1 += total;
+                        checks
+                    },
+                ),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_runner_urls() {
+        assert_eq!(
+            Runner::parse("ssh://deploy@10.0.0.5:2222").unwrap(),
+            Runner {
+                user: Some("deploy".to_string()),
+                host: "10.0.0.5".to_string(),
+                port: 2222,
+            }
+        );
+        let runner = Runner::parse("ssh://[::1]").unwrap();
+        assert_eq!((runner.host.as_str(), runner.port), ("::1", 22));
+        assert_eq!(runner.scp_target("/tmp/k"), "[::1]:/tmp/k");
+        assert!(Runner::parse("http://host").is_err());
+        assert!(Runner::parse("ssh://host/path").is_err());
+    }
+
+    #[test]
+    fn remote_args_drop_coordinator_flags() {
+        let args = strings(&[
+            "https://example.com",
+            "-c",
+            "20",
+            "--runners",
+            "ssh://a,ssh://b",
+            "-o",
+            "out.json",
+            "--format=md",
+            "-f",
+            "local.toml",
+            "-y",
+            "-H",
+            "X-Test: it's",
+        ]);
+        assert_eq!(
+            remote_args(&args, Some("/tmp/k.toml")),
+            strings(&[
+                "https://example.com",
+                "-c",
+                "20",
+                "--config",
+                "/tmp/k.toml",
+                "-H",
+                "X-Test: it's",
+            ])
+        );
+
+        // Short flags with their values attached, and clustered
+        let args = strings(&[
+            "-flocal.toml",
+            "-oout.json",
+            "-yqc20",
+            "--rate=10",
+            "-H",
+            "-o: not a flag",
+        ]);
+        assert_eq!(
+            remote_args(&args, Some("/tmp/k.toml")),
+            strings(&[
+                "--config",
+                "/tmp/k.toml",
+                "-c20",
+                "--rate=10",
+                "-H",
+                "-o: not a flag",
+            ])
+        );
+        assert_eq!(
+            remote_args(&strings(&["-f=local.toml", "-o=out.json"]), None),
+            Vec::<String>::new()
+        );
+        assert_eq!(shell_quote("X-Test: it's"), r"'X-Test: it'\''s'");
+        assert_eq!(shell_quote("--rate=10"), "--rate=10");
+    }
+
+    #[test]
+    fn merges_counts_and_weights_latency() {
+        let a = StatsSnapshot {
+            elapsed: Duration::from_secs(10),
+            total_requests: 300,
+            successful: 300,
+            requests_per_sec: 30.0,
+            latency_min_us: 1_000,
+            latency_max_us: 5_000,
+            latency_mean_us: 2_000.0,
+            latency_p99_us: 4_000,
+            status_codes: HashMap::from([(200, 300)]),
+            ..Default::default()
+        };
+        let b = StatsSnapshot {
+            elapsed: Duration::from_secs(11),
+            total_requests: 100,
+            successful: 50,
+            failed: 50,
+            requests_per_sec: 10.0,
+            latency_min_us: 500,
+            latency_max_us: 9_000,
+            latency_mean_us: 6_000.0,
+            latency_p99_us: 8_000,
+            status_codes: HashMap::from([(200, 50), (500, 50)]),
+            ..Default::default()
+        };
+
+        let merged = merge_snapshots(&[a, b]);
+        assert_eq!(merged.elapsed, Duration::from_secs(11));
+        assert_eq!(merged.total_requests, 400);
+        assert_eq!(merged.requests_per_sec, 40.0);
+        assert_eq!(merged.error_rate, 0.125);
+        assert_eq!((merged.latency_min_us, merged.latency_max_us), (500, 9_000));
+        assert_eq!(merged.latency_mean_us, 3_000.0);
+        assert_eq!(merged.latency_p99_us, 5_000);
+        assert_eq!(merged.status_codes[&200], 350);
+        assert_eq!(merged.status_codes[&500], 50);
+    }
+
+    fn with_latencies(latencies_us: &[u64]) -> StatsSnapshot {
+        let mut histogram = Histogram::<u64>::new(3).unwrap();
+        for &latency in latencies_us {
+            histogram.record(latency).unwrap();
+        }
+        StatsSnapshot {
+            total_requests: latencies_us.len() as u64,
+            latency_percentiles: vec![(99.0, histogram.value_at_percentile(99.0))],
+            latency_histogram: Some(Arc::new(histogram)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merges_latency_histograms() {
+        // 2% of all requests are slow, so the merged p99 is slow although
+        // the busy runner's p99 is fast
+        let fast = with_latencies(&[10_000; 980]);
+        let slow = with_latencies(&[1_000_000; 20]);
+
+        let merged = merge_snapshots(&[fast.clone(), slow]);
+        assert_eq!(merged.latency_p50_us / 1000, 10);
+        assert_eq!(merged.latency_p99_us / 1000, 1_000);
+        assert_eq!(merged.latency_percentiles[0].0, 99.0);
+        assert_eq!(merged.latency_percentiles[0].1 / 1000, 1_000);
+        assert_eq!(merged.latency_histogram.unwrap().len(), 1_000);
+
+        // One runner without a histogram: weighted fallback
+        let old = StatsSnapshot {
+            total_requests: 20,
+            latency_p99_us: 1_000_000,
+            ..Default::default()
+        };
+        let merged = merge_snapshots(&[fast, old]);
+        assert!(merged.latency_histogram.is_none());
+        assert!(merged.latency_p99_us < 100_000);
+    }
+
+    #[test]
+    fn latency_histogram_survives_the_json_line() {
+        let config = LoadConfig::default();
+        let output = create_output(&with_latencies(&[2_000, 4_000]), &config, None, None);
+        let line = serde_json::to_string(&output).unwrap();
+        let (output, _) = read_result(&line).unwrap();
+        let histogram = output.to_snapshot().latency_histogram.unwrap();
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram.max() / 1000, 4);
+    }
+
+    #[test]
+    fn runners_that_end_abnormally_fail() {
+        let config = LoadConfig::default();
+        let done = |exit_code, stderr: &str| Finished {
+            runner: Runner::parse("ssh://load1").unwrap(),
+            exit_code,
+            stderr: stderr.to_string(),
+            result: Some(create_output(
+                &StatsSnapshot::default(),
+                &config,
+                None,
+                None,
+            )),
+        };
+        assert_eq!(done(Some(0), "").failure(false), None);
+        assert_eq!(done(Some(4), "").failure(false), None);
+        assert_eq!(
+            done(Some(101), "thread 'main' panicked").failure(false),
+            Some("exited with status 101: thread 'main' panicked".to_string())
+        );
+        assert_eq!(
+            done(Some(255), "").failure(false),
+            Some("exited with status 255".to_string())
+        );
+        assert!(done(None, "").failure(false).is_some());
+        assert_eq!(done(None, "").failure(true), None);
+
+        let silent = Finished {
+            result: None,
+            ..done(Some(0), "")
+        };
+        assert_eq!(silent.failure(true), Some("no output".to_string()));
+    }
+}
@@ -0,0 +1,228 @@
+//! Trend checks for scheduled runs (`run --every`) and `_trend` thresholds
+//!
+//! Each run is judged against the median of the runs before it rather than a
+//! fixed baseline, so gradual drift shows up and a single noisy run doesn't
+//! set the bar for the next one.
+
+use crate::engine::metric_value;
+use crate::history;
+use crate::output::json::JsonOutput;
+use crate::types::{LoadConfig, StatsSnapshot, ThresholdResult, TrendThreshold};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendCheck {
+    pub metric: &'static str,
+    /// Median over the trend window
+    pub baseline: f64,
+    pub current: f64,
+    /// Change relative to the baseline; positive means worse
+    pub worse_pct: f64,
+    pub regressed: bool,
+}
+
+/// Compare `current` against the median of `window`; empty until there is history
+pub fn evaluate_trend(
+    window: &[JsonOutput],
+    current: &JsonOutput,
+    threshold_pct: f64,
+) -> Vec<TrendCheck> {
+    if window.is_empty() {
+        return Vec::new();
+    }
+    vec![
+        check(
+            "p99_latency_ms",
+            window,
+            current,
+            |o| o.latency_us.p99 as f64 / 1000.0,
+            false,
+            threshold_pct,
+        ),
+        check(
+            "error_rate_pct",
+            window,
+            current,
+            |o| o.summary.error_rate * 100.0,
+            false,
+            threshold_pct,
+        ),
+        check(
+            "rps",
+            window,
+            current,
+            |o| o.summary.requests_per_sec,
+            true,
+            threshold_pct,
+        ),
+    ]
+}
+
+fn check(
+    metric: &'static str,
+    window: &[JsonOutput],
+    current: &JsonOutput,
+    value: impl Fn(&JsonOutput) -> f64,
+    higher_is_better: bool,
+    threshold_pct: f64,
+) -> TrendCheck {
+    let baseline = median(window.iter().map(&value).collect());
+    let current = value(current);
+    let worse = if higher_is_better {
+        baseline - current
+    } else {
+        current - baseline
+    };
+    let worse_pct = if baseline > 0.0 {
+        worse / baseline * 100.0
+    } else if worse > 0.0 {
+        100.0 // Any errors when the baseline had none is bad
+    } else {
+        0.0
+    };
+    TrendCheck {
+        metric,
+        baseline,
+        current,
+        worse_pct,
+        regressed: worse_pct > threshold_pct,
+    }
+}
+
+/// Evaluate `config.trend_thresholds` against the runs stored in history.
+/// Without history to compare against they're skipped with a note.
+pub fn check_trend_thresholds(
+    config: &LoadConfig,
+    snapshot: &StatsSnapshot,
+) -> Vec<ThresholdResult> {
+    let Some(widest) = config.trend_thresholds.iter().map(|t| t.window).max() else {
+        return Vec::new();
+    };
+    let Some(ref path) = config.db_url else {
+        eprintln!("⚠️  Trend thresholds compare against run history; skipped without --db-url");
+        return Vec::new();
+    };
+    let now = Utc::now();
+    let since = chrono::Duration::from_std(widest)
+        .ok()
+        .and_then(|widest| now.checked_sub_signed(widest))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC);
+    let history = match history::results_since(path, &config.url, since) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("⚠️  Trend thresholds skipped: {}", e);
+            return Vec::new();
+        }
+    };
+    let history: Vec<(DateTime<Utc>, StatsSnapshot)> = history
+        .into_iter()
+        .map(|(started_at, result)| (started_at, result.to_snapshot()))
+        .collect();
+
+    let mut results = Vec::new();
+    for threshold in &config.trend_thresholds {
+        let since = chrono::Duration::from_std(threshold.window)
+            .ok()
+            .and_then(|window| now.checked_sub_signed(window))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let window: Vec<&StatsSnapshot> = history
+            .iter()
+            .filter(|(started_at, _)| *started_at >= since)
+            .map(|(_, snapshot)| snapshot)
+            .collect();
+        match evaluate_trend_threshold(threshold, &window, snapshot) {
+            Some(result) => results.push(result),
+            None => eprintln!(
+                "Trend threshold {}_trend skipped: no runs against {} in the last {}",
+                threshold.metric,
+                config.url,
+                humantime::format_duration(threshold.window)
+            ),
+        }
+    }
+    results
+}
+
+/// The change from the median of `window` in percent, checked against the
+/// threshold; None without runs to take a median of
+fn evaluate_trend_threshold(
+    threshold: &TrendThreshold,
+    window: &[&StatsSnapshot],
+    current: &StatsSnapshot,
+) -> Option<ThresholdResult> {
+    if window.is_empty() {
+        return None;
+    }
+    let baseline = median(
+        window
+            .iter()
+            .map(|snapshot| metric_value(&threshold.metric, snapshot))
+            .collect(),
+    );
+    let current = metric_value(&threshold.metric, current);
+    let change_pct = if baseline > 0.0 {
+        (current - baseline) / baseline * 100.0
+    } else if current > 0.0 {
+        100.0 // Anything against a baseline of nothing
+    } else {
+        0.0
+    };
+    Some(ThresholdResult {
+        metric: format!("{}_trend", threshold.metric),
+        condition: format!(
+            "{}_trend {} {:+}% over {} (median {:.2} of {} runs)",
+            threshold.metric,
+            threshold.operator.as_str(),
+            threshold.change_pct,
+            humantime::format_duration(threshold.window),
+            baseline,
+            window.len()
+        ),
+        actual: change_pct,
+        passed: threshold
+            .operator
+            .evaluate(change_pct, threshold.change_pct),
+    })
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// One line per scheduled run, plus a line for each metric that regressed
+pub fn print_run(run: u32, output: &JsonOutput, checks: &[TrendCheck], exit_code: i32) {
+    let status = match exit_code {
+        0 => "✓",
+        _ => "✗",
+    };
+    let trend = if checks.is_empty() {
+        "no trend yet".to_string()
+    } else if checks.iter().any(|c| c.regressed) {
+        "trend regression".to_string()
+    } else {
+        "within trend".to_string()
+    };
+    println!(
+        "{} Run {}: {:.1} req/s, p99 {:.2}ms, errors {:.2}% - {} (exit {})",
+        status,
+        run,
+        output.summary.requests_per_sec,
+        output.latency_us.p99 as f64 / 1000.0,
+        output.summary.error_rate * 100.0,
+        trend,
+        exit_code
+    );
+    for check in checks.iter().filter(|c| c.regressed) {
+        println!(
+            "    {}: {:.2} vs median {:.2} ({:+.1}% worse)",
+            check.metric, check.current, check.baseline, check.worse_pct
+        );
+    }
+}
@@ -0,0 +1,512 @@
+//! `kaioken serve`: web dashboard and REST control API
+//!
+//! Drives load tests on a remote generator box where the TUI isn't usable.
+//! One run at a time; the latest run's live stats and final result stay
+//! available until the next one starts.
+
+pub(crate) mod http;
+
+use crate::cli::{RunArgs, ServeArgs};
+use crate::config::{merge_config, parse_config};
+use crate::engine::{Engine, apply_check_stats, evaluate_thresholds};
+use crate::history;
+use crate::output::json::create_output;
+use crate::types::{LoadConfig, RunPhase, RunState, StatsSnapshot};
+use chrono::{DateTime, Utc};
+use http::{Request, Response, read_request};
+use serde_json::{Value, json};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+const DASHBOARD: &str = include_str!("dashboard.html");
+
+/// How often live stats are pushed to event stream clients
+const EVENT_INTERVAL: Duration = Duration::from_millis(500);
+
+struct Server {
+    token: Option<String>,
+    /// `--bind` as given and `--allow-host` names, besides the address a
+    /// connection came in on
+    allowed_hosts: Vec<String>,
+    current: Mutex<Option<Arc<Run>>>,
+    next_id: AtomicU64,
+}
+
+struct Run {
+    id: u64,
+    config: LoadConfig,
+    started_at: DateTime<Utc>,
+    cancel: CancellationToken,
+    /// Set by `POST /api/stop`; the engine cancels its own token at the end
+    /// of every run, so its final state can't tell the two apart
+    stopped: AtomicBool,
+    snapshot_rx: watch::Receiver<StatsSnapshot>,
+    state_rx: watch::Receiver<RunState>,
+    phase_rx: watch::Receiver<RunPhase>,
+    outcome_rx: watch::Receiver<Option<Outcome>>,
+}
+
+#[derive(Clone)]
+enum Outcome {
+    /// Exit code as `kaioken run` would return it, plus the JSON result
+    Finished {
+        exit_code: i32,
+        result: Arc<Value>,
+    },
+    Failed(String),
+}
+
+pub async fn run_serve(args: &ServeArgs) -> Result<i32, String> {
+    let listener = TcpListener::bind(&args.bind)
+        .await
+        .map_err(|e| format!("Failed to bind {}: {}", args.bind, e))?;
+    let addr = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to bind {}: {}", args.bind, e))?;
+
+    eprintln!("kaioken serve listening on http://{}", addr);
+    if args.token.is_none() && !addr.ip().is_loopback() {
+        eprintln!(
+            "⚠️  No --token set: anyone who can reach {} can start load tests",
+            addr
+        );
+    }
+
+    let server = Arc::new(Server {
+        token: args.token.clone(),
+        allowed_hosts: std::iter::once(args.bind.clone())
+            .chain(args.allow_hosts.iter().cloned())
+            .collect(),
+        current: Mutex::new(None),
+        next_id: AtomicU64::new(1),
+    });
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                if let Some(run) = server.current() {
+                    run.cancel.cancel();
+                }
+                break;
+            }
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let server = server.clone();
+                    tokio::spawn(async move { server.handle(stream).await });
+                }
+                Err(e) => tracing::warn!("Failed to accept connection: {}", e),
+            }
+        }
+    }
+
+    Ok(0)
+}
+
+impl Server {
+    fn current(&self) -> Option<Arc<Run>> {
+        self.current
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    async fn handle(&self, mut stream: TcpStream) {
+        let request = match read_request(&mut stream).await {
+            Ok(Some(request)) => request,
+            _ => return,
+        };
+
+        // A web page the operator has open can reach this server too, by a
+        // cross-origin request or by rebinding its own name to this address
+        let local = stream.local_addr().ok();
+        let Some(host) = request
+            .header("host")
+            .filter(|host| self.host_allowed(host, local))
+        else {
+            let _ = Response::error(403, "Host not allowed")
+                .write(&mut stream)
+                .await;
+            return;
+        };
+        if request
+            .header("origin")
+            .is_some_and(|origin| origin != format!("http://{}", host))
+        {
+            let _ = Response::error(403, "Cross-origin requests are not allowed")
+                .write(&mut stream)
+                .await;
+            return;
+        }
+
+        if request.path.starts_with("/api/") && !self.authorized(&request) {
+            let _ = Response::error(401, "Missing or invalid token")
+                .write(&mut stream)
+                .await;
+            return;
+        }
+
+        let response = match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/") => Response::html(DASHBOARD),
+            ("GET", "/health") => Response::text(200, "OK"),
+            ("GET", "/api/status") => Response::json(200, &status_json(self.current().as_deref())),
+            ("GET", "/api/events") => return self.stream_events(stream).await,
+            ("GET", "/api/stream") => return self.stream_run(stream).await,
+            ("GET", "/api/result") => self.result(),
+            ("POST", "/api/runs") => self.start(&request),
+            ("POST", "/api/stop") => self.stop(),
+            (
+                _,
+                "/" | "/health" | "/api/status" | "/api/events" | "/api/stream" | "/api/result"
+                | "/api/runs" | "/api/stop",
+            ) => Response::error(405, "Method not allowed"),
+            _ => Response::error(404, "Not found"),
+        };
+        let _ = response.write(&mut stream).await;
+    }
+
+    /// `Host` is the address the connection came in on (`localhost` too, on
+    /// loopback), `--bind` as given, or an `--allow-host` name
+    fn host_allowed(&self, host: &str, local: Option<SocketAddr>) -> bool {
+        // Without the port; IPv6 addresses come in brackets
+        let name = match host.rfind(']') {
+            Some(end) => &host[..=end],
+            None => host.split(':').next().unwrap_or(host),
+        };
+        let local_match = local.is_some_and(|addr| {
+            host == addr.to_string()
+                || addr.ip().is_loopback() && host == format!("localhost:{}", addr.port())
+        });
+        local_match
+            || self
+                .allowed_hosts
+                .iter()
+                .any(|allowed| allowed == host || allowed == name)
+    }
+
+    fn authorized(&self, request: &Request) -> bool {
+        let Some(ref token) = self.token else {
+            return true;
+        };
+        let bearer = request
+            .header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::to_string);
+        bearer
+            .or_else(|| request.query_param("token"))
+            .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+    }
+
+    /// Start a run from a TOML config (the same format as `kaioken run -f`)
+    fn start(&self, request: &Request) -> Response {
+        // Browsers can't send this cross-origin without asking first
+        let media_type = request
+            .header("content-type")
+            .and_then(|v| v.split(';').next())
+            .map(|v| v.trim().to_ascii_lowercase());
+        if media_type.as_deref() != Some("application/toml") {
+            return Response::error(415, "Send the config as Content-Type: application/toml");
+        }
+
+        let config = match std::str::from_utf8(&request.body)
+            .map_err(|_| "Config must be UTF-8 TOML".to_string())
+            .and_then(parse_config)
+            .and_then(|toml| {
+                if toml.has_includes() {
+                    return Err(
+                        "extends/include refer to files on the client; send the merged config"
+                            .to_string(),
+                    );
+                }
+                merge_config(&RunArgs::default(), Some(toml))
+            })
+            .and_then(|config| match writes_files(&config) {
+                Some(option) => Err(format!(
+                    "{} writes files on the server, which configs sent over HTTP can't do",
+                    option
+                )),
+                None => Ok(config),
+            }) {
+            Ok(config) => config,
+            Err(e) => return Response::error(400, &e),
+        };
+
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        if current
+            .as_ref()
+            .is_some_and(|run| run.outcome_rx.borrow().is_none())
+        {
+            return Response::error(409, "A run is already in progress");
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let run = Arc::new(spawn_run(id, config));
+        tracing::info!("Started run {} against {}", id, run.config.url);
+        *current = Some(run);
+
+        Response::json(202, &json!({ "id": id }))
+    }
+
+    fn stop(&self) -> Response {
+        match self.current() {
+            Some(run) if run.outcome_rx.borrow().is_none() => {
+                run.stopped.store(true, Ordering::Relaxed);
+                run.cancel.cancel();
+                Response::json(200, &json!({ "id": run.id, "stopping": true }))
+            }
+            _ => Response::error(409, "No run in progress"),
+        }
+    }
+
+    /// Final result of the latest run, in `--format json` layout
+    fn result(&self) -> Response {
+        let Some(run) = self.current() else {
+            return Response::error(404, "No runs yet");
+        };
+        let outcome = run.outcome_rx.borrow().clone();
+        match outcome {
+            Some(Outcome::Finished { result, .. }) => Response::json(200, &result),
+            Some(Outcome::Failed(e)) => Response::error(409, &format!("Run failed: {}", e)),
+            None => Response::error(409, "Run still in progress"),
+        }
+    }
+
+    /// Server-sent events: the status object every `EVENT_INTERVAL` until the
+    /// client disconnects
+    async fn stream_events(&self, mut stream: TcpStream) {
+        let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+        if stream.write_all(head.as_bytes()).await.is_err() {
+            return;
+        }
+        let mut interval = tokio::time::interval(EVENT_INTERVAL);
+        loop {
+            interval.tick().await;
+            let event = format!(
+                "event: status\ndata: {}\n\n",
+                status_json(self.current().as_deref())
+            );
+            if stream.write_all(event.as_bytes()).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Server-sent events for `kaioken attach`: the status and result so far
+    /// of the latest run (or the next one, once started) every
+    /// `EVENT_INTERVAL`, ending after its final result
+    async fn stream_run(&self, mut stream: TcpStream) {
+        let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+        if stream.write_all(head.as_bytes()).await.is_err() {
+            return;
+        }
+        let mut interval = tokio::time::interval(EVENT_INTERVAL);
+        let run = loop {
+            interval.tick().await;
+            if let Some(run) = self.current() {
+                break run;
+            }
+            if stream.write_all(b": waiting for a run\n\n").await.is_err() {
+                return;
+            }
+        };
+        loop {
+            // Read first: once finished, the frame carries the final result
+            let outcome = run.outcome_rx.borrow().clone();
+            let result = match outcome {
+                Some(Outcome::Finished { ref result, .. }) => Value::clone(result),
+                _ => serde_json::to_value(create_output(
+                    &run.snapshot_rx.borrow(),
+                    &run.config,
+                    None,
+                    None,
+                ))
+                .unwrap_or_default(),
+            };
+            let event = format!(
+                "event: run\ndata: {}\n\n",
+                json!({ "status": status_json(Some(&run)), "result": result })
+            );
+            if stream.write_all(event.as_bytes()).await.is_err() || outcome.is_some() {
+                return;
+            }
+            interval.tick().await;
+        }
+    }
+}
+
+/// The option of a config that would write to the server's disk, if any
+fn writes_files(config: &LoadConfig) -> Option<&'static str> {
+    if config.slow_log.is_some() {
+        Some("slow_log")
+    } else if config.db_url.is_some() {
+        Some("db_url")
+    } else {
+        None
+    }
+}
+
+/// Compare without returning early, so timing doesn't tell how much of a
+/// guessed token was right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn spawn_run(id: u64, config: LoadConfig) -> Run {
+    let engine = Engine::new(config.clone());
+    let cancel = engine.cancel_token();
+    let snapshot_rx = engine.snapshot_rx();
+    let state_rx = engine.state_rx();
+    let phase_rx = engine.phase_rx();
+    let fail_fast_flag = engine.threshold_failed_flag();
+    let check_stats_ref = engine.check_stats_ref();
+    let history_run = engine.history_run();
+    let (outcome_tx, outcome_rx) = watch::channel(None);
+
+    let run_config = config.clone();
+    let final_snapshot_rx = snapshot_rx.clone();
+    tokio::spawn(async move {
+        let outcome = match engine.run().await {
+            Ok(stats) => {
+                let mut snapshot = final_snapshot_rx.borrow().clone();
+                let check_stats = check_stats_ref
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clone();
+                apply_check_stats(&mut snapshot, &check_stats);
+
+                let threshold_results = evaluate_thresholds(&run_config.thresholds, &snapshot);
+                let thresholds_passed = threshold_results.iter().all(|r| r.passed);
+                let exit_code = if !thresholds_passed || fail_fast_flag.load(Ordering::Relaxed) {
+                    4
+                } else if stats.failed > 0 && stats.error_rate() > 0.5 {
+                    1
+                } else {
+                    0
+                };
+
+                let output = create_output(
+                    &snapshot,
+                    &run_config,
+                    (!threshold_results.is_empty()).then_some(threshold_results.as_slice()),
+                    (!check_stats.is_empty()).then_some(&check_stats),
+                );
+                if let Some(ref run) = history_run
+                    && let Err(e) = history::finish_run(run, &output, exit_code)
+                {
+                    tracing::warn!("{}", e);
+                }
+                match serde_json::to_value(&output) {
+                    Ok(result) => Outcome::Finished {
+                        exit_code,
+                        result: Arc::new(result),
+                    },
+                    Err(e) => Outcome::Failed(format!("Failed to serialize result: {}", e)),
+                }
+            }
+            Err(e) => Outcome::Failed(e),
+        };
+        tracing::info!("Run {} finished", id);
+        let _ = outcome_tx.send(Some(outcome));
+    });
+
+    Run {
+        id,
+        config,
+        started_at: Utc::now(),
+        cancel,
+        stopped: AtomicBool::new(false),
+        snapshot_rx,
+        state_rx,
+        phase_rx,
+        outcome_rx,
+    }
+}
+
+fn status_json(run: Option<&Run>) -> Value {
+    let Some(run) = run else {
+        return json!({ "state": "idle" });
+    };
+
+    let outcome = run.outcome_rx.borrow().clone();
+    let engine_state = *run.state_rx.borrow();
+    let state = match outcome {
+        Some(Outcome::Failed(_)) => "failed",
+        Some(Outcome::Finished { .. }) if run.stopped.load(Ordering::Relaxed) => "cancelled",
+        Some(Outcome::Finished { .. }) => "completed",
+        // The result is still being put together
+        None if engine_state.is_terminal() => "stopping",
+        _ => state_str(engine_state),
+    };
+    let mut status = json!({
+        "id": run.id,
+        "state": state,
+        "url": run.config.url,
+        "started_at": run.started_at.to_rfc3339(),
+        "duration_secs": run.config.duration.as_secs_f64(),
+        "warmup": *run.phase_rx.borrow() == RunPhase::Warmup,
+        "live": live_json(&run.snapshot_rx.borrow()),
+    });
+    match outcome {
+        Some(Outcome::Finished { exit_code, .. }) => status["exit_code"] = json!(exit_code),
+        Some(Outcome::Failed(e)) => status["error"] = json!(e),
+        None => {}
+    }
+    status
+}
+
+pub(crate) fn state_str(state: RunState) -> &'static str {
+    match state {
+        RunState::Initializing => "starting",
+        RunState::Running => "running",
+        RunState::Paused => "paused",
+        RunState::Stopping => "stopping",
+        RunState::Completed => "completed",
+        RunState::Cancelled => "cancelled",
+        RunState::Error => "failed",
+    }
+}
+
+/// The state `state_str` named
+pub(crate) fn parse_state(state: &str) -> Option<RunState> {
+    Some(match state {
+        "starting" => RunState::Initializing,
+        "running" => RunState::Running,
+        "paused" => RunState::Paused,
+        "stopping" => RunState::Stopping,
+        "completed" => RunState::Completed,
+        "cancelled" => RunState::Cancelled,
+        "failed" => RunState::Error,
+        _ => return None,
+    })
+}
+
+pub(crate) fn live_json(snapshot: &StatsSnapshot) -> Value {
+    json!({
+        "elapsed_secs": snapshot.elapsed.as_secs_f64(),
+        "total_requests": snapshot.total_requests,
+        "successful": snapshot.successful,
+        "failed": snapshot.failed,
+        "rps": snapshot.rolling_rps,
+        "requests_per_sec": snapshot.requests_per_sec,
+        "error_rate": snapshot.error_rate,
+        "bytes_received": snapshot.bytes_received,
+        "bytes_sent": snapshot.bytes_sent,
+        "rx_mbps": snapshot.rolling_rx_bps / 1_000_000.0,
+        "tx_mbps": snapshot.rolling_tx_bps / 1_000_000.0,
+        "vus_active": snapshot.vus_active,
+        "vus_max": snapshot.vus_max,
+        "target_rate": snapshot.target_rate,
+        "latency_ms": {
+            "p50": snapshot.latency_p50_us as f64 / 1000.0,
+            "p90": snapshot.latency_p90_us as f64 / 1000.0,
+            "p99": snapshot.latency_p99_us as f64 / 1000.0,
+            "max": snapshot.latency_max_us as f64 / 1000.0,
+        },
+        "status_codes": snapshot.status_codes,
+    })
+}
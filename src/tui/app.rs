@@ -1,4 +1,4 @@
-use crate::output::write_json;
+use crate::output::{planned_duration, write_json};
 use crate::tui::theme::ThemeMode;
 use crate::tui::{Flavor, Theme, ui};
 use crate::types::{LoadConfig, RunPhase, RunState, StatsSnapshot};
@@ -9,8 +9,9 @@ use crossterm::{
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::io::{self, stdout};
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::watch;
+use tokio::sync::{Notify, watch};
 use tokio_util::sync::CancellationToken;
 
 pub struct App {
@@ -23,6 +24,8 @@ pub struct App {
     theme_mode: ThemeMode,
     flavor: Flavor,
     output_path: Option<String>,
+    // Set when the config file can be reloaded; holds the last outcome
+    reload: Option<(Arc<Notify>, watch::Receiver<String>)>,
 }
 
 impl App {
@@ -46,9 +49,16 @@ impl App {
             theme_mode: ThemeMode::default(),
             flavor: Flavor::new(serious),
             output_path,
+            reload: None,
         }
     }
 
+    /// Reload the config file with `r`, showing the outcome in the footer
+    pub fn with_reload(mut self, requests: Arc<Notify>, status: watch::Receiver<String>) -> Self {
+        self.reload = Some((requests, status));
+        self
+    }
+
     pub async fn run(mut self) -> io::Result<()> {
         enable_raw_mode()?;
         let mut stdout = stdout();
@@ -76,6 +86,7 @@ impl App {
             let snapshot = self.snapshot_rx.borrow().clone();
             let state = *self.state_rx.borrow();
             let phase = *self.phase_rx.borrow();
+            let reload_status = self.reload.as_ref().map(|(_, rx)| rx.borrow().clone());
 
             terminal.draw(|frame| {
                 ui::render(
@@ -85,11 +96,13 @@ impl App {
                     phase,
                     &self.config.url,
                     self.config.concurrency,
-                    self.config.duration,
+                    planned_duration(&self.config),
+                    self.config.max_requests,
                     self.config.warmup,
                     &self.theme,
                     self.theme_mode,
                     &self.flavor,
+                    reload_status.as_deref(),
                 );
             })?;
 
@@ -118,6 +131,11 @@ impl App {
                                 let _ = write_json(&snapshot, &self.config, path, None, None);
                             }
                         }
+                        KeyCode::Char('r') => {
+                            if let Some((requests, _)) = &self.reload {
+                                requests.notify_one();
+                            }
+                        }
                         KeyCode::Char('t') => {
                             self.theme_mode = self.theme_mode.cycle();
                             self.theme = Theme::from_mode(self.theme_mode);
@@ -1,5 +1,6 @@
+use crate::output::{Projection, clock};
 use crate::tui::theme::ThemeMode;
-use crate::tui::widgets::{LatencyWidget, PowerWidget, StatusWidget};
+use crate::tui::widgets::{LatencyWidget, PowerWidget, StatusWidget, format_number};
 use crate::tui::{Flavor, Theme};
 use crate::types::{RunPhase, RunState, StatsSnapshot};
 use ratatui::{
@@ -19,10 +20,12 @@ pub fn render(
     config_url: &str,
     config_concurrency: u32,
     config_duration: Duration,
+    config_max_requests: u64,
     config_warmup: Duration,
     theme: &Theme,
     theme_mode: ThemeMode,
     flavor: &Flavor,
+    reload_status: Option<&str>,
 ) {
     let size = frame.area();
 
@@ -45,6 +48,7 @@ pub fn render(
         config_url,
         config_concurrency,
         config_duration,
+        config_max_requests,
         config_warmup,
         theme,
         flavor,
@@ -60,7 +64,16 @@ pub fn render(
 
     StatusWidget::new(snapshot, theme).render(frame, chunks[2]);
 
-    render_footer(frame, chunks[3], state, phase, theme, theme_mode, flavor);
+    render_footer(
+        frame,
+        chunks[3],
+        state,
+        phase,
+        theme,
+        theme_mode,
+        flavor,
+        reload_status,
+    );
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -73,6 +86,7 @@ fn render_header(
     url: &str,
     concurrency: u32,
     duration: Duration,
+    max_requests: u64,
     warmup: Duration,
     theme: &Theme,
     flavor: &Flavor,
@@ -101,9 +115,13 @@ fn render_header(
     };
 
     let time_display = if phase == RunPhase::Warmup && !warmup.is_zero() {
+        // Stats restart when warmup ends, so `elapsed` is warmup progress until then
         let warmup_secs = warmup.as_secs();
+        let warmed = elapsed.min(warmup_secs);
         format!(
-            "    [warmup {:02}:{:02}]",
+            "    [warmup {:02}:{:02}/{:02}:{:02}]",
+            warmed / 60,
+            warmed % 60,
             warmup_secs / 60,
             warmup_secs % 60
         )
@@ -117,11 +135,27 @@ fn render_header(
         )
     };
 
-    let header_line = Line::from(vec![
+    let mut spans = vec![
         Span::styled(format!("  {}    ", title), theme.title),
         Span::styled(truncated_url, theme.normal),
         Span::styled(time_display, theme.muted),
-    ]);
+    ];
+    // Where the run ends up at the current rates, once there are any
+    if state == RunState::Running && phase == RunPhase::Running && !snapshot.elapsed.is_zero() {
+        let projection = Projection::new(snapshot, duration, max_requests);
+        let unit = if snapshot.is_websocket { "msgs" } else { "req" };
+        spans.push(Span::styled(
+            format!(
+                "  ETA {}  ~{} {}  ~{}",
+                clock(projection.eta),
+                format_number(projection.total),
+                unit,
+                format_bytes(projection.bytes)
+            ),
+            theme.muted,
+        ));
+    }
+    let header_line = Line::from(spans);
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -131,6 +165,19 @@ fn render_header(
     frame.render_widget(paragraph, area);
 }
 
+/// `456 KB`, `12.3 MB`, `1.2 GB`
+fn format_bytes(bytes: u64) -> String {
+    let bytes = bytes as f64;
+    if bytes >= 1e9 {
+        format!("{:.1} GB", bytes / 1e9)
+    } else if bytes >= 1e6 {
+        format!("{:.1} MB", bytes / 1e6)
+    } else {
+        format!("{:.0} KB", bytes / 1e3)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn render_footer(
     frame: &mut Frame,
     area: Rect,
@@ -139,6 +186,7 @@ fn render_footer(
     theme: &Theme,
     theme_mode: ThemeMode,
     flavor: &Flavor,
+    reload_status: Option<&str>,
 ) {
     let status = match state {
         RunState::Initializing => Span::styled(flavor.status_initializing(), theme.muted),
@@ -157,9 +205,18 @@ fn render_footer(
     };
 
     let theme_indicator = Span::styled(format!("[{}]", theme_mode.name()), theme.highlight);
-    let help = Span::styled("  [q]uit  [s]ave  [t]heme", theme.muted);
-
-    let line = Line::from(vec![theme_indicator, help, Span::raw("    "), status]);
+    let help = match reload_status {
+        Some(_) => "  [q]uit  [s]ave  [t]heme  [r]eload",
+        None => "  [q]uit  [s]ave  [t]heme",
+    };
+    let help = Span::styled(help, theme.muted);
+
+    let mut spans = vec![theme_indicator, help, Span::raw("    "), status];
+    if let Some(message) = reload_status.filter(|m| !m.is_empty()) {
+        spans.push(Span::raw("    "));
+        spans.push(Span::styled(message.to_string(), theme.muted));
+    }
+    let line = Line::from(spans);
 
     let paragraph = Paragraph::new(line);
     frame.render_widget(paragraph, area);
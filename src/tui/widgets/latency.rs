@@ -27,11 +27,14 @@ impl<'a> LatencyWidget<'a> {
         let use_corrected = self.snapshot.latency_correction_enabled
             && self.snapshot.corrected_latency_p50_us.is_some();
 
-        let title = if use_corrected {
-            " LATENCY (ms) [corrected] "
+        let mut title = if use_corrected {
+            " LATENCY (ms) [corrected] ".to_string()
         } else {
-            " LATENCY (ms) "
+            " LATENCY (ms) ".to_string()
         };
+        if let Some(apdex) = self.snapshot.apdex {
+            title.push_str(&format!("· Apdex {:.2} ", apdex.score()));
+        }
 
         let block = Block::default()
             .title(title)
@@ -90,21 +93,39 @@ impl<'a> LatencyWidget<'a> {
     }
 
     fn render_websocket(&self, frame: &mut Frame, area: Rect) {
+        // Broadcast mode: the interesting latency is publish -> subscriber delivery
+        let is_broadcast = self.snapshot.ws_broadcast_expected > 0;
+        let title = if is_broadcast {
+            " WS DELIVERY LATENCY (ms) "
+        } else {
+            " WS MESSAGE LATENCY (ms) "
+        };
+
         let block = Block::default()
-            .title(" WS MESSAGE LATENCY (ms) ")
+            .title(title)
             .title_style(self.theme.header)
             .borders(Borders::ALL)
             .border_style(self.theme.border);
 
-        let p50 = self.snapshot.ws_latency_p50_us;
-        let p95 = self.snapshot.ws_latency_p95_us;
-        let p99 = self.snapshot.ws_latency_p99_us;
+        let (p50, p95, p99) = if is_broadcast {
+            (
+                self.snapshot.ws_delivery_latency_p50_us,
+                self.snapshot.ws_delivery_latency_p95_us,
+                self.snapshot.ws_delivery_latency_p99_us,
+            )
+        } else {
+            (
+                self.snapshot.ws_latency_p50_us,
+                self.snapshot.ws_latency_p95_us,
+                self.snapshot.ws_latency_p99_us,
+            )
+        };
         let connect_mean = self.snapshot.ws_connect_time_mean_us;
         let connect_p99 = self.snapshot.ws_connect_time_p99_us;
 
         let max_latency = p99.max(1) as f64;
 
-        let lines = vec![
+        let mut lines = vec![
             {
                 let ms = p50 as f64 / 1000.0;
                 let bar_width = ((p50 as f64 / max_latency) * 15.0) as usize;
@@ -152,6 +173,26 @@ impl<'a> LatencyWidget<'a> {
             ]),
         ];
 
+        if is_broadcast {
+            let loss = self.snapshot.ws_broadcast_loss_rate();
+            lines.push(Line::from(vec![
+                Span::styled("Delivered:    ", self.theme.muted),
+                Span::styled(
+                    format!(
+                        "{}/{}  loss {:.2}%",
+                        self.snapshot.ws_broadcast_delivered,
+                        self.snapshot.ws_broadcast_expected,
+                        loss * 100.0
+                    ),
+                    if loss > 0.01 {
+                        self.theme.warning
+                    } else {
+                        self.theme.normal
+                    },
+                ),
+            ]));
+        }
+
         let paragraph = Paragraph::new(lines).block(block);
         frame.render_widget(paragraph, area);
     }
@@ -3,5 +3,5 @@ mod power;
 mod status;
 
 pub use latency::LatencyWidget;
-pub use power::PowerWidget;
+pub use power::{PowerWidget, format_number};
 pub use status::StatusWidget;
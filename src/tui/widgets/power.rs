@@ -66,6 +66,21 @@ impl<'a> PowerWidget<'a> {
                         ),
                         self.theme.normal,
                     ),
+                    Span::styled(
+                        if self.snapshot.ws_reconnects > 0 {
+                            format!(
+                                "  {} reconnects ({} failed)",
+                                self.snapshot.ws_reconnects, self.snapshot.ws_reconnect_failures
+                            )
+                        } else {
+                            String::new()
+                        },
+                        if self.snapshot.ws_reconnect_failures > 0 {
+                            self.theme.warning
+                        } else {
+                            self.theme.muted
+                        },
+                    ),
                 ]),
                 Line::from(vec![
                     Span::styled("Messages:    ", self.theme.normal),
@@ -191,6 +206,8 @@ impl<'a> PowerWidget<'a> {
                         format!("{:>7}", format_number(self.snapshot.total_requests)),
                         self.theme.normal,
                     ),
+                    Span::styled("  Net: ", self.theme.normal),
+                    Span::styled(self.network_rate(), self.theme.muted),
                 ]),
             ]
         } else {
@@ -222,6 +239,8 @@ impl<'a> PowerWidget<'a> {
                         format!("{:>8}", format_number(self.snapshot.total_requests)),
                         self.theme.normal,
                     ),
+                    Span::styled("  Net: ", self.theme.normal),
+                    Span::styled(self.network_rate(), self.theme.muted),
                 ]),
                 Line::from(vec![
                     Span::styled("Errors:      ", self.theme.normal),
@@ -243,6 +262,28 @@ impl<'a> PowerWidget<'a> {
             ]
         };
 
+        if let Some(conns) = self.snapshot.connections {
+            lines.push(Line::from(vec![
+                Span::styled("Connections: ", self.theme.normal),
+                Span::styled(
+                    format!("{:>5.0} new/s", conns.rolling_opened_per_sec),
+                    self.theme.normal,
+                ),
+                Span::styled(
+                    format!("  ({:.1}% reused)", conns.reuse_rate() * 100.0),
+                    if conns.opened > 0 && conns.reuse_rate() < 0.5 {
+                        self.theme.warning
+                    } else {
+                        self.theme.muted
+                    },
+                ),
+            ]));
+        }
+
+        if let Some(line) = self.checks_line() {
+            lines.push(line);
+        }
+
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             render_sparkline(&self.snapshot.timeline),
@@ -252,9 +293,71 @@ impl<'a> PowerWidget<'a> {
         let paragraph = Paragraph::new(lines).block(block);
         frame.render_widget(paragraph, area);
     }
+
+    /// Check pass rate in the last full timeline bucket, against the run
+    /// so far, with the worst check of that bucket when one is failing
+    fn checks_line(&self) -> Option<Line<'a>> {
+        let timeline = &self.snapshot.timeline;
+        let (passed, total) = timeline
+            .iter()
+            .flat_map(|b| b.checks.values())
+            .fold((0, 0), |(p, t), (passed, total)| (p + passed, t + total));
+        if total == 0 {
+            return None;
+        }
+        let overall = passed as f64 / total as f64;
+        // The newest bucket is still filling up
+        let recent = timeline[..timeline.len().saturating_sub(1)]
+            .iter()
+            .rev()
+            .find(|b| !b.checks.is_empty())
+            .unwrap_or(&timeline[timeline.len() - 1]);
+        let now = recent.check_pass_rate().unwrap_or(overall);
+
+        let mut spans = vec![
+            Span::styled("Checks:      ", self.theme.normal),
+            Span::styled(
+                format!("{:>7.2}% now", now * 100.0),
+                if now < overall - 0.01 {
+                    self.theme.error
+                } else if now < 1.0 {
+                    self.theme.warning
+                } else {
+                    self.theme.success
+                },
+            ),
+            Span::styled(
+                format!("  ({:.2}% overall)", overall * 100.0),
+                self.theme.muted,
+            ),
+        ];
+        let worst = recent
+            .checks
+            .iter()
+            .filter(|(_, (passed, total))| passed < total)
+            .min_by(|(_, a), (_, b)| {
+                (a.0 as f64 / a.1 as f64).total_cmp(&(b.0 as f64 / b.1 as f64))
+            });
+        if let Some((name, (passed, total))) = worst {
+            spans.push(Span::styled(
+                format!("  {} {:.1}%", name, *passed as f64 / *total as f64 * 100.0),
+                self.theme.warning,
+            ));
+        }
+        Some(Line::from(spans))
+    }
+
+    /// Ingress and egress over the last second
+    fn network_rate(&self) -> String {
+        format!(
+            "↓{:.1} ↑{:.1} Mbps",
+            self.snapshot.rolling_rx_bps / 1_000_000.0,
+            self.snapshot.rolling_tx_bps / 1_000_000.0
+        )
+    }
 }
 
-fn format_number(n: u64) -> String {
+pub fn format_number(n: u64) -> String {
     if n >= 1_000_000 {
         format!("{:.1}M", n as f64 / 1_000_000.0)
     } else if n >= 1_000 {
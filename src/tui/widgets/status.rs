@@ -85,26 +85,50 @@ impl<'a> StatusWidget<'a> {
             ErrorKind::Reset,
             ErrorKind::Http,
             ErrorKind::Body,
+            ErrorKind::JsonRpc,
+            ErrorKind::Plugin,
+            ErrorKind::Deadline,
             ErrorKind::Other,
         ];
 
-        let lines: Vec<Line> = error_order
-            .iter()
-            .filter_map(|kind| {
-                self.snapshot.errors.get(kind).map(|count| {
-                    let suggestion = kind.suggestion();
-                    let mut spans = vec![
-                        Span::styled(format!("{:<10} ", kind.as_str()), self.theme.error),
-                        Span::styled(format!("{:<6}", count), self.theme.normal),
-                    ];
-                    if !suggestion.is_empty() {
-                        spans.push(Span::styled(format!(" {}", suggestion), self.theme.muted));
-                    }
-                    Line::from(spans)
+        // A VU failing every request says more than the counts it's hidden in
+        let mut lines: Vec<Line> = Vec::new();
+        if let Some(ref workers) = self.snapshot.workers {
+            let failing: Vec<_> = workers.failing().collect();
+            if let [worker, rest @ ..] = failing.as_slice() {
+                let others = if rest.is_empty() {
+                    String::new()
+                } else {
+                    format!(" (+{} more)", rest.len())
+                };
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "worker {} has failed its last {} requests ({}){}",
+                        worker.id, worker.streak, worker.last_error, others
+                    ),
+                    self.theme.warning,
+                )));
+            }
+        }
+
+        lines.extend(
+            error_order
+                .iter()
+                .filter_map(|kind| {
+                    self.snapshot.errors.get(kind).map(|count| {
+                        let suggestion = kind.suggestion();
+                        let mut spans = vec![
+                            Span::styled(format!("{:<10} ", kind.as_str()), self.theme.error),
+                            Span::styled(format!("{:<6}", count), self.theme.normal),
+                        ];
+                        if !suggestion.is_empty() {
+                            spans.push(Span::styled(format!(" {}", suggestion), self.theme.muted));
+                        }
+                        Line::from(spans)
+                    })
                 })
-            })
-            .take(5)
-            .collect();
+                .take(5 - lines.len()),
+        );
 
         let lines = if lines.is_empty() {
             vec![Line::from(Span::styled("No errors", self.theme.success))]
@@ -0,0 +1,612 @@
+//! Config linter (`kaioken validate`)
+//!
+//! Goes further than `run --dry-run`: every problem in a file is reported
+//! rather than the first, and configs that parse but won't do what was
+//! meant are flagged too (typoed keys serde would ignore, scenarios that can
+//! never run, `${var}` references nothing provides, unused extractions).
+
+use crate::cli::{RunArgs, ValidateArgs};
+use crate::config::{
+    RUNTIME_VARS, TomlConfig, is_runtime_var, merge_config, parse_check_condition,
+    parse_threshold_expr, parse_trend_threshold_expr, resolve_includes, substitute_env_vars,
+    test_tables,
+};
+use crate::types::{ExtractionSource, FormField, ThresholdMetric};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Level {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Finding {
+    level: Level,
+    /// Stable identifier for filtering, e.g. `unknown-variable`
+    code: &'static str,
+    /// Where in the config, e.g. `scenarios[1].extract.token`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<String>,
+    message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct FileReport {
+    path: String,
+    valid: bool,
+    errors: usize,
+    warnings: usize,
+    findings: Vec<Finding>,
+}
+
+#[derive(Default)]
+struct Findings(Vec<Finding>);
+
+impl Findings {
+    fn error(&mut self, code: &'static str, location: Option<String>, message: String) {
+        self.0.push(Finding {
+            level: Level::Error,
+            code,
+            location,
+            message,
+        });
+    }
+
+    fn warning(&mut self, code: &'static str, location: Option<String>, message: String) {
+        self.0.push(Finding {
+            level: Level::Warning,
+            code,
+            location,
+            message,
+        });
+    }
+
+    fn has_errors(&self) -> bool {
+        self.0.iter().any(|f| f.level == Level::Error)
+    }
+}
+
+/// Top-level sections `TomlConfig` understands
+const SECTIONS: &[&str] = &[
+    "target",
+    "load",
+    "websocket",
+    "scenarios",
+    "thresholds",
+    "checks",
+    "stages",
+    "jsonrpc",
+    "tests",
+    "endpoint_groups",
+];
+
+pub fn run_validate(args: &ValidateArgs) -> Result<i32, String> {
+    let reports: Vec<FileReport> = args
+        .configs
+        .iter()
+        .map(|path| {
+            let findings = validate_file(path).0;
+            let errors = findings.iter().filter(|f| f.level == Level::Error).count();
+            let warnings = findings.len() - errors;
+            FileReport {
+                path: path.display().to_string(),
+                valid: errors == 0 && (!args.strict || warnings == 0),
+                errors,
+                warnings,
+                findings,
+            }
+        })
+        .collect();
+
+    let valid = reports.iter().all(|r| r.valid);
+
+    if args.json {
+        let output = serde_json::json!({
+            "valid": valid,
+            "files": reports,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&output)
+                .map_err(|e| format!("Failed to serialize report: {}", e))?
+        );
+    } else {
+        for report in &reports {
+            print_report(report);
+        }
+    }
+
+    Ok(if valid { 0 } else { 1 })
+}
+
+fn print_report(report: &FileReport) {
+    for finding in &report.findings {
+        let level = match finding.level {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        };
+        match finding.location {
+            Some(ref location) => println!(
+                "{}: {}[{}] {}: {}",
+                report.path, level, finding.code, location, finding.message
+            ),
+            None => println!(
+                "{}: {}[{}] {}",
+                report.path, level, finding.code, finding.message
+            ),
+        }
+    }
+
+    if report.findings.is_empty() {
+        println!("✓ {}: no problems found", report.path);
+    } else {
+        let status = if report.valid { "✓" } else { "✗" };
+        println!(
+            "{} {}: {} error(s), {} warning(s)",
+            status, report.path, report.errors, report.warnings
+        );
+    }
+}
+
+fn validate_file(path: &Path) -> Findings {
+    let mut findings = Findings::default();
+
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            findings.error("unreadable", None, format!("Failed to read file: {}", e));
+            return findings;
+        }
+    };
+
+    // Unset variables stay as `${VAR}`, so the rest of the file is still checked
+    let (content, missing) = substitute_env_vars(&content);
+    for name in missing {
+        findings.error(
+            "env-var",
+            None,
+            format!(
+                "Environment variable '{}' is not set and has no default (use ${{{}:-default}})",
+                name, name
+            ),
+        );
+    }
+
+    let value: toml::Table = match content.parse() {
+        Ok(value) => value,
+        Err(e) => {
+            findings.error("parse", None, format!("Invalid TOML: {}", e));
+            return findings;
+        }
+    };
+
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let value = match resolve_includes(value, path, &mut vec![canonical]) {
+        Ok(value) => value,
+        Err(e) => {
+            findings.error("include", None, e);
+            return findings;
+        }
+    };
+
+    let is_suite = value
+        .get("tests")
+        .is_some_and(|t| t.as_array().is_none_or(|tests| !tests.is_empty()));
+    if !is_suite {
+        validate_table(value, &mut findings);
+        return findings;
+    }
+
+    let tests = match test_tables(value) {
+        Ok(tests) => tests,
+        Err(e) => {
+            findings.error("invalid-test", Some("tests".to_string()), e);
+            return findings;
+        }
+    };
+
+    // Check each test as `run --test` would see it. Findings every test
+    // shares come from the top level and are reported once.
+    let per_test: Vec<(String, Vec<Finding>)> = tests
+        .into_iter()
+        .map(|(name, table)| {
+            let mut test_findings = Findings::default();
+            validate_table(table, &mut test_findings);
+            (name, test_findings.0)
+        })
+        .collect();
+    for (i, (name, test_findings)) in per_test.iter().enumerate() {
+        for finding in test_findings {
+            let shared = per_test.iter().all(|(_, other)| other.contains(finding));
+            if shared {
+                if i == 0 {
+                    findings.0.push(finding.clone());
+                }
+                continue;
+            }
+            let prefix = format!("tests[{}]", name);
+            findings.0.push(Finding {
+                location: Some(match finding.location {
+                    Some(ref location) => format!("{}.{}", prefix, location),
+                    None => prefix,
+                }),
+                ..finding.clone()
+            });
+        }
+    }
+
+    findings
+}
+
+/// Checks for one config, with any `[[tests]]` already merged in
+fn validate_table(mut value: toml::Table, findings: &mut Findings) {
+    for key in value.keys() {
+        if !SECTIONS.contains(&key.as_str()) {
+            findings.warning(
+                "unknown-section",
+                Some(key.clone()),
+                format!("Unknown section '{}'{}", key, did_you_mean(key, SECTIONS)),
+            );
+        }
+    }
+
+    // Thresholds are checked here; `ThresholdsConfig` rejects unknown keys,
+    // which would stop deserialization at the first typo
+    let thresholds = value.remove("thresholds");
+    if let Some(ref thresholds) = thresholds {
+        check_thresholds(thresholds, findings);
+    }
+
+    let mut ignored = Vec::new();
+    let toml: TomlConfig = match serde_ignored::deserialize(toml::Value::Table(value), |path| {
+        ignored.push(format_path(&path))
+    }) {
+        Ok(toml) => toml,
+        Err(e) => {
+            findings.error("invalid-config", None, e.to_string());
+            return;
+        }
+    };
+
+    for path in ignored {
+        // Unknown top-level sections were reported above
+        if !path.contains('.') && !path.contains('[') {
+            continue;
+        }
+        findings.warning(
+            "unknown-key",
+            Some(path.clone()),
+            format!("Unknown key '{}' is ignored", path),
+        );
+    }
+
+    check_scenarios(&toml, findings);
+    check_variables(&toml, findings);
+    check_checks(&toml, findings);
+    check_files(&toml, findings);
+
+    // Catch anything else `run` would reject (method names, stage mixes, ...)
+    if !findings.has_errors() {
+        let mut toml = toml;
+        if let Some(thresholds) = thresholds {
+            toml.thresholds = thresholds.try_into().unwrap_or_default();
+        }
+        if let Err(e) = merge_config(&RunArgs::default(), Some(toml)) {
+            findings.error("invalid-config", None, e);
+        }
+    }
+}
+
+fn check_thresholds(thresholds: &toml::Value, findings: &mut Findings) {
+    let Some(table) = thresholds.as_table() else {
+        findings.error(
+            "invalid-threshold",
+            Some("thresholds".to_string()),
+            "[thresholds] must be a table".to_string(),
+        );
+        return;
+    };
+
+    let names: Vec<String> = ThresholdMetric::ALL.iter().map(|m| m.to_string()).collect();
+    let metrics: Vec<&str> = names.iter().map(String::as_str).collect();
+    for (key, value) in table {
+        let location = Some(format!("thresholds.{}", key));
+        let trend = key.strip_suffix("_trend").and_then(ThresholdMetric::parse);
+        let Some(metric) = trend.or_else(|| ThresholdMetric::parse(key)) else {
+            findings.error(
+                "unknown-threshold",
+                location,
+                format!(
+                    "Unknown threshold metric '{}'{}",
+                    key,
+                    did_you_mean(key, &metrics)
+                ),
+            );
+            continue;
+        };
+        match value.as_str() {
+            Some(expr) => {
+                let parsed = match trend {
+                    Some(_) => parse_trend_threshold_expr(metric, expr).map(|_| ()),
+                    None => parse_threshold_expr(metric, expr).map(|_| ()),
+                };
+                if let Err(e) = parsed {
+                    findings.error("invalid-threshold", location, e);
+                }
+            }
+            None => findings.error(
+                "invalid-threshold",
+                location,
+                format!("Threshold must be a string like \"< 500\", got {}", value),
+            ),
+        }
+    }
+}
+
+fn scenario_name(toml: &TomlConfig, index: usize) -> String {
+    toml.scenarios[index]
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("scenario_{}", index + 1))
+}
+
+fn check_scenarios(toml: &TomlConfig, findings: &mut Findings) {
+    let names: Vec<String> = (0..toml.scenarios.len())
+        .map(|i| scenario_name(toml, i))
+        .collect();
+    let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+
+    for (i, scenario) in toml.scenarios.iter().enumerate() {
+        let location = Some(format!("scenarios[{}]", i));
+
+        if scenario.weight.is_zero() && scenario.depends_on.is_none() {
+            findings.warning(
+                "unreachable-scenario",
+                location.clone(),
+                format!(
+                    "Scenario '{}' has weight 0 and no depends_on, so it never runs",
+                    names[i]
+                ),
+            );
+        }
+
+        if let Some(ref dep) = scenario.depends_on
+            && !names.contains(dep)
+        {
+            findings.error(
+                "unknown-dependency",
+                location.clone(),
+                format!(
+                    "Scenario '{}' depends on unknown scenario '{}'{}",
+                    names[i],
+                    dep,
+                    did_you_mean(dep, &name_refs)
+                ),
+            );
+        }
+
+        for (var, source) in &scenario.extract {
+            if let Err(e) = ExtractionSource::parse(source) {
+                findings.error(
+                    "invalid-extraction",
+                    Some(format!("scenarios[{}].extract.{}", i, var)),
+                    e,
+                );
+            }
+        }
+    }
+
+    if toml.scenarios.len() > 1 && toml.scenarios.iter().all(|s| s.weight.is_zero()) {
+        findings.error(
+            "no-reachable-scenarios",
+            Some("scenarios".to_string()),
+            "Every scenario has weight 0".to_string(),
+        );
+    }
+}
+
+/// `${name}` references left after env interpolation, with any `:-default` dropped
+fn var_refs(text: &str) -> Vec<String> {
+    let re = regex_lite::Regex::new(r"\$\{([^}]+)\}").unwrap();
+    re.captures_iter(text)
+        .map(|cap| {
+            let expr = cap.get(1).unwrap().as_str();
+            expr.split(":-").next().unwrap_or(expr).to_string()
+        })
+        .collect()
+}
+
+fn check_variables(toml: &TomlConfig, findings: &mut Findings) {
+    let extracted: BTreeSet<&str> = toml
+        .scenarios
+        .iter()
+        .flat_map(|s| s.extract.keys().map(String::as_str))
+        .collect();
+    let mut known: Vec<&str> = RUNTIME_VARS.to_vec();
+    known.extend(extracted.iter().copied());
+
+    // Interpolated text per location: URLs, headers and bodies
+    let mut sources: Vec<(String, String)> = Vec::new();
+    if let Some(ref url) = toml.target.url {
+        sources.push(("target.url".to_string(), url.clone()));
+    }
+    if let Some(ref body) = toml.target.body {
+        sources.push(("target.body".to_string(), body.clone()));
+    }
+    for (name, value) in &toml.target.headers {
+        sources.push((format!("target.headers.{}", name), value.clone()));
+    }
+    for (i, scenario) in toml.scenarios.iter().enumerate() {
+        sources.push((format!("scenarios[{}].url", i), scenario.url.clone()));
+        if let Some(ref body) = scenario.body {
+            sources.push((format!("scenarios[{}].body", i), body.clone()));
+        }
+        if let Some(ref path) = scenario.body_file
+            && let Ok(body) = std::fs::read_to_string(path)
+        {
+            sources.push((format!("scenarios[{}].body_file", i), body));
+        }
+        for (name, value) in &scenario.headers {
+            sources.push((format!("scenarios[{}].headers.{}", i, name), value.clone()));
+        }
+    }
+
+    let mut used = HashSet::new();
+    for (location, text) in &sources {
+        for name in var_refs(text) {
+            // Unset env vars were reported when interpolating
+            if !is_runtime_var(&name) {
+                continue;
+            }
+            if known.contains(&name.as_str()) {
+                used.insert(name);
+                continue;
+            }
+            findings.error(
+                "unknown-variable",
+                Some(location.clone()),
+                format!(
+                    "'${{{}}}' is not a built-in or extracted variable{}",
+                    name,
+                    did_you_mean(&name, &known)
+                ),
+            );
+        }
+    }
+
+    for (i, scenario) in toml.scenarios.iter().enumerate() {
+        let mut vars: Vec<&String> = scenario.extract.keys().collect();
+        vars.sort();
+        for var in vars {
+            if !used.contains(var) {
+                findings.warning(
+                    "unused-extraction",
+                    Some(format!("scenarios[{}].extract.{}", i, var)),
+                    format!("Extracted variable '{}' is never used as ${{{}}}", var, var),
+                );
+            }
+        }
+    }
+}
+
+fn check_checks(toml: &TomlConfig, findings: &mut Findings) {
+    let mut seen = HashSet::new();
+    for (i, check) in toml.checks.iter().enumerate() {
+        let location = Some(format!("checks[{}]", i));
+        if let Err(e) = parse_check_condition(&check.condition) {
+            findings.error(
+                "invalid-check",
+                location.clone(),
+                format!("Check '{}': {}", check.name, e),
+            );
+        }
+        if !seen.insert(check.name.as_str()) {
+            findings.warning(
+                "duplicate-check",
+                location,
+                format!(
+                    "Check name '{}' is used more than once; results are hard to tell apart",
+                    check.name
+                ),
+            );
+        }
+    }
+}
+
+fn check_files(toml: &TomlConfig, findings: &mut Findings) {
+    let target = &toml.target;
+    let mut files: Vec<(String, String)> = [
+        ("target.body_file", &target.body_file),
+        ("target.body_lines_file", &target.body_lines_file),
+        ("target.cert", &target.cert),
+        ("target.key", &target.key),
+        ("target.cacert", &target.cacert),
+        ("target.urls_from_file", &target.urls_from_file),
+    ]
+    .into_iter()
+    .filter_map(|(location, path)| path.clone().map(|p| (location.to_string(), p)))
+    // `-` streams URLs or body lines from stdin
+    .filter(|(location, path)| {
+        path != "-"
+            || !matches!(
+                location.as_str(),
+                "target.body_lines_file" | "target.urls_from_file"
+            )
+    })
+    .collect();
+
+    for (i, field) in target.form_data.iter().enumerate() {
+        let location = format!("target.form_data[{}]", i);
+        match FormField::parse(field) {
+            Ok(FormField::File { path, .. }) => {
+                files.push((location, path.display().to_string()));
+            }
+            Ok(FormField::Text { .. }) => {}
+            Err(e) => findings.error("invalid-form-field", Some(location), e),
+        }
+    }
+    for (i, scenario) in toml.scenarios.iter().enumerate() {
+        if let Some(ref path) = scenario.body_file {
+            files.push((format!("scenarios[{}].body_file", i), path.clone()));
+        }
+    }
+
+    for (location, path) in files {
+        if !Path::new(&path).exists() {
+            findings.error(
+                "missing-file",
+                Some(location),
+                format!("File not found: {}", path),
+            );
+        }
+    }
+}
+
+/// Render a serde_ignored path as `scenarios[0].wieght`
+fn format_path(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, index } => format!("{}[{}]", format_path(parent), index),
+        Path::Map { parent, key } => {
+            let parent = format_path(parent);
+            if parent.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", parent, key)
+            }
+        }
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => format_path(parent),
+    }
+}
+
+/// `" (did you mean 'x'?)"` for the closest candidate within a couple of edits
+fn did_you_mean(name: &str, candidates: &[&str]) -> String {
+    candidates
+        .iter()
+        .map(|c| (edit_distance(name, c), c))
+        .filter(|(d, _)| *d > 0 && *d <= 2.max(name.len() / 4))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| format!(" (did you mean '{}'?)", c))
+        .unwrap_or_default()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
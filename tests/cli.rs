@@ -230,6 +230,151 @@ mod init_command {
     }
 }
 
+mod doctor_command {
+    use super::*;
+
+    #[test]
+    fn doctor_reports_checks() {
+        kaioken()
+            .args(["doctor", "http://localhost:8080/", "-c", "10"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "10 connections to http://localhost:8080/",
+            ))
+            .stdout(predicate::str::contains("dns"))
+            .stdout(predicate::str::contains("localhost ->"));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn doctor_fails_past_port_range() {
+        let output = kaioken()
+            .args(["doctor", "--json", "-c", "100000000"])
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["ok"], false);
+        let ports = json["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["name"] == "ephemeral ports")
+            .unwrap();
+        assert_eq!(ports["status"], "fail");
+    }
+}
+
+mod validate_command {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn validate_accepts_good_config() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("good.toml");
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "http://localhost:8080/items/${REQUEST_ID}"
+
+[thresholds]
+p99_latency_ms = "< 500"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["validate", config.to_str().unwrap()])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("no problems found"));
+    }
+
+    #[test]
+    fn validate_reports_every_problem() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("bad.toml");
+        fs::write(
+            &config,
+            r#"
+[load]
+concurency = 10
+
+[thresholds]
+p99_latncy_ms = "< 500"
+
+[[scenarios]]
+name = "login"
+url = "http://localhost:8080/login"
+body_file = "does-not-exist.json"
+[scenarios.extract]
+token = "json:$.token"
+session = "header:X-Session"
+
+[[scenarios]]
+name = "profile"
+url = "http://localhost:8080/me?t=${tokn}"
+headers = { Authorization = "Bearer ${token}" }
+weight = 0
+
+[[checks]]
+name = "ok"
+condition = "status = 200"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["validate", config.to_str().unwrap()])
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains(
+                "warning[unknown-key] load.concurency",
+            ))
+            .stdout(predicate::str::contains("did you mean 'p99_latency_ms'?"))
+            .stdout(predicate::str::contains("error[missing-file]"))
+            .stdout(predicate::str::contains("warning[unused-extraction]"))
+            .stdout(predicate::str::contains("warning[unreachable-scenario]"))
+            .stdout(predicate::str::contains("'${tokn}'"))
+            .stdout(predicate::str::contains("error[invalid-check]"));
+    }
+
+    #[test]
+    fn validate_json_output_and_strict() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("warn.toml");
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "http://localhost:8080/"
+timeot = "5s"
+"#,
+        )
+        .unwrap();
+
+        let output = kaioken()
+            .args(["validate", "--json", config.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "warnings alone should pass");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["valid"], true);
+        assert_eq!(json["files"][0]["warnings"], 1);
+        assert_eq!(json["files"][0]["findings"][0]["code"], "unknown-key");
+        assert_eq!(json["files"][0]["findings"][0]["location"], "target.timeot");
+
+        kaioken()
+            .args(["validate", "--strict", config.to_str().unwrap()])
+            .assert()
+            .failure();
+    }
+}
+
 mod completions {
     use super::*;
 
@@ -274,6 +419,74 @@ mod man_page {
     }
 }
 
+mod calibrate_command {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn calibrate_saves_results() {
+        let dir = tempdir().unwrap();
+
+        kaioken()
+            .env("XDG_CACHE_HOME", dir.path())
+            .args(["calibrate", "-d", "200ms", "--max-concurrency", "8"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Max RPS:"));
+
+        let saved = fs::read_to_string(dir.path().join("kaioken/calibration.json")).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&saved).unwrap();
+        assert!(json["max_rps"].as_f64().unwrap() > 0.0);
+        assert_eq!(json["best_concurrency"], 8);
+    }
+
+    #[test]
+    fn run_warns_above_calibrated_ceiling() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("kaioken")).unwrap();
+        fs::write(
+            dir.path().join("kaioken/calibration.json"),
+            r#"{
+                "version": "1.0.0",
+                "measured_at": "2026-01-01T00:00:00Z",
+                "cores": 4,
+                "threads": null,
+                "max_rps": 5000.0,
+                "best_concurrency": 64,
+                "max_connections": 128,
+                "connection_limited": true,
+                "steps": []
+            }"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .env("XDG_CACHE_HOME", dir.path())
+            .args([
+                "run",
+                "http://localhost:8080",
+                "--arrival-rate",
+                "20000",
+                "--max-vus",
+                "500",
+                "--dry-run",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("calibrated ceiling of ~5000 RPS"))
+            .stderr(predicate::str::contains("500 connections exceeds the 128"));
+
+        // Within the ceiling: no warnings
+        kaioken()
+            .env("XDG_CACHE_HOME", dir.path())
+            .args(["run", "http://localhost:8080", "-c", "50", "--dry-run"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("calibrat").not());
+    }
+}
+
 mod websocket_cli {
     use super::*;
 
@@ -326,6 +539,26 @@ mod websocket_cli {
             .success();
     }
 
+    #[test]
+    fn ws_subprotocol_flag_accepted() {
+        kaioken()
+            .args([
+                "run",
+                "ws://localhost:8080/graphql",
+                "--ws-subprotocol",
+                "graphql-ws",
+                "--ws-subprotocol",
+                "graphql-transport-ws",
+                "-H",
+                "Authorization: Bearer token",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("graphql-ws, graphql-transport-ws"));
+    }
+
     #[test]
     fn help_shows_websocket_options() {
         kaioken()
@@ -335,6 +568,158 @@ mod websocket_cli {
             .stdout(predicate::str::contains("--ws-message-interval"))
             .stdout(predicate::str::contains("--ws-fire-and-forget"));
     }
+
+    #[test]
+    fn ws_reconnect_every_flag_accepted() {
+        kaioken()
+            .args([
+                "run",
+                "ws://localhost:8080/ws",
+                "--ws-reconnect-every",
+                "30s",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Reconnect:   every 30s"));
+    }
+
+    #[test]
+    fn ws_churn_sets_the_reconnect_interval() {
+        kaioken()
+            .args([
+                "run",
+                "ws://localhost:8080/ws",
+                "--ws-churn",
+                "5",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Reconnect:   every 20s"));
+    }
+
+    #[test]
+    fn ws_publishers_enables_broadcast_mode() {
+        kaioken()
+            .args([
+                "run",
+                "ws://localhost:8080/chat",
+                "--ws-publishers",
+                "2",
+                "-c",
+                "50",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "broadcast (2 publishers, 48 subscribers)",
+            ));
+    }
+
+    #[test]
+    fn ws_publishers_must_leave_subscribers() {
+        kaioken()
+            .args([
+                "run",
+                "ws://localhost:8080/chat",
+                "--ws-publishers",
+                "5",
+                "-c",
+                "5",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "at least one connection subscribes",
+            ));
+    }
+
+    #[test]
+    fn ws_compression_flag_accepted() {
+        kaioken()
+            .args([
+                "run",
+                "ws://localhost:8080/ws",
+                "--ws-compression",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Compression: permessage-deflate"));
+    }
+}
+
+mod raw_cli {
+    use super::*;
+
+    #[test]
+    fn raw_target_shows_matcher() {
+        kaioken()
+            .args([
+                "run",
+                "udp://127.0.0.1:514",
+                "--raw-expect-bytes",
+                "4",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Raw:         udp 127.0.0.1:514 (response: 4 bytes)",
+            ));
+    }
+
+    #[test]
+    fn raw_target_requires_port() {
+        kaioken()
+            .args(["run", "tcp://localhost", "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("needs a host and port"));
+    }
+
+    #[test]
+    fn raw_expect_requires_raw_target() {
+        kaioken()
+            .args([
+                "run",
+                "http://localhost:8080",
+                "--raw-expect",
+                "OK",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "require a tcp:// or udp:// target",
+            ));
+    }
+
+    #[test]
+    fn raw_expect_rejects_invalid_regex() {
+        kaioken()
+            .args([
+                "run",
+                "tcp://localhost:9000",
+                "--raw-expect",
+                "(",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid --raw-expect regex"));
+    }
 }
 
 mod import_command {
@@ -722,3 +1107,127 @@ mod prometheus_cli {
             .stderr(predicate::str::contains("cannot be used with"));
     }
 }
+
+mod runners_cli {
+    use super::*;
+
+    #[test]
+    fn runners_validate_on_dry_run() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run"])
+            .args(["--runners", "ssh://deploy@10.0.0.5,ssh://10.0.0.6:2222"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Configuration validated"));
+    }
+
+    #[test]
+    fn runners_reject_bad_urls_and_targets() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run"])
+            .args(["--runners", "http://10.0.0.5"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("expected ssh://"));
+
+        kaioken()
+            .args(["run", "ws://example.com", "--dry-run"])
+            .args(["--runners", "ssh://10.0.0.5"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--runners only applies to http://",
+            ));
+
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "--every", "1h"])
+            .args(["--runners", "ssh://10.0.0.5"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("cannot be used with"));
+    }
+}
+
+mod k8s_command {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const CONFIG: &str = r#"[target]
+url = "https://${K8S_TEST_UNSET_HOST}/api"
+
+[load]
+concurrency = 5
+duration = "10s"
+"#;
+
+    #[test]
+    fn generates_configmap_and_indexed_job() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("test.toml");
+        fs::write(&config, CONFIG).unwrap();
+
+        kaioken()
+            .args(["k8s", "generate", "-f", config.to_str().unwrap()])
+            .args(["--image", "kaioken:1.0", "--name", "checkout"])
+            .args(["--parallelism", "4", "--secret", "api-env"])
+            .args(["--", "--max-requests", "100"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("kind: ConfigMap"))
+            .stdout(predicate::str::contains("name: checkout-config"))
+            .stdout(predicate::str::contains(
+                "    url = \"https://${K8S_TEST_UNSET_HOST}/api\"",
+            ))
+            .stdout(predicate::str::contains("kind: Job"))
+            .stdout(predicate::str::contains("completionMode: Indexed"))
+            .stdout(predicate::str::contains("parallelism: 4"))
+            .stdout(predicate::str::contains("name: api-env"))
+            .stdout(predicate::str::contains(
+                r#""--no-tui", "-y", "--max-requests", "100"]"#,
+            ))
+            .stderr(predicate::str::contains(
+                "Not validated: ${K8S_TEST_UNSET_HOST}",
+            ));
+    }
+
+    #[test]
+    fn rejects_bad_names_and_flags() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("test.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[load]\nconcurrency = 5\n",
+        )
+        .unwrap();
+        let generate = || {
+            let mut cmd = kaioken();
+            cmd.args(["k8s", "generate", "-f", config.to_str().unwrap()])
+                .args(["--image", "kaioken:1.0"]);
+            cmd
+        };
+
+        generate()
+            .args(["--name", "Load_Test"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid name 'Load_Test'"));
+
+        generate()
+            .args(["--", "--no-such-flag"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid run flags"));
+
+        generate()
+            .args(["--", "-f", "other.toml"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Pass the config with -f"));
+
+        generate()
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("completionMode").not());
+    }
+}
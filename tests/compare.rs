@@ -441,3 +441,69 @@ mod warnings {
             .stdout(predicate::str::contains("Concurrency differs"));
     }
 }
+
+mod schema_versions {
+    use super::*;
+
+    #[test]
+    fn upgrades_results_written_before_schema_versions() {
+        let dir = tempdir().unwrap();
+        let baseline = dir.path().join("baseline.json");
+        let current = dir.path().join("current.json");
+
+        // Written before schema_version, and before configurable timeline buckets
+        let old = create_test_results(1000, 100.0, 0.01, 10000, None, None).replace(
+            r#""timeline": []"#,
+            r#""timeline": [{"elapsed_secs": 0, "requests": 100, "errors": 1}]"#,
+        );
+        let new = create_test_results(1000, 100.0, 0.01, 10000, None, None)
+            .replacen('{', r#"{"schema_version": 2,"#, 1)
+            .replace(r#""dummy": 0"#, r#""timeline_interval_ms": 1000"#)
+            .replace(
+                r#""bytes_received": 1000000"#,
+                r#""bytes_received": 1000000, "bytes_sent": 0, "rx_mbps": 0.0, "tx_mbps": 0.0"#,
+            );
+        fs::write(&baseline, old).unwrap();
+        fs::write(&current, new).unwrap();
+
+        kaioken()
+            .args([
+                "compare",
+                baseline.to_str().unwrap(),
+                current.to_str().unwrap(),
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Baseline is schema version 1, upgraded to 2",
+            ))
+            .stdout(predicate::str::contains("Current is schema version").not());
+    }
+
+    #[test]
+    fn rejects_results_from_a_newer_schema() {
+        let dir = tempdir().unwrap();
+        let baseline = dir.path().join("baseline.json");
+        let current = dir.path().join("current.json");
+
+        let results = create_test_results(1000, 100.0, 0.01, 10000, None, None);
+        fs::write(&baseline, &results).unwrap();
+        fs::write(
+            &current,
+            results.replacen('{', r#"{"schema_version": 99,"#, 1),
+        )
+        .unwrap();
+
+        kaioken()
+            .args([
+                "compare",
+                baseline.to_str().unwrap(),
+                current.to_str().unwrap(),
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "written under result schema version 99, newer than this kaioken reads (2)",
+            ));
+    }
+}
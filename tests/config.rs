@@ -190,6 +190,64 @@ target_rate = 100
             .failure()
             .stderr(predicate::str::contains("Cannot mix"));
     }
+
+    #[test]
+    fn message_rate_stages_validate() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "ws://localhost:8080/ws"
+
+[[stages]]
+duration = "30s"
+target_message_rate = 10
+
+[[stages]]
+duration = "1m"
+target_message_rate = 200
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "max rate: 200 msg/s per connection",
+            ));
+    }
+
+    #[test]
+    fn message_rate_stages_require_websocket_url() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com/api"
+
+[[stages]]
+duration = "30s"
+target_message_rate = 10
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "require a ws:// or wss:// target URL",
+            ));
+    }
 }
 
 mod scenarios_config {
@@ -267,13 +325,9 @@ endpoint = "users"
             .assert()
             .success();
     }
-}
-
-mod arrival_rate_config {
-    use super::*;
 
     #[test]
-    fn arrival_rate_config_validates() {
+    fn mixed_http_and_websocket_scenarios_validate() {
         let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
 
@@ -283,10 +337,16 @@ mod arrival_rate_config {
 [target]
 url = "https://example.com/api"
 
-[load]
-arrival_rate = 100
-max_vus = 200
-duration = "5m"
+[[scenarios]]
+name = "rest"
+url = "https://example.com/users"
+weight = 3
+
+[[scenarios]]
+name = "live"
+url = "wss://example.com/live"
+body = '{"type":"subscribe"}'
+weight = 1
 "#,
         )
         .unwrap();
@@ -295,11 +355,81 @@ duration = "5m"
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
             .success()
-            .stderr(predicate::str::contains("Configuration validated"));
+            .stderr(predicate::str::contains("live (WS wss://example.com/live)"));
+
+        // One VU can't serve both sides
+        kaioken()
+            .args([
+                "run",
+                "-f",
+                config.to_str().unwrap(),
+                "-c",
+                "1",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("at least 2 VUs"));
     }
 
     #[test]
-    fn arrival_rate_conflicts_with_concurrency() {
+    fn percentage_weights_validate() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        let run = |weights: [&str; 3]| {
+            fs::write(
+                &config,
+                format!(
+                    r#"
+[target]
+url = "https://example.com/api"
+
+[[scenarios]]
+name = "browse"
+url = "https://example.com/items"
+weight = {}
+
+[[scenarios]]
+name = "checkout"
+url = "https://example.com/cart"
+weight = {}
+
+[[scenarios]]
+name = "login"
+url = "https://example.com/login"
+weight = {}
+"#,
+                    weights[0], weights[1], weights[2]
+                ),
+            )
+            .unwrap();
+            kaioken()
+                .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+                .assert()
+        };
+
+        // Dependency-only scenarios can keep weight 0
+        run(["\"35%\"", "\"65%\"", "0"])
+            .success()
+            .stderr(predicate::str::contains("weight=7 (35%)"))
+            .stderr(predicate::str::contains("weight=13 (65%)"));
+
+        run(["\"60%\"", "\"30%\"", "\"5%\""])
+            .failure()
+            .stderr(predicate::str::contains("add up to 95%, not 100%"));
+        run(["\"60%\"", "\"40%\"", "1"])
+            .failure()
+            .stderr(predicate::str::contains(
+                "Scenario 'login' needs a percentage weight",
+            ));
+        run(["\"35\"", "1", "1"])
+            .failure()
+            .stderr(predicate::str::contains("invalid weight '35'"));
+    }
+
+    #[test]
+    fn websocket_scenarios_reject_arrival_rate() {
         let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
 
@@ -310,26 +440,28 @@ duration = "5m"
 url = "https://example.com/api"
 
 [load]
-concurrency = 50
-arrival_rate = 100
-duration = "5m"
+arrival_rate = 50
+
+[[scenarios]]
+name = "live"
+url = "ws://example.com/live"
 "#,
         )
         .unwrap();
 
-        // Should warn or handle the conflict gracefully
         kaioken()
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .success(); // arrival_rate takes precedence
+            .failure()
+            .stderr(predicate::str::contains("WebSocket scenarios"));
     }
 }
 
-mod thresholds_config {
+mod arrival_rate_config {
     use super::*;
 
     #[test]
-    fn all_threshold_metrics_validate() {
+    fn arrival_rate_config_validates() {
         let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
 
@@ -340,21 +472,9 @@ mod thresholds_config {
 url = "https://example.com/api"
 
 [load]
-concurrency = 10
-duration = "5s"
-
-[thresholds]
-p50_latency_ms = "< 100"
-p75_latency_ms = "< 200"
-p90_latency_ms = "< 300"
-p95_latency_ms = "< 400"
-p99_latency_ms = "< 500"
-p999_latency_ms = "< 1000"
-mean_latency_ms = "< 200"
-max_latency_ms = "< 2000"
-error_rate = "< 0.01"
-rps = "> 100"
-check_pass_rate = "> 0.95"
+arrival_rate = 100
+max_vus = 200
+duration = "5m"
 "#,
         )
         .unwrap();
@@ -363,11 +483,11 @@ check_pass_rate = "> 0.95"
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
             .success()
-            .stderr(predicate::str::contains("11 defined"));
+            .stderr(predicate::str::contains("Configuration validated"));
     }
 
     #[test]
-    fn invalid_threshold_metric_fails() {
+    fn arrival_rate_conflicts_with_concurrency() {
         let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
 
@@ -378,30 +498,22 @@ check_pass_rate = "> 0.95"
 url = "https://example.com/api"
 
 [load]
-concurrency = 10
-duration = "5s"
-
-[thresholds]
-invalid_metric = "< 100"
+concurrency = 50
+arrival_rate = 100
+duration = "5m"
 "#,
         )
         .unwrap();
 
-        // Unknown threshold metrics must fail with helpful error listing valid ones
+        // Should warn or handle the conflict gracefully
         kaioken()
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .failure()
-            .stderr(predicate::str::contains("unknown field"))
-            .stderr(predicate::str::contains("p95_latency_ms"));
+            .success(); // arrival_rate takes precedence
     }
-}
-
-mod checks_config {
-    use super::*;
 
     #[test]
-    fn status_checks_validate() {
+    fn vu_autoscale_validates() {
         let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
 
@@ -412,20 +524,11 @@ mod checks_config {
 url = "https://example.com/api"
 
 [load]
-concurrency = 10
-duration = "5s"
-
-[[checks]]
-name = "is_200"
-condition = "status == 200"
-
-[[checks]]
-name = "is_success"
-condition = "status in [200, 201, 204]"
-
-[[checks]]
-name = "not_error"
-condition = "status < 400"
+arrival_rate = 100
+duration = "5m"
+vu_autoscale = true
+vu_step = 5
+vu_cap = 50
 "#,
         )
         .unwrap();
@@ -434,15 +537,22 @@ condition = "status < 400"
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
             .success()
-            .stderr(predicate::str::contains("Checks"))
-            .stderr(predicate::str::contains("3 defined"));
+            .stderr(predicate::str::contains(
+                "autoscaled 5 at a time up to 50, 1s apart, shed after 10s idle",
+            ));
     }
 
     #[test]
-    fn body_checks_validate() {
+    fn vu_autoscale_needs_an_arrival_rate() {
+        kaioken()
+            .args(["run", "https://example.com/api", "--vu-autoscale"])
+            .args(["--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("needs arrival rate mode"));
+
         let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
-
         fs::write(
             &config,
             r#"
@@ -450,179 +560,214 @@ condition = "status < 400"
 url = "https://example.com/api"
 
 [load]
-concurrency = 10
-duration = "5s"
-
-[[checks]]
-name = "has_success"
-condition = 'body contains "success"'
-
-[[checks]]
-name = "valid_json"
-condition = 'body matches "\{.*\}"'
+arrival_rate = 100
+vu_cap = 50
 "#,
         )
         .unwrap();
-
         kaioken()
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .success();
+            .failure()
+            .stderr(predicate::str::contains("need vu_autoscale = true"));
     }
-}
-
-mod scenario_without_target {
-    use super::*;
 
     #[test]
-    fn scenario_only_config_fails_with_explicit_message() {
+    fn rate_profile_replays_over_the_window() {
         let dir = tempdir().unwrap();
-        let config = dir.path().join("config.toml");
-
+        let profile = dir.path().join("day.csv");
         fs::write(
-            &config,
-            r#"
-[load]
-concurrency = 10
-duration = "10s"
-
-[[scenarios]]
-name = "get_users"
-url = "https://example.com/users"
-method = "GET"
-weight = 1
-"#,
+            &profile,
+            "time,rps\n# overnight lull\n00:00,20\n06:00,50\n12:00,400\n18:00,300\n24:00,20\n",
         )
         .unwrap();
 
-        // Should fail with explicit message about needing [target]
         kaioken()
-            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .args([
+                "run",
+                "https://example.com/api",
+                "--rate-profile",
+                profile.to_str().unwrap(),
+                "--rate-profile-window",
+                "24m",
+                "--dry-run",
+                "-y",
+            ])
             .assert()
-            .failure()
-            .stderr(predicate::str::contains("[target]"))
-            .stderr(predicate::str::contains("[[scenarios]]"));
+            .success()
+            .stderr(predicate::str::contains(
+                "(5 points, 86400s replayed over 1440s, max rate: 400 RPS)",
+            ));
     }
-}
-
-mod extraction_config {
-    use super::*;
 
     #[test]
-    fn json_extraction_validates() {
+    fn rate_profile_rejects_bad_input() {
         let dir = tempdir().unwrap();
-        let config = dir.path().join("config.toml");
+        let profile = dir.path().join("profile.txt");
+        let run = |contents: &str, extra: &[&str]| {
+            fs::write(&profile, contents).unwrap();
+            kaioken()
+                .args(["run", "https://example.com/api", "--rate-profile"])
+                .arg(&profile)
+                .args(extra)
+                .args(["--dry-run", "-y"])
+                .assert()
+                .failure()
+        };
+
+        run("0 10\n60 20\n30 10\n", &[]).stderr(predicate::str::contains("offsets must increase"));
+        run("0 10\n", &[]).stderr(predicate::str::contains("at least two points"));
+        run("0 10\n60 lots\n", &[]).stderr(predicate::str::contains("line 2"));
+        run("0 10\n60 20\n", &["--arrival-rate", "10"])
+            .stderr(predicate::str::contains("cannot be used with"));
+        run("0 10\n60 20\n", &["--rate-profile-window", "0s"])
+            .stderr(predicate::str::contains("greater than zero"));
+    }
+}
 
-        fs::write(
-            &config,
-            r#"
+mod test_suites {
+    use super::*;
+
+    const SUITE: &str = r#"
 [target]
 url = "https://example.com/api"
 
 [load]
 concurrency = 10
-duration = "5s"
+duration = "30s"
 
-[[scenarios]]
-name = "login"
-url = "https://example.com/auth"
-method = "POST"
-body = '{"user": "test"}'
-weight = 0
+[thresholds]
+error_rate = "< 0.01"
 
-[scenarios.extract]
-token = "json:$.access_token"
+[[tests]]
+name = "smoke"
+load = { concurrency = 1, duration = "5s" }
 
-[[scenarios]]
-name = "profile"
-url = "https://example.com/me"
-method = "GET"
-weight = 1
+[[tests]]
+name = "stress"
+[tests.load]
+concurrency = 200
+[tests.thresholds]
+p99_latency_ms = "< 800"
+"#;
 
-[scenarios.headers]
-Authorization = "Bearer ${token}"
-"#,
-        )
-        .unwrap();
+    #[test]
+    fn test_inherits_and_overrides_top_level() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("suite.toml");
+        fs::write(&config, SUITE).unwrap();
 
         kaioken()
-            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .args(["run", "-f", config.to_str().unwrap(), "--test", "stress"])
+            .args(["--dry-run", "-y"])
             .assert()
-            .success();
+            .success()
+            .stderr(predicate::str::contains("Concurrency: 200"))
+            .stderr(predicate::str::contains("Duration:    30s"))
+            .stderr(predicate::str::contains("error_rate < 0.01"))
+            .stderr(predicate::str::contains("p99_latency_ms < 800"));
     }
-}
-
-mod env_var_interpolation {
-    use super::*;
 
     #[test]
-    fn env_var_with_default_validates() {
+    fn suite_requires_test_selection() {
         let dir = tempdir().unwrap();
-        let config = dir.path().join("config.toml");
+        let config = dir.path().join("suite.toml");
+        fs::write(&config, SUITE).unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("defines tests (smoke, stress)"));
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--test", "soak"])
+            .args(["--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("No test named 'soak'"));
+    }
 
+    #[test]
+    fn duplicate_test_names_fail() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("suite.toml");
         fs::write(
             &config,
             r#"
 [target]
-url = "${API_URL:-https://example.com/api}"
+url = "https://example.com/api"
 
-[load]
-concurrency = 10
-duration = "5s"
+[[tests]]
+name = "smoke"
+
+[[tests]]
+name = "smoke"
 "#,
         )
         .unwrap();
 
         kaioken()
-            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .args(["run", "-f", config.to_str().unwrap(), "--test", "all"])
+            .args(["--dry-run", "-y"])
             .assert()
-            .success()
-            .stderr(predicate::str::contains("https://example.com/api"));
+            .failure()
+            .stderr(predicate::str::contains("Duplicate test name 'smoke'"));
     }
 }
 
-mod websocket_config {
+mod config_includes {
     use super::*;
 
     #[test]
-    fn websocket_url_validates() {
+    fn extends_and_include_layer_under_config() {
         let dir = tempdir().unwrap();
-        let config = dir.path().join("config.toml");
-
+        fs::create_dir(dir.path().join("shared")).unwrap();
         fs::write(
-            &config,
+            dir.path().join("shared/base.toml"),
             r#"
 [target]
-url = "ws://localhost:8080/ws"
+url = "https://example.com/api"
+headers = { Authorization = "Bearer abc" }
 
 [load]
-concurrency = 10
-duration = "5s"
+concurrency = 5
+
+[thresholds]
+error_rate = "< 0.01"
+
+[[scenarios]]
+name = "list"
+url = "https://example.com/api/items"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("checks.toml"),
+            r#"
+[[checks]]
+name = "ok"
+condition = "status == 200"
 "#,
         )
         .unwrap();
-
-        kaioken()
-            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
-            .assert()
-            .success()
-            .stderr(predicate::str::contains("ws://localhost:8080/ws"));
-    }
-
-    #[test]
-    fn websocket_wss_url_validates() {
-        let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
-
         fs::write(
             &config,
             r#"
-[target]
-url = "wss://example.com/socket"
+extends = "shared/base.toml"
+include = ["checks.toml"]
 
 [load]
-concurrency = 5
-duration = "10s"
+concurrency = 20
+
+[thresholds]
+p99_latency_ms = "< 500"
+
+[[scenarios]]
+name = "create"
+url = "https://example.com/api/items"
+method = "POST"
 "#,
         )
         .unwrap();
@@ -631,27 +776,40 @@ duration = "10s"
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
             .success()
-            .stderr(predicate::str::contains("wss://example.com/socket"));
+            .stderr(predicate::str::contains("Scenarios:   2 defined"))
+            .stderr(predicate::str::contains("Concurrency: 20"))
+            .stderr(predicate::str::contains("Headers:     1 custom"))
+            .stderr(predicate::str::contains("error_rate < 0.01"))
+            .stderr(predicate::str::contains("p99_latency_ms < 500"))
+            .stderr(predicate::str::contains("Checks:      1 defined"));
     }
 
     #[test]
-    fn websocket_config_section_validates() {
+    fn include_cycle_fails() {
         let dir = tempdir().unwrap();
-        let config = dir.path().join("config.toml");
+        fs::write(dir.path().join("a.toml"), "extends = \"b.toml\"\n").unwrap();
+        fs::write(dir.path().join("b.toml"), "extends = \"a.toml\"\n").unwrap();
+
+        kaioken()
+            .args(["run", "-f"])
+            .arg(dir.path().join("a.toml"))
+            .args(["--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Config include cycle"));
+    }
 
+    #[test]
+    fn missing_include_fails() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
         fs::write(
             &config,
             r#"
-[target]
-url = "ws://localhost:8080/ws"
-
-[load]
-concurrency = 10
-duration = "5s"
+include = ["nope.toml"]
 
-[websocket]
-message_interval = "50ms"
-mode = "echo"
+[target]
+url = "https://example.com/api"
 "#,
         )
         .unwrap();
@@ -659,11 +817,16 @@ mode = "echo"
         kaioken()
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .success();
+            .failure()
+            .stderr(predicate::str::contains("nope.toml"));
     }
+}
+
+mod socket_config {
+    use super::*;
 
     #[test]
-    fn websocket_fire_and_forget_mode_validates() {
+    fn socket_settings_validate() {
         let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
 
@@ -671,15 +834,10 @@ mode = "echo"
             &config,
             r#"
 [target]
-url = "ws://localhost:8080/events"
-
-[load]
-concurrency = 20
-duration = "30s"
-
-[websocket]
-message_interval = "10ms"
-mode = "fire_and_forget"
+url = "http://localhost:8080"
+tcp_nodelay = false
+pool_max_idle_per_host = 8
+pool_idle_timeout = "5s"
 "#,
         )
         .unwrap();
@@ -687,198 +845,282 @@ mode = "fire_and_forget"
         kaioken()
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .success();
+            .success()
+            .stderr(predicate::str::contains("TCP_NODELAY: disabled"))
+            .stderr(predicate::str::contains(
+                "Pool:        8 idle per host, 5s idle timeout",
+            ));
     }
-}
-
-#[cfg(feature = "grpc")]
-mod grpc_config {
-    use super::*;
 
     #[test]
-    fn grpc_service_requires_method() {
+    fn socket_buffers_apply_to_raw_targets() {
         kaioken()
             .args([
                 "run",
-                "https://localhost:50051",
-                "--grpc-service",
-                "hello.Greeter",
+                "tcp://localhost:9000",
+                "--send-buffer",
+                "65536",
+                "--recv-buffer",
+                "65536",
                 "--dry-run",
                 "-y",
             ])
             .assert()
-            .failure()
-            .stderr(predicate::str::contains(
-                "Both --grpc-service and --grpc-method must be provided together",
-            ));
+            .success()
+            .stderr(predicate::str::contains("SO_SNDBUF:   65536 bytes"));
     }
 
     #[test]
-    fn grpc_method_requires_service() {
+    fn socket_buffers_rejected_for_http() {
         kaioken()
             .args([
                 "run",
-                "https://localhost:50051",
-                "--grpc-method",
-                "SayHello",
+                "http://localhost:8080",
+                "--send-buffer",
+                "65536",
                 "--dry-run",
                 "-y",
             ])
             .assert()
             .failure()
             .stderr(predicate::str::contains(
-                "Both --grpc-service and --grpc-method must be provided together",
+                "the HTTP client does not expose socket buffer sizes",
             ));
     }
+}
+
+mod threads_config {
+    use super::*;
 
     #[test]
-    fn grpc_empty_service_rejected() {
-        // Empty string is treated as "not provided", so triggers the pairing check
+    fn threads_setting_validates() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "http://localhost:8080"
+
+[load]
+concurrency = 16
+threads = 4
+"#,
+        )
+        .unwrap();
+
         kaioken()
-            .args([
-                "run",
-                "https://localhost:50051",
-                "--grpc-service",
-                "",
-                "--grpc-method",
-                "SayHello",
-                "--dry-run",
-                "-y",
-            ])
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .failure()
-            .stderr(predicate::str::contains("must be provided together"));
+            .success()
+            .stderr(predicate::str::contains("Runtimes:    4"));
     }
 
     #[test]
-    fn grpc_insecure_rejected() {
+    fn threads_reject_arrival_rate() {
         kaioken()
             .args([
                 "run",
-                "https://localhost:50051",
-                "--grpc-service",
-                "hello.Greeter",
-                "--grpc-method",
-                "SayHello",
-                "--insecure",
+                "http://localhost:8080",
+                "--threads",
+                "2",
+                "--arrival-rate",
+                "100",
                 "--dry-run",
                 "-y",
             ])
             .assert()
             .failure()
-            .stderr(predicate::str::contains(
-                "--insecure is not supported for gRPC",
-            ));
+            .stderr(predicate::str::contains("--threads only applies"));
     }
+}
+
+mod thresholds_config {
+    use super::*;
 
     #[test]
-    fn grpc_valid_config_passes() {
+    fn all_threshold_metrics_validate() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com/api"
+
+[load]
+concurrency = 10
+duration = "5s"
+
+[thresholds]
+p50_latency_ms = "< 100"
+p75_latency_ms = "< 200"
+p90_latency_ms = "< 300"
+p95_latency_ms = "< 400"
+p99_latency_ms = "< 500"
+p999_latency_ms = "< 1000"
+mean_latency_ms = "< 200"
+max_latency_ms = "< 2000"
+error_rate = "< 0.01"
+rps = "> 100"
+check_pass_rate = "> 0.95"
+"#,
+        )
+        .unwrap();
+
         kaioken()
-            .args([
-                "run",
-                "https://localhost:50051",
-                "--grpc-service",
-                "hello.Greeter",
-                "--grpc-method",
-                "SayHello",
-                "--dry-run",
-                "-y",
-            ])
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
             .success()
-            .stderr(predicate::str::contains("Configuration validated"));
+            .stderr(predicate::str::contains("11 defined"));
     }
 
     #[test]
-    fn grpc_binary_body_file_works() {
+    fn invalid_threshold_metric_fails() {
         let dir = tempdir().unwrap();
-        let body_file = dir.path().join("request.bin");
+        let config = dir.path().join("config.toml");
 
-        // Write binary protobuf-like data (non-UTF8)
-        // This is a simple protobuf: field 1, varint, value 150
-        // In real use this would be actual protobuf bytes
-        let binary_data: Vec<u8> = vec![0x08, 0x96, 0x01, 0xFF, 0xFE, 0x00, 0x80];
-        fs::write(&body_file, &binary_data).unwrap();
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com/api"
+
+[load]
+concurrency = 10
+duration = "5s"
+
+[thresholds]
+invalid_metric = "< 100"
+"#,
+        )
+        .unwrap();
 
+        // Unknown threshold metrics must fail with helpful error listing valid ones
         kaioken()
-            .args([
-                "run",
-                "https://localhost:50051",
-                "--grpc-service",
-                "hello.Greeter",
-                "--grpc-method",
-                "SayHello",
-                "--body-file",
-                body_file.to_str().unwrap(),
-                "--dry-run",
-                "-y",
-            ])
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .success()
-            .stderr(predicate::str::contains("Configuration validated"));
+            .failure()
+            .stderr(predicate::str::contains("unknown field"))
+            .stderr(predicate::str::contains("p95_latency_ms"));
     }
 }
 
-#[cfg(feature = "http3")]
-mod http3_config {
+mod checks_config {
     use super::*;
 
     #[test]
-    fn http3_requires_https() {
+    fn status_checks_validate() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com/api"
+
+[load]
+concurrency = 10
+duration = "5s"
+
+[[checks]]
+name = "is_200"
+condition = "status == 200"
+
+[[checks]]
+name = "is_success"
+condition = "status in [200, 201, 204]"
+
+[[checks]]
+name = "not_error"
+condition = "status < 400"
+"#,
+        )
+        .unwrap();
+
         kaioken()
-            .args(["run", "http://localhost:8080", "--http3", "--dry-run", "-y"])
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .failure()
-            .stderr(predicate::str::contains("HTTP/3 requires HTTPS"));
+            .success()
+            .stderr(predicate::str::contains("Checks"))
+            .stderr(predicate::str::contains("3 defined"));
     }
 
     #[test]
-    fn http3_with_https_passes() {
+    fn body_checks_validate() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com/api"
+
+[load]
+concurrency = 10
+duration = "5s"
+
+[[checks]]
+name = "has_success"
+condition = 'body contains "success"'
+
+[[checks]]
+name = "valid_json"
+condition = 'body matches "\{.*\}"'
+"#,
+        )
+        .unwrap();
+
         kaioken()
-            .args([
-                "run",
-                "https://localhost:8080",
-                "--http3",
-                "--dry-run",
-                "-y",
-            ])
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .success()
-            .stderr(predicate::str::contains("Configuration validated"));
+            .success();
     }
 }
 
-#[cfg(all(feature = "http3", feature = "grpc"))]
-mod protocol_conflict {
+mod scenario_without_target {
     use super::*;
 
     #[test]
-    fn http3_and_grpc_conflict() {
+    fn scenario_only_config_fails_with_explicit_message() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[load]
+concurrency = 10
+duration = "10s"
+
+[[scenarios]]
+name = "get_users"
+url = "https://example.com/users"
+method = "GET"
+weight = 1
+"#,
+        )
+        .unwrap();
+
+        // Should fail with explicit message about needing [target]
         kaioken()
-            .args([
-                "run",
-                "https://localhost:8080",
-                "--http3",
-                "--grpc-service",
-                "hello.Greeter",
-                "--grpc-method",
-                "SayHello",
-                "--dry-run",
-                "-y",
-            ])
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
             .failure()
-            .stderr(predicate::str::contains(
-                "Cannot use --http3 with --grpc-service",
-            ));
+            .stderr(predicate::str::contains("[target]"))
+            .stderr(predicate::str::contains("[[scenarios]]"));
     }
 }
 
-mod v1_3_config {
+mod extraction_config {
     use super::*;
 
     #[test]
-    fn rand_regex_url_config_validates() {
+    fn json_extraction_validates() {
         let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
 
@@ -886,7 +1128,30 @@ mod v1_3_config {
             &config,
             r#"
 [target]
-rand_regex_url = "https://example\\.com/users/[0-9]+"
+url = "https://example.com/api"
+
+[load]
+concurrency = 10
+duration = "5s"
+
+[[scenarios]]
+name = "login"
+url = "https://example.com/auth"
+method = "POST"
+body = '{"user": "test"}'
+weight = 0
+
+[scenarios.extract]
+token = "json:$.access_token"
+
+[[scenarios]]
+name = "profile"
+url = "https://example.com/me"
+method = "GET"
+weight = 1
+
+[scenarios.headers]
+Authorization = "Bearer ${token}"
 "#,
         )
         .unwrap();
@@ -894,53 +1159,58 @@ rand_regex_url = "https://example\\.com/users/[0-9]+"
         kaioken()
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .success()
-            .stderr(predicate::str::contains("Configuration validated"));
+            .success();
     }
 
     #[test]
-    fn urls_from_file_config_validates() {
+    fn extraction_failure_rate_threshold_validates() {
         let dir = tempdir().unwrap();
-        let urls_file = dir.path().join("urls.txt");
-        fs::write(&urls_file, "https://example.com/1\nhttps://example.com/2\n").unwrap();
-
         let config = dir.path().join("config.toml");
+
         fs::write(
             &config,
-            format!(
-                r#"
+            r#"
 [target]
-urls_from_file = "{}"
+url = "https://example.com/api"
+
+[[scenarios]]
+name = "login"
+url = "https://example.com/auth"
+
+[scenarios.extract]
+token = "json:$.access_token"
+
+[thresholds]
+extraction_failure_rate = "< 0.01"
 "#,
-                urls_file.to_str().unwrap().replace('\\', "\\\\")
-            ),
         )
         .unwrap();
 
         kaioken()
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .success()
-            .stderr(predicate::str::contains("Configuration validated"));
+            .success();
     }
+}
+
+mod env_var_interpolation {
+    use super::*;
 
     #[test]
-    fn body_lines_config_validates() {
+    fn env_var_with_default_validates() {
         let dir = tempdir().unwrap();
-        let body_file = dir.path().join("bodies.jsonl");
-        fs::write(&body_file, "{\"id\":1}\n{\"id\":2}\n").unwrap();
-
         let config = dir.path().join("config.toml");
+
         fs::write(
             &config,
-            format!(
-                r#"
+            r#"
 [target]
-url = "https://example.com/api"
-body_lines_file = "{}"
+url = "${API_URL:-https://example.com/api}"
+
+[load]
+concurrency = 10
+duration = "5s"
 "#,
-                body_file.to_str().unwrap().replace('\\', "\\\\")
-            ),
         )
         .unwrap();
 
@@ -948,11 +1218,15 @@ body_lines_file = "{}"
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
             .success()
-            .stderr(predicate::str::contains("Configuration validated"));
+            .stderr(predicate::str::contains("https://example.com/api"));
     }
+}
+
+mod websocket_config {
+    use super::*;
 
     #[test]
-    fn connect_to_config_validates() {
+    fn websocket_url_validates() {
         let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
 
@@ -960,8 +1234,11 @@ body_lines_file = "{}"
             &config,
             r#"
 [target]
-url = "https://example.com/api"
-connect_to = "example.com:127.0.0.1:8080"
+url = "ws://localhost:8080/ws"
+
+[load]
+concurrency = 10
+duration = "5s"
 "#,
         )
         .unwrap();
@@ -970,11 +1247,11 @@ connect_to = "example.com:127.0.0.1:8080"
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
             .success()
-            .stderr(predicate::str::contains("Configuration validated"));
+            .stderr(predicate::str::contains("ws://localhost:8080/ws"));
     }
 
     #[test]
-    fn burst_mode_config_validates() {
+    fn websocket_wss_url_validates() {
         let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
 
@@ -982,11 +1259,11 @@ connect_to = "example.com:127.0.0.1:8080"
             &config,
             r#"
 [target]
-url = "https://example.com/api"
+url = "wss://example.com/socket"
 
 [load]
-burst_rate = 100
-burst_delay = "1s"
+concurrency = 5
+duration = "10s"
 "#,
         )
         .unwrap();
@@ -995,11 +1272,11 @@ burst_delay = "1s"
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
             .success()
-            .stderr(predicate::str::contains("Configuration validated"));
+            .stderr(predicate::str::contains("wss://example.com/socket"));
     }
 
     #[test]
-    fn db_url_config_validates() {
+    fn websocket_config_section_validates() {
         let dir = tempdir().unwrap();
         let config = dir.path().join("config.toml");
 
@@ -1007,10 +1284,15 @@ burst_delay = "1s"
             &config,
             r#"
 [target]
-url = "https://example.com/api"
+url = "ws://localhost:8080/ws"
 
 [load]
-db_url = "results.db"
+concurrency = 10
+duration = "5s"
+
+[websocket]
+message_interval = "50ms"
+mode = "echo"
 "#,
         )
         .unwrap();
@@ -1018,7 +1300,3207 @@ db_url = "results.db"
         kaioken()
             .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
             .assert()
-            .success()
-            .stderr(predicate::str::contains("Configuration validated"));
+            .success();
+    }
+
+    #[test]
+    fn websocket_fire_and_forget_mode_validates() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "ws://localhost:8080/events"
+
+[load]
+concurrency = 20
+duration = "30s"
+
+[websocket]
+message_interval = "10ms"
+mode = "fire_and_forget"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn websocket_subprotocols_validate() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "wss://localhost:8080/graphql"
+
+[target.headers]
+Cookie = "session=abc123"
+
+[websocket]
+subprotocols = ["graphql-ws"]
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Subprotocol: graphql-ws"));
+    }
+
+    #[test]
+    fn websocket_zero_reconnect_interval_fails() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "ws://localhost:8080/ws"
+
+[websocket]
+reconnect_every = "0s"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("ws_reconnect_every"));
+    }
+
+    #[test]
+    fn websocket_churn_must_be_a_percentage() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        for websocket in ["churn = 150", "churn = 5\nreconnect_every = \"30s\""] {
+            fs::write(
+                &config,
+                format!(
+                    "[target]\nurl = \"ws://localhost:8080/ws\"\n\n[websocket]\n{}\n",
+                    websocket
+                ),
+            )
+            .unwrap();
+
+            kaioken()
+                .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+                .assert()
+                .failure()
+                .stderr(predicate::str::contains("ws_churn"));
+        }
+    }
+
+    #[test]
+    fn websocket_broadcast_body_requires_tag() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "ws://localhost:8080/chat"
+body = '{"type":"chat"}'
+
+[websocket]
+mode = "broadcast"
+publishers = 1
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("${BROADCAST_TAG}"));
+    }
+
+    #[test]
+    fn websocket_compression_validates() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "ws://localhost:8080/ws"
+
+[websocket]
+compression = true
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("permessage-deflate"));
+    }
+}
+
+#[cfg(feature = "grpc")]
+mod grpc_config {
+    use super::*;
+
+    #[test]
+    fn grpc_service_requires_method() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:50051",
+                "--grpc-service",
+                "hello.Greeter",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Both --grpc-service and --grpc-method must be provided together",
+            ));
+    }
+
+    #[test]
+    fn grpc_method_requires_service() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:50051",
+                "--grpc-method",
+                "SayHello",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Both --grpc-service and --grpc-method must be provided together",
+            ));
+    }
+
+    #[test]
+    fn grpc_empty_service_rejected() {
+        // Empty string is treated as "not provided", so triggers the pairing check
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:50051",
+                "--grpc-service",
+                "",
+                "--grpc-method",
+                "SayHello",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("must be provided together"));
+    }
+
+    #[test]
+    fn grpc_insecure_rejected() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:50051",
+                "--grpc-service",
+                "hello.Greeter",
+                "--grpc-method",
+                "SayHello",
+                "--insecure",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--insecure is not supported for gRPC",
+            ));
+    }
+
+    #[test]
+    fn grpc_valid_config_passes() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:50051",
+                "--grpc-service",
+                "hello.Greeter",
+                "--grpc-method",
+                "SayHello",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Configuration validated"));
+    }
+
+    #[test]
+    fn grpc_binary_body_file_works() {
+        let dir = tempdir().unwrap();
+        let body_file = dir.path().join("request.bin");
+
+        // Write binary protobuf-like data (non-UTF8)
+        // This is a simple protobuf: field 1, varint, value 150
+        // In real use this would be actual protobuf bytes
+        let binary_data: Vec<u8> = vec![0x08, 0x96, 0x01, 0xFF, 0xFE, 0x00, 0x80];
+        fs::write(&body_file, &binary_data).unwrap();
+
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:50051",
+                "--grpc-service",
+                "hello.Greeter",
+                "--grpc-method",
+                "SayHello",
+                "--body-file",
+                body_file.to_str().unwrap(),
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Configuration validated"));
+    }
+}
+
+#[cfg(feature = "http3")]
+mod http3_config {
+    use super::*;
+
+    #[test]
+    fn http3_requires_https() {
+        kaioken()
+            .args(["run", "http://localhost:8080", "--http3", "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("HTTP/3 requires HTTPS"));
+    }
+
+    #[test]
+    fn http3_with_https_passes() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8080",
+                "--http3",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Configuration validated"));
+    }
+
+    #[test]
+    fn http3_arrival_rate_is_open_model() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8443",
+                "--http3",
+                "--arrival-rate",
+                "100",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Load Model:  Open (arrival rate)"));
+    }
+
+    #[test]
+    fn http3_0rtt_requires_http3() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8080",
+                "--http3-0rtt",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--http3-0rtt requires --http3"));
+    }
+
+    #[test]
+    fn protocol_race_validates() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8443",
+                "--protocol-race",
+                "split",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("HTTP/2 vs HTTP/3 race (split)"));
+
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8443",
+                "--protocol-race",
+                "parallel",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid --protocol-race mode"));
+
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8443",
+                "--protocol-race",
+                "sequential",
+                "--http3",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("drop --http2/--http3"));
+
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8443",
+                "--protocol-race",
+                "split",
+                "-c",
+                "1",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("at least 2 VUs"));
+    }
+
+    #[test]
+    fn http3_rejects_proxy() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8080",
+                "--http3",
+                "--proxy",
+                "http://proxy:3128",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--proxy is not supported with --http3",
+            ));
+    }
+
+    #[test]
+    fn http3_scenarios_require_https() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://localhost:8443"
+
+[[scenarios]]
+name = "secure"
+url = "https://localhost:8443/a"
+
+[[scenarios]]
+name = "plain"
+url = "http://localhost:8080/b"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args([
+                "run",
+                "-f",
+                config.to_str().unwrap(),
+                "--http3",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Scenario 'plain': HTTP/3 requires HTTPS",
+            ));
+    }
+}
+
+#[cfg(feature = "socketio")]
+mod socketio_config {
+    use super::*;
+
+    #[test]
+    fn socketio_event_validates() {
+        kaioken()
+            .args([
+                "run",
+                "http://localhost:3000",
+                "--socketio-event",
+                "chat",
+                "--socketio-namespace",
+                "/rooms",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Socket.IO:   event=chat namespace=/rooms",
+            ));
+    }
+
+    #[test]
+    fn socketio_namespace_requires_slash() {
+        kaioken()
+            .args([
+                "run",
+                "http://localhost:3000",
+                "--socketio-event",
+                "chat",
+                "--socketio-namespace",
+                "rooms",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("must start with '/'"));
+    }
+}
+
+#[cfg(feature = "mqtt")]
+mod mqtt_config {
+    use super::*;
+
+    #[test]
+    fn mqtt_splits_publishers_and_subscribers() {
+        kaioken()
+            .args([
+                "run",
+                "mqtt://localhost:1883",
+                "-c",
+                "10",
+                "--mqtt-topic",
+                "sensors/${PUBLISHER}/temp",
+                "--mqtt-qos",
+                "1",
+                "--mqtt-subscriber-ratio",
+                "0.8",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "MQTT:        topic=sensors/${PUBLISHER}/temp qos=1 (2 publishers, 8 subscribers)",
+            ));
+    }
+
+    #[test]
+    fn mqtt_topic_placeholder_must_fill_level() {
+        kaioken()
+            .args([
+                "run",
+                "mqtt://localhost:1883",
+                "--mqtt-topic",
+                "sensors/dev-${PUBLISHER}",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("must fill a whole topic level"));
+    }
+
+    #[test]
+    fn mqtt_subscribers_need_broadcast_tag() {
+        kaioken()
+            .args([
+                "run",
+                "mqtt://localhost:1883",
+                "-b",
+                "hello",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("${BROADCAST_TAG}"));
+    }
+
+    #[test]
+    fn mqtt_rejects_arrival_rate() {
+        kaioken()
+            .args([
+                "run",
+                "mqtt://localhost:1883",
+                "--arrival-rate",
+                "10",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("MQTT mode runs a fixed set"));
+    }
+}
+
+#[cfg(feature = "redis")]
+mod redis_config {
+    use super::*;
+
+    #[test]
+    fn redis_commands_listed() {
+        kaioken()
+            .args([
+                "run",
+                "redis://localhost:6379/0",
+                "--redis-command",
+                "SET user:${RANDOM_KEY} \"hello world\"",
+                "--redis-command",
+                "GET user:${RANDOM_KEY}",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Redis:       2 command template(s)",
+            ))
+            .stderr(predicate::str::contains("  - GET user:${RANDOM_KEY}"));
+    }
+
+    #[test]
+    fn redis_command_requires_redis_target() {
+        kaioken()
+            .args([
+                "run",
+                "http://localhost:8080",
+                "--redis-command",
+                "PING",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("requires a redis:// target"));
+    }
+
+    #[test]
+    fn redis_rejects_bad_database() {
+        kaioken()
+            .args(["run", "redis://localhost/cache", "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid Redis database number"));
+    }
+}
+
+mod jsonrpc_config {
+    use super::*;
+
+    #[test]
+    fn jsonrpc_section_validates() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "http://localhost:8545"
+
+[jsonrpc]
+method = "eth_getBalance"
+params = ["0x407d73d8a49eeb85d32cf465507dd71d507100c1", "latest"]
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Method:      POST"))
+            .stderr(predicate::str::contains(
+                "JSON-RPC:    method=eth_getBalance",
+            ));
+    }
+
+    #[test]
+    fn jsonrpc_rejects_scalar_params() {
+        kaioken()
+            .args([
+                "run",
+                "http://localhost:8545",
+                "--jsonrpc-method",
+                "eth_blockNumber",
+                "--jsonrpc-params",
+                "42",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("must be an array or object"));
+    }
+
+    #[test]
+    fn jsonrpc_rejects_invalid_params_json() {
+        kaioken()
+            .args([
+                "run",
+                "http://localhost:8545",
+                "--jsonrpc-method",
+                "eth_blockNumber",
+                "--jsonrpc-params",
+                "[1,",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid --jsonrpc-params JSON"));
+    }
+
+    #[test]
+    fn jsonrpc_rejects_config_body() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "http://localhost:8545"
+body = '{"hello":"world"}'
+
+[jsonrpc]
+method = "eth_blockNumber"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "JSON-RPC mode builds the request body",
+            ));
+    }
+}
+
+#[cfg(all(feature = "http3", feature = "grpc"))]
+mod protocol_conflict {
+    use super::*;
+
+    #[test]
+    fn http3_and_grpc_conflict() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8080",
+                "--http3",
+                "--grpc-service",
+                "hello.Greeter",
+                "--grpc-method",
+                "SayHello",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Cannot use --http3 with --grpc-service",
+            ));
+    }
+}
+
+mod v1_3_config {
+    use super::*;
+
+    #[test]
+    fn rand_regex_url_config_validates() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+rand_regex_url = "https://example\\.com/users/[0-9]+"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Configuration validated"));
+    }
+
+    #[test]
+    fn urls_from_file_config_validates() {
+        let dir = tempdir().unwrap();
+        let urls_file = dir.path().join("urls.txt");
+        fs::write(&urls_file, "https://example.com/1\nhttps://example.com/2\n").unwrap();
+
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            format!(
+                r#"
+[target]
+urls_from_file = "{}"
+"#,
+                urls_file.to_str().unwrap().replace('\\', "\\\\")
+            ),
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Configuration validated"));
+    }
+
+    #[test]
+    fn body_lines_config_validates() {
+        let dir = tempdir().unwrap();
+        let body_file = dir.path().join("bodies.jsonl");
+        fs::write(&body_file, "{\"id\":1}\n{\"id\":2}\n").unwrap();
+
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            format!(
+                r#"
+[target]
+url = "https://example.com/api"
+body_lines_file = "{}"
+"#,
+                body_file.to_str().unwrap().replace('\\', "\\\\")
+            ),
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Configuration validated"));
+    }
+
+    #[test]
+    fn connect_to_config_validates() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com/api"
+connect_to = "example.com:127.0.0.1:8080"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Configuration validated"));
+    }
+
+    #[test]
+    fn burst_mode_config_validates() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com/api"
+
+[load]
+burst_rate = 100
+burst_delay = "1s"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Configuration validated"));
+    }
+
+    #[test]
+    fn db_url_config_validates() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com/api"
+
+[load]
+db_url = "results.db"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Configuration validated"));
+    }
+}
+
+mod backpressure_config {
+    use super::*;
+
+    #[test]
+    fn backpressure_policy_from_toml() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "http://localhost:8080"
+
+[load]
+burst_rate = 100
+burst_delay = "1s"
+backpressure = "drop"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Backpressure: drop"));
+    }
+
+    #[test]
+    fn invalid_backpressure_policy_rejected() {
+        kaioken()
+            .args([
+                "run",
+                "http://localhost:8080",
+                "--backpressure",
+                "spill",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Invalid backpressure policy 'spill'",
+            ));
+    }
+}
+
+mod tls_backend_config {
+    use super::*;
+
+    #[test]
+    fn rustls_backend_accepted() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8443",
+                "--tls-backend",
+                "rustls",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn invalid_tls_backend_rejected() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8443",
+                "--tls-backend",
+                "boringssl",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Invalid --tls-backend 'boringssl'",
+            ));
+    }
+
+    #[cfg(not(feature = "native-tls"))]
+    #[test]
+    fn native_backend_requires_feature() {
+        kaioken()
+            .args([
+                "run",
+                "https://localhost:8443",
+                "--tls-backend",
+                "native",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--features native-tls"));
+    }
+
+    #[cfg(feature = "native-tls")]
+    #[test]
+    fn native_backend_from_toml() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://localhost:8443"
+tls_backend = "native"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("TLS:         native"));
+    }
+
+    #[cfg(feature = "native-tls")]
+    #[test]
+    fn native_backend_rejected_for_non_http_targets() {
+        kaioken()
+            .args([
+                "run",
+                "tcp://localhost:9000",
+                "--tls-backend",
+                "native",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("only applies to http://"));
+    }
+}
+
+mod endpoint_groups_config {
+    use super::*;
+
+    #[test]
+    fn endpoint_groups_validate() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com/products/red-shoes"
+
+[[endpoint_groups]]
+pattern = "/products/[^/]+"
+name = "/products/{slug}"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn invalid_endpoint_group_pattern_rejected() {
+        kaioken()
+            .args([
+                "run",
+                "https://example.com/users/1",
+                "--endpoint-group",
+                "/users/(\\d+=/users/{id}",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid endpoint group pattern"));
+    }
+}
+
+mod timeline_interval_config {
+    use super::*;
+
+    #[test]
+    fn timeline_interval_below_minimum_rejected() {
+        kaioken()
+            .args([
+                "run",
+                "https://example.com",
+                "--timeline-interval",
+                "10ms",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Timeline interval must be at least 100ms",
+            ));
+    }
+}
+
+mod trace_propagation_config {
+    use super::*;
+
+    #[test]
+    fn trace_propagation_from_toml_accepted() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("kaioken.toml");
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com"
+trace_propagation = "b3"
+trace_slowest = 20
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn unknown_trace_format_rejected() {
+        kaioken()
+            .args([
+                "run",
+                "https://example.com",
+                "--trace-propagation",
+                "zipkin",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("expected w3c or b3"));
+    }
+}
+
+mod apdex_config {
+    use super::*;
+
+    #[test]
+    fn apdex_threshold_requires_target_time() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("kaioken.toml");
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com"
+
+[thresholds]
+apdex = "> 0.9"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("needs a target time"));
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap()])
+            .args(["--apdex-t", "300ms", "--dry-run", "-y"])
+            .assert()
+            .success();
+    }
+}
+
+mod auto_warmup_config {
+    use super::*;
+
+    #[test]
+    fn auto_warmup_from_toml_accepted() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("kaioken.toml");
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com"
+
+[load]
+auto_warmup = true
+warmup = "30s"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn auto_warmup_rejected_for_websocket_and_burst() {
+        kaioken()
+            .args([
+                "run",
+                "wss://example.com/ws",
+                "--auto-warmup",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("only applies to http://"));
+
+        kaioken()
+            .args(["run", "https://example.com", "--auto-warmup"])
+            .args([
+                "--burst-rate",
+                "10",
+                "--burst-delay",
+                "1s",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("not supported with burst mode"));
+    }
+}
+
+mod slow_log_config {
+    use super::*;
+
+    #[test]
+    fn slow_log_from_toml_accepted() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("kaioken.toml");
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com"
+
+[load]
+slow_log = { threshold = "500ms", path = "slow.ndjson" }
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn slow_log_rejects_bad_threshold_and_burst() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--slow-log", "fast", "slow.ndjson"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid --slow-log threshold"));
+
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--slow-log", "500ms", "slow.ndjson"])
+            .args(["--burst-rate", "10", "--burst-delay", "1s"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("not supported with burst mode"));
+    }
+}
+
+mod percentiles_config {
+    use super::*;
+
+    #[test]
+    fn custom_percentile_threshold_needs_the_percentile() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("kaioken.toml");
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com"
+
+[load]
+percentiles = [50, 99, 99.99]
+
+[thresholds]
+p9999_latency_ms = "< 500"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success();
+
+        // Dropping p75 also drops its threshold metric
+        let contents = fs::read_to_string(&config).unwrap();
+        fs::write(&config, format!("{}p75_latency_ms = \"< 200\"\n", contents)).unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "The p75_latency_ms threshold needs 75 in percentiles",
+            ));
+    }
+
+    #[test]
+    fn percentiles_out_of_range_rejected() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--percentiles", "50,100"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("must be between 0 and 100"));
+
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--percentiles", "99.99999"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("more than 3 decimal places"));
+    }
+}
+
+mod plugin_config {
+    use super::*;
+
+    #[cfg(not(feature = "plugins"))]
+    #[test]
+    fn plugins_require_feature() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com"
+
+[load]
+plugins = ["sign.wasm"]
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--features plugins"));
+    }
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn plugins_from_toml_validate() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com"
+
+[load]
+plugins = ["sign.wasm", "validate.wasm"]
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success();
+    }
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn plugin_only_hooks_http() {
+        kaioken()
+            .args(["run", "ws://localhost:8080", "--dry-run", "-y"])
+            .args(["--plugin", "sign.wasm"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--plugin only applies to http:// and https:// targets",
+            ));
+    }
+}
+
+mod chaos_config {
+    use super::*;
+
+    fn run_with_chaos(chaos: &str, args: &[&str]) -> assert_cmd::assert::Assert {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            format!(
+                "[target]\nurl = \"https://example.com\"\n\n[chaos]\n{}\n",
+                chaos
+            ),
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .args(args)
+            .assert()
+    }
+
+    #[test]
+    fn chaos_from_toml_accepted() {
+        run_with_chaos(
+            "latency = \"10ms..200ms\"\nlatency_rate = 0.1\nabort_rate = 0.05\nreset_rate = 0.05",
+            &[],
+        )
+        .success();
+        run_with_chaos("latency = \"50ms\"", &[]).success();
+    }
+
+    #[test]
+    fn chaos_rejects_bad_rates_and_ranges() {
+        run_with_chaos("abort_rate = 1.5", &[])
+            .failure()
+            .stderr(predicate::str::contains(
+                "abort_rate must be between 0 and 1",
+            ));
+        run_with_chaos("abort_rate = 0.6\nreset_rate = 0.6", &[])
+            .failure()
+            .stderr(predicate::str::contains("add up to more than 1"));
+        run_with_chaos("latency = \"200ms..10ms\"", &[])
+            .failure()
+            .stderr(predicate::str::contains("range is backwards"));
+        run_with_chaos("latency_rate = 0.5", &[])
+            .failure()
+            .stderr(predicate::str::contains("latency_rate requires latency"));
+        run_with_chaos("jitter = \"5ms\"", &[])
+            .failure()
+            .stderr(predicate::str::contains("unknown field"));
+    }
+
+    #[test]
+    fn chaos_rejects_open_model_and_proxied_resets() {
+        run_with_chaos("abort_rate = 0.1", &["--arrival-rate", "10"])
+            .failure()
+            .stderr(predicate::str::contains(
+                "not supported with an arrival rate",
+            ));
+        run_with_chaos(
+            "abort_rate = 0.1",
+            &["--burst-rate", "10", "--burst-delay", "1s"],
+        )
+        .failure()
+        .stderr(predicate::str::contains("not supported with burst mode"));
+        run_with_chaos("reset_rate = 0.1", &["--proxy", "http://127.0.0.1:3128"])
+            .failure()
+            .stderr(predicate::str::contains(
+                "reset_rate is not supported with --proxy",
+            ));
+    }
+}
+
+mod honor_retry_after_config {
+    use super::*;
+
+    fn run_with_load(load: &str, args: &[&str]) -> assert_cmd::assert::Assert {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            format!(
+                "[target]\nurl = \"https://example.com\"\n\n[load]\n{}\n",
+                load
+            ),
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .args(args)
+            .assert()
+    }
+
+    #[test]
+    fn honor_retry_after_from_toml_or_flag() {
+        run_with_load("honor_retry_after = true", &[])
+            .success()
+            .stderr(predicate::str::contains("Throttling:"));
+        run_with_load("concurrency = 5", &["--honor-retry-after"])
+            .success()
+            .stderr(predicate::str::contains("Throttling:"));
+        run_with_load("concurrency = 5", &[])
+            .success()
+            .stderr(predicate::str::contains("Throttling:").not());
+    }
+
+    #[test]
+    fn honor_retry_after_rejects_open_model_and_bursts() {
+        run_with_load("honor_retry_after = true\narrival_rate = 10", &[])
+            .failure()
+            .stderr(predicate::str::contains(
+                "not supported with an arrival rate",
+            ));
+        run_with_load(
+            "honor_retry_after = true",
+            &["--burst-rate", "10", "--burst-delay", "1s"],
+        )
+        .failure()
+        .stderr(predicate::str::contains("not supported with burst mode"));
+    }
+}
+
+mod target_hosts_config {
+    use super::*;
+
+    fn run_with_hosts(hosts: &str) -> assert_cmd::assert::Assert {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            format!(
+                "[target]\nurl = \"https://api.example.com/health\"\n\n{}\n",
+                hosts
+            ),
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+    }
+
+    #[test]
+    fn hosts_listed_with_their_share() {
+        run_with_hosts(
+            r#"
+[[target.hosts]]
+url = "https://us.example.com"
+weight = 3
+
+[[target.hosts]]
+url = "https://eu.example.com:8443/"
+name = "eu"
+"#,
+        )
+        .success()
+        .stderr(predicate::str::contains("Hosts:       2 defined"))
+        .stderr(predicate::str::contains(
+            "us.example.com (https://us.example.com) weight=3 (75%)",
+        ))
+        .stderr(predicate::str::contains(
+            "eu (https://eu.example.com:8443) weight=1 (25%)",
+        ));
+    }
+
+    #[test]
+    fn hosts_must_be_distinct_base_urls() {
+        run_with_hosts("[[target.hosts]]\nurl = \"https://us.example.com/v2\"\n")
+            .failure()
+            .stderr(predicate::str::contains("without a path"));
+        run_with_hosts("[[target.hosts]]\nurl = \"us.example.com\"\n")
+            .failure()
+            .stderr(predicate::str::contains("Invalid host 'us.example.com'"));
+        run_with_hosts(
+            "[[target.hosts]]\nurl = \"http://a.example.com\"\n\n[[target.hosts]]\nurl = \"https://a.example.com\"\n",
+        )
+        .failure()
+        .stderr(predicate::str::contains("Duplicate host name 'a.example.com'"));
+        run_with_hosts("[[target.hosts]]\nurl = \"https://a.example.com\"\nweight = 0\n")
+            .failure()
+            .stderr(predicate::str::contains("weight 0"));
+    }
+
+    #[test]
+    fn host_header_list_adds_virtual_hosts() {
+        let dir = tempdir().unwrap();
+        let list = dir.path().join("tenants.txt");
+        fs::write(
+            &list,
+            "tenant-a.example.com\n\n# off\ntenant-b.example.com\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "https://gateway.example.com", "--dry-run", "-y"])
+            .args(["--host-header-list", list.to_str().unwrap()])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Hosts:       2 defined"))
+            .stderr(predicate::str::contains(
+                "tenant-b.example.com (Host: tenant-b.example.com) weight=1 (50%)",
+            ));
+
+        kaioken()
+            .args(["run", "https://gateway.example.com", "--dry-run", "-y"])
+            .args(["--host-header-list", list.to_str().unwrap(), "--http2"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Host header overrides need HTTP/1.1",
+            ));
+
+        run_with_hosts("[[target.hosts]]\nweight = 2\n")
+            .failure()
+            .stderr(predicate::str::contains("need a url or a host_header"));
+    }
+}
+
+mod ip_family_config {
+    use super::*;
+
+    fn dry_run(url: &str, args: &[&str]) -> assert_cmd::assert::Assert {
+        kaioken()
+            .args(["run", url, "--dry-run", "-y"])
+            .args(args)
+            .assert()
+    }
+
+    #[test]
+    fn family_flags_shown_in_dry_run() {
+        dry_run("https://example.com", &["-6", "--no-happy-eyeballs"])
+            .success()
+            .stderr(predicate::str::contains("IP family:   ipv6"))
+            .stderr(predicate::str::contains("Happy eyeballs: off"));
+        dry_run("https://example.com", &["--ipv4", "--ipv6"])
+            .failure()
+            .stderr(predicate::str::contains("cannot be used with"));
+    }
+
+    #[test]
+    fn family_from_toml() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\nip_family = \"ipv4\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("IP family:   ipv4"));
+
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\nip_family = \"v4\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid ip_family 'v4'"));
+    }
+
+    #[test]
+    fn family_must_match_addresses_that_skip_dns() {
+        dry_run("http://127.0.0.1:8080/health", &["--ipv6"])
+            .failure()
+            .stderr(predicate::str::contains(
+                "--ipv6 conflicts with the address 127.0.0.1",
+            ));
+        dry_run("http://[::1]/health", &["--ipv6"]).success();
+        dry_run(
+            "https://example.com",
+            &["--ipv6", "--connect-to", "example.com:443:127.0.0.1:8443"],
+        )
+        .failure()
+        .stderr(predicate::str::contains(
+            "--ipv6 conflicts with the address 127.0.0.1",
+        ));
+        dry_run("ws://example.com/socket", &["--ipv4"])
+            .failure()
+            .stderr(predicate::str::contains(
+                "--ipv4 is not supported for WebSocket targets",
+            ));
+    }
+}
+
+mod network_profile_config {
+    use super::*;
+
+    fn dry_run(args: &[&str]) -> assert_cmd::assert::Assert {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(args)
+            .assert()
+    }
+
+    #[test]
+    fn presets_and_overrides() {
+        dry_run(&["--network-profile", "dsl"])
+            .success()
+            .stderr(predicate::str::contains(
+                "Network:     dsl (1.5 Mbit/s down, 384 kbit/s up, +50ms RTT)",
+            ));
+        dry_run(&["--network-profile", "4g", "--network-rtt", "20ms"])
+            .success()
+            .stderr(predicate::str::contains(
+                "4g (9 Mbit/s down, 9 Mbit/s up, +20ms RTT)",
+            ));
+        dry_run(&["--network-profile", "5g"])
+            .failure()
+            .stderr(predicate::str::contains("Invalid network profile '5g'"));
+        dry_run(&["--network-rtt", "20ms"])
+            .failure()
+            .stderr(predicate::str::contains("require --network-profile"));
+    }
+
+    #[test]
+    fn custom_profile_from_toml() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        let write = |network: &str| {
+            fs::write(
+                &config,
+                format!(
+                    "[target]\nurl = \"https://example.com\"\n\n[network]\n{}\n",
+                    network
+                ),
+            )
+            .unwrap();
+        };
+        let run = || {
+            kaioken()
+                .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+                .assert()
+        };
+
+        write("profile = \"custom\"\ndown = \"10mbit\"\nup = \"512kbit\"\nrtt = \"100ms\"");
+        run().success().stderr(predicate::str::contains(
+            "custom (10 Mbit/s down, 512 kbit/s up, +100ms RTT)",
+        ));
+        write("profile = \"custom\"\nrtt = \"100ms\"");
+        run()
+            .failure()
+            .stderr(predicate::str::contains("needs a download bandwidth"));
+        write("profile = \"custom\"\ndown = \"10MB\"");
+        run()
+            .failure()
+            .stderr(predicate::str::contains("Invalid bandwidth '10MB'"));
+    }
+
+    #[test]
+    fn http_targets_only() {
+        kaioken()
+            .args(["run", "ws://example.com/socket", "--dry-run", "-y"])
+            .args(["--network-profile", "3g"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--network-profile only applies to http:// and https:// targets",
+            ));
+    }
+}
+
+mod deadline_config {
+    use super::*;
+
+    fn dry_run(args: &[&str]) -> assert_cmd::assert::Assert {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(args)
+            .assert()
+    }
+
+    #[test]
+    fn retries_and_deadline_from_flags_or_toml() {
+        dry_run(&["--retries", "2", "--deadline", "2s"])
+            .success()
+            .stderr(predicate::str::contains("Retries:     2"))
+            .stderr(predicate::str::contains("Deadline:    2s per request"));
+        dry_run(&[])
+            .success()
+            .stderr(predicate::str::contains("Deadline:").not());
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[load]\nretries = 3\ndeadline = \"1500ms\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Retries:     3"))
+            .stderr(predicate::str::contains("Deadline:    1.5s per request"));
+    }
+
+    #[test]
+    fn deadline_rejects_zero_and_open_model() {
+        dry_run(&["--deadline", "0s"])
+            .failure()
+            .stderr(predicate::str::contains("must be greater than zero"));
+        dry_run(&["--deadline", "2s", "--arrival-rate", "10"])
+            .failure()
+            .stderr(predicate::str::contains(
+                "--deadline is not supported with an arrival rate",
+            ));
+        dry_run(&[
+            "--retries",
+            "2",
+            "--burst-rate",
+            "10",
+            "--burst-delay",
+            "1s",
+        ])
+        .failure()
+        .stderr(predicate::str::contains(
+            "--retries is not supported with burst mode",
+        ));
+    }
+}
+
+mod proxy_list_config {
+    use super::*;
+
+    fn run_with_config(config: &str, args: &[&str]) -> assert_cmd::assert::Assert {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("proxies.txt"),
+            "http://10.0.0.1:3128\n# spare\nsocks5://10.0.0.2:1080\n",
+        )
+        .unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, config.replace("DIR", dir.path().to_str().unwrap())).unwrap();
+        kaioken()
+            .args(["run", "-f", path.to_str().unwrap(), "--dry-run", "-y"])
+            .args(args)
+            .assert()
+    }
+
+    #[test]
+    fn proxy_list_from_toml_or_flag() {
+        run_with_config(
+            "[target]\nurl = \"https://example.com\"\nproxy_list = \"DIR/proxies.txt\"\n",
+            &[],
+        )
+        .success()
+        .stderr(predicate::str::contains(
+            "Proxy list:  2 proxies, rotated per vu",
+        ));
+
+        let dir = tempdir().unwrap();
+        let list = dir.path().join("proxies.txt");
+        fs::write(&list, "http://10.0.0.1:3128\n").unwrap();
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--proxy-list", list.to_str().unwrap()])
+            .args(["--proxy-rotation", "request"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Proxy list:  1 proxies, rotated per request",
+            ));
+    }
+
+    #[test]
+    fn proxy_list_rejects_bad_settings() {
+        let config = "[target]\nurl = \"https://example.com\"\nproxy_list = \"DIR/proxies.txt\"\n";
+        run_with_config(config, &["--proxy-rotation", "sticky"])
+            .failure()
+            .stderr(predicate::str::contains("Invalid proxy rotation 'sticky'"));
+        run_with_config(config, &["--burst-rate", "10", "--burst-delay", "1s"])
+            .failure()
+            .stderr(predicate::str::contains(
+                "--proxy-list is not supported with burst mode",
+            ));
+        run_with_config(
+            "[target]\nurl = \"https://example.com\"\nproxy = \"http://p:8080\"\nproxy_list = \"DIR/proxies.txt\"\n",
+            &[],
+        )
+        .failure()
+        .stderr(predicate::str::contains("mutually exclusive"));
+        run_with_config(
+            "[target]\nurl = \"https://example.com\"\nproxy_list = \"DIR/missing.txt\"\n",
+            &[],
+        )
+        .failure()
+        .stderr(predicate::str::contains("Failed to read proxy list"));
+        run_with_config(
+            "[target]\nurl = \"https://example.com\"\n",
+            &["--proxy-rotation", "request"],
+        )
+        .failure()
+        .stderr(predicate::str::contains(
+            "--proxy-rotation requires --proxy-list",
+        ));
+    }
+
+    #[test]
+    fn scenarios_override_the_proxy() {
+        run_with_config(
+            r#"[target]
+url = "https://example.com"
+proxy = "http://proxy:8080"
+
+[[scenarios]]
+name = "internal"
+url = "https://internal.example.com"
+proxy = ""
+
+[[scenarios]]
+name = "eu"
+url = "https://example.com/eu"
+proxy = "http://eu-proxy:8080"
+"#,
+            &[],
+        )
+        .success()
+        .stderr(predicate::str::contains("weight=1 (50%) direct"))
+        .stderr(predicate::str::contains("weight=1 (50%) own proxy"));
+        run_with_config(
+            r#"[target]
+url = "https://example.com"
+
+[[scenarios]]
+name = "socket"
+url = "wss://example.com/ws"
+proxy = "http://proxy:8080"
+"#,
+            &[],
+        )
+        .failure()
+        .stderr(predicate::str::contains(
+            "Scenario 'socket': proxy only applies to HTTP scenarios",
+        ));
+    }
+}
+
+mod cookie_seeding_config {
+    use super::*;
+
+    fn dry_run_with_cookies(cookies: &str, target: &str) -> assert_cmd::assert::Assert {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("cookies.txt");
+        fs::write(&file, cookies).unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            format!(
+                "[target]\nurl = \"https://app.example.com\"\ncookie_file = \"{}\"\n{}\n",
+                file.display(),
+                target
+            ),
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+    }
+
+    #[test]
+    fn seeds_from_cookie_file_and_map() {
+        dry_run_with_cookies(
+            "# Netscape HTTP Cookie File\n\
+             .example.com\tTRUE\t/\tTRUE\t0\tsession\tabc\n\
+             #HttpOnly_app.example.com\tFALSE\t/\tFALSE\t4102444800\tcsrf\txyz\n\
+             app.example.com\tFALSE\t/\tFALSE\t1000000000\told\tgone\n",
+            "\n[target.cookies]\nlocale = \"en\"",
+        )
+        .success()
+        .stderr(predicate::str::contains("Cookies:     3 seeded"));
+    }
+
+    #[test]
+    fn rejects_malformed_cookie_files() {
+        dry_run_with_cookies("example.com\tFALSE\t/\tsession\tabc\n", "")
+            .failure()
+            .stderr(predicate::str::contains(
+                "line 1: expected 7 tab-separated fields",
+            ));
+        dry_run_with_cookies("example.com\tFALSE\t/\tFALSE\tsoon\tsession\tabc\n", "")
+            .failure()
+            .stderr(predicate::str::contains("invalid expiry 'soon'"));
+        dry_run_with_cookies("# only comments\n", "")
+            .failure()
+            .stderr(predicate::str::contains("has no unexpired cookies"));
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--cookie-file", "/nonexistent/cookies.txt"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Failed to read cookie file"));
+    }
+}
+
+mod csrf_config {
+    use super::*;
+
+    #[test]
+    fn csrf_source_and_header_from_cli_or_config() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args([
+                "--csrf",
+                "cookie:XSRF-TOKEN",
+                "--csrf-header",
+                "X-XSRF-TOKEN",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "CSRF:        token sent in X-XSRF-TOKEN",
+            ));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[csrf]\nfrom = \"json:data.csrf\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "CSRF:        token sent in X-CSRF-Token",
+            ));
+    }
+
+    #[test]
+    fn rejects_invalid_csrf_settings() {
+        let run = |args: &[&str]| {
+            kaioken()
+                .args(["run", "https://example.com", "--dry-run", "-y"])
+                .args(args)
+                .assert()
+                .failure()
+        };
+        run(&["--csrf", "body"]).stderr(predicate::str::contains(
+            "Unknown CSRF token source: 'body'",
+        ));
+        run(&["--csrf-header", "X-CSRF-Token"])
+            .stderr(predicate::str::contains("--csrf-header requires --csrf"));
+        run(&["--csrf", "header:X-Token", "--csrf-header", "bad header"]).stderr(
+            predicate::str::contains("Invalid CSRF header name 'bad header'"),
+        );
+        run(&["--csrf", "cookie:XSRF-TOKEN", "--arrival-rate", "10"]).stderr(
+            predicate::str::contains("--csrf is not supported with an arrival rate"),
+        );
+    }
+}
+
+mod stdin_input_config {
+    use super::*;
+
+    #[test]
+    fn first_streamed_url_is_the_target() {
+        kaioken()
+            .args(["run", "--urls-from-file", "-", "--dry-run", "-y"])
+            .write_stdin(
+                "# generated\n\nhttps://example.com/users/1\nhttps://example.com/users/2\n",
+            )
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Target:      https://example.com/users/1",
+            ))
+            .stderr(predicate::str::contains(
+                "Input:       URLs streamed from stdin",
+            ));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\nmethod = \"POST\"\nbody_lines_file = \"-\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .write_stdin("{\"id\":1}\n")
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Input:       Body lines streamed from stdin",
+            ));
+    }
+
+    #[test]
+    fn rejects_unusable_stdin_streams() {
+        let run = |args: &[&str], stdin: &str| {
+            kaioken()
+                .args(["run", "https://example.com", "--dry-run", "-y"])
+                .args(args)
+                .write_stdin(stdin.to_string())
+                .assert()
+                .failure()
+        };
+        run(&["--urls-from-file", "-"], "# nothing\n\n")
+            .stderr(predicate::str::contains("stdin ('-') is empty"));
+        run(&["--urls-from-file", "-", "--body-lines", "-"], "x\n").stderr(
+            predicate::str::contains("--urls-from-file and --body-lines can't both read stdin"),
+        );
+        run(&["--body-lines", "-", "--arrival-rate", "10"], "x\n").stderr(
+            predicate::str::contains("Reading stdin ('-') is not supported with an arrival rate"),
+        );
+    }
+}
+
+mod seed_config {
+    use super::*;
+
+    #[test]
+    fn seed_from_cli_or_load_section() {
+        kaioken()
+            .args([
+                "run",
+                "https://example.com",
+                "--seed",
+                "42",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Seed:        42"));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[load]\nseed = 7\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Seed:        7"));
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--seed", "9"])
+            .args(["--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Seed:        9"));
+    }
+
+    #[test]
+    fn rejects_non_numeric_seeds() {
+        kaioken()
+            .args([
+                "run",
+                "https://example.com",
+                "--seed",
+                "abc",
+                "--dry-run",
+                "-y",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "invalid value 'abc' for '--seed <N>'",
+            ));
+    }
+}
+
+mod cli_threshold_config {
+    use super::*;
+
+    #[test]
+    fn cli_thresholds_add_to_and_override_config() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[thresholds]\np99_latency_ms = \"< 800\"\nrps = \"> 10\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .args(["--threshold", "p99_latency_ms<500"])
+            .args(["--threshold", "error_rate <= 0.01"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Thresholds:  3 defined"))
+            .stderr(predicate::str::contains("p99_latency_ms < 500"))
+            .stderr(predicate::str::contains("error_rate <= 0.01"))
+            .stderr(predicate::str::contains("rps > 10"))
+            .stderr(predicate::str::contains("800").not());
+    }
+
+    #[test]
+    fn rejects_malformed_cli_thresholds() {
+        let run = |threshold: &str| {
+            kaioken()
+                .args(["run", "https://example.com", "--dry-run", "-y"])
+                .args(["--threshold", threshold])
+                .assert()
+                .failure()
+        };
+        run("p99_latency_ms").stderr(predicate::str::contains(
+            "Invalid --threshold 'p99_latency_ms'. Expected METRIC<VALUE",
+        ));
+        run("p42_latency_ms<500").stderr(predicate::str::contains(
+            "The p42_latency_ms threshold needs 42 in percentiles",
+        ));
+        run("latency<500").stderr(predicate::str::contains(
+            "Unknown threshold metric 'latency'",
+        ));
+        run("rps>fast").stderr(predicate::str::contains(
+            "Invalid threshold value for 'rps': 'fast'",
+        ));
+    }
+}
+
+mod prometheus_labels_config {
+    use super::*;
+
+    #[test]
+    fn merges_labels_and_instance_from_config_and_cli() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("labels.toml");
+        std::fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[load]\nprometheus_port = 9464\nprometheus_instance = \"gen-1\"\n\n[load.prometheus_labels]\nenv = \"staging\"\nrun_id = \"nightly-42\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .args(["--prometheus-label", "env=prod"])
+            .args(["--prometheus-label", "git_sha=abc123"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Prometheus:  serve on :9464, instance gen-1",
+            ))
+            .stderr(predicate::str::contains("env=\"prod\""))
+            .stderr(predicate::str::contains("git_sha=\"abc123\""))
+            .stderr(predicate::str::contains("run_id=\"nightly-42\""));
+    }
+
+    #[test]
+    fn rejects_bad_labels_and_instances() {
+        let run = |args: &[&str]| {
+            kaioken()
+                .args(["run", "https://example.com", "--dry-run", "-y"])
+                .args(args)
+                .assert()
+                .failure()
+        };
+        run(&["--prometheus-label", "env=prod"]).stderr(predicate::str::contains(
+            "Prometheus labels, instance and buckets need --prometheus-pushgateway or --prometheus-port",
+        ));
+        run(&["--prometheus-port", "9464", "--prometheus-label", "env"]).stderr(
+            predicate::str::contains("Invalid --prometheus-label 'env'. Expected 'NAME=VALUE'"),
+        );
+        run(&[
+            "--prometheus-port",
+            "9464",
+            "--prometheus-label",
+            "git-sha=abc",
+        ])
+        .stderr(predicate::str::contains(
+            "Invalid Prometheus label name 'git-sha'",
+        ));
+        run(&[
+            "--prometheus-port",
+            "9464",
+            "--prometheus-label",
+            "instance=a",
+        ])
+        .stderr(predicate::str::contains("with --prometheus-instance"));
+        run(&["--prometheus-port", "9464", "--prometheus-instance", "a/b"]).stderr(
+            predicate::str::contains("Invalid Prometheus instance 'a/b'"),
+        );
+    }
+}
+
+mod prometheus_buckets_config {
+    use super::*;
+
+    #[test]
+    fn reads_buckets_from_cli_and_config() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--prometheus-port", "9464"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("14 latency buckets"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("buckets.toml");
+        std::fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[load]\nprometheus_port = 9464\nprometheus_buckets = [50, 10, 250]\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("3 latency buckets"));
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .args(["--prometheus-buckets", "5,10"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("2 latency buckets"));
+    }
+
+    #[test]
+    fn rejects_non_positive_buckets() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--prometheus-port", "9464", "--prometheus-buckets", "0,10"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Prometheus bucket 0 must be a positive number of ms",
+            ));
+    }
+}
+
+mod burst_ramp_config {
+    use super::*;
+
+    #[test]
+    fn accepts_ramp_and_jitter_from_flags_and_toml() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--burst-rate", "10", "--burst-delay", "1s"])
+            .args(["--burst-ramp-to", "100", "--burst-jitter", "200ms"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Configuration validated"));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("burst.toml");
+        std::fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[load]\nburst_rate = 10\nburst_delay = \"1s\"\nburst_ramp_to = 50\nburst_jitter = \"250ms\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn rejects_jitter_longer_than_delay() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--burst-rate", "10", "--burst-delay", "100ms"])
+            .args(["--burst-jitter", "1s"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "can't be longer than the delay between bursts",
+            ));
+    }
+
+    #[test]
+    fn ramp_requires_burst_rate() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--burst-ramp-to", "100"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--burst-rate"));
+    }
+}
+
+mod rate_burst_config {
+    use super::*;
+
+    #[test]
+    fn shows_burst_with_rate_limit() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--rate", "100", "--rate-burst", "500"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("100 req/s, bursts of 500"));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("rate.toml");
+        std::fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[load]\nrate = 50\nrate_burst = 200\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("50 req/s, bursts of 200"));
+    }
+
+    #[test]
+    fn requires_a_rate_limit() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--rate-burst", "500"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--rate-burst needs a rate limit (--rate)",
+            ));
+    }
+
+    #[test]
+    fn rejects_zero_burst() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--rate", "100", "--rate-burst", "0"])
+            .assert()
+            .failure();
+    }
+}
+
+mod request_id_config {
+    use super::*;
+
+    #[test]
+    fn shows_request_id_header() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--request-id-header", "X-Request-ID"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Request IDs: sent in X-Request-ID, checked in responses",
+            ));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("ids.toml");
+        std::fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\nrequest_id_header = \"X-Correlation-ID\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("sent in X-Correlation-ID"));
+    }
+
+    #[test]
+    fn rejects_invalid_header_names() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--request-id-header", "X Request ID"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Invalid --request-id-header 'X Request ID'",
+            ));
+    }
+
+    #[test]
+    fn only_applies_to_http_targets() {
+        kaioken()
+            .args(["run", "ws://example.com", "--dry-run", "-y"])
+            .args(["--request-id-header", "X-Request-ID"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--request-id-header only applies to http:// and https:// targets",
+            ));
+    }
+}
+
+mod duplicate_config {
+    use super::*;
+
+    #[test]
+    fn shows_duplicate_rate_and_header() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--duplicate-rate", "0.1"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Duplicates:  10% of requests sent twice, same Idempotency-Key",
+            ));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("dupes.toml");
+        std::fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[load]\nduplicate_rate = 0.5\nidempotency_header = \"X-Idempotency-Key\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "50% of requests sent twice, same X-Idempotency-Key",
+            ));
+    }
+
+    #[test]
+    fn rejects_rates_outside_zero_to_one() {
+        for rate in ["0", "1.5"] {
+            kaioken()
+                .args(["run", "https://example.com", "--dry-run", "-y"])
+                .args(["--duplicate-rate", rate])
+                .assert()
+                .failure()
+                .stderr(predicate::str::contains(
+                    "--duplicate-rate must be above 0 and at most 1",
+                ));
+        }
+    }
+
+    #[test]
+    fn header_needs_a_rate() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--idempotency-header", "X-Key"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--duplicate-rate"));
+    }
+
+    #[test]
+    fn not_supported_with_arrival_rate() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--duplicate-rate", "0.1", "--arrival-rate", "50"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--duplicate-rate is not supported with an arrival rate",
+            ));
+    }
+}
+
+mod conditional_requests_config {
+    use super::*;
+
+    #[test]
+    fn conditional_requests_from_cli_and_toml() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--conditional-requests"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Caching:     revalidate with If-None-Match/If-Modified-Since",
+            ));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("cache.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[load]\nconditional_requests = true\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Caching:"));
+    }
+
+    #[test]
+    fn not_supported_with_arrival_rate_or_bursts() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--conditional-requests", "--arrival-rate", "50"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--conditional-requests is not supported with an arrival rate",
+            ));
+        kaioken()
+            .args(["run", "ws://example.com", "--dry-run", "-y"])
+            .args(["--conditional-requests"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--conditional-requests only applies to http:// and https:// targets",
+            ));
+    }
+}
+
+mod probe_config {
+    use super::*;
+
+    #[test]
+    fn probe_from_cli_and_toml() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--probe", "tls", "--arrival-rate", "50"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Probe:       tls only, no HTTP requests",
+            ));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("probe.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\nprobe = \"dns\"\n\n[load]\narrival_rate = 10\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Probe:       dns only"));
+    }
+
+    #[test]
+    fn rejects_unknown_layers() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--probe", "icmp", "--arrival-rate", "50"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Invalid --probe 'icmp' (expected dns, connect or tls)",
+            ));
+    }
+
+    #[test]
+    fn needs_an_arrival_rate() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--probe", "connect", "-c", "10"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--probe runs at an arrival rate"));
+    }
+
+    #[test]
+    fn tls_needs_https() {
+        kaioken()
+            .args(["run", "http://example.com", "--dry-run", "-y"])
+            .args(["--probe", "tls", "--arrival-rate", "50"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--probe tls needs an https:// target",
+            ));
+    }
+}
+
+mod client_cert_pool_config {
+    use super::*;
+
+    fn pool_dir(names: &[&str]) -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        for name in names {
+            fs::write(dir.path().join(format!("{}.crt", name)), "cert").unwrap();
+            fs::write(dir.path().join(format!("{}.key", name)), "key").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn reads_pools_from_directories_and_lists() {
+        let dir = pool_dir(&["a", "b", "c"]);
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--client-cert-pool", dir.path().to_str().unwrap()])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Cert pool:   3 client certificates, dealt out per VU",
+            ));
+
+        let list = dir.path().join("certs.txt");
+        fs::write(&list, "# cert key\na.crt a.key\nb.crt   b.key\n").unwrap();
+        let config = dir.path().join("mtls.toml");
+        fs::write(
+            &config,
+            format!(
+                "[target]\nurl = \"https://example.com\"\nclient_cert_pool = \"{}\"\n",
+                list.display()
+            ),
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Cert pool:   2 client certificates",
+            ));
+    }
+
+    #[test]
+    fn rejects_certificates_without_keys() {
+        let dir = pool_dir(&["a"]);
+        fs::write(dir.path().join("b.pem"), "cert").unwrap();
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--client-cert-pool", dir.path().to_str().unwrap()])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("no key for"));
+    }
+
+    #[test]
+    fn unique_needs_a_certificate_per_vu() {
+        let dir = pool_dir(&["a", "b"]);
+        let run = |vus: &str| {
+            kaioken()
+                .args(["run", "https://example.com", "--dry-run", "-y", "-c", vus])
+                .args(["--client-cert-pool", dir.path().to_str().unwrap()])
+                .args(["--client-cert-assign", "unique"])
+                .assert()
+        };
+        run("2").success();
+        run("3").failure().stderr(predicate::str::contains(
+            "--client-cert-assign unique needs a certificate per VU: 2 for 3 VUs",
+        ));
+    }
+
+    #[test]
+    fn needs_https_and_no_single_cert() {
+        let dir = pool_dir(&["a"]);
+        kaioken()
+            .args(["run", "http://example.com", "--dry-run", "-y"])
+            .args(["--client-cert-pool", dir.path().to_str().unwrap()])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--client-cert-pool needs an https:// target",
+            ));
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--client-cert-pool", dir.path().to_str().unwrap()])
+            .args(["--cert", "a.crt", "--key", "a.key"])
+            .assert()
+            .failure();
+    }
+}
+
+mod compress_body_config {
+    use super::*;
+
+    #[test]
+    fn compress_body_from_cli_and_toml() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["-b", "{}", "--compress-body", "gzip"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Compression: bodies sent gzip-encoded",
+            ));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("ingest.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\nbody = \"{}\"\ncompress_body = \"deflate\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("bodies sent deflate-encoded"));
+    }
+
+    #[test]
+    fn rejects_unknown_encodings_and_forms() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--compress-body", "br"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Invalid --compress-body 'br' (expected gzip or deflate)",
+            ));
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--compress-body", "gzip", "-F", "name=value"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--compress-body can't be combined with --form",
+            ));
+    }
+}
+
+mod sni_config {
+    use super::*;
+
+    #[test]
+    fn sends_a_server_name_of_its_own() {
+        kaioken()
+            .args(["run", "https://origin.internal", "--dry-run", "-y"])
+            .args(["--sni", "tenant.example.com"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "SNI:         tenant.example.com, URL host sent as Host over HTTP/1.1",
+            ));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("sni.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://origin.internal\"\nsni = \"tenant.example.com\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("SNI:         tenant.example.com"));
+    }
+
+    #[test]
+    fn rejects_names_that_are_not_dns_names() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--sni", "example.com:443"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid --sni 'example.com:443'"));
+    }
+
+    #[test]
+    fn needs_https_over_http1() {
+        kaioken()
+            .args(["run", "http://example.com", "--dry-run", "-y"])
+            .args(["--sni", "tenant.example.com"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--sni needs an https:// target"));
+
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--sni", "tenant.example.com", "--http2"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("needs HTTP/1.1"));
+    }
+
+    #[test]
+    fn only_reuses_connect_to_for_the_target_host() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--sni", "tenant.example.com"])
+            .args(["--connect-to", "other.com:443:127.0.0.1:8443"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--sni only combines with a --connect-to for the target host",
+            ));
+    }
+}
+
+mod max_connections_config {
+    use super::*;
+
+    #[test]
+    fn caps_the_connections_and_the_idle_pool() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y", "-c", "200"])
+            .args(["--max-connections", "8"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Connections: at most 8 at once, shared by all VUs",
+            ))
+            .stderr(predicate::str::contains("Pool:        8 idle per host"));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\nmax_connections = 4\n\n[load]\narrival_rate = 100\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("at most 4 at once"));
+    }
+
+    #[test]
+    fn rejected_with_http2_and_zero() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--max-connections", "4", "--http2"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--max-connections caps HTTP/1.1 connections; --http2 sends every request over one",
+            ));
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--max-connections", "0"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--max-connections must be at least 1",
+            ));
+    }
+}
+
+mod think_time_config {
+    use super::*;
+
+    #[test]
+    fn accepts_ranges_and_distributions() {
+        for (think_time, shown) in [
+            ("500ms", "Think time:  500ms"),
+            ("1s..3s", "Think time:  1s..3s"),
+            ("normal(2s, 500ms)", "Think time:  normal(2s, 500ms)"),
+            ("exp(2s)", "Think time:  exp(2s)"),
+        ] {
+            kaioken()
+                .args(["run", "https://example.com", "--dry-run", "-y"])
+                .args(["--think-time", think_time])
+                .assert()
+                .success()
+                .stderr(predicate::str::contains(shown));
+        }
+
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--think-time", "3s..1s"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Invalid think time '3s..1s': range is backwards",
+            ));
+    }
+
+    #[test]
+    fn scenarios_pause_for_their_own_think_time() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com"
+
+[load]
+think_time = "200ms"
+
+[[scenarios]]
+name = "browse"
+url = "https://example.com/products"
+think_time = "normal(8s, 2s)"
+
+[[scenarios]]
+name = "poll"
+url = "https://example.com/status"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("think=normal(8s, 2s)"))
+            .stderr(predicate::str::contains("Think time:  200ms"));
+    }
+
+    #[test]
+    fn rejected_on_websocket_scenarios() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            r#"
+[target]
+url = "https://example.com"
+
+[[scenarios]]
+name = "feed"
+url = "wss://example.com/feed"
+think_time = "1s"
+"#,
+        )
+        .unwrap();
+
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Scenario 'feed': think_time only applies to HTTP scenarios",
+            ));
+    }
+}
+
+mod failover_config {
+    use super::*;
+
+    #[test]
+    fn reports_per_timeline_bucket() {
+        kaioken()
+            .args([
+                "run",
+                "https://example.com",
+                "--dry-run",
+                "-y",
+                "--failover",
+            ])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Failover:    availability every 1s, downtime and recovery per incident",
+            ));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("failover.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[load]\nfailover = true\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .args(["--timeline-interval", "500ms"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("availability every 500ms"));
+    }
+
+    #[test]
+    fn rejected_for_websocket_targets() {
+        kaioken()
+            .args([
+                "run",
+                "ws://example.com/socket",
+                "--dry-run",
+                "-y",
+                "--failover",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--failover is not supported for WebSocket, raw socket or MQTT targets",
+            ));
+    }
+}
+
+mod pause_on_errors_config {
+    use super::*;
+
+    #[test]
+    fn pauses_after_ten_seconds_of_failures_by_default() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .arg("--pause-on-errors")
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Outages:     pause after 10s of failures, resume on a passing health check",
+            ));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("outages.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[load]\npause_on_errors = \"30s\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("pause after 30s of failures"));
+    }
+
+    #[test]
+    fn rejected_with_an_arrival_rate() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--pause-on-errors", "5s", "--arrival-rate", "10"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--pause-on-errors is not supported with an arrival rate",
+            ));
+    }
+}
+
+mod expect_content_type_config {
+    use super::*;
+
+    #[test]
+    fn lists_the_expected_types() {
+        kaioken()
+            .args(["run", "https://example.com", "--dry-run", "-y"])
+            .args(["--expect-content-type", "Application/JSON"])
+            .args(["--expect-content-type", "text/*"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Content:     application/json, text/*; others count as unexpected",
+            ));
+
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\nexpect_content_types = [\"application/json\"]\n\n[thresholds]\nunexpected_content_type = \"< 1\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Content:     application/json"));
+    }
+
+    #[test]
+    fn rejects_malformed_types() {
+        for content_type in ["json", "text/html; charset=utf-8", "*/json"] {
+            kaioken()
+                .args(["run", "https://example.com", "--dry-run", "-y"])
+                .args(["--expect-content-type", content_type])
+                .assert()
+                .failure()
+                .stderr(predicate::str::contains(format!(
+                    "Invalid content type '{}'",
+                    content_type
+                )));
+        }
+    }
+}
+
+mod trend_threshold_config {
+    use super::*;
+
+    #[test]
+    fn lists_trend_thresholds() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(
+            &config,
+            "[target]\nurl = \"https://example.com\"\n\n[thresholds]\np99_latency_ms = \"< 500\"\np99_latency_ms_trend = \"< +10% over 7d\"\nrps_trend = \">= -5% over 12h\"\n",
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Thresholds:  1 defined"))
+            .stderr(predicate::str::contains(
+                "Trends:      2 defined, against runs stored with --db-url",
+            ))
+            .stderr(predicate::str::contains(
+                "  - p99_latency_ms_trend < +10% over 7days",
+            ))
+            .stderr(predicate::str::contains("  - rps_trend >= -5% over 12h"));
+
+        // --threshold replaces the config's trend for the same metric
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--dry-run", "-y"])
+            .args(["--threshold", "p99_latency_ms_trend<+25% over 1d"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Trends:      2 defined"))
+            .stderr(predicate::str::contains(
+                "  - p99_latency_ms_trend < +25% over 1day",
+            ));
+    }
+
+    #[test]
+    fn rejects_malformed_trends() {
+        for expr in [
+            "< +10% over 0s",
+            "< +10 over 7d",
+            "< +10%",
+            "< +10% over soon",
+        ] {
+            kaioken()
+                .args(["run", "https://example.com", "--dry-run", "-y"])
+                .args(["--threshold", &format!("error_rate_trend{}", expr)])
+                .assert()
+                .failure()
+                .stderr(predicate::str::contains(
+                    "Invalid trend threshold for 'error_rate_trend'",
+                ));
+        }
     }
 }
@@ -119,6 +119,11 @@ async fn load_test_csv_output() {
     let content = fs::read_to_string(&output).unwrap();
     assert!(content.contains("metric,value"));
     assert!(content.contains("total_requests"));
+    let started_at = content
+        .lines()
+        .find_map(|line| line.strip_prefix("started_at,"))
+        .unwrap();
+    assert!(chrono::DateTime::parse_from_rfc3339(started_at).is_ok());
 }
 
 #[tokio::test]
@@ -210,6 +215,39 @@ async fn load_test_max_requests() {
     assert!(total >= 10, "Expected at least 10 requests, got {}", total);
 }
 
+#[tokio::test]
+async fn load_test_on_worker_runtimes() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let url = format!("{}/health", server.uri());
+
+    kaioken()
+        .args([
+            "run",
+            &url,
+            "-c",
+            "4",
+            "--threads",
+            "2",
+            "-n",
+            "20",
+            "--no-tui",
+            "-y",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&output).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let total = json["summary"]["total_requests"].as_u64().unwrap();
+
+    assert!(total >= 20, "Expected at least 20 requests, got {}", total);
+    assert_eq!(json["summary"]["failed"].as_u64(), Some(0));
+}
+
 #[tokio::test]
 async fn load_test_rate_limiting() {
     let server = setup_mock_server().await;
@@ -311,3 +349,3100 @@ async fn load_test_arrival_rate_mode() {
             .is_some()
     );
 }
+
+#[tokio::test]
+async fn mixed_http_and_websocket_scenarios_report_both() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    let output = dir.path().join("results.json");
+
+    fs::write(
+        &config,
+        format!(
+            r#"
+[target]
+url = "{uri}/health"
+
+[load]
+concurrency = 4
+duration = "1s"
+
+[[scenarios]]
+name = "rest"
+url = "{uri}/health"
+weight = 1
+
+[[scenarios]]
+name = "live"
+url = "ws://127.0.0.1:1/ws"
+weight = 1
+"#,
+            uri = server.uri()
+        ),
+    )
+    .unwrap();
+
+    kaioken()
+        .args([
+            "run",
+            "-f",
+            config.to_str().unwrap(),
+            "--no-tui",
+            "-y",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&output).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+    // HTTP VUs hit the mock server, WS VUs fail to connect to a closed port
+    assert!(json["summary"]["total_requests"].as_u64().unwrap() > 0);
+    assert!(json["websocket"]["connection_errors"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn websocket_server_close_codes_reported() {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+
+    // Echo server that answers the first message, then closes with 1013
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+                    return;
+                };
+                if let Some(Ok(msg)) = ws.next().await {
+                    let _ = ws.send(msg).await;
+                }
+                if ws.next().await.is_some() {
+                    let _ = ws
+                        .close(Some(CloseFrame {
+                            code: CloseCode::Again,
+                            reason: "overloaded".into(),
+                        }))
+                        .await;
+                }
+                while let Some(Ok(_)) = ws.next().await {}
+            });
+        }
+    });
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+
+    kaioken()
+        .args([
+            "run",
+            &format!("ws://{}/ws", addr),
+            "-c",
+            "2",
+            "-d",
+            "1s",
+            "--ws-message-interval",
+            "10ms",
+            "--no-tui",
+            "-y",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&output).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+    let close = &json["websocket"]["close_codes"]["1013"];
+    assert!(close["count"].as_u64().unwrap() > 0);
+    assert_eq!(close["name"], "Try Again Later");
+    assert_eq!(close["reason"], "overloaded");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn websocket_warmup_is_not_measured() {
+    use futures_util::{SinkExt, StreamExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+                    return;
+                };
+                while let Some(Ok(msg)) = ws.next().await {
+                    let _ = ws.send(msg).await;
+                }
+            });
+        }
+    });
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    kaioken()
+        .args(["run", &format!("ws://{}/ws", addr), "-c", "2"])
+        .args([
+            "-d",
+            "1s",
+            "--warmup",
+            "1s",
+            "--ws-message-interval",
+            "20ms",
+        ])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let ws = &json["websocket"];
+    // Two connections at one message per 20ms send ~100 in each second;
+    // only the measured second is counted
+    let sent = ws["messages_sent"].as_u64().unwrap();
+    assert!(sent > 0 && sent <= 110, "{}", sent);
+    // Connections opened during warmup are still reported
+    assert_eq!(ws["connections_established"].as_u64(), Some(2));
+    assert!(ws["connect_time_us"]["p99"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn raw_tcp_line_protocol_round_trips() {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    // Line server answering every command with "+OK <command>"
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let (read, mut write) = stream.into_split();
+                let mut lines = BufReader::new(read).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if write
+                        .write_all(format!("+OK {}\r\n", line).as_bytes())
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+
+    kaioken()
+        .args([
+            "run",
+            &format!("tcp://{}", addr),
+            "-b",
+            "PING ${MESSAGE_ID}\n",
+            "--raw-expect",
+            r"^\+OK PING \d+\r\n$",
+            "-c",
+            "2",
+            "-d",
+            "1s",
+            "--ws-message-interval",
+            "10ms",
+            "--no-tui",
+            "-y",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&output).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+    let ws = &json["websocket"];
+    assert!(ws["messages_sent"].as_u64().unwrap() > 10);
+    assert_eq!(ws["messages_sent"], ws["messages_received"]);
+    assert_eq!(ws["connections_established"], 2);
+    assert_eq!(ws["error_rate"], 0.0);
+}
+
+#[tokio::test]
+async fn jsonrpc_error_members_count_as_failures() {
+    use wiremock::matchers::body_partial_json;
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rpc"))
+        .and(body_partial_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_call",
+            "params": [{"to": "0x01"}],
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"execution reverted"},"id":1}"#,
+        ))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+
+    kaioken()
+        .args([
+            "run",
+            &format!("{}/rpc", server.uri()),
+            "--jsonrpc-method",
+            "eth_call",
+            "--jsonrpc-params",
+            r#"[{"to": "0x01"}]"#,
+            "-c",
+            "2",
+            "-n",
+            "10",
+            "--no-tui",
+            "-y",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .code(1); // every request failed
+
+    let content = fs::read_to_string(&output).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+    let total = json["summary"]["total_requests"].as_u64().unwrap();
+    assert!(total >= 10, "Expected at least 10 requests, got {}", total);
+    assert_eq!(json["summary"]["failed"].as_u64().unwrap(), total);
+    assert_eq!(json["status_codes"]["200"].as_u64().unwrap(), total);
+    assert_eq!(json["errors"]["jsonrpc"].as_u64().unwrap(), total);
+    assert_eq!(json["jsonrpc_errors"]["-32000"].as_u64().unwrap(), total);
+}
+
+#[tokio::test]
+async fn test_serve_runs_config_over_api() {
+    let server = setup_mock_server().await;
+
+    // Grab a free port for the control API
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("kaioken"))
+        .args(["serve", "--bind", &format!("127.0.0.1:{}", port)])
+        .args(["--token", "secret"])
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let api = format!("http://127.0.0.1:{}", port);
+    let client = reqwest::Client::new();
+
+    let mut up = false;
+    for _ in 0..50 {
+        if client.get(format!("{}/health", api)).send().await.is_ok() {
+            up = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(up, "serve did not start listening");
+
+    let unauthorized = client
+        .get(format!("{}/api/status", api))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(unauthorized.status().as_u16(), 401);
+
+    let config = format!(
+        "[target]\nurl = \"{}/health\"\n\n[load]\nconcurrency = 2\nmax_requests = 20\n",
+        server.uri()
+    );
+    let start = |config: String| {
+        client
+            .post(format!("{}/api/runs", api))
+            .bearer_auth("secret")
+            .header("content-type", "application/toml")
+            .body(config)
+    };
+
+    // Guards against web pages driving the server from the operator's browser
+    let untyped = client
+        .post(format!("{}/api/runs", api))
+        .bearer_auth("secret")
+        .body(config.clone())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(untyped.status().as_u16(), 415);
+    let cross_origin = start(config.clone())
+        .header("origin", "http://evil.example")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(cross_origin.status().as_u16(), 403);
+    let rebound = client
+        .get(format!("{}/api/status", api))
+        .header("host", format!("evil.example:{}", port))
+        .bearer_auth("secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(rebound.status().as_u16(), 403);
+    let slow_log = start(format!(
+        "{}slow_log = {{ threshold = \"1ms\", path = \"/tmp/kaioken-serve-slow.ndjson\" }}\n",
+        config
+    ))
+    .send()
+    .await
+    .unwrap();
+    assert_eq!(slow_log.status().as_u16(), 400);
+    let error = slow_log.text().await.unwrap();
+    assert!(error.contains("slow_log writes files"), "{}", error);
+
+    let started = start(config)
+        .header("origin", api.clone())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(started.status().as_u16(), 202);
+
+    let mut status = serde_json::Value::Null;
+    for _ in 0..100 {
+        let body = client
+            .get(format!("{}/api/status?token=secret", api))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        status = serde_json::from_str(&body).unwrap();
+        if status["state"] == "completed" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(status["state"], "completed", "status: {}", status);
+    assert_eq!(status["exit_code"], 0);
+
+    let body = client
+        .get(format!("{}/api/result", api))
+        .bearer_auth("secret")
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    let result: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let total = result["summary"]["total_requests"].as_u64().unwrap();
+    assert!(total >= 20, "Expected at least 20 requests, got {}", total);
+
+    child.kill().unwrap();
+    let _ = child.wait();
+}
+
+#[tokio::test]
+async fn test_record_writes_weighted_scenarios() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("recorded.toml");
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("kaioken"))
+        .args(["record", "--bind", &format!("127.0.0.1:{}", port)])
+        .args(["--target", &server.uri(), "--limit", "3"])
+        .args(["-o", output.to_str().unwrap()])
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let proxy = format!("http://127.0.0.1:{}", port);
+    let client = reqwest::Client::new();
+
+    let mut up = false;
+    for _ in 0..50 {
+        if let Ok(response) = client.get(format!("{}/health", proxy)).send().await {
+            assert_eq!(response.status().as_u16(), 200);
+            up = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(up, "record did not start listening");
+
+    client
+        .get(format!("{}/health", proxy))
+        .send()
+        .await
+        .unwrap();
+    let response = client.get(format!("{}/error", proxy)).send().await.unwrap();
+    assert_eq!(response.status().as_u16(), 500);
+
+    assert!(child.wait().unwrap().success());
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert!(content.contains("# Recorded by kaioken record"));
+    assert!(content.contains(&format!("url = \"{}/health\"", server.uri())));
+    assert!(content.contains(&format!("url = \"{}/error\"", server.uri())));
+    assert!(content.contains("weight = 2"));
+}
+
+#[tokio::test]
+async fn test_history_lists_shows_and_compares_runs() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("runs.db");
+    let url = format!("{}/health", server.uri());
+
+    for _ in 0..2 {
+        kaioken()
+            .args(["run", &url, "-c", "2", "-n", "20", "--no-tui", "-y"])
+            .args(["--db-url", db.to_str().unwrap()])
+            .assert()
+            .success();
+    }
+
+    let list = kaioken()
+        .args([
+            "history",
+            "list",
+            "--json",
+            "--db-url",
+            db.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    let runs: serde_json::Value = serde_json::from_slice(&list.get_output().stdout).unwrap();
+    let runs = runs.as_array().unwrap();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0]["id"], 2); // newest first
+    assert_eq!(runs[0]["url"], url.as_str());
+    assert_eq!(runs[0]["exit_code"], 0);
+    assert!(runs[0]["snapshots"].as_u64().unwrap() > 0);
+    assert!(runs[0]["total_requests"].as_u64().unwrap() >= 20);
+
+    let show = kaioken()
+        .args([
+            "history",
+            "show",
+            "1",
+            "--json",
+            "--db-url",
+            db.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    let result: serde_json::Value = serde_json::from_slice(&show.get_output().stdout).unwrap();
+    assert_eq!(result["metadata"]["target"]["url"], url.as_str());
+    assert!(result["summary"]["total_requests"].as_u64().unwrap() >= 20);
+
+    kaioken()
+        .args(["history", "compare", "1", "2", "--json"])
+        .args(["--threshold-p99", "100000", "--threshold-p999", "100000"])
+        .args([
+            "--threshold-rps",
+            "100000",
+            "--threshold-error-rate",
+            "100000",
+        ])
+        .args(["--db-url", db.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("run #1"));
+
+    kaioken()
+        .args(["history", "show", "9", "--db-url", db.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("No run with ID 9"));
+}
+
+#[tokio::test]
+async fn test_report_rerenders_saved_result() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let saved = dir.path().join("results.json");
+
+    kaioken()
+        .args([
+            "run",
+            &format!("{}/health", server.uri()),
+            "-c",
+            "2",
+            "-n",
+            "20",
+            "--no-tui",
+            "-y",
+            "-o",
+            saved.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    kaioken()
+        .args(["report", saved.to_str().unwrap(), "--serious"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Load Test Results"))
+        .stdout(predicates::str::contains("200:"));
+
+    kaioken()
+        .args(["report", saved.to_str().unwrap(), "-f", "md"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(format!(
+            "`{}/health`",
+            server.uri()
+        )));
+
+    let html = dir.path().join("report.html");
+    kaioken()
+        .args(["report", saved.to_str().unwrap(), "-f", "html"])
+        .args(["-o", html.to_str().unwrap()])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(&html).unwrap().contains("<html"));
+
+    // JSON is re-emitted as saved, metadata included
+    let report = kaioken()
+        .args(["report", saved.to_str().unwrap(), "-f", "json"])
+        .assert()
+        .success();
+    let original: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&saved).unwrap()).unwrap();
+    let rerendered: serde_json::Value =
+        serde_json::from_slice(&report.get_output().stdout).unwrap();
+    assert_eq!(original, rerendered);
+
+    kaioken()
+        .args(["report", saved.to_str().unwrap(), "-f", "xml"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Unknown format"));
+}
+
+#[tokio::test]
+async fn test_suite_runs_tests_in_sequence() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let config = dir.path().join("suite.toml");
+    fs::write(
+        &config,
+        format!(
+            r#"
+[target]
+url = "{}/health"
+
+[load]
+concurrency = 2
+max_requests = 20
+
+[[tests]]
+name = "smoke"
+load = {{ concurrency = 1 }}
+
+[[tests]]
+name = "errors"
+target = {{ url = "{}/error" }}
+thresholds = {{ error_rate = "< 0.1" }}
+"#,
+            server.uri(),
+            server.uri()
+        ),
+    )
+    .unwrap();
+
+    let output = dir.path().join("results.json");
+    let run = kaioken()
+        .args(["run", "-f", config.to_str().unwrap(), "--test", "all"])
+        .args(["--json", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .code(4);
+
+    let combined: serde_json::Value = serde_json::from_slice(&run.get_output().stdout).unwrap();
+    assert_eq!(combined["exit_code"], 4);
+    let tests = combined["tests"].as_array().unwrap();
+    assert_eq!(tests.len(), 2);
+    assert_eq!(tests[0]["name"], "smoke");
+    assert_eq!(tests[0]["exit_code"], 0);
+    assert_eq!(tests[0]["result"]["metadata"]["load"]["concurrency"], 1);
+    assert_eq!(tests[1]["name"], "errors");
+    assert_eq!(tests[1]["exit_code"], 4);
+
+    // -o gets one file per test
+    assert!(dir.path().join("results-smoke.json").exists());
+    assert!(dir.path().join("results-errors.json").exists());
+}
+
+#[tokio::test]
+async fn test_scheduled_runs_record_history_and_trend() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("history.db");
+    let url = format!("{}/health", server.uri());
+
+    let run = kaioken()
+        .args(["run", &url, "-c", "2", "-n", "20", "--json", "-y"])
+        .args([
+            "--every",
+            "100ms",
+            "--times",
+            "2",
+            "--trend-threshold",
+            "10000",
+        ])
+        .args(["--db-url", db.to_str().unwrap()])
+        .assert()
+        .success();
+
+    // One NDJSON line per run; only the second has a trend to compare against
+    let stdout = String::from_utf8_lossy(&run.get_output().stdout).into_owned();
+    let runs: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0]["run"], 1);
+    assert!(runs[0]["trend"].as_array().unwrap().is_empty());
+    assert_eq!(runs[1]["run"], 2);
+    assert_eq!(runs[1]["trend"].as_array().unwrap().len(), 3);
+    assert_eq!(runs[1]["exit_code"], 0);
+    assert!(
+        runs[1]["result"]["summary"]["total_requests"]
+            .as_u64()
+            .unwrap()
+            >= 20
+    );
+
+    // Each run is stored in history as it finishes
+    let list = kaioken()
+        .args([
+            "history",
+            "list",
+            "--json",
+            "--db-url",
+            db.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    let stored: serde_json::Value = serde_json::from_slice(&list.get_output().stdout).unwrap();
+    assert_eq!(stored.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn control_port_accepts_ctl_commands() {
+    let server = setup_mock_server().await;
+    let url = format!("{}/health", server.uri());
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+        .to_string();
+
+    let run = std::process::Command::new(assert_cmd::cargo::cargo_bin("kaioken"))
+        .args(["run", &url, "-c", "2", "-r", "50", "-d", "30s"])
+        .args(["--no-tui", "-y", "--control-port", &port])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // The port opens once the run has started
+    let status = (0..50)
+        .find_map(|_| {
+            let output = kaioken()
+                .args(["ctl", "-p", &port, "status", "--json"])
+                .output()
+                .unwrap();
+            // The rate shows once the run has set up its plan
+            let ready = output.status.success()
+                && serde_json::from_slice::<serde_json::Value>(&output.stdout)
+                    .is_ok_and(|status| !status["duration_secs"].is_null());
+            if ready {
+                Some(output)
+            } else {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                None
+            }
+        })
+        .expect("control port never answered");
+    let status: serde_json::Value = serde_json::from_slice(&status.stdout).unwrap();
+    assert_eq!(status["state"], "running");
+    assert_eq!(status["rate"], 50);
+    assert_eq!(status["duration_secs"], 30.0);
+
+    kaioken()
+        .args(["ctl", "-p", &port, "extend", "1m"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Extended by 1m"));
+    kaioken()
+        .args(["ctl", "-p", &port, "set-rate", "0"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("at least 1"));
+    kaioken()
+        .args(["ctl", "-p", &port, "stop"])
+        .assert()
+        .success();
+
+    // Stopping ends the run early and still writes the result
+    let output = run.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(result["metadata"]["duration_secs"].as_u64().unwrap() < 30);
+    assert!(result["summary"]["total_requests"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn endpoint_stats_group_id_bearing_urls() {
+    use wiremock::matchers::path_regex;
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/(users|products)/[^/]+$"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let urls = dir.path().join("urls.txt");
+    let output = dir.path().join("results.json");
+    let paths = [
+        "/users/1",
+        "/users/22",
+        "/products/red-shoes",
+        "/products/blue-hat",
+    ];
+    let lines: Vec<String> = paths
+        .iter()
+        .map(|p| format!("{}{}", server.uri(), p))
+        .collect();
+    fs::write(&urls, lines.join("\n")).unwrap();
+
+    kaioken()
+        .args([
+            "run",
+            "--urls-from-file",
+            urls.to_str().unwrap(),
+            "--endpoint-group",
+            "/products/[^/]+=/products/{slug}",
+            "-c",
+            "2",
+            "-n",
+            "40",
+            "--no-tui",
+            "-y",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let endpoints = json["endpoints"].as_array().unwrap();
+    let mut names: Vec<&str> = endpoints
+        .iter()
+        .map(|e| e["name"].as_str().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["GET /products/{slug}", "GET /users/{id}"]);
+
+    let grouped: u64 = endpoints
+        .iter()
+        .map(|e| e["requests"].as_u64().unwrap())
+        .sum();
+    assert_eq!(grouped, json["summary"]["total_requests"].as_u64().unwrap());
+}
+
+#[tokio::test]
+async fn large_bodies_report_upload_timing() {
+    use wiremock::matchers::header;
+
+    const BODY_LEN: usize = 200_000;
+    let server = MockServer::start().await;
+    // Streamed bodies must still go out with a Content-Length
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(header("content-length", BODY_LEN.to_string().as_str()))
+        .respond_with(ResponseTemplate::new(201))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let body = dir.path().join("body.bin");
+    let output = dir.path().join("results.json");
+    fs::write(&body, "x".repeat(BODY_LEN)).unwrap();
+    let url = format!("{}/upload", server.uri());
+
+    kaioken()
+        .args(["run", &url, "-m", "POST", "-c", "2", "-n", "10"])
+        .args(["--body-file", body.to_str().unwrap()])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let total = json["summary"]["total_requests"].as_u64().unwrap();
+    assert_eq!(json["summary"]["failed"], 0);
+    let upload = &json["upload"];
+    assert_eq!(
+        upload["bytes_sent"].as_u64().unwrap(),
+        total * BODY_LEN as u64
+    );
+    assert_eq!(upload["timed_requests"].as_u64().unwrap(), total);
+    assert!(upload["time_us"]["p50"].as_u64().unwrap() > 0);
+    assert!(upload["throughput_bytes_per_sec"].as_f64().unwrap() > 0.0);
+
+    // The same bytes show up as egress throughput
+    let summary = &json["summary"];
+    assert_eq!(summary["bytes_sent"], upload["bytes_sent"]);
+    assert!(summary["tx_mbps"].as_f64().unwrap() > 0.0);
+    let timeline = json["timeline"].as_array().unwrap();
+    assert!(
+        timeline
+            .iter()
+            .any(|t| t["tx_mbps"].as_f64().unwrap() > 0.0)
+    );
+}
+
+#[tokio::test]
+async fn timeline_interval_sets_bucket_width() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let url = format!("{}/health", server.uri());
+
+    kaioken()
+        .args(["run", &url, "-c", "2", "-d", "1s"])
+        .args(["--timeline-interval", "250ms"])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    assert_eq!(json["metadata"]["load"]["timeline_interval_ms"], 250);
+
+    let timeline = json["timeline"].as_array().unwrap();
+    assert!(timeline.len() >= 3);
+    for bucket in timeline {
+        assert_eq!(bucket["elapsed_ms"].as_u64().unwrap_or(0) % 250, 0);
+    }
+    // Buckets carry wall-clock starts offset from the run start
+    let started_at =
+        chrono::DateTime::parse_from_rfc3339(json["metadata"]["started_at"].as_str().unwrap())
+            .unwrap();
+    for bucket in timeline {
+        let timestamp =
+            chrono::DateTime::parse_from_rfc3339(bucket["timestamp"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            (timestamp - started_at).num_milliseconds(),
+            bucket["elapsed_ms"].as_i64().unwrap()
+        );
+    }
+    let total: u64 = timeline
+        .iter()
+        .map(|b| b["requests"].as_u64().unwrap())
+        .sum();
+    assert_eq!(total, json["summary"]["total_requests"].as_u64().unwrap());
+    assert!(timeline.iter().any(|b| {
+        b["latency_us"]["p99"].as_u64().unwrap() > 0 && b["bytes_received"].as_u64().unwrap() > 0
+    }));
+}
+
+#[tokio::test]
+async fn failed_requests_are_sampled() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/overloaded"))
+        .respond_with(ResponseTemplate::new(503).set_body_string(r#"{"error":"queue full"}"#))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let errors = dir.path().join("errors.jsonl");
+    let url = format!("{}/overloaded", server.uri());
+
+    kaioken()
+        .args(["run", &url, "-c", "2", "-n", "20", "--error-samples", "2"])
+        .args(["--errors-out", errors.to_str().unwrap()])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .code(1); // Every request failed
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let samples = json["error_samples"].as_array().unwrap();
+    assert_eq!(samples.len(), 2);
+    for sample in samples {
+        assert_eq!(sample["kind"], "status 503");
+        assert_eq!(sample["status"], 503);
+        assert_eq!(sample["request"], format!("GET {}", url));
+        assert_eq!(sample["body"], r#"{"error":"queue full"}"#);
+    }
+
+    let lines: Vec<serde_json::Value> = fs::read_to_string(&errors)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["kind"], "status 503");
+}
+
+#[tokio::test]
+async fn trace_propagation_reports_slowest_trace_ids() {
+    use wiremock::matchers::header_regex;
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header_regex(
+            "traceparent",
+            "^00-[0-9a-f]{32}-[0-9a-f]{16}-01$",
+        ))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(header_regex("x-b3-traceid", "^[0-9a-f]{32}$"))
+        .and(header_regex("x-b3-spanid", "^[0-9a-f]{16}$"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let uri = server.uri();
+    let runs: [&[&str]; 2] = [
+        &["--trace-propagation", "w3c", "-c", "2", "-n", "30"],
+        &[
+            "--trace-propagation",
+            "b3",
+            "--arrival-rate",
+            "50",
+            "-d",
+            "1s",
+        ],
+    ];
+
+    for args in runs {
+        kaioken()
+            .args(["run", &uri, "--trace-slowest", "5"])
+            .args(args)
+            .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+            .assert()
+            .success();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        // Requests without the headers would get wiremock's 404
+        assert_eq!(json["summary"]["failed"], 0);
+        let traces = json["slowest_traces"].as_array().unwrap();
+        assert_eq!(traces.len(), 5);
+        let latencies: Vec<u64> = traces
+            .iter()
+            .map(|t| t["latency_us"].as_u64().unwrap())
+            .collect();
+        assert!(latencies.windows(2).all(|w| w[0] >= w[1]));
+        for trace in traces {
+            assert_eq!(trace["trace_id"].as_str().unwrap().len(), 32);
+        }
+    }
+}
+
+#[tokio::test]
+async fn apdex_score_reported_and_thresholded() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let config = dir.path().join("kaioken.toml");
+    fs::write(
+        &config,
+        format!(
+            r#"
+[target]
+url = "{}"
+
+[load]
+concurrency = 2
+max_requests = 20
+apdex_t = "1s"
+
+[thresholds]
+apdex = "> 0.9"
+"#,
+            server.uri()
+        ),
+    )
+    .unwrap();
+
+    kaioken()
+        .args(["run", "-f", config.to_str().unwrap()])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let apdex = &json["apdex"];
+    assert_eq!(apdex["t_ms"], 1000.0);
+    assert_eq!(apdex["score"], 1.0);
+    assert_eq!(apdex["rating"], "excellent");
+    assert_eq!(apdex["satisfied"], json["summary"]["total_requests"]);
+    assert_eq!(apdex["frustrated"], 0);
+}
+
+#[tokio::test]
+async fn connection_reuse_is_counted() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let uri = server.uri();
+    let run = |extra: &[&str]| {
+        kaioken()
+            .args(["run", &uri, "-c", "2", "-n", "40"])
+            .args(extra)
+            .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+            .assert()
+            .success();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        (
+            json["summary"]["total_requests"].as_u64().unwrap(),
+            json["connections"].clone(),
+        )
+    };
+
+    // Keepalive: each worker opens a connection once and reuses it
+    let (total, conns) = run(&[]);
+    assert!(conns["opened"].as_u64().unwrap() <= 2);
+    assert!(conns["reused"].as_u64().unwrap() >= total - 2);
+    assert!(conns["reuse_rate"].as_f64().unwrap() > 0.9);
+
+    // Without keepalive every request needs its own connection
+    let (total, conns) = run(&["--disable-keepalive"]);
+    assert_eq!(conns["opened"].as_u64().unwrap(), total);
+    assert_eq!(conns["reused"], 0);
+}
+
+#[tokio::test]
+async fn slow_log_records_only_slow_requests() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/fast"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(ResponseTemplate::new(503).set_delay(std::time::Duration::from_millis(300)))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let slow_log = dir.path().join("slow.ndjson");
+    let config = dir.path().join("kaioken.toml");
+    fs::write(
+        &config,
+        format!(
+            r#"
+[target]
+url = "{uri}/fast"
+
+[load]
+concurrency = 2
+max_requests = 40
+
+[[scenarios]]
+name = "fast"
+url = "{uri}/fast"
+weight = 3
+
+[[scenarios]]
+name = "slow"
+url = "{uri}/slow"
+weight = 1
+"#,
+            uri = server.uri()
+        ),
+    )
+    .unwrap();
+
+    kaioken()
+        .args(["run", "-f", config.to_str().unwrap(), "--no-tui", "-y"])
+        .args(["--slow-log", "200ms", slow_log.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let entries: Vec<serde_json::Value> = fs::read_to_string(&slow_log)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(!entries.is_empty());
+    for entry in &entries {
+        assert_eq!(entry["scenario"], "slow");
+        assert_eq!(entry["request"], format!("GET {}/slow", server.uri()));
+        assert_eq!(entry["status"], 503);
+        assert!(entry["latency_us"].as_u64().unwrap() >= 200_000);
+        assert!(entry["timestamp"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn auto_warmup_records_detected_length() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+
+    // Too short a cap to see five steady windows, so the cap ends warmup
+    let started = std::time::Instant::now();
+    kaioken()
+        .args(["run", &server.uri(), "-c", "2", "-d", "1s"])
+        .args(["--auto-warmup", "--warmup", "2s"])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+    assert!(started.elapsed() >= std::time::Duration::from_secs(3));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let warmup = &json["metadata"]["load"]["auto_warmup"];
+    assert_eq!(warmup["settled"], false);
+    let detected_ms = warmup["detected_ms"].as_u64().unwrap();
+    assert!((2000..3000).contains(&detected_ms), "{detected_ms}");
+    assert!(json["summary"]["total_requests"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn custom_percentiles_reported() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let csv = dir.path().join("results.csv");
+    let uri = format!("{}/health", server.uri());
+
+    kaioken()
+        .args(["run", &uri, "-c", "2", "-n", "50", "--no-tui", "-y"])
+        .args(["--percentiles", "99.99,50", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let percentiles = json["latency_us"]["percentiles"].as_object().unwrap();
+    assert_eq!(
+        percentiles.keys().collect::<Vec<_>>(),
+        vec!["p50", "p9999"],
+        "only the configured percentiles are listed"
+    );
+    assert_eq!(percentiles["p50"], json["latency_us"]["p50"]);
+    assert!(percentiles["p9999"].as_u64() >= percentiles["p50"].as_u64());
+
+    kaioken()
+        .args(["run", &uri, "-c", "2", "-n", "50", "--no-tui", "-y"])
+        .args(["--percentiles", "50,99.99", "--format", "csv"])
+        .args(["-o", csv.to_str().unwrap()])
+        .assert()
+        .success();
+    let csv = fs::read_to_string(&csv).unwrap();
+    assert!(csv.contains("latency_p9999_ms,"));
+    assert!(!csv.contains("latency_p75_ms,"));
+}
+
+#[tokio::test]
+async fn summary_interval_prints_progress_lines() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let output = kaioken()
+        .args(["run", &server.uri(), "-c", "2", "-d", "2500ms"])
+        .args(["--summary-interval", "1s", "--no-tui", "-y"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<&str> = stderr
+        .lines()
+        .filter(|line| line.contains(" requests | "))
+        .collect();
+    assert!(lines.len() >= 2, "{stderr}");
+    assert!(lines[0].starts_with('['));
+    assert!(lines[0].contains("/s | p99 "));
+    assert!(lines[0].contains("| errors 0 (0.00%)"));
+}
+
+#[tokio::test]
+async fn progress_bar_redraws_a_single_line() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let output = kaioken()
+        .args(["run", &server.uri(), "-c", "2", "-d", "2500ms"])
+        .args([
+            "--progress",
+            "bar",
+            "--summary-interval",
+            "500ms",
+            "--no-tui",
+            "-y",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let redraws: Vec<&str> = stderr
+        .split('\r')
+        .filter(|draw| draw.starts_with('['))
+        .collect();
+    assert!(redraws.len() >= 3, "{stderr}");
+    assert!(redraws[0].contains("% 00:0"));
+    assert!(redraws[0].contains(" ETA 00:0"));
+    assert!(redraws[0].contains(" requests | "));
+    assert!(redraws[0].contains("| errors 0"));
+    assert!(!stderr.contains('\x1b'));
+    // Drawn in place, with a newline only after the last draw
+    let (last, earlier) = redraws.split_last().unwrap();
+    assert!(earlier.iter().all(|draw| !draw.contains('\n')));
+    assert!(last.contains('\n'));
+}
+
+#[tokio::test]
+async fn recent_window_reported_in_json() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let uri = format!("{}/health", server.uri());
+
+    kaioken()
+        .args(["run", &uri, "-c", "2", "-d", "2s", "--no-tui", "-y"])
+        .args(["--percentiles", "50,99", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let recent = &json["recent"];
+    // A 2s run fits in the window, so it matches the cumulative numbers
+    let window = recent["window_secs"].as_f64().unwrap();
+    assert!((1.5..3.0).contains(&window), "{window}");
+    assert_eq!(recent["requests"], json["summary"]["total_requests"]);
+    assert!(recent["requests_per_sec"].as_f64().unwrap() > 0.0);
+    let latency = recent["latency_us"].as_object().unwrap();
+    assert_eq!(latency.keys().collect::<Vec<_>>(), vec!["p50", "p99"]);
+    assert_eq!(latency["p50"], json["latency_us"]["p50"]);
+}
+
+/// Signs requests with an `x-signed` header, counts them as the `signed`
+/// metric and passes responses with status 200
+#[cfg(feature = "plugins")]
+const SIGNER_PLUGIN: &str = r#"
+(module
+  (import "kaioken" "metric" (func $metric (param i32 i32 f64)))
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 1024))
+  (data (i32.const 0) "{\"headers\":[[\"x-signed\",\"yes\"]]}")
+  (data (i32.const 64) "signed")
+  (data (i32.const 96) "\"status\":200")
+  (func (export "kaioken_alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $len)))
+    (local.get $ptr))
+  (func (export "kaioken_free") (param i32 i32)
+    (global.set $next (i32.const 1024)))
+  (func (export "on_request") (param i32 i32) (result i64)
+    (call $metric (i32.const 64) (i32.const 6) (f64.const 1))
+    (i64.const 32))
+  (func (export "on_response") (param $ptr i32) (param $len i32) (result i32)
+    (local $i i32)
+    (local $j i32)
+    (block $done
+      (loop $outer
+        (br_if $done (i32.gt_s (i32.add (local.get $i) (i32.const 12)) (local.get $len)))
+        (local.set $j (i32.const 0))
+        (block $mismatch
+          (loop $inner
+            (br_if $mismatch
+              (i32.ne
+                (i32.load8_u (i32.add (local.get $ptr) (i32.add (local.get $i) (local.get $j))))
+                (i32.load8_u (i32.add (i32.const 96) (local.get $j)))))
+            (local.set $j (i32.add (local.get $j) (i32.const 1)))
+            (br_if $inner (i32.lt_s (local.get $j) (i32.const 12)))
+            (return (i32.const 1))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $outer)))
+    (i32.const 0)))
+"#;
+
+#[cfg(feature = "plugins")]
+#[tokio::test]
+async fn plugins_rewrite_requests_and_report_checks() {
+    use wiremock::matchers::header;
+
+    // Unsigned requests fall through to wiremock's 404
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header("x-signed", "yes"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let plugin = dir.path().join("signer.wasm");
+    fs::write(&plugin, wat::parse_str(SIGNER_PLUGIN).unwrap()).unwrap();
+    let output = dir.path().join("results.json");
+
+    // Closed model, then the arrival-rate executor
+    for mode in [&["-c", "2"][..], &["--arrival-rate", "50"][..]] {
+        kaioken()
+            .args(["run", &server.uri(), "-n", "20", "--no-tui", "-y"])
+            .args(mode)
+            .args(["--plugin", plugin.to_str().unwrap()])
+            .args(["-o", output.to_str().unwrap()])
+            .assert()
+            .success();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        let total = json["summary"]["total_requests"].as_u64().unwrap();
+        assert!(total > 0);
+        assert_eq!(json["status_codes"]["200"].as_u64(), Some(total));
+
+        // In-flight requests past -n still go through the hooks
+        let check = &json["checks"]["results"]["plugin:signer"];
+        assert!(check["total"].as_u64().unwrap() >= total);
+        assert_eq!(check["pass_rate"].as_f64(), Some(1.0));
+        let signed = &json["plugin_metrics"]["signed"];
+        assert!(signed["count"].as_u64().unwrap() >= total);
+        assert_eq!(signed["mean"].as_f64(), Some(1.0));
+    }
+}
+
+#[tokio::test]
+async fn chaos_injects_delays_aborts_and_resets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(100)))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let config = dir.path().join("chaos.toml");
+    fs::write(
+        &config,
+        format!(
+            r#"
+[target]
+url = "{}"
+
+[chaos]
+latency = "1ms..5ms"
+abort_rate = 0.3
+abort_after = "20ms"
+reset_rate = 0.3
+"#,
+            server.uri()
+        ),
+    )
+    .unwrap();
+    let output = dir.path().join("results.json");
+
+    kaioken()
+        .args(["run", "-f", config.to_str().unwrap(), "-c", "4", "-d", "2s"])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let chaos = &json["chaos"];
+    assert!(chaos["delayed"].as_u64().unwrap() > 0);
+    assert!(chaos["aborted"].as_u64().unwrap() > 0);
+    assert!(chaos["reset"].as_u64().unwrap() > 0);
+    // Only the requests chaos let through are measured, and they all succeed
+    let total = json["summary"]["total_requests"].as_u64().unwrap();
+    assert!(total > 0);
+    assert_eq!(json["status_codes"]["200"].as_u64(), Some(total));
+}
+
+/// Stand-ins for ssh and scp that run and copy on this machine
+#[cfg(unix)]
+fn fake_ssh(dir: &std::path::Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    for (name, script) in [
+        (
+            "ssh",
+            "#!/bin/sh\nfor last; do :; done\nexec sh -c \"$last\"\n",
+        ),
+        (
+            "scp",
+            "#!/bin/sh\nfor arg; do src=$dest; dest=$arg; done\ncp \"$src\" \"${dest#*:}\"\n",
+        ),
+    ] {
+        let path = dir.join(name);
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn runners_run_remotely_and_merge_results() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    fake_ssh(dir.path());
+    let config = dir.path().join("runner.toml");
+    fs::write(
+        &config,
+        format!(
+            "[target]\nurl = \"{}/health\"\n\n[load]\nconcurrency = 2\nmax_requests = 30\n",
+            server.uri()
+        ),
+    )
+    .unwrap();
+    let output = dir.path().join("results.json");
+    let path = format!(
+        "{}:{}",
+        dir.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let bin = assert_cmd::cargo::cargo_bin("kaioken");
+
+    kaioken()
+        .env("PATH", path)
+        .args(["run", "-f", config.to_str().unwrap(), "-y", "-q"])
+        .args(["--runners", "ssh://a,ssh://tester@b:2222"])
+        .args(["--runner-bin", bin.to_str().unwrap()])
+        .args(["-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let total = json["summary"]["total_requests"].as_u64().unwrap();
+    // Each runner stops at max_requests; in-flight requests may add a few
+    assert!((60..70).contains(&total), "{}", total);
+    assert_eq!(json["status_codes"]["200"].as_u64(), Some(total));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn sighup_reloads_stages_mid_run() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let config = dir.path().join("staged.toml");
+    let staged =
+        |stages: &str| format!("[target]\nurl = \"{}/health\"\n\n{}", server.uri(), stages);
+    fs::write(
+        &config,
+        staged("[[stages]]\nduration = \"2s\"\ntarget = 2\n"),
+    )
+    .unwrap();
+
+    let run = std::process::Command::new(assert_cmd::cargo::cargo_bin("kaioken"))
+        .args(["run", "-f", config.to_str().unwrap(), "--json", "-y"])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Add a second stage while the first is running
+    tokio::time::sleep(std::time::Duration::from_millis(800)).await;
+    fs::write(
+        &config,
+        staged(
+            "[[stages]]\nduration = \"2s\"\ntarget = 2\n\n[[stages]]\nduration = \"2s\"\ntarget = 2\n",
+        ),
+    )
+    .unwrap();
+    let status = std::process::Command::new("kill")
+        .args(["-HUP", &run.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let output = run.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Config reloaded: 2 stages, 4s in total"),
+        "{}",
+        stderr
+    );
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(result["metadata"]["duration_secs"].as_f64().unwrap() >= 3.5);
+    assert!(result["summary"]["total_requests"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn honor_retry_after_waits_out_throttling() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/limited"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .mount(&server)
+        .await;
+    let url = format!("{}/limited", server.uri());
+
+    let output = kaioken()
+        .args(["run", &url, "-c", "2", "-d", "2s", "--honor-retry-after"])
+        .args(["--json", "-y"])
+        .output()
+        .unwrap();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let throttle = &json["throttle"];
+    let throttled = throttle["throttled"].as_u64().unwrap();
+    assert!(throttled > 0);
+    assert_eq!(throttle["retry_after"].as_u64(), Some(throttled));
+    assert!(throttle["waited_secs"].as_f64().unwrap() >= 1.0);
+    // Each VU waits a second after every response
+    let total = json["summary"]["total_requests"].as_u64().unwrap();
+    assert!(total <= 6, "{}", total);
+}
+
+#[tokio::test]
+async fn deadline_caps_retries_of_slow_failures() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/flaky"))
+        .respond_with(ResponseTemplate::new(503).set_delay(std::time::Duration::from_millis(200)))
+        .mount(&server)
+        .await;
+    let url = format!("{}/flaky", server.uri());
+
+    let output = kaioken()
+        .args(["run", &url, "-c", "2", "-d", "2s", "--json", "-y"])
+        .args(["--retries", "5", "--deadline", "500ms"])
+        .output()
+        .unwrap();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let total = json["summary"]["total_requests"].as_u64().unwrap();
+    assert!(total > 0);
+    // Every request runs out of budget retrying, whatever the per-attempt timeout
+    assert_eq!(json["errors"]["deadline"].as_u64(), Some(total));
+    assert_eq!(json["metadata"]["load"]["deadline_ms"].as_u64(), Some(500));
+    assert!(json["latency_us"]["p50"].as_u64().unwrap() >= 500_000);
+}
+
+#[tokio::test]
+async fn proxy_list_rotates_and_scenarios_can_go_direct() {
+    // Plain HTTP proxies get the request with an absolute URI; answering it is enough
+    let mut proxies = Vec::new();
+    for _ in 0..2 {
+        let proxy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&proxy)
+            .await;
+        proxies.push(proxy);
+    }
+    let direct = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let list = dir.path().join("proxies.txt");
+    fs::write(
+        &list,
+        format!(
+            "# egress pool\n{}\n\n{}\n",
+            proxies[0].uri(),
+            proxies[1].uri()
+        ),
+    )
+    .unwrap();
+    let config = dir.path().join("proxies.toml");
+    fs::write(
+        &config,
+        format!(
+            r#"[target]
+url = "http://unused.invalid/health"
+proxy_list = "{}"
+proxy_rotation = "request"
+
+[[scenarios]]
+name = "proxied"
+url = "http://unused.invalid/health"
+
+[[scenarios]]
+name = "direct"
+url = "{}/health"
+proxy = ""
+"#,
+            list.display(),
+            direct.uri()
+        ),
+    )
+    .unwrap();
+
+    let output = kaioken()
+        .args(["run", "-f", config.to_str().unwrap()])
+        .args(["-c", "1", "-n", "20", "--json", "-y"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["summary"]["total_requests"].as_u64(), Some(20));
+    assert_eq!(json["summary"]["failed"].as_u64(), Some(0));
+    let first = proxies[0].received_requests().await.unwrap().len();
+    let second = proxies[1].received_requests().await.unwrap().len();
+    let direct = direct.received_requests().await.unwrap().len();
+    // One VU, yet both proxies were used, and the direct scenario skipped them
+    assert!(first > 0 && second > 0, "{} and {}", first, second);
+    assert_eq!(first + second, 10);
+    assert_eq!(direct, 10);
+}
+
+#[tokio::test]
+async fn seeded_cookies_are_sent_from_the_first_request() {
+    use wiremock::matchers::header_regex;
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/account"))
+        .and(header_regex("cookie", "session=abc123"))
+        .and(header_regex("cookie", "theme=dark"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+    let dir = tempdir().unwrap();
+    let cookies = dir.path().join("cookies.txt");
+    fs::write(
+        &cookies,
+        "# Netscape HTTP Cookie File\n#HttpOnly_127.0.0.1\tFALSE\t/\tFALSE\t0\tsession\tabc123\n",
+    )
+    .unwrap();
+    let config = dir.path().join("cookies.toml");
+    fs::write(
+        &config,
+        format!(
+            "[target]\nurl = \"{}/account\"\ncookie_file = \"{}\"\n\n[target.cookies]\ntheme = \"dark\"\n",
+            server.uri(),
+            cookies.display()
+        ),
+    )
+    .unwrap();
+
+    let output = kaioken()
+        .args(["run", "-f", config.to_str().unwrap()])
+        .args(["-c", "2", "-n", "10", "--json", "-y"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert!(json["summary"]["total_requests"].as_u64().unwrap() >= 10);
+    assert_eq!(json["summary"]["failed"].as_u64(), Some(0));
+}
+
+#[tokio::test]
+async fn csrf_token_is_carried_from_get_to_post() {
+    use wiremock::matchers::header;
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200).insert_header("set-cookie", "XSRF-TOKEN=t0k3n; Path=/"),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(header("X-XSRF-TOKEN", "t0k3n"))
+        .respond_with(ResponseTemplate::new(201))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(403))
+        .mount(&server)
+        .await;
+    let dir = tempdir().unwrap();
+    let config = dir.path().join("csrf.toml");
+    fs::write(
+        &config,
+        format!(
+            r#"[target]
+url = "{0}/form"
+
+[csrf]
+from = "cookie:XSRF-TOKEN"
+header = "X-XSRF-TOKEN"
+
+[[scenarios]]
+name = "form"
+url = "{0}/form"
+
+[[scenarios]]
+name = "submit"
+url = "{0}/form"
+method = "POST"
+body = "name=kaioken"
+"#,
+            server.uri()
+        ),
+    )
+    .unwrap();
+
+    let output = kaioken()
+        .args(["run", "-f", config.to_str().unwrap()])
+        .args(["-c", "1", "-n", "10", "--json", "-y"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    // Only a POST sent before the VU's first GET goes without the token
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert!(json["summary"]["total_requests"].as_u64().unwrap() >= 10);
+    assert!(json["summary"]["failed"].as_u64().unwrap() <= 1);
+}
+
+#[tokio::test]
+async fn stdin_urls_are_sent_once_and_end_the_run() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(5)
+        .mount(&server)
+        .await;
+    let urls: String = (1..=5)
+        .map(|id| format!("{}/users/{}\n", server.uri(), id))
+        .collect();
+
+    let started = std::time::Instant::now();
+    let output = kaioken()
+        .args(["run", "--urls-from-file", "-"])
+        .args(["-c", "3", "-d", "30s", "--json", "-y"])
+        .write_stdin(urls)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    // Ends at EOF, well before the duration
+    assert!(started.elapsed() < std::time::Duration::from_secs(15));
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["summary"]["total_requests"].as_u64(), Some(5));
+    assert_eq!(json["summary"]["failed"].as_u64(), Some(0));
+}
+
+#[tokio::test]
+async fn seeded_runs_send_the_same_random_urls() {
+    async fn paths(seed: &str) -> Vec<String> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        kaioken()
+            .args(["run", "--rand-regex-url"])
+            .arg(format!(
+                "{}/users/[a-z]{{4}}[0-9]{{3}}",
+                server.uri().replace('.', "\\.")
+            ))
+            .args(["-c", "1", "-n", "20", "--seed", seed, "--no-tui", "-y"])
+            .assert()
+            .success();
+        server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| request.url.path().to_string())
+            .collect()
+    }
+
+    let first = paths("7").await;
+    assert!(first.len() >= 20);
+    assert_eq!(first[..20], paths("7").await[..20]);
+    assert_ne!(first[..20], paths("8").await[..20]);
+}
+
+#[tokio::test]
+async fn cli_thresholds_gate_the_exit_code() {
+    let server = setup_mock_server().await;
+    let run = |path: &str, threshold: &str| {
+        kaioken()
+            .arg("run")
+            .arg(format!("{}{}", server.uri(), path))
+            .args(["-c", "2", "-n", "20", "--json", "-y"])
+            .args(["--threshold", threshold])
+            .args(["--threshold", "p99_latency_ms < 5000"])
+            .assert()
+    };
+
+    run("/health", "error_rate<0.01").success();
+    let failed = run("/error", "error_rate<0.01").code(4);
+
+    let json: serde_json::Value = serde_json::from_slice(&failed.get_output().stdout).unwrap();
+    let thresholds = json["thresholds"]["results"].as_array().unwrap();
+    assert_eq!(thresholds.len(), 2);
+}
+
+#[tokio::test]
+async fn arrival_rate_sends_auth_forms_and_rotated_inputs() {
+    use wiremock::matchers::{body_string, header, header_regex};
+
+    let server = MockServer::start().await;
+    for (p, body) in [("/a", "one"), ("/b", "two")] {
+        Mock::given(method("POST"))
+            .and(path(p))
+            .and(header("authorization", "Basic dXNlcjpwYXNz"))
+            .and(body_string(body))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(header_regex("content-type", "^multipart/form-data"))
+        .respond_with(ResponseTemplate::new(201))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let urls = dir.path().join("urls.txt");
+    let bodies = dir.path().join("bodies.txt");
+    let output = dir.path().join("results.json");
+    fs::write(&urls, format!("{0}/a\n{0}/b\n", server.uri())).unwrap();
+    fs::write(&bodies, "one\ntwo\n").unwrap();
+
+    let run = |args: &[&str]| {
+        kaioken()
+            .args(["run", "-m", "POST", "--arrival-rate", "100", "-n", "20"])
+            .args(args)
+            .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+            .assert()
+            .success();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        json
+    };
+
+    // Iteration n takes line n of each file, so URLs and bodies stay paired
+    let json = run(&[
+        "--urls-from-file",
+        urls.to_str().unwrap(),
+        "--body-lines",
+        bodies.to_str().unwrap(),
+        "--basic-auth",
+        "user:pass",
+    ]);
+    let total = json["summary"]["total_requests"].as_u64().unwrap();
+    assert!(total > 0);
+    assert_eq!(json["status_codes"]["200"].as_u64(), Some(total));
+
+    let upload = format!("{}/upload", server.uri());
+    let json = run(&[&upload, "--form", "name=kaioken"]);
+    let total = json["summary"]["total_requests"].as_u64().unwrap();
+    assert!(total > 0);
+    assert_eq!(json["status_codes"]["201"].as_u64(), Some(total));
+}
+
+#[tokio::test]
+async fn burst_mode_ramps_and_reports_burst_stats() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    kaioken()
+        .args(["run", &server.uri(), "-d", "1s"])
+        .args(["--burst-rate", "2", "--burst-ramp-to", "8"])
+        .args(["--burst-delay", "50ms", "--burst-jitter", "20ms"])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let bursts = &json["bursts"];
+    assert!(bursts["bursts"].as_u64().unwrap() > 1);
+    assert_eq!(bursts["min_size"].as_u64(), Some(2));
+    let max_size = bursts["max_size"].as_u64().unwrap();
+    assert!(max_size > 2 && max_size <= 8, "max_size {max_size}");
+    assert!(bursts["max_concurrent"].as_u64().unwrap() <= 8);
+    assert!(bursts["completion_mean_ms"].as_f64().unwrap() > 0.0);
+}
+
+#[tokio::test]
+async fn stages_report_per_stage_results() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let config = dir.path().join("staged.toml");
+    let output = dir.path().join("results.json");
+
+    let run = |stages: &str| {
+        fs::write(
+            &config,
+            format!("[target]\nurl = \"{}/health\"\n\n{}", server.uri(), stages),
+        )
+        .unwrap();
+        kaioken()
+            .args(["run", "-f", config.to_str().unwrap(), "--no-tui", "-y"])
+            .args(["-o", output.to_str().unwrap()])
+            .assert()
+            .success();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        json
+    };
+
+    let vus = run(
+        "[[stages]]\nduration = \"1s\"\ntarget = 2\n\n[[stages]]\nduration = \"1s\"\ntarget = 4\n",
+    );
+    let rates = run(
+        "[[stages]]\nduration = \"1s\"\ntarget_rate = 20\n\n[[stages]]\nduration = \"1s\"\ntarget_rate = 40\n",
+    );
+    for (json, targets) in [(vus, [2, 4]), (rates, [20, 40])] {
+        let stages = json["stages"].as_array().unwrap();
+        assert_eq!(stages.len(), 2, "{}", json["stages"]);
+        let mut total = 0;
+        for (stage, target) in stages.iter().zip(targets) {
+            assert_eq!(stage["target"].as_u64(), Some(target));
+            assert!(stage["requests"].as_u64().unwrap() > 0);
+            assert!(stage["requests_per_sec"].as_f64().unwrap() > 0.0);
+            assert!(stage["latency_us"]["p99"].as_u64().is_some());
+            total += stage["requests"].as_u64().unwrap();
+        }
+        assert_eq!(stages[0]["stage"].as_u64(), Some(1));
+        assert_eq!(json["summary"]["total_requests"].as_u64(), Some(total));
+    }
+}
+
+#[tokio::test]
+async fn workers_failing_every_request_are_reported() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let url = format!("{}/error", server.uri());
+
+    let summary = kaioken()
+        .args(["run", &url, "-c", "1", "-n", "60", "--no-tui", "-y"])
+        .args(["--format", "summary"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&summary.stdout);
+    assert!(stdout.contains("Unhealthy Workers:"), "{}", stdout);
+    assert!(stdout.contains("still failing, 60 in a row"), "{}", stdout);
+
+    kaioken()
+        .args(["run", &url, "-c", "1", "-n", "60", "--no-tui", "-y"])
+        .args(["-o", output.to_str().unwrap()])
+        .output()
+        .unwrap();
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let workers = &json["workers"];
+    assert_eq!(workers["workers"].as_u64(), Some(1));
+    let unhealthy = workers["unhealthy"].as_array().unwrap();
+    assert_eq!(unhealthy.len(), 1, "{}", workers);
+    assert_eq!(unhealthy[0]["worker"].as_u64(), Some(0));
+    assert!(unhealthy[0]["streak"].as_u64().unwrap() >= 50);
+    assert_eq!(unhealthy[0]["last_error"], "status 500");
+}
+
+#[tokio::test]
+async fn request_ids_are_checked_in_responses() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/echo"))
+        .respond_with(|request: &wiremock::Request| {
+            let id = request.headers["x-request-id"].clone();
+            ResponseTemplate::new(200).insert_header("x-request-id", id)
+        })
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/crossed"))
+        .respond_with(ResponseTemplate::new(200).insert_header("x-request-id", "someone-else"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/plain"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+
+    let run = |route: &str| {
+        kaioken()
+            .args(["run", &format!("{}/{}", server.uri(), route)])
+            .args(["-c", "1", "-n", "20", "--no-tui", "-y"])
+            .args(["--request-id-header", "X-Request-ID"])
+            .args(["-o", output.to_str().unwrap()])
+            .assert()
+            .success();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        json["request_ids"].clone()
+    };
+
+    let echoed = run("echo");
+    assert_eq!(echoed["responses"].as_u64(), Some(20), "{}", echoed);
+    assert_eq!(echoed["matched"].as_u64(), Some(20));
+    assert_eq!(echoed["mismatched"].as_u64(), Some(0));
+
+    let crossed = run("crossed");
+    assert_eq!(crossed["mismatched"].as_u64(), Some(20), "{}", crossed);
+
+    let missing = run("plain");
+    assert_eq!(missing["missing"].as_u64(), Some(20), "{}", missing);
+}
+
+#[tokio::test]
+async fn duplicated_requests_compare_their_responses() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/orders"))
+        .and(wiremock::matchers::header_exists("idempotency-key"))
+        .respond_with(ResponseTemplate::new(201).set_body_string(r#"{"id":1}"#))
+        .mount(&server)
+        .await;
+    let created = std::sync::atomic::AtomicU64::new(0);
+    Mock::given(method("POST"))
+        .and(path("/racy"))
+        .respond_with(move |_: &wiremock::Request| {
+            let id = created.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            ResponseTemplate::new(201).set_body_string(format!(r#"{{"id":{}}}"#, id))
+        })
+        .mount(&server)
+        .await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+
+    let run = |route: &str| {
+        kaioken()
+            .args(["run", &format!("{}/{}", server.uri(), route)])
+            .args(["-m", "POST", "-b", r#"{"item":"book"}"#])
+            .args(["-c", "2", "-d", "1s", "--no-tui", "-y"])
+            .args(["--duplicate-rate", "1"])
+            .args(["-o", output.to_str().unwrap()])
+            .assert()
+            .success();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        json
+    };
+
+    let idempotent = run("orders");
+    let duplicates = &idempotent["duplicates"];
+    let pairs = duplicates["pairs"].as_u64().unwrap();
+    assert!(pairs > 0, "{}", duplicates);
+    assert_eq!(duplicates["consistent"].as_u64(), Some(pairs));
+
+    let racy = run("racy");
+    let duplicates = &racy["duplicates"];
+    assert!(duplicates["pairs"].as_u64().unwrap() > 0);
+    assert_eq!(duplicates["body_mismatch"], duplicates["pairs"]);
+}
+
+#[tokio::test]
+async fn conditional_requests_report_the_not_modified_ratio() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/asset"))
+        .and(wiremock::matchers::header("if-none-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304).insert_header("etag", "\"v1\""))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/asset"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .set_body_string("x".repeat(4096)),
+        )
+        .mount(&server)
+        .await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+
+    kaioken()
+        .args(["run", &format!("{}/asset", server.uri())])
+        .args(["-c", "2", "-n", "20", "--no-tui", "-y"])
+        .args(["--conditional-requests"])
+        .args(["-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let revalidation = &json["revalidation"];
+
+    // Each VU fetches once in full, then revalidates
+    let conditional = revalidation["conditional"].as_u64().unwrap();
+    assert!(conditional > 0, "{}", revalidation);
+    assert_eq!(revalidation["not_modified"].as_u64(), Some(conditional));
+    assert_eq!(revalidation["not_modified_ratio"].as_f64(), Some(1.0));
+    assert_eq!(revalidation["full_fetches"].as_u64(), Some(2));
+    assert!(revalidation["full_fetch_mean_us"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn probes_connect_without_sending_requests() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+
+    for probe in ["dns", "connect"] {
+        kaioken()
+            .args(["run", &format!("{}/health", server.uri())])
+            .args(["--probe", probe, "--arrival-rate", "20", "-d", "1s"])
+            .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+            .assert()
+            .success();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert!(json["summary"]["total_requests"].as_u64().unwrap() > 0);
+        assert_eq!(json["summary"]["failed"].as_u64(), Some(0));
+    }
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn extraction_failures_are_counted_per_scenario() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"user":"ana"}"#))
+        .mount(&server)
+        .await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let config = dir.path().join("kaioken.toml");
+    let write = |threshold: &str| {
+        fs::write(
+            &config,
+            format!(
+                r#"
+[target]
+url = "{uri}/login"
+
+[load]
+concurrency = 1
+max_requests = 10
+
+[[scenarios]]
+name = "login"
+url = "{uri}/login"
+method = "POST"
+
+[scenarios.extract]
+user = "json:$.user"
+token = "json:$.token"
+
+[thresholds]
+extraction_failure_rate = "{threshold}"
+"#,
+                uri = server.uri()
+            ),
+        )
+        .unwrap();
+    };
+
+    write("< 0.6");
+    kaioken()
+        .args(["run", "-f", config.to_str().unwrap()])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let extractions = json["extractions"].as_array().unwrap();
+    assert_eq!(extractions.len(), 2, "{}", json["extractions"]);
+    let named = |name: &str| extractions.iter().find(|e| e["name"] == name).unwrap();
+    let user = named("user");
+    assert_eq!(user["scenario"], "login");
+    assert_eq!(user["attempts"], json["summary"]["total_requests"]);
+    assert_eq!(user["failed"], 0);
+    let token = named("token");
+    assert_eq!(token["failed"], token["attempts"]);
+
+    // Half of all extractions find nothing
+    write("< 0.1");
+    kaioken()
+        .args(["run", "-f", config.to_str().unwrap(), "--no-tui", "-y"])
+        .assert()
+        .code(4);
+}
+
+#[tokio::test]
+async fn scenario_mix_is_reported_against_the_weights() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let config = dir.path().join("kaioken.toml");
+    fs::write(
+        &config,
+        format!(
+            r#"
+[target]
+url = "{uri}/"
+
+[load]
+concurrency = 1
+max_requests = 40
+
+[[scenarios]]
+name = "browse"
+url = "{uri}/browse"
+weight = 3
+
+[[scenarios]]
+name = "buy"
+url = "{uri}/buy"
+weight = 1
+"#,
+            uri = server.uri()
+        ),
+    )
+    .unwrap();
+
+    kaioken()
+        .args(["run", "-f", config.to_str().unwrap()])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let mix = json["scenario_mix"].as_array().unwrap();
+    assert_eq!(mix.len(), 2, "{}", json["scenario_mix"]);
+    assert_eq!(mix[0]["scenario"], "browse");
+    assert_eq!(mix[0]["weight"], 3);
+    assert_eq!(mix[0]["expected_pct"], 75.0);
+    assert_eq!(mix[1]["expected_pct"], 25.0);
+    let requests: u64 = mix.iter().map(|m| m["requests"].as_u64().unwrap()).sum();
+    assert_eq!(
+        requests,
+        json["summary"]["total_requests"].as_u64().unwrap()
+    );
+    // One VU rotating through the weights stays on the mix
+    for share in mix {
+        assert!(
+            share["drift_pct"].as_f64().unwrap().abs() < 10.0,
+            "{}",
+            share
+        );
+    }
+
+    kaioken()
+        .args(["report", output.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Scenario Mix:"));
+}
+
+#[tokio::test]
+async fn vu_autoscale_grows_the_pool_on_the_timeline() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(200)))
+        .mount(&server)
+        .await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+
+    // 100 req/s of 200ms requests keep about 20 VUs busy; the pool starts at 10
+    kaioken()
+        .args(["run", &server.uri(), "--arrival-rate", "100", "-d", "2s"])
+        .args(["--vu-autoscale", "--vu-step", "4", "--vu-cooldown", "0s"])
+        .args(["--vu-cap", "40", "--no-tui", "-y"])
+        .args(["-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let scaling = &json["vu_scaling"];
+    assert!(scaling["scale_ups"].as_u64().unwrap() > 0, "{}", scaling);
+    let peak = scaling["peak"].as_u64().unwrap();
+    assert!(peak > 10 && peak <= 40, "{}", scaling);
+    assert_eq!(scaling["cap"], 40);
+    let first = &scaling["events"][0];
+    assert_eq!(first["from"], 10);
+    assert_eq!(first["to"], 14);
+    assert_eq!(json["summary"]["arrival_rate"]["max_vus"], 40);
+
+    let timeline = json["timeline"].as_array().unwrap();
+    assert!(!timeline.is_empty());
+    assert!(timeline.iter().all(|b| b["vus"].as_u64().unwrap() >= 10));
+}
+
+#[tokio::test]
+async fn rate_profile_drives_the_arrival_rate() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let profile = dir.path().join("profile.csv");
+    let output = dir.path().join("results.json");
+    // An hour at a steady 30 RPS, then a ramp to 60, squeezed into 2s
+    fs::write(&profile, "0,30\n1800,30\n3600,60\n").unwrap();
+
+    kaioken()
+        .args(["run", &format!("{}/health", server.uri()), "--no-tui", "-y"])
+        .args(["--rate-profile", profile.to_str().unwrap()])
+        .args(["--rate-profile-window", "2s"])
+        .args(["-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let stages = json["stages"].as_array().unwrap();
+    // Stage N ramps to point N; the first point only sets the starting rate
+    let targets: Vec<_> = stages
+        .iter()
+        .map(|s| (s["stage"].as_u64(), s["target"].as_u64()))
+        .collect();
+    assert_eq!(
+        targets,
+        [(Some(2), Some(30)), (Some(3), Some(60))],
+        "{}",
+        json["stages"]
+    );
+    // The first second runs at 30 RPS from the start, not ramping up from 0
+    let steady = stages[0]["requests"].as_u64().unwrap();
+    assert!((20..=40).contains(&steady), "{}", json["stages"]);
+}
+
+#[tokio::test]
+async fn target_hosts_split_requests_by_weight() {
+    let blue = setup_mock_server().await;
+    let green = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let config = dir.path().join("hosts.toml");
+    fs::write(
+        &config,
+        format!(
+            r#"[target]
+url = "http://unused.invalid/health"
+
+[[target.hosts]]
+url = "{}"
+name = "blue"
+weight = 3
+
+[[target.hosts]]
+url = "{}"
+name = "green"
+"#,
+            blue.uri(),
+            green.uri()
+        ),
+    )
+    .unwrap();
+
+    let output = kaioken()
+        .args(["run", "-f", config.to_str().unwrap()])
+        .args(["-c", "2", "-n", "400", "--json", "-y"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let hosts = json["hosts"].as_array().unwrap();
+    let requests = |name: &str| {
+        hosts
+            .iter()
+            .find(|h| h["name"] == name)
+            .and_then(|h| h["requests"].as_u64())
+            .unwrap()
+    };
+    let (blue_requests, green_requests) = (requests("blue"), requests("green"));
+    assert_eq!(
+        blue_requests + green_requests,
+        json["summary"]["total_requests"].as_u64().unwrap()
+    );
+    assert!(
+        blue_requests > green_requests * 2,
+        "{} vs {}",
+        blue_requests,
+        green_requests
+    );
+    assert_eq!(json["summary"]["failed"].as_u64(), Some(0));
+    assert_eq!(
+        blue.received_requests().await.unwrap().len() as u64,
+        blue_requests
+    );
+}
+
+#[tokio::test]
+async fn host_header_list_fans_one_address_out_across_virtual_hosts() {
+    let server = MockServer::start().await;
+    for tenant in ["tenant-a.example.com", "tenant-b.example.com"] {
+        Mock::given(method("GET"))
+            .and(wiremock::matchers::header("host", tenant))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+    }
+    let dir = tempdir().unwrap();
+    let list = dir.path().join("tenants.txt");
+    fs::write(
+        &list,
+        "# tenants\ntenant-a.example.com\ntenant-b.example.com\n",
+    )
+    .unwrap();
+
+    let output = kaioken()
+        .args(["run", &server.uri()])
+        .args(["--host-header-list", list.to_str().unwrap()])
+        .args(["-c", "2", "-n", "100", "--json", "-y"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    // Every request matched a tenant's mock, and both tenants got a share
+    assert_eq!(json["summary"]["failed"].as_u64(), Some(0));
+    let hosts = json["hosts"].as_array().unwrap();
+    assert_eq!(hosts.len(), 2, "{}", json["hosts"]);
+    assert!(hosts.iter().all(|h| h["requests"].as_u64().unwrap() > 0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pause_on_errors_waits_for_the_target_to_come_back() {
+    // Nothing listens on the port until the target "comes back" a second in
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+        let server = MockServer::builder()
+            .listener(std::net::TcpListener::bind(addr).unwrap())
+            .start()
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        std::future::pending::<()>().await;
+    });
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    kaioken()
+        .args(["run", &format!("http://{}/health", addr)])
+        .args(["-c", "2", "-d", "4s", "--pause-on-errors", "300ms"])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let outage = &json["outages"][0];
+    assert_eq!(outage["recovered"], true, "{}", json["outages"]);
+    assert!(outage["health_checks"].as_u64().unwrap() >= 1);
+    assert!(outage["duration_secs"].as_f64().unwrap() >= 0.3);
+    assert!(json["summary"]["successful"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn max_connections_shares_a_few_connections_among_many_vus() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(20)))
+        .mount(&server)
+        .await;
+    let url = format!("{}/health", server.uri());
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    kaioken()
+        .args([
+            "run",
+            &url,
+            "-c",
+            "20",
+            "-n",
+            "200",
+            "--max-connections",
+            "2",
+        ])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    assert_eq!(json["summary"]["failed"].as_u64(), Some(0));
+    // 20 VUs, but only two requests, and so two connections, at a time
+    assert!(
+        json["connections"]["opened"].as_u64().unwrap() <= 2,
+        "{}",
+        json["connections"]
+    );
+    let cap = &json["connection_cap"];
+    assert_eq!(cap["limit"].as_u64(), Some(2));
+    assert!(cap["waited"].as_u64().unwrap() > 100, "{}", cap);
+    assert!(cap["wait_max_ms"].as_f64().unwrap() >= 20.0);
+}
+
+#[tokio::test]
+async fn result_file_follows_the_published_schema() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"ok": true})))
+        .mount(&server)
+        .await;
+    let url = format!("{}/health", server.uri());
+
+    // Turn on optional sections so more of the schema is exercised
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    kaioken()
+        .args(["run", &url, "-c", "2", "-n", "20"])
+        .args([
+            "--threshold",
+            "p99_latency_ms<10000",
+            "--threshold",
+            "error_rate<0.5",
+        ])
+        .args(["--apdex-t", "300ms", "--percentiles", "50,99,99.9"])
+        .args(["--expect-content-type", "application/json"])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+    let result: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+
+    let schema = kaioken().arg("schema").assert().success();
+    let schema: serde_json::Value = serde_json::from_slice(&schema.get_output().stdout).unwrap();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    let errors: Vec<String> = validator
+        .iter_errors(&result)
+        .map(|e| format!("{}: {}", e.instance_path, e))
+        .collect();
+    assert!(errors.is_empty(), "{:#?}", errors);
+    for key in result.as_object().unwrap().keys() {
+        assert!(
+            schema["properties"].get(key).is_some(),
+            "{} isn't in the schema",
+            key
+        );
+    }
+
+    // Readers refuse files that don't follow it, naming what's wrong
+    let mut broken = result.clone();
+    broken["summary"]["total_requests"] = "many".into();
+    let broken_path = dir.path().join("broken.json");
+    fs::write(&broken_path, broken.to_string()).unwrap();
+    kaioken()
+        .args([
+            "compare",
+            output.to_str().unwrap(),
+            broken_path.to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("summary.total_requests"));
+}
+
+#[tokio::test]
+async fn unexpected_content_types_fail_a_threshold() {
+    // Five HTML pages served with 200, five HTML 404s, then JSON
+    let server = MockServer::start().await;
+    let html = |status| {
+        ResponseTemplate::new(status).set_body_raw("<html></html>", "text/html; charset=utf-8")
+    };
+    Mock::given(method("GET"))
+        .respond_with(html(200))
+        .up_to_n_times(5)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(html(404))
+        .up_to_n_times(5)
+        .with_priority(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"ok": true})))
+        .with_priority(3)
+        .mount(&server)
+        .await;
+    let url = format!("{}/api", server.uri());
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    kaioken()
+        .args(["run", &url, "-c", "1", "-n", "100"])
+        .args(["--expect-content-type", "application/json"])
+        .args(["--threshold", "unexpected_content_type<1"])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .code(4);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let content_types = &json["content_types"];
+    // The 404s are already errors, so only the 200s are unexpected
+    assert_eq!(
+        content_types["unexpected"].as_u64(),
+        Some(5),
+        "{}",
+        content_types
+    );
+    assert_eq!(content_types["expected"][0], "application/json");
+    let types = content_types["types"].as_array().unwrap();
+    assert_eq!(types[0]["content_type"], "application/json");
+    assert_eq!(types[0]["responses"].as_u64(), Some(90));
+    assert_eq!(types[0]["unexpected"].as_u64(), Some(0));
+    assert_eq!(types[1]["content_type"], "text/html");
+    assert_eq!(types[1]["responses"].as_u64(), Some(10));
+    assert_eq!(types[1]["unexpected"].as_u64(), Some(5));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failover_reports_downtime_and_recovery() {
+    // The target serves, fails every request from 1s to 2.8s, then serves again
+    let server = MockServer::start().await;
+    let url = format!("{}/health", server.uri());
+    let status = |code| Mock::given(method("GET")).respond_with(ResponseTemplate::new(code));
+    status(200).mount(&server).await;
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+        server.reset().await;
+        status(503).mount(&server).await;
+        tokio::time::sleep(std::time::Duration::from_millis(1800)).await;
+        server.reset().await;
+        status(200).mount(&server).await;
+        std::future::pending::<()>().await;
+    });
+
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    kaioken()
+        .args(["run", &url, "-c", "2", "-d", "5s", "--failover"])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let failover = &json["failover"];
+    assert_eq!(failover["interval_secs"].as_f64(), Some(1.0));
+    let incidents = failover["incidents"].as_array().unwrap();
+    assert_eq!(incidents.len(), 1, "{}", failover);
+    assert!(incidents[0]["downtime_secs"].as_f64().unwrap() >= 1.0);
+    assert!(incidents[0]["error_burst_secs"].as_f64().unwrap() >= 2.0);
+    assert!(incidents[0]["time_to_recovery_secs"].as_f64().is_some());
+    let availability = failover["availability"].as_array().unwrap();
+    assert!(availability.iter().any(|a| a.as_f64().unwrap() < 0.5));
+    assert_eq!(availability.last().unwrap().as_f64(), Some(1.0));
+}
+
+#[tokio::test]
+async fn ipv4_only_reaches_dual_stack_name_over_ipv4() {
+    // wiremock listens on 127.0.0.1, and localhost may resolve to ::1 first
+    let server = setup_mock_server().await;
+    let url = format!("http://localhost:{}/health", server.address().port());
+
+    let output = kaioken()
+        .args(["run", &url, "-c", "2", "-n", "20", "--ipv4", "--json", "-y"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["metadata"]["target"]["ip_family"], "ipv4");
+    assert_eq!(json["summary"]["failed"].as_u64(), Some(0));
+    assert!(json["summary"]["total_requests"].as_u64().unwrap() >= 20);
+}
+
+#[tokio::test]
+async fn network_profile_adds_round_trip_and_transfer_time() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("x".repeat(100_000)))
+        .mount(&server)
+        .await;
+    let url = format!("{}/page", server.uri());
+
+    // 800 kbit at 8 Mbit/s is 100ms, plus a 50ms round trip
+    let output = kaioken()
+        .args(["run", &url, "-c", "1", "-n", "3"])
+        .args(["--network-profile", "custom", "--network-down", "8mbit"])
+        .args(["--network-rtt", "50ms", "--json", "-y"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(
+        json["metadata"]["load"]["network_profile"]["name"],
+        "custom"
+    );
+    assert_eq!(
+        json["metadata"]["load"]["network_profile"]["down_bps"].as_u64(),
+        Some(8_000_000)
+    );
+    assert!(json["latency_us"]["min"].as_u64().unwrap() >= 150_000);
+}
+
+#[tokio::test]
+async fn check_pass_rates_are_broken_down_per_timeline_bucket() {
+    let server = setup_mock_server().await;
+    let dir = tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    let output = dir.path().join("results.json");
+    fs::write(
+        &config,
+        format!(
+            r#"
+[target]
+url = "{}/health"
+
+[[checks]]
+name = "is_200"
+condition = "status == 200"
+
+[[checks]]
+name = "is_teapot"
+condition = "status == 418"
+"#,
+            server.uri()
+        ),
+    )
+    .unwrap();
+
+    kaioken()
+        .args(["run", "-f", config.to_str().unwrap(), "-c", "2", "-d", "1s"])
+        .args(["--timeline-interval", "250ms"])
+        .args(["--no-tui", "-y", "-o", output.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let timeline = json["timeline"].as_array().unwrap();
+    let with_checks: Vec<_> = timeline
+        .iter()
+        .filter(|b| b.get("checks").is_some())
+        .collect();
+    assert!(with_checks.len() >= 2, "{}", json["timeline"]);
+    for bucket in &with_checks {
+        assert_eq!(bucket["checks"]["is_200"]["pass_rate"], 1.0);
+        assert_eq!(bucket["checks"]["is_teapot"]["pass_rate"], 0.0);
+    }
+
+    // The buckets add up to the cumulative totals
+    let bucketed: u64 = with_checks
+        .iter()
+        .map(|b| b["checks"]["is_200"]["total"].as_u64().unwrap())
+        .sum();
+    let total = json["checks"]["results"]["is_200"]["total"]
+        .as_u64()
+        .unwrap();
+    assert!(bucketed > 0 && bucketed <= total);
+}
+
+#[tokio::test]
+async fn compressed_bodies_are_sent_with_content_encoding() {
+    use wiremock::matchers::header;
+
+    // Only compressed payloads are accepted, like an ingestion endpoint
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/ingest"))
+        .and(header("content-encoding", "gzip"))
+        .respond_with(|request: &wiremock::Request| {
+            // gzip magic bytes
+            if request.body.starts_with(&[0x1f, 0x8b]) {
+                ResponseTemplate::new(202)
+            } else {
+                ResponseTemplate::new(400)
+            }
+        })
+        .mount(&server)
+        .await;
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("results.json");
+    let url = format!("{}/ingest", server.uri());
+    let body = r#"{"level":"info","msg":"request served"}"#.repeat(50);
+
+    // Prebuilt and interpolated bodies take different paths
+    for body in [body.clone(), format!("{}${{REQUEST_ID}}", body)] {
+        kaioken()
+            .args([
+                "run", &url, "-m", "POST", "-b", &body, "-c", "2", "-n", "20",
+            ])
+            .args(["--compress-body", "gzip", "--no-tui", "-y"])
+            .args(["-o", output.to_str().unwrap()])
+            .assert()
+            .success();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        let codes = json["status_codes"].as_object().unwrap();
+        assert_eq!(codes.keys().collect::<Vec<_>>(), ["202"], "{}", json);
+        // Bytes sent are what went over the wire
+        let requests = json["summary"]["total_requests"].as_u64().unwrap();
+        let sent = json["summary"]["bytes_sent"].as_u64().unwrap();
+        assert!(sent > 0 && sent < requests * body.len() as u64 / 4);
+    }
+}
+
+#[tokio::test]
+async fn template_format_renders_the_result() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let url = format!("{}/health", server.uri());
+
+    let dir = tempdir().unwrap();
+    let template = dir.path().join("report.tera");
+    fs::write(
+        &template,
+        "h3. {{ metadata.target.url }}\n\
+         {{ summary.total_requests }} requests, {{ summary.failed }} failed\n\
+         {% for code, count in status_codes %}{{ code }}={{ count }}{% endfor %}\n",
+    )
+    .unwrap();
+    let rendered = dir.path().join("report.txt");
+    let saved = dir.path().join("results.json");
+
+    kaioken()
+        .args(["run", &url, "-c", "2", "-n", "20", "--no-tui", "-y"])
+        .args([
+            "--format",
+            "template",
+            "--template",
+            template.to_str().unwrap(),
+        ])
+        .args(["-o", rendered.to_str().unwrap()])
+        .assert()
+        .success();
+    let report = fs::read_to_string(&rendered).unwrap();
+    assert!(report.starts_with(&format!("h3. {}\n", url)), "{}", report);
+    assert!(report.contains(" requests, 0 failed\n200="), "{}", report);
+
+    // Saved results can be rendered later
+    kaioken()
+        .args(["run", &url, "-c", "2", "-n", "20", "--no-tui", "-y"])
+        .args(["-o", saved.to_str().unwrap()])
+        .assert()
+        .success();
+    kaioken()
+        .args(["report", saved.to_str().unwrap(), "-f", "template"])
+        .args(["--template", template.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(" requests, 0 failed"));
+
+    // A broken template fails before anything is sent
+    let sent = server.received_requests().await.unwrap().len();
+    fs::write(&template, "{{ summary.total_requests ").unwrap();
+    kaioken()
+        .args(["run", &url, "-c", "2", "-n", "20", "--no-tui", "-y"])
+        .args([
+            "--format",
+            "template",
+            "--template",
+            template.to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Invalid template"));
+    kaioken()
+        .args(["run", &url, "-c", "2", "-n", "20", "--no-tui", "-y"])
+        .args(["--format", "template"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("needs --template FILE"));
+    assert_eq!(server.received_requests().await.unwrap().len(), sent);
+}
+
+#[tokio::test]
+async fn attach_follows_a_served_run_to_its_result() {
+    let target = setup_mock_server().await;
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let addr = format!("127.0.0.1:{}", port);
+
+    let mut serve = std::process::Command::new(assert_cmd::cargo::cargo_bin("kaioken"))
+        .args(["serve", "-b", &addr, "--token", "secret"])
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let client = reqwest::Client::new();
+    let mut up = false;
+    for _ in 0..50 {
+        if client
+            .get(format!("http://{}/health", addr))
+            .send()
+            .await
+            .is_ok()
+        {
+            up = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(up, "serve never came up");
+
+    kaioken()
+        .args(["attach", &addr, "--token", "wrong", "--no-tui"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Missing or invalid token"));
+
+    // Attached before the run starts, it waits for one
+    let attach = std::process::Command::new(assert_cmd::cargo::cargo_bin("kaioken"))
+        .args([
+            "attach",
+            &addr,
+            "--token",
+            "secret",
+            "--no-tui",
+            "--serious",
+        ])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(700)).await;
+
+    let config = format!(
+        "[target]\nurl = \"{}/health\"\n\n[load]\nconcurrency = 2\nduration = \"2s\"\n\n[thresholds]\np99_latency_ms = \"< 0.001\"\n",
+        target.uri()
+    );
+    let started = client
+        .post(format!("http://{}/api/runs", addr))
+        .bearer_auth("secret")
+        .header("content-type", "application/toml")
+        .body(config)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(started.status(), 202);
+
+    let output = attach.wait_with_output().unwrap();
+    let _ = serve.kill();
+    let _ = serve.wait();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // Failed thresholds come back as the exit code `run` would give
+    assert_eq!(output.status.code(), Some(4), "{}", stderr);
+    assert!(stderr.contains("waiting for one to start"), "{}", stderr);
+    assert!(stdout.contains("Load Test Results"), "{}", stdout);
+    assert!(stdout.contains("p99_latency_ms"), "{}", stdout);
+}
+
+#[tokio::test]
+async fn trend_thresholds_compare_against_stored_runs() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let url = format!("{}/health", server.uri());
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("history.db");
+    let run = || {
+        let mut cmd = kaioken();
+        cmd.args(["run", &url, "-c", "2", "-n", "20", "--no-tui", "-y"])
+            .args(["--db-url", db.to_str().unwrap()])
+            .args(["--threshold", "error_rate_trend<=+10% over 7d"]);
+        cmd
+    };
+
+    // Nothing to compare the first run against
+    run().assert().success().stderr(predicates::str::contains(
+        "Trend threshold error_rate_trend skipped: no runs",
+    ));
+    let second = run().assert().success();
+    let result: serde_json::Value = serde_json::from_slice(&second.get_output().stdout).unwrap();
+    let trend = &result["thresholds"]["results"][0];
+    assert_eq!(
+        trend["condition"],
+        "error_rate_trend <= +10% over 7days (median 0.00 of 1 runs)"
+    );
+    assert_eq!(trend["passed"], true);
+
+    // Errors where the stored runs had none fail it
+    server.reset().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    let third = run().assert().code(4);
+    let result: serde_json::Value = serde_json::from_slice(&third.get_output().stdout).unwrap();
+    let trend = &result["thresholds"]["results"][0];
+    assert_eq!(trend["metric"], "error_rate_trend");
+    assert_eq!(trend["actual"], 100.0);
+    assert_eq!(trend["passed"], false);
+}